@@ -0,0 +1,3 @@
+// everything the cdylib exports is in floatfs: the wasm-bindgen and pyo3 glue and the #[no_mangle] c symbols are
+// kept by linking it in, which this does
+pub use floatfs_core::*;
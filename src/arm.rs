@@ -0,0 +1,130 @@
+// aarch64 FPCR/FPSR emulation: builds the Context an arm core would use for a given FPCR value, and the
+// alternative half-precision (AHP) conversions. arm detects tininess before rounding, and its nan
+// propagation (signaling first, then the first operand) is already the default rule in nan_logic.
+
+use core::any::TypeId;
+
+use crate::format::Binary16;
+use crate::{Context, Flags, Format, NanMode, RoundingMode, SoftFloat, Tininess, F16};
+
+/// An FPCR value. Only the fields below are modeled; the trap enables and FEAT_AFP bits are ignored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Fpcr(pub u32);
+
+impl Fpcr {
+    pub const AHP: u32 = 1 << 26; // alternative half-precision: no infinities or nans, exponent 31 is a number
+    pub const DN: u32 = 1 << 25; // default nan
+    pub const FZ: u32 = 1 << 24; // flush single and double subnormals to zero
+    pub const RMODE_SHIFT: u32 = 22; // 2-bit rounding mode field
+    pub const FZ16: u32 = 1 << 19; // flush half-precision subnormals to zero
+
+    fn has(&self, bit: u32) -> bool {
+        self.0 & bit != 0
+    }
+
+    pub fn rounding(&self) -> RoundingMode {
+        match (self.0 >> Fpcr::RMODE_SHIFT) & 3 {
+            0 => RoundingMode::TiesToEven,
+            1 => RoundingMode::TowardPositive,
+            2 => RoundingMode::TowardNegative,
+            _ => RoundingMode::TowardZero,
+        }
+    }
+
+    // FZ16 covers binary16 and FZ everything else
+    fn flushes<F: Format>(&self) -> bool {
+        if TypeId::of::<F>() == TypeId::of::<Binary16>() {
+            self.has(Fpcr::FZ16)
+        } else {
+            self.has(Fpcr::FZ)
+        }
+    }
+
+    fn base_context(&self) -> Context {
+        Context {
+            rounding: self.rounding(),
+            tininess: Tininess::BeforeRounding,
+            nan_mode: if self.has(Fpcr::DN) { NanMode::Canonical } else { NanMode::Propagate },
+            ..Context::default()
+        }
+    }
+
+    /// The context for arithmetic in format F.
+    pub fn context<F: Format>(&self) -> Context {
+        let flush = self.flushes::<F>();
+        Context {
+            flush_inputs: flush,
+            flush_outputs: flush,
+            ..self.base_context()
+        }
+    }
+
+    /// The context for converting from F to G. Conversions never flush binary16 (FZ16 doesn't apply to them),
+    /// but FZ still flushes a single or double on either side.
+    pub fn convert_context<F: Format, G: Format>(&self) -> Context {
+        let half = |id: TypeId| id == TypeId::of::<Binary16>();
+        Context {
+            flush_inputs: self.has(Fpcr::FZ) && !half(TypeId::of::<F>()),
+            flush_outputs: self.has(Fpcr::FZ) && !half(TypeId::of::<G>()),
+            ..self.base_context()
+        }
+    }
+
+    /// Converts to binary16, or to the alternative format if AHP is set. Use a context from `convert_context`.
+    pub fn to_half<F: Format>(&self, x: &SoftFloat<F>, ctx: &mut Context) -> u16 {
+        if !self.has(Fpcr::AHP) {
+            return x.convert::<Binary16>(ctx).to_bits();
+        }
+        let sign = (x.get_sign() as u16) << 15;
+        if x.is_nan() {
+            ctx.flags.raise(Flags::INVALID);
+            return sign; // no nans to give, so zero
+        }
+        if x.is_infinity() {
+            ctx.flags.raise(Flags::INVALID);
+            return sign | 0x7FFF; // no infinity either: the largest magnitude
+        }
+        // anything below 2^16 rounds exactly as in binary16, and 2^16 itself is encoded where binary16 keeps
+        // infinity, so only the overflow flag needs dropping. from 2^16 up, round half the value and bump the exponent.
+        let mut local = Context { flags: Flags::empty(), ..*ctx };
+        if x.get_exponent() < 16 {
+            let half = x.convert::<Binary16>(&mut local);
+            ctx.flags.raise(Flags(local.flags.0 & !Flags::OVERFLOW.0));
+            return half.to_bits();
+        }
+        let halved = SoftFloat::<F>::from_raw(x.raw() - (1 << F::MANTISSA_BITS)); // far from subnormal, so exact
+        let half = halved.convert::<Binary16>(&mut local);
+        if local.flags.contains(Flags::OVERFLOW) {
+            ctx.flags.raise(Flags::INVALID); // past the largest magnitude, 131008: saturate, without inexact
+            return sign | 0x7FFF;
+        }
+        ctx.flags.raise(local.flags);
+        half.to_bits() + (1 << 10)
+    }
+
+    /// Widens binary16 bits, read as the alternative format if AHP is set. Always exact.
+    pub fn from_half<F: Format>(&self, bits: u16, ctx: &mut Context) -> SoftFloat<F> {
+        let half = F16::from_bits(bits);
+        if self.has(Fpcr::AHP) && half.biased_exponent() == 31 {
+            // 2^16 up to 131008: convert the halved value and double it
+            let doubled: SoftFloat<F> = F16::from_bits(bits - (1 << 10)).convert(ctx);
+            return SoftFloat::from_raw(doubled.raw() + (1 << F::MANTISSA_BITS));
+        }
+        half.convert(ctx)
+    }
+}
+
+/// The FPSR cumulative exception bits for a set of flags: IOC, DZC, OFC, UFC, IXC from bit 0 up, and IDC at bit 7.
+/// A flushed result counts as underflow without inexact.
+pub fn fpsr(flags: Flags) -> u32 {
+    let bits = [
+        (Flags::INVALID, 1 << 0),
+        (Flags::DIVIDE_BY_ZERO, 1 << 1),
+        (Flags::OVERFLOW, 1 << 2),
+        (Flags::UNDERFLOW, 1 << 3),
+        (Flags::OUTPUT_DENORMAL, 1 << 3),
+        (Flags::INEXACT, 1 << 4),
+        (Flags::INPUT_DENORMAL, 1 << 7),
+    ];
+    bits.iter().filter(|(flag, _)| flags.contains(*flag)).fold(0, |fpsr, (_, bit)| fpsr | bit)
+}
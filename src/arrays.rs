@@ -0,0 +1,52 @@
+// soft-float arithmetic over ndarray arrays of Float (the ndarray feature). the element-wise operations round
+// through a Context, so a whole array operation runs in one rounding mode and its flags collect in one place, and
+// the sums take any of the Summation options, over the whole array or along an axis. arrays can be views, slices
+// or strided; the elements are always visited in logical (row-major) order, whatever the memory layout, so the
+// results don't depend on how an array happens to be stored.
+//
+//     let mut ctx = Context::new(RoundingMode::TowardZero);
+//     let product = arrays::apply(Operation::Multiply, &a, &b, &mut ctx).unwrap();
+//     let column_totals = arrays::sum_axis(&product, Axis(0), Summation::Exact);
+
+use ndarray::{Array, ArrayBase, Axis, Data, Dimension, RemoveAxis, Zip};
+
+use crate::sum::Summation;
+use crate::{Context, Float, Operation};
+
+/// a op b element by element, each rounded in the context's mode, with the flags of every element raised in it.
+/// None if the shapes differ: there's no broadcasting.
+pub fn apply<S, T, D>(op: Operation, a: &ArrayBase<S, D>, b: &ArrayBase<T, D>, ctx: &mut Context) -> Option<Array<Float, D>>
+where
+    S: Data<Elem = Float>,
+    T: Data<Elem = Float>,
+    D: Dimension,
+{
+    if a.shape() != b.shape() {
+        return None;
+    }
+    Some(Zip::from(a).and(b).map_collect(|x, y| match op {
+        Operation::Add => ctx.add(x, y),
+        Operation::Subtract => ctx.subtract(x, y),
+        Operation::Multiply => ctx.multiply(x, y),
+        Operation::Divide => ctx.divide(x, y),
+    }))
+}
+
+/// The sum of every element, in logical order. An empty array sums to +0.
+pub fn sum<S, D>(a: &ArrayBase<S, D>, summation: Summation) -> Float
+where
+    S: Data<Elem = Float>,
+    D: Dimension,
+{
+    summation.sum(a.iter())
+}
+
+/// The sums along an axis: the result has that axis removed, and each element is the sum of the lane of the
+/// input running along it, in order. Panics if the axis is out of bounds, as ndarray's own reductions do.
+pub fn sum_axis<S, D>(a: &ArrayBase<S, D>, axis: Axis, summation: Summation) -> Array<Float, D::Smaller>
+where
+    S: Data<Elem = Float>,
+    D: RemoveAxis,
+{
+    Zip::from(a.lanes(axis)).map_collect(|lane| summation.sum(lane.iter()))
+}
@@ -0,0 +1,98 @@
+// assert_float_eq!, for tests that compare Floats: exact bits, or within some ulps. on failure it shows what you'd
+// otherwise print by hand, both values with their bits and parts and how many ulps apart they are:
+//
+//   assert_float_eq!(result, Float::new(expected));
+//   assert_float_eq!(sum, reference, ulps = 2, "summing {} values", n);
+//
+// the ulp count is the number of steps between the two along the ordered floats, so the zeros are 0 apart and the
+// largest finite value is 1 from infinity. with a tolerance, any two nans match; without one, only the same bits do.
+
+use core::fmt;
+
+use crate::Float;
+
+/// Asserts two Floats are equal: the same bits, or with `ulps = n` at most n ulps apart (nans matching any nan).
+/// Takes an optional message after that, as `assert_eq!` does, only formatted on failure.
+#[macro_export]
+macro_rules! assert_float_eq {
+    (@check $left:expr, $right:expr, $ulps:expr, $message:expr) => {
+        match (&$left, &$right, $ulps) {
+            (left, right, ulps) => {
+                if !$crate::assert::float_eq(left, right, ulps) {
+                    $crate::assert::failed(left, right, ulps, $message)
+                }
+            }
+        }
+    };
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::assert_float_eq!(@check $left, $right, None, None)
+    };
+    ($left:expr, $right:expr, ulps = $ulps:expr $(,)?) => {
+        $crate::assert_float_eq!(@check $left, $right, Some($ulps), None)
+    };
+    ($left:expr, $right:expr, ulps = $ulps:expr, $($arg:tt)+) => {
+        $crate::assert_float_eq!(@check $left, $right, Some($ulps), Some(format_args!($($arg)+)))
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        $crate::assert_float_eq!(@check $left, $right, None, Some(format_args!($($arg)+)))
+    };
+}
+
+#[doc(hidden)]
+pub fn float_eq(left: &Float, right: &Float, ulps: Option<u64>) -> bool {
+    match ulps {
+        None => left.to_bits() == right.to_bits(),
+        Some(_) if left.is_nan() || right.is_nan() => left.is_nan() && right.is_nan(),
+        Some(ulps) => left.ulp_distance(right) <= ulps,
+    }
+}
+
+// one side of the failure message: the value, its bits and its fields
+struct Side<'a>(&'a Float);
+
+impl fmt::Display for Side<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:e} ({:#018x}: {})", self.0.to_f64(), self.0.to_bits(), self.0.parts())
+    }
+}
+
+// the ulp distance, which means nothing once a nan is involved
+struct Apart<'a>(&'a Float, &'a Float);
+
+impl fmt::Display for Apart<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.0.is_nan() || self.1.is_nan() {
+            write!(f, "a nan, no ulp distance")
+        } else {
+            write!(f, "{} ulps", self.0.ulp_distance(self.1))
+        }
+    }
+}
+
+// what was asked for, plus the caller's message if there's one
+struct Header<'a>(Option<u64>, Option<fmt::Arguments<'a>>);
+
+impl fmt::Display for Header<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            None => write!(f, "(exact bits)")?,
+            Some(ulps) => write!(f, "(within {ulps} ulps)")?,
+        }
+        match self.1 {
+            None => Ok(()),
+            Some(message) => write!(f, ": {message}"),
+        }
+    }
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn failed(left: &Float, right: &Float, ulps: Option<u64>, message: Option<fmt::Arguments>) -> ! {
+    panic!(
+        "assertion `left == right` failed {}\n  left: {}\n right: {}\n apart: {}",
+        Header(ulps, message),
+        Side(left),
+        Side(right),
+        Apart(left, right)
+    )
+}
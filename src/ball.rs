@@ -0,0 +1,247 @@
+// ball (midpoint-radius) arithmetic, as in Arb: a Ball is a midpoint and a radius and stands for every real within
+// the radius of the midpoint. the midpoint is worked out as usual, rounded to nearest, and the radius with every
+// rounding upward, covering both the radii going in and the rounding error of the new midpoint, so the exact
+// result of the operation on any members of the operands is inside the result. that's cheaper than an interval
+// [lo, hi], which has to round both ends outward every time: here only the radius, which just needs a rough
+// bound, is rounded the slow way. the elementary functions rely on the ones in math and trig being faithful,
+// within an ulp of the exact value.
+//
+//   let x = Ball::new(Float::new(1.5), Float::new(1e-10));
+//   let y = x.exp().multiply(&x).subtract(&Ball::exact(Float::new(1.0)));
+//   println!("{} {}", y.lower().to_f64(), y.upper().to_f64());
+//
+// a ball whose midpoint is a nan is indeterminate: it contains every real. so does one with an infinite radius,
+// which is what overflow gives.
+
+use crate::{Context, Float, RoundingMode, F64};
+
+/// A real known to be within `rad` of `mid`.
+#[derive(Debug)]
+pub struct Ball {
+    mid: Float,
+    rad: Float,
+}
+
+fn add_up(a: &Float, b: &Float) -> Float {
+    a.add_with_rounding(b, RoundingMode::TowardPositive)
+}
+
+// a zero times anything is zero here, infinite radius included: an exact zero times any real is still zero
+fn mul_up(a: &Float, b: &Float) -> Float {
+    if a.is_zero() || b.is_zero() {
+        return Float::new(0.0);
+    }
+    a.multiply_with_rounding(b, RoundingMode::TowardPositive)
+}
+
+fn div_up(a: &Float, b: &Float) -> Float {
+    a.divide_with_rounding(b, RoundingMode::TowardPositive)
+}
+
+// the gap from |x| up to the next double, which bounds the error of a faithful result x. infinite for
+// the largest double, since its faithful neighbour above is infinity
+fn ulp(x: &Float) -> Float {
+    let x = x.abs();
+    x.next_up().add_with_rounding(&x.negated(), RoundingMode::TowardPositive)
+}
+
+// half an ulp, for results rounded to nearest. a subnormal ulp doesn't halve, so it stays whole
+fn half_ulp(x: &Float) -> Float {
+    mul_up(&ulp(x), &Float::new(0.5))
+}
+
+fn soft(f: &Float) -> F64 {
+    F64::from_bits(f.to_bits())
+}
+
+fn hard(f: F64) -> Float {
+    Float::from_bits(f.to_bits())
+}
+
+impl Ball {
+    /// The ball around `mid` of radius |rad|. A nan radius makes it infinite.
+    pub fn new(mid: Float, rad: Float) -> Ball {
+        Ball::from_parts(mid, rad.abs())
+    }
+
+    /// The ball holding just `mid`.
+    pub fn exact(mid: Float) -> Ball {
+        Ball::from_parts(mid, Float::new(0.0))
+    }
+
+    /// The ball holding every real, which is what an operation gives when it can't say anything better (a
+    /// divisor ball around zero, a logarithm of a ball reaching zero).
+    pub fn indeterminate() -> Ball {
+        Ball { mid: Float::nan(), rad: Float::infinity(false) }
+    }
+
+    // a nan midpoint is indeterminate and an overflowed one (or a nan radius) has an infinite radius
+    fn from_parts(mid: Float, rad: Float) -> Ball {
+        if mid.is_nan() {
+            return Ball::indeterminate();
+        }
+        if mid.is_infinity() || rad.is_nan() {
+            return Ball { mid, rad: Float::infinity(false) };
+        }
+        Ball { mid, rad }
+    }
+
+    pub fn mid(&self) -> &Float {
+        &self.mid
+    }
+
+    pub fn rad(&self) -> &Float {
+        &self.rad
+    }
+
+    pub fn copy(&self) -> Ball {
+        Ball { mid: self.mid.copy(), rad: self.rad.copy() }
+    }
+
+    /// Whether the ball is a single number.
+    pub fn is_exact(&self) -> bool {
+        self.rad.is_zero()
+    }
+
+    /// Whether the midpoint and radius are both finite, so the ball has real bounds.
+    pub fn is_finite(&self) -> bool {
+        self.mid.is_finite() && self.rad.is_finite()
+    }
+
+    /// mid - rad, rounded down: a double no greater than anything in the ball.
+    pub fn lower(&self) -> Float {
+        self.mid.add_with_rounding(&self.rad.negated(), RoundingMode::TowardNegative)
+    }
+
+    /// mid + rad, rounded up: a double no less than anything in the ball.
+    pub fn upper(&self) -> Float {
+        add_up(&self.mid, &self.rad)
+    }
+
+    /// Whether x is in the ball. An indeterminate or infinite ball contains every x but a nan.
+    pub fn contains(&self, x: &Float) -> bool {
+        if x.is_nan() {
+            return false;
+        }
+        if self.mid.is_nan() || self.rad.is_infinity() {
+            return true;
+        }
+        self.lower().to_f64() <= x.to_f64() && x.to_f64() <= self.upper().to_f64()
+    }
+
+    pub fn negate(&self) -> Ball {
+        Ball::from_parts(self.mid.negated(), self.rad.copy())
+    }
+
+    /// The midpoints' sum, with two-sum's exact rounding error added to the radius, so an exact sum of exact
+    /// balls stays exact.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn add(&self, other: &Ball) -> Ball {
+        let (mid, err) = self.mid.two_sum(&other.mid);
+        let rad = add_up(&add_up(&self.rad, &other.rad), &err.abs());
+        Ball::from_parts(mid, rad)
+    }
+
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn subtract(&self, other: &Ball) -> Ball {
+        self.add(&other.negate())
+    }
+
+    /// (m1 + d1)(m2 + d2) - m1 m2 = m1 d2 + m2 d1 + d1 d2, so the radius is |m1| r2 + |m2| r1 + r1 r2 plus
+    /// two-prod's rounding error. That error is exact unless the product is within 2^53 of underflowing, where
+    /// it's rounded too and the next double up bounds it.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn multiply(&self, other: &Ball) -> Ball {
+        let (mid, err) = self.mid.two_prod(&other.mid);
+        let tiny = mid.get_exponent() < -969 && !self.mid.is_zero() && !other.mid.is_zero();
+        let err = if tiny { err.abs().next_up() } else { err.abs() };
+        let spread = add_up(&mul_up(&self.mid.abs(), &other.rad), &mul_up(&other.mid.abs(), &self.rad));
+        let rad = add_up(&add_up(&spread, &mul_up(&self.rad, &other.rad)), &err);
+        Ball::from_parts(mid, rad)
+    }
+
+    /// x / y - m1 / m2 = (m2 d1 - m1 d2) / (y m2), so the radius is (|m2| r1 + |m1| r2) / (|m2| (|m2| - r2)),
+    /// with the denominator rounded down, plus half an ulp for the rounded quotient. A divisor ball reaching zero
+    /// gives an indeterminate ball.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn divide(&self, other: &Ball) -> Ball {
+        let gap = other.mid.abs().add_with_rounding(&other.rad.negated(), RoundingMode::TowardNegative);
+        if gap.is_nan() || gap.is_zero() || gap.get_sign() {
+            return Ball::indeterminate();
+        }
+        let mid = self.mid.divide(&other.mid);
+        let spread = add_up(&mul_up(&other.mid.abs(), &self.rad), &mul_up(&self.mid.abs(), &other.rad));
+        let scale = other.mid.abs().multiply_with_rounding(&gap, RoundingMode::TowardNegative);
+        let propagated = if spread.is_zero() { spread } else { div_up(&spread, &scale) };
+        let rad = add_up(&propagated, &half_ulp(&mid));
+        Ball::from_parts(mid, rad)
+    }
+
+    // f(mid) and the radius from the error propagated through f plus an ulp for the faithful f(mid), or an
+    // indeterminate ball for a midpoint that isn't finite
+    fn function(&self, f: impl Fn(&F64, &mut Context) -> F64, propagate: impl Fn(&Float) -> Float) -> Ball {
+        if !self.mid.is_finite() {
+            return Ball::indeterminate();
+        }
+        let mid = hard(f(&soft(&self.mid), &mut Context::default()));
+        let propagated = if self.rad.is_zero() { Float::new(0.0) } else { propagate(&mid) };
+        let rad = add_up(&propagated, &ulp(&mid));
+        Ball::from_parts(mid, rad)
+    }
+
+    /// exp(m + d) - exp(m) is at most exp(m) (exp(r) - 1) for |d| <= r, which is the radius.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn exp(&self) -> Ball {
+        let grown = hard(soft(&self.rad).expm1(&mut Context::default())).next_up();
+        self.function(F64::exp, |mid| mul_up(&mid.next_up(), &grown))
+    }
+
+    /// ln has slope at most 1 / (m - r) on the ball, so the radius is r / (m - r). A ball reaching zero or
+    /// below gives an indeterminate ball.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn ln(&self) -> Ball {
+        let low = self.lower();
+        if low.is_nan() || low.is_zero() || low.get_sign() {
+            return Ball::indeterminate();
+        }
+        self.function(F64::ln, |_| div_up(&self.rad, &low))
+    }
+
+    /// sqrt(m) - sqrt(m - r) = r / (sqrt(m) + sqrt(m - r)) <= r / sqrt(m), which also bounds the change going
+    /// up, so that's the radius. sqrt is pow(x, 1/2) here, which agrees with it once x is known to be positive.
+    /// A ball reaching below zero gives an indeterminate ball.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn sqrt(&self) -> Ball {
+        let low = self.lower();
+        if low.is_nan() || low.get_sign() && !low.is_zero() {
+            return Ball::indeterminate();
+        }
+        let half = soft(&Float::new(0.5));
+        self.function(|x, ctx| x.powf(&half, ctx), |mid| div_up(&self.rad, &mid.next_down()))
+    }
+
+    /// sin and cos move no faster than their argument and never by more than 2, so the radius is min(r, 2).
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn sin(&self) -> Ball {
+        self.function(F64::sin, |_| bounded(&self.rad, 2.0))
+    }
+
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn cos(&self) -> Ball {
+        self.function(F64::cos, |_| bounded(&self.rad, 2.0))
+    }
+
+    /// atan's slope is at most 1 and its range is pi wide, so the radius is min(r, 4).
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn atan(&self) -> Ball {
+        self.function(F64::atan, |_| bounded(&self.rad, 4.0))
+    }
+}
+
+fn bounded(rad: &Float, limit: f64) -> Float {
+    if rad.to_f64() < limit {
+        rad.copy()
+    } else {
+        Float::new(limit)
+    }
+}
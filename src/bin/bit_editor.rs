@@ -0,0 +1,196 @@
+// terminal version of float.exposed: toggle bits and watch the value change.
+// run with `cargo run --features tui --bin bit_editor [initial value]`
+
+use std::io::{self, Write};
+
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEventKind},
+    execute, queue,
+    style::{Color, Print, ResetColor, SetAttribute, SetBackgroundColor, SetForegroundColor, Attribute},
+    terminal::{self, ClearType},
+};
+use floatfs::Float;
+
+enum Mode {
+    Bits,
+    Exponent, // typing an unbiased exponent
+    Mantissa, // typing the 52 mantissa bits in hex
+    Decimal, // typing a new value
+}
+
+struct Editor {
+    value: Float,
+    cursor: usize, // 0 is the sign bit, 63 is the last mantissa bit
+    mode: Mode,
+    input: String,
+    message: String,
+}
+
+fn field_color(i: usize) -> Color {
+    match i {
+        0 => Color::Blue,
+        1..=11 => Color::Green,
+        _ => Color::Red,
+    }
+}
+
+impl Editor {
+    fn toggle(&mut self) {
+        self.value = Float::from_bits(self.value.to_bits() ^ (1 << (63 - self.cursor)));
+    }
+
+    fn commit_input(&mut self) {
+        let v = &self.value;
+        let input = self.input.trim();
+        self.message.clear();
+        match self.mode {
+            Mode::Exponent => match input.parse::<i16>() {
+                Ok(exp) if (-1023..=1024).contains(&exp) => {
+                    self.value = Float::from_parts(v.get_sign(), exp, v.get_mantissa());
+                }
+                _ => self.message = format!("exponent must be an integer in -1023..=1024, got {:?}", input),
+            },
+            Mode::Mantissa => {
+                let digits = input.trim_start_matches("0x");
+                match u64::from_str_radix(digits, 16) {
+                    Ok(m) if m < (1 << 52) => {
+                        self.value = Float::from_parts(v.get_sign(), v.get_exponent(), m);
+                    }
+                    _ => self.message = format!("mantissa must be at most 13 hex digits, got {:?}", input),
+                }
+            }
+            Mode::Decimal => {
+                let parsed = if let Some(hex) = input.strip_prefix("0x") {
+                    u64::from_str_radix(hex, 16).ok().map(Float::from_bits)
+                } else {
+                    input.parse::<f64>().ok().map(Float::new)
+                };
+                match parsed {
+                    Some(f) => self.value = f,
+                    None => self.message = format!("couldn't parse {:?}", input),
+                }
+            }
+            Mode::Bits => {}
+        }
+        self.mode = Mode::Bits;
+        self.input.clear();
+    }
+
+    // returns false when the editor should quit
+    fn handle(&mut self, code: KeyCode) -> bool {
+        if !matches!(self.mode, Mode::Bits) {
+            match code {
+                KeyCode::Enter => self.commit_input(),
+                KeyCode::Esc => {
+                    self.mode = Mode::Bits;
+                    self.input.clear();
+                }
+                KeyCode::Backspace => {
+                    self.input.pop();
+                }
+                KeyCode::Char(c) => self.input.push(c),
+                _ => {}
+            }
+            return true;
+        }
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Left | KeyCode::Char('h') => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right | KeyCode::Char('l') => self.cursor = (self.cursor + 1).min(63),
+            KeyCode::Char(' ') | KeyCode::Enter => self.toggle(),
+            KeyCode::Up | KeyCode::Char('k') => self.value = self.value.next_up(),
+            KeyCode::Down | KeyCode::Char('j') => self.value = self.value.next_down(),
+            KeyCode::Char('n') => self.value.negate(),
+            KeyCode::Char('e') => self.mode = Mode::Exponent,
+            KeyCode::Char('m') => self.mode = Mode::Mantissa,
+            KeyCode::Char('d') => self.mode = Mode::Decimal,
+            _ => {}
+        }
+        true
+    }
+
+    fn draw(&self, out: &mut impl Write) -> io::Result<()> {
+        queue!(out, cursor::MoveTo(0, 0), terminal::Clear(ClearType::All))?;
+        queue!(out, Print("float bit editor\r\n\r\n"))?;
+
+        let bits = self.value.to_bits();
+        for i in 0..64 {
+            if i == 1 || i == 12 {
+                queue!(out, Print(" "))?;
+            }
+            let bit = (bits >> (63 - i)) & 1;
+            queue!(out, SetForegroundColor(field_color(i)), SetAttribute(Attribute::Bold))?;
+            if i == self.cursor {
+                queue!(out, SetBackgroundColor(Color::White))?;
+            }
+            queue!(out, Print(bit), ResetColor, SetAttribute(Attribute::Reset))?;
+        }
+        let pointer = self.cursor + if self.cursor >= 12 { 2 } else if self.cursor >= 1 { 1 } else { 0 };
+        queue!(out, Print(format!("\r\n{}^\r\n\r\n", " ".repeat(pointer))))?;
+
+        let v = &self.value;
+        let rows = [
+            format!("value:     {:e}", v.to_f64()),
+            format!("decimal:   {}", v.to_f64()),
+            format!("hex:       0x{:016x}", bits),
+            format!("class:     {:?}{}", v.classify(), if v.is_signaling_nan() { " (signaling)" } else { "" }),
+            format!("sign:      {}", v.get_sign() as u8),
+            format!("exponent:  {} (biased {})", v.get_exponent(), (bits >> 52) & 0x7FF),
+            format!("mantissa:  0x{:013x}", v.get_mantissa()),
+            format!("next down: {:e}", v.next_down().to_f64()),
+            format!("next up:   {:e}", v.next_up().to_f64()),
+        ];
+        for row in rows {
+            queue!(out, Print(row), Print("\r\n"))?;
+        }
+
+        let prompt = match self.mode {
+            Mode::Bits => String::new(),
+            Mode::Exponent => format!("new exponent: {}", self.input),
+            Mode::Mantissa => format!("new mantissa (hex): {}", self.input),
+            Mode::Decimal => format!("new value (decimal or 0x bits): {}", self.input),
+        };
+        queue!(
+            out,
+            Print("\r\n←/→ move  space toggle  ↑/↓ next up/down  n negate  e exponent  m mantissa  d value  q quit\r\n"),
+            Print(&self.message),
+            Print("\r\n"),
+            Print(prompt)
+        )?;
+        out.flush()
+    }
+}
+
+fn main() -> io::Result<()> {
+    let initial = std::env::args()
+        .nth(1)
+        .and_then(|s| s.parse::<f64>().ok())
+        .unwrap_or(1.1);
+    let mut editor = Editor {
+        value: Float::new(initial),
+        cursor: 0,
+        mode: Mode::Bits,
+        input: String::new(),
+        message: String::new(),
+    };
+
+    let mut out = io::stdout();
+    terminal::enable_raw_mode()?;
+    execute!(out, terminal::EnterAlternateScreen, cursor::Hide)?;
+
+    let result = (|| -> io::Result<()> {
+        loop {
+            editor.draw(&mut out)?;
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && !editor.handle(key.code) {
+                    return Ok(());
+                }
+            }
+        }
+    })();
+
+    execute!(out, cursor::Show, terminal::LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+    result
+}
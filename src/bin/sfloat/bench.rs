@@ -0,0 +1,190 @@
+// sfloat bench: how long the soft operations take, and how many times slower they are than the host's
+//
+//   $ sfloat bench --format f32 --op mul,div --class normal,subnormal --output csv >> bench.csv
+//
+// each row times one operation on one class of operands, cycling through a fixed pool of them (seeded, so every
+// run sees the same ones): normal operands near 1, a subnormal times a normal near 1 (the slow path in both),
+// or zeros, infinities and nans. the host timing is only there for f32 and f64, the formats it has.
+
+use std::hint::black_box;
+use std::ops::{Add, Div, Mul, Sub};
+use std::time::{Duration, Instant};
+
+use clap::ValueEnum;
+use floatfs::format::Storage;
+use floatfs::{Context, Format, SoftFloat};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::{value_name, with_format, FormatArg, OperationArg};
+
+#[derive(clap::Args)]
+pub struct Args {
+    #[arg(long, value_enum, default_value = "f64")]
+    format: FormatArg,
+    /// The operations to time, all of them if not given
+    #[arg(long, value_enum, value_delimiter = ',')]
+    op: Vec<OperationArg>,
+    /// The operand classes, all of them if not given
+    #[arg(long, value_enum, value_delimiter = ',')]
+    class: Vec<Class>,
+    /// Operations per row
+    #[arg(long, default_value_t = 1_000_000)]
+    iterations: u64,
+    #[arg(long, value_enum, default_value = "text")]
+    output: Output,
+}
+
+/// What the operands are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Class {
+    Normal,
+    Subnormal,
+    Special, // zeros, infinities and nans
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Output {
+    Text,
+    Csv,
+    Json,
+}
+
+// one operation on one class
+struct Row {
+    op: OperationArg,
+    class: Class,
+    soft: Duration,
+    host: Option<Duration>,
+}
+
+const POOL: usize = 1024;
+
+pub fn run(args: &Args) -> Result<(), String> {
+    with_format!(args.format, bench(args))
+}
+
+fn bench<F: Format>(args: &Args) -> Result<(), String> {
+    let ops = if args.op.is_empty() { OperationArg::value_variants().to_vec() } else { args.op.clone() };
+    let classes = if args.class.is_empty() { Class::value_variants().to_vec() } else { args.class.clone() };
+    let mut rows = Vec::new();
+    for &class in &classes {
+        let mut rng = StdRng::seed_from_u64(class as u64);
+        let pool: Vec<(u128, u128)> = (0..POOL).map(|_| operands::<F>(&mut rng, class)).collect();
+        for &op in &ops {
+            let soft = soft_time::<F>(op, &pool, args.iterations);
+            let host = match args.format {
+                FormatArg::F32 => Some(host_time(op, &pool, args.iterations, |bits| f32::from_bits(bits as u32))),
+                FormatArg::F64 => Some(host_time(op, &pool, args.iterations, |bits| f64::from_bits(bits as u64))),
+                _ => None,
+            };
+            rows.push(Row { op, class, soft, host });
+        }
+    }
+    print_rows(args, &rows);
+    Ok(())
+}
+
+// one pair of encodings
+fn operands<F: Format>(rng: &mut StdRng, class: Class) -> (u128, u128) {
+    let m = F::MANTISSA_BITS;
+    let bias = (1u128 << (F::EXPONENT_BITS - 1)) - 1;
+    let sign = |rng: &mut StdRng| (rng.random::<bool>() as u128) << (F::EXPONENT_BITS + m);
+    let mantissa = |rng: &mut StdRng| rng.random::<u128>() >> (128 - m);
+    // within a few binades of 1, so the results stay normal too
+    let near_one = |rng: &mut StdRng| sign(rng) | (bias - 2 + rng.random_range(0..5)) << m | mantissa(rng);
+    match class {
+        Class::Normal => (near_one(rng), near_one(rng)),
+        Class::Subnormal => (sign(rng) | mantissa(rng).max(1), near_one(rng)),
+        Class::Special => {
+            let all_ones = ((1u128 << F::EXPONENT_BITS) - 1) << m;
+            let special = |rng: &mut StdRng| match rng.random_range(0..4) {
+                0 => sign(rng),                         // a zero
+                1 => sign(rng) | all_ones,              // an infinity
+                2 => all_ones | 1 << (m - 1),           // a quiet nan
+                _ => all_ones | mantissa(rng) >> 1 | 1, // a signaling nan
+            };
+            (special(rng), special(rng))
+        }
+    }
+}
+
+fn soft_time<F: Format>(op: OperationArg, pool: &[(u128, u128)], iterations: u64) -> Duration {
+    let pool: Vec<(SoftFloat<F>, SoftFloat<F>)> = pool
+        .iter()
+        .map(|&(a, b)| (SoftFloat::from_bits(F::Bits::from_u128(a)), SoftFloat::from_bits(F::Bits::from_u128(b))))
+        .collect();
+    let mut ctx = Context::default();
+    match op {
+        OperationArg::Add => time(&pool, iterations, |x, y| x.add(&y, &mut ctx)),
+        OperationArg::Sub => time(&pool, iterations, |x, y| x.subtract(&y, &mut ctx)),
+        OperationArg::Mul => time(&pool, iterations, |x, y| x.multiply(&y, &mut ctx)),
+        OperationArg::Div => time(&pool, iterations, |x, y| x.divide(&y, &mut ctx)),
+    }
+}
+
+fn host_time<T>(op: OperationArg, pool: &[(u128, u128)], iterations: u64, from_bits: impl Fn(u128) -> T) -> Duration
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Div<Output = T>,
+{
+    let pool: Vec<(T, T)> = pool.iter().map(|&(a, b)| (from_bits(a), from_bits(b))).collect();
+    match op {
+        OperationArg::Add => time(&pool, iterations, |x, y| x + y),
+        OperationArg::Sub => time(&pool, iterations, |x, y| x - y),
+        OperationArg::Mul => time(&pool, iterations, |x, y| x * y),
+        OperationArg::Div => time(&pool, iterations, |x, y| x / y),
+    }
+}
+
+// black_box keeps the compiler from hoisting the operation out of the loop, or dropping it
+fn time<T: Copy, R>(pool: &[(T, T)], iterations: u64, mut operation: impl FnMut(T, T) -> R) -> Duration {
+    let start = Instant::now();
+    for i in 0..iterations as usize {
+        let (x, y) = black_box(pool[i % POOL]);
+        black_box(operation(x, y));
+    }
+    start.elapsed()
+}
+
+fn print_rows(args: &Args, rows: &[Row]) {
+    let per_op = |time: Duration| time.as_secs_f64() * 1e9 / args.iterations.max(1) as f64;
+    let slowdown = |row: &Row| row.host.map(|host| row.soft.as_secs_f64() / host.as_secs_f64());
+    let format = value_name(args.format);
+    match args.output {
+        Output::Text => {
+            println!("{:<6}{:<11}{:>12}{:>12}{:>10}", "op", "class", "soft ns", "host ns", "slowdown");
+            for row in rows {
+                let host = row.host.map_or("-".into(), |host| format!("{:.2}", per_op(host)));
+                let slowdown = slowdown(row).map_or("-".into(), |s| format!("{s:.1}x"));
+                let (op, class) = (value_name(row.op), value_name(row.class));
+                println!("{op:<6}{class:<11}{:>12.2}{host:>12}{slowdown:>10}", per_op(row.soft));
+            }
+        }
+        Output::Csv => {
+            println!("format,op,class,iterations,soft_ns,host_ns,slowdown");
+            for row in rows {
+                let host = row.host.map_or(String::new(), |host| format!("{:.3}", per_op(host)));
+                let slowdown = slowdown(row).map_or(String::new(), |s| format!("{s:.3}"));
+                let (op, class) = (value_name(row.op), value_name(row.class));
+                println!("{format},{op},{class},{},{:.3},{host},{slowdown}", args.iterations, per_op(row.soft));
+            }
+        }
+        Output::Json => {
+            // one object per line inside the array, so it diffs well too
+            println!("[");
+            for (i, row) in rows.iter().enumerate() {
+                let host = row.host.map_or("null".into(), |host| format!("{:.3}", per_op(host)));
+                let slowdown = slowdown(row).map_or("null".into(), |s| format!("{s:.3}"));
+                let (op, class) = (value_name(row.op), value_name(row.class));
+                let comma = if i + 1 < rows.len() { "," } else { "" };
+                println!(
+                    "  {{\"format\": \"{format}\", \"op\": \"{op}\", \"class\": \"{class}\", \"iterations\": {}, \
+                     \"soft_ns\": {:.3}, \"host_ns\": {host}, \"slowdown\": {slowdown}}}{comma}",
+                    args.iterations,
+                    per_op(row.soft)
+                );
+            }
+            println!("]");
+        }
+    }
+}
@@ -0,0 +1,307 @@
+// sfloat calc: an expression evaluated in one format and rounding mode, every literal and every operation rounded
+// as an FPU set up that way would, then the result and the flags raised along the way.
+//
+//   $ sfloat calc --rounding=down --format=f32 "1.1 * 3 + 0x1p-149"
+//   3.2999997e0  0x40533332
+//   flags      inexact
+//
+// --fixed=2 --group=, adds the result as 3.30, or 1,234.50, printed from the exact value.
+//
+// + - * / with the usual precedence, unary minus (a sign flip, which raises nothing), parentheses, and calls to the
+// library's functions: exp(x), pow(x, y) and so on. literals are anything inspect takes, so 0x7F800001 is that
+// encoding while 0x1p-149 is a hex float.
+
+use std::marker::PhantomData;
+
+use floatfs::{Context, Flags, Format, SoftFloat};
+
+use crate::{flag_names, hex, read_value, with_format, FormatArg, RoundingArg};
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// The expression, such as "1.1 * 3 + 0x1p-149" or "pow(2, 0.5) - 1"
+    #[arg(allow_hyphen_values = true)]
+    expression: String,
+    #[arg(long, value_enum, default_value = "f64")]
+    format: FormatArg,
+    #[arg(long, value_enum, default_value = "nearest")]
+    rounding: RoundingArg,
+    /// Print each operation with its result and the flags it raised
+    #[arg(long)]
+    steps: bool,
+    /// Also print the result with this many digits after the point
+    #[arg(long)]
+    fixed: Option<usize>,
+    /// Group the digits before the point in threes with this character, in the --fixed result
+    #[arg(long, requires = "fixed")]
+    group: Option<char>,
+}
+
+pub fn run(args: &Args) -> Result<(), String> {
+    with_format!(args.format, calc(args))
+}
+
+fn calc<F: Format>(args: &Args) -> Result<(), String> {
+    let mut evaluator = Evaluator::<F> {
+        tokens: tokenize(&args.expression)?,
+        position: 0,
+        depth: 0,
+        ctx: Context::new(args.rounding.into()),
+        steps: args.steps,
+        format: PhantomData,
+    };
+    let x = evaluator.expression()?;
+    if let Some(token) = evaluator.tokens.get(evaluator.position) {
+        return Err(format!("unexpected {token}"));
+    }
+    println!("{}  {}", x.to_shortest_decimal(), hex(&x));
+    if let Some(places) = args.fixed {
+        println!("fixed      {}", x.to_fixed_decimal(places, args.group));
+    }
+    println!("flags      {}", flag_names(evaluator.ctx.flags));
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Literal(String),
+    Name(String),
+    Symbol(char), // + - * / ( ) ,
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Token::Literal(text) | Token::Name(text) => write!(f, "{text}"),
+            Token::Symbol(c) => write!(f, "{c}"),
+        }
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() || c == '.' {
+            // a literal runs on through letters (hex digits, exponents) and takes a sign right after an exponent
+            // marker: e in decimal, p in hex, where e is a digit
+            let hex = chars[i..].starts_with(&['0', 'x']) || chars[i..].starts_with(&['0', 'X']);
+            let marker = if hex { ['p', 'P'] } else { ['e', 'E'] };
+            let start = i;
+            i += 1;
+            while i < chars.len()
+                && (chars[i].is_ascii_alphanumeric()
+                    || chars[i] == '.'
+                    || matches!(chars[i], '+' | '-') && marker.contains(&chars[i - 1]))
+            {
+                i += 1;
+            }
+            tokens.push(Token::Literal(chars[start..i].iter().collect()));
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let name: String = chars[start..i].iter().collect();
+            let literal = ["inf", "infinity", "nan"].iter().any(|n| name.eq_ignore_ascii_case(n));
+            tokens.push(if literal { Token::Literal(name) } else { Token::Name(name) });
+        } else if "+-*/(),".contains(c) {
+            tokens.push(Token::Symbol(c));
+            i += 1;
+        } else {
+            return Err(format!("unexpected {c:?}"));
+        }
+    }
+    Ok(tokens)
+}
+
+type Unary<F> = fn(&SoftFloat<F>, &mut Context) -> SoftFloat<F>;
+type Binary<F> = fn(&SoftFloat<F>, &SoftFloat<F>, &mut Context) -> SoftFloat<F>;
+
+fn unary<F: Format>(name: &str) -> Option<Unary<F>> {
+    Some(match name {
+        "exp" => SoftFloat::exp,
+        "exp2" => SoftFloat::exp2,
+        "exp10" => SoftFloat::exp10,
+        "expm1" => SoftFloat::expm1,
+        "ln" | "log" => SoftFloat::ln,
+        "log1p" => SoftFloat::log1p,
+        "log2" => SoftFloat::log2,
+        "log10" => SoftFloat::log10,
+        "cbrt" => SoftFloat::cbrt,
+        "sin" => SoftFloat::sin,
+        "cos" => SoftFloat::cos,
+        "tan" => SoftFloat::tan,
+        "sinpi" => SoftFloat::sinpi,
+        "cospi" => SoftFloat::cospi,
+        "asin" => SoftFloat::asin,
+        "acos" => SoftFloat::acos,
+        "atan" => SoftFloat::atan,
+        "sinh" => SoftFloat::sinh,
+        "cosh" => SoftFloat::cosh,
+        "tanh" => SoftFloat::tanh,
+        "asinh" => SoftFloat::asinh,
+        "acosh" => SoftFloat::acosh,
+        "atanh" => SoftFloat::atanh,
+        "tgamma" => SoftFloat::tgamma,
+        "lgamma" => SoftFloat::lgamma,
+        _ => return None,
+    })
+}
+
+fn binary<F: Format>(name: &str) -> Option<Binary<F>> {
+    Some(match name {
+        "pow" => SoftFloat::powf,
+        "hypot" => SoftFloat::hypot,
+        "atan2" => SoftFloat::atan2,
+        "fmin" => SoftFloat::fmin,
+        "fmax" => SoftFloat::fmax,
+        "fdim" => SoftFloat::fdim,
+        _ => return None,
+    })
+}
+
+// how deep parentheses, calls and prefix signs can nest before the recursion would run out of stack
+const MAX_DEPTH: usize = 256;
+
+// recursive descent, evaluating as it goes
+struct Evaluator<F: Format> {
+    tokens: Vec<Token>,
+    position: usize,
+    depth: usize, // unary calls in progress: every way of nesting goes through it
+    ctx: Context,
+    steps: bool,
+    format: PhantomData<F>,
+}
+
+impl<F: Format> Evaluator<F> {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn eat(&mut self, symbol: char) -> bool {
+        let found = self.tokens.get(self.position) == Some(&Token::Symbol(symbol));
+        self.position += found as usize;
+        found
+    }
+
+    fn expect(&mut self, symbol: char) -> Result<(), String> {
+        match self.next() {
+            Some(Token::Symbol(c)) if c == symbol => Ok(()),
+            Some(token) => Err(format!("expected {symbol}, found {token}")),
+            None => Err(format!("expected {symbol} at the end")),
+        }
+    }
+
+    // runs one operation with its own flags, so --steps can show them, then adds them to the running total
+    fn step(&mut self, label: String, operation: impl FnOnce(&mut Context) -> SoftFloat<F>) -> SoftFloat<F> {
+        let mut ctx = Context { flags: Flags::empty(), ..self.ctx };
+        let x = operation(&mut ctx);
+        self.ctx.flags.raise(ctx.flags);
+        if self.steps {
+            println!("{label} = {}  {}  ({})", x.to_shortest_decimal(), hex(&x), flag_names(ctx.flags));
+        }
+        x
+    }
+
+    fn expression(&mut self) -> Result<SoftFloat<F>, String> {
+        let mut x = self.term()?;
+        loop {
+            let (symbol, operation): (char, Binary<F>) = if self.eat('+') {
+                ('+', SoftFloat::add)
+            } else if self.eat('-') {
+                ('-', SoftFloat::subtract)
+            } else {
+                return Ok(x);
+            };
+            let y = self.term()?;
+            let label = format!("{} {symbol} {}", x.to_shortest_decimal(), y.to_shortest_decimal());
+            x = self.step(label, |ctx| operation(&x, &y, ctx));
+        }
+    }
+
+    fn term(&mut self) -> Result<SoftFloat<F>, String> {
+        let mut x = self.unary()?;
+        loop {
+            let (symbol, operation): (char, Binary<F>) = if self.eat('*') {
+                ('*', SoftFloat::multiply)
+            } else if self.eat('/') {
+                ('/', SoftFloat::divide)
+            } else {
+                return Ok(x);
+            };
+            let y = self.unary()?;
+            let label = format!("{} {symbol} {}", x.to_shortest_decimal(), y.to_shortest_decimal());
+            x = self.step(label, |ctx| operation(&x, &y, ctx));
+        }
+    }
+
+    fn unary(&mut self) -> Result<SoftFloat<F>, String> {
+        if self.depth == MAX_DEPTH {
+            return Err("expression nested too deeply".into());
+        }
+        self.depth += 1;
+        let x = self.signed();
+        self.depth -= 1;
+        x
+    }
+
+    fn signed(&mut self) -> Result<SoftFloat<F>, String> {
+        if self.eat('-') {
+            let mut x = self.unary()?;
+            x.negate();
+            return Ok(x);
+        }
+        if self.eat('+') {
+            return self.unary();
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<SoftFloat<F>, String> {
+        match self.next() {
+            Some(Token::Literal(text)) => {
+                // converting a literal is an operation too, with flags of its own
+                let mut ctx = Context { flags: Flags::empty(), ..self.ctx };
+                let x = read_value(&text, &mut ctx)?;
+                self.ctx.flags.raise(ctx.flags);
+                if self.steps && !ctx.flags.is_empty() {
+                    println!("{text} rounds to {}  {}  ({})", x.to_shortest_decimal(), hex(&x), flag_names(ctx.flags));
+                }
+                Ok(x)
+            }
+            Some(Token::Symbol('(')) => {
+                let x = self.expression()?;
+                self.expect(')')?;
+                Ok(x)
+            }
+            Some(Token::Name(name)) => {
+                self.expect('(')?;
+                let mut args = vec![self.expression()?];
+                while self.eat(',') {
+                    args.push(self.expression()?);
+                }
+                self.expect(')')?;
+                let label = format!(
+                    "{name}({})",
+                    args.iter().map(|a| a.to_shortest_decimal()).collect::<Vec<_>>().join(", ")
+                );
+                match (unary::<F>(&name), binary::<F>(&name), args.as_slice()) {
+                    (Some(f), _, [x]) => Ok(self.step(label, |ctx| f(x, ctx))),
+                    (_, Some(f), [x, y]) => Ok(self.step(label, |ctx| f(x, y, ctx))),
+                    (Some(_), _, _) => Err(format!("{name} takes one argument")),
+                    (_, Some(_), _) => Err(format!("{name} takes two arguments")),
+                    _ => Err(format!("no function called {name}")),
+                }
+            }
+            Some(token) => Err(format!("unexpected {token}")),
+            None => Err("unexpected end of the expression".into()),
+        }
+    }
+}
@@ -0,0 +1,33 @@
+// sfloat conformance: the IEEE 754-2019 conformance report, as a table or as json
+//
+//   $ sfloat conformance --json > conformance.json
+
+use floatfs::conformance::{self, Status};
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Print the machine-readable report instead of the table
+    #[arg(long)]
+    json: bool,
+}
+
+pub fn run(args: &Args) -> Result<(), String> {
+    let entries = conformance::report();
+    if args.json {
+        print!("{}", conformance::to_json(&entries));
+    } else {
+        let width = entries.iter().map(|e| e.operation.len()).max().unwrap_or(0).min(48); // the longest can run over
+        for e in &entries {
+            let provided = if e.status == Status::Unsupported { &e.detail } else { e.provided_by };
+            println!("{:<7}{:<width$}  {:<12}{:>7}  {provided}", e.clause, e.operation, e.status.name(), e.cases);
+            if e.status == Status::Failed {
+                println!("{:7}{:width$}  {}", "", "", e.detail);
+            }
+        }
+    }
+    let failed = entries.iter().filter(|e| e.status == Status::Failed).count();
+    if failed > 0 {
+        return Err(format!("{failed} requirements failed their checks"));
+    }
+    Ok(())
+}
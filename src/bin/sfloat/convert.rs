@@ -0,0 +1,227 @@
+// sfloat convert: a file of raw f64 or f32 values rounded into a narrower format, for preparing data sets the same
+// way on every machine
+//
+//   $ sfloat convert weights.f32 --to bf16 --output weights.bf16
+//   $ sfloat convert samples.f64 --from f64 --to e5m2 --rounding zero --output samples.fp8
+//   $ sfloat convert activations.f32 --to e4m3 --saturate --output activations.fp8
+//
+// both files are packed little-endian encodings, one after another, in the narrowest whole number of bytes (so
+// e5m2 is one byte each). the file is streamed, so it can be larger than memory. every value is converted with
+// convert in the given rounding mode, and the report counts the values that raised each flag: overflow to
+// infinity (or the largest finite value, rounding toward it), underflow, inexact, and invalid for signaling nans.
+//
+// E4M3 has no infinities, so it doesn't round like a Format: it goes through floatfs::quantize, unscaled and to
+// nearest even, from f32 (f64 is rounded to f32 first, which can't change the result: 24 bits is more than twice
+// E4M3's 4 plus 2). there overflow counts the finite values past 448, which become the nan, or with --saturate
+// ±448; underflow counts the nonzero values that became zero; and saturated counts the values --saturate replaced
+// by ±448, infinities included. decimal64 is written in the BID encoding by to_decimal64, with tininess before
+// rounding as IEEE 754 has it for decimal formats.
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::PathBuf;
+
+use clap::ValueEnum;
+use floatfs::format::{BFloat16, Binary16, Binary32, Binary64, Storage, E5M2};
+use floatfs::quantize::{dequantize, quantize, Element, Rounding, Saturation, Scaling};
+use floatfs::{Context, Flags, Format, SoftFloat, F32};
+
+use crate::{value_name, RoundingArg, TininessArg};
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// The raw values to read
+    input: PathBuf,
+    /// Where to write the converted values
+    #[arg(long, short)]
+    output: PathBuf,
+    #[arg(long, value_enum, default_value = "f32")]
+    from: Source,
+    /// The format to write
+    #[arg(long, value_enum)]
+    to: Target,
+    /// How to round; e4m3 only rounds to nearest
+    #[arg(long, value_enum, default_value = "nearest")]
+    rounding: RoundingArg,
+    /// When a result is tiny enough to raise underflow, after rounding if not given; decimal64 is always before
+    /// rounding, and e4m3 takes no --tininess, as its underflow counts the values that became zero
+    #[arg(long, value_enum)]
+    tininess: Option<TininessArg>,
+    /// Overflow to ±448 instead of the nan, and infinities too (e4m3 only)
+    #[arg(long)]
+    saturate: bool,
+}
+
+/// What the input file holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Source {
+    F32,
+    F64,
+}
+
+/// What to convert to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Target {
+    /// bfloat16: f32's 8 exponent bits and 7 mantissa bits
+    Bf16,
+    /// IEEE half
+    F16,
+    /// OCP FP8 E5M2, IEEE-like with 5 exponent bits and 2 mantissa bits
+    E5m2,
+    /// OCP FP8 E4M3FN: 4 exponent bits and 3 mantissa bits, no infinities and a largest finite value of 448
+    E4m3,
+    /// IEEE single, from f64 only
+    F32,
+    /// IEEE decimal64, in the binary integer decimal (BID) encoding
+    Decimal64,
+}
+
+pub fn run(args: &Args) -> Result<(), String> {
+    if args.saturate && args.to != Target::E4m3 {
+        return Err("--saturate is for --to e4m3; the other targets overflow as --rounding says".into());
+    }
+    match (args.from, args.to) {
+        (Source::F32, Target::Bf16) => convert::<Binary32, BFloat16>(args),
+        (Source::F32, Target::F16) => convert::<Binary32, Binary16>(args),
+        (Source::F32, Target::E5m2) => convert::<Binary32, E5M2>(args),
+        (Source::F32, Target::E4m3) => to_e4m3::<Binary32>(args),
+        (Source::F32, Target::F32) => Err("the input is already f32".into()),
+        (Source::F32, Target::Decimal64) => to_decimal64::<Binary32>(args),
+        (Source::F64, Target::Bf16) => convert::<Binary64, BFloat16>(args),
+        (Source::F64, Target::F16) => convert::<Binary64, Binary16>(args),
+        (Source::F64, Target::E5m2) => convert::<Binary64, E5M2>(args),
+        (Source::F64, Target::E4m3) => to_e4m3::<Binary64>(args),
+        (Source::F64, Target::F32) => convert::<Binary64, Binary32>(args),
+        (Source::F64, Target::Decimal64) => to_decimal64::<Binary64>(args),
+    }
+}
+
+// bytes per value in the file
+fn width<F: Format>() -> usize {
+    (1 + F::EXPONENT_BITS + F::MANTISSA_BITS).div_ceil(8) as usize
+}
+
+fn convert<F: Format, G: Format>(args: &Args) -> Result<(), String> {
+    let tininess = args.tininess.unwrap_or(TininessArg::After);
+    let mut ctx = Context { tininess: tininess.into(), ..Context::new(args.rounding.into()) };
+    let (values, counts) = stream(args, width::<F>(), width::<G>(), |bits| {
+        let x: SoftFloat<F> = SoftFloat::from_bits(F::Bits::from_u128(bits));
+        let y: SoftFloat<G> = x.convert(&mut ctx);
+        (y.to_bits().to_u128(), ctx.take_flags())
+    })?;
+    report(args, values, counts, F::NAME, G::NAME);
+    Ok(())
+}
+
+fn to_decimal64<F: Format>(args: &Args) -> Result<(), String> {
+    let mut ctx = Context::new(args.rounding.into());
+    let (values, counts) = stream(args, width::<F>(), 8, |bits| {
+        let x: SoftFloat<F> = SoftFloat::from_bits(F::Bits::from_u128(bits));
+        (x.to_decimal64(&mut ctx) as u128, ctx.take_flags())
+    })?;
+    report(args, values, counts, F::NAME, "decimal64");
+    Ok(())
+}
+
+fn to_e4m3<F: Format>(args: &Args) -> Result<(), String> {
+    if args.rounding != RoundingArg::Nearest {
+        return Err(format!("e4m3 only rounds to nearest, not {}", value_name(args.rounding)));
+    }
+    if args.tininess.is_some() {
+        return Err("--tininess isn't for e4m3, whose underflow count is the nonzero values that became zero".into());
+    }
+    let saturation = if args.saturate { Saturation::Saturating } else { Saturation::NonSaturating };
+    let mut ctx = Context::new(args.rounding.into());
+    let mut saturated = 0u64;
+    let (values, counts) = stream(args, width::<F>(), 1, |bits| {
+        let x: SoftFloat<F> = SoftFloat::from_bits(F::Bits::from_u128(bits));
+        // to f32 first, exactly from f32 but for quieting a signaling nan, which is the invalid flag
+        let y: F32 = x.convert(&mut ctx);
+        let narrowed = ctx.take_flags();
+        let y = f32::from_bits(y.to_bits());
+        let mut code = [0u8];
+        let stats = quantize(&[y], &mut code, Element::E4M3, Rounding::NearestEven, Scaling::Unscaled, saturation);
+        let mut back = [0f32];
+        dequantize(&code, &mut back, Element::E4M3, 0);
+        // past f32's range already counts as overflow, and below it as underflow if it became zero there
+        let overflow = stats.overflows == 1 || narrowed.contains(Flags::OVERFLOW);
+        let underflow = stats.underflows == 1 || !x.is_zero() && y == 0.0;
+        let mut flags = Flags::empty();
+        for (raised, flag) in [
+            (overflow, Flags::OVERFLOW),
+            (underflow, Flags::UNDERFLOW),
+            (narrowed.contains(Flags::INEXACT) || y.is_finite() && back[0] != y, Flags::INEXACT),
+            (narrowed.contains(Flags::INVALID), Flags::INVALID),
+        ] {
+            if raised {
+                flags.raise(flag);
+            }
+        }
+        saturated += (args.saturate && (overflow || y.is_infinite())) as u64;
+        (code[0] as u128, flags)
+    })?;
+    report(args, values, counts, F::NAME, "e4m3");
+    if args.saturate {
+        println!("{:<10} {saturated}", "saturated");
+    }
+    Ok(())
+}
+
+// reads whole values of from bytes each, encodes them to values of to bytes, and counts the values that raised
+// each flag in COUNTED
+fn stream(
+    args: &Args,
+    from: usize,
+    to: usize,
+    mut encode: impl FnMut(u128) -> (u128, Flags),
+) -> Result<(u64, [u64; 4]), String> {
+    let open = |path: &PathBuf| File::open(path).map_err(|e| format!("{}: {e}", path.display()));
+    let create = |path: &PathBuf| File::create(path).map_err(|e| format!("{}: {e}", path.display()));
+    let mut input = BufReader::new(open(&args.input)?);
+    let mut output = BufWriter::new(create(&args.output)?);
+    let write_error = |e: std::io::Error| format!("{}: {e}", args.output.display());
+
+    let (mut values, mut counts) = (0u64, [0u64; 4]);
+    let mut buffer = vec![0u8; from * 8192];
+    let mut filled = 0;
+    loop {
+        let read = input.read(&mut buffer[filled..]).map_err(|e| format!("{}: {e}", args.input.display()))?;
+        filled += read;
+        // whole values only; a partial one waits for the rest of its bytes
+        let whole = filled - filled % from;
+        for chunk in buffer[..whole].chunks_exact(from) {
+            let mut bytes = [0u8; 16];
+            bytes[..from].copy_from_slice(chunk);
+            let (y, flags) = encode(u128::from_le_bytes(bytes));
+            for (count, &(flag, _)) in counts.iter_mut().zip(&COUNTED) {
+                *count += flags.contains(flag) as u64;
+            }
+            output.write_all(&y.to_le_bytes()[..to]).map_err(write_error)?;
+        }
+        values += (whole / from) as u64;
+        buffer.copy_within(whole..filled, 0);
+        filled -= whole;
+        if read == 0 {
+            break;
+        }
+    }
+    output.flush().map_err(write_error)?;
+    if filled != 0 {
+        return Err(format!("{} ends partway through a value: {filled} bytes left over", args.input.display()));
+    }
+    Ok((values, counts))
+}
+
+const COUNTED: [(Flags, &str); 4] = [
+    (Flags::OVERFLOW, "overflow"),
+    (Flags::UNDERFLOW, "underflow"),
+    (Flags::INEXACT, "inexact"),
+    (Flags::INVALID, "invalid"),
+];
+
+fn report(args: &Args, values: u64, counts: [u64; 4], from: &str, to: &str) {
+    println!("{values} values, {from} to {to}, rounding {}", value_name(args.rounding));
+    for (count, (_, name)) in counts.iter().zip(COUNTED) {
+        println!("{name:<10} {count}");
+    }
+}
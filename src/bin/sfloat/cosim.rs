@@ -0,0 +1,98 @@
+// sfloat cosim: floatfs as a golden model behind a pipe, for an RTL simulation or a program in another language
+// to check its results against a line at a time without linking anything
+//
+//   $ sfloat cosim --format f32
+//   add rne 3F800000 33800000
+//   3F800000 01
+//   div 2 0x3F800000 0x00000000
+//   7F800000 08
+//
+// a request is the operation (add, sub, mul, div, or the testbench op codes 0 to 3), the rounding mode (rne, rtz,
+// rdn, rup, rmm, or the risc-v rm field 0 to 4), and two operands in hex with or without 0x. the answer is the
+// result and the flags as testbench vectors have them: the encoding in hex, zero padded, and the five flags in the
+// fflags layout (NV DZ OF UF NX from bit 4 down). a request that can't be read gets "error" and why, so the two
+// sides stay in step; blank lines and lines starting with # get nothing.
+
+use std::io::{BufRead, Write};
+
+use floatfs::format::Storage;
+use floatfs::testbench::{self, Vector};
+use floatfs::{riscv, Context, Format, Operation, RoundingMode, SoftFloat};
+
+use crate::{with_format, FormatArg, NanArg, TininessArg};
+
+#[derive(clap::Args)]
+pub struct Args {
+    #[arg(long, value_enum, default_value = "f32")]
+    format: FormatArg,
+    /// Which nan an operation on nans gives
+    #[arg(long, value_enum, default_value = "propagate")]
+    nan: NanArg,
+    /// When a result is tiny enough to raise underflow
+    #[arg(long, value_enum, default_value = "after")]
+    tininess: TininessArg,
+}
+
+pub fn run(args: &Args) -> Result<(), String> {
+    with_format!(args.format, cosim(args))
+}
+
+fn cosim<F: Format>(args: &Args) -> Result<(), String> {
+    let ctx = Context { nan_mode: args.nan.into(), tininess: args.tininess.into(), ..Context::default() };
+    let mut out = std::io::stdout().lock();
+    for line in std::io::stdin().lock().lines() {
+        let line = line.map_err(|e| e.to_string())?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let answer = match request::<F>(line) {
+            Ok((op, rounding, a, b)) => {
+                let v = Vector::new(op, a, b, &Context { rounding, ..ctx });
+                testbench::line("{result} {flags}", &v)
+            }
+            Err(message) => format!("error {message}"),
+        };
+        // flushed every line, as the other side waits for it before sending the next
+        writeln!(out, "{answer}").and_then(|_| out.flush()).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+type Request<F> = (Operation, RoundingMode, SoftFloat<F>, SoftFloat<F>);
+
+fn request<F: Format>(line: &str) -> Result<Request<F>, String> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let [op, rounding, a, b] = fields[..] else {
+        return Err(format!("expected 4 fields, op rm a b, not {}", fields.len()));
+    };
+    let op = match op.to_ascii_lowercase().as_str() {
+        "add" | "0" => Operation::Add,
+        "sub" | "1" => Operation::Subtract,
+        "mul" | "2" => Operation::Multiply,
+        "div" | "3" => Operation::Divide,
+        _ => return Err(format!("unknown operation {op}")),
+    };
+    let rm = match rounding.to_ascii_lowercase().as_str() {
+        "rne" => 0,
+        "rtz" => 1,
+        "rdn" => 2,
+        "rup" => 3,
+        "rmm" => 4,
+        digit => digit.parse().unwrap_or(u8::MAX),
+    };
+    let rounding = riscv::rounding_from_rm(rm).ok_or_else(|| format!("unknown rounding mode {rounding}"))?;
+    Ok((op, rounding, operand(a)?, operand(b)?))
+}
+
+fn operand<F: Format>(text: &str) -> Result<SoftFloat<F>, String> {
+    let hex = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")).unwrap_or(text);
+    let width = 1 + F::EXPONENT_BITS + F::MANTISSA_BITS;
+    if hex.is_empty() || !hex.bytes().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("{text} isn't hex"));
+    }
+    match u128::from_str_radix(hex, 16) {
+        Ok(bits) if bits >> width == 0 => Ok(SoftFloat::from_bits(F::Bits::from_u128(bits))),
+        _ => Err(format!("{text} is wider than {width} bits")),
+    }
+}
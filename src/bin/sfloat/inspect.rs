@@ -0,0 +1,85 @@
+// sfloat inspect: everything about one value, generic over the format
+//
+//   $ sfloat inspect --format f16 0x3c00
+//   binary16 0x3C00
+//     0 01111 0000000000
+//     s eeeee mmmmmmmmmm
+//   sign       0 (positive)
+//   exponent   15 - 15 = 0
+//   ...
+
+use std::num::FpCategory;
+
+use floatfs::format::Storage;
+use floatfs::{Context, Format, SoftFloat};
+
+use crate::{flag_names, hex, is_encoding, read_value, with_format, FormatArg, RoundingArg};
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// A decimal like 1.1, a hex float like 0x1.8p3, inf or nan, or the encoding as 0x and hex digits
+    #[arg(allow_hyphen_values = true)]
+    value: String,
+    #[arg(long, value_enum, default_value = "f64")]
+    format: FormatArg,
+    /// How a literal that isn't exactly representable is rounded
+    #[arg(long, value_enum, default_value = "nearest")]
+    rounding: RoundingArg,
+}
+
+pub fn run(args: &Args) -> Result<(), String> {
+    with_format!(args.format, inspect(args))
+}
+
+fn inspect<F: Format>(args: &Args) -> Result<(), String> {
+    let mut ctx = Context::new(args.rounding.into());
+    let x: SoftFloat<F> = read_value(&args.value, &mut ctx)?;
+    let (e, m) = (F::EXPONENT_BITS as usize, F::MANTISSA_BITS as usize);
+    let bias = (1i32 << (e - 1)) - 1;
+    let bits = format!("{:0width$b}", x.to_bits().to_u128(), width = 1 + e + m);
+
+    // a literal says whether it was rounded; an encoding is taken as is
+    if is_encoding(&args.value) {
+        println!("{} {}", F::NAME, hex(&x));
+    } else {
+        let rounded = if ctx.flags.is_empty() { "exact".into() } else { flag_names(ctx.flags) };
+        println!("{} {}, from {} ({rounded})", F::NAME, hex(&x), args.value);
+    }
+    println!("  {} {} {}", &bits[..1], &bits[1..1 + e], &bits[1 + e..]);
+    println!("  s {} {}", "e".repeat(e), "m".repeat(m));
+
+    println!("sign       {} ({})", x.get_sign() as u8, if x.get_sign() { "negative" } else { "positive" });
+    let biased = x.biased_exponent() as i32;
+    let class = x.classify();
+    match class {
+        FpCategory::Normal => println!("exponent   {biased} - {bias} = {}", biased - bias),
+        FpCategory::Subnormal => println!("exponent   0, subnormal: read as 1 - {bias} = {}", 1 - bias),
+        FpCategory::Zero => println!("exponent   0"),
+        FpCategory::Infinite | FpCategory::Nan => println!("exponent   {biased} (all ones)"),
+    }
+    let mantissa = format!("0x{:0digits$X}", x.get_mantissa(), digits = m.div_ceil(4));
+    match class {
+        FpCategory::Normal => println!("mantissa   {mantissa}, significand 1.{}", &bits[1 + e..]),
+        FpCategory::Subnormal => println!("mantissa   {mantissa}, significand 0.{}", &bits[1 + e..]),
+        _ => println!("mantissa   {mantissa}"),
+    }
+    let name = match class {
+        FpCategory::Nan if x.is_signaling_nan() => "signaling nan",
+        FpCategory::Nan => "quiet nan",
+        FpCategory::Infinite => "infinite",
+        FpCategory::Zero => "zero",
+        FpCategory::Subnormal => "subnormal",
+        FpCategory::Normal => "normal",
+    };
+    println!("class      {name}");
+    if x.is_nan() {
+        return Ok(());
+    }
+
+    println!("exact      {}", x.to_exact_decimal());
+    println!("shortest   {}", x.to_shortest_decimal());
+    let (down, up) = (x.next_down(), x.next_up());
+    println!("next down  {}  {}", hex(&down), down.to_shortest_decimal());
+    println!("next up    {}  {}", hex(&up), up.to_shortest_decimal());
+    Ok(())
+}
@@ -0,0 +1,224 @@
+// the sfloat command line tool, for looking at floats without writing a program:
+// `cargo run --features cli --bin sfloat -- inspect 1.1`
+
+mod bench;
+mod calc;
+mod conformance;
+mod convert;
+mod cosim;
+mod inspect;
+mod step;
+mod stress;
+mod vectors;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use floatfs::format::Storage;
+use floatfs::{Context, Flags, Format, NanMode, Operation, RoundingMode, SoftFloat, Tininess};
+
+#[derive(Parser)]
+#[command(name = "sfloat", about = "Software floating point from the command line")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Show a value's bit layout, fields, exact decimal value and neighbors
+    Inspect(inspect::Args),
+    /// Evaluate an expression with every operation rounded, and show the flags it raised
+    Calc(calc::Args),
+    /// Check an operation on random operands against the host's arithmetic
+    Stress(stress::Args),
+    /// Time the soft operations against the host's
+    Bench(bench::Args),
+    /// Check the crate against the operations IEEE 754-2019 requires and recommends
+    Conformance(conformance::Args),
+    /// Write test vectors for a hardware testbench, one per line in hex
+    Vectors(vectors::Args),
+    /// Answer operation requests from stdin with results and flags, for co-simulation
+    Cosim(cosim::Args),
+    /// Step through an addition or multiplication a stage at a time, for teaching
+    Step(step::Args),
+    /// Convert a file of raw f32 or f64 values to a narrower format or decimal64, counting overflows and underflows
+    Convert(convert::Args),
+}
+
+/// The formats a value can be in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FormatArg {
+    F16,
+    F32,
+    F64,
+    F80, // x87 extended, without the explicit integer bit
+    Minifloat, // 4 exponent bits and 3 mantissa bits, IEEE-like
+}
+
+/// Calls `$run::<F>($args...)` with F the format picked by `$format`.
+macro_rules! with_format {
+    ($format:expr, $run:ident($($args:expr),*)) => {
+        match $format {
+            $crate::FormatArg::F16 => $run::<floatfs::format::Binary16>($($args),*),
+            $crate::FormatArg::F32 => $run::<floatfs::format::Binary32>($($args),*),
+            $crate::FormatArg::F64 => $run::<floatfs::format::Binary64>($($args),*),
+            $crate::FormatArg::F80 => $run::<floatfs::format::Extended>($($args),*),
+            $crate::FormatArg::Minifloat => $run::<floatfs::format::Minifloat>($($args),*),
+        }
+    };
+}
+pub(crate) use with_format;
+
+/// The basic operations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OperationArg {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl OperationArg {
+    pub fn operation(self) -> Operation {
+        match self {
+            OperationArg::Add => Operation::Add,
+            OperationArg::Sub => Operation::Subtract,
+            OperationArg::Mul => Operation::Multiply,
+            OperationArg::Div => Operation::Divide,
+        }
+    }
+
+    pub fn symbol(self) -> char {
+        match self {
+            OperationArg::Add => '+',
+            OperationArg::Sub => '-',
+            OperationArg::Mul => '*',
+            OperationArg::Div => '/',
+        }
+    }
+}
+
+/// How a result is rounded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum RoundingArg {
+    Nearest, // ties to even
+    Away, // nearest, ties away from zero
+    Zero,
+    Up,
+    Down,
+}
+
+impl From<RoundingArg> for RoundingMode {
+    fn from(rounding: RoundingArg) -> RoundingMode {
+        match rounding {
+            RoundingArg::Nearest => RoundingMode::TiesToEven,
+            RoundingArg::Away => RoundingMode::TiesToAway,
+            RoundingArg::Zero => RoundingMode::TowardZero,
+            RoundingArg::Up => RoundingMode::TowardPositive,
+            RoundingArg::Down => RoundingMode::TowardNegative,
+        }
+    }
+}
+
+/// Which nan an operation on nans gives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum NanArg {
+    Propagate,         // the operand's payload, quieted
+    Canonical,         // always the default nan, as risc-v
+    FirstOperand,      // x86 sse
+    LargerSignificand, // x87
+}
+
+impl From<NanArg> for NanMode {
+    fn from(nan: NanArg) -> NanMode {
+        match nan {
+            NanArg::Propagate => NanMode::Propagate,
+            NanArg::Canonical => NanMode::Canonical,
+            NanArg::FirstOperand => NanMode::FirstOperand,
+            NanArg::LargerSignificand => NanMode::LargerSignificand,
+        }
+    }
+}
+
+/// When a result is tiny enough to raise underflow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TininessArg {
+    Before, // arm
+    After,  // x86, risc-v
+}
+
+impl From<TininessArg> for Tininess {
+    fn from(tininess: TininessArg) -> Tininess {
+        match tininess {
+            TininessArg::Before => Tininess::BeforeRounding,
+            TininessArg::After => Tininess::AfterRounding,
+        }
+    }
+}
+
+/// Whether text is an encoding, 0x and nothing but hex digits, rather than a literal.
+pub fn is_encoding(text: &str) -> bool {
+    let hex = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X"));
+    hex.is_some_and(|hex| !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// A value as typed: an encoding (see is_encoding), or a literal for SoftFloat::parse (decimal, a hex float with a
+/// p exponent, inf or nan) rounded in ctx.
+pub fn read_value<F: Format>(text: &str, ctx: &mut Context) -> Result<SoftFloat<F>, String> {
+    if is_encoding(text) {
+        let width = 1 + F::EXPONENT_BITS + F::MANTISSA_BITS;
+        let bits = u128::from_str_radix(&text[2..], 16).map_err(|_| format!("{text} is too long for an encoding"))?;
+        if bits >> width != 0 {
+            return Err(format!("{text} has more than the {width} bits of {}", F::NAME));
+        }
+        return Ok(SoftFloat::from_bits(F::Bits::from_u128(bits)));
+    }
+    SoftFloat::parse(text, ctx).map_err(|e| format!("{text}: {e}"))
+}
+
+/// The encoding in hex, padded to the format's width: 0x3C00 for 1 in f16.
+pub fn hex<F: Format>(x: &SoftFloat<F>) -> String {
+    let digits = (1 + F::EXPONENT_BITS + F::MANTISSA_BITS).div_ceil(4) as usize;
+    format!("0x{:0digits$X}", x.to_bits().to_u128())
+}
+
+/// The name clap knows a value by, for printing a command line back: f32 for FormatArg::F32.
+pub fn value_name(value: impl ValueEnum) -> String {
+    value.to_possible_value().map_or(String::new(), |v| v.get_name().into())
+}
+
+/// The names of the raised flags, or "none".
+pub fn flag_names(flags: Flags) -> String {
+    let names = [
+        (Flags::INVALID, "invalid"),
+        (Flags::DIVIDE_BY_ZERO, "divide by zero"),
+        (Flags::OVERFLOW, "overflow"),
+        (Flags::UNDERFLOW, "underflow"),
+        (Flags::INEXACT, "inexact"),
+        (Flags::INPUT_DENORMAL, "input denormal"),
+        (Flags::OUTPUT_DENORMAL, "output denormal"),
+    ];
+    let raised: Vec<&str> = names.iter().filter(|(flag, _)| flags.contains(*flag)).map(|(_, name)| *name).collect();
+    if raised.is_empty() {
+        "none".into()
+    } else {
+        raised.join(", ")
+    }
+}
+
+fn main() {
+    let result = match Cli::parse().command {
+        Command::Inspect(args) => inspect::run(&args),
+        Command::Calc(args) => calc::run(&args),
+        Command::Stress(args) => stress::run(&args),
+        Command::Bench(args) => bench::run(&args),
+        Command::Conformance(args) => conformance::run(&args),
+        Command::Vectors(args) => vectors::run(&args),
+        Command::Cosim(args) => cosim::run(&args),
+        Command::Step(args) => step::run(&args),
+        Command::Convert(args) => convert::run(&args),
+    };
+    if let Err(message) = result {
+        eprintln!("error: {message}");
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,132 @@
+// sfloat step: a repl for computer architecture courses. type an operation and it goes through what an FPU does
+// with it a stage at a time, waiting for enter between stages: extract the fields, align, add, normalize, round
+// for + and -, and extract the fields, multiply, normalize, round for *.
+//
+//   $ sfloat step
+//   > 1 + 0x1p-53
+//   1. extract fields
+//      a: sign 0, exponent     0, significand 1.0000000000000000000000000000000000000000000000000000
+//      b: sign 0, exponent   -53, significand 1.0000000000000000000000000000000000000000000000000000
+//   (enter for the next stage, q for the rest at once)
+//
+// binary64 only, which is what Float::add_explained and Float::multiply_explained work in. operands are anything
+// inspect takes. "rounding down" (or any other --rounding name) changes the mode, "quit" leaves. when stdin isn't
+// a terminal, or with --all, it prints every stage without stopping, so a file of operations makes a handout.
+
+use std::io::{BufRead, IsTerminal, Write};
+
+use clap::ValueEnum;
+use floatfs::format::Binary64;
+use floatfs::{Context, Flags, Float, RoundingMode, SoftFloat};
+
+use crate::{read_value, value_name, RoundingArg};
+
+#[derive(clap::Args)]
+pub struct Args {
+    #[arg(long, value_enum, default_value = "nearest")]
+    rounding: RoundingArg,
+    /// Print every stage at once instead of stopping after each
+    #[arg(long)]
+    all: bool,
+}
+
+const HELP: &str = "\
+an operation is two operands with +, - or * between them, spaced out: 1.5 + 0x1p-60, 0.1 * 3, 1 - 0x3FEFFFFFFFFFFFFF
+rounding nearest|away|zero|up|down   change the rounding mode
+help                                 this
+quit                                 leave";
+
+pub fn run(args: &Args) -> Result<(), String> {
+    let interactive = std::io::stdin().is_terminal();
+    let pause = interactive && !args.all;
+    let mut rounding = args.rounding;
+    let mut lines = std::io::stdin().lock().lines();
+    if interactive {
+        println!("binary64, rounding {}. type help for what it takes", value_name(rounding));
+    }
+    loop {
+        if interactive {
+            print!("> ");
+            std::io::stdout().flush().map_err(|e| e.to_string())?;
+        }
+        let Some(line) = lines.next() else {
+            return Ok(());
+        };
+        let line = line.map_err(|e| e.to_string())?;
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words[..] {
+            [] => {}
+            ["quit" | "exit" | "q"] => return Ok(()),
+            ["help"] => println!("{HELP}"),
+            ["rounding"] => println!("rounding {}", value_name(rounding)),
+            ["rounding", name] => match RoundingArg::from_str(name, true) {
+                Ok(mode) => rounding = mode,
+                Err(_) => println!("no rounding mode called {name}"),
+            },
+            _ => match explain(&words, rounding.into()) {
+                Ok(text) => show(&text, pause, &mut lines)?,
+                Err(message) => println!("{message}"),
+            },
+        }
+    }
+}
+
+// the explanation for one operation, with a note first for anything that isn't what it looks like
+fn explain(words: &[&str], mode: RoundingMode) -> Result<String, String> {
+    let [a, op, b] = words[..] else {
+        return Err("expected an operation like 1.5 + 0x1p-60, with spaces around the operator (or help)".into());
+    };
+    let mut notes = String::new();
+    let x = operand(a, mode, &mut notes)?;
+    let mut y = operand(b, mode, &mut notes)?;
+    let explanation = match op {
+        "+" => x.add_explained_with_rounding(&y, mode).to_string(),
+        "-" => {
+            // subtraction is an addition with b's sign flipped, which is all the hardware does too
+            notes.push_str(&format!("{a} - {b} is {a} + (-{b})\n"));
+            y.negate();
+            x.add_explained_with_rounding(&y, mode).to_string()
+        }
+        "*" => x.multiply_explained_with_rounding(&y, mode).to_string(),
+        _ => return Err(format!("no stages for {op}, only +, - and *")),
+    };
+    Ok(notes + &explanation)
+}
+
+fn operand(text: &str, mode: RoundingMode, notes: &mut String) -> Result<Float, String> {
+    let mut ctx = Context::new(mode);
+    let x: SoftFloat<Binary64> = read_value(text, &mut ctx)?;
+    if ctx.flags.contains(Flags::INEXACT) {
+        // worth saying, since 0.1 + 0.2 is really adding two other numbers
+        notes.push_str(&format!("{text} isn't exact in binary64, so it's {}\n", x.to_exact_decimal()));
+    }
+    Ok(Float::from_bits(x.to_bits()))
+}
+
+// prints the explanation a stage at a time: a stage starts at a numbered line and runs to the next one
+fn show(text: &str, pause: bool, lines: &mut impl Iterator<Item = std::io::Result<String>>) -> Result<(), String> {
+    let mut stages: Vec<String> = Vec::new();
+    for line in text.lines() {
+        match stages.last_mut() {
+            Some(stage) if !line.starts_with(|c: char| c.is_ascii_digit()) => {
+                stage.push('\n');
+                stage.push_str(line);
+            }
+            _ => stages.push(line.to_string()),
+        }
+    }
+    let mut pause = pause;
+    for (i, stage) in stages.iter().enumerate() {
+        println!("{stage}");
+        if pause && i + 1 < stages.len() && stage.starts_with(|c: char| c.is_ascii_digit()) {
+            print!("(enter for the next stage, q for the rest at once) ");
+            std::io::stdout().flush().map_err(|e| e.to_string())?;
+            match lines.next() {
+                Some(Ok(answer)) => pause = answer.trim() != "q",
+                Some(Err(e)) => return Err(e.to_string()),
+                None => pause = false,
+            }
+        }
+    }
+    Ok(())
+}
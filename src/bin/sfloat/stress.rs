@@ -0,0 +1,215 @@
+// sfloat stress: random operands through one soft operation, checked against the host's f64 arithmetic
+//
+//   $ sfloat stress --op add --format f32 --distribution near --iterations 100000000 --threads 8
+//
+// the reference is the host result in f64, narrowed to the format with convert. that's the correctly rounded
+// result for every format here: f64 has more than 2p + 2 bits for p up to 24, so rounding twice can't go wrong
+// (and f64 itself is just the host). f80 has no host type, so on x86_64 its reference is the x87 itself, as in
+// x87_hardware_test in main.rs, and elsewhere there's none. only round to nearest, as that's all the host does
+// without touching its control register.
+//
+// each thread runs its own generator, seeded with the seed plus its number, so a failure reruns on one thread
+// with the seed it printed.
+
+use std::any::TypeId;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+
+use clap::ValueEnum;
+use floatfs::format::{Binary64, Extended, Storage};
+#[cfg(target_arch = "x86_64")]
+use floatfs::F80;
+use floatfs::{random, Context, Format, Operation, SoftFloat};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::{hex, value_name, with_format, FormatArg, OperationArg};
+
+#[derive(clap::Args)]
+pub struct Args {
+    #[arg(long, value_enum, default_value = "mul")]
+    op: OperationArg,
+    #[arg(long, value_enum, default_value = "f64")]
+    format: FormatArg,
+    /// Operations to check, split between the threads
+    #[arg(long, default_value_t = 10_000_000)]
+    iterations: u64,
+    /// The first thread's seed; a random one if not given
+    #[arg(long)]
+    seed: Option<u64>,
+    #[arg(long, value_enum, default_value = "bits")]
+    distribution: Distribution,
+    /// Defaults to one per core
+    #[arg(long)]
+    threads: Option<usize>,
+}
+
+/// How the operands are picked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Distribution {
+    Bits,    // uniformly random encodings
+    Special, // floatfs::random::special: zeros, subnormals, infinities, nans and the edges of each range
+    Near,    // the second operand close to the first, so add and sub cancel
+}
+
+pub fn run(args: &Args) -> Result<(), String> {
+    with_format!(args.format, stress(args))
+}
+
+// a failing case: the thread's seed and how far it got, and what went wrong
+struct Failure {
+    seed: u64,
+    iterations: u64,
+    report: String,
+}
+
+fn stress<F: Format>(args: &Args) -> Result<(), String> {
+    let x87 = cfg!(target_arch = "x86_64") && TypeId::of::<F>() == TypeId::of::<Extended>();
+    if (F::EXPONENT_BITS > 11 || F::MANTISSA_BITS > 52) && !x87 {
+        return Err(format!("no reference for {}: the host only has f64, and an x87 on x86_64", F::NAME));
+    }
+    let seed = args.seed.unwrap_or_else(rand::random);
+    let threads = args.threads.unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |n| n.get())).max(1);
+    let plural = if threads == 1 { "" } else { "s" };
+    println!("{} {}, seed {seed}, {} iterations on {threads} thread{plural}", F::NAME, value_name(args.op), args.iterations);
+
+    let start = Instant::now();
+    let stop = AtomicBool::new(false);
+    let failures: Vec<Failure> = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads as u64)
+            .map(|t| {
+                // the first threads take the remainder
+                let iterations = args.iterations / threads as u64 + (t < args.iterations % threads as u64) as u64;
+                let (seed, stop) = (seed.wrapping_add(t), &stop);
+                scope.spawn(move || check::<F>(args, seed, iterations, stop))
+            })
+            .collect();
+        workers.into_iter().filter_map(|worker| worker.join().unwrap()).collect()
+    });
+
+    let Some(failure) = failures.first() else {
+        println!("passed in {:.1?}", start.elapsed());
+        return Ok(());
+    };
+    println!("{}", failure.report);
+    println!("rerun with:");
+    println!(
+        "  sfloat stress --op {} --format {} --distribution {} --seed {} --iterations {} --threads 1",
+        value_name(args.op),
+        value_name(args.format),
+        value_name(args.distribution),
+        failure.seed,
+        failure.iterations
+    );
+    Err("stress test failed".into())
+}
+
+fn check<F: Format>(args: &Args, seed: u64, iterations: u64, stop: &AtomicBool) -> Option<Failure> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let operation = args.op.operation();
+    for i in 0..iterations {
+        // every so often, see whether another thread already failed
+        if i % 65536 == 0 && stop.load(Ordering::Relaxed) {
+            return None;
+        }
+        let (x, y) = operands::<F>(&mut rng, args.distribution, args.op);
+        let actual = operation.apply(&x, &y, &mut Context::default());
+        let expected = reference(operation, &x, &y);
+        if actual.to_bits() == expected.to_bits() || actual.is_nan() && expected.is_nan() {
+            continue;
+        }
+        stop.store(true, Ordering::Relaxed);
+        let symbol = args.op.symbol();
+        let report = format!(
+            "mismatch: {} {symbol} {}\n  x         {}  {}\n  y         {}  {}\n  expected  {}  {}\n  actual    {}  {}\n\
+             try: sfloat calc --format {} --steps \"{} {symbol} {}\"",
+            hex(&x),
+            hex(&y),
+            hex(&x),
+            x.to_shortest_decimal(),
+            hex(&y),
+            y.to_shortest_decimal(),
+            hex(&expected),
+            expected.to_shortest_decimal(),
+            hex(&actual),
+            actual.to_shortest_decimal(),
+            value_name(args.format),
+            hex(&x),
+            hex(&y),
+        );
+        return Some(Failure { seed, iterations: i + 1, report });
+    }
+    None
+}
+
+fn operands<F: Format>(rng: &mut StdRng, distribution: Distribution, op: OperationArg) -> (SoftFloat<F>, SoftFloat<F>) {
+    let width = 1 + F::EXPONENT_BITS + F::MANTISSA_BITS;
+    let sign = 1u128 << (width - 1);
+    let (a, b) = (rng.random::<u128>() >> (128 - width), rng.random::<u128>() >> (128 - width));
+    let (a, b) = match distribution {
+        Distribution::Bits => (a, b),
+        Distribution::Special => {
+            let mut bits = || rng.random();
+            let (x, y) = (random::special::<F>(&mut bits), random::special::<F>(&mut bits));
+            (x.to_bits().to_u128(), y.to_bits().to_u128())
+        }
+        Distribution::Near => {
+            let low = b >> rng.random_range(1..width);
+            // opposite signs for add, so it's a subtraction too
+            (a, if op == OperationArg::Add { a ^ sign ^ low } else { a ^ low })
+        }
+    };
+    (SoftFloat::from_bits(F::Bits::from_u128(a)), SoftFloat::from_bits(F::Bits::from_u128(b)))
+}
+
+// the host's f64 result, narrowed to F, or for f80 the x87's
+fn reference<F: Format>(operation: Operation, x: &SoftFloat<F>, y: &SoftFloat<F>) -> SoftFloat<F> {
+    #[cfg(target_arch = "x86_64")]
+    if TypeId::of::<F>() == TypeId::of::<Extended>() {
+        let f80 = |x: &SoftFloat<F>| F80::from_bits(x.to_bits().to_u128());
+        return SoftFloat::from_bits(F::Bits::from_u128(x87(operation, &f80(x), &f80(y)).to_bits()));
+    }
+    let host = |x: &SoftFloat<F>| f64::from_bits(x.convert::<Binary64>(&mut Context::default()).to_bits());
+    let (a, b) = (host(x), host(y));
+    let result = match operation {
+        Operation::Add => a + b,
+        Operation::Subtract => a - b,
+        Operation::Multiply => a * b,
+        Operation::Divide => a / b,
+    };
+    SoftFloat::<Binary64>::from_bits(result.to_bits()).convert(&mut Context::default())
+}
+
+// x op y on the x87. fninit leaves it at 64-bit precision, rounding to nearest, with every exception masked
+#[cfg(target_arch = "x86_64")]
+fn x87(operation: Operation, x: &F80, y: &F80) -> F80 {
+    let (a, b) = (x.to_x87_bytes(), y.to_x87_bytes());
+    let mut result = [0u8; 10];
+    macro_rules! run {
+        ($insn:literal) => {
+            unsafe {
+                std::arch::asm!(
+                    "fninit",
+                    "fld tbyte ptr [{b}]",
+                    "fld tbyte ptr [{a}]",
+                    concat!($insn, " st, st(1)"),
+                    "fstp tbyte ptr [{result}]",
+                    "fstp st(0)",
+                    "fninit",
+                    a = in(reg) &a,
+                    b = in(reg) &b,
+                    result = in(reg) &mut result,
+                    out("st(0)") _, out("st(1)") _, out("st(2)") _, out("st(3)") _,
+                    out("st(4)") _, out("st(5)") _, out("st(6)") _, out("st(7)") _,
+                )
+            }
+        };
+    }
+    match operation {
+        Operation::Add => run!("fadd"),
+        Operation::Subtract => run!("fsub"),
+        Operation::Multiply => run!("fmul"),
+        Operation::Divide => run!("fdiv"),
+    }
+    F80::from_x87_bytes(result)
+}
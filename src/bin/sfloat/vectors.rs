@@ -0,0 +1,74 @@
+// sfloat vectors: test vectors for an RTL testbench, one per line in hex, from floatfs::testbench
+//
+//   $ sfloat vectors --format f32 --op add,mul --rounding nearest,zero --count 10000 > fpu_vectors.hex
+//   $ sfloat vectors --format f16 --template "{op} {rm} {a} {b} {result} {flags}" --nan canonical
+//
+// count vectors for each operation in each rounding mode. the first line says how to make the same file again.
+
+use floatfs::testbench::{self, DEFAULT_TEMPLATE};
+use floatfs::{Context, Format};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::{value_name, with_format, FormatArg, NanArg, OperationArg, RoundingArg, TininessArg};
+
+#[derive(clap::Args)]
+pub struct Args {
+    #[arg(long, value_enum, default_value = "f32")]
+    format: FormatArg,
+    #[arg(long, value_enum, value_delimiter = ',', default_value = "add")]
+    op: Vec<OperationArg>,
+    #[arg(long, value_enum, value_delimiter = ',', default_value = "nearest")]
+    rounding: Vec<RoundingArg>,
+    /// Vectors for each operation and rounding mode
+    #[arg(long, default_value_t = 1000)]
+    count: usize,
+    /// A random one if not given
+    #[arg(long)]
+    seed: Option<u64>,
+    /// The fields of a line: {a} {b} {result} {flags} {rm} {op}, with anything else copied as is
+    #[arg(long, default_value = DEFAULT_TEMPLATE)]
+    template: String,
+    /// Which nan an operation on nans gives
+    #[arg(long, value_enum, default_value = "propagate")]
+    nan: NanArg,
+    /// When a result is tiny enough to raise underflow
+    #[arg(long, value_enum, default_value = "after")]
+    tininess: TininessArg,
+    /// Leave out the comment lines at the top
+    #[arg(long)]
+    no_header: bool,
+}
+
+pub fn run(args: &Args) -> Result<(), String> {
+    with_format!(args.format, vectors(args))
+}
+
+fn vectors<F: Format>(args: &Args) -> Result<(), String> {
+    let seed = args.seed.unwrap_or_else(rand::random);
+    let mut rng = StdRng::seed_from_u64(seed);
+    let (nan_mode, tininess) = (args.nan.into(), args.tininess.into());
+    if !args.no_header {
+        let list = |names: Vec<String>| names.join(",");
+        println!(
+            "// sfloat vectors --format {} --op {} --rounding {} --count {} --seed {seed} --template \"{}\" --nan {} --tininess {}",
+            value_name(args.format),
+            list(args.op.iter().map(|&op| value_name(op)).collect()),
+            list(args.rounding.iter().map(|&mode| value_name(mode)).collect()),
+            args.count,
+            args.template,
+            value_name(args.nan),
+            value_name(args.tininess),
+        );
+        println!("{}", testbench::header::<F>(&args.template));
+    }
+    for &op in &args.op {
+        for &rounding in &args.rounding {
+            let ctx = Context { nan_mode, tininess, ..Context::new(rounding.into()) };
+            for v in testbench::vectors::<F>(op.operation(), &ctx, args.count, &mut || rng.random()) {
+                println!("{}", testbench::line(&args.template, &v));
+            }
+        }
+    }
+    Ok(())
+}
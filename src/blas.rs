@@ -0,0 +1,111 @@
+// BLAS level 1 on slices of Float, so small linear algebra can stay on the soft-float path and give the same
+// bits everywhere. axpy, scal and asum do the same roundings in the same order as the reference BLAS (no fused
+// multiply-add), so they match it on any machine that doesn't fuse; nrm2 is more careful than it. fma_slices and
+// fma_accumulate are the fused element-wise forms, for layers that want one rounding per multiply-add. every
+// vector is taken as an iterator, so slices work as they are and the strided forms (incx, incy) are iterator
+// adapters, with nothing copied out first:
+//
+//     scal(&alpha, data.iter_mut().skip(j).step_by(columns)); // column j of a row-major matrix
+//     let norm = nrm2(samples.iter().skip(1).step_by(2)); // the right channel of interleaved stereo
+
+use core::cmp::Ordering;
+
+use crate::wide::Wide;
+use crate::{Context, Float, F64};
+
+/// y = alpha * x + y, over the shorter of x and y, rounding the product and then the sum.
+/// Like the reference daxpy, alpha = 0 returns without touching y, even where x has infinities or nans.
+#[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+pub fn axpy<'a, 'b>(alpha: &Float, x: impl IntoIterator<Item = &'a Float>, y: impl IntoIterator<Item = &'b mut Float>) {
+    if alpha.is_zero() {
+        return;
+    }
+    for (xi, yi) in x.into_iter().zip(y) {
+        *yi = alpha.multiply(xi).add(yi);
+    }
+}
+
+/// x = alpha * x.
+#[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+pub fn scal<'a>(alpha: &Float, x: impl IntoIterator<Item = &'a mut Float>) {
+    for xi in x {
+        *xi = alpha.multiply(xi);
+    }
+}
+
+/// dst = a * b + c element by element, over the shortest of the four, each element rounded once to
+/// nearest as a fused multiply-add would: no rounding between the product and the sum.
+#[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+pub fn fma_slices<'a, 'b>(
+    dst: impl IntoIterator<Item = &'a mut Float>,
+    a: impl IntoIterator<Item = &'b Float>,
+    b: impl IntoIterator<Item = &'b Float>,
+    c: impl IntoIterator<Item = &'b Float>,
+) {
+    for (((di, ai), bi), ci) in dst.into_iter().zip(a).zip(b).zip(c) {
+        *di = ai.fma(bi, ci);
+    }
+}
+
+/// dst += a * b element by element, over the shortest of the three, each element rounded once.
+#[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+pub fn fma_accumulate<'a, 'b>(
+    dst: impl IntoIterator<Item = &'a mut Float>,
+    a: impl IntoIterator<Item = &'b Float>,
+    b: impl IntoIterator<Item = &'b Float>,
+) {
+    for ((di, ai), bi) in dst.into_iter().zip(a).zip(b) {
+        *di = ai.fma(bi, di);
+    }
+}
+
+/// The sum of the magnitudes, |x0| + |x1| + ..., left to right. An empty vector gives +0.
+#[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+pub fn asum<'a>(x: impl IntoIterator<Item = &'a Float>) -> Float {
+    x.into_iter().fold(Float::new(0.0), |sum, xi| sum.add(&Float::from_bits(xi.to_bits() & !(1 << 63))))
+}
+
+/// The Euclidean norm, sqrt(x0^2 + x1^2 + ...), correctly rounded. Instead of the reference dnrm2's running
+/// rescaling, the squares are summed in the 128-bit intermediate, whose exponent range is wide enough that
+/// nothing in between can overflow or underflow, as in hypot: only a norm that is itself out of range
+/// overflows. An infinity wins over quiet nans, as in hypot; an empty vector gives +0.
+#[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+pub fn nrm2<'a>(x: impl IntoIterator<Item = &'a Float>) -> Float {
+    // one pass, so the specials are only noted on the way and sorted out after
+    let (mut signaling, mut infinity, mut nan) = (false, false, false);
+    let mut squares = Wide::ZERO;
+    for xi in x {
+        if xi.is_nan() || xi.is_infinity() {
+            signaling |= xi.is_signaling_nan();
+            infinity |= xi.is_infinity();
+            nan |= xi.is_nan();
+            continue;
+        }
+        let w = Wide::from_soft(&F64::from_bits(xi.to_bits()));
+        squares = squares.add(w.mul(w));
+    }
+    if signaling {
+        return Float::nan();
+    }
+    if infinity {
+        return Float::infinity(false);
+    }
+    if nan {
+        return Float::nan();
+    }
+    if squares.is_zero() {
+        return Float::new(0.0);
+    }
+    // as in hypot: the root rounded to q, the nearest number with one more bit than a double, and then the sum
+    // against q^2 says which side of q the norm is on. the squares are exact and the sum only ever drops
+    // positive bits into its sticky bit, so that comparison is right even when the sum itself isn't exact
+    let root = squares.sqrt();
+    let q = Wide::new(false, root.exponent + 74, ((root.significand >> 73) + 1) >> 1);
+    let result = match (squares.exponent - 128, squares.significand, 0).cmp(&q.mul_exact(q)) {
+        Ordering::Equal => q,
+        Ordering::Less => Wide::new(false, q.exponent, q.significand - 1),
+        Ordering::Greater => Wide { significand: q.significand | 1, ..q },
+    };
+    let norm: F64 = result.round(&mut Context::default());
+    Float::from_bits(norm.to_bits())
+}
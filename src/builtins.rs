@@ -0,0 +1,208 @@
+// the libgcc / compiler-rt soft-float entry points (__adddf3, __mulsf3, __fixdfsi, ...), so this crate can be
+// the float runtime on targets without an fpu. build with `--features builtins`; the symbols are strong, so
+// they take precedence over the weak ones in rust's compiler_builtins.
+//
+// unlike the c builtins, every operation runs through a Context and ors its flags into a process-wide
+// register, the same way the ffi module does it. read them back with `take_flags`.
+
+use core::cmp::Ordering;
+use core::sync::atomic::{self, AtomicU8};
+
+use crate::{Context, Flags, Float, Format, RoundingMode, SoftFloat, F32, F64};
+
+static ROUNDING: AtomicU8 = AtomicU8::new(0); // position in MODES
+static FLAGS: AtomicU8 = AtomicU8::new(0);
+
+const MODES: [RoundingMode; 5] = [
+    RoundingMode::TiesToEven,
+    RoundingMode::TiesToAway,
+    RoundingMode::TowardZero,
+    RoundingMode::TowardPositive,
+    RoundingMode::TowardNegative,
+];
+
+pub fn set_rounding(mode: RoundingMode) {
+    let index = MODES.iter().position(|m| *m == mode).unwrap_or(0);
+    ROUNDING.store(index as u8, atomic::Ordering::Relaxed);
+}
+
+pub fn rounding() -> RoundingMode {
+    // get instead of indexing, so there's no bounds check panic in the runtime
+    MODES.get(ROUNDING.load(atomic::Ordering::Relaxed) as usize).copied().unwrap_or_default()
+}
+
+// returns the flags raised since the last call and clears them
+pub fn take_flags() -> Flags {
+    Flags(FLAGS.swap(0, atomic::Ordering::Relaxed))
+}
+
+fn with_context<T>(op: impl FnOnce(&mut Context) -> T) -> T {
+    let mut ctx = Context::new(rounding());
+    let result = op(&mut ctx);
+    FLAGS.fetch_or(ctx.flags.0, atomic::Ordering::Relaxed);
+    result
+}
+
+// arithmetic
+
+#[no_mangle]
+pub extern "C" fn __adddf3(a: f64, b: f64) -> f64 {
+    f64::from_bits(with_context(|ctx| ctx.add(&Float::new(a), &Float::new(b))).to_bits())
+}
+
+#[no_mangle]
+pub extern "C" fn __subdf3(a: f64, b: f64) -> f64 {
+    f64::from_bits(with_context(|ctx| ctx.subtract(&Float::new(a), &Float::new(b))).to_bits())
+}
+
+#[no_mangle]
+pub extern "C" fn __muldf3(a: f64, b: f64) -> f64 {
+    f64::from_bits(with_context(|ctx| ctx.multiply(&Float::new(a), &Float::new(b))).to_bits())
+}
+
+#[no_mangle]
+pub extern "C" fn __divdf3(a: f64, b: f64) -> f64 {
+    f64::from_bits(with_context(|ctx| ctx.divide(&Float::new(a), &Float::new(b))).to_bits())
+}
+
+fn f32_op(a: f32, b: f32, op: fn(&F32, &F32, &mut Context) -> F32) -> f32 {
+    f32::from_bits(with_context(|ctx| op(&F32::from_bits(a.to_bits()), &F32::from_bits(b.to_bits()), ctx)).to_bits())
+}
+
+#[no_mangle]
+pub extern "C" fn __addsf3(a: f32, b: f32) -> f32 {
+    f32_op(a, b, F32::add)
+}
+
+#[no_mangle]
+pub extern "C" fn __subsf3(a: f32, b: f32) -> f32 {
+    f32_op(a, b, F32::subtract)
+}
+
+#[no_mangle]
+pub extern "C" fn __mulsf3(a: f32, b: f32) -> f32 {
+    f32_op(a, b, F32::multiply)
+}
+
+#[no_mangle]
+pub extern "C" fn __divsf3(a: f32, b: f32) -> f32 {
+    f32_op(a, b, F32::divide)
+}
+
+// conversions between the two formats
+
+#[no_mangle]
+pub extern "C" fn __extendsfdf2(a: f32) -> f64 {
+    f64::from_bits(with_context(|ctx| ctx.f32_to_f64(a.to_bits())).to_bits())
+}
+
+#[no_mangle]
+pub extern "C" fn __truncdfsf2(a: f64) -> f32 {
+    let result: F32 = with_context(|ctx| F64::from_bits(a.to_bits()).convert(ctx));
+    f32::from_bits(result.to_bits())
+}
+
+// float to integer. these truncate and saturate like rust's `as`: nan gives 0 and out of range values clamp.
+// either case raises invalid.
+
+macro_rules! fix {
+    ($name:ident, $soft:ty, $float:ty, $int:ty, $convert:ident) => {
+        #[no_mangle]
+        pub extern "C" fn $name(a: $float) -> $int {
+            let value = <$soft>::from_bits(a.to_bits());
+            match with_context(|ctx| value.$convert(RoundingMode::TowardZero, ctx)) {
+                Some(n) => n,
+                None if value.is_nan() => 0,
+                None if value.get_sign() => <$int>::MIN,
+                None => <$int>::MAX,
+            }
+        }
+    };
+}
+
+fix!(__fixdfsi, F64, f64, i32, to_i32);
+fix!(__fixdfdi, F64, f64, i64, to_i64);
+fix!(__fixunsdfsi, F64, f64, u32, to_u32);
+fix!(__fixunsdfdi, F64, f64, u64, to_u64);
+fix!(__fixsfsi, F32, f32, i32, to_i32);
+fix!(__fixsfdi, F32, f32, i64, to_i64);
+fix!(__fixunssfsi, F32, f32, u32, to_u32);
+fix!(__fixunssfdi, F32, f32, u64, to_u64);
+
+// integer to float, rounded in the current mode
+
+macro_rules! float {
+    ($name:ident, $soft:ty, $float:ty, $int:ty, $convert:ident, $wide:ty) => {
+        #[no_mangle]
+        pub extern "C" fn $name(a: $int) -> $float {
+            <$float>::from_bits(with_context(|ctx| <$soft>::$convert(a as $wide, ctx)).to_bits())
+        }
+    };
+}
+
+float!(__floatsidf, F64, f64, i32, from_i64, i64);
+float!(__floatdidf, F64, f64, i64, from_i64, i64);
+float!(__floatunsidf, F64, f64, u32, from_u64, u64);
+float!(__floatundidf, F64, f64, u64, from_u64, u64);
+float!(__floatsisf, F32, f32, i32, from_i64, i64);
+float!(__floatdisf, F32, f32, i64, from_i64, i64);
+float!(__floatunsisf, F32, f32, u32, from_u64, u64);
+float!(__floatundisf, F32, f32, u64, from_u64, u64);
+
+// comparisons. each returns <0, 0 or >0 like a three-way compare, and the name says what an unordered
+// (nan) comparison returns: the eq/ne/lt/le ones return 1 and the gt/ge ones -1, so the caller's `op 0`
+// test comes out false. the relational ones raise invalid for any nan, equality only for a signaling one.
+
+fn compare<F: Format>(a: SoftFloat<F>, b: SoftFloat<F>, unordered: i32, signal_quiet: bool) -> i32 {
+    match a.compare(&b) {
+        Some(Ordering::Less) => -1,
+        Some(Ordering::Equal) => 0,
+        Some(Ordering::Greater) => 1,
+        None => {
+            if signal_quiet || a.is_signaling_nan() || b.is_signaling_nan() {
+                FLAGS.fetch_or(Flags::INVALID.0, atomic::Ordering::Relaxed);
+            }
+            unordered
+        }
+    }
+}
+
+macro_rules! cmp {
+    ($name:ident, $soft:ty, $float:ty, $unordered:expr, $signal_quiet:expr) => {
+        #[no_mangle]
+        pub extern "C" fn $name(a: $float, b: $float) -> i32 {
+            compare(<$soft>::from_bits(a.to_bits()), <$soft>::from_bits(b.to_bits()), $unordered, $signal_quiet)
+        }
+    };
+}
+
+cmp!(__eqdf2, F64, f64, 1, false);
+cmp!(__nedf2, F64, f64, 1, false);
+cmp!(__ltdf2, F64, f64, 1, true);
+cmp!(__ledf2, F64, f64, 1, true);
+cmp!(__gtdf2, F64, f64, -1, true);
+cmp!(__gedf2, F64, f64, -1, true);
+cmp!(__eqsf2, F32, f32, 1, false);
+cmp!(__nesf2, F32, f32, 1, false);
+cmp!(__ltsf2, F32, f32, 1, true);
+cmp!(__lesf2, F32, f32, 1, true);
+cmp!(__gtsf2, F32, f32, -1, true);
+cmp!(__gesf2, F32, f32, -1, true);
+
+// nonzero if either operand is a nan. quiet, like the hardware unordered compare
+fn unordered<F: Format>(a: SoftFloat<F>, b: SoftFloat<F>) -> i32 {
+    if a.is_signaling_nan() || b.is_signaling_nan() {
+        FLAGS.fetch_or(Flags::INVALID.0, atomic::Ordering::Relaxed);
+    }
+    (a.is_nan() || b.is_nan()) as i32
+}
+
+#[no_mangle]
+pub extern "C" fn __unorddf2(a: f64, b: f64) -> i32 {
+    unordered(F64::from_bits(a.to_bits()), F64::from_bits(b.to_bits()))
+}
+
+#[no_mangle]
+pub extern "C" fn __unordsf2(a: f32, b: f32) -> i32 {
+    unordered(F32::from_bits(a.to_bits()), F32::from_bits(b.to_bits()))
+}
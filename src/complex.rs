@@ -0,0 +1,160 @@
+// complex numbers over Float, with multiplication and division as C's Annex G has them (the _Cmultd and _Cdivd
+// example code in G.5.1). the textbook formulas get the special cases wrong: (inf + i nan) * (1 + i) should be
+// infinite, 1 / (0 + 0i) infinite and 1 / (inf + i inf) zero, but they all come out nan + i nan. Annex G
+// computes the textbook result and only when both parts are nan works out what it should have been. division
+// also scales the divisor to around 1 first, so c^2 + d^2 doesn't overflow or underflow when the quotient is
+// in range. every operation rounds to nearest.
+
+use crate::wide::Wide;
+use crate::{Context, Float, F64};
+
+/// A complex number, re + i im.
+#[derive(Debug)]
+pub struct Complex<T> {
+    pub re: T,
+    pub im: T,
+}
+
+// the magnitude of `magnitude` with the sign of `sign`
+fn copysign(magnitude: f64, sign: &Float) -> Float {
+    Float::from_bits(magnitude.to_bits() & !(1 << 63) | sign.to_bits() & 1 << 63)
+}
+
+// 1 with the sign of an infinity, 0 with the sign of anything else: how Annex G boxes an infinite operand
+fn boxed(f: &Float) -> Float {
+    copysign(if f.is_infinity() { 1.0 } else { 0.0 }, f)
+}
+
+// a nan replaced by a zero of the same sign, anything else left alone
+fn nan_to_zero(f: Float) -> Float {
+    if f.is_nan() {
+        copysign(0.0, &f)
+    } else {
+        f
+    }
+}
+
+// x * 2^n, rounded once
+fn scalbn(x: &Float, n: i32) -> Float {
+    if x.is_zero() || !x.is_finite() {
+        return x.copy();
+    }
+    let scaled: F64 = Wide::from_soft(&F64::from_bits(x.to_bits())).scale(n).round(&mut Context::default());
+    Float::from_bits(scaled.to_bits())
+}
+
+// logb for a finite nonzero x: the exponent of its leading bit, subnormals included
+fn logb(x: &Float) -> i32 {
+    Wide::from_soft(&F64::from_bits(x.to_bits())).magnitude()
+}
+
+impl Complex<Float> {
+    pub fn new(re: Float, im: Float) -> Complex<Float> {
+        Complex { re, im }
+    }
+
+    pub fn copy(&self) -> Complex<Float> {
+        Complex::new(self.re.copy(), self.im.copy())
+    }
+
+    /// Infinite if either part is, even when the other is a nan, as Annex G counts it.
+    pub fn is_infinity(&self) -> bool {
+        self.re.is_infinity() || self.im.is_infinity()
+    }
+
+    /// A nan if either part is and it isn't infinite.
+    pub fn is_nan(&self) -> bool {
+        (self.re.is_nan() || self.im.is_nan()) && !self.is_infinity()
+    }
+
+    pub fn conj(&self) -> Complex<Float> {
+        Complex::new(self.re.copy(), self.im.negated())
+    }
+
+    pub fn add(&self, other: &Complex<Float>) -> Complex<Float> {
+        Complex::new(self.re.add(&other.re), self.im.add(&other.im))
+    }
+
+    pub fn subtract(&self, other: &Complex<Float>) -> Complex<Float> {
+        Complex::new(self.re.subtract(&other.re), self.im.subtract(&other.im))
+    }
+
+    /// (a + ib)(c + id) = (ac - bd) + i(ad + bc), four products and two sums, each rounded. When both parts come
+    /// out nan, an infinite operand (or an overflowed product) makes the result infinite again: infinite parts
+    /// are boxed to ±1, the other parts' nans become zeros, and the product is recomputed times infinity.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn multiply(&self, other: &Complex<Float>) -> Complex<Float> {
+        let (mut a, mut b, mut c, mut d) = (self.re.copy(), self.im.copy(), other.re.copy(), other.im.copy());
+        let (ac, bd, ad, bc) = (a.multiply(&c), b.multiply(&d), a.multiply(&d), b.multiply(&c));
+        let (x, y) = (ac.subtract(&bd), ad.add(&bc));
+        if !(x.is_nan() && y.is_nan()) {
+            return Complex::new(x, y);
+        }
+        let mut recalc = false;
+        if a.is_infinity() || b.is_infinity() {
+            (a, b) = (boxed(&a), boxed(&b));
+            (c, d) = (nan_to_zero(c), nan_to_zero(d));
+            recalc = true;
+        }
+        if c.is_infinity() || d.is_infinity() {
+            (c, d) = (boxed(&c), boxed(&d));
+            (a, b) = (nan_to_zero(a), nan_to_zero(b));
+            recalc = true;
+        }
+        if !recalc && (ac.is_infinity() || bd.is_infinity() || ad.is_infinity() || bc.is_infinity()) {
+            // overflow in a product that then met an infinity of the other sign
+            (a, b, c, d) = (nan_to_zero(a), nan_to_zero(b), nan_to_zero(c), nan_to_zero(d));
+            recalc = true;
+        }
+        if !recalc {
+            return Complex::new(x, y);
+        }
+        let infinity = Float::infinity(false);
+        let x = infinity.multiply(&a.multiply(&c).subtract(&b.multiply(&d)));
+        let y = infinity.multiply(&a.multiply(&d).add(&b.multiply(&c)));
+        Complex::new(x, y)
+    }
+
+    /// (a + ib) / (c + id), with c and d first scaled by a power of two to bring the larger to [1, 2), so the
+    /// denominator c^2 + d^2 stays in range, and the quotient scaled back at the end. When both parts come out
+    /// nan: a nonzero over zero is an infinity (signed by c and the numerator), infinite over finite is infinite,
+    /// and finite over infinite is zero.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn divide(&self, other: &Complex<Float>) -> Complex<Float> {
+        let (mut a, mut b, mut c, mut d) = (self.re.copy(), self.im.copy(), other.re.copy(), other.im.copy());
+        // fmax: a nan only wins if both are
+        let (abs_c, abs_d) = (c.abs(), d.abs());
+        let big = if c.is_nan() || !d.is_nan() && abs_d.to_bits() > abs_c.to_bits() { abs_d } else { abs_c };
+        let finite_logb = big.is_finite() && !big.is_zero();
+        let mut scale = 0;
+        if finite_logb {
+            scale = logb(&big);
+            (c, d) = (scalbn(&c, -scale), scalbn(&d, -scale));
+        }
+        let denominator = c.multiply(&c).add(&d.multiply(&d));
+        let x = scalbn(&a.multiply(&c).add(&b.multiply(&d)).divide(&denominator), -scale);
+        let y = scalbn(&b.multiply(&c).subtract(&a.multiply(&d)).divide(&denominator), -scale);
+        if !(x.is_nan() && y.is_nan()) {
+            return Complex::new(x, y);
+        }
+        if denominator.is_zero() && (!a.is_nan() || !b.is_nan()) {
+            let infinity = copysign(f64::INFINITY, &c);
+            return Complex::new(infinity.multiply(&a), infinity.multiply(&b));
+        }
+        let infinity = Float::infinity(false);
+        if (a.is_infinity() || b.is_infinity()) && c.is_finite() && d.is_finite() {
+            (a, b) = (boxed(&a), boxed(&b));
+            let x = infinity.multiply(&a.multiply(&c).add(&b.multiply(&d)));
+            let y = infinity.multiply(&b.multiply(&c).subtract(&a.multiply(&d)));
+            return Complex::new(x, y);
+        }
+        if big.is_infinity() && a.is_finite() && b.is_finite() {
+            (c, d) = (boxed(&c), boxed(&d));
+            let zero = Float::new(0.0);
+            let x = zero.multiply(&a.multiply(&c).add(&b.multiply(&d)));
+            let y = zero.multiply(&b.multiply(&c).subtract(&a.multiply(&d)));
+            return Complex::new(x, y);
+        }
+        Complex::new(x, y)
+    }
+}
@@ -0,0 +1,730 @@
+// IEEE 754-2019 conformance report: the operations and attributes the standard requires of a binary
+// implementation (clauses 3 to 7), the recommended operations of clause 9, and for each one what in floatfs
+// provides it and whether a set of checks of it passes. The decimal formats and their operations are left out;
+// this is a binary-only crate.
+//
+//   let entries = conformance::report();
+//   std::fs::write("conformance.json", conformance::to_json(&entries))?;
+//
+// the checks are spot checks, a few thousand operands per clause against the host's binary32 and binary64 (which
+// are IEEE on every platform rust supports) or against exactly known results. the exhaustive and random tests in
+// main.rs go much further; this is the map of what's there.
+
+use core::num::FpCategory;
+use std::fmt::Write;
+
+use crate::format::{Binary16, Binary32, Binary64, Format, Storage};
+use crate::{Context, Flags, Float, RoundingMode, SoftFloat, F32, F64};
+
+/// How a requirement stands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Verified,    // provided, and every check passed
+    Failed,      // provided, but a check failed
+    Unsupported, // not provided
+}
+
+impl Status {
+    pub fn name(self) -> &'static str {
+        match self {
+            Status::Verified => "verified",
+            Status::Failed => "failed",
+            Status::Unsupported => "unsupported",
+        }
+    }
+}
+
+/// One requirement of the standard and how floatfs meets it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub clause: &'static str,
+    pub operation: &'static str,   // as the standard names it
+    pub provided_by: &'static str, // the floatfs api, empty when unsupported
+    pub status: Status,
+    pub cases: usize,   // how many checks ran
+    pub detail: String, // the failing case, or why it's unsupported
+}
+
+// a running count of checks, so an entry can say how many it passed
+struct Cases(usize);
+
+impl Cases {
+    fn check(&mut self, ok: bool, case: impl FnOnce() -> String) -> Result<(), String> {
+        self.0 += 1;
+        if ok {
+            Ok(())
+        } else {
+            Err(case())
+        }
+    }
+}
+
+type Check = fn(&mut Cases) -> Result<(), String>;
+
+struct Requirement {
+    clause: &'static str,
+    operation: &'static str,
+    provided_by: &'static str,
+    check: Option<Check>, // None when unsupported
+    note: &'static str,   // why
+}
+
+const fn has(clause: &'static str, operation: &'static str, provided_by: &'static str, check: Check) -> Requirement {
+    Requirement { clause, operation, provided_by, check: Some(check), note: "" }
+}
+
+const fn missing(clause: &'static str, operation: &'static str, note: &'static str) -> Requirement {
+    Requirement { clause, operation, provided_by: "", check: None, note }
+}
+
+const REQUIREMENTS: &[Requirement] = &[
+    has("3.4", "binary16 encoding", "format::Binary16", |c| encoding::<Binary16>(c, 0x7BFF)),
+    has("3.4", "binary32 encoding", "format::Binary32", |c| encoding::<Binary32>(c, 0x7F7F_FFFF)),
+    has("3.4", "binary64 encoding", "format::Binary64", |c| encoding::<Binary64>(c, 0x7FEF_FFFF_FFFF_FFFF)),
+    missing("3.4", "binary128 encoding", "no 128-bit format"),
+    has("4.2", "rounding-direction attribute as a dynamic mode", "Context::rounding", dynamic_mode),
+    has("4.3.1", "roundTiesToEven", "RoundingMode::TiesToEven", |c| rounding(c, RoundingMode::TiesToEven)),
+    has("4.3.1", "roundTiesToAway", "RoundingMode::TiesToAway", |c| rounding(c, RoundingMode::TiesToAway)),
+    has("4.3.2", "roundTowardPositive", "RoundingMode::TowardPositive", |c| rounding(c, RoundingMode::TowardPositive)),
+    has("4.3.2", "roundTowardNegative", "RoundingMode::TowardNegative", |c| rounding(c, RoundingMode::TowardNegative)),
+    has("4.3.2", "roundTowardZero", "RoundingMode::TowardZero", |c| rounding(c, RoundingMode::TowardZero)),
+    missing("5.3.1", "roundToIntegral*", "only conversions to integer formats (to_i64, lrint)"),
+    has("5.3.1", "nextUp, nextDown", "SoftFloat::next_up, next_down", next),
+    missing("5.3.1", "remainder", "not implemented"),
+    missing("5.3.1", "minimum, maximum", "fmin and fmax ignore a nan, as minimumNumber does"),
+    has("5.3.1", "minimumNumber, maximumNumber", "SoftFloat::fmin, fmax", min_max_number),
+    missing("5.3.1", "minimumMagnitude, maximumMagnitude, *MagnitudeNumber", "not implemented"),
+    missing("5.3.3", "scaleB, logB", "not implemented"),
+    has("5.4.1", "addition", "SoftFloat::add", |c| arithmetic(c, '+')),
+    has("5.4.1", "subtraction", "SoftFloat::subtract", |c| arithmetic(c, '-')),
+    has("5.4.1", "multiplication", "SoftFloat::multiply", |c| arithmetic(c, '*')),
+    has("5.4.1", "division", "SoftFloat::divide", |c| arithmetic(c, '/')),
+    missing("5.4.1", "squareRoot", "not implemented"),
+    has("5.4.1", "fusedMultiplyAdd", "Float::fma (binary64)", fused_multiply_add),
+    has("5.4.1", "convertFromInt", "SoftFloat::from_i64, from_u64", from_int),
+    has("5.4.1", "convertToIntegerExact*", "SoftFloat::to_i64 and the other to_* with a RoundingMode", to_int),
+    missing("5.4.1", "convertToInteger* (not signaling inexact)", "to_i64 always raises inexact; lround for TiesToAway"),
+    has("5.4.2", "convertFormat", "SoftFloat::convert", convert_format),
+    has("5.4.2", "convertFromDecimalCharacter", "SoftFloat::parse, FromStr", from_decimal),
+    has("5.4.2", "convertToDecimalCharacter", "SoftFloat::to_shortest_decimal, to_exact_decimal", to_decimal),
+    has("5.4.3", "convertFromHexCharacter", "SoftFloat::parse", from_hex),
+    missing("5.4.3", "convertToHexCharacter", "not implemented"),
+    has("5.5.1", "copy, negate", "Copy, SoftFloat::negate", copy_negate),
+    missing("5.5.1", "abs, copySign", "not implemented"),
+    has("5.6.1", "compareQuiet*", "SoftFloat::compare", compare_quiet),
+    missing("5.6.1", "compareSignaling*", "compare raises nothing for a quiet nan"),
+    has("5.7.2", "class", "SoftFloat::classify, is_signaling_nan", class),
+    has(
+        "5.7.2",
+        "isSignMinus, isNormal, isFinite, isZero, isSubnormal, isInfinite, isNaN, isSignaling",
+        "SoftFloat::get_sign, classify, is_zero, is_infinity, is_nan, is_signaling_nan",
+        class,
+    ),
+    missing("5.7.2", "isCanonical, radix", "not implemented (every binary encoding is canonical, the radix is 2)"),
+    has("5.7.2", "totalOrder, totalOrderMag", "Float::to_total_order_key (binary64)", total_order),
+    has("5.7.4", "lowerFlags, raiseFlags, testFlags, saveAllFlags, restoreFlags", "Flags, Context::flags", flags),
+    has("6.1", "infinity arithmetic", "SoftFloat", infinity),
+    has("6.2", "NaN propagation", "SoftFloat", nan_propagation),
+    has("6.3", "sign bit of zero results", "SoftFloat", sign_of_zero),
+    has("7.2", "invalid operation", "Flags::INVALID", invalid),
+    has("7.3", "division by zero", "Flags::DIVIDE_BY_ZERO", divide_by_zero),
+    has("7.4", "overflow", "Flags::OVERFLOW", overflow),
+    has("7.5", "underflow", "Flags::UNDERFLOW", underflow),
+    has("7.6", "inexact", "Flags::INEXACT", inexact),
+    has("9.2", "exp, expm1, exp2, exp10", "SoftFloat::exp, expm1, exp2, exp10", exponentials),
+    missing("9.2", "exp2m1, exp10m1", "not implemented"),
+    has("9.2", "log, log2, log10, logp1", "SoftFloat::ln, log2, log10, log1p", logarithms),
+    missing("9.2", "log2p1, log10p1", "not implemented"),
+    has("9.2", "hypot", "SoftFloat::hypot", |c| {
+        values(c, "hypot", &[(3.0, 4.0, 5.0), (-5.0, 12.0, 13.0), (f64::INFINITY, f64::NAN, f64::INFINITY)], |x, y, ctx| {
+            x.hypot(y, ctx)
+        })
+    }),
+    missing("9.2", "rSqrt, compound, rootn, powr", "not implemented"),
+    has("9.2", "pown, pow", "SoftFloat::powi_exact, powf", powers),
+    has("9.2", "sin, cos, tan, asin, acos, atan, atan2", "SoftFloat::sin, cos, tan, asin, acos, atan, atan2", trigonometric),
+    has("9.2", "sinPi, cosPi", "SoftFloat::sinpi, cospi", pi_trigonometric),
+    missing("9.2", "tanPi, asinPi, acosPi, atanPi, atan2Pi", "not implemented"),
+    has("9.2", "sinh, cosh, tanh, asinh, acosh, atanh", "SoftFloat::sinh, cosh, tanh, asinh, acosh, atanh", hyperbolic),
+    has("9.5", "augmentedAddition", "Float::augmented_add (binary64)", augmented_addition),
+    missing("9.5", "augmentedSubtraction", "augmented_add with the operand negated"),
+    has("9.5", "augmentedMultiplication", "Float::augmented_mul (binary64)", augmented_multiplication),
+];
+
+/// Runs the checks of every requirement.
+pub fn report() -> Vec<Entry> {
+    REQUIREMENTS
+        .iter()
+        .map(|r| {
+            let mut cases = Cases(0);
+            let (status, detail) = match r.check.map(|check| check(&mut cases)) {
+                None => (Status::Unsupported, r.note.into()),
+                Some(Ok(())) => (Status::Verified, String::new()),
+                Some(Err(case)) => (Status::Failed, case),
+            };
+            Entry {
+                clause: r.clause,
+                operation: r.operation,
+                provided_by: r.provided_by,
+                status,
+                cases: cases.0,
+                detail,
+            }
+        })
+        .collect()
+}
+
+/// The report as json: the standard, the crate version, counts by status and then every entry, one per line.
+pub fn to_json(entries: &[Entry]) -> String {
+    let count = |status| entries.iter().filter(|e| e.status == status).count();
+    let mut out = String::new();
+    out.push_str("{\n  \"standard\": \"IEEE 754-2019\",\n");
+    let _ = writeln!(out, "  \"crate\": \"floatfs {}\",", env!("CARGO_PKG_VERSION"));
+    let _ = writeln!(
+        out,
+        "  \"verified\": {}, \"failed\": {}, \"unsupported\": {},",
+        count(Status::Verified),
+        count(Status::Failed),
+        count(Status::Unsupported)
+    );
+    out.push_str("  \"entries\": [\n");
+    for (i, e) in entries.iter().enumerate() {
+        let _ = write!(
+            out,
+            "    {{\"clause\": {}, \"operation\": {}, \"provided_by\": {}, \"status\": \"{}\", \"cases\": {}, \"detail\": {}}}",
+            json_string(e.clause),
+            json_string(e.operation),
+            json_string(e.provided_by),
+            e.status.name(),
+            e.cases,
+            json_string(&e.detail)
+        );
+        out.push_str(if i + 1 < entries.len() { ",\n" } else { "\n" });
+    }
+    out.push_str("  ]\n}\n");
+    out
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+// the operands: splitmix64 bits, with a share pushed to zeros and subnormals, to infinities and nans, and to
+// around 1, so each check sees every class
+fn operands(n: usize) -> impl Iterator<Item = f64> {
+    let mut state = 0x9E37_79B9_7F4A_7C15u64;
+    (0..n).map(move |_| {
+        state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        f64::from_bits(match z % 8 {
+            0 => z & 0x800F_FFFF_FFFF_FFFF,
+            1 => z | 0x7FF0_0000_0000_0000,
+            2 => z & 0x800F_FFFF_FFFF_FFFF | 0x3FE0_0000_0000_0000,
+            _ => z,
+        })
+    })
+}
+
+const SWEEP: usize = 4000;
+
+fn pairs() -> impl Iterator<Item = (f64, f64)> {
+    let mut second = operands(2 * SWEEP).skip(SWEEP);
+    operands(SWEEP).map(move |x| (x, second.next().unwrap_or(0.0)))
+}
+
+fn soft(x: f64) -> F64 {
+    F64::from_bits(x.to_bits())
+}
+
+// the same encoding, or both nans
+fn same(x: &F64, y: f64) -> bool {
+    x.to_bits() == y.to_bits() || x.is_nan() && y.is_nan()
+}
+
+fn hex(x: f64) -> String {
+    format!("{:#018x}", x.to_bits())
+}
+
+fn encoding<F: Format>(c: &mut Cases, largest: u128) -> Result<(), String> {
+    let bits = |x: SoftFloat<F>| x.to_bits().to_u128();
+    let m = F::MANTISSA_BITS;
+    let infinity = largest + 1;
+    c.check(bits(SoftFloat::largest_finite(false)) == largest, || format!("{} largest finite", F::NAME))?;
+    c.check(bits(SoftFloat::infinity(false)) == infinity, || format!("{} infinity", F::NAME))?;
+    let nan = SoftFloat::<F>::nan();
+    c.check(nan.is_nan() && !nan.is_signaling_nan(), || format!("{} default nan isn't quiet", F::NAME))?;
+    let signaling = SoftFloat::<F>::from_bits(F::Bits::from_u128(infinity | 1));
+    c.check(signaling.is_signaling_nan(), || format!("{} nan with the quiet bit clear", F::NAME))?;
+    let smallest = SoftFloat::<F>::from_bits(F::Bits::from_u128(1));
+    c.check(smallest.classify() == FpCategory::Subnormal, || format!("{} smallest subnormal", F::NAME))?;
+    let normal = SoftFloat::<F>::from_bits(F::Bits::from_u128(1 << m));
+    c.check(normal.classify() == FpCategory::Normal, || format!("{} smallest normal", F::NAME))?;
+    let negative = SoftFloat::<F>::from_bits(F::Bits::from_u128(1 << (F::EXPONENT_BITS + m)));
+    c.check(negative.is_zero() && negative.get_sign(), || format!("{} negative zero", F::NAME))
+}
+
+fn rounding(c: &mut Cases, mode: RoundingMode) -> Result<(), String> {
+    // sums a quarter, a half (a tie) and three quarters of the way from x to the next double up, x in [1, 2) so
+    // that's x + u, and the same negated. the tie from 1 goes down to even, the one from 1 + u up
+    let u = f64::EPSILON;
+    for (x, y, quarters) in [(1.0, u / 4.0, 1), (1.0, u / 2.0, 2), (1.0, 3.0 * u / 4.0, 3), (1.0 + u, u / 2.0, 2)] {
+        for sign in [1.0, -1.0] {
+            let (low, high) = (x, x + u);
+            let magnitude = match mode {
+                RoundingMode::TiesToEven if quarters == 2 => if low.to_bits() & 1 == 0 { low } else { high },
+                RoundingMode::TiesToEven | RoundingMode::TiesToAway => if quarters >= 2 { high } else { low },
+                RoundingMode::TowardZero => low,
+                RoundingMode::TowardPositive => if sign > 0.0 { high } else { low },
+                RoundingMode::TowardNegative => if sign > 0.0 { low } else { high },
+            };
+            let (x, y, expected) = (sign * x, sign * y, sign * magnitude);
+            let mut ctx = Context::new(mode);
+            let sum = soft(x).add(&soft(y), &mut ctx);
+            c.check(same(&sum, expected) && ctx.flags == Flags::INEXACT, || {
+                format!("{x:e} + {y:e} in {mode:?} gave {} rather than {}", hex(f64::from_bits(sum.to_bits())), hex(expected))
+            })?;
+        }
+    }
+    Ok(())
+}
+
+fn next(c: &mut Cases) -> Result<(), String> {
+    for x in operands(SWEEP) {
+        let (up, down) = (soft(x).next_up(), soft(x).next_down());
+        c.check(same(&up, x.next_up()), || format!("next_up({})", hex(x)))?;
+        c.check(same(&down, x.next_down()), || format!("next_down({})", hex(x)))?;
+    }
+    Ok(())
+}
+
+fn min_max_number(c: &mut Cases) -> Result<(), String> {
+    let mut ctx = Context::default();
+    for (x, y) in pairs() {
+        // the host's min and max pick either zero for -0 and +0, where minimumNumber wants -0 and maximumNumber +0
+        let (min, max) = if x == 0.0 && y == 0.0 {
+            (if x.is_sign_negative() { x } else { y }, if x.is_sign_positive() { x } else { y })
+        } else {
+            (x.min(y), x.max(y))
+        };
+        c.check(same(&soft(x).fmin(&soft(y), &mut ctx), min), || format!("fmin({}, {})", hex(x), hex(y)))?;
+        c.check(same(&soft(x).fmax(&soft(y), &mut ctx), max), || format!("fmax({}, {})", hex(x), hex(y)))?;
+    }
+    Ok(())
+}
+
+fn arithmetic(c: &mut Cases, op: char) -> Result<(), String> {
+    for (x, y) in pairs() {
+        let mut ctx = Context::default();
+        let (result, expected) = match op {
+            '+' => (soft(x).add(&soft(y), &mut ctx), x + y),
+            '-' => (soft(x).subtract(&soft(y), &mut ctx), x - y),
+            '*' => (soft(x).multiply(&soft(y), &mut ctx), x * y),
+            _ => (soft(x).divide(&soft(y), &mut ctx), x / y),
+        };
+        c.check(same(&result, expected), || format!("binary64 {} {op} {}", hex(x), hex(y)))?;
+        // and in binary32, where the host's result is exact or rounded once from f64's 53 bits, which is safe
+        let (a, b) = (x as f32, y as f32);
+        let f32 = |x: f32| F32::from_bits(x.to_bits());
+        let (result, expected) = match op {
+            '+' => (f32(a).add(&f32(b), &mut ctx), a + b),
+            '-' => (f32(a).subtract(&f32(b), &mut ctx), a - b),
+            '*' => (f32(a).multiply(&f32(b), &mut ctx), a * b),
+            _ => (f32(a).divide(&f32(b), &mut ctx), a / b),
+        };
+        let ok = result.to_bits() == expected.to_bits() || result.is_nan() && expected.is_nan();
+        c.check(ok, || format!("binary32 {:#010x} {op} {:#010x}", a.to_bits(), b.to_bits()))?;
+    }
+    Ok(())
+}
+
+fn fused_multiply_add(c: &mut Cases) -> Result<(), String> {
+    // one rounding, against the host's, including products that cancel against the addend exactly or nearly
+    let mut third = operands(3 * SWEEP).skip(2 * SWEEP);
+    for (x, y) in pairs() {
+        let z = third.next().unwrap_or(0.0);
+        for addend in [z, -(x * y), -(x * y) + z * f64::EPSILON] {
+            let result = Float::new(x).fma(&Float::new(y), &Float::new(addend));
+            c.check(same(&F64::from_bits(result.to_bits()), x.mul_add(y, addend)), || {
+                format!("fma({}, {}, {})", hex(x), hex(y), hex(addend))
+            })?;
+        }
+    }
+    // exact cancellation gives +0, but -0 rounding toward negative
+    let zero = Float::new(3.0).fma_with_rounding(&Float::new(-2.0), &Float::new(6.0), RoundingMode::TowardNegative);
+    c.check(zero.to_bits() == (-0.0f64).to_bits(), || "fma(3, -2, 6) toward negative isn't -0".into())
+}
+
+fn from_int(c: &mut Cases) -> Result<(), String> {
+    let mut ctx = Context::default();
+    for x in operands(SWEEP) {
+        let n = x.to_bits() as i64 >> (x.to_bits() % 64); // integers of every size
+        c.check(same(&F64::from_i64(n, &mut ctx), n as f64), || format!("from_i64({n})"))?;
+        let n = n as u64;
+        c.check(same(&F64::from_u64(n, &mut ctx), n as f64), || format!("from_u64({n})"))?;
+    }
+    Ok(())
+}
+
+fn to_int(c: &mut Cases) -> Result<(), String> {
+    let modes = [
+        (RoundingMode::TiesToEven, f64::round_ties_even as fn(f64) -> f64),
+        (RoundingMode::TiesToAway, f64::round),
+        (RoundingMode::TowardZero, f64::trunc),
+        (RoundingMode::TowardPositive, f64::ceil),
+        (RoundingMode::TowardNegative, f64::floor),
+    ];
+    for x in operands(SWEEP).map(|x| x * 1e10) {
+        for (mode, round) in modes {
+            let mut ctx = Context::default();
+            let rounded = round(x);
+            let limit = 2f64.powi(63);
+            let expected = (-limit..limit).contains(&rounded).then_some(rounded as i64);
+            let result = soft(x).to_i64(mode, &mut ctx);
+            c.check(result == expected, || format!("to_i64({}, {mode:?}) gave {result:?}", hex(x)))?;
+            // exact: invalid when out of range, and otherwise inexact if rounding changed anything
+            let flags = match expected {
+                None => Flags::INVALID,
+                Some(_) if rounded != x => Flags::INEXACT,
+                Some(_) => Flags::empty(),
+            };
+            c.check(ctx.flags == flags, || format!("to_i64({}, {mode:?}) raised {:?}", hex(x), ctx.flags))?;
+        }
+    }
+    Ok(())
+}
+
+fn convert_format(c: &mut Cases) -> Result<(), String> {
+    for x in operands(SWEEP) {
+        let mut ctx = Context::default();
+        let narrow: F32 = soft(x).convert(&mut ctx);
+        let expected = x as f32;
+        let ok = narrow.to_bits() == expected.to_bits() || narrow.is_nan() && expected.is_nan();
+        c.check(ok, || format!("binary64 {} to binary32", hex(x)))?;
+        let mut ctx = Context::default();
+        let wide: F64 = narrow.convert(&mut ctx);
+        c.check(same(&wide, expected as f64), || format!("binary32 {:#010x} to binary64", expected.to_bits()))?;
+        c.check(ctx.flags.is_empty(), || format!("widening {:#010x} raised {:?}", expected.to_bits(), ctx.flags))?;
+    }
+    Ok(())
+}
+
+fn from_decimal(c: &mut Cases) -> Result<(), String> {
+    for x in operands(SWEEP).filter(|x| x.is_finite()) {
+        // 25 digits, more than enough to land between two doubles rather than on one
+        for text in [format!("{x:.25e}"), format!("{x:e}")] {
+            let parsed = F64::parse(&text, &mut Context::default());
+            let expected: f64 = text.parse().unwrap_or(f64::NAN);
+            c.check(parsed.is_ok_and(|p| same(&p, expected)), || format!("parse(\"{text}\")"))?;
+        }
+    }
+    Ok(())
+}
+
+fn to_decimal(c: &mut Cases) -> Result<(), String> {
+    for x in operands(SWEEP).filter(|x| x.is_finite()) {
+        let shortest = soft(x).to_shortest_decimal();
+        c.check(shortest == format!("{x:e}"), || format!("to_shortest_decimal({}) = {shortest}", hex(x)))?;
+        // the exact expansion reads back as the same double
+        let exact = soft(x).to_exact_decimal();
+        c.check(exact.parse::<f64>().is_ok_and(|y| y.to_bits() == x.to_bits()), || format!("to_exact_decimal({})", hex(x)))?;
+    }
+    Ok(())
+}
+
+fn from_hex(c: &mut Cases) -> Result<(), String> {
+    let cases = [
+        ("0x1p0", 1.0),
+        ("0x1.8p1", 3.0),
+        ("-0x1.fffffffffffffp1023", -f64::MAX),
+        ("0x1p-1074", 5e-324),
+        ("0x0.0000000000001p-1022", 5e-324),
+        ("0x1.00000000000008p0", 1.0), // a tie, to even
+        ("0x1.00000000000018p0", 1.0 + 2.0 * f64::EPSILON),
+        ("0x1p1024", f64::INFINITY),
+    ];
+    for (text, expected) in cases {
+        let parsed = F64::parse(text, &mut Context::default());
+        c.check(parsed.is_ok_and(|p| same(&p, expected)), || format!("parse(\"{text}\")"))?;
+    }
+    Ok(())
+}
+
+fn copy_negate(c: &mut Cases) -> Result<(), String> {
+    for x in operands(SWEEP) {
+        let copy = soft(x);
+        let mut negated = copy;
+        negated.negate(); // takes no context, so it's quiet even for a signaling nan
+        c.check(copy.to_bits() == x.to_bits(), || format!("copy({})", hex(x)))?;
+        c.check(negated.to_bits() == (-x).to_bits(), || format!("negate({})", hex(x)))?;
+    }
+    Ok(())
+}
+
+fn compare_quiet(c: &mut Cases) -> Result<(), String> {
+    for (x, y) in pairs().chain([(0.0, -0.0), (f64::NAN, f64::NAN), (f64::INFINITY, f64::MAX)]) {
+        let ordering = soft(x).compare(&soft(y));
+        c.check(ordering == x.partial_cmp(&y), || format!("compare({}, {}) gave {ordering:?}", hex(x), hex(y)))?;
+    }
+    Ok(())
+}
+
+fn class(c: &mut Cases) -> Result<(), String> {
+    for x in operands(SWEEP) {
+        let s = soft(x);
+        c.check(s.classify() == x.classify(), || format!("classify({})", hex(x)))?;
+        c.check(s.get_sign() == x.is_sign_negative(), || format!("get_sign({})", hex(x)))?;
+        let predicates = [s.is_nan() == x.is_nan(), s.is_infinity() == x.is_infinite(), s.is_zero() == (x == 0.0)];
+        c.check(predicates.iter().all(|&ok| ok), || format!("predicates of {}", hex(x)))?;
+        let signaling = x.is_nan() && x.to_bits() & 1 << 51 == 0;
+        c.check(s.is_signaling_nan() == signaling, || format!("is_signaling_nan({})", hex(x)))?;
+    }
+    Ok(())
+}
+
+fn total_order(c: &mut Cases) -> Result<(), String> {
+    let key = |x: f64| Float::new(x).to_total_order_key();
+    let ascending = [-f64::NAN, f64::NEG_INFINITY, -1.0, -f64::from_bits(1), -0.0, 0.0, f64::from_bits(1), 1.0];
+    for (x, y) in ascending.iter().zip(ascending.iter().skip(1).chain([&f64::INFINITY, &f64::NAN])) {
+        c.check(key(*x) < key(*y), || format!("totalOrder doesn't put {} before {}", hex(*x), hex(*y)))?;
+    }
+    // the host's total_cmp is totalOrder, and totalOrderMag is totalOrder of the magnitudes
+    for (x, y) in pairs() {
+        c.check(key(x).cmp(&key(y)) == x.total_cmp(&y), || format!("totalOrder({}, {})", hex(x), hex(y)))?;
+        let magnitude = |x: f64| Float::new(x).abs().to_total_order_key();
+        let ok = magnitude(x).cmp(&magnitude(y)) == x.abs().total_cmp(&y.abs());
+        c.check(ok, || format!("totalOrderMag({}, {})", hex(x), hex(y)))?;
+    }
+    Ok(())
+}
+
+fn flags(c: &mut Cases) -> Result<(), String> {
+    let mut ctx = Context::default();
+    c.check(ctx.flags.is_empty(), || "a new context has flags raised".into())?;
+    ctx.flags.raise(Flags::OVERFLOW | Flags::INEXACT);
+    c.check(ctx.flags.contains(Flags::OVERFLOW) && !ctx.flags.contains(Flags::INVALID), || "testFlags".into())?;
+    let saved = ctx.flags;
+    ctx.flags = Flags::empty();
+    let _ = F64::nan().multiply(&soft(0.0), &mut ctx);
+    c.check(ctx.flags.is_empty(), || "a quiet nan operand raised a flag".into())?;
+    ctx.flags = saved;
+    c.check(ctx.take_flags() == saved && ctx.flags.is_empty(), || "take_flags doesn't lower the flags".into())
+}
+
+fn dynamic_mode(c: &mut Cases) -> Result<(), String> {
+    // the same operation gives each mode's result as the context's mode changes
+    let third = |mode| soft(1.0).divide(&soft(3.0), &mut Context::new(mode)).to_bits();
+    let (down, up) = (third(RoundingMode::TowardNegative), third(RoundingMode::TowardPositive));
+    c.check(up == down + 1, || "1/3 rounded down and up aren't neighbors".into())?;
+    c.check(third(RoundingMode::TiesToEven) == (1.0f64 / 3.0).to_bits(), || "1/3 to nearest".into())?;
+    c.check(third(RoundingMode::TowardZero) == down, || "1/3 toward zero".into())
+}
+
+fn infinity(c: &mut Cases) -> Result<(), String> {
+    let inf = soft(f64::INFINITY);
+    let mut ctx = Context::default();
+    c.check(same(&inf.add(&soft(1.0), &mut ctx), f64::INFINITY), || "inf + 1".into())?;
+    c.check(same(&inf.multiply(&soft(-2.0), &mut ctx), f64::NEG_INFINITY), || "inf * -2".into())?;
+    c.check(same(&soft(1.0).divide(&inf, &mut ctx), 0.0), || "1 / inf".into())?;
+    c.check(ctx.flags.is_empty(), || "exact operations on infinities raised flags".into())
+}
+
+fn nan_propagation(c: &mut Cases) -> Result<(), String> {
+    let quiet = F64::nan();
+    let signaling = F64::from_bits(0x7FF0_0000_0000_0001);
+    for x in operands(100).filter(|x| !x.is_nan()) {
+        let mut ctx = Context::default();
+        let result = quiet.add(&soft(x), &mut ctx);
+        c.check(result.is_nan() && !result.is_signaling_nan() && ctx.flags.is_empty(), || format!("nan + {}", hex(x)))?;
+        let result = soft(x).multiply(&signaling, &mut ctx);
+        let ok = result.is_nan() && !result.is_signaling_nan() && ctx.flags == Flags::INVALID;
+        c.check(ok, || format!("{} * signaling nan", hex(x)))?;
+    }
+    Ok(())
+}
+
+fn sign_of_zero(c: &mut Cases) -> Result<(), String> {
+    for x in operands(SWEEP).filter(|x| x.is_finite()) {
+        for mode in [RoundingMode::TiesToEven, RoundingMode::TowardZero, RoundingMode::TowardNegative] {
+            // x - x is +0, or -0 rounding toward negative
+            let zero = soft(x).subtract(&soft(x), &mut Context::new(mode));
+            let negative = mode == RoundingMode::TowardNegative;
+            c.check(zero.is_zero() && zero.get_sign() == negative, || format!("{} - itself in {mode:?}", hex(x)))?;
+        }
+        // a product or quotient has the exclusive or of the signs
+        let product = soft(x).multiply(&soft(-0.0), &mut Context::default());
+        c.check(product.get_sign() != x.is_sign_negative(), || format!("{} * -0", hex(x)))?;
+    }
+    let sum = soft(-0.0).add(&soft(-0.0), &mut Context::default());
+    c.check(sum.is_zero() && sum.get_sign(), || "-0 + -0".into())
+}
+
+// each operation with the flags it should raise
+fn flag_cases(c: &mut Cases, cases: &[(f64, char, f64, Flags)]) -> Result<(), String> {
+    for &(x, op, y, expected) in cases {
+        let mut ctx = Context::default();
+        let _ = match op {
+            '+' => soft(x).add(&soft(y), &mut ctx),
+            '-' => soft(x).subtract(&soft(y), &mut ctx),
+            '*' => soft(x).multiply(&soft(y), &mut ctx),
+            _ => soft(x).divide(&soft(y), &mut ctx),
+        };
+        c.check(ctx.flags == expected, || format!("{x:e} {op} {y:e} raised {:?}, not {expected:?}", ctx.flags))?;
+    }
+    Ok(())
+}
+
+fn invalid(c: &mut Cases) -> Result<(), String> {
+    let inf = f64::INFINITY;
+    let cases = [(0.0, '/', 0.0, Flags::INVALID), (inf, '-', inf, Flags::INVALID), (0.0, '*', inf, Flags::INVALID)];
+    flag_cases(c, &cases)?;
+    flag_cases(c, &[(inf, '/', inf, Flags::INVALID), (-inf, '+', inf, Flags::INVALID), (inf, '+', inf, Flags::empty())])
+}
+
+fn divide_by_zero(c: &mut Cases) -> Result<(), String> {
+    let mut ctx = Context::default();
+    c.check(same(&soft(-1.0).divide(&soft(0.0), &mut ctx), f64::NEG_INFINITY), || "-1 / 0".into())?;
+    flag_cases(c, &[(1.0, '/', 0.0, Flags::DIVIDE_BY_ZERO), (1.0, '/', -0.0, Flags::DIVIDE_BY_ZERO)])?;
+    flag_cases(c, &[(f64::INFINITY, '/', 0.0, Flags::empty())])
+}
+
+fn overflow(c: &mut Cases) -> Result<(), String> {
+    let both = Flags::OVERFLOW | Flags::INEXACT;
+    flag_cases(c, &[(f64::MAX, '*', 2.0, both), (f64::MAX, '+', f64::MAX, both), (f64::MAX, '/', 0.5, both)])?;
+    // the rounding mode decides between infinity and the largest finite value
+    for (mode, expected) in [
+        (RoundingMode::TiesToEven, f64::INFINITY),
+        (RoundingMode::TowardZero, f64::MAX),
+        (RoundingMode::TowardPositive, f64::INFINITY),
+        (RoundingMode::TowardNegative, f64::MAX),
+    ] {
+        let result = soft(f64::MAX).multiply(&soft(2.0), &mut Context::new(mode));
+        c.check(same(&result, expected), || format!("MAX * 2 in {mode:?}"))?;
+    }
+    Ok(())
+}
+
+fn underflow(c: &mut Cases) -> Result<(), String> {
+    let tiny = f64::MIN_POSITIVE;
+    let both = Flags::UNDERFLOW | Flags::INEXACT;
+    // an exact subnormal result is not an underflow under default exception handling, an inexact one is
+    flag_cases(c, &[(tiny, '/', 4.0, Flags::empty()), (tiny, '/', 3.0, both), (5e-324, '*', 0.5, both)])?;
+    flag_cases(c, &[(tiny, '*', 1.0, Flags::empty()), (1e-300, '*', 1e-300, both)])
+}
+
+fn inexact(c: &mut Cases) -> Result<(), String> {
+    flag_cases(c, &[(1.0, '/', 3.0, Flags::INEXACT), (1.0, '+', 1e-30, Flags::INEXACT), (1.0, '+', 1.0, Flags::empty())])?;
+    flag_cases(c, &[(0.1, '*', 10.0, Flags::INEXACT), (0.5, '*', 4.0, Flags::empty())])
+}
+
+// exactly known results of a function of one or two arguments (y unused for one)
+fn values(c: &mut Cases, name: &str, cases: &[(f64, f64, f64)], f: impl Fn(&F64, &F64, &mut Context) -> F64) -> Result<(), String> {
+    for &(x, y, expected) in cases {
+        let mut ctx = Context::default();
+        let result = f(&soft(x), &soft(y), &mut ctx);
+        c.check(same(&result, expected), || format!("{name}({x:e}, {y:e}) = {}", hex(f64::from_bits(result.to_bits()))))?;
+    }
+    Ok(())
+}
+
+fn exponentials(c: &mut Cases) -> Result<(), String> {
+    let nan = f64::NAN;
+    values(c, "exp", &[(0.0, 0.0, 1.0), (f64::NEG_INFINITY, 0.0, 0.0), (nan, 0.0, nan)], |x, _, ctx| x.exp(ctx))?;
+    values(c, "expm1", &[(0.0, 0.0, 0.0), (-0.0, 0.0, -0.0), (f64::NEG_INFINITY, 0.0, -1.0)], |x, _, ctx| x.expm1(ctx))?;
+    values(c, "exp2", &[(3.0, 0.0, 8.0), (-1074.0, 0.0, 5e-324), (1024.0, 0.0, f64::INFINITY)], |x, _, ctx| x.exp2(ctx))?;
+    values(c, "exp10", &[(2.0, 0.0, 100.0), (22.0, 0.0, 1e22), (0.0, 0.0, 1.0)], |x, _, ctx| x.exp10(ctx))
+}
+
+fn logarithms(c: &mut Cases) -> Result<(), String> {
+    let inf = f64::INFINITY;
+    values(c, "log", &[(1.0, 0.0, 0.0), (0.0, 0.0, -inf), (inf, 0.0, inf)], |x, _, ctx| x.ln(ctx))?;
+    values(c, "log2", &[(8.0, 0.0, 3.0), (5e-324, 0.0, -1074.0), (0.5, 0.0, -1.0)], |x, _, ctx| x.log2(ctx))?;
+    values(c, "log10", &[(1000.0, 0.0, 3.0), (1e22, 0.0, 22.0), (1.0, 0.0, 0.0)], |x, _, ctx| x.log10(ctx))?;
+    values(c, "logp1", &[(0.0, 0.0, 0.0), (-0.0, 0.0, -0.0), (-1.0, 0.0, -inf)], |x, _, ctx| x.log1p(ctx))
+}
+
+fn powers(c: &mut Cases) -> Result<(), String> {
+    values(c, "pown", &[(3.0, 4.0, 81.0), (-2.0, 3.0, -8.0), (2.0, -2.0, 0.25)], |x, y, ctx| {
+        x.powi_exact(f64::from_bits(y.to_bits()) as i32, ctx)
+    })?;
+    let nan = f64::NAN;
+    values(c, "pow", &[(2.0, 10.0, 1024.0), (4.0, 0.5, 2.0), (nan, 0.0, 1.0), (1.0, nan, 1.0)], |x, y, ctx| x.powf(y, ctx))
+}
+
+fn trigonometric(c: &mut Cases) -> Result<(), String> {
+    values(c, "sin", &[(0.0, 0.0, 0.0), (-0.0, 0.0, -0.0)], |x, _, ctx| x.sin(ctx))?;
+    values(c, "cos", &[(0.0, 0.0, 1.0), (-0.0, 0.0, 1.0)], |x, _, ctx| x.cos(ctx))?;
+    values(c, "tan", &[(0.0, 0.0, 0.0), (-0.0, 0.0, -0.0)], |x, _, ctx| x.tan(ctx))?;
+    values(c, "asin", &[(0.0, 0.0, 0.0), (-0.0, 0.0, -0.0)], |x, _, ctx| x.asin(ctx))?;
+    values(c, "acos", &[(1.0, 0.0, 0.0)], |x, _, ctx| x.acos(ctx))?;
+    values(c, "atan", &[(0.0, 0.0, 0.0), (-0.0, 0.0, -0.0)], |x, _, ctx| x.atan(ctx))?;
+    values(c, "atan2", &[(0.0, 1.0, 0.0), (-0.0, 1.0, -0.0)], |y, x, ctx| y.atan2(x, ctx))?;
+    // and the invalid cases
+    let nan = f64::NAN;
+    for (name, x, result) in [
+        ("sin", f64::INFINITY, soft(f64::INFINITY).sin(&mut Context::default())),
+        ("asin", 2.0, soft(2.0).asin(&mut Context::default())),
+        ("acos", -1.5, soft(-1.5).acos(&mut Context::default())),
+    ] {
+        c.check(same(&result, nan), || format!("{name}({x:e}) isn't a nan"))?;
+    }
+    Ok(())
+}
+
+fn pi_trigonometric(c: &mut Cases) -> Result<(), String> {
+    values(c, "sinPi", &[(1.0, 0.0, 0.0), (-1.0, 0.0, -0.0), (0.5, 0.0, 1.0), (1.5, 0.0, -1.0)], |x, _, ctx| x.sinpi(ctx))?;
+    values(c, "cosPi", &[(1.0, 0.0, -1.0), (0.0, 0.0, 1.0), (2.0, 0.0, 1.0), (0.5, 0.0, 0.0)], |x, _, ctx| x.cospi(ctx))
+}
+
+fn hyperbolic(c: &mut Cases) -> Result<(), String> {
+    let inf = f64::INFINITY;
+    values(c, "sinh", &[(0.0, 0.0, 0.0), (-0.0, 0.0, -0.0), (-inf, 0.0, -inf)], |x, _, ctx| x.sinh(ctx))?;
+    values(c, "cosh", &[(0.0, 0.0, 1.0), (-inf, 0.0, inf)], |x, _, ctx| x.cosh(ctx))?;
+    values(c, "tanh", &[(0.0, 0.0, 0.0), (-inf, 0.0, -1.0), (inf, 0.0, 1.0)], |x, _, ctx| x.tanh(ctx))?;
+    values(c, "asinh", &[(0.0, 0.0, 0.0), (-0.0, 0.0, -0.0), (inf, 0.0, inf)], |x, _, ctx| x.asinh(ctx))?;
+    values(c, "acosh", &[(1.0, 0.0, 0.0), (inf, 0.0, inf)], |x, _, ctx| x.acosh(ctx))?;
+    values(c, "atanh", &[(0.0, 0.0, 0.0), (-0.0, 0.0, -0.0)], |x, _, ctx| x.atanh(ctx))
+}
+
+fn augmented_addition(c: &mut Cases) -> Result<(), String> {
+    let u = f64::EPSILON;
+    // (a, b) with a rounded ties toward zero, so a tie keeps the smaller magnitude where ties to even might not
+    for (x, y, a, b) in [(1.0, u / 2.0, 1.0, u / 2.0), (1.0 + u, u / 2.0, 1.0 + u, u / 2.0), (1.0, -1.0, 0.0, 0.0)] {
+        let (sum, error) = Float::new(x).augmented_add(&Float::new(y));
+        let ok = sum.to_bits() == a.to_bits() && error.to_bits() == b.to_bits();
+        c.check(ok, || format!("augmented_add({x:e}, {y:e}) = ({:e}, {:e})", sum.to_f64(), error.to_f64()))?;
+    }
+    for (x, y) in pairs().filter(|(x, y)| (x + y).is_finite() && x.is_finite() && y.is_finite()) {
+        // the two halves add back up to the sum, exactly
+        let (sum, error) = Float::new(x).augmented_add(&Float::new(y));
+        let (s, e) = Float::new(x).two_sum(&Float::new(y));
+        let ok = sum.to_f64() + error.to_f64() == s.to_f64() + e.to_f64() && sum.to_f64().abs() <= s.to_f64().abs();
+        c.check(ok, || format!("augmented_add({}, {})", hex(x), hex(y)))?;
+    }
+    Ok(())
+}
+
+fn augmented_multiplication(c: &mut Cases) -> Result<(), String> {
+    let u = f64::EPSILON;
+    for (x, y, a, b) in [(1.0 + u, 1.0 + u, 1.0 + 2.0 * u, u * u), (3.0, 0.5, 1.5, 0.0), (0.0, -1.0, -0.0, -0.0)] {
+        let (product, error) = Float::new(x).augmented_mul(&Float::new(y));
+        let ok = product.to_bits() == a.to_bits() && error.to_bits() == b.to_bits();
+        c.check(ok, || format!("augmented_mul({x:e}, {y:e}) = ({:e}, {:e})", product.to_f64(), error.to_f64()))?;
+    }
+    Ok(())
+}
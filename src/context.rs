@@ -0,0 +1,175 @@
+use core::num::FpCategory;
+
+use crate::{Float, RoundingMode};
+
+/// The five IEEE 754 exception flags, plus two for flush-to-zero that IEEE doesn't have.
+/// The five standard bit values match Berkeley SoftFloat's `softfloat_flag_*`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Flags(pub u8);
+
+impl Flags {
+    pub const INEXACT: Flags = Flags(1);
+    pub const UNDERFLOW: Flags = Flags(2);
+    pub const OVERFLOW: Flags = Flags(4);
+    pub const DIVIDE_BY_ZERO: Flags = Flags(8);
+    pub const INVALID: Flags = Flags(16);
+    pub const INPUT_DENORMAL: Flags = Flags(32); // a subnormal operand was read as zero (flush_inputs)
+    pub const OUTPUT_DENORMAL: Flags = Flags(64); // a tiny result was replaced by zero (flush_outputs)
+
+    pub fn empty() -> Flags {
+        Flags(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn contains(&self, other: Flags) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn raise(&mut self, other: Flags) {
+        self.0 |= other.0;
+    }
+}
+
+impl core::ops::BitOr for Flags {
+    type Output = Flags;
+
+    fn bitor(self, other: Flags) -> Flags {
+        Flags(self.0 | other.0)
+    }
+}
+
+/// When a result counts as tiny for the purposes of the underflow flag.
+/// IEEE 754 leaves this to the implementation: x86 checks after rounding, ARM before.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Tininess {
+    BeforeRounding, // tiny if the exact result is below the smallest normal
+    #[default]
+    AfterRounding, // tiny if the result, rounded as if the exponent range were unbounded, is below the smallest normal
+}
+
+/// What an operation returns when an operand is nan. Invalid operations like 0 * inf always give the default nan,
+/// which is negative under the two x86 modes and positive otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NanMode {
+    #[default]
+    Propagate, // quiet the operand nan (see Float::nan_logic for which one wins) and keep its payload
+    Canonical, // always the default nan: positive, quiet bit only. risc-v does this, and arm with FPCR.DN set
+    FirstOperand, // x86 sse: the first nan operand, quieted, even if only the second is signaling
+    LargerSignificand, // x87: a quiet nan over a signaling one, otherwise the one with the larger significand
+}
+
+/// How hard the math functions (exp, ln, sin, pow, ...) work for their result. Basic arithmetic, cbrt and hypot
+/// are always correctly rounded, and so are tgamma and lgamma, whose error grows too much through the reflection
+/// and exp(ln gamma) for shorter series to pay off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Accuracy {
+    #[default]
+    CorrectlyRounded, // the nearest value in the rounding direction, except within about 2^-120 of a boundary
+    Faithful, // one of the two values either side of the exact result (the exact one if representable), but faster
+}
+
+/// Rounding attributes plus the sticky exception flags that operations run through it raise.
+///
+/// The two flush switches are the non-IEEE denormal modes hardware has (arm FZ, x86 DAZ and FTZ). A flushed
+/// result only raises OUTPUT_DENORMAL, since architectures disagree on what that should count as.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Context {
+    pub rounding: RoundingMode,
+    pub tininess: Tininess,
+    pub nan_mode: NanMode,
+    pub flush_inputs: bool, // read subnormal operands as zeros of the same sign
+    pub flush_outputs: bool, // replace tiny results (by the tininess rule) with zeros of the same sign
+    pub accuracy: Accuracy,
+    pub flags: Flags,
+}
+
+impl Context {
+    pub fn new(rounding: RoundingMode) -> Context {
+        Context {
+            rounding,
+            ..Context::default()
+        }
+    }
+
+    // returns the raised flags and clears them
+    pub fn take_flags(&mut self) -> Flags {
+        core::mem::take(&mut self.flags)
+    }
+
+    // with flush_inputs set, a subnormal operand counts as a zero of the same sign
+    pub(crate) fn flush_input(&mut self, f: &Float) -> Float {
+        if self.flush_inputs && f.classify() == FpCategory::Subnormal {
+            self.flags.raise(Flags::INPUT_DENORMAL);
+            return Float::from_bits(f.to_bits() & (1 << 63));
+        }
+        f.copy()
+    }
+
+    pub fn multiply(&mut self, a: &Float, b: &Float) -> Float {
+        let (a, b) = (self.flush_input(a), self.flush_input(b));
+        a.multiply_in(&b, self)
+    }
+
+    pub fn add(&mut self, a: &Float, b: &Float) -> Float {
+        let (a, b) = (self.flush_input(a), self.flush_input(b));
+        a.add_in(&b, self)
+    }
+
+    pub fn subtract(&mut self, a: &Float, b: &Float) -> Float {
+        let (a, mut negated) = (self.flush_input(a), self.flush_input(b));
+        if !b.is_nan() {
+            negated.negate(); // a nan operand comes back out with its sign unchanged, as on hardware
+        }
+        a.add_in(&negated, self)
+    }
+
+    pub fn divide(&mut self, a: &Float, b: &Float) -> Float {
+        let (a, b) = (self.flush_input(a), self.flush_input(b));
+        a.divide_in(&b, self)
+    }
+
+    /// a * b + c rounded once, in this context's mode.
+    pub fn fused_multiply_add(&mut self, a: &Float, b: &Float, c: &Float) -> Float {
+        let (a, b, c) = (self.flush_input(a), self.flush_input(b), self.flush_input(c));
+        a.fma_in(&b, &c, self)
+    }
+
+    /// Widens binary32 bits to a Float. Always exact; the only flag it can raise is invalid, for a signaling nan
+    /// (or input denormal, when flushing).
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn f32_to_f64(&mut self, bits: u32) -> Float {
+        let sign = bits >> 31 == 1;
+        let exponent = ((bits >> 23) & 0xFF) as i16;
+        let mantissa = (bits & ((1 << 23) - 1)) as u64;
+
+        if exponent == 0xFF {
+            if mantissa == 0 {
+                return Float::infinity(sign);
+            }
+            if mantissa >> 22 == 0 {
+                self.flags.raise(Flags::INVALID); // signaling
+            }
+            if self.nan_mode == NanMode::Canonical {
+                return Float::nan();
+            }
+            // keep the payload in the top mantissa bits and quiet it
+            return Float::from_bits(((sign as u64) << 63) | (0x7FF << 52) | (mantissa << 29) | (1 << 51));
+        }
+        if exponent == 0 {
+            if mantissa == 0 {
+                return Float::from_bits((sign as u64) << 63);
+            }
+            if self.flush_inputs {
+                self.flags.raise(Flags::INPUT_DENORMAL);
+                return Float::from_bits((sign as u64) << 63);
+            }
+            // every binary32 subnormal is a binary64 normal: shift the leading 1 up to the implicit position
+            let shift = mantissa.leading_zeros() - (63 - 23);
+            return Float::from_parts(sign, -126 - shift as i16, (mantissa << (29 + shift)) & ((1 << 52) - 1));
+        }
+        Float::from_parts(sign, exponent - 127, mantissa << 29)
+    }
+}
@@ -0,0 +1,924 @@
+// decimal strings for SoftFloat: parsing with one correct rounding in any mode, and printing either the exact value
+// or the shortest digits that read back as the same float. all of it is exact integer work on a small bignum, so
+// nothing goes through the host's f64 and every format gets its own precision.
+//
+//   let x: F32 = "1.1".parse().unwrap();
+//   let y = F16::parse("0x1.ffcp15", &mut ctx)?; // hex floats too, with a binary exponent
+//   x.to_exact_decimal() // 1.10000002384185791015625
+//   x.to_shortest_decimal() // 1.1e0, as {:e} prints an f32
+//   x.to_engineering_decimal(4) // 1.100e0, and 12.34e3 for 12340
+//   x.to_fixed_decimal(2, Some(',')) // 1.10, and 1,234,567.12 for 1234567.125
+//   parse_f128("0.1", &mut ctx)? // 0x3FFB999999999999999999999999999A, for a host f128
+//   x.to_decimal64(&mut ctx) // 0x2FE3E871B540C000, binary64 1.1 rounded to 16 digits as an IEEE decimal64 (BID)
+//   F64::from_decimal128(bits, &mut ctx) // an IEEE decimal128 (BID) rounded once into binary64
+//
+// parsing takes the digits as one big integer D and the exponent e, then works out D * 10^e to 127 bits plus a
+// sticky bit (dividing by 10^-e for negative e) and hands that to round_pack, so it is exact however near a tie the
+// input is. past 12000 significant digits the rest only count as sticky: no value of any format is halfway between
+// two others with more digits than that.
+//
+// the decimal interchange formats go the same way without any text: a decimal64 or decimal128 is a coefficient
+// and an exponent already, so it goes straight into the parser's rounding, and the other way the exact digits
+// are rounded once to 16 or 34 of them.
+
+use core::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::format::Binary16;
+use crate::{Context, Flags, Float, Format, RoundingMode, SoftFloat, F64};
+
+const MAX_DIGITS: usize = 12000;
+// 10^5000 is above the largest x87 extended and 10^-5000 below half its smallest subnormal
+const MAX_DECIMAL_EXPONENT: i64 = 5000;
+
+// an IEEE decimal interchange format: values coefficient * 10^quantum, with at most `digits` digits in the
+// coefficient and the quantum in min_quantum..=max_quantum, stored plus -min_quantum
+struct DecimalFormat {
+    digits: u32,
+    min_quantum: i64,
+    max_quantum: i64,
+}
+
+const DECIMAL64: DecimalFormat = DecimalFormat { digits: 16, min_quantum: -398, max_quantum: 369 };
+const DECIMAL128: DecimalFormat = DecimalFormat { digits: 34, min_quantum: -6176, max_quantum: 6111 };
+
+impl DecimalFormat {
+    // 10^digits, one past the largest coefficient
+    fn limit(&self) -> u128 {
+        10u128.pow(self.digits)
+    }
+
+    // the exponent of the smallest normal value, 10^-383 for decimal64: below it a value is tiny
+    fn min_exponent(&self) -> i64 {
+        self.min_quantum + self.digits as i64 - 1
+    }
+}
+
+// a decimal value without its sign, between the encoding and the rounding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Decimal {
+    Finite(u128, i64), // coefficient, quantum
+    Infinity,
+    Nan(bool), // signaling
+}
+
+/// The error from parsing a string that isn't a float literal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseFloatError;
+
+impl fmt::Display for ParseFloatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid float literal")
+    }
+}
+
+impl std::error::Error for ParseFloatError {}
+
+// a natural number in little-endian 32-bit limbs, without leading zero limbs
+#[derive(Debug, Clone)]
+struct Big(Vec<u32>);
+
+impl Big {
+    fn from_u128(v: u128) -> Big {
+        let mut b = Big((0..4).map(|i| (v >> (32 * i)) as u32).collect());
+        b.trim();
+        b
+    }
+
+    fn trim(&mut self) {
+        while self.0.last() == Some(&0) {
+            self.0.pop();
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn bits(&self) -> u64 {
+        match self.0.last() {
+            None => 0,
+            Some(top) => 32 * self.0.len() as u64 - top.leading_zeros() as u64,
+        }
+    }
+
+    fn bit(&self, i: u64) -> bool {
+        self.0.get((i / 32) as usize).is_some_and(|limb| limb >> (i % 32) & 1 == 1)
+    }
+
+    fn mul_add_small(&mut self, m: u32, a: u32) {
+        let mut carry = a as u64;
+        for limb in self.0.iter_mut() {
+            let t = *limb as u64 * m as u64 + carry;
+            *limb = t as u32;
+            carry = t >> 32;
+        }
+        if carry != 0 {
+            self.0.push(carry as u32);
+        }
+        self.trim();
+    }
+
+    // self * base^n
+    fn mul_pow(&mut self, base: u32, mut n: u64) {
+        // the largest power of base in a u32, taken as many times as it fits
+        let (mut chunk, mut k) = (base, 1);
+        while let Some(next) = chunk.checked_mul(base) {
+            (chunk, k) = (next, k + 1);
+        }
+        while n >= k {
+            self.mul_add_small(chunk, 0);
+            n -= k;
+        }
+        self.mul_add_small(base.pow(n as u32), 0);
+    }
+
+    fn shl(&mut self, n: u64) {
+        if self.is_zero() {
+            return;
+        }
+        let (limbs, bits) = ((n / 32) as usize, (n % 32) as u32);
+        if bits != 0 {
+            let mut carry = 0;
+            for limb in self.0.iter_mut() {
+                let t = (*limb as u64) << bits | carry;
+                *limb = t as u32;
+                carry = t >> 32;
+            }
+            if carry != 0 {
+                self.0.push(carry as u32);
+            }
+        }
+        self.0.splice(0..0, core::iter::repeat_n(0, limbs));
+    }
+
+    fn compare(&self, other: &Big) -> Ordering {
+        self.0.len().cmp(&other.0.len()).then_with(|| self.0.iter().rev().cmp(other.0.iter().rev()))
+    }
+
+    // self - other, for other <= self
+    fn sub_assign(&mut self, other: &Big) {
+        let mut borrow = 0;
+        for (i, limb) in self.0.iter_mut().enumerate() {
+            let t = *limb as i64 - *other.0.get(i).unwrap_or(&0) as i64 - borrow;
+            *limb = t as u32;
+            borrow = (t < 0) as i64;
+        }
+        self.trim();
+    }
+
+    fn shr(&self, n: u64) -> Big {
+        let (limbs, bits) = ((n / 32) as usize, (n % 32) as u32);
+        let Some(rest) = self.0.get(limbs..) else { return Big(Vec::new()) };
+        let mut out: Vec<u32> = rest.to_vec();
+        if bits != 0 {
+            for i in 0..out.len() {
+                let above = out.get(i + 1).map_or(0, |&limb| limb << (32 - bits));
+                out[i] = out[i] >> bits | above;
+            }
+        }
+        let mut b = Big(out);
+        b.trim();
+        b
+    }
+
+    // (self / d, self % d), a bit at a time from where the quotient starts: the conversions only ever want a
+    // quotient of a hundred or so bits, however long the two are
+    fn divrem(&self, d: &Big) -> (Big, Big) {
+        let mut q = Big(vec![0; self.0.len()]);
+        let start = self.bits().saturating_sub(d.bits());
+        let mut r = self.shr(start);
+        for i in (0..=start).rev() {
+            if i < start {
+                r.shl(1);
+                if self.bit(i) {
+                    if r.is_zero() {
+                        r.0.push(1);
+                    } else {
+                        r.0[0] |= 1;
+                    }
+                }
+            }
+            if r.compare(d) != Ordering::Less {
+                r.sub_assign(d);
+                q.0[(i / 32) as usize] |= 1 << (i % 32);
+            }
+        }
+        q.trim();
+        (q, r)
+    }
+
+    // self /= d, returning the remainder
+    fn div_small(&mut self, d: u32) -> u32 {
+        let mut r = 0u64;
+        for limb in self.0.iter_mut().rev() {
+            let t = r << 32 | *limb as u64;
+            *limb = (t / d as u64) as u32;
+            r = t % d as u64;
+        }
+        self.trim();
+        r as u32
+    }
+
+    // the top 126 bits as (bits, how far they were shifted down, whether anything nonzero was dropped)
+    fn top(&self) -> (u128, i64, bool) {
+        let bits = self.bits();
+        let shift = bits.saturating_sub(126);
+        let mut top = 0u128;
+        for i in (shift..bits).rev() {
+            top = top << 1 | self.bit(i) as u128;
+        }
+        let sticky = (0..shift).any(|i| self.bit(i));
+        (top, shift as i64, sticky)
+    }
+
+    fn to_digits(&self) -> Vec<u8> {
+        let mut n = self.clone();
+        let mut groups = Vec::new();
+        while !n.is_zero() {
+            groups.push(n.div_small(1_000_000_000));
+        }
+        let mut digits = Vec::new();
+        for (i, group) in groups.iter().rev().enumerate() {
+            let text = if i == 0 { group.to_string() } else { format!("{group:09}") };
+            digits.extend(text.bytes().map(|b| b - b'0'));
+        }
+        digits
+    }
+}
+
+fn from_digits(digits: &[u8]) -> Big {
+    let mut n = Big(Vec::new());
+    for chunk in digits.chunks(9) {
+        let value = chunk.iter().fold(0, |v, &d| v * 10 + d as u32);
+        n.mul_add_small(10u32.pow(chunk.len() as u32), value);
+    }
+    n
+}
+
+// sign * n * 2^exponent, plus a little more if sticky, rounded into the format
+fn round_big<F: Format>(sign: bool, n: &Big, exponent: i64, sticky: bool, ctx: &mut Context) -> SoftFloat<F> {
+    let (top, shift, dropped) = n.top();
+    let top = top << (126 - (n.bits() - shift as u64)); // 126 bits exactly, so the sticky bit is far below
+    let exponent = exponent + shift - (126 - (n.bits() as i64 - shift)) - 1;
+    let exponent = exponent.clamp(i32::MIN as i64 / 2, i32::MAX as i64 / 2) as i32;
+    SoftFloat::round_pack(sign, exponent, top << 1 | (sticky || dropped) as u128, ctx)
+}
+
+// sign * digits * 10^exponent, digits without leading zeros
+fn from_decimal<F: Format>(sign: bool, digits: &[u8], exponent: i64, ctx: &mut Context) -> SoftFloat<F> {
+    let end = digits.iter().rposition(|&d| d != 0).map_or(0, |i| i + 1);
+    if end == 0 {
+        return SoftFloat::zero(sign);
+    }
+    let exponent = exponent + (digits.len() - end) as i64;
+    let digits = &digits[..end];
+    // the value is below 10^magnitude and at least 10^(magnitude - 1)
+    let magnitude = exponent + digits.len() as i64;
+    if magnitude > MAX_DECIMAL_EXPONENT {
+        return SoftFloat::round_pack(sign, 1 << 20, 1, ctx);
+    }
+    if magnitude < -MAX_DECIMAL_EXPONENT {
+        return SoftFloat::round_pack(sign, -(1 << 20), 1, ctx);
+    }
+    let (digits, exponent, sticky) = if digits.len() > MAX_DIGITS {
+        (&digits[..MAX_DIGITS], exponent + (digits.len() - MAX_DIGITS) as i64, true)
+    } else {
+        (digits, exponent, false)
+    };
+    let mut n = from_digits(digits);
+    if sticky {
+        // a 1 past the kept digits keeps the value off any tie, as the dropped ones did
+        n.mul_add_small(10, 1);
+    }
+    let exponent = exponent - sticky as i64;
+    if exponent >= 0 {
+        n.mul_pow(10, exponent as u64);
+        return round_big(sign, &n, 0, false, ctx);
+    }
+    // n / 10^-e, to at least 126 quotient bits and a remainder for the sticky bit
+    let mut d = Big::from_u128(1);
+    d.mul_pow(10, exponent.unsigned_abs());
+    let k = (126 + d.bits()).saturating_sub(n.bits());
+    n.shl(k);
+    let (q, r) = n.divrem(&d);
+    round_big(sign, &q, -(k as i64), !r.is_zero(), ctx)
+}
+
+// sign * hex digits * 2^exponent
+fn from_hex<F: Format>(sign: bool, digits: &[u8], exponent: i64, ctx: &mut Context) -> SoftFloat<F> {
+    let mut n = Big(Vec::new());
+    for &d in digits {
+        n.mul_add_small(16, d as u32);
+    }
+    if n.is_zero() {
+        return SoftFloat::zero(sign);
+    }
+    round_big(sign, &n, exponent, false, ctx)
+}
+
+// [sign] digits [. digits] [e|p [sign] digits], as (sign, digit values, the radix's exponent of the last digit).
+// None if it isn't that shape
+fn split(s: &str, radix: u32, marker: u8) -> Option<(Vec<u8>, i64)> {
+    let bytes = s.as_bytes();
+    let mut i = 0;
+    let (mut digits, mut fraction, mut seen) = (Vec::new(), 0i64, false);
+    let mut point = false;
+    while i < bytes.len() {
+        let c = bytes[i];
+        if c == b'.' && !point {
+            point = true;
+        } else if let Some(d) = (c as char).to_digit(radix) {
+            seen = true;
+            // leading zeros say nothing
+            if d != 0 || !digits.is_empty() {
+                digits.push(d as u8);
+            }
+            fraction += point as i64;
+        } else {
+            break;
+        }
+        i += 1;
+    }
+    if !seen {
+        return None;
+    }
+    let mut exponent = 0i64;
+    if i < bytes.len() {
+        if bytes[i].to_ascii_lowercase() != marker {
+            return None;
+        }
+        i += 1;
+        let negative = match bytes.get(i) {
+            Some(b'-') => true,
+            Some(b'+') => false,
+            _ => {
+                i -= 1;
+                false
+            }
+        };
+        i += 1;
+        if i >= bytes.len() {
+            return None;
+        }
+        for &c in &bytes[i..] {
+            let d = (c as char).to_digit(10)? as i64;
+            exponent = (exponent * 10 + d).min(1 << 40); // past that it's a zero or an infinity anyway
+        }
+        if negative {
+            exponent = -exponent;
+        }
+    }
+    let scale = if radix == 16 { 4 } else { 1 };
+    Some((digits, exponent - fraction * scale))
+}
+
+impl<F: Format> SoftFloat<F> {
+    /// Parses a float literal, rounded once in ctx.rounding with the flags a conversion raises: a decimal like
+    /// `-1.25e-3` or `.5`, a hex float like `0x1.8p3` (the p exponent is a power of two, and optional), or `inf`,
+    /// `infinity` or `nan` in any case, any of them with a sign. Nan is the default quiet one.
+    pub fn parse(s: &str, ctx: &mut Context) -> Result<Self, ParseFloatError> {
+        let (sign, rest) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+        let with_sign = |mut x: Self| {
+            if sign {
+                x.negate();
+            }
+            x
+        };
+        if rest.eq_ignore_ascii_case("inf") || rest.eq_ignore_ascii_case("infinity") {
+            return Ok(Self::infinity(sign));
+        }
+        if rest.eq_ignore_ascii_case("nan") {
+            return Ok(with_sign(Self::nan()));
+        }
+        if let Some(hex) = rest.strip_prefix("0x").or_else(|| rest.strip_prefix("0X")) {
+            let (digits, exponent) = split(hex, 16, b'p').ok_or(ParseFloatError)?;
+            return Ok(from_hex(sign, &digits, exponent, ctx));
+        }
+        let (digits, exponent) = split(rest, 10, b'e').ok_or(ParseFloatError)?;
+        Ok(from_decimal(sign, &digits, exponent, ctx))
+    }
+
+    // the value as decimal digits, without leading or trailing zeros, times 10^exponent. finite nonzero only
+    fn exact_digits(&self) -> (Vec<u8>, i64) {
+        let u = self.unpack();
+        let mut n = Big::from_u128(u.significand);
+        let exponent = if u.exponent >= 0 {
+            n.shl(u.exponent as u64);
+            0
+        } else {
+            // m / 2^k = m 5^k / 10^k
+            n.mul_pow(5, u.exponent.unsigned_abs() as u64);
+            u.exponent as i64
+        };
+        let mut digits = n.to_digits();
+        let end = digits.iter().rposition(|&d| d != 0).map_or(0, |i| i + 1);
+        let zeros = digits.len() - end;
+        digits.truncate(end);
+        (digits, exponent + zeros as i64)
+    }
+
+    /// Every digit of the value, in plain positional notation: 0.1 in binary64 is
+    /// 0.1000000000000000055511151231257827021181583404541015625. Infinities are `inf` and nans `NaN`.
+    pub fn to_exact_decimal(&self) -> String {
+        let sign = if self.get_sign() { "-" } else { "" };
+        if self.is_nan() {
+            return "NaN".into();
+        }
+        if self.is_infinity() {
+            return format!("{sign}inf");
+        }
+        if self.is_zero() {
+            return format!("{sign}0");
+        }
+        let (digits, exponent) = self.exact_digits();
+        let text: String = digits.iter().map(|d| (b'0' + d) as char).collect();
+        if exponent >= 0 {
+            return format!("{sign}{text}{}", "0".repeat(exponent as usize));
+        }
+        let point = digits.len() as i64 + exponent; // digits before the point
+        if point > 0 {
+            format!("{sign}{}.{}", &text[..point as usize], &text[point as usize..])
+        } else {
+            format!("{sign}0.{}{text}", "0".repeat(point.unsigned_abs() as usize))
+        }
+    }
+
+    /// The fewest significant digits that parse back (rounding to nearest) to this value, the nearest such if
+    /// there's a choice and the larger in magnitude on a tie, in the style and with the digits of `{:e}`: `1.1e0`,
+    /// `-5e-324`. Infinities are `inf` and nans `NaN`.
+    pub fn to_shortest_decimal(&self) -> String {
+        let sign = if self.get_sign() { "-" } else { "" };
+        if self.is_nan() {
+            return "NaN".into();
+        }
+        if self.is_infinity() {
+            return format!("{sign}inf");
+        }
+        if self.is_zero() {
+            return format!("{sign}0e0");
+        }
+        let (exact, exponent) = self.exact_digits();
+        let reads_back = |digits: &[u8], exponent: i64| {
+            let mut ctx = Context::new(RoundingMode::TiesToEven);
+            from_decimal::<F>(self.get_sign(), digits, exponent, &mut ctx).to_bits() == self.to_bits()
+        };
+        // the n-digit decimal that reads back, if there is one: of the two either side of the value, the nearer
+        // first (a tie going away from zero, as in {:e})
+        let candidate = |n: usize| {
+            if n >= exact.len() {
+                return Some((exact.clone(), exponent));
+            }
+            let shift = exponent + (exact.len() - n) as i64;
+            let down = exact[..n].to_vec();
+            let up = increment(&down);
+            let (near, far) = if exact[n] >= 5 { (up, down) } else { (down, up) };
+            [near, far].into_iter().find(|digits| reads_back(digits, shift)).map(|digits| (digits, shift))
+        };
+        // if n digits read back then so do n + 1 (the one on the same side is nearer still), so search for the
+        // fewest
+        let (mut low, mut high) = (1, exact.len());
+        while low < high {
+            let mid = (low + high) / 2;
+            if candidate(mid).is_some() {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+        let (digits, exponent) = candidate(low).unwrap_or((exact.clone(), exponent));
+        // normalize a carry out of the top (999 + 1) and any trailing zeros
+        let end = digits.iter().rposition(|&d| d != 0).map_or(1, |i| i + 1);
+        let (digits, exponent) = (&digits[..end], exponent + (digits.len() - end) as i64);
+        let text: String = digits.iter().map(|d| (b'0' + d) as char).collect();
+        let scientific = exponent + digits.len() as i64 - 1;
+        if text.len() == 1 {
+            format!("{sign}{text}e{scientific}")
+        } else {
+            format!("{sign}{}.{}e{scientific}", &text[..1], &text[1..])
+        }
+    }
+
+    /// The value to `significant` digits (0 counts as 1), rounded to nearest with ties to even, with the exponent
+    /// a multiple of 3, so one to three digits come before the point: 12340 to 4 digits is `12.34e3` and 0.00012
+    /// to 2 is `120e-6`. Trailing zeros are kept, since they say how many digits there are, as `{:.3e}` does.
+    /// Infinities are `inf` and nans `NaN`.
+    pub fn to_engineering_decimal(&self, significant: usize) -> String {
+        let sign = if self.get_sign() { "-" } else { "" };
+        if self.is_nan() {
+            return "NaN".into();
+        }
+        if self.is_infinity() {
+            return format!("{sign}inf");
+        }
+        let n = significant.max(1);
+        let (mut digits, mut scientific) = if self.is_zero() {
+            (vec![0; n], 0)
+        } else {
+            let (exact, exponent) = self.exact_digits();
+            (exact.clone(), exponent + exact.len() as i64 - 1)
+        };
+        if digits.len() > n {
+            // the digits are exact, so a 5 followed by nothing but zeros is a true tie
+            let rest = digits.split_off(n);
+            let beyond = rest[1..].iter().any(|&d| d != 0);
+            if rest[0] > 5 || rest[0] == 5 && (beyond || digits[n - 1] % 2 == 1) {
+                digits = increment(&digits);
+                if digits.len() > n {
+                    digits.truncate(n); // 999 up to 1000, one place higher
+                    scientific += 1;
+                }
+            }
+        }
+        digits.resize(n, 0);
+        let exponent = scientific.div_euclid(3) * 3;
+        let before = (scientific - exponent + 1) as usize;
+        let mut text: String = digits.iter().map(|d| (b'0' + d) as char).collect();
+        if before >= n {
+            text.push_str(&"0".repeat(before - n));
+        } else {
+            text.insert(before, '.');
+        }
+        format!("{sign}{text}e{exponent}")
+    }
+
+    /// The value in positional notation with `places` digits after the point, rounded to nearest with ties to
+    /// even as `{:.2}` does, and the digits before the point in groups of three split by `separator` if there is
+    /// one: 1234567.125 to 2 places with ',' is `1,234,567.12`. The grouping is done on the rounded digits, so it
+    /// can't change them. A negative value that rounds to zero keeps its sign. Infinities are `inf` and nans `NaN`.
+    pub fn to_fixed_decimal(&self, places: usize, separator: Option<char>) -> String {
+        let sign = if self.get_sign() { "-" } else { "" };
+        if self.is_nan() {
+            return "NaN".into();
+        }
+        if self.is_infinity() {
+            return format!("{sign}inf");
+        }
+        let (mut digits, exponent) = if self.is_zero() { (vec![0], 0) } else { self.exact_digits() };
+        // the value times 10^places, as digits * 10^shift, rounded to an integer
+        let shift = exponent + places as i64;
+        if shift >= 0 {
+            digits.resize(digits.len() + shift as usize, 0);
+        } else {
+            let dropped = shift.unsigned_abs() as usize;
+            let (kept, rest) = if dropped >= digits.len() {
+                let mut rest = vec![0; dropped - digits.len()];
+                rest.extend(&digits);
+                (vec![0], rest)
+            } else {
+                let rest = digits.split_off(digits.len() - dropped);
+                (digits, rest)
+            };
+            let beyond = rest[1..].iter().any(|&d| d != 0);
+            let odd = kept.last().is_some_and(|d| d % 2 == 1);
+            digits = if rest[0] > 5 || rest[0] == 5 && (beyond || odd) { increment(&kept) } else { kept };
+        }
+        if digits.len() <= places {
+            let mut padded = vec![0; places + 1 - digits.len()];
+            padded.extend(&digits);
+            digits = padded;
+        }
+        let (integer, fraction) = digits.split_at(digits.len() - places);
+        let mut text = String::from(sign);
+        for (i, d) in integer.iter().enumerate() {
+            if let Some(separator) = separator.filter(|_| i > 0 && (integer.len() - i) % 3 == 0) {
+                text.push(separator);
+            }
+            text.push((b'0' + d) as char);
+        }
+        if places > 0 {
+            text.push('.');
+            text.extend(fraction.iter().map(|d| (b'0' + d) as char));
+        }
+        text
+    }
+
+    /// The value as an IEEE 754 decimal64, in the binary integer decimal (BID) encoding that Intel's library and
+    /// GCC on x86 use: rounded once to 16 digits in ctx.rounding, with the flags the conversion raises. Tininess is
+    /// detected before rounding, as IEEE 754 has it for decimal formats. An exact result keeps the quantum 10^0 if
+    /// it's an integer and otherwise the fewest digits after the point (100 is 100 * 10^0, 1.5 is 15 * 10^-1), zeros
+    /// included, and nans come out as the quiet nan with their sign.
+    pub fn to_decimal64(&self, ctx: &mut Context) -> u64 {
+        let sign = (self.get_sign() as u64) << 63;
+        sign | encode_bid64(self.round_decimal(&DECIMAL64, ctx))
+    }
+
+    /// The value as an IEEE 754 decimal128 in the BID encoding, rounded once to 34 digits as to_decimal64 rounds
+    /// to 16. Every binary64 and x87 value is well inside its range, so for those it never overflows or underflows.
+    pub fn to_decimal128(&self, ctx: &mut Context) -> u128 {
+        let sign = (self.get_sign() as u128) << 127;
+        sign | encode_bid128(self.round_decimal(&DECIMAL128, ctx))
+    }
+
+    /// A decimal64 in the BID encoding, rounded once into this format in ctx.rounding with the flags a conversion
+    /// raises (underflow as ctx.tininess says). A coefficient past 10^16 - 1 isn't canonical and reads as zero, as
+    /// IEEE 754 has it, and a signaling nan raises invalid and becomes the quiet nan with its sign.
+    pub fn from_decimal64(bits: u64, ctx: &mut Context) -> Self {
+        Self::from_decimal_value(bits >> 63 == 1, decode_bid64(bits), ctx)
+    }
+
+    /// A decimal128 in the BID encoding, rounded once into this format as from_decimal64 rounds a decimal64.
+    pub fn from_decimal128(bits: u128, ctx: &mut Context) -> Self {
+        Self::from_decimal_value(bits >> 127 == 1, decode_bid128(bits), ctx)
+    }
+
+    // the value rounded to format's digits in ctx.rounding, raising the flags. zeros keep the quantum 10^0
+    fn round_decimal(&self, format: &DecimalFormat, ctx: &mut Context) -> Decimal {
+        if self.is_nan() {
+            if self.is_signaling_nan() {
+                ctx.flags.raise(Flags::INVALID);
+            }
+            return Decimal::Nan(false);
+        }
+        if self.is_infinity() {
+            return Decimal::Infinity;
+        }
+        if self.is_zero() {
+            return Decimal::Finite(0, 0);
+        }
+        let (digits, exponent) = self.exact_digits();
+        let (n, p) = (digits.len() as i64, format.digits as i64);
+        // integers at 10^0, then at most p digits, and no finer than the smallest subnormal's quantum
+        let mut quantum = exponent.min(0).max(exponent + n - p).max(format.min_quantum);
+        let dropped = quantum - exponent;
+        let kept = (n - dropped.max(0)).max(0) as usize;
+        let mut coefficient = digits[..kept].iter().fold(0u128, |c, &d| c * 10 + d as u128);
+        if dropped < 0 {
+            coefficient *= 10u128.pow(-dropped as u32); // an integer's zeros, which the p digits have room for
+        }
+        // exact_digits has no trailing zeros, so dropping any digit at all is inexact
+        if dropped > 0 {
+            let (half_digit, beyond) = match digits.get(kept) {
+                Some(&d) if dropped <= n => (d, digits[kept + 1..].iter().any(|&d| d != 0)),
+                _ => (0, true), // every digit is below the first dropped place
+            };
+            let (half, sticky) = (half_digit >= 5, half_digit % 5 != 0 || beyond);
+            if ctx.rounding.round_up(self.get_sign(), coefficient % 2 == 1, half, sticky) {
+                coefficient += 1;
+                if coefficient == format.limit() {
+                    (coefficient, quantum) = (coefficient / 10, quantum + 1); // 9999... up to 10000...
+                }
+            }
+            ctx.flags.raise(Flags::INEXACT);
+            if exponent + n - 1 < format.min_exponent() {
+                ctx.flags.raise(Flags::UNDERFLOW);
+            }
+        }
+        if quantum > format.max_quantum {
+            // a large integer with few digits still fits, with zeros on the end of its coefficient
+            let shift = (quantum - format.max_quantum) as u32;
+            let scaled = 10u128.checked_pow(shift).and_then(|power| coefficient.checked_mul(power));
+            match scaled.filter(|&c| c < format.limit()) {
+                Some(c) => (coefficient, quantum) = (c, format.max_quantum),
+                None => {
+                    ctx.flags.raise(Flags::OVERFLOW | Flags::INEXACT);
+                    if ctx.rounding.overflows_to_infinity(self.get_sign()) {
+                        return Decimal::Infinity;
+                    }
+                    return Decimal::Finite(format.limit() - 1, format.max_quantum);
+                }
+            }
+        }
+        Decimal::Finite(coefficient, quantum)
+    }
+
+    fn from_decimal_value(sign: bool, value: Decimal, ctx: &mut Context) -> Self {
+        match value {
+            Decimal::Finite(coefficient, quantum) => {
+                from_decimal(sign, &Big::from_u128(coefficient).to_digits(), quantum, ctx)
+            }
+            Decimal::Infinity => Self::infinity(sign),
+            Decimal::Nan(signaling) => {
+                if signaling {
+                    ctx.flags.raise(Flags::INVALID);
+                }
+                let mut nan = Self::nan();
+                if sign {
+                    nan.negate();
+                }
+                nan
+            }
+        }
+    }
+}
+
+// the BID encodings, without the sign bit. after it come five combination bits: 11111 is a nan (signaling if the
+// next bit is set) and 11110 an infinity. otherwise the exponent comes first and then the coefficient's low bits;
+// if the first two are 11 the exponent is two places lower and the coefficient's top bits are an implicit 100
+
+const BID64_INFINITY: u64 = 0x7800_0000_0000_0000;
+const BID64_NAN: u64 = 0x7C00_0000_0000_0000;
+
+// coefficients of 2^53 and up (to 10^16 - 1) don't fit decimal64's 53-bit field, so they take the 11 form
+fn encode_bid64(value: Decimal) -> u64 {
+    let (coefficient, quantum) = match value {
+        Decimal::Finite(coefficient, quantum) => (coefficient as u64, quantum),
+        Decimal::Infinity => return BID64_INFINITY,
+        Decimal::Nan(_) => return BID64_NAN,
+    };
+    let biased = (quantum - DECIMAL64.min_quantum) as u64;
+    if coefficient < 1 << 53 {
+        biased << 53 | coefficient
+    } else {
+        0b11 << 61 | biased << 51 | coefficient & ((1 << 51) - 1)
+    }
+}
+
+fn decode_bid64(bits: u64) -> Decimal {
+    match bits >> 58 & 0b11111 {
+        0b11111 => return Decimal::Nan(bits >> 57 & 1 == 1),
+        0b11110 => return Decimal::Infinity,
+        _ => {}
+    }
+    let (biased, coefficient) = if bits >> 61 & 0b11 == 0b11 {
+        (bits >> 51 & 0x3FF, 0b100 << 51 | bits & ((1 << 51) - 1))
+    } else {
+        (bits >> 53 & 0x3FF, bits & ((1 << 53) - 1))
+    };
+    let coefficient = coefficient as u128;
+    let canonical = if coefficient < DECIMAL64.limit() { coefficient } else { 0 };
+    Decimal::Finite(canonical, biased as i64 + DECIMAL64.min_quantum)
+}
+
+const BID128_INFINITY: u128 = 0x7800 << 112;
+const BID128_NAN: u128 = 0x7C00 << 112;
+
+// 10^34 - 1 is below 2^113, so decimal128 never needs the 11 form, and anything in it isn't canonical
+fn encode_bid128(value: Decimal) -> u128 {
+    match value {
+        Decimal::Finite(coefficient, quantum) => ((quantum - DECIMAL128.min_quantum) as u128) << 113 | coefficient,
+        Decimal::Infinity => BID128_INFINITY,
+        Decimal::Nan(_) => BID128_NAN,
+    }
+}
+
+fn decode_bid128(bits: u128) -> Decimal {
+    match bits >> 122 & 0b11111 {
+        0b11111 => return Decimal::Nan(bits >> 121 & 1 == 1),
+        0b11110 => return Decimal::Infinity,
+        _ => {}
+    }
+    let (biased, coefficient) = if bits >> 125 & 0b11 == 0b11 {
+        (bits >> 111 & 0x3FFF, DECIMAL128.limit()) // past 2^113, so never canonical
+    } else {
+        (bits >> 113 & 0x3FFF, bits & ((1 << 113) - 1))
+    };
+    let canonical = if coefficient < DECIMAL128.limit() { coefficient } else { 0 };
+    Decimal::Finite(canonical, biased as i64 + DECIMAL128.min_quantum)
+}
+
+/// IEEE 754's quantize for decimal64s in the BID encoding: x's value with y's quantum, so quantizing by 0.01
+/// rounds to cents. Going to a coarser quantum rounds in ctx.rounding and raises inexact if that drops anything
+/// (never underflow); going to a finer one that needs more than 16 digits, or an infinity on only one side,
+/// raises invalid and gives the quiet nan. Nans come out quiet with their sign, a signaling one raising invalid.
+pub fn quantize_decimal64(x: u64, y: u64, ctx: &mut Context) -> u64 {
+    let (x, y) = ((x >> 63 == 1, decode_bid64(x)), (y >> 63 == 1, decode_bid64(y)));
+    let (sign, value) = quantize(&DECIMAL64, x, y, ctx);
+    (sign as u64) << 63 | encode_bid64(value)
+}
+
+/// quantize_decimal64 for decimal128s, with 34 digits to fit the result in.
+pub fn quantize_decimal128(x: u128, y: u128, ctx: &mut Context) -> u128 {
+    let (x, y) = ((x >> 127 == 1, decode_bid128(x)), (y >> 127 == 1, decode_bid128(y)));
+    let (sign, value) = quantize(&DECIMAL128, x, y, ctx);
+    (sign as u128) << 127 | encode_bid128(value)
+}
+
+/// IEEE 754's sameQuantum for decimal64s in the BID encoding: whether both have the same exponent, so 1.0 and
+/// 1.00 don't and 1.0 and 2.5 do. Two infinities or two nans have the same quantum, and it never raises a flag.
+pub fn same_quantum_decimal64(x: u64, y: u64) -> bool {
+    same_quantum(decode_bid64(x), decode_bid64(y))
+}
+
+/// same_quantum_decimal64 for decimal128s.
+pub fn same_quantum_decimal128(x: u128, y: u128) -> bool {
+    same_quantum(decode_bid128(x), decode_bid128(y))
+}
+
+fn same_quantum(x: Decimal, y: Decimal) -> bool {
+    match (x, y) {
+        (Decimal::Finite(_, p), Decimal::Finite(_, q)) => p == q,
+        (Decimal::Infinity, Decimal::Infinity) | (Decimal::Nan(_), Decimal::Nan(_)) => true,
+        _ => false,
+    }
+}
+
+// quantize on signs and values, giving the result's sign and value
+fn quantize(format: &DecimalFormat, x: (bool, Decimal), y: (bool, Decimal), ctx: &mut Context) -> (bool, Decimal) {
+    let invalid = |ctx: &mut Context| {
+        ctx.flags.raise(Flags::INVALID);
+        (false, Decimal::Nan(false))
+    };
+    match (x.1, y.1) {
+        (Decimal::Nan(_), _) | (_, Decimal::Nan(_)) => {
+            if x.1 == Decimal::Nan(true) || y.1 == Decimal::Nan(true) {
+                ctx.flags.raise(Flags::INVALID);
+            }
+            let sign = if matches!(x.1, Decimal::Nan(_)) { x.0 } else { y.0 };
+            (sign, Decimal::Nan(false))
+        }
+        (Decimal::Infinity, Decimal::Infinity) => (x.0, Decimal::Infinity),
+        (Decimal::Infinity, _) | (_, Decimal::Infinity) => invalid(ctx),
+        (Decimal::Finite(coefficient, exponent), Decimal::Finite(_, quantum)) if quantum <= exponent => {
+            // exact, if the zeros on the end fit
+            let power = 10u128.checked_pow((exponent - quantum) as u32);
+            let scaled = power.and_then(|power| coefficient.checked_mul(power));
+            match scaled.filter(|&c| c < format.limit()) {
+                Some(c) => (x.0, Decimal::Finite(c, quantum)),
+                None if coefficient == 0 => (x.0, Decimal::Finite(0, quantum)),
+                None => invalid(ctx),
+            }
+        }
+        (Decimal::Finite(coefficient, exponent), Decimal::Finite(_, quantum)) => {
+            // a coefficient is below 10^34, so past 35 places it's all below half the last one kept
+            let dropped = quantum - exponent;
+            let (mut kept, half, sticky) = if dropped > 35 {
+                (0, false, coefficient != 0)
+            } else {
+                let power = 10u128.pow(dropped as u32);
+                let rest = coefficient % power;
+                (coefficient / power, rest * 2 >= power, rest != 0 && rest * 2 != power)
+            };
+            // kept has at least a digit fewer than the format allows, so this can't carry out of it
+            if ctx.rounding.round_up(x.0, kept % 2 == 1, half, sticky) {
+                kept += 1;
+            }
+            if half || sticky {
+                ctx.flags.raise(Flags::INEXACT);
+            }
+            (x.0, Decimal::Finite(kept, quantum))
+        }
+    }
+}
+
+// digits + 1 in the last place, growing a digit on a carry out of the top
+fn increment(digits: &[u8]) -> Vec<u8> {
+    let mut digits = digits.to_vec();
+    for d in digits.iter_mut().rev() {
+        if *d == 9 {
+            *d = 0;
+        } else {
+            *d += 1;
+            return digits;
+        }
+    }
+    digits.insert(0, 1);
+    digits
+}
+
+// binary128, for its strings only: SoftFloat's arithmetic stops at 63 mantissa bits, but parsing and printing are
+// integer work that just needs the significand in a u128, and round_big keeps 14 bits past binary128's 113
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Binary128;
+
+impl Format for Binary128 {
+    type Bits = u128;
+    const EXPONENT_BITS: u32 = 15;
+    const MANTISSA_BITS: u32 = 112;
+    const NAME: &'static str = "binary128";
+}
+
+/// Parses a float literal straight to binary128, rounded once at its own precision as `SoftFloat::parse` rounds,
+/// and returns the encoding: the bits of a Rust `f128` or a C `_Float128`.
+pub fn parse_f128(s: &str, ctx: &mut Context) -> Result<u128, ParseFloatError> {
+    SoftFloat::<Binary128>::parse(s, ctx).map(|x| x.to_bits())
+}
+
+/// The shortest digits that parse back to a binary128 encoding, at most 36 of them, as `to_shortest_decimal`
+/// prints them.
+pub fn format_f128(bits: u128) -> String {
+    SoftFloat::<Binary128>::from_bits(bits).to_shortest_decimal()
+}
+
+/// Parses a float literal straight to binary16 (`F16::parse`, as an encoding for a Rust `f16`).
+pub fn parse_f16(s: &str, ctx: &mut Context) -> Result<u16, ParseFloatError> {
+    SoftFloat::<Binary16>::parse(s, ctx).map(|x| x.to_bits())
+}
+
+/// The shortest digits that parse back to a binary16 encoding: 0.1 is `1e-1`, not binary64's digits for the
+/// same value.
+pub fn format_f16(bits: u16) -> String {
+    SoftFloat::<Binary16>::from_bits(bits).to_shortest_decimal()
+}
+
+impl<F: Format> FromStr for SoftFloat<F> {
+    type Err = ParseFloatError;
+
+    /// SoftFloat::parse, rounding to nearest and dropping the flags.
+    fn from_str(s: &str) -> Result<Self, ParseFloatError> {
+        SoftFloat::parse(s, &mut Context::default())
+    }
+}
+
+impl FromStr for Float {
+    type Err = ParseFloatError;
+
+    fn from_str(s: &str) -> Result<Self, ParseFloatError> {
+        s.parse::<F64>().map(Float::from)
+    }
+}
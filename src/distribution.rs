@@ -0,0 +1,142 @@
+// rand's distributions for SoftFloat and Float (the rand feature), so rng.random::<F64>() and Uniform::new(low, high)
+// work without sampling bits by hand. StandardUniform is random::unit, as rand's own f64 is: evenly spaced values
+// in [0, 1). Open01 and OpenClosed01 shift those by half a step and a whole one, as rand does. Uniform is
+// random::range, which gives every float in the range its share rather than only the evenly spaced ones.
+
+use core::cmp::Ordering;
+
+use rand::distr::uniform::{Error, SampleBorrow, SampleUniform, UniformSampler};
+use rand::distr::{Distribution, Open01, OpenClosed01, StandardUniform};
+use rand::Rng;
+
+use crate::format::Binary64;
+use crate::random::{range, unit};
+use crate::{Context, Float, Format, SoftFloat, F64};
+
+impl<F: Format> Distribution<SoftFloat<F>> for StandardUniform {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> SoftFloat<F> {
+        unit(&mut || rng.next_u64())
+    }
+}
+
+impl<F: Format> Distribution<SoftFloat<F>> for OpenClosed01 {
+    // (k + 1) / 2^(m + 1), in (0, 1]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> SoftFloat<F> {
+        let precision = F::MANTISSA_BITS + 1;
+        let k = rng.next_u64() >> (64 - precision);
+        SoftFloat::round_pack(false, -(precision as i32), k as u128 + 1, &mut Context::default())
+    }
+}
+
+impl<F: Format> Distribution<SoftFloat<F>> for Open01 {
+    // (2k + 1) / 2^(m + 1) for k of m bits, in (0, 1)
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> SoftFloat<F> {
+        let m = F::MANTISSA_BITS;
+        let k = if m == 0 { 0 } else { rng.next_u64() >> (64 - m) };
+        SoftFloat::round_pack(false, -(m as i32 + 1), 2 * k as u128 + 1, &mut Context::default())
+    }
+}
+
+impl Distribution<Float> for StandardUniform {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Float {
+        Float::from(Distribution::<F64>::sample(self, rng))
+    }
+}
+
+impl Distribution<Float> for OpenClosed01 {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Float {
+        Float::from(Distribution::<F64>::sample(self, rng))
+    }
+}
+
+impl Distribution<Float> for Open01 {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Float {
+        Float::from(Distribution::<F64>::sample(self, rng))
+    }
+}
+
+/// The sampler behind `Uniform<SoftFloat<F>>`: random::range over [low, high).
+#[derive(Debug, Clone, Copy)]
+pub struct UniformSoftFloat<F: Format> {
+    low: SoftFloat<F>,
+    high: SoftFloat<F>, // exclusive; equal to low for an inclusive range of one value
+}
+
+impl<F: Format> UniformSampler for UniformSoftFloat<F> {
+    type X = SoftFloat<F>;
+
+    fn new<B1, B2>(low: B1, high: B2) -> Result<Self, Error>
+    where
+        B1: SampleBorrow<Self::X> + Sized,
+        B2: SampleBorrow<Self::X> + Sized,
+    {
+        let (low, high) = (*low.borrow(), *high.borrow());
+        if low.is_nan() || high.is_nan() || low.is_infinity() || high.is_infinity() {
+            return Err(Error::NonFinite);
+        }
+        if low.compare(&high) != Some(Ordering::Less) {
+            return Err(Error::EmptyRange);
+        }
+        Ok(UniformSoftFloat { low, high })
+    }
+
+    /// [low, high] is sampled as [low, next float above high), so high gets the share of the gap above it, except
+    /// that it stays exclusive when high is the largest finite value.
+    fn new_inclusive<B1, B2>(low: B1, high: B2) -> Result<Self, Error>
+    where
+        B1: SampleBorrow<Self::X> + Sized,
+        B2: SampleBorrow<Self::X> + Sized,
+    {
+        let (low, high) = (*low.borrow(), *high.borrow());
+        if low.is_nan() || high.is_nan() || low.is_infinity() || high.is_infinity() {
+            return Err(Error::NonFinite);
+        }
+        if low.compare(&high) == Some(Ordering::Equal) {
+            return Ok(UniformSoftFloat { low, high: low });
+        }
+        let above = high.next_up();
+        UniformSoftFloat::new(low, if above.is_infinity() { high } else { above })
+    }
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> SoftFloat<F> {
+        range(&self.low, &self.high, &mut || rng.next_u64()).unwrap_or(self.low)
+    }
+}
+
+impl<F: Format> SampleUniform for SoftFloat<F> {
+    type Sampler = UniformSoftFloat<F>;
+}
+
+/// The sampler behind `Uniform<Float>`, the binary64 one.
+#[derive(Debug, Clone, Copy)]
+pub struct UniformFloat(UniformSoftFloat<Binary64>);
+
+impl UniformSampler for UniformFloat {
+    type X = Float;
+
+    fn new<B1, B2>(low: B1, high: B2) -> Result<Self, Error>
+    where
+        B1: SampleBorrow<Self::X> + Sized,
+        B2: SampleBorrow<Self::X> + Sized,
+    {
+        let (low, high) = (F64::from(low.borrow().copy()), F64::from(high.borrow().copy()));
+        UniformSoftFloat::new(low, high).map(UniformFloat)
+    }
+
+    fn new_inclusive<B1, B2>(low: B1, high: B2) -> Result<Self, Error>
+    where
+        B1: SampleBorrow<Self::X> + Sized,
+        B2: SampleBorrow<Self::X> + Sized,
+    {
+        let (low, high) = (F64::from(low.borrow().copy()), F64::from(high.borrow().copy()));
+        UniformSoftFloat::new_inclusive(low, high).map(UniformFloat)
+    }
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Float {
+        Float::from(self.0.sample(rng))
+    }
+}
+
+impl SampleUniform for Float {
+    type Sampler = UniformFloat;
+}
@@ -0,0 +1,293 @@
+// an expression kept exact until it's rounded, once, at the end. sums, differences and products of floating-point
+// numbers are all dyadic, significand * 2^exponent with an integer significand, so every step here is done in full
+// on a limbs significand and nothing rounds until round. that's a * b + c * d "as if with infinite precision"
+// without an fma or a dot product routine for each shape of expression:
+//
+//     let x = (Exact::from(&a) * &b + Exact::from(&c) * &d).round();
+//
+// Float has no operators of its own, so an expression starts with Exact::from, and the operators take Exacts,
+// Floats, SoftFloats and integers on the right. there's no division: quotients generally aren't dyadic.
+//
+// the significand holds up to limbs::MAX_LIMBS limbs, 4096 bits. any sum or product of two doubles fits, and so
+// does most of what's built from a few more; past that the lowest bits are jammed into the last one, the way the
+// adders do it, which still rounds right at the end unless a later subtraction cancels all but those last bits.
+
+use core::ops;
+
+use crate::format::Format;
+use crate::limbs::{self, MAX_LIMBS};
+use crate::wide::Wide;
+use crate::{Context, Flags, Float, RoundingMode, SoftFloat, F64};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Finite,
+    Infinity,
+    Nan { invalid: bool }, // from inf - inf, inf * 0 or a signaling nan, so rounding raises invalid
+}
+
+/// A sum, difference or product of floating-point numbers, held exactly, to be rounded once with `round`.
+#[derive(Debug, Clone, Copy)]
+pub struct Exact {
+    kind: Kind,
+    sign: bool,
+    // an exact zero's sign can depend on the rounding: x - x is -0 rounding toward negative and +0 otherwise. this
+    // is the sign toward negative, the same as sign for anything but a zero
+    sign_down: bool,
+    exponent: i32, // what the significand's last bit is worth
+    len: usize,    // limbs in use, the top one nonzero. zero has none
+    limbs: [u64; MAX_LIMBS],
+}
+
+impl Default for Exact {
+    fn default() -> Exact {
+        Exact::zero(false, false)
+    }
+}
+
+impl Exact {
+    fn special(kind: Kind, sign: bool) -> Exact {
+        Exact { kind, sign, sign_down: sign, exponent: 0, len: 0, limbs: [0; MAX_LIMBS] }
+    }
+
+    fn zero(sign: bool, sign_down: bool) -> Exact {
+        Exact { sign_down, ..Exact::special(Kind::Finite, sign) }
+    }
+
+    fn invalid() -> Exact {
+        Exact::special(Kind::Nan { invalid: true }, false)
+    }
+
+    // a nan operand, or both, passed on
+    fn nan(&self, other: &Exact) -> Option<Exact> {
+        match (self.kind, other.kind) {
+            (Kind::Nan { invalid: a }, Kind::Nan { invalid: b }) => {
+                Some(Exact::special(Kind::Nan { invalid: a || b }, self.sign))
+            }
+            (Kind::Nan { .. }, _) => Some(*self),
+            (_, Kind::Nan { .. }) => Some(*other),
+            _ => None,
+        }
+    }
+
+    // significand * 2^exponent, with the significand any number of limbs long. trailing zeros go into the exponent,
+    // and bits that still don't fit are jammed
+    fn finite(sign: bool, mut exponent: i32, significand: &mut [u64]) -> Exact {
+        let Some(low) = significand.iter().position(|&limb| limb != 0) else {
+            return Exact::zero(sign, sign);
+        };
+        let zeros = low as u32 * 64 + significand[low].trailing_zeros();
+        limbs::shr_assign(significand, zeros);
+        exponent = exponent.saturating_add(zeros as i32);
+        let bits = significand.len() as u32 * 64 - limbs::leading_zeros(significand);
+        if bits > MAX_LIMBS as u32 * 64 {
+            let shift = bits - MAX_LIMBS as u32 * 64;
+            significand[0] |= limbs::shr_assign(significand, shift) as u64;
+            exponent = exponent.saturating_add(shift as i32);
+        }
+        let len = significand.iter().rposition(|&limb| limb != 0).map_or(0, |top| top + 1);
+        let mut x = Exact { exponent, len, ..Exact::special(Kind::Finite, sign) };
+        x.limbs[..len].copy_from_slice(&significand[..len]);
+        x
+    }
+
+    // the exponent just above the leading 1
+    fn top(&self) -> i64 {
+        self.exponent as i64 + (self.len as u32 * 64 - limbs::leading_zeros(&self.limbs[..self.len])) as i64
+    }
+
+    fn sum(self, other: Exact) -> Exact {
+        if let Some(nan) = self.nan(&other) {
+            return nan;
+        }
+        match (self.kind, other.kind) {
+            (Kind::Infinity, Kind::Infinity) if self.sign != other.sign => return Exact::invalid(),
+            (Kind::Infinity, _) => return self,
+            (_, Kind::Infinity) => return other,
+            _ => {}
+        }
+        // zeros add as IEEE addition has them: -0 only from two -0s, or toward negative from a -0
+        if self.len == 0 && other.len == 0 {
+            return Exact::zero(self.sign && other.sign, self.sign_down || other.sign_down);
+        }
+        if other.len == 0 {
+            return self;
+        }
+        if self.len == 0 {
+            return other;
+        }
+
+        // both in a window of one more limb than either can have, which the sum's carry fits in. it starts at the
+        // lower last bit unless that leaves the sum too long, in which case it's cut off and the rest jammed
+        let top = self.top().max(other.top());
+        let low = (self.exponent.min(other.exponent) as i64).max(top - MAX_LIMBS as i64 * 64);
+        let place = |x: &Exact| {
+            let mut window = [0u64; MAX_LIMBS + 1];
+            window[..x.len].copy_from_slice(&x.limbs[..x.len]);
+            let offset = x.exponent as i64 - low;
+            if offset >= 0 {
+                limbs::shl_assign(&mut window, offset as u32);
+            } else {
+                window[0] |= limbs::shr_assign(&mut window, (-offset).min(u32::MAX as i64) as u32) as u64;
+            }
+            window
+        };
+        let (mut a, mut b) = (place(&self), place(&other));
+        let sign = if self.sign == other.sign {
+            limbs::add_assign(&mut a, &b);
+            self.sign
+        } else {
+            match limbs::cmp(&a, &b) {
+                core::cmp::Ordering::Equal => return Exact::zero(false, true),
+                core::cmp::Ordering::Greater => {
+                    limbs::sub_assign(&mut a, &b);
+                    self.sign
+                }
+                core::cmp::Ordering::Less => {
+                    limbs::sub_assign(&mut b, &a);
+                    a = b;
+                    other.sign
+                }
+            }
+        };
+        Exact::finite(sign, low as i32, &mut a)
+    }
+
+    fn product(self, other: Exact) -> Exact {
+        let sign = self.sign != other.sign;
+        if let Some(nan) = self.nan(&other) {
+            return nan;
+        }
+        if self.kind == Kind::Infinity || other.kind == Kind::Infinity {
+            let zero = |x: &Exact| x.kind == Kind::Finite && x.len == 0;
+            return if zero(&self) || zero(&other) { Exact::invalid() } else { Exact::special(Kind::Infinity, sign) };
+        }
+        if self.len == 0 || other.len == 0 {
+            return Exact::zero(sign, self.sign_down != other.sign_down);
+        }
+        let n = self.len + other.len;
+        let mut product = [0u64; 2 * MAX_LIMBS];
+        limbs::mul(&self.limbs[..self.len], &other.limbs[..other.len], &mut product[..n]);
+        Exact::finite(sign, self.exponent.saturating_add(other.exponent), &mut product[..n])
+    }
+
+    /// The value rounded once to the nearest Float, ties to even.
+    pub fn round(&self) -> Float {
+        self.round_with_rounding(RoundingMode::TiesToEven)
+    }
+
+    /// The value rounded once to a Float in the given mode. A sum that cancels exactly is -0 rounding toward
+    /// negative and +0 otherwise, as it would be from a single addition.
+    pub fn round_with_rounding(&self, mode: RoundingMode) -> Float {
+        let rounded: F64 = self.round_to(&mut Context::new(mode));
+        Float::from_bits(rounded.to_bits())
+    }
+
+    /// The value rounded once into any format, raising inexact, overflow and underflow as a single operation
+    /// would (the steps before can't overflow or underflow), and invalid if the expression took inf - inf,
+    /// inf * 0 or a signaling nan along the way.
+    pub fn round_to<F: Format>(&self, ctx: &mut Context) -> SoftFloat<F> {
+        match self.kind {
+            Kind::Nan { invalid } => {
+                if invalid {
+                    ctx.flags.raise(Flags::INVALID);
+                }
+                return SoftFloat::nan();
+            }
+            Kind::Infinity => return SoftFloat::infinity(self.sign),
+            Kind::Finite => {}
+        }
+        if self.len == 0 {
+            let down = ctx.rounding == RoundingMode::TowardNegative;
+            return SoftFloat::zero(if down { self.sign_down } else { self.sign });
+        }
+        // the top 128 bits, the rest jammed into the last of them
+        let mut top = self.limbs;
+        let shift = (self.top() - self.exponent as i64).saturating_sub(128).max(0) as u32;
+        let sticky = limbs::shr_assign(&mut top[..self.len], shift);
+        let significand = (top[0] as u128 | (top[1] as u128) << 64) | sticky as u128;
+        // far past every format's range either way, and well clear of overflowing round_pack's arithmetic
+        let exponent = (self.exponent as i64 + shift as i64).clamp(-(1 << 20), 1 << 20) as i32;
+        Wide::new(self.sign, exponent, significand).round(ctx)
+    }
+}
+
+impl<F: Format> From<SoftFloat<F>> for Exact {
+    fn from(x: SoftFloat<F>) -> Exact {
+        if x.is_nan() {
+            return Exact::special(Kind::Nan { invalid: x.is_signaling_nan() }, x.get_sign());
+        }
+        if x.is_infinity() {
+            return Exact::special(Kind::Infinity, x.get_sign());
+        }
+        if x.is_zero() {
+            return Exact::zero(x.get_sign(), x.get_sign());
+        }
+        let u = x.unpack();
+        Exact::finite(u.sign, u.exponent, &mut [u.significand as u64, (u.significand >> 64) as u64])
+    }
+}
+
+impl From<&Float> for Exact {
+    fn from(x: &Float) -> Exact {
+        Exact::from(F64::from_bits(x.to_bits()))
+    }
+}
+
+impl From<Float> for Exact {
+    fn from(x: Float) -> Exact {
+        Exact::from(&x)
+    }
+}
+
+impl From<i64> for Exact {
+    fn from(value: i64) -> Exact {
+        Exact::finite(value < 0, 0, &mut [value.unsigned_abs()])
+    }
+}
+
+impl ops::Neg for Exact {
+    type Output = Exact;
+    fn neg(self) -> Exact {
+        Exact { sign: !self.sign, sign_down: !self.sign_down, ..self }
+    }
+}
+
+impl<T: Into<Exact>> ops::Add<T> for Exact {
+    type Output = Exact;
+    fn add(self, other: T) -> Exact {
+        self.sum(other.into())
+    }
+}
+
+impl<T: Into<Exact>> ops::Sub<T> for Exact {
+    type Output = Exact;
+    fn sub(self, other: T) -> Exact {
+        self.sum(-other.into())
+    }
+}
+
+impl<T: Into<Exact>> ops::Mul<T> for Exact {
+    type Output = Exact;
+    fn mul(self, other: T) -> Exact {
+        self.product(other.into())
+    }
+}
+
+impl<T: Into<Exact>> ops::AddAssign<T> for Exact {
+    fn add_assign(&mut self, other: T) {
+        *self = self.sum(other.into());
+    }
+}
+
+impl<T: Into<Exact>> ops::SubAssign<T> for Exact {
+    fn sub_assign(&mut self, other: T) {
+        *self = self.sum(-other.into());
+    }
+}
+
+impl<T: Into<Exact>> ops::MulAssign<T> for Exact {
+    fn mul_assign(&mut self, other: T) {
+        *self = self.product(other.into());
+    }
+}
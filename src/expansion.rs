@@ -0,0 +1,94 @@
+// shewchuk's floating-point expansions: a number held exactly as a sum of Floats, smallest magnitude first, no two
+// of which overlap (the lowest set bit of each component is above the highest of the one before). sums and
+// products of them are exact, built from two_sum and two_prod, and the largest component is the value to within
+// an ulp. zero components are left out, except that zero itself is the one-component expansion [+0].
+// outputs go into a slice the caller provides, so this needs no allocator; each function says how much room it
+// needs, and returns the number of components written.
+
+use crate::Float;
+
+// magnitude order on the bits, fine for anything but nans
+pub(crate) fn magnitude(f: &Float) -> u64 {
+    f.to_bits() & !(1 << 63)
+}
+
+// adds hh to the expansion being built in h, unless it's zero
+fn push(h: &mut [Float], length: &mut usize, hh: Float) {
+    if !hh.is_zero() {
+        h[*length] = hh;
+        *length += 1;
+    }
+}
+
+// the last component, which zero gets even if nothing else went in
+fn finish(h: &mut [Float], mut length: usize, q: Float) -> usize {
+    if !q.is_zero() || length == 0 {
+        h[length] = q;
+        length += 1;
+    }
+    length
+}
+
+/// The expansion e plus the Float b, exactly, into h, which needs room for e.len() + 1 components (and panics
+/// without it).
+pub fn grow_expansion(e: &[Float], b: &Float, h: &mut [Float]) -> usize {
+    assert!(h.len() > e.len(), "grow_expansion needs room for {} components", e.len() + 1);
+    let (mut q, mut length) = (b.copy(), 0);
+    for component in e {
+        let (sum, hh) = q.two_sum(component);
+        push(h, &mut length, hh);
+        q = sum;
+    }
+    finish(h, length, q)
+}
+
+/// The sum of the expansions e and f, exactly, into h, which needs room for e.len() + f.len() components (and
+/// panics without it). The components of both are merged smallest first and run through two_sum, which is
+/// Shewchuk's fast expansion sum with the exact two_sum at every step.
+pub fn expansion_sum(e: &[Float], f: &[Float], h: &mut [Float]) -> usize {
+    assert!(h.len() >= e.len() + f.len(), "expansion_sum needs room for {} components", e.len() + f.len());
+    let (mut e, mut f) = (e.iter().peekable(), f.iter().peekable());
+    let mut next = || match (e.peek(), f.peek()) {
+        (Some(a), Some(b)) if magnitude(b) < magnitude(a) => f.next(),
+        (Some(_), _) => e.next(),
+        _ => f.next(),
+    };
+    let Some(first) = next() else {
+        return finish(h, 0, Float::new(0.0));
+    };
+    let (mut q, mut length) = (first.copy(), 0);
+    while let Some(component) = next() {
+        let (sum, hh) = q.two_sum(component);
+        push(h, &mut length, hh);
+        q = sum;
+    }
+    finish(h, length, q)
+}
+
+/// The expansion e times the Float b, exactly, into h, which needs room for 2 * e.len() components (and panics
+/// without it). Products whose error falls below the subnormals aren't exact, as with two_prod.
+pub fn scale_expansion(e: &[Float], b: &Float, h: &mut [Float]) -> usize {
+    assert!(h.len() >= 2 * e.len(), "scale_expansion needs room for {} components", 2 * e.len());
+    let mut components = e.iter();
+    let Some(first) = components.next() else {
+        return finish(h, 0, Float::new(0.0));
+    };
+    let (mut q, hh) = first.two_prod(b);
+    let mut length = 0;
+    push(h, &mut length, hh);
+    for component in components {
+        let (product, product_error) = component.two_prod(b);
+        let (sum, hh) = q.two_sum(&product_error);
+        push(h, &mut length, hh);
+        let (sum, hh) = product.two_sum(&sum);
+        push(h, &mut length, hh);
+        q = sum;
+    }
+    finish(h, length, q)
+}
+
+/// The expansion's value rounded, roughly: its components added up smallest first, within an ulp or so of the
+/// exact sum. The largest component alone is already that close for an expansion these functions made.
+pub fn estimate(e: &[Float]) -> Float {
+    e.iter().fold(Float::new(0.0), |sum, component| sum.add(component))
+}
@@ -0,0 +1,633 @@
+use core::fmt;
+
+use crate::format::Format;
+use crate::{Context, Flags, Float, Operation, RoundingMode, SoftFloat};
+
+/// The fields pulled out of one operand before any arithmetic happens.
+#[derive(Debug, Clone, Copy)]
+pub struct OperandFields {
+    pub sign: bool,
+    pub exponent: i16, // unbiased, as returned by get_exponent (so -1023 for subnormals)
+    pub mantissa: u64, // the 52 stored bits
+    pub significand: u64, // mantissa with the implicit leading bit (0 for subnormals)
+}
+
+impl OperandFields {
+    fn of(f: &Float) -> Self {
+        let mut exponent = f.get_exponent();
+        let significand = f.get_full_mantissa(&mut exponent);
+        OperandFields {
+            sign: f.get_sign(),
+            exponent: f.get_exponent(),
+            mantissa: f.get_mantissa(),
+            significand,
+        }
+    }
+}
+
+/// Why an operation finished without going through the rounding stage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpecialCase {
+    Nan, // at least one operand was nan
+    InfinityTimesZero,
+    InfinityMinusInfinity, // an addition of infinities with opposite signs
+    Infinity,
+    Zero,
+    Cancellation, // an addition of x and -x, whose significands cancel exactly
+    Overflow,
+    Underflow,
+}
+
+/// What the rounding stage did with the bits that were shifted out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoundingDecision {
+    Exact, // nothing nonzero was shifted out
+    RoundDown, // truncated
+    RoundUp, // incremented
+}
+
+/// What the rounding stage of a `SoftFloat` operation did, as returned by `add_traced` and the other `_traced`
+/// operations, for auditing where precision went.
+///
+/// The value before rounding is significand * 2^exponent. It's exact, except that bits an operation had to drop
+/// on the way (an alignment shift, a division's remainder) are jammed into the last bit, below the sticky bit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoundingTrace {
+    pub sign: bool,
+    pub exponent: i32,
+    pub significand: u128,
+    pub dropped: u32, // how many low bits of significand rounding removes (more for a subnormal result)
+    pub guard: bool,  // the highest of them
+    pub round: bool,  // the next
+    pub sticky: bool, // whether any below those is set
+    pub decision: RoundingDecision,
+    pub subnormal: bool, // rounded to the precision left below the smallest normal
+    pub overflow: bool,  // too big to round at all, so the result went to infinity or the largest finite value
+}
+
+/// A stage-by-stage record of one multiplication, as produced by `Float::multiply_explained`.
+///
+/// Stages that were never reached (because of a special case) are left at zero.
+#[derive(Debug)]
+pub struct MultiplyExplanation {
+    pub a: OperandFields,
+    pub b: OperandFields,
+    pub sign: bool,
+    pub exponent_sum: i16, // a.exponent + b.exponent, before adjusting for subnormals
+    pub product: u128, // full 106-bit product of the significands
+    pub normalization_shift: i32, // positive is a right shift, negative is a left shift
+    pub normalized_exponent: i16,
+    pub shift: u32, // right shift that brings the product down to 53 bits (more for subnormals)
+    pub guard: bool,
+    pub round: bool,
+    pub sticky: bool,
+    pub mode: RoundingMode,
+    pub rounding: RoundingDecision,
+    pub special: Option<SpecialCase>,
+    pub result: Float,
+}
+
+/// A stage-by-stage record of one addition, as produced by `Float::add_explained`.
+///
+/// The stages work on the operand with the larger magnitude first, so `swapped` says whether that was b.
+/// Stages that were never reached (because of a special case) are left at zero.
+#[derive(Debug)]
+pub struct AddExplanation {
+    pub a: OperandFields,
+    pub b: OperandFields,
+    pub swapped: bool,
+    pub sign: bool, // the larger operand's
+    pub subtraction: bool, // the signs differ, so the significands are subtracted
+    pub exponent_difference: u32,
+    pub larger: u128, // the larger significand, shifted left by 52 like the product in multiply
+    pub aligned: u128, // the smaller one shifted right by exponent_difference, anything shifted out jammed into bit 0
+    pub sum: u128, // larger + aligned, or larger - aligned (106 bits at most)
+    pub normalization_shift: i32, // positive is a right shift, negative is a left shift
+    pub normalized_exponent: i16,
+    pub shift: u32, // right shift that brings the sum down to 53 bits (more for subnormals)
+    pub guard: bool,
+    pub round: bool,
+    pub sticky: bool,
+    pub mode: RoundingMode,
+    pub rounding: RoundingDecision,
+    pub special: Option<SpecialCase>,
+    pub result: Float,
+}
+
+impl Float {
+    /// Multiplies like `multiply`, but also records every intermediate stage.
+    pub fn multiply_explained(&self, other: &Float) -> MultiplyExplanation {
+        self.multiply_explained_with_rounding(other, RoundingMode::TiesToEven)
+    }
+
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn multiply_explained_with_rounding(&self, other: &Float, mode: RoundingMode) -> MultiplyExplanation {
+        let mut e = MultiplyExplanation {
+            a: OperandFields::of(self),
+            b: OperandFields::of(other),
+            sign: self.get_sign() ^ other.get_sign(),
+            exponent_sum: 0,
+            product: 0,
+            normalization_shift: 0,
+            normalized_exponent: 0,
+            shift: 0,
+            guard: false,
+            round: false,
+            sticky: false,
+            mode,
+            rounding: RoundingDecision::Exact,
+            special: None,
+            result: Float::nan(),
+        };
+
+        if let Some(nan) = self.nan_logic(other) {
+            e.special = Some(SpecialCase::Nan);
+            e.result = nan;
+            return e;
+        }
+        if self.is_infinity() || other.is_infinity() {
+            if self.is_zero() || other.is_zero() {
+                e.special = Some(SpecialCase::InfinityTimesZero);
+                e.result = Float::nan();
+            } else {
+                e.special = Some(SpecialCase::Infinity);
+                e.result = Float::infinity(e.sign);
+            }
+            return e;
+        }
+        if self.is_zero() || other.is_zero() {
+            e.special = Some(SpecialCase::Zero);
+            e.result = Float::from_bits((e.sign as u64) << 63);
+            return e;
+        }
+
+        e.exponent_sum = self.get_exponent() + other.get_exponent();
+        let mut exponent = e.exponent_sum;
+        let mut mantissa_full = u128::from(self.get_full_mantissa(&mut exponent)) * u128::from(other.get_full_mantissa(&mut exponent));
+        e.product = mantissa_full;
+
+        // same normalization as multiply: get the leading 1 to bit 105
+        if mantissa_full >> 105 != 0 {
+            e.normalization_shift = 1;
+            exponent += 1;
+            mantissa_full = (mantissa_full >> 1) | (mantissa_full & 1);
+        } else {
+            let shift_amt = mantissa_full.leading_zeros() - (128 - 105);
+            e.normalization_shift = -(shift_amt as i32);
+            mantissa_full <<= shift_amt;
+            exponent -= shift_amt as i16;
+        }
+        e.normalized_exponent = exponent;
+
+        if exponent >= 1024 {
+            e.special = Some(SpecialCase::Overflow);
+            e.result = if mode.overflows_to_infinity(e.sign) {
+                Float::infinity(e.sign)
+            } else {
+                Float::largest_finite(e.sign)
+            };
+            return e;
+        }
+
+        let mut shift = 52;
+        if exponent <= -1023 {
+            if exponent < -1075 {
+                e.special = Some(SpecialCase::Underflow);
+                e.sticky = mantissa_full != 0;
+                let bump = mode.round_up(e.sign, false, false, e.sticky) as u64;
+                e.result = Float::from_bits(((e.sign as u64) << 63) | bump);
+                return e;
+            }
+            shift += (-1023 + 1 - exponent) as u32;
+            exponent = -1023;
+        }
+        e.shift = shift;
+
+        e.guard = (mantissa_full >> (shift - 1)) & 1 == 1;
+        e.round = (mantissa_full >> (shift - 2)) & 1 == 1;
+        e.sticky = mantissa_full & ((1u128 << (shift - 2)) - 1) != 0;
+
+        let truncated = (mantissa_full >> shift) as u64;
+        let rounded = Float::shift_and_round(mantissa_full, shift, e.sign, mode);
+        e.rounding = if rounded != truncated {
+            RoundingDecision::RoundUp
+        } else if e.guard || e.round || e.sticky {
+            RoundingDecision::RoundDown
+        } else {
+            RoundingDecision::Exact
+        };
+
+        e.result = Float::pack_rounded(e.sign, exponent, rounded);
+        e
+    }
+
+    /// Adds like `add`, but also records every intermediate stage.
+    pub fn add_explained(&self, other: &Float) -> AddExplanation {
+        self.add_explained_with_rounding(other, RoundingMode::TiesToEven)
+    }
+
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn add_explained_with_rounding(&self, other: &Float, mode: RoundingMode) -> AddExplanation {
+        let mut e = AddExplanation {
+            a: OperandFields::of(self),
+            b: OperandFields::of(other),
+            swapped: false,
+            sign: false,
+            subtraction: self.get_sign() != other.get_sign(),
+            exponent_difference: 0,
+            larger: 0,
+            aligned: 0,
+            sum: 0,
+            normalization_shift: 0,
+            normalized_exponent: 0,
+            shift: 0,
+            guard: false,
+            round: false,
+            sticky: false,
+            mode,
+            rounding: RoundingDecision::Exact,
+            special: None,
+            result: Float::nan(),
+        };
+
+        if let Some(nan) = self.nan_logic(other) {
+            e.special = Some(SpecialCase::Nan);
+            e.result = nan;
+            return e;
+        }
+        if self.is_infinity() || other.is_infinity() {
+            if self.is_infinity() && other.is_infinity() && e.subtraction {
+                e.special = Some(SpecialCase::InfinityMinusInfinity);
+                e.result = Float::nan();
+            } else {
+                e.special = Some(SpecialCase::Infinity);
+                e.result = if self.is_infinity() { self.copy() } else { other.copy() };
+            }
+            return e;
+        }
+        // an exact zero sum is +0, except in round-down where it is -0
+        let zero_sign = if e.subtraction { mode == RoundingMode::TowardNegative } else { self.get_sign() };
+        if self.is_zero() || other.is_zero() {
+            e.special = Some(SpecialCase::Zero);
+            e.result = match (self.is_zero(), other.is_zero()) {
+                (true, true) => Float::from_bits((zero_sign as u64) << 63),
+                (true, false) => other.copy(),
+                _ => self.copy(),
+            };
+            return e;
+        }
+
+        // same ordering as add: by magnitude, so the difference can't go negative
+        e.swapped = (self.to_bits() & !(1 << 63)) < (other.to_bits() & !(1 << 63));
+        let (a, b) = if e.swapped { (other, self) } else { (self, other) };
+        e.sign = a.get_sign();
+        let mut exp_a = a.get_exponent();
+        let mut exp_b = b.get_exponent();
+        e.larger = u128::from(a.get_full_mantissa(&mut exp_a)) << 52;
+        let mantissa_b = u128::from(b.get_full_mantissa(&mut exp_b)) << 52;
+        e.exponent_difference = (exp_a - exp_b) as u32;
+
+        e.aligned = if e.exponent_difference >= 105 {
+            1
+        } else {
+            let shifted_out = mantissa_b & ((1 << e.exponent_difference) - 1);
+            (mantissa_b >> e.exponent_difference) | (shifted_out != 0) as u128
+        };
+
+        let mut mantissa_full = if e.subtraction { e.larger - e.aligned } else { e.larger + e.aligned };
+        e.sum = mantissa_full;
+        if mantissa_full == 0 {
+            e.special = Some(SpecialCase::Cancellation);
+            e.result = Float::from_bits((zero_sign as u64) << 63);
+            return e;
+        }
+
+        let mut exponent = exp_a;
+        if mantissa_full >> 105 != 0 {
+            e.normalization_shift = 1;
+            exponent += 1;
+            mantissa_full = (mantissa_full >> 1) | (mantissa_full & 1);
+        } else {
+            let shift_amt = mantissa_full.leading_zeros() - (128 - 105);
+            e.normalization_shift = -(shift_amt as i32);
+            mantissa_full <<= shift_amt;
+            exponent -= shift_amt as i16;
+        }
+        e.normalized_exponent = exponent;
+
+        if exponent >= 1024 {
+            e.special = Some(SpecialCase::Overflow);
+            e.result = if mode.overflows_to_infinity(e.sign) {
+                Float::infinity(e.sign)
+            } else {
+                Float::largest_finite(e.sign)
+            };
+            return e;
+        }
+
+        // a sum is never below the smallest subnormal, so unlike multiply there's no underflow to zero here
+        let mut shift = 52;
+        if exponent <= -1023 {
+            shift += (-1023 + 1 - exponent) as u32;
+            exponent = -1023;
+        }
+        e.shift = shift;
+
+        e.guard = (mantissa_full >> (shift - 1)) & 1 == 1;
+        e.round = (mantissa_full >> (shift - 2)) & 1 == 1;
+        e.sticky = mantissa_full & ((1u128 << (shift - 2)) - 1) != 0;
+
+        let truncated = (mantissa_full >> shift) as u64;
+        let rounded = Float::shift_and_round(mantissa_full, shift, e.sign, mode);
+        e.rounding = if rounded != truncated {
+            RoundingDecision::RoundUp
+        } else if e.guard || e.round || e.sticky {
+            RoundingDecision::RoundDown
+        } else {
+            RoundingDecision::Exact
+        };
+
+        e.result = Float::pack_rounded(e.sign, exponent, rounded);
+        e
+    }
+}
+
+fn write_operand(f: &mut fmt::Formatter<'_>, name: &str, op: &OperandFields) -> fmt::Result {
+    writeln!(
+        f,
+        "   {}: sign {}, exponent {:5}, significand {}.{:052b}",
+        name, op.sign as u8, op.exponent, op.significand >> 52, op.mantissa
+    )
+}
+
+impl fmt::Display for RoundingTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.sign { "-" } else { "" };
+        write!(f, "{sign}{:b} * 2^{}, ", self.significand, self.exponent)?;
+        if self.overflow {
+            return write!(f, "overflow, {:?}", self.decision);
+        }
+        write!(
+            f,
+            "dropping {} bits{}: guard {}, round {}, sticky {}, {:?}",
+            self.dropped,
+            if self.subnormal { " (subnormal)" } else { "" },
+            self.guard as u8,
+            self.round as u8,
+            self.sticky as u8,
+            self.decision
+        )
+    }
+}
+
+impl fmt::Display for AddExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "1. extract fields")?;
+        write_operand(f, "a", &self.a)?;
+        write_operand(f, "b", &self.b)?;
+
+        match self.special {
+            Some(SpecialCase::Nan) => writeln!(f, "   an operand is nan, so the result is a quiet nan")?,
+            Some(SpecialCase::InfinityMinusInfinity) => {
+                writeln!(f, "   infinity - infinity is invalid, so the result is nan")?
+            }
+            Some(SpecialCase::Infinity) => writeln!(f, "   an operand is infinite, so the result is that infinity")?,
+            Some(SpecialCase::Zero) => writeln!(f, "   an operand is zero, so the result is the other one")?,
+            _ => {
+                let (larger, smaller) = if self.swapped { ("b", "a") } else { ("a", "b") };
+                writeln!(
+                    f,
+                    "2. align: {smaller} is smaller, shift its significand right by the exponent difference, {}",
+                    self.exponent_difference
+                )?;
+                writeln!(f, "   {larger}  {:0106b}", self.larger)?;
+                writeln!(f, "   {smaller}  {:0106b}", self.aligned)?;
+                if self.aligned & 1 == 1 && self.exponent_difference > 52 {
+                    writeln!(f, "   (bits shifted out are kept as a sticky 1 at the bottom)")?;
+                }
+                if self.subtraction {
+                    writeln!(f, "3. subtract: the signs differ, so {larger} - {smaller}, with {larger}'s sign {}", self.sign as u8)?;
+                } else {
+                    writeln!(f, "3. add: the signs are the same, {}", self.sign as u8)?;
+                }
+                writeln!(f, "   {:0106b}", self.sum)?;
+
+                if self.special == Some(SpecialCase::Cancellation) {
+                    writeln!(f, "   the significands cancel exactly, so the result is zero ({:?})", self.mode)?;
+                } else {
+                    if self.normalization_shift > 0 {
+                        writeln!(f, "4. normalize: the sum carried into bit 106, shift right by 1")?;
+                    } else {
+                        writeln!(f, "4. normalize: shift left by {}", -self.normalization_shift)?;
+                    }
+                    writeln!(f, "   exponent is now {}", self.normalized_exponent)?;
+
+                    if self.special == Some(SpecialCase::Overflow) {
+                        writeln!(f, "   exponent >= 1024, overflow ({:?})", self.mode)?;
+                    } else {
+                        if self.shift > 52 {
+                            writeln!(f, "5. round: the result is subnormal, shift right by {} instead of 52", self.shift)?;
+                        } else {
+                            writeln!(f, "5. round: shift right by 52 to keep 53 bits")?;
+                        }
+                        writeln!(
+                            f,
+                            "   guard {}, round {}, sticky {}",
+                            self.guard as u8, self.round as u8, self.sticky as u8
+                        )?;
+                        let decision = match self.rounding {
+                            RoundingDecision::Exact => "exact, nothing to round",
+                            RoundingDecision::RoundDown => "round down (truncate)",
+                            RoundingDecision::RoundUp => "round up (increment)",
+                        };
+                        writeln!(f, "   rounding ({:?}): {}", self.mode, decision)?;
+                    }
+                }
+            }
+        }
+
+        writeln!(
+            f,
+            "6. result: sign {}, exponent {}, mantissa {:052b} = {:e}",
+            self.result.get_sign() as u8,
+            self.result.get_exponent(),
+            self.result.get_mantissa(),
+            self.result.to_f64()
+        )
+    }
+}
+
+impl fmt::Display for MultiplyExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "1. extract fields")?;
+        write_operand(f, "a", &self.a)?;
+        write_operand(f, "b", &self.b)?;
+        writeln!(f, "2. sign: {} xor {} = {}", self.a.sign as u8, self.b.sign as u8, self.sign as u8)?;
+
+        match self.special {
+            Some(SpecialCase::Nan) => writeln!(f, "   an operand is nan, so the result is a quiet nan")?,
+            Some(SpecialCase::InfinityTimesZero) => writeln!(f, "   infinity * 0 is invalid, so the result is nan")?,
+            Some(SpecialCase::Infinity) => writeln!(f, "   an operand is infinite, so the result is infinite")?,
+            Some(SpecialCase::Zero) => writeln!(f, "   an operand is zero, so the result is zero")?,
+            _ => {
+                writeln!(f, "3. exponent sum: {} + {} = {}", self.a.exponent, self.b.exponent, self.exponent_sum)?;
+                writeln!(f, "4. product of significands (106 bits):")?;
+                writeln!(f, "   {:0106b}", self.product)?;
+                if self.normalization_shift > 0 {
+                    writeln!(f, "5. normalize: product overflowed into bit 106, shift right by 1")?;
+                } else {
+                    writeln!(f, "5. normalize: shift left by {}", -self.normalization_shift)?;
+                }
+                writeln!(f, "   exponent is now {}", self.normalized_exponent)?;
+
+                match self.special {
+                    Some(SpecialCase::Overflow) => writeln!(f, "   exponent >= 1024, overflow ({:?})", self.mode)?,
+                    Some(SpecialCase::Underflow) => writeln!(f, "   exponent < -1075, underflow ({:?})", self.mode)?,
+                    _ => {
+                        if self.shift > 52 {
+                            writeln!(f, "6. subnormal result: shift right by {} instead of 52", self.shift)?;
+                        } else {
+                            writeln!(f, "6. shift right by 52 to keep 53 bits")?;
+                        }
+                        writeln!(
+                            f,
+                            "   guard {}, round {}, sticky {}",
+                            self.guard as u8, self.round as u8, self.sticky as u8
+                        )?;
+                        let decision = match self.rounding {
+                            RoundingDecision::Exact => "exact, nothing to round",
+                            RoundingDecision::RoundDown => "round down (truncate)",
+                            RoundingDecision::RoundUp => "round up (increment)",
+                        };
+                        writeln!(f, "7. rounding ({:?}): {}", self.mode, decision)?;
+                    }
+                }
+            }
+        }
+
+        writeln!(
+            f,
+            "8. result: sign {}, exponent {}, mantissa {:052b} = {:e}",
+            self.result.get_sign() as u8,
+            self.result.get_exponent(),
+            self.result.get_mantissa(),
+            self.result.to_f64()
+        )
+    }
+}
+
+/// The same result rounded once, straight into F, and twice, by way of the wider format W,
+/// as produced by `SoftFloat::double_rounding` and `SoftFloat::convert_double_rounding`.
+#[derive(Debug, Clone, Copy)]
+pub struct DoubleRounding<F: Format, W: Format> {
+    pub mode: RoundingMode,
+    pub once: SoftFloat<F>,
+    pub once_flags: Flags,
+    pub intermediate: SoftFloat<W>, // the first of the two roundings
+    pub twice: SoftFloat<F>,
+    pub twice_flags: Flags, // from both roundings
+}
+
+impl<F: Format, W: Format> DoubleRounding<F, W> {
+    /// Whether the two results differ. Any two nans count as the same: widening quiets signaling nans, which can
+    /// change which operand's payload comes through, but that's not a rounding difference.
+    pub fn differs(&self) -> bool {
+        self.once.to_bits() != self.twice.to_bits() && !(self.once.is_nan() && self.twice.is_nan())
+    }
+
+    /// How many ulps the second rounding moved the result (positive is up). 0 if either is nan.
+    pub fn ulps(&self) -> i128 {
+        if self.once.is_nan() || self.twice.is_nan() {
+            return 0;
+        }
+        // same ordering key as compare: the magnitude bits, negated for negative values
+        let key = |x: &SoftFloat<F>| -> i128 {
+            let magnitude = (x.raw() & !(1 << (F::EXPONENT_BITS + F::MANTISSA_BITS))) as i128;
+            if x.get_sign() {
+                -magnitude
+            } else {
+                magnitude
+            }
+        };
+        key(&self.twice) - key(&self.once)
+    }
+}
+
+impl<F: Format> SoftFloat<F> {
+    /// Runs `op` once in F, and once in W with the operands widened and the result narrowed back, to see whether
+    /// rounding twice changes anything. W should be at least as wide as F, so widening is exact.
+    /// Only the attributes of `ctx` are used; its flags are left alone.
+    pub fn double_rounding<W: Format>(&self, op: Operation, other: &Self, ctx: &Context) -> DoubleRounding<F, W> {
+        let mut direct = Context { flags: Flags::empty(), ..*ctx };
+        let once = op.apply(self, other, &mut direct);
+        let mut wide = Context { flags: Flags::empty(), ..*ctx };
+        let (a, b) = (self.convert::<W>(&mut wide), other.convert::<W>(&mut wide));
+        let intermediate = op.apply(&a, &b, &mut wide);
+        let twice = intermediate.convert(&mut wide);
+        DoubleRounding {
+            mode: ctx.rounding,
+            once,
+            once_flags: direct.flags,
+            intermediate,
+            twice,
+            twice_flags: wide.flags,
+        }
+    }
+
+    /// Converts to G directly, and by way of W, which should sit between the two (binary64 to binary16
+    /// through binary32, say).
+    pub fn convert_double_rounding<W: Format, G: Format>(&self, ctx: &Context) -> DoubleRounding<G, W> {
+        let mut direct = Context { flags: Flags::empty(), ..*ctx };
+        let once = self.convert(&mut direct);
+        let mut wide = Context { flags: Flags::empty(), ..*ctx };
+        let intermediate = self.convert::<W>(&mut wide);
+        let twice = intermediate.convert(&mut wide);
+        DoubleRounding {
+            mode: ctx.rounding,
+            once,
+            once_flags: direct.flags,
+            intermediate,
+            twice,
+            twice_flags: wide.flags,
+        }
+    }
+}
+
+fn write_flags(f: &mut fmt::Formatter<'_>, flags: Flags) -> fmt::Result {
+    let names = [
+        (Flags::INVALID, "invalid"),
+        (Flags::DIVIDE_BY_ZERO, "divide by zero"),
+        (Flags::OVERFLOW, "overflow"),
+        (Flags::UNDERFLOW, "underflow"),
+        (Flags::INEXACT, "inexact"),
+        (Flags::INPUT_DENORMAL, "input denormal"),
+        (Flags::OUTPUT_DENORMAL, "output denormal"),
+    ];
+    if flags.is_empty() {
+        return write!(f, "no flags");
+    }
+    let mut first = true;
+    for (flag, name) in names {
+        if flags.contains(flag) {
+            write!(f, "{}{}", if first { "" } else { ", " }, name)?;
+            first = false;
+        }
+    }
+    Ok(())
+}
+
+impl<F: Format, W: Format> fmt::Display for DoubleRounding<F, W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "rounded once ({:?}): {:?}, ", self.mode, self.once)?;
+        write_flags(f, self.once_flags)?;
+        write!(f, "\nthrough {}: {:?}, then {:?}, ", W::NAME, self.intermediate, self.twice)?;
+        write_flags(f, self.twice_flags)?;
+        if self.differs() {
+            writeln!(f, "\ndouble rounding changed the result by {} ulp", self.ulps())
+        } else if self.once_flags != self.twice_flags {
+            writeln!(f, "\nsame result, but different flags")
+        } else {
+            writeln!(f, "\nsame result")
+        }
+    }
+}
@@ -0,0 +1,238 @@
+// c entry points with the same names, types and global state as berkeley softfloat 3, so emulators written
+// against softfloat.h can link against this crate instead. build the shared library
+// with `cargo build --release -p floatfs-bindings --features ffi`.
+//
+// softfloat keeps the rounding mode, tininess mode and flags in (optionally thread-local) globals. rust can't
+// export a thread-local symbol, so these are process-wide atomics; they have the same layout as the
+// uint_fast8_t globals softfloat.h declares, so c code reads and writes them directly.
+
+#![allow(non_camel_case_types, non_upper_case_globals)]
+
+use core::cmp;
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use crate::format::{Binary32, Format};
+use crate::{Context, Flags, Float, RoundingMode, SoftFloat, Tininess, F32, F64};
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct float32_t {
+    pub v: u32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct float64_t {
+    pub v: u64,
+}
+
+// values of softfloat_roundingMode
+pub const softfloat_round_near_even: u8 = 0;
+pub const softfloat_round_minMag: u8 = 1;
+pub const softfloat_round_min: u8 = 2;
+pub const softfloat_round_max: u8 = 3;
+pub const softfloat_round_near_maxMag: u8 = 4;
+pub const softfloat_round_odd: u8 = 6;
+
+// values of softfloat_detectTininess
+pub const softfloat_tininess_beforeRounding: u8 = 0;
+pub const softfloat_tininess_afterRounding: u8 = 1;
+
+#[no_mangle]
+pub static softfloat_roundingMode: AtomicU8 = AtomicU8::new(softfloat_round_near_even);
+#[no_mangle]
+pub static softfloat_detectTininess: AtomicU8 = AtomicU8::new(softfloat_tininess_afterRounding);
+#[no_mangle]
+pub static softfloat_exceptionFlags: AtomicU8 = AtomicU8::new(0); // same bit values as Flags
+
+// the mode softfloat_roundingMode selects, or None for round_odd (or garbage), which has no RoundingMode
+fn rounding_mode() -> Option<RoundingMode> {
+    match softfloat_roundingMode.load(Ordering::Relaxed) {
+        softfloat_round_near_even => Some(RoundingMode::TiesToEven),
+        softfloat_round_minMag => Some(RoundingMode::TowardZero),
+        softfloat_round_min => Some(RoundingMode::TowardNegative),
+        softfloat_round_max => Some(RoundingMode::TowardPositive),
+        softfloat_round_near_maxMag => Some(RoundingMode::TiesToAway),
+        _ => None,
+    }
+}
+
+// builds a context from the globals, runs op in it, then ors the raised flags back into softfloat_exceptionFlags
+fn with_context<T>(rounding: RoundingMode, op: impl FnOnce(&mut Context) -> T) -> T {
+    let tininess = match softfloat_detectTininess.load(Ordering::Relaxed) {
+        softfloat_tininess_beforeRounding => Tininess::BeforeRounding,
+        _ => Tininess::AfterRounding,
+    };
+    let mut ctx = Context {
+        rounding,
+        tininess,
+        ..Context::default()
+    };
+    let result = op(&mut ctx);
+    softfloat_exceptionFlags.fetch_or(ctx.flags.0, Ordering::Relaxed);
+    result
+}
+
+// with_context for an operation that can round. under a mode we can't round in, it raises invalid and returns nan
+// rather than quietly rounding some other way
+fn rounded<T>(nan: T, op: impl FnOnce(&mut Context) -> T) -> T {
+    match rounding_mode() {
+        Some(rounding) => with_context(rounding, op),
+        None => {
+            softfloat_raiseFlags(Flags::INVALID.0);
+            nan
+        }
+    }
+}
+
+// with_context for an exact operation, which gives the same result in every mode, round_odd included
+fn exact<T>(op: impl FnOnce(&mut Context) -> T) -> T {
+    with_context(rounding_mode().unwrap_or(RoundingMode::TiesToEven), op)
+}
+
+#[no_mangle]
+pub extern "C" fn softfloat_raiseFlags(flags: u8) {
+    softfloat_exceptionFlags.fetch_or(flags, Ordering::Relaxed);
+}
+
+fn f64_op(a: float64_t, b: float64_t, op: fn(&mut Context, &Float, &Float) -> Float) -> float64_t {
+    let result = rounded(Float::nan(), |ctx| op(ctx, &Float::from_bits(a.v), &Float::from_bits(b.v)));
+    float64_t { v: result.to_bits() }
+}
+
+#[no_mangle]
+pub extern "C" fn f64_mul(a: float64_t, b: float64_t) -> float64_t {
+    f64_op(a, b, Context::multiply)
+}
+
+#[no_mangle]
+pub extern "C" fn f64_add(a: float64_t, b: float64_t) -> float64_t {
+    f64_op(a, b, Context::add)
+}
+
+#[no_mangle]
+pub extern "C" fn f64_sub(a: float64_t, b: float64_t) -> float64_t {
+    f64_op(a, b, Context::subtract)
+}
+
+#[no_mangle]
+pub extern "C" fn f64_div(a: float64_t, b: float64_t) -> float64_t {
+    f64_op(a, b, Context::divide)
+}
+
+// the binary32 operations, on the generic soft-float path
+fn f32_op(a: float32_t, b: float32_t, op: fn(&F32, &F32, &mut Context) -> F32) -> float32_t {
+    let result = rounded(F32::nan(), |ctx| op(&F32::from_bits(a.v), &F32::from_bits(b.v), ctx));
+    float32_t { v: result.to_bits() }
+}
+
+#[no_mangle]
+pub extern "C" fn f32_add(a: float32_t, b: float32_t) -> float32_t {
+    f32_op(a, b, F32::add)
+}
+
+#[no_mangle]
+pub extern "C" fn f32_sub(a: float32_t, b: float32_t) -> float32_t {
+    f32_op(a, b, F32::subtract)
+}
+
+#[no_mangle]
+pub extern "C" fn f32_mul(a: float32_t, b: float32_t) -> float32_t {
+    f32_op(a, b, F32::multiply)
+}
+
+#[no_mangle]
+pub extern "C" fn f32_div(a: float32_t, b: float32_t) -> float32_t {
+    f32_op(a, b, F32::divide)
+}
+
+#[no_mangle]
+pub extern "C" fn f32_to_f64(a: float32_t) -> float64_t {
+    let result = exact(|ctx| ctx.f32_to_f64(a.v));
+    float64_t { v: result.to_bits() }
+}
+
+#[no_mangle]
+pub extern "C" fn f64_to_f32(a: float64_t) -> float32_t {
+    let result = rounded(F32::nan(), |ctx| F64::from_bits(a.v).convert::<Binary32>(ctx));
+    float32_t { v: result.to_bits() }
+}
+
+// softfloat's comparisons are false when unordered. eq and the _quiet ones raise invalid only for a signaling nan,
+// lt, le and eq_signaling for any nan
+fn compare<F: Format>(a: SoftFloat<F>, b: SoftFloat<F>, signaling: bool, holds: fn(cmp::Ordering) -> bool) -> bool {
+    let invalid = if signaling {
+        a.is_nan() || b.is_nan()
+    } else {
+        a.is_signaling_nan() || b.is_signaling_nan()
+    };
+    if invalid {
+        softfloat_raiseFlags(Flags::INVALID.0);
+    }
+    a.compare(&b).is_some_and(holds)
+}
+
+#[no_mangle]
+pub extern "C" fn f64_eq(a: float64_t, b: float64_t) -> bool {
+    compare(F64::from_bits(a.v), F64::from_bits(b.v), false, cmp::Ordering::is_eq)
+}
+
+#[no_mangle]
+pub extern "C" fn f64_le(a: float64_t, b: float64_t) -> bool {
+    compare(F64::from_bits(a.v), F64::from_bits(b.v), true, cmp::Ordering::is_le)
+}
+
+#[no_mangle]
+pub extern "C" fn f64_lt(a: float64_t, b: float64_t) -> bool {
+    compare(F64::from_bits(a.v), F64::from_bits(b.v), true, cmp::Ordering::is_lt)
+}
+
+#[no_mangle]
+pub extern "C" fn f64_eq_signaling(a: float64_t, b: float64_t) -> bool {
+    compare(F64::from_bits(a.v), F64::from_bits(b.v), true, cmp::Ordering::is_eq)
+}
+
+#[no_mangle]
+pub extern "C" fn f64_le_quiet(a: float64_t, b: float64_t) -> bool {
+    compare(F64::from_bits(a.v), F64::from_bits(b.v), false, cmp::Ordering::is_le)
+}
+
+#[no_mangle]
+pub extern "C" fn f64_lt_quiet(a: float64_t, b: float64_t) -> bool {
+    compare(F64::from_bits(a.v), F64::from_bits(b.v), false, cmp::Ordering::is_lt)
+}
+
+#[no_mangle]
+pub extern "C" fn f32_eq(a: float32_t, b: float32_t) -> bool {
+    compare(F32::from_bits(a.v), F32::from_bits(b.v), false, cmp::Ordering::is_eq)
+}
+
+#[no_mangle]
+pub extern "C" fn f32_le(a: float32_t, b: float32_t) -> bool {
+    compare(F32::from_bits(a.v), F32::from_bits(b.v), true, cmp::Ordering::is_le)
+}
+
+#[no_mangle]
+pub extern "C" fn f32_lt(a: float32_t, b: float32_t) -> bool {
+    compare(F32::from_bits(a.v), F32::from_bits(b.v), true, cmp::Ordering::is_lt)
+}
+
+#[no_mangle]
+pub extern "C" fn f32_eq_signaling(a: float32_t, b: float32_t) -> bool {
+    compare(F32::from_bits(a.v), F32::from_bits(b.v), true, cmp::Ordering::is_eq)
+}
+
+#[no_mangle]
+pub extern "C" fn f32_le_quiet(a: float32_t, b: float32_t) -> bool {
+    compare(F32::from_bits(a.v), F32::from_bits(b.v), false, cmp::Ordering::is_le)
+}
+
+#[no_mangle]
+pub extern "C" fn f32_lt_quiet(a: float32_t, b: float32_t) -> bool {
+    compare(F32::from_bits(a.v), F32::from_bits(b.v), false, cmp::Ordering::is_lt)
+}
+
+#[no_mangle]
+pub extern "C" fn f64_isSignalingNaN(a: float64_t) -> bool {
+    Float::from_bits(a.v).is_signaling_nan()
+}
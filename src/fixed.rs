@@ -0,0 +1,149 @@
+// Q format fixed point: a Qm.n number is an integer read as integer / 2^n, with m bits above the binary point. so
+// Q15 (Q0.15) is an i16 holding [-1, 1) in steps of 2^-15, and UQ8.8 a u16 holding [0, 256). raw values are passed
+// around as i128, which holds every format up to 64 bits signed or unsigned; cast to the storage type at the edge.
+//
+//   let q15 = QFormat::signed(0, 15);
+//   let raw = F32::from_bits(0.3f32.to_bits()).to_fixed(q15, RoundingMode::TiesToEven, Overflow::Saturate, &mut ctx);
+//   let back: F32 = SoftFloat::from_fixed(raw, q15, &mut ctx);
+//
+// going to fixed point rounds in the mode given (DSP code often wants a different one from the float rounding) and
+// raises inexact like the integer conversions. a value out of range raises invalid and saturates or wraps. coming
+// back rounds in ctx.rounding, which only matters when the fixed format has more bits than the float's significand.
+
+use crate::soft::shift_round;
+use crate::{Context, Flags, Format, RoundingMode, SoftFloat};
+
+/// A fixed-point format: `integer_bits` above the binary point and `fraction_bits` below it, plus a sign bit if
+/// it's signed (two's complement), for at most 64 bits in all.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QFormat {
+    integer_bits: u32,
+    fraction_bits: u32,
+    signed: bool,
+}
+
+/// What a conversion to fixed point does with a value out of range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Overflow {
+    #[default]
+    Saturate, // clamp to the largest or smallest value, as DSP saturating arithmetic does
+    Wrap, // keep the low bits, as an integer cast does
+}
+
+impl QFormat {
+    /// Qm.n: a sign bit, m integer bits and n fraction bits. Q15 is `signed(0, 15)` and Q16.16 `signed(15, 16)`.
+    /// Panics (at compile time in a const) if that's more than 64 bits.
+    pub const fn signed(integer_bits: u32, fraction_bits: u32) -> QFormat {
+        assert!(integer_bits <= 63 && fraction_bits <= 63 - integer_bits, "a QFormat is at most 64 bits");
+        QFormat { integer_bits, fraction_bits, signed: true }
+    }
+
+    /// UQm.n: m integer bits and n fraction bits, no sign. Panics if that's no bits or more than 64.
+    pub const fn unsigned(integer_bits: u32, fraction_bits: u32) -> QFormat {
+        assert!(integer_bits <= 64 && fraction_bits <= 64 - integer_bits, "a QFormat is at most 64 bits");
+        assert!(integer_bits + fraction_bits > 0, "a QFormat needs at least one bit");
+        QFormat { integer_bits, fraction_bits, signed: false }
+    }
+
+    pub fn integer_bits(&self) -> u32 {
+        self.integer_bits
+    }
+
+    pub fn fraction_bits(&self) -> u32 {
+        self.fraction_bits
+    }
+
+    pub fn is_signed(&self) -> bool {
+        self.signed
+    }
+
+    /// The storage width, sign bit included.
+    pub fn bits(&self) -> u32 {
+        self.integer_bits + self.fraction_bits + self.signed as u32
+    }
+
+    /// The smallest raw value: -2^(bits - 1), or 0 unsigned.
+    pub fn min_raw(&self) -> i128 {
+        if self.signed {
+            -(1 << (self.bits() - 1))
+        } else {
+            0
+        }
+    }
+
+    /// The largest raw value: 2^(bits - 1) - 1, or 2^bits - 1 unsigned.
+    pub fn max_raw(&self) -> i128 {
+        (1 << (self.bits() - self.signed as u32)) - 1
+    }
+
+    // the raw value with the same low bits that's in range, as a two's complement cast gives
+    fn wrap(&self, raw: i128) -> i128 {
+        let bits = self.bits();
+        let low = raw & ((1 << bits) - 1);
+        if self.signed && low >> (bits - 1) == 1 {
+            low - (1 << bits)
+        } else {
+            low
+        }
+    }
+}
+
+impl<F: Format> SoftFloat<F> {
+    /// The raw fixed-point value nearest self in the given mode, that is self * 2^n rounded to an integer, raising
+    /// inexact if it isn't exact. Out of range raises invalid (and not inexact) and gives the nearest limit or
+    /// the wrapped low bits. A nan gives 0 and an infinity the limit of its sign, both raising invalid, whichever
+    /// the overflow mode.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn to_fixed(&self, format: QFormat, mode: RoundingMode, overflow: Overflow, ctx: &mut Context) -> i128 {
+        let x = self.flushed(ctx);
+        let limit = |sign: bool| if sign { format.min_raw() } else { format.max_raw() };
+        if x.is_nan() || x.is_infinity() {
+            ctx.flags.raise(Flags::INVALID);
+            return if x.is_nan() { 0 } else { limit(x.get_sign()) };
+        }
+        if x.is_zero() {
+            return 0;
+        }
+        let u = x.unpack();
+        let exponent = u.exponent + format.fraction_bits as i32;
+        let (magnitude, inexact) = if exponent >= 64 {
+            // a multiple of 2^64: out of every range, and 2^64 has the same (zero) low bits for wrapping
+            (1 << 64, false)
+        } else if exponent >= 0 {
+            (u.significand << exponent, false)
+        } else {
+            shift_round(u.significand, exponent.unsigned_abs(), u.sign, mode)
+        };
+        let bound = if u.sign { format.min_raw().unsigned_abs() } else { format.max_raw() as u128 };
+        if magnitude > bound {
+            ctx.flags.raise(Flags::INVALID);
+            return match overflow {
+                Overflow::Saturate => limit(u.sign),
+                Overflow::Wrap => {
+                    let low = (magnitude & u64::MAX as u128) as i128;
+                    format.wrap(if u.sign { -low } else { low })
+                }
+            };
+        }
+        if inexact {
+            ctx.flags.raise(Flags::INEXACT);
+        }
+        if u.sign {
+            -(magnitude as i128)
+        } else {
+            magnitude as i128
+        }
+    }
+
+    /// The value of a raw fixed-point number, raw / 2^n, rounded in ctx.rounding if the format has more bits than
+    /// the significand. Only the low `format.bits()` bits of raw count, so an i16 or a u16 cast straight to i128
+    /// both work. Zero is +0.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn from_fixed(raw: i128, format: QFormat, ctx: &mut Context) -> Self {
+        let raw = format.wrap(raw);
+        if raw == 0 {
+            return Self::zero(false);
+        }
+        Self::round_pack(raw < 0, -(format.fraction_bits as i32), raw.unsigned_abs(), ctx)
+    }
+}
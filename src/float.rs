@@ -0,0 +1,1017 @@
+use core::fmt;
+use core::num::FpCategory;
+
+use crate::wide::Wide;
+use crate::{Context, Flags, NanMode, RoundingMode, Tininess, F64};
+
+#[cfg_attr(feature = "rkyv", derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize))]
+pub struct Float {
+    bits: u64,
+}
+
+#[cfg(feature = "rkyv")]
+impl ArchivedFloat {
+    /// The value, read in place from the archive.
+    pub fn get(&self) -> Float {
+        Float::from_bits(self.bits.to_native())
+    }
+}
+
+/// Why `Float::try_from_parts` turned its parts down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PartsError {
+    ExponentOutOfRange(i16), // outside -1023..=1024, all the 11-bit field holds
+    MantissaOutOfRange(u64), // 2^52 or more, wider than the 52-bit field
+}
+
+impl fmt::Display for PartsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PartsError::ExponentOutOfRange(exponent) => write!(f, "exponent {exponent} is outside -1023..=1024"),
+            PartsError::MantissaOutOfRange(mantissa) => write!(f, "mantissa {mantissa:#x} doesn't fit in 52 bits"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PartsError {}
+
+impl Float {
+    pub fn from_bits(bits: u64) -> Self {
+        Float { bits }
+    }
+
+    pub fn new(value: f64) -> Self {
+        Float {
+            bits: value.to_bits(),
+        }
+    }
+
+    pub fn to_bits(&self) -> u64 {
+        self.bits
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        f64::from_bits(self.bits)
+    }
+
+    pub fn get_sign(&self) -> bool {
+        (self.bits >> 63) & 1 == 1 // false for positive, true for negative
+    }
+
+    // the field minus the bias, so subnormals report -1023 where they're interpreted as -1022, and zeros too.
+    // full_significand has the exponent a value actually has
+    pub fn get_exponent(&self) -> i16 {
+        let exp_bits = ((self.bits >> 52) & ((1 << 11) - 1)) as i16;
+        exp_bits - 1023 // Subtracting the bias
+    }
+
+    pub fn get_mantissa(&self) -> u64 {
+        self.bits & ((1 << 52) - 1) // last 52 bits
+    }
+
+    /// The 11-bit exponent field as it's stored: 0 for zeros and subnormals, 2047 for infinities and nans.
+    pub fn biased_exponent(&self) -> u16 {
+        ((self.bits >> 52) & ((1 << 11) - 1)) as u16
+    }
+
+    /// The 52-bit trailing significand field, without the implicit bit (the same bits as `get_mantissa`).
+    pub fn trailing_significand(&self) -> u64 {
+        self.get_mantissa()
+    }
+
+    /// The significand with its implicit bit, and the exponent that goes with it, so a finite value is
+    /// significand * 2^(exponent - 52). Subnormals have no implicit bit and an exponent of -1022, and zeros are
+    /// (0, -1022). For infinities and nans it's the raw fields: the trailing significand and 1024.
+    pub fn full_significand(&self) -> (u64, i16) {
+        if self.biased_exponent() == 2047 {
+            return (self.get_mantissa(), 1024);
+        }
+        let mut exponent = self.get_exponent();
+        (self.get_full_mantissa(&mut exponent), exponent)
+    }
+
+    pub fn negate(&mut self) {
+        self.bits ^= 1 << 63; // flip the sign bit by XORing because 1^0=1 and 1^1=0
+    }
+
+    /// The value with its sign bit flipped, nans included; no rounding and no flags.
+    pub fn negated(&self) -> Float {
+        Float::from_bits(self.bits ^ 1 << 63)
+    }
+
+    /// The magnitude: the sign bit cleared, nans included, with no rounding or flags.
+    pub fn abs(&self) -> Float {
+        Float::from_bits(self.bits & !(1 << 63))
+    }
+
+    /// IEEE 754's compareQuietLess: -0 and +0 are equal, and a nan on either side makes it false.
+    pub fn less_than(&self, other: &Float) -> bool {
+        !self.is_nan() && !other.is_nan() && self.ordered_bits() < other.ordered_bits()
+    }
+    /// IEEE 754's compareQuietGreater, with less_than's rules for zeros and nans.
+    pub fn greater_than(&self, other: &Float) -> bool {
+        other.less_than(self)
+    }
+    /// IEEE 754's compareQuietEqual, with less_than's rules for zeros and nans: -0 equals +0, and no nan equals itself.
+    pub fn equals(&self, other: &Float) -> bool {
+        !self.is_nan() && !other.is_nan() && self.ordered_bits() == other.ordered_bits()
+    }
+
+    // maps the bits onto a line where neighbouring floats are neighbouring integers (-0 and +0 both map to 0)
+    fn ordered_bits(&self) -> i64 {
+        let magnitude = (self.bits & !(1 << 63)) as i64;
+        if self.get_sign() {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+
+    /// A key whose unsigned order is IEEE 754's totalOrder: -nan < -inf < ... < -0 < +0 < ... < +inf < +nan, with
+    /// nans ordered by payload, further out the larger it is. Positives get their sign bit set and negatives have
+    /// every bit flipped, so sorting or bucketing by the key sorts the floats.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn to_total_order_key(&self) -> u64 {
+        if self.get_sign() {
+            !self.bits
+        } else {
+            self.bits | 1 << 63
+        }
+    }
+
+    /// The float a to_total_order_key key came from.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn from_total_order_key(key: u64) -> Float {
+        if key >> 63 == 1 {
+            Float::from_bits(key & !(1 << 63))
+        } else {
+            Float::from_bits(!key)
+        }
+    }
+
+    /// Number of representable values between self and other (0 if they are equal, or are +0 and -0).
+    /// Nans are ordered past the infinities, so distances involving them aren't meaningful.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn ulp_distance(&self, other: &Float) -> u64 {
+        self.ordered_bits().abs_diff(other.ordered_bits())
+    }
+
+    /// How many values lie in [a, b], ends included and the two zeros counted once: what between(a, b) yields.
+    /// 0 if a > b or either is a nan.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn count_between(a: &Float, b: &Float) -> u64 {
+        Float::between(a, b).remaining()
+    }
+
+    /// Every float from a up to b, both included, in order, as repeated next_up steps would give them, and
+    /// backwards too. The two zeros come out once, as a if it's a zero, otherwise b if it is, otherwise +0.
+    /// Empty if a > b or either is a nan.
+    pub fn between(a: &Float, b: &Float) -> Between {
+        let zero = if a.is_zero() { a.bits } else if b.is_zero() { b.bits } else { 0 };
+        if a.is_nan() || b.is_nan() || a.ordered_bits() > b.ordered_bits() {
+            return Between { front: 1, back: 0, zero };
+        }
+        Between { front: a.ordered_bits(), back: b.ordered_bits(), zero }
+    }
+
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn from_parts(sign: bool, exponent: i16, mantissa: u64) -> Self {
+        Float {
+            bits: (
+                (sign as u64) << 63) |
+                ((((exponent.wrapping_add(1023)) as u64) & ((1 << 11)-1)) << 52) | // select lower 11 bits of exponent and shift
+                (mantissa & ((1 << 52) - 1) // select lower 52 bits of mantissa
+            ),
+        }
+    }
+
+    /// from_parts, but parts that don't fit their fields are an error instead of being cut down to their low bits.
+    /// The exponent is the unbiased field, as get_exponent gives it: -1023 for zeros and subnormals, 1024 for
+    /// infinities and nans.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn try_from_parts(sign: bool, exponent: i16, mantissa: u64) -> Result<Self, PartsError> {
+        if !(-1023..=1024).contains(&exponent) {
+            return Err(PartsError::ExponentOutOfRange(exponent));
+        }
+        if mantissa >= 1 << 52 {
+            return Err(PartsError::MantissaOutOfRange(mantissa));
+        }
+        Ok(Float::from_parts_unchecked(sign, exponent, mantissa))
+    }
+
+    /// from_parts without the masking, for hot paths whose parts are known to fit. An exponent outside
+    /// -1023..=1024 or a mantissa of 2^52 or more spills into the neighbouring fields.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn from_parts_unchecked(sign: bool, exponent: i16, mantissa: u64) -> Self {
+        Float::from_bits((sign as u64) << 63 | ((exponent as i64 + 1023) as u64) << 52 | mantissa)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.get_exponent() == -1023 && self.get_mantissa() == 0
+    }
+
+    pub fn is_nan(&self) -> bool {
+        self.get_exponent() == 1024 && self.get_mantissa() != 0
+    }
+
+    pub fn is_infinity(&self) -> bool {
+        self.get_exponent() == 1024 && self.get_mantissa() == 0
+    }
+
+    /// Zero, subnormal or normal: anything but an infinity or a nan.
+    pub fn is_finite(&self) -> bool {
+        self.get_exponent() != 1024
+    }
+
+    pub fn classify(&self) -> FpCategory {
+        if self.is_nan() {
+            FpCategory::Nan
+        } else if self.is_infinity() {
+            FpCategory::Infinite
+        } else if self.is_zero() {
+            FpCategory::Zero
+        } else if self.get_exponent() == -1023 {
+            FpCategory::Subnormal
+        } else {
+            FpCategory::Normal
+        }
+    }
+
+    // nans with the top mantissa bit clear are signaling
+    pub fn is_signaling_nan(&self) -> bool {
+        self.is_nan() && (self.get_mantissa() >> 51) == 0
+    }
+
+    // smallest representable value greater than self
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn next_up(&self) -> Float {
+        if self.is_nan() || self.bits == 0x7FF0000000000000 {
+            return self.copy(); // nan and +infinity stay put
+        }
+        if self.is_zero() {
+            return Float::from_bits(1); // smallest positive subnormal, from either zero
+        }
+        if self.get_sign() {
+            Float::from_bits(self.bits - 1) // negative: moving up shrinks the magnitude
+        } else {
+            Float::from_bits(self.bits + 1)
+        }
+    }
+
+    // largest representable value less than self
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn next_down(&self) -> Float {
+        if self.is_nan() || self.bits == 0xFFF0000000000000 {
+            return self.copy(); // nan and -infinity stay put
+        }
+        if self.is_zero() {
+            return Float::from_bits((1 << 63) | 1); // smallest negative subnormal
+        }
+        if self.get_sign() {
+            Float::from_bits(self.bits + 1)
+        } else {
+            Float::from_bits(self.bits - 1)
+        }
+    }
+
+    pub fn nan() -> Float {
+        Float::from_bits(0x7FF8000000000000)
+    }
+
+    // what an invalid operation returns: x86 sets the sign of its default nan
+    pub(crate) fn default_nan(mode: NanMode) -> Float {
+        if matches!(mode, NanMode::FirstOperand | NanMode::LargerSignificand) {
+            Float::from_bits(0xFFF8000000000000)
+        } else {
+            Float::nan()
+        }
+    }
+
+    pub fn infinity(sign: bool) -> Float {
+        Float::from_bits((sign as u64) << 63 | (0x7FF << 52)) // infinity
+    }
+
+    pub fn copy(&self) -> Float {
+        Float { bits: self.bits }
+    }
+
+    pub fn largest_finite(sign: bool) -> Float {
+        Float::from_bits((sign as u64) << 63 | 0x7FEFFFFFFFFFFFFF) // largest finite number
+    }
+
+    // fn smallest_normal() -> float {
+    //     float::from_bits(0x0010000000000000) // smallest normal number
+    // }
+    // fn smallest_subnormal() -> float {
+    //     float::from_bits(0x0000000000000001) // smallest subnormal number
+    // }
+
+    pub(crate) fn nan_logic(&self, other: &Float) -> Option<Float> {
+        // this nan logic is not super important but matches apple's cpu behavior
+        // the rule is that signaling nans take precedence over quiet nans,
+        // that if both are the same type the first operand takes precedence,
+        // and that if one is a nan and the other is not, the nan is returned.
+        let self_is_nan = self.is_nan();
+        let other_is_nan = other.is_nan();
+        if self_is_nan || other_is_nan {
+            let chosen_nan = if other_is_nan
+                && (other.get_mantissa() >> 51) == 0
+                && !(self_is_nan && (self.get_mantissa() >> 51) == 0)
+            {
+                // other is signaling nan and self is not signaling nan
+                other.bits
+            } else if self_is_nan {
+                self.bits
+            } else {
+                other.bits
+            };
+            return Some(Float::from_bits(chosen_nan | 1 << 51)); // quiet nan
+        }
+        None
+    }
+
+    // returns mantissa with implicit leading 1 and adjusts exponent for subnormals
+    pub(crate) fn get_full_mantissa(&self, exponent: &mut i16) -> u64 {
+        let is_normal = (((self.bits >> 52) & ((1 << 11) - 1)) != 0) as u64; // exponent bits non-zero
+        *exponent += 1 - is_normal as i16; // adjust exponent for subnormal (interpreted as -1022)
+        self.get_mantissa() | (is_normal << 52) // implicit leading 1
+    }
+
+    // shifts x right by any amount, jamming everything shifted out into the lowest bit so a nonzero x never
+    // vanishes: whatever is left below the rounding position still counts as a sticky bit
+    pub(crate) fn shift_right_jam(x: u128, shift: u32) -> u128 {
+        if shift >= 128 {
+            return (x != 0) as u128;
+        }
+        (x >> shift) | (x & ((1 << shift) - 1) != 0) as u128
+    }
+
+    // shifts mantissa_full right by shift (at least 1, less than 128), rounding in the given mode
+    pub(crate) fn shift_and_round(mantissa_full: u128, shift: u32, sign: bool, mode: RoundingMode) -> u64 {
+        let mantissa = (mantissa_full >> shift) as u64;
+        let half = (mantissa_full >> (shift - 1)) & 1 == 1; // first bit shifted out
+        let sticky = mantissa_full & ((1u128 << (shift - 1)) - 1) != 0; // anything below it
+
+        if mode.round_up(sign, mantissa & 1 == 1, half, sticky) {
+            // this can carry into bit 53 (or bit 52 for subnormals), which pack_rounded turns into an exponent increment
+            mantissa + 1
+        } else {
+            // round down (truncate)
+            mantissa
+        }
+    }
+
+    // assembles a result from a rounded significand. exponent -1023 marks a subnormal.
+    // unlike from_parts this adds the significand instead of masking it, so a carry out of the mantissa from
+    // rounding bumps the exponent: 1.11..1 rounds up to 10.0, and the largest subnormal rounds up to the smallest normal.
+    pub(crate) fn pack_rounded(sign: bool, exponent: i16, significand: u64) -> Float {
+        let biased = if exponent == -1023 { 0 } else { (exponent + 1022) as u64 }; // the implicit 1 supplies the last +1
+        Float::from_bits(((sign as u64) << 63) | ((biased << 52) + significand))
+    }
+
+    // an exact result that skipped round_pack, like x + 0, still has to be flushed if it is subnormal
+    fn flush_output(&self, ctx: &mut Context) -> Float {
+        if ctx.flush_outputs && self.get_exponent() == -1023 && !self.is_zero() {
+            ctx.flags.raise(Flags::OUTPUT_DENORMAL);
+            return Float::from_bits(self.bits & (1 << 63));
+        }
+        self.copy()
+    }
+
+    // quiets whichever nan nan_logic picks (or gives the default nan, if the context asks for it),
+    // raising invalid if either operand was signaling
+    fn propagate_nan(&self, other: &Float, ctx: &mut Context) -> Option<Float> {
+        let nan = self.nan_logic(other)?;
+        if self.is_signaling_nan() || other.is_signaling_nan() {
+            ctx.flags.raise(Flags::INVALID);
+        }
+        match ctx.nan_mode {
+            NanMode::Propagate => Some(nan),
+            NanMode::Canonical => Some(Float::nan()),
+            NanMode::FirstOperand => Some(Float::from_bits(if self.is_nan() { self.bits } else { other.bits } | 1 << 51)),
+            NanMode::LargerSignificand => {
+                let (a, b) = (self.is_nan(), other.is_nan());
+                let chosen = if !b || (a && other.is_signaling_nan() && !self.is_signaling_nan()) {
+                    self
+                } else if !a || self.is_signaling_nan() != other.is_signaling_nan() {
+                    other
+                } else {
+                    // both quiet or both signaling: the larger significand, and between equal ones the positive one
+                    let key = |f: &Float| (f.bits & !(1 << 63), f.bits >> 63 == 0);
+                    if key(other) > key(self) {
+                        other
+                    } else {
+                        self
+                    }
+                };
+                Some(Float::from_bits(chosen.bits | 1 << 51))
+            }
+        }
+    }
+
+    // rounds and packs a finite nonzero result, raising inexact/underflow/overflow as needed. shared by multiply and add.
+    // the leading 1 of mantissa_full has to be at bit 104, so the value is 1.xxx * 2^exponent with 104 bits after the point.
+    pub(crate) fn round_pack(sign: bool, mut exponent: i16, mantissa_full: u128, ctx: &mut Context) -> Float {
+        let mode = ctx.rounding;
+
+        if exponent >= 1024 {
+            // too big even before rounding. directed modes that round towards zero stop at the largest finite value
+            ctx.flags.raise(Flags::OVERFLOW | Flags::INEXACT);
+            if mode.overflows_to_infinity(sign) {
+                return Float::infinity(sign);
+            }
+            return Float::largest_finite(sign);
+        }
+
+        let mut shift = 52; // we want to shift right by 52 to get 53 bits (including implicit leading 1). another way to think of this is that when we multiplied the mantissas we did an implicit mult by 2^52.
+
+        if exponent <= -1023 {
+            // the exact result is below the smallest normal. after rounding it can only reach the smallest normal from exponent -1023,
+            // and only if rounding to the full 53 bits (as though the exponent went lower) carries out.
+            let tiny = exponent < -1023
+                || ctx.tininess == Tininess::BeforeRounding
+                || Float::shift_and_round(mantissa_full, shift, sign, mode) >> 53 == 0;
+            if tiny && ctx.flush_outputs {
+                ctx.flags.raise(Flags::OUTPUT_DENORMAL);
+                return Float::from_bits((sign as u64) << 63);
+            }
+            // can we create a subnormal number?
+            if exponent < -1075 {
+                // min subnormal is 2^-52 * 2^-1022 = 2^-1074. we still allow exponent -1075 because we might round up to that value.
+                // below that everything is shifted out, so only the sticky bit is left to round with: zero, or the min subnormal when rounding away from zero.
+                if mantissa_full != 0 {
+                    ctx.flags.raise(Flags::UNDERFLOW | Flags::INEXACT);
+                }
+                let bump = mode.round_up(sign, false, false, mantissa_full != 0) as u64;
+                return Float::from_bits(((sign as u64) << 63) | bump);
+            }
+            shift += (-1023 + 1 - exponent) as u32; // correct by induction: if exponent is -1023, we want to shift by 1 extra since -1022 is the exponent this subnormal will be interpreted as having. if exponent is -1024 we want to shift by 2 extra, etc.
+            exponent = -1023; // mark as subnormal
+            if mantissa_full & ((1u128 << shift) - 1) != 0 {
+                ctx.flags.raise(Flags::INEXACT);
+                if tiny {
+                    ctx.flags.raise(Flags::UNDERFLOW); // underflow is only signaled when the tiny result is also inexact
+                }
+            }
+        } else if mantissa_full & ((1u128 << shift) - 1) != 0 {
+            ctx.flags.raise(Flags::INEXACT);
+        }
+
+        let result = Float::pack_rounded(sign, exponent, Float::shift_and_round(mantissa_full, shift, sign, mode));
+        if result.is_infinity() {
+            ctx.flags.raise(Flags::OVERFLOW); // rounding carried out of the largest exponent
+        }
+        result
+    }
+
+    pub fn multiply(&self, other: &Float) -> Float {
+        self.multiply_with_rounding(other, RoundingMode::TiesToEven)
+    }
+
+    pub fn multiply_with_rounding(&self, other: &Float, mode: RoundingMode) -> Float {
+        self.multiply_in(other, &mut Context::new(mode))
+    }
+
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub(crate) fn multiply_in(&self, other: &Float, ctx: &mut Context) -> Float {
+        if let Some(nan) = self.propagate_nan(other, ctx) {
+            return nan;
+        }
+
+        let sign = self.get_sign() ^ other.get_sign(); // same sign means pos, else neg
+
+        if self.is_infinity() || other.is_infinity() {
+            if self.is_zero() || other.is_zero() {
+                ctx.flags.raise(Flags::INVALID);
+                return Float::default_nan(ctx.nan_mode); // infinity * 0 = nan
+            }
+            return Float::infinity(sign);
+        }
+        if self.is_zero() || other.is_zero() {
+            return Float::from_bits((sign as u64) << 63); // an all-zero product would have nothing to normalize
+        }
+
+        let mut exponent = self.get_exponent() + other.get_exponent();
+
+        let mut mantissa_full = u128::from(self.get_full_mantissa(&mut exponent)) * u128::from(other.get_full_mantissa(&mut exponent)); // 53 + 53 = 106 bits
+
+        // if-else block normalizes mantissa_full so that the 105th bit is set.
+        // why bit 105? because we're going to shift down by 52 and so the implicit 1 will be correctly at bit 53.
+        if mantissa_full >> 105 != 0 {
+            // is 106th bit set? this means we overflowed.
+            exponent += 1;
+            mantissa_full = (mantissa_full >> 1) | (mantissa_full & 1); // keep the shifted out bit around as a sticky bit so rounding still sees it
+        } else {
+            // this case only happens when subnormals are involved, since min normal mantissa is 2^52 and 2^52 * 2^52 = 2^104, which has the 105th bit set.
+            // todo: handle upper case by using leading zeros too?
+            let shift_amt = mantissa_full.leading_zeros() - (128 - 105); // this will never be negative since we handled that case above. we want 23 leading zeros.
+            mantissa_full <<= shift_amt;
+            exponent -= shift_amt as i16;
+        }
+
+        Float::round_pack(sign, exponent, mantissa_full, ctx)
+    }
+
+    pub fn add(&self, other: &Float) -> Float {
+        self.add_with_rounding(other, RoundingMode::TiesToEven)
+    }
+
+    pub fn add_with_rounding(&self, other: &Float, mode: RoundingMode) -> Float {
+        self.add_in(other, &mut Context::new(mode))
+    }
+
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub(crate) fn add_in(&self, other: &Float, ctx: &mut Context) -> Float {
+        if let Some(nan) = self.propagate_nan(other, ctx) {
+            return nan;
+        }
+
+        if self.is_infinity() {
+            if other.is_infinity() && self.get_sign() != other.get_sign() {
+                ctx.flags.raise(Flags::INVALID);
+                return Float::default_nan(ctx.nan_mode); // infinity + -infinity = nan
+            }
+            return self.copy();
+        }
+        if other.is_infinity() {
+            return other.copy();
+        }
+
+        // an exact zero sum is +0, except in round-down where it is -0. (-0) + (-0) keeps its sign in every mode.
+        let zero_sum_sign = |a: &Float, b: &Float| -> bool {
+            if a.get_sign() == b.get_sign() {
+                a.get_sign()
+            } else {
+                ctx.rounding == RoundingMode::TowardNegative
+            }
+        };
+
+        if self.is_zero() {
+            if other.is_zero() {
+                return Float::from_bits((zero_sum_sign(self, other) as u64) << 63);
+            }
+            return other.flush_output(ctx);
+        }
+        if other.is_zero() {
+            return self.flush_output(ctx);
+        }
+
+        // both are finite and non-zero
+
+        // order by magnitude rather than exponent, so that with equal exponents a - b can't go negative
+        // and the sign of the result is always the sign of a.
+        let (a, b) = if (self.bits & !(1 << 63)) >= (other.bits & !(1 << 63)) {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        let mut exp_a = a.get_exponent();
+        let mut exp_b = b.get_exponent();
+
+        let sign = a.get_sign();
+        let mantissa_a = u128::from(a.get_full_mantissa(&mut exp_a)) << 52; // same layout as the product in multiply: 104 bits after the point
+        let mut mantissa_b = u128::from(b.get_full_mantissa(&mut exp_b)) << 52;
+
+        let exp_diff = (exp_a - exp_b) as u32;
+
+        if exp_diff > 54 {
+            // b is below a quarter of a's last place, so all it can do is set the sticky bit. adding it leaves a
+            // with only the sticky bit behind it, taking it away leaves one place below a with the half and sticky
+            // bits set. either way the sum is a or its neighbor on b's side, without lining b up at all.
+            // a is normal here (b is at least the smallest subnormal), so the neighbor is one step of the bits.
+            let same_sign = a.get_sign() == b.get_sign();
+            ctx.flags.raise(Flags::INEXACT);
+            if ctx.rounding.round_up(sign, false, !same_sign, true) != same_sign {
+                return a.copy();
+            }
+            let result = Float::from_bits(if same_sign { a.bits + 1 } else { a.bits - 1 });
+            if result.is_infinity() {
+                ctx.flags.raise(Flags::OVERFLOW); // stepped out of the largest finite value
+            }
+            return result;
+        }
+
+        // anything shifted out of b is collapsed into the lowest bit, which is far enough below the rounding position
+        // (bit 52 or higher) that it only acts as a sticky bit
+        mantissa_b = Float::shift_right_jam(mantissa_b, exp_diff);
+
+        let mut mantissa_full = if a.get_sign() == b.get_sign() {
+            mantissa_a + mantissa_b // 106 bits at most
+        } else {
+            mantissa_a - mantissa_b // never negative since |a| >= |b|
+        };
+
+        if mantissa_full == 0 {
+            return Float::from_bits((zero_sum_sign(a, b) as u64) << 63); // x + (-x)
+        }
+
+        let mut exponent = exp_a;
+        if mantissa_full >> 105 != 0 {
+            // the sum carried into bit 105
+            exponent += 1;
+            mantissa_full = (mantissa_full >> 1) | (mantissa_full & 1);
+        } else {
+            // cancellation (or subnormal operands) left the leading 1 lower down. shifting left is exact.
+            let shift_amt = mantissa_full.leading_zeros() - (128 - 105);
+            mantissa_full <<= shift_amt;
+            exponent -= shift_amt as i16;
+        }
+
+        Float::round_pack(sign, exponent, mantissa_full, ctx)
+    }
+
+    /// self - other, as add with other negated, except that a nan other keeps its sign (like Context::subtract).
+    pub fn subtract(&self, other: &Float) -> Float {
+        self.subtract_with_rounding(other, RoundingMode::TiesToEven)
+    }
+
+    pub fn subtract_with_rounding(&self, other: &Float, mode: RoundingMode) -> Float {
+        Context::new(mode).subtract(self, other)
+    }
+
+    pub fn divide(&self, other: &Float) -> Float {
+        self.divide_with_rounding(other, RoundingMode::TiesToEven)
+    }
+
+    pub fn divide_with_rounding(&self, other: &Float, mode: RoundingMode) -> Float {
+        self.divide_in(other, &mut Context::new(mode))
+    }
+
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub(crate) fn divide_in(&self, other: &Float, ctx: &mut Context) -> Float {
+        if let Some(nan) = self.propagate_nan(other, ctx) {
+            return nan;
+        }
+
+        let sign = self.get_sign() ^ other.get_sign(); // same sign means pos, else neg
+
+        if self.is_infinity() {
+            if other.is_infinity() {
+                ctx.flags.raise(Flags::INVALID);
+                return Float::default_nan(ctx.nan_mode); // infinity / infinity = nan
+            }
+            return Float::infinity(sign); // infinity / finite = infinity
+        }
+        if other.is_infinity() {
+            return Float::from_bits((sign as u64) << 63); // finite / infinity = 0
+        }
+        if other.is_zero() {
+            if self.is_zero() {
+                ctx.flags.raise(Flags::INVALID);
+                return Float::default_nan(ctx.nan_mode); // 0 / 0 = nan
+            }
+            ctx.flags.raise(Flags::DIVIDE_BY_ZERO);
+            return Float::infinity(sign); // x / 0 is an exact infinity, not nan
+        }
+        if self.is_zero() {
+            return Float::from_bits((sign as u64) << 63); // zero
+        }
+
+        // unlike multiply, subnormal significands have to be normalized first: a divisor with leading zeros
+        // would make the quotient too long, and a dividend with leading zeros would leave it too short.
+        let normalized = |f: &Float| -> (u64, i16) {
+            let mut exponent = f.get_exponent();
+            let mantissa = f.get_full_mantissa(&mut exponent);
+            let shift = mantissa.leading_zeros() - 11; // 0 for normals
+            (mantissa << shift, exponent - shift as i16)
+        };
+        let (mantissa_a, exp_a) = normalized(self);
+        let (mantissa_b, exp_b) = normalized(other);
+        let mut exponent = exp_a - exp_b;
+
+        // both mantissas are in [2^52, 2^53), so a / b is in (1/2, 2). shifting a up by 75 (as far as u128 allows)
+        // leaves a quotient with 75 or 76 bits, plenty for 53 bits plus rounding.
+        let numerator = u128::from(mantissa_a) << 75;
+        let divisor = u128::from(mantissa_b) | (1 << 52); // already set by normalization, but this way the compiler can see it isn't zero
+        let mut mantissa_full = numerator / divisor;
+        let remainder = numerator % divisor;
+
+        // normalize so the 105th bit is set, like multiply. shifting left is exact.
+        if mantissa_full >> 75 != 0 {
+            mantissa_full <<= 29; // a >= b
+        } else {
+            mantissa_full <<= 30;
+            exponent -= 1;
+        }
+        mantissa_full |= (remainder != 0) as u128; // sticky bit for whatever the division left over
+
+        Float::round_pack(sign, exponent, mantissa_full, ctx)
+    }
+
+    /// self * b + c with a single rounding, IEEE 754's fusedMultiplyAdd.
+    pub fn fma(&self, b: &Float, c: &Float) -> Float {
+        self.fma_with_rounding(b, c, RoundingMode::TiesToEven)
+    }
+
+    pub fn fma_with_rounding(&self, b: &Float, c: &Float, mode: RoundingMode) -> Float {
+        self.fma_in(b, c, &mut Context::new(mode))
+    }
+
+    // the product of two doubles is exact in a Wide, and the sum lines it up with c in 256 bits before jamming
+    // what's left over, so rounding that is rounding the exact value
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub(crate) fn fma_in(&self, b: &Float, c: &Float, ctx: &mut Context) -> Float {
+        let finite_nonzero = |x: &Float| x.is_finite() && !x.is_zero();
+        if !finite_nonzero(self) || !finite_nonzero(b) {
+            // a zero, infinite or nan product is exact, so rounding it changes nothing
+            let product = self.multiply_in(b, ctx);
+            return product.add_in(c, ctx);
+        }
+        if c.is_zero() {
+            return self.multiply_in(b, ctx); // a nonzero product plus a zero is the product, rounded once
+        }
+        if !c.is_finite() {
+            return Float::new(0.0).add_in(c, ctx); // however large the product, quieting a signaling nan
+        }
+        let wide = |x: &Float| Wide::from_soft(&F64::from_bits(x.bits));
+        let sum = wide(self).mul(wide(b)).add(wide(c));
+        if sum.is_zero() {
+            // exact cancellation, which is +0 but in rounding toward negative
+            return Float::from_bits(((ctx.rounding == RoundingMode::TowardNegative) as u64) << 63);
+        }
+        let rounded: F64 = sum.round(ctx);
+        Float::from_bits(rounded.to_bits())
+    }
+
+    // the result rounded to nearest, and whether it's exact: inexact is the one flag that says so, whatever else
+    // the operation raised. an overflow is inexact, an infinity or nan the operands make isn't
+    fn exactly(op: impl FnOnce(&mut Context) -> Float) -> (Float, bool) {
+        let mut ctx = Context::default();
+        let result = op(&mut ctx);
+        (result, !ctx.flags.contains(Flags::INEXACT))
+    }
+
+    /// self + other rounded to nearest, and true when no rounding happened, without a Context to check the
+    /// inexact flag in.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn add_exact(&self, other: &Float) -> (Float, bool) {
+        Float::exactly(|ctx| self.add_in(other, ctx))
+    }
+
+    /// self * other rounded to nearest, and true when no rounding happened.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn multiply_exact(&self, other: &Float) -> (Float, bool) {
+        Float::exactly(|ctx| self.multiply_in(other, ctx))
+    }
+
+    /// self / other rounded to nearest, and true when no rounding happened.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn divide_exact(&self, other: &Float) -> (Float, bool) {
+        Float::exactly(|ctx| self.divide_in(other, ctx))
+    }
+
+    /// (s, err) with s = a + b rounded to nearest and s + err = a + b exactly, the error-free transformation
+    /// compensated algorithms are built from. Knuth's two-sum, so it doesn't need to know which operand is
+    /// bigger. err is +0 when s overflows or is nan, since there's no finite error to give.
+    pub fn two_sum(&self, other: &Float) -> (Float, Float) {
+        let sub = |a: &Float, b: &Float| a.add(&Float::from_bits(b.bits ^ 1 << 63));
+        let s = self.add(other);
+        if s.is_nan() || s.is_infinity() {
+            return (s, Float::new(0.0));
+        }
+        let a = sub(&s, other);
+        let b = sub(&s, &a);
+        (s, sub(self, &a).add(&sub(other, &b)))
+    }
+
+    /// (p, err) with p = a * b rounded to nearest and p + err = a * b exactly, as fma(a, b, -p) gives on hardware
+    /// that has one. Two 53-bit significands multiply exactly in soft-float, so this needs neither an fma nor
+    /// Dekker's splitting, which overflows for operands past 2^996. err is only inexact when it falls below
+    /// the subnormals (a product within a factor of 2^53 of the underflow threshold), and +0 when p overflows or
+    /// is nan.
+    pub fn two_prod(&self, other: &Float) -> (Float, Float) {
+        let p = self.multiply(other);
+        if p.is_nan() || p.is_infinity() {
+            return (p, Float::new(0.0));
+        }
+        let wide = |f: &Float| Wide::from_soft(&F64::from_bits(f.bits));
+        let err: F64 = wide(self).mul(wide(other)).sub(wide(&p)).round(&mut Context::default());
+        (p, Float::from_bits(err.to_bits()))
+    }
+
+    /// self - other when Sterbenz's lemma says it's exact: both finite, of the same sign (or both zeros), and
+    /// other / 2 <= self <= 2 * other in magnitude. Subnormals included, since underflow is gradual. None
+    /// otherwise, even when the difference happens to be exact anyway, so a Some is something to assert on.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn sub_exact(&self, other: &Float) -> Option<Float> {
+        if self.is_nan() || other.is_nan() || self.is_infinity() || other.is_infinity() {
+            return None;
+        }
+        if self.get_sign() != other.get_sign() && !(self.is_zero() && other.is_zero()) {
+            return None;
+        }
+        // doubling is exact, or overflows to an infinity that is rightly larger than the other operand
+        let (a, b) = (Float::from_bits(self.bits & !(1 << 63)), Float::from_bits(other.bits & !(1 << 63)));
+        let two = Float::new(2.0);
+        if a.greater_than(&two.multiply(&b)) || b.greater_than(&two.multiply(&a)) {
+            return None;
+        }
+        Some(self.add(&Float::from_bits(other.bits ^ 1 << 63)))
+    }
+
+    // w rounded to nearest with ties toward zero, which only the augmented operations use. one unit off the 128th
+    // bit of the magnitude turns an exact tie into just below one and leaves every other case on the same side
+    // (there are 70-odd bits to spare below the last kept one), so ties away from zero then does it.
+    fn round_ties_toward_zero(w: Wide) -> Float {
+        let nudged = Wide::new(w.sign, w.exponent, w.significand - 1);
+        let rounded: F64 = nudged.round(&mut Context::new(RoundingMode::TiesToAway));
+        Float::from_bits(rounded.to_bits())
+    }
+
+    // the augmented operations' rules for what isn't a finite nonzero a: a nan, an infinity or an exact zero in
+    // both halves. otherwise a zero b takes the sign of a
+    fn augmented(a: Float, b: Float) -> (Float, Float) {
+        if a.is_nan() || a.is_infinity() || a.is_zero() {
+            return (a.copy(), a);
+        }
+        if b.is_zero() {
+            let zero = Float::from_bits(a.bits & 1 << 63);
+            return (a, zero);
+        }
+        (a, b)
+    }
+
+    /// IEEE 754-2019 augmentedAddition: (a, b) with a = self + other rounded to nearest, ties toward zero, and
+    /// b = self + other - a, which is always exact. Unlike two_sum, the rounding doesn't depend on the last bit
+    /// of a, which is what reproducible summation wants. When a overflows or is a nan, or the sum is exactly
+    /// zero, b is a copy of a; a zero b otherwise has the sign of a.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn augmented_add(&self, other: &Float) -> (Float, Float) {
+        let s = self.add(other);
+        if self.is_nan() || other.is_nan() || self.is_infinity() || other.is_infinity() || s.is_zero() {
+            return (s.copy(), s); // the zero's sign is the same in every nearest mode
+        }
+        let wide = |f: &Float| Wide::from_soft(&F64::from_bits(f.bits));
+        let a = Float::round_ties_toward_zero(wide(self).add(wide(other)));
+        if a.is_infinity() {
+            return (a.copy(), a);
+        }
+        if s.is_infinity() {
+            // the sum was exactly halfway between the largest finite value and the next power of two, where ties
+            // to even overflows and ties toward zero doesn't
+            return (a.copy(), Float::from_bits((a.bits & 1 << 63) | (970 + 1023) << 52));
+        }
+        // a and the ties-to-even s are at most an ulp apart, and the exact b is no more than half of one
+        let (s, error) = self.two_sum(other);
+        let b = error.add(&s.add(&Float::from_bits(a.bits ^ 1 << 63)));
+        Float::augmented(a, b)
+    }
+
+    /// IEEE 754-2019 augmentedMultiplication: (a, b) with a = self * other rounded to nearest, ties toward zero,
+    /// and b = self * other - a, rounded the same way. b is exact unless it falls below the subnormals. Special
+    /// values and zero products give b = a, and a zero b otherwise has the sign of a.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn augmented_mul(&self, other: &Float) -> (Float, Float) {
+        if self.is_nan() || other.is_nan() || self.is_infinity() || other.is_infinity() || self.is_zero() || other.is_zero() {
+            let p = self.multiply(other);
+            return (p.copy(), p);
+        }
+        let wide = |f: &Float| Wide::from_soft(&F64::from_bits(f.bits));
+        let product = wide(self).mul(wide(other)); // 106 bits, so exact
+        let a = Float::round_ties_toward_zero(product);
+        if a.is_infinity() || a.is_zero() {
+            return (a.copy(), a); // a zero a means the product was under half the smallest subnormal, so b is 0 too
+        }
+        let residual = product.sub(wide(&a)); // exact too: a is within an ulp of the product
+        let b = if residual.is_zero() { Float::new(0.0) } else { Float::round_ties_toward_zero(residual) };
+        Float::augmented(a, b)
+    }
+
+    #[cfg(feature = "std")]
+    pub fn print_bits(&self) {
+        println!("{:064b}", self.bits);
+    }
+
+    /// The fields of the encoding and what they mean, to print (`println!("{}", x.parts())`) or to look at.
+    pub fn parts(&self) -> FloatParts {
+        let biased_exponent = (self.bits >> 52 & 0x7FF) as u16;
+        FloatParts {
+            sign: self.get_sign(),
+            biased_exponent,
+            unbiased_exponent: biased_exponent.max(1) as i16 - 1023,
+            mantissa: self.get_mantissa(),
+            category: self.classify(),
+        }
+    }
+}
+
+/// A Float taken apart, from Float::parts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FloatParts {
+    pub sign: bool, // true for negative
+    pub biased_exponent: u16, // the 11-bit field: 0 for zeros and subnormals, 2047 for infinities and nans
+    pub unbiased_exponent: i16, // the power of two of the leading bit's place: -1022 for zeros and subnormals too
+    pub mantissa: u64, // the 52-bit field, without the implicit bit
+    pub category: FpCategory,
+}
+
+fn category_name(category: FpCategory) -> &'static str {
+    match category {
+        FpCategory::Nan => "nan",
+        FpCategory::Infinite => "infinite",
+        FpCategory::Zero => "zero",
+        FpCategory::Subnormal => "subnormal",
+        FpCategory::Normal => "normal",
+    }
+}
+
+impl fmt::Display for FloatParts {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "sign {}, exponent {} (biased {}), mantissa {:052b}, {}",
+            self.sign as u8,
+            self.unbiased_exponent,
+            self.biased_exponent,
+            self.mantissa,
+            category_name(self.category)
+        )
+    }
+}
+
+// Float(0x3FF199999999999A ≈ 1.1, normal), the value being the shortest decimal that reads back as it. {:#?} gives
+// all the parts in place of the class
+impl fmt::Debug for Float {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Float(0x{:016X} ≈ {:?}, ", self.bits, self.to_f64())?;
+        if f.alternate() {
+            write!(f, "{})", self.parts())
+        } else if self.is_signaling_nan() {
+            write!(f, "signaling nan)")
+        } else {
+            write!(f, "{})", category_name(self.classify()))
+        }
+    }
+}
+
+/// The iterator from Float::between: positions on the ordered_bits line, front and back both still to come.
+#[derive(Debug, Clone)]
+pub struct Between {
+    front: i64,
+    back: i64,
+    zero: u64, // the bits the zero position comes out as
+}
+
+impl Between {
+    fn at(&self, position: i64) -> Float {
+        match position {
+            0 => Float::from_bits(self.zero),
+            p if p < 0 => Float::from_bits(1 << 63 | p.unsigned_abs()),
+            p => Float::from_bits(p as u64),
+        }
+    }
+
+    /// How many are left, which can be more than a usize holds on 32-bit targets.
+    pub fn remaining(&self) -> u64 {
+        if self.front > self.back {
+            0
+        } else {
+            self.back.abs_diff(self.front) + 1
+        }
+    }
+}
+
+impl Iterator for Between {
+    type Item = Float;
+
+    fn next(&mut self) -> Option<Float> {
+        self.nth(0)
+    }
+
+    // skips ahead without stepping, so step_by is cheap
+    fn nth(&mut self, n: usize) -> Option<Float> {
+        if (n as u64) >= self.remaining() {
+            self.front = 1;
+            self.back = 0;
+            return None;
+        }
+        let position = self.front.wrapping_add_unsigned(n as u64); // n can be past i64::MAX, the sum can't
+        self.front = position + 1;
+        Some(self.at(position))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.remaining();
+        (usize::try_from(remaining).unwrap_or(usize::MAX), usize::try_from(remaining).ok())
+    }
+}
+
+impl DoubleEndedIterator for Between {
+    fn next_back(&mut self) -> Option<Float> {
+        self.nth_back(0)
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<Float> {
+        if (n as u64) >= self.remaining() {
+            self.front = 1;
+            self.back = 0;
+            return None;
+        }
+        let position = self.back.wrapping_sub_unsigned(n as u64);
+        self.back = position - 1;
+        Some(self.at(position))
+    }
+}
+
+impl core::iter::FusedIterator for Between {}
@@ -0,0 +1,112 @@
+use core::fmt::Debug;
+
+/// An unsigned integer wide enough to hold one encoded value of a format.
+pub trait Storage: Copy + Eq + Debug + Default {
+    fn to_u128(self) -> u128;
+    fn from_u128(v: u128) -> Self; // keeps the low bits
+}
+
+macro_rules! impl_storage {
+    ($($t:ty),*) => {
+        $(impl Storage for $t {
+            fn to_u128(self) -> u128 {
+                self as u128
+            }
+
+            fn from_u128(v: u128) -> Self {
+                v as $t
+            }
+        })*
+    };
+}
+
+impl_storage!(u8, u16, u32, u64, u128);
+
+/// Describes a binary interchange-style layout: a sign bit, then EXPONENT_BITS of biased exponent,
+/// then MANTISSA_BITS of trailing significand (the leading 1 is implicit, as in binary64).
+///
+/// The generic arithmetic in `SoftFloat` keeps intermediate results in a u128, which limits it to
+/// MANTISSA_BITS <= 63 (so a product of two significands still fits).
+pub trait Format: Copy + Debug + Default + Eq + 'static {
+    type Bits: Storage;
+    const EXPONENT_BITS: u32;
+    const MANTISSA_BITS: u32;
+    const NAME: &'static str;
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Binary16;
+
+impl Format for Binary16 {
+    type Bits = u16;
+    const EXPONENT_BITS: u32 = 5;
+    const MANTISSA_BITS: u32 = 10;
+    const NAME: &'static str = "binary16";
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Binary32;
+
+impl Format for Binary32 {
+    type Bits = u32;
+    const EXPONENT_BITS: u32 = 8;
+    const MANTISSA_BITS: u32 = 23;
+    const NAME: &'static str = "binary32";
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Binary64;
+
+impl Format for Binary64 {
+    type Bits = u64;
+    const EXPONENT_BITS: u32 = 11;
+    const MANTISSA_BITS: u32 = 52;
+    const NAME: &'static str = "binary64";
+}
+
+/// The x87 80-bit extended format, without its explicit integer bit: the x87 keeps that bit in memory and
+/// registers, but it is just the implicit 1 of the other formats (we don't model unnormals and pseudo-denormals).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Extended;
+
+impl Format for Extended {
+    type Bits = u128;
+    const EXPONENT_BITS: u32 = 15;
+    const MANTISSA_BITS: u32 = 63;
+    const NAME: &'static str = "x87 extended";
+}
+
+/// The 8-bit textbook minifloat: 4 exponent bits, 3 mantissa bits, with infinities and nans like the wider formats.
+/// Small enough to check every pair of operands.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Minifloat;
+
+impl Format for Minifloat {
+    type Bits = u8;
+    const EXPONENT_BITS: u32 = 4;
+    const MANTISSA_BITS: u32 = 3;
+    const NAME: &'static str = "minifloat";
+}
+
+/// bfloat16: binary32's 8 exponent bits with 7 mantissa bits, so f32's range at a third of its precision.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BFloat16;
+
+impl Format for BFloat16 {
+    type Bits = u16;
+    const EXPONENT_BITS: u32 = 8;
+    const MANTISSA_BITS: u32 = 7;
+    const NAME: &'static str = "bfloat16";
+}
+
+/// OCP FP8 E5M2: 5 exponent bits and 2 mantissa bits, laid out like binary16 with its low byte dropped. The other
+/// FP8 format, E4M3, has no infinities, so it isn't a Format; quantize handles it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct E5M2;
+
+impl Format for E5M2 {
+    type Bits = u8;
+    const EXPONENT_BITS: u32 = 5;
+    const MANTISSA_BITS: u32 = 2;
+    const NAME: &'static str = "e5m2";
+}
@@ -0,0 +1,180 @@
+// the gamma function and the log of its absolute value. ln gamma comes from stirling's series once x is at least
+// 64, from the recurrence gamma(x + 1) = x gamma(x) below that, from its taylor series around 1 and 2 where it has
+// zeros, and from the reflection formula for negative x. everything is in Wide and rounded once, as in math.rs.
+
+use crate::format::Format;
+use crate::math::{exp_wide, ln_wide, log1p_wide, FULL_BITS};
+use crate::trig::sin_pi_wide;
+use crate::wide::Wide;
+use crate::{Context, Flags, SoftFloat};
+
+// the constants here are truncated to 128 bits, not rounded
+const EULER: Wide = Wide { sign: false, exponent: -128, significand: 0x93C467E37DB0C7A4D1BE3F810152CB56 };
+const HALF_LN_2PI: Wide = Wide { sign: false, exponent: -128, significand: 0xEB3F8E4325F5A53494BC900144192023 };
+const LN_PI: Wide = Wide { sign: false, exponent: -127, significand: 0x928682473D0DE85EAFCAB635421FA4CC };
+const PI: Wide = Wide { sign: false, exponent: -126, significand: 0xC90FDAA22168C234C4C6628B80DC1CD1 };
+
+// zeta(2) to zeta(18), as significands of numbers in [1, 2), truncated too
+const ZETA: [u128; 17] = [
+    0xD28D3312983E991873D8912200BACE5E,
+    0x99DD0027803109C1B8B8AE2CF3483F8D,
+    0x8A8991563EC241B5F91211196E5235FB,
+    0x84BA0C7653E66DBD118271E8CCFFA309,
+    0x82384C260492247BED9785A807822B3C,
+    0x811196D0A679C46D4DFACD442D6216FB,
+    0x80859B57C31CB745F2CE526EDAD3266D,
+    0x8041CF9EC0B5AB817FD07DD8E6B4333C,
+    0x80209719D2DDCBF0C08F982A180602E9,
+    0x8010318DF24599544704E31613975ECD,
+    0x80081052D9669820CDC841785C2B9599,
+    0x80040564E845DEF583199647DA2C6EF4,
+    0x800201C95E569C2AC3C308714D6917B9,
+    0x80010097BCBF11BED0AAF45D7F519D60,
+    0x8000803266F5917879D0156AFFDBC10B,
+    0x80004010C1CDA19A034DE24813DF174A,
+    0x80002005932A6E89972535C39995BAF2,
+];
+
+// B2, B4, ..., B26 as fractions, for stirling's series
+const BERNOULLI: [(i64, i64); 13] = [
+    (1, 6),
+    (-1, 30),
+    (1, 42),
+    (-1, 30),
+    (5, 66),
+    (-691, 2730),
+    (7, 6),
+    (-3617, 510),
+    (43867, 798),
+    (-174611, 330),
+    (854513, 138),
+    (-236364091, 2730),
+    (8553103, 6),
+];
+
+// stirling's series is good to 2^-130 from 2^6 = 64 on with the 13 terms above
+const STIRLING_MAGNITUDE: i32 = 6;
+
+// ln gamma(1 + z) for |z| <= 2^-8, as -euler z + zeta(2) z^2/2 - zeta(3) z^3/3 + ...
+fn series_at_one(z: Wide) -> Wide {
+    if z.is_zero() {
+        return Wide::ZERO; // lgamma(1) and lgamma(2) are +0
+    }
+    let mut sum = EULER.mul(z).neg();
+    let mut power = z.neg();
+    for (k, zeta) in (2..).zip(ZETA) {
+        power = power.mul(z.neg());
+        sum = sum.add(Wide::new(false, -127, zeta).mul(power).div(Wide::from_int(k)));
+    }
+    sum
+}
+
+// (x - 1/2) ln x - x + ln(2 pi)/2 + B2/(2*1 x) + B4/(4*3 x^3) + ..., for x >= 64
+fn stirling(x: Wide) -> Wide {
+    let mut sum = x.sub(Wide::ONE.scale(-1)).mul(ln_wide(x, FULL_BITS)).sub(x).add(HALF_LN_2PI);
+    let inverse = Wide::ONE.div(x);
+    let inverse2 = inverse.mul(inverse);
+    let mut power = inverse;
+    for (k, (numerator, denominator)) in (1..).zip(BERNOULLI) {
+        sum = sum.add(Wide::from_int(numerator).mul(power).div(Wide::from_int(denominator * 2 * k * (2 * k - 1))));
+        power = power.mul(inverse2);
+    }
+    sum
+}
+
+// ln gamma(x) for positive x
+fn ln_gamma(x: Wide) -> Wide {
+    let near = Wide::ONE.scale(-8);
+    // x - 1 and x - 2 are exact when they are this small, which keeps the zeros at 1 and 2 accurate
+    let (z1, z2) = (x.sub(Wide::ONE), x.sub(Wide::from_int(2)));
+    if z1.is_zero() || z1.magnitude() < near.magnitude() {
+        return series_at_one(z1);
+    }
+    if z2.is_zero() || z2.magnitude() < near.magnitude() {
+        // ln gamma(2 + z) = ln gamma(1 + z) + ln(1 + z)
+        return series_at_one(z2).add(log1p_wide(z2, FULL_BITS));
+    }
+    if x.magnitude() >= STIRLING_MAGNITUDE {
+        return stirling(x);
+    }
+    // gamma(x) = gamma(x + n)/(x (x + 1) ... (x + n - 1)), with x + n just past 64
+    let mut product = x;
+    let mut shifted = x.add(Wide::ONE);
+    while shifted.magnitude() < STIRLING_MAGNITUDE {
+        product = product.mul(shifted);
+        shifted = shifted.add(Wide::ONE);
+    }
+    stirling(shifted).sub(ln_wide(product, FULL_BITS))
+}
+
+impl<F: Format> SoftFloat<F> {
+    /// The gamma function, extending (x - 1)! to the reals. Exact for the positive integers whose factorial the
+    /// format holds. tgamma(±0) = ±inf (divide by zero), tgamma(+inf) = +inf, and negative integers and -inf are
+    /// invalid; results too large or small overflow or underflow as usual.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn tgamma(&self, ctx: &mut Context) -> Self {
+        let x = self.flushed(ctx);
+        if let Some(nan) = x.propagate_nan(&x, ctx) {
+            return nan;
+        }
+        if x.is_zero() {
+            ctx.flags.raise(Flags::DIVIDE_BY_ZERO);
+            return Self::infinity(x.get_sign());
+        }
+        if x.is_infinity() && !x.get_sign() {
+            return x;
+        }
+        let integer = x.integer_parity().is_some();
+        if x.get_sign() && integer {
+            ctx.flags.raise(Flags::INVALID);
+            return Self::default_nan(ctx.nan_mode);
+        }
+        let w = Wide::from_soft(&x);
+        // exactly, up to 34!, which is the largest factorial under 2^128 and well past the last representable one
+        let n = w.round_to_int();
+        if integer && (1..=35).contains(&n) {
+            let factorial = (1..n).fold(1u128, |product, k| product * k as u128);
+            return Wide::new(false, 0, factorial).round(ctx);
+        }
+        if !w.sign {
+            return exp_wide(ln_gamma(w), FULL_BITS).round(ctx);
+        }
+        // gamma(x) = pi/(sin(pi x) gamma(1 - x))
+        PI.div(sin_pi_wide(w, FULL_BITS).mul(exp_wide(ln_gamma(Wide::ONE.sub(w)), FULL_BITS))).round(ctx)
+    }
+
+    /// ln |gamma(x)|. lgamma(1) = lgamma(2) = +0, and the poles at zero and the negative integers give +inf with
+    /// divide by zero, as does either infinity (without the flag). Correctly rounded like the rest of the math
+    /// functions, except very close to the zeros below -2 (about -2.457, -2.747, -3.144, ...), where the result
+    /// is tiny and only accurate to about 2^-118 absolute: enough for binary64 and narrower, but the 64-bit
+    /// extended format can be off by a few dozen ulps there.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn lgamma(&self, ctx: &mut Context) -> Self {
+        self.lgamma_r(ctx).0
+    }
+
+    /// lgamma, and the sign of gamma(x) as 1 or -1, like C's lgamma_r. The sign is -1 for -0 and 1 for the other
+    /// poles and nans.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn lgamma_r(&self, ctx: &mut Context) -> (Self, i32) {
+        let x = self.flushed(ctx);
+        if let Some(nan) = x.propagate_nan(&x, ctx) {
+            return (nan, 1);
+        }
+        if x.is_infinity() {
+            return (Self::infinity(false), 1);
+        }
+        if x.is_zero() || (x.get_sign() && x.integer_parity().is_some()) {
+            ctx.flags.raise(Flags::DIVIDE_BY_ZERO);
+            return (Self::infinity(false), if x.is_zero() && x.get_sign() { -1 } else { 1 });
+        }
+        let w = Wide::from_soft(&x);
+        if !w.sign {
+            return (ln_gamma(w).round(ctx), 1);
+        }
+        // ln |gamma(x)| = ln pi - ln |sin(pi x)| - ln gamma(1 - x), and gamma(x) has the sign of sin(pi x)
+        let sin = sin_pi_wide(w, FULL_BITS);
+        let result = LN_PI.sub(ln_wide(Wide { sign: false, ..sin }, FULL_BITS)).sub(ln_gamma(Wide::ONE.sub(w)));
+        (result.round(ctx), if sin.sign { -1 } else { 1 })
+    }
+}
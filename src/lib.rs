@@ -0,0 +1,82 @@
+// the arithmetic only needs core. printing, the bit layouts and the bindings need the (default) std feature.
+// the library is only an rlib, so it builds without std on any target:
+// `cargo build --lib --no-default-features --target thumbv7em-none-eabihf`
+// the shared library for the bindings and the c symbols is the floatfs-bindings crate in bindings/.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub mod arm;
+#[cfg(feature = "ndarray")]
+pub mod arrays;
+#[doc(hidden)]
+pub mod assert;
+pub mod ball;
+pub mod blas;
+#[cfg(feature = "builtins")]
+pub mod builtins;
+pub mod complex;
+#[cfg(feature = "std")]
+pub mod conformance;
+mod context;
+#[cfg(feature = "std")]
+mod decimal;
+#[cfg(feature = "rand")]
+pub mod distribution;
+pub mod exact;
+pub mod expansion;
+mod explain;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod fixed;
+mod float;
+pub mod format;
+mod gamma;
+pub mod limbs;
+pub mod lza;
+mod math;
+pub mod polynomial;
+pub mod predicates;
+pub mod presets;
+#[cfg(kani)]
+mod proofs;
+#[cfg(feature = "python")]
+mod python;
+pub mod quantize;
+pub mod random;
+#[cfg(feature = "bigint")]
+mod ratio;
+pub mod riscv;
+mod rounding;
+#[cfg(feature = "std")]
+pub mod smtlib;
+mod soft;
+pub mod sort;
+pub mod stats;
+pub mod sum;
+#[cfg(feature = "std")]
+pub mod testbench;
+mod trig;
+#[cfg(feature = "std")]
+mod visualize;
+#[cfg(feature = "wasm")]
+mod wasm;
+mod wide;
+pub mod x86;
+pub mod x87;
+
+pub use context::{Accuracy, Context, Flags, NanMode, Tininess};
+#[cfg(feature = "std")]
+pub use decimal::{
+    format_f128, format_f16, parse_f128, parse_f16, quantize_decimal128, quantize_decimal64, same_quantum_decimal128,
+    same_quantum_decimal64, ParseFloatError,
+};
+pub use explain::{
+    AddExplanation, DoubleRounding, MultiplyExplanation, OperandFields, RoundingDecision, RoundingTrace, SpecialCase,
+};
+#[cfg(feature = "rkyv")]
+pub use float::ArchivedFloat;
+pub use float::{Between, Float, FloatParts, PartsError};
+pub use format::Format;
+pub use rounding::RoundingMode;
+#[cfg(feature = "rkyv")]
+pub use soft::ArchivedSoftFloat;
+pub use soft::{Operation, SoftFloat, F16, F32, F64, F80};
@@ -0,0 +1,233 @@
+// a small multi-limb integer backend, for significands wider than a u128: slices of u64 limbs, least significant
+// first like the superaccumulator's, worked on in place the way gmp's mpn layer does it, so nothing allocates and
+// it all works in core. it's sized for significands, a few limbs to a few dozen: multiplication only switches to
+// karatsuba at KARATSUBA_THRESHOLD limbs, and the scratch space both it and division need is on the stack, which
+// caps operands at MAX_LIMBS.
+//
+// lengths are the callers' to get right (mul wants a.len() + b.len() limbs to write to, and so on) and ones that
+// don't fit panic, like copy_from_slice.
+
+use core::cmp::Ordering;
+
+/// The most limbs an operand of mul or div_rem can have: 4096 bits.
+pub const MAX_LIMBS: usize = 64;
+
+/// Operands this long and longer are multiplied with karatsuba, shorter ones limb by limb.
+pub const KARATSUBA_THRESHOLD: usize = 16;
+
+/// a += b, where b is no longer than a, carrying on through a. Returns the carry out of a's top limb.
+pub fn add_assign(a: &mut [u64], b: &[u64]) -> bool {
+    let mut carry = false;
+    for (i, limb) in a.iter_mut().enumerate() {
+        let other = b.get(i).copied().unwrap_or(0);
+        if i >= b.len() && !carry {
+            break;
+        }
+        let (sum, first) = limb.overflowing_add(other);
+        let (sum, second) = sum.overflowing_add(carry as u64);
+        *limb = sum;
+        carry = first || second;
+    }
+    carry
+}
+
+/// a -= b, where b is no longer than a. Returns the borrow out of a's top limb, which is set when b > a.
+pub fn sub_assign(a: &mut [u64], b: &[u64]) -> bool {
+    let mut borrow = false;
+    for (i, limb) in a.iter_mut().enumerate() {
+        let other = b.get(i).copied().unwrap_or(0);
+        if i >= b.len() && !borrow {
+            break;
+        }
+        let (difference, first) = limb.overflowing_sub(other);
+        let (difference, second) = difference.overflowing_sub(borrow as u64);
+        *limb = difference;
+        borrow = first || second;
+    }
+    borrow
+}
+
+/// Shifts a left by any amount, dropping what goes past the top.
+pub fn shl_assign(a: &mut [u64], shift: u32) {
+    let (limbs, bits) = ((shift / 64) as usize, shift % 64);
+    for i in (0..a.len()).rev() {
+        let high = if i >= limbs { a[i - limbs] } else { 0 };
+        let low = if i > limbs { a[i - limbs - 1] } else { 0 };
+        a[i] = if bits == 0 { high } else { high << bits | low >> (64 - bits) };
+    }
+}
+
+/// Shifts a right by any amount. Returns whether anything nonzero was shifted out, to jam into a sticky bit.
+pub fn shr_assign(a: &mut [u64], shift: u32) -> bool {
+    let (limbs, bits) = ((shift / 64) as usize, shift % 64);
+    let sticky = a.iter().take(limbs).any(|&limb| limb != 0)
+        || bits != 0 && a.get(limbs).is_some_and(|&limb| limb << (64 - bits) != 0);
+    for i in 0..a.len() {
+        let low = a.get(i + limbs).copied().unwrap_or(0);
+        let high = a.get(i + limbs + 1).copied().unwrap_or(0);
+        a[i] = if bits == 0 { low } else { low >> bits | high << (64 - bits) };
+    }
+    sticky
+}
+
+/// Compares a and b as numbers, whatever their lengths.
+pub fn cmp(a: &[u64], b: &[u64]) -> Ordering {
+    let len = a.len().max(b.len());
+    (0..len)
+        .rev()
+        .map(|i| a.get(i).copied().unwrap_or(0).cmp(&b.get(i).copied().unwrap_or(0)))
+        .find(|&order| order != Ordering::Equal)
+        .unwrap_or(Ordering::Equal)
+}
+
+/// The zero bits above a's leading 1 (all of them for zero).
+pub fn leading_zeros(a: &[u64]) -> u32 {
+    match a.iter().rposition(|&limb| limb != 0) {
+        Some(top) => (a.len() - 1 - top) as u32 * 64 + a[top].leading_zeros(),
+        None => a.len() as u32 * 64,
+    }
+}
+
+/// out = a * b, with out a.len() + b.len() limbs long.
+pub fn mul(a: &[u64], b: &[u64], out: &mut [u64]) {
+    assert!(a.len() <= MAX_LIMBS && b.len() <= MAX_LIMBS && out.len() == a.len() + b.len());
+    let (a, b) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    if b.len() < KARATSUBA_THRESHOLD {
+        return mul_schoolbook(a, b, out);
+    }
+    if a.len() == b.len() {
+        return karatsuba(a, b, out);
+    }
+    // the longer one in pieces as long as the shorter, each a square karatsuba, added in at its offset
+    out.fill(0);
+    let mut piece = [0u64; 2 * MAX_LIMBS];
+    for (i, chunk) in a.chunks(b.len()).enumerate() {
+        let piece = &mut piece[..chunk.len() + b.len()];
+        mul(chunk, b, piece);
+        add_assign(&mut out[i * b.len()..], piece);
+    }
+}
+
+/// out = a * b a limb at a time, as mul does below the threshold. out is a.len() + b.len() limbs.
+pub fn mul_schoolbook(a: &[u64], b: &[u64], out: &mut [u64]) {
+    assert!(out.len() == a.len() + b.len());
+    out.fill(0);
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, &y) in b.iter().enumerate() {
+            // x * y + out + carry is at most (2^64 - 1)^2 + 2 (2^64 - 1) = 2^128 - 1, so it fits
+            let t = x as u128 * y as u128 + out[i + j] as u128 + carry as u128;
+            out[i + j] = t as u64;
+            carry = (t >> 64) as u64;
+        }
+        out[i + b.len()] = carry;
+    }
+}
+
+// a and b the same length n: with a = a1 B + a0 and b = b1 B + b0, a b is a1 b1 B^2 + ((a0 + a1)(b0 + b1) -
+// a0 b0 - a1 b1) B + a0 b0, three half-size products instead of four
+fn karatsuba(a: &[u64], b: &[u64], out: &mut [u64]) {
+    let n = a.len();
+    let half = n / 2;
+    let (a0, a1) = a.split_at(half);
+    let (b0, b1) = b.split_at(half);
+    let high = n - half;
+
+    // a0 b0 in the bottom of out and a1 b1 in the top, as they don't overlap
+    let (low, top) = out.split_at_mut(2 * half);
+    mul(a0, b0, low);
+    mul(a1, b1, top);
+
+    // the sums are one limb longer than the high halves, for the carry
+    let (mut sum_a, mut sum_b) = ([0u64; MAX_LIMBS / 2 + 1], [0u64; MAX_LIMBS / 2 + 1]);
+    let (sum_a, sum_b) = (&mut sum_a[..high + 1], &mut sum_b[..high + 1]);
+    sum_a[..high].copy_from_slice(a1);
+    sum_a[high] = add_assign(&mut sum_a[..high], a0) as u64;
+    sum_b[..high].copy_from_slice(b1);
+    sum_b[high] = add_assign(&mut sum_b[..high], b0) as u64;
+    let mut middle = [0u64; MAX_LIMBS + 2];
+    let middle = &mut middle[..2 * (high + 1)];
+    mul(sum_a, sum_b, middle);
+    sub_assign(middle, &out[..2 * half]);
+    sub_assign(middle, &out[2 * half..]);
+    // what's left is less than 2^(128 high), so the top limbs are zero past what out has room for
+    let room = out.len() - half;
+    add_assign(&mut out[half..], &middle[..middle.len().min(room)]);
+}
+
+/// quotient = a / b and remainder = a % b, with quotient a.len() limbs and remainder b.len(). b can't be zero.
+/// Returns whether the remainder is nonzero, the sticky bit for a quotient being rounded.
+pub fn div_rem(a: &[u64], b: &[u64], quotient: &mut [u64], remainder: &mut [u64]) -> bool {
+    assert!(a.len() <= MAX_LIMBS && quotient.len() == a.len() && remainder.len() == b.len());
+    let n = b.iter().rposition(|&limb| limb != 0).expect("division by zero") + 1;
+    quotient.fill(0);
+    remainder.fill(0);
+    if cmp(a, b) == Ordering::Less {
+        remainder[..a.len().min(b.len())].copy_from_slice(&a[..a.len().min(b.len())]);
+        return a.iter().any(|&limb| limb != 0);
+    }
+    let m = a.iter().rposition(|&limb| limb != 0).map_or(0, |top| top + 1);
+
+    if n == 1 {
+        // a single limb divisor, a limb of the quotient at a time
+        let mut rest = 0u64;
+        for i in (0..m).rev() {
+            let t = (rest as u128) << 64 | a[i] as u128;
+            quotient[i] = (t / b[0] as u128) as u64;
+            rest = (t % b[0] as u128) as u64;
+        }
+        remainder[0] = rest;
+        return rest != 0;
+    }
+
+    // knuth's algorithm d (taocp 4.3.1): shift both so the divisor's top bit is set, which makes each estimated
+    // quotient limb at most two too big
+    let shift = b[n - 1].leading_zeros();
+    let mut v = [0u64; MAX_LIMBS];
+    let v = &mut v[..n];
+    v.copy_from_slice(&b[..n]);
+    shl_assign(v, shift);
+    let mut u = [0u64; MAX_LIMBS + 1];
+    let u = &mut u[..m + 1];
+    u[..m].copy_from_slice(&a[..m]);
+    shl_assign(u, shift); // into the extra top limb
+
+    let base = 1u128 << 64;
+    for j in (0..=m - n).rev() {
+        let top = (u[j + n] as u128) << 64 | u[j + n - 1] as u128;
+        let mut estimate = top / v[n - 1] as u128;
+        let mut rest = top % v[n - 1] as u128;
+        while estimate >= base || estimate * v[n - 2] as u128 > (rest << 64 | u[j + n - 2] as u128) {
+            estimate -= 1;
+            rest += v[n - 1] as u128;
+            if rest >= base {
+                break;
+            }
+        }
+
+        // u[j..=j + n] -= estimate * v
+        let (mut carry, mut borrow) = (0u128, false);
+        for i in 0..n {
+            let product = estimate * v[i] as u128 + carry;
+            carry = product >> 64;
+            let (difference, first) = u[i + j].overflowing_sub(product as u64);
+            let (difference, second) = difference.overflowing_sub(borrow as u64);
+            u[i + j] = difference;
+            borrow = first || second;
+        }
+        let (difference, first) = u[j + n].overflowing_sub(carry as u64);
+        let (difference, second) = difference.overflowing_sub(borrow as u64);
+        u[j + n] = difference;
+
+        // the rare case where the estimate was still one too big: add v back
+        if first || second {
+            estimate -= 1;
+            add_assign(&mut u[j..=j + n], v);
+        }
+        quotient[j] = estimate as u64;
+    }
+
+    shr_assign(&mut u[..n], shift);
+    remainder[..n].copy_from_slice(&u[..n]);
+    u[..n].iter().any(|&limb| limb != 0)
+}
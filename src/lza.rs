@@ -0,0 +1,102 @@
+// leading zero anticipation, the way an FPU adder finds its normalization shift. after an effective subtraction
+// the difference can start with any number of zeros (everything the operands had in common cancels), and the
+// shifter needs to know how many. counting them, as count_leading_zeros does, has to wait for the subtraction to
+// finish its carries; an LZA works out a string of indicator bits from the operands alone, in parallel with the
+// subtraction, whose leading one is where the difference's is or one place above it. the shifter moves the
+// difference by that much, and a last one-bit shift, if the top bit is still 0, corrects it.
+//
+// the indicator is Schmookler and Nowka's ("Leading zero anticipation and detection, a comparison of methods",
+// ARITH 2001). a - b is a + !b + 1, and it looks at a and !b a bit at a time: t where they differ, g where both are
+// 1, z where both are 0. a bit is set where the pattern around it ends the leading run of the sum:
+//
+//   f_i = t_(i+1) (g_i !z_(i-1) + z_i !g_(i-1)) + !t_(i+1) (z_i !z_(i-1) + g_i !g_(i-1))
+//
+// with i+1 the more significant neighbor. it leaves out the carry in of 1, which is why it can be one too high.
+//
+// everything here takes significands of width bits (1 to 128) in the low bits of a u128, with a > b, which is how
+// the adders order them; lining b up with a comes first, as in add_explained.
+
+/// The indicator string for a - b: its leading one is at the difference's leading one, or one place above.
+#[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+pub fn indicator(a: u128, b: u128, width: u32) -> u128 {
+    let (width, mask) = width_mask(width);
+    let (x, y) = (a & mask, !b & mask);
+    let t = x ^ y;
+    let g = x & y;
+    let z = !(x | y) & mask;
+    // each bit's neighbors: above the top bit a extends with 0 and !b with 1, so t is 1 there; below the bottom
+    // there's nothing, so g and z are 0
+    let t_above = t >> 1 | 1 << (width - 1);
+    let (g_below, z_below) = (g << 1 & mask, z << 1 & mask);
+    (t_above & (g & !z_below | z & !g_below) | !t_above & (z & !z_below | g & !g_below)) & mask
+}
+
+/// The normalization shift the indicator predicts for a - b: the difference's leading zeros, or one fewer.
+#[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+pub fn anticipate(a: u128, b: u128, width: u32) -> u32 {
+    leading_zeros(indicator(a, b, width), width)
+}
+
+/// The leading zeros of a - b, counted once the subtraction is done.
+#[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+pub fn count_leading_zeros(a: u128, b: u128, width: u32) -> u32 {
+    let (_, mask) = width_mask(width);
+    leading_zeros(a.wrapping_sub(b) & mask, width)
+}
+
+/// a - b normalized the way hardware with an LZA does it, with the steps along the way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Normalization {
+    pub difference: u128,
+    pub anticipated: u32, // the shift from the indicator
+    pub corrected: bool,  // the top bit was still 0 after it, so one more shift
+    pub significand: u128, // the difference with its leading 1 in the top bit
+    pub shift: u32,        // the total, which is count_leading_zeros
+}
+
+/// Shifts a - b left by the anticipated amount, then by one more if that left the top bit 0.
+#[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+pub fn normalize(a: u128, b: u128, width: u32) -> Normalization {
+    let (width, mask) = width_mask(width);
+    let difference = a.wrapping_sub(b) & mask;
+    let anticipated = anticipate(a, b, width);
+    let shifted = difference.checked_shl(anticipated).unwrap_or(0) & mask;
+    let corrected = shifted >> (width - 1) == 0 && shifted != 0;
+    let significand = if corrected { shifted << 1 & mask } else { shifted };
+    Normalization { difference, anticipated, corrected, significand, shift: anticipated + corrected as u32 }
+}
+
+/// a, !b, the t/g/z string, the indicator and the difference, one per line, lined up bit for bit.
+#[cfg(feature = "std")]
+pub fn diagram(a: u128, b: u128, width: u32) -> String {
+    let (width, mask) = width_mask(width);
+    let w = width as usize;
+    let (x, y) = (a & mask, !b & mask);
+    let tgz: String = (0..width)
+        .rev()
+        .map(|i| match (x >> i & 1, y >> i & 1) {
+            (1, 1) => 'g',
+            (0, 0) => 'z',
+            _ => 't',
+        })
+        .collect();
+    let n = normalize(a, b, width);
+    format!(
+        "a      {x:0w$b}\n!b     {y:0w$b}\n       {tgz}\nf      {:0w$b}  anticipates {}\na - b  {:0w$b}  {} leading zeros{}",
+        indicator(a, b, width),
+        n.anticipated,
+        n.difference,
+        n.shift,
+        if n.corrected { ", so one more shift to correct" } else { "" }
+    )
+}
+
+fn width_mask(width: u32) -> (u32, u128) {
+    let width = width.clamp(1, 128);
+    (width, u128::MAX >> (128 - width))
+}
+
+fn leading_zeros(x: u128, width: u32) -> u32 {
+    let (width, mask) = width_mask(width);
+    (x & mask).leading_zeros() - (128 - width)
+}
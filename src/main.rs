@@ -1,386 +1,6012 @@
-#[derive(Debug)]
-struct Float {
-    bits: u64,
+#![allow(dead_code)] // the checks below are switched on by hand from main()
+
+use floatfs::{assert_float_eq, Float};
+
+fn mult_check_print(a: Float, b: Float, print: bool) {
+    let result = a.multiply(&b);
+    let expected = a.to_f64() * b.to_f64();
+    let actual = result.to_f64();
+
+    if print {
+        println!("{}", a.parts());
+        println!("{}", b.parts());
+        println!("{}", result.parts());
+    }
+
+    // any nan matches: the host's payload isn't one we promise to copy
+    if !(expected.is_nan() && actual.is_nan()) {
+        assert_float_eq!(
+            result,
+            Float::new(expected),
+            "{:e} * {:e}\n{}{}",
+            a.to_f64(),
+            b.to_f64(),
+            result.bit_diff(&Float::new(expected), true),
+            a.multiply_explained(&b)
+        );
+    }
+    if print {
+        println!("Match!");
+        println!("x: {}, y: {}", a.to_f64(), b.to_f64());
+        println!("expected: {:e}, actual: {:e}", expected, actual);
+    }
+}
+
+fn mult_stress_test() {
+    use rand::Rng;
+    let mut rng = rand::rng();
+    for _ in 0..10_000_000 {
+        let fx = Float::from_bits(rng.random());
+        let fy = Float::from_bits(rng.random());
+        mult_check_print(fx, fy, false);
+    }
+    println!("Stress test passed!");
+}
+
+fn add_check_print(a: Float, b: Float, print: bool) {
+    let result = a.add(&b);
+    let expected = a.to_f64() + b.to_f64();
+    let actual = result.to_f64();
+
+    if print {
+        println!("{}", a.parts());
+        println!("{}", b.parts());
+        println!("{}", result.parts());
+    }
+
+    // any nan matches: the host's payload isn't one we promise to copy
+    if !(expected.is_nan() && actual.is_nan()) {
+        assert_float_eq!(
+            result,
+            Float::new(expected),
+            "{:e} + {:e}\n{}",
+            a.to_f64(),
+            b.to_f64(),
+            result.bit_diff(&Float::new(expected), true)
+        );
+    }
+    if print {
+        println!("Match!");
+        println!("x: {}, y: {}", a.to_f64(), b.to_f64());
+        println!("expected: {:e}, actual: {:e}", expected, actual);
+    }
+}
+
+fn add_stress_test() {
+    use rand::Rng;
+    let mut rng = rand::rng();
+    for _ in 0..10_000_000 {
+        let fx = Float::from_bits(rng.random());
+        // half the time, put y close to -x so the cancellation paths get exercised
+        let fy = if rng.random() {
+            Float::from_bits(rng.random())
+        } else {
+            Float::from_bits((fx.to_bits() ^ (1 << 63)) ^ (rng.random::<u64>() >> rng.random_range(1..64)))
+        };
+        add_check_print(fx, fy, false);
+    }
+    println!("Add stress test passed!");
+}
+
+fn div_check_print(a: Float, b: Float, print: bool) {
+    let result = a.divide(&b);
+    let expected = a.to_f64() / b.to_f64();
+    let actual = result.to_f64();
+
+    if print {
+        println!("{}", a.parts());
+        println!("{}", b.parts());
+        println!("{}", result.parts());
+    }
+
+    // any nan matches: the host's payload isn't one we promise to copy
+    if !(expected.is_nan() && actual.is_nan()) {
+        assert_float_eq!(
+            result,
+            Float::new(expected),
+            "{:e} / {:e}\n{}",
+            a.to_f64(),
+            b.to_f64(),
+            result.bit_diff(&Float::new(expected), true)
+        );
+    }
+    if print {
+        println!("Match!");
+        println!("x: {}, y: {}", a.to_f64(), b.to_f64());
+        println!("expected: {:e}, actual: {:e}", expected, actual);
+    }
+}
+
+fn div_stress_test() {
+    use rand::Rng;
+    let mut rng = rand::rng();
+    for _ in 0..10_000_000 {
+        div_check_print(Float::from_bits(rng.random()), Float::from_bits(rng.random()), false);
+    }
+    println!("Div stress test passed!");
+}
+
+fn mult_explained_test() {
+    // the explanation must end in exactly the same bits as multiply
+    use rand::Rng;
+    let mut rng = rand::rng();
+    for _ in 0..1_000_000 {
+        let a = Float::from_bits(rng.random());
+        let b = Float::from_bits(rng.random());
+        let explained = a.multiply_explained(&b);
+        if explained.result.to_bits() != a.multiply(&b).to_bits() {
+            println!("{}", explained);
+            panic!("Explained result differs from multiply");
+        }
+    }
+    println!("Explain test passed!");
+}
+
+fn add_explained_test() {
+    // the explanation must end in exactly the same bits as add, in every mode, including for the edge cases
+    use floatfs::RoundingMode;
+    use rand::Rng;
+    let mut rng = rand::rng();
+    let edges = [
+        0,
+        1,
+        0x000F_FFFF_FFFF_FFFF,
+        0x0010_0000_0000_0000,
+        0x3FF0_0000_0000_0000,
+        0x7FEF_FFFF_FFFF_FFFF,
+        0x7FF0_0000_0000_0000,
+        0x7FF8_0000_0000_0000,
+        0x7FF0_0000_0000_0001,
+    ];
+    let modes = [
+        RoundingMode::TiesToEven,
+        RoundingMode::TiesToAway,
+        RoundingMode::TowardZero,
+        RoundingMode::TowardPositive,
+        RoundingMode::TowardNegative,
+    ];
+    for i in 0..1_000_000 {
+        // edges with either sign, then random bits, then operands close enough to cancel
+        let (a, b) = match i % 3 {
+            0 => {
+                let sign = |rng: &mut rand::rngs::ThreadRng| (rng.random::<bool>() as u64) << 63;
+                (edges[i % edges.len()] | sign(&mut rng), edges[i / 3 % edges.len()] | sign(&mut rng))
+            }
+            1 => (rng.random(), rng.random()),
+            _ => {
+                let a: u64 = rng.random();
+                (a, a ^ 1 << 63 ^ rng.random::<u64>() >> rng.random_range(1..64))
+            }
+        };
+        let (a, b) = (Float::from_bits(a), Float::from_bits(b));
+        let mode = modes[i % modes.len()];
+        let explained = a.add_explained_with_rounding(&b, mode);
+        if explained.result.to_bits() != a.add_with_rounding(&b, mode).to_bits() {
+            println!("{}", explained);
+            panic!("Explained result differs from add");
+        }
+    }
+    println!("Add explain test passed!");
+}
+
+fn wide_gap_add_test() {
+    // once b is more than 54 places below a, add returns a or the next value over without lining b up. the sum and
+    // the flags must still be what rounding the exact sum gives, including next to powers of two and the largest
+    // finite value, and for subtraction too. the generic formats take a similar shortcut a place further out
+    use floatfs::exact::Exact;
+    use floatfs::format::{Binary16, Binary32, Extended, Minifloat, Storage};
+    use floatfs::{Context, Format, RoundingMode, SoftFloat, F64};
+    use rand::Rng;
+    let mut rng = rand::rng();
+    let modes = [
+        RoundingMode::TiesToEven,
+        RoundingMode::TiesToAway,
+        RoundingMode::TowardZero,
+        RoundingMode::TowardPositive,
+        RoundingMode::TowardNegative,
+    ];
+    let edges: [u64; 4] = [0x7FEF_FFFF_FFFF_FFFF, 0x3FF0_0000_0000_0000, 0x0370_0000_0000_0000, 0x7FE0_0000_0000_0000];
+    for i in 0..1_000_000 {
+        // a normal a, then a b whose exponent is 53 to 60 places lower, or anywhere lower, down into the subnormals
+        let a_bits = if i % 4 == 0 {
+            edges[i / 4 % edges.len()]
+        } else {
+            rng.random_range(0x0380_0000_0000_0000..0x7FF0_0000_0000_0000)
+        };
+        let a_exponent = a_bits >> 52;
+        let gap = if i % 2 == 0 { rng.random_range(53..=60) } else { rng.random_range(53..=a_exponent) };
+        let b_bits = (a_exponent.saturating_sub(gap) << 52) | (rng.random::<u64>() & 0x000F_FFFF_FFFF_FFFF);
+        let sign = |rng: &mut rand::rngs::ThreadRng| (rng.random::<bool>() as u64) << 63;
+        let a = Float::from_bits(a_bits | sign(&mut rng));
+        let b = Float::from_bits(b_bits.max(1) | sign(&mut rng));
+        let mode = modes[i % modes.len()];
+
+        let mut ctx = Context::new(mode);
+        let sum = ctx.add(&a, &b);
+        let mut expected_ctx = Context::new(mode);
+        let expected: F64 = (Exact::from(&a) + &b).round_to(&mut expected_ctx);
+        assert_eq!(sum.to_bits(), expected.to_bits(), "{:?} + {:?} in {:?}", a.to_f64(), b.to_f64(), mode);
+        assert_eq!(ctx.flags, expected_ctx.flags, "{:?} + {:?} in {:?}", a.to_f64(), b.to_f64(), mode);
+
+        let mut ctx = Context::new(mode);
+        let difference = ctx.subtract(&a, &b);
+        let mut expected_ctx = Context::new(mode);
+        let expected: F64 = (Exact::from(&a) - &b).round_to(&mut expected_ctx);
+        assert_eq!(difference.to_bits(), expected.to_bits(), "{:?} - {:?} in {:?}", a.to_f64(), b.to_f64(), mode);
+        assert_eq!(ctx.flags, expected_ctx.flags, "{:?} - {:?} in {:?}", a.to_f64(), b.to_f64(), mode);
+        assert_eq!(a.subtract_with_rounding(&b, mode).to_bits(), difference.to_bits());
+    }
+
+    fn check<F: Format>(rng: &mut rand::rngs::ThreadRng, modes: &[RoundingMode]) {
+        let field = |sign: bool, exponent: u128, mantissa: u128| {
+            let sign = (sign as u128) << (F::EXPONENT_BITS + F::MANTISSA_BITS);
+            SoftFloat::<F>::from_bits(F::Bits::from_u128(sign | exponent << F::MANTISSA_BITS | mantissa))
+        };
+        let (max_exponent, mantissa_mask) = ((1u128 << F::EXPONENT_BITS) - 2, (1u128 << F::MANTISSA_BITS) - 1);
+        let precision = F::MANTISSA_BITS as u128 + 1;
+        for i in 0..100_000 {
+            let a_exponent = if i % 4 == 0 { max_exponent } else { rng.random_range(precision + 2..=max_exponent) };
+            let a_mantissa = if i % 8 == 1 { 0 } else { rng.random::<u128>() & mantissa_mask };
+            let gap = rng.random_range(precision..=(precision + 6).min(a_exponent));
+            let b_mantissa = (rng.random::<u128>() & mantissa_mask).max(1);
+            let a = field(rng.random(), a_exponent, a_mantissa);
+            let b = field(rng.random(), a_exponent - gap, b_mantissa);
+            let mode = modes[i % modes.len()];
+            let mut ctx = Context::new(mode);
+            let sum = a.add(&b, &mut ctx);
+            let mut expected_ctx = Context::new(mode);
+            let expected: SoftFloat<F> = (Exact::from(a) + b).round_to(&mut expected_ctx);
+            let message = format!("{} {:?} + {:?} in {:?}", F::NAME, a, b, mode);
+            assert_eq!(sum.to_bits().to_u128(), expected.to_bits().to_u128(), "{message}");
+            assert_eq!(ctx.flags, expected_ctx.flags, "{message}");
+        }
+    }
+    check::<Minifloat>(&mut rng, &modes);
+    check::<Binary16>(&mut rng, &modes);
+    check::<Binary32>(&mut rng, &modes);
+    check::<Extended>(&mut rng, &modes);
+    println!("Wide gap add test passed!");
+}
+
+fn panic_free_test() {
+    // every arithmetic entry point on the bit patterns most likely to hit an edge (zeros, subnormal and normal
+    // boundaries, the largest finite value, infinities, quiet and signaling nans) in every rounding mode.
+    // run it in a debug build so overflow checks are on. `cargo build --profile no-panic --features no-panic`
+    // is the stronger check: main() then calls this, and the binary fails to link if the compiler can't prove
+    // the functions it reaches never panic.
+    use floatfs::exact::Exact;
+    use floatfs::fixed::{Overflow, QFormat};
+    use floatfs::quantize::{dequantize, quantize, Element, Rounding, Saturation, Scaling};
+    use floatfs::{Context, Operation, RoundingMode, F32, F64};
+    let edges64: Vec<u64> = [0, 1, 0x000FFFFFFFFFFFFF, 0x0010000000000000, 0x3FF0000000000000, 0x7FEFFFFFFFFFFFFF, 0x7FF0000000000000, 0x7FF0000000000001, 0x7FF8000000000000]
+        .iter()
+        .flat_map(|b| [*b, b ^ (1 << 63)])
+        .collect();
+    let edges32: Vec<u32> = [0, 1, 0x007FFFFF, 0x00800000, 0x3F800000, 0x7F7FFFFF, 0x7F800000, 0x7F800001, 0x7FC00000]
+        .iter()
+        .flat_map(|b| [*b, b ^ (1 << 31)])
+        .collect();
+    let modes = [
+        RoundingMode::TiesToEven,
+        RoundingMode::TiesToAway,
+        RoundingMode::TowardZero,
+        RoundingMode::TowardPositive,
+        RoundingMode::TowardNegative,
+    ];
+
+    for mode in modes {
+        let mut ctx = Context::new(mode);
+        for &a in &edges64 {
+            let (fa, sa) = (Float::from_bits(a), F64::from_bits(a));
+            fa.next_up();
+            fa.next_down();
+            let _: F32 = sa.convert(&mut ctx);
+            sa.to_i32(mode, &mut ctx);
+            sa.to_i64(mode, &mut ctx);
+            sa.to_u32(mode, &mut ctx);
+            sa.to_u64(mode, &mut ctx);
+            sa.fits_in_i64();
+            sa.fits_in_u32();
+            fa.ulp_distance(&fa);
+            Float::count_between(&fa, &fa.next_up());
+            F64::count_between(&sa, &F64::infinity(false));
+            sa.next_up();
+            sa.next_down();
+            for format in [QFormat::signed(0, 15), QFormat::signed(63, 0), QFormat::unsigned(0, 64), QFormat::unsigned(1, 0)] {
+                for overflow in [Overflow::Saturate, Overflow::Wrap] {
+                    let raw = sa.to_fixed(format, mode, overflow, &mut ctx);
+                    let _ = F64::from_fixed(raw, format, &mut ctx);
+                    let _ = F64::from_fixed(a as i128, format, &mut ctx);
+                }
+            }
+            sa.exp(&mut ctx);
+            sa.ln(&mut ctx);
+            sa.log2(&mut ctx);
+            sa.log10(&mut ctx);
+            sa.exp2(&mut ctx);
+            sa.exp10(&mut ctx);
+            sa.expm1(&mut ctx);
+            sa.log1p(&mut ctx);
+            sa.sin(&mut ctx);
+            sa.cos(&mut ctx);
+            sa.tan(&mut ctx);
+            sa.sin_cos(&mut ctx);
+            sa.reduce_mod_pi_over_2(&mut ctx);
+            sa.sinpi(&mut ctx);
+            sa.cospi(&mut ctx);
+            sa.asin(&mut ctx);
+            sa.acos(&mut ctx);
+            sa.atan(&mut ctx);
+            sa.cbrt(&mut ctx);
+            sa.tgamma(&mut ctx);
+            sa.lgamma_r(&mut ctx);
+            sa.sinh(&mut ctx);
+            sa.cosh(&mut ctx);
+            sa.tanh(&mut ctx);
+            sa.asinh(&mut ctx);
+            sa.acosh(&mut ctx);
+            sa.atanh(&mut ctx);
+            for &b in &edges64 {
+                let (fb, sb) = (Float::from_bits(b), F64::from_bits(b));
+                ctx.multiply(&fa, &fb);
+                ctx.add(&fa, &fb);
+                ctx.subtract(&fa, &fb);
+                ctx.divide(&fa, &fb);
+                fa.ulp_distance(&fb);
+                fa.multiply_explained_with_rounding(&fb, mode);
+                fa.add_explained_with_rounding(&fb, mode);
+                fa.two_sum(&fb);
+                fa.two_prod(&fb);
+                fa.sub_exact(&fb);
+                fa.add_exact(&fb);
+                fa.multiply_exact(&fb);
+                fa.divide_exact(&fb);
+                fa.augmented_add(&fb);
+                fa.augmented_mul(&fb);
+                floatfs::polynomial::horner(&[fa.copy(), fb.copy()], &fb);
+                floatfs::polynomial::horner_compensated(&[fa.copy(), fb.copy()], &fb);
+                floatfs::sum::sum_kahan(&[fa.copy(), fb.copy(), fa.copy()]);
+                floatfs::sum::sum_neumaier(&[fa.copy(), fb.copy(), fa.copy()]);
+                floatfs::sum::sum_pairwise(&[fa.copy(), fb.copy(), fa.copy()], 2);
+                floatfs::sum::sum_exact(&[fa.copy(), fb.copy(), fa.copy()]);
+                floatfs::sum::sum_reproducible(&[fa.copy(), fb.copy(), fa.copy()]);
+                floatfs::blas::nrm2(&[fa.copy(), fb.copy()]);
+                floatfs::blas::asum(&[fa.copy(), fb.copy()]);
+                let mut y = [fb.copy(), fa.copy()];
+                floatfs::blas::axpy(&fa, &[fb.copy(), fb.copy()], &mut y);
+                floatfs::blas::scal(&fb, &mut y);
+                floatfs::blas::fma_slices(&mut y, &[fa.copy(), fb.copy()], &[fb.copy(), fb.copy()], &[fa.copy(), fa.copy()]);
+                floatfs::blas::fma_accumulate(&mut y, &[fb.copy(), fa.copy()], &[fa.copy(), fb.copy()]);
+                let (z, w) = (floatfs::complex::Complex::new(fa.copy(), fb.copy()), floatfs::complex::Complex::new(fb.copy(), fa.copy()));
+                z.multiply(&w);
+                z.divide(&w);
+                w.divide(&z.conj());
+                let (x, y) = (floatfs::ball::Ball::new(fa.copy(), fb.copy()), floatfs::ball::Ball::exact(fb.copy()));
+                x.add(&y).multiply(&x).divide(&y);
+                x.exp().ln().sqrt().sin().cos().atan();
+                let mut dot = floatfs::sum::Superaccumulator::new();
+                dot.add_product(&fa, &fb);
+                dot.value_with_rounding(mode);
+                let mut quire = floatfs::sum::Quire::new();
+                quire.add_product(&sa, &sb);
+                quire.add(&sa);
+                quire.round(&mut ctx);
+                for mut stats in [floatfs::stats::RunningStats::new(), floatfs::stats::RunningStats::compensated()] {
+                    stats.add(&fa);
+                    stats.add(&fb);
+                    stats.mean();
+                    stats.sample_variance();
+                }
+                let (p, q) = ([fa.copy(), fb.copy()], [fb.copy(), Float::new(1.0)]);
+                floatfs::predicates::orient2d(&p, &q, &[Float::new(0.0), fa.copy()]);
+                floatfs::predicates::incircle(&p, &q, &[Float::new(0.0), fa.copy()], &[fb.copy(), fb.copy()]);
+                let (p, q) = ([fa.copy(), fb.copy(), Float::new(2.0)], [fb.copy(), Float::new(1.0), fa.copy()]);
+                floatfs::predicates::orient3d(&p, &q, &[Float::new(0.0), fa.copy(), fb.copy()], &[fb.copy(), fb.copy(), fa.copy()]);
+                sa.add(&sb, &mut ctx);
+                sa.subtract(&sb, &mut ctx);
+                sa.multiply(&sb, &mut ctx);
+                sa.divide(&sb, &mut ctx);
+                sa.multiply_traced(&sb, &mut ctx);
+                sa.powf(&sb, &mut ctx);
+                sa.powi(b as i32, &mut ctx);
+                sa.powi_exact(b as i32, &mut ctx);
+                sa.atan2(&sb, &mut ctx);
+                sa.hypot(&sb, &mut ctx);
+                sa.fdim(&sb, &mut ctx);
+                sa.fmax(&sb, &mut ctx);
+                sa.fmin(&sb, &mut ctx);
+                sa.midpoint(&sb, &mut ctx);
+                sa.lerp(&sb, &sa, &mut ctx);
+                sb.lerp(&sa, &sb, &mut ctx);
+                sa.lround(&mut ctx);
+                sa.lround_i32(&mut ctx);
+                sa.lrint(&mut ctx);
+                sa.lrint_i32(&mut ctx);
+                sa.compare(&sb);
+                let _: F64 = (Exact::from(sa) * sb + sa - Exact::from(sb) * sb * sa).round_to(&mut ctx);
+            }
+        }
+        for &a in &edges32 {
+            let sa = F32::from_bits(a);
+            ctx.f32_to_f64(a);
+            let _: F64 = sa.convert(&mut ctx);
+            for &b in &edges32 {
+                let sb = F32::from_bits(b);
+                sa.add(&sb, &mut ctx);
+                sa.subtract(&sb, &mut ctx);
+                sa.multiply(&sb, &mut ctx);
+                sa.divide(&sb, &mut ctx);
+                sa.add_traced(&sb, &mut ctx);
+                sa.subtract_traced(&sb, &mut ctx);
+                sa.multiply_traced(&sb, &mut ctx);
+                sa.divide_traced(&sb, &mut ctx);
+            }
+        }
+        for width in [0, 1, 53, 128, 200] {
+            for (a, b) in [(0, 0), (1, 0), (0, 1), (u128::MAX, 1), (u128::MAX, u128::MAX), (1 << 127, (1 << 127) - 1)] {
+                floatfs::lza::anticipate(a, b, width);
+                floatfs::lza::count_leading_zeros(a, b, width);
+                floatfs::lza::normalize(a, b, width);
+            }
+        }
+        for v in [0, 1, -1, i64::MIN, i64::MAX] {
+            F64::from_i64(v, &mut ctx);
+            F32::from_i64(v, &mut ctx);
+            F64::from_u64(v as u64, &mut ctx);
+            F32::from_u64(v as u64, &mut ctx);
+        }
+    }
+    for exponent in [i16::MIN, -1023, 0, 1024, i16::MAX] {
+        Float::from_parts(true, exponent, u64::MAX);
+        let _ = Float::try_from_parts(true, exponent, u64::MAX);
+        Float::from_parts_unchecked(true, exponent, u64::MAX);
+    }
+    let edges: Vec<f32> = edges32.iter().map(|&b| f32::from_bits(b)).collect();
+    let elements = [
+        Element::BFloat16,
+        Element::Binary16,
+        Element::E4M3,
+        Element::E5M2,
+        Element::E2M3,
+        Element::E3M2,
+        Element::E2M1,
+    ];
+    for element in elements {
+        for rounding in [Rounding::NearestEven, Rounding::Stochastic(1)] {
+            for scaling in [Scaling::Unscaled, Scaling::Amax, Scaling::Exponent(i32::MIN), Scaling::Exponent(i32::MAX)] {
+                let mut codes = [0u16; 18];
+                for saturation in [Saturation::NonSaturating, Saturation::Saturating] {
+                    let stats = quantize(&edges, &mut codes, element, rounding, scaling, saturation);
+                    dequantize(&codes, &mut [0f32; 18], element, stats.scale_exponent);
+                }
+            }
+        }
+        let codes: Vec<u16> = (0..=u16::MAX).collect();
+        dequantize(&codes, &mut vec![0f32; codes.len()], element, 0);
+        for op in [Operation::Add, Operation::Subtract, Operation::Multiply, Operation::Divide] {
+            for (a, b) in [(0, 0), (1, u32::MAX), (u32::MAX, 0x7F), (0x7C, 0xFC)] {
+                element.apply(op, a, b);
+            }
+        }
+    }
+    for source in [0, 1, u64::MAX] {
+        let mut bits = || source;
+        floatfs::random::unit::<floatfs::format::Binary64>(&mut bits);
+        floatfs::random::unit_dense::<floatfs::format::Binary64>(&mut bits);
+        floatfs::random::unit_dense::<floatfs::format::Extended>(&mut bits);
+        floatfs::random::unit_dense::<floatfs::format::Minifloat>(&mut bits);
+        floatfs::random::special::<floatfs::format::Extended>(&mut bits);
+        for &a in &edges64 {
+            for &b in &edges64 {
+                floatfs::random::range(&F64::from_bits(a), &F64::from_bits(b), &mut bits);
+            }
+        }
+    }
+    println!("Panic free test passed!");
+}
+
+fn overflow_test() {
+    // an overflowing result in every rounding mode and sign is infinity when rounding to nearest or toward it, and
+    // the largest finite value otherwise, raising overflow and inexact either way. both ways there: an exponent out
+    // of range before rounding, and rounding carrying out of the largest finite value, which only overflows when
+    // the mode rounds past max (otherwise it's just inexact, as the result rounded without an exponent limit is max).
+    use floatfs::format::{Binary16, Binary32, Binary64, Extended, Minifloat};
+    use floatfs::{Context, Flags, Float, Format, Operation, RoundingMode, SoftFloat, F64};
+
+    const MODES: [RoundingMode; 5] = [
+        RoundingMode::TiesToEven,
+        RoundingMode::TiesToAway,
+        RoundingMode::TowardZero,
+        RoundingMode::TowardPositive,
+        RoundingMode::TowardNegative,
+    ];
+    fn to_infinity(mode: RoundingMode, sign: bool) -> bool {
+        match mode {
+            RoundingMode::TiesToEven | RoundingMode::TiesToAway => true,
+            RoundingMode::TowardZero => false,
+            RoundingMode::TowardPositive => !sign,
+            RoundingMode::TowardNegative => sign,
+        }
+    }
+    fn overflowed<F: Format>(mode: RoundingMode, sign: bool) -> SoftFloat<F> {
+        if to_infinity(mode, sign) { SoftFloat::infinity(sign) } else { SoftFloat::largest_finite(sign) }
+    }
+
+    fn neg<F: Format>(mut x: SoftFloat<F>) -> SoftFloat<F> {
+        x.negate();
+        x
+    }
+
+    fn check<F: Format>() {
+        let exact = &mut Context::default();
+        let int = |n: i64| SoftFloat::<F>::from_i64(n, &mut Context::default());
+        let max = SoftFloat::<F>::largest_finite(false);
+        let ulp = max.subtract(&max.next_down(), exact);
+        let (half, half_ulp, quarter_ulp) = (int(1).divide(&int(2), exact), ulp.divide(&int(2), exact), ulp.divide(&int(4), exact));
+        assert!(exact.flags.is_empty());
+        let cases = [
+            (Operation::Multiply, max, int(2)),
+            (Operation::Add, max, max),
+            (Operation::Subtract, max, neg(max)),
+            (Operation::Divide, max, half),
+        ];
+        for mode in MODES {
+            for sign in [false, true] {
+                let signed = |x: SoftFloat<F>| if sign { neg(x) } else { x };
+                for (i, &(op, a, b)) in cases.iter().enumerate() {
+                    // the sign goes on a, and on b too unless b's sign doesn't matter
+                    let b = if op == Operation::Multiply || op == Operation::Divide { b } else { signed(b) };
+                    let mut ctx = Context::new(mode);
+                    let result = op.apply(&signed(a), &b, &mut ctx);
+                    let expected = overflowed::<F>(mode, sign);
+                    assert!(
+                        result.to_bits() == expected.to_bits() && ctx.flags == Flags::OVERFLOW | Flags::INEXACT,
+                        "{} case {i}, {mode:?}, sign {sign}: {:?}",
+                        F::NAME,
+                        ctx.flags
+                    );
+                }
+                // a half ulp past max is a tie, and max is odd, so rounding to nearest goes past it. a quarter ulp only
+                // goes past when rounding toward it
+                let directed = mode != RoundingMode::TiesToEven && mode != RoundingMode::TiesToAway;
+                for (past, over) in [(half_ulp, to_infinity(mode, sign)), (quarter_ulp, to_infinity(mode, sign) && directed)] {
+                    let mut ctx = Context::new(mode);
+                    let result = signed(max).add(&signed(past), &mut ctx);
+                    let (expected, flags) = if over {
+                        (SoftFloat::infinity(sign), Flags::OVERFLOW | Flags::INEXACT)
+                    } else {
+                        (signed(max), Flags::INEXACT)
+                    };
+                    assert!(
+                        result.to_bits() == expected.to_bits() && ctx.flags == flags,
+                        "{} max + {:?}, {mode:?}, sign {sign}: {:?}",
+                        F::NAME,
+                        past.to_bits(),
+                        ctx.flags
+                    );
+                }
+
+                // reading a number too big for the format
+                let mut ctx = Context::new(mode);
+                let parsed = SoftFloat::<F>::parse(if sign { "-1e5000" } else { "1e5000" }, &mut ctx).unwrap();
+                assert!(parsed.to_bits() == overflowed::<F>(mode, sign).to_bits(), "{} parse {mode:?} {sign}", F::NAME);
+                assert!(ctx.flags == Flags::OVERFLOW | Flags::INEXACT, "{} parse flags {:?}", F::NAME, ctx.flags);
+            }
+        }
+    }
+    check::<Binary16>();
+    check::<Binary32>();
+    check::<Binary64>();
+    check::<Extended>();
+    check::<Minifloat>();
+
+    // narrowing, and integers past binary16's range
+    for mode in MODES {
+        for sign in [false, true] {
+            let mut ctx = Context::new(mode);
+            let x = F64::largest_finite(sign).convert::<Binary32>(&mut ctx);
+            assert!(x.to_bits() == overflowed::<Binary32>(mode, sign).to_bits() && ctx.flags == Flags::OVERFLOW | Flags::INEXACT);
+        }
+        for (n, sign) in [(i64::MAX, false), (i64::MIN, true)] {
+            let mut ctx = Context::new(mode);
+            let x = SoftFloat::<Binary16>::from_i64(n, &mut ctx);
+            assert!(x.to_bits() == overflowed::<Binary16>(mode, sign).to_bits() && ctx.flags == Flags::OVERFLOW | Flags::INEXACT);
+        }
+    }
+
+    // and Float, through the context and the explained versions
+    for mode in MODES {
+        for sign in [false, true] {
+            let expected = overflowed::<Binary64>(mode, sign).to_bits();
+            let max = Float::from_bits(F64::largest_finite(sign).to_bits());
+            let two = Float::from_bits(2f64.to_bits());
+            let mut ctx = Context::new(mode);
+            assert_eq!(ctx.multiply(&max, &two).to_bits(), expected);
+            assert_eq!(ctx.add(&max, &max).to_bits(), expected);
+            assert_eq!(ctx.divide(&max, &Float::from_bits(0.5f64.to_bits())).to_bits(), expected);
+            assert_eq!(ctx.flags, Flags::OVERFLOW | Flags::INEXACT);
+            assert_eq!(max.multiply_explained_with_rounding(&two, mode).result.to_bits(), expected);
+            assert_eq!(max.add_explained_with_rounding(&max, mode).result.to_bits(), expected);
+        }
+    }
+    println!("Overflow test passed!");
+}
+
+fn zero_sign_test() {
+    // the sign of a zero sum: x + (-x) and x - x are +0, except in round-down where they're -0, in every format
+    // and for every size of x. two zeros of the same sign keep it, (-0) + (-0) = -0 and (-0) - (+0) = -0 in every
+    // mode, and x + 0 is x, whatever sign the zero has.
+    use floatfs::format::{Binary16, Binary32, Binary64, Extended, Minifloat};
+    use floatfs::{Context, Float, Format, RoundingMode, SoftFloat};
+
+    const MODES: [RoundingMode; 5] = [
+        RoundingMode::TiesToEven,
+        RoundingMode::TiesToAway,
+        RoundingMode::TowardZero,
+        RoundingMode::TowardPositive,
+        RoundingMode::TowardNegative,
+    ];
+
+    fn check<F: Format>() {
+        let one = SoftFloat::<F>::from_i64(1, &mut Context::default());
+        let smallest = SoftFloat::<F>::zero(false).next_up();
+        let values = [smallest, smallest.next_up(), one, one.next_up(), SoftFloat::largest_finite(false)];
+        for mode in MODES {
+            let cancelled = mode == RoundingMode::TowardNegative;
+            let name = |what: &str| format!("{} {what} {mode:?}", F::NAME);
+            for x in values {
+                for sign in [false, true] {
+                    let (mut x, mut minus_x) = (x, x);
+                    if sign { x.negate() } else { minus_x.negate() }
+                    let mut ctx = Context::new(mode);
+                    let sum = x.add(&minus_x, &mut ctx);
+                    assert!(sum.is_zero() && sum.get_sign() == cancelled, "{}", name("x + -x"));
+                    let difference = x.subtract(&x, &mut ctx);
+                    assert!(difference.is_zero() && difference.get_sign() == cancelled, "{}", name("x - x"));
+                    assert!(ctx.flags.is_empty(), "{}", name("cancellation flags"));
+
+                    for zero_sign in [false, true] {
+                        let zero = SoftFloat::<F>::zero(zero_sign);
+                        assert!(x.add(&zero, &mut ctx).to_bits() == x.to_bits(), "{}", name("x + 0"));
+                        assert!(zero.add(&x, &mut ctx).to_bits() == x.to_bits(), "{}", name("0 + x"));
+                        assert!(x.subtract(&zero, &mut ctx).to_bits() == x.to_bits(), "{}", name("x - 0"));
+                    }
+                }
+            }
+            let zeros = [SoftFloat::<F>::zero(false), SoftFloat::<F>::zero(true)];
+            for a in zeros {
+                for b in zeros {
+                    let mut ctx = Context::new(mode);
+                    let sum = a.add(&b, &mut ctx);
+                    let expected = if a.get_sign() == b.get_sign() { a.get_sign() } else { cancelled };
+                    assert!(sum.is_zero() && sum.get_sign() == expected, "{}", name("0 + 0"));
+                    let difference = a.subtract(&b, &mut ctx);
+                    let expected = if a.get_sign() != b.get_sign() { a.get_sign() } else { cancelled };
+                    assert!(difference.is_zero() && difference.get_sign() == expected, "{}", name("0 - 0"));
+                    assert!(ctx.flags.is_empty());
+                }
+            }
+        }
+    }
+    check::<Binary16>();
+    check::<Binary32>();
+    check::<Binary64>();
+    check::<Extended>();
+    check::<Minifloat>();
+
+    // Float, through the context and add_explained, and flushed subnormal inputs, which are zeros of their own sign
+    for mode in MODES {
+        let cancelled = mode == RoundingMode::TowardNegative;
+        let f = |x: f64| Float::from_bits(x.to_bits());
+        let mut ctx = Context::new(mode);
+        for x in [5e-324, 1.0, 0.1, f64::MAX] {
+            for (a, b) in [(x, -x), (-x, x)] {
+                assert_eq!(ctx.add(&f(a), &f(b)).to_bits(), (cancelled as u64) << 63, "{a} + {b} {mode:?}");
+                assert_eq!(ctx.subtract(&f(a), &f(a)).to_bits(), (cancelled as u64) << 63, "{a} - {a} {mode:?}");
+                assert_eq!(f(a).add_explained_with_rounding(&f(b), mode).result.to_bits(), (cancelled as u64) << 63);
+            }
+        }
+        assert_eq!(ctx.add(&f(-0.0), &f(-0.0)).to_bits(), (-0f64).to_bits());
+        assert_eq!(ctx.subtract(&f(-0.0), &f(0.0)).to_bits(), (-0f64).to_bits());
+        assert_eq!(ctx.add(&f(0.0), &f(-0.0)).to_bits(), (cancelled as u64) << 63);
+        assert_eq!(f(-0.0).add_explained_with_rounding(&f(-0.0), mode).result.to_bits(), (-0f64).to_bits());
+        assert_eq!(f(0.0).add_explained_with_rounding(&f(-0.0), mode).result.to_bits(), (cancelled as u64) << 63);
+        assert!(ctx.flags.is_empty());
+
+        let mut ctx = Context { flush_inputs: true, ..Context::new(mode) };
+        assert_eq!(ctx.add(&f(-5e-324), &f(-1e-310)).to_bits(), (-0f64).to_bits());
+        assert_eq!(ctx.add(&f(5e-324), &f(-1e-310)).to_bits(), (cancelled as u64) << 63);
+        let mut ctx = Context { flush_inputs: true, ..Context::new(mode) };
+        let (a, b) = (SoftFloat::<Binary32>::zero(true).next_down(), SoftFloat::<Binary32>::zero(false).next_up());
+        let sum = a.add(&b, &mut ctx).add(&a, &mut ctx);
+        assert!(sum.is_zero() && sum.get_sign() == cancelled, "flushed {mode:?}");
+    }
+    println!("Zero sign test passed!");
+}
+
+fn underflow_test() {
+    // results around the bottom of the normal range, in every format and with tininess detected before and after
+    // rounding. underflow is raised only for a tiny result that's also inexact; a result just below the smallest
+    // normal can round up to it, and is then not tiny after rounding if rounding it to full precision would have
+    // got there too; and a result between 2^-1075 and the smallest subnormal (binary64's numbers) rounds up to it.
+    use floatfs::format::{Binary16, Binary32, Binary64, Extended, Minifloat, Storage};
+    use floatfs::RoundingMode::{TiesToAway, TiesToEven, TowardNegative, TowardPositive, TowardZero};
+    use floatfs::{Context, Flags, Float, Format, Operation, SoftFloat, Tininess, F64};
+
+    fn neg<F: Format>(mut x: SoftFloat<F>) -> SoftFloat<F> {
+        x.negate();
+        x
+    }
+
+    fn check<F: Format>() {
+        let bits = |b: u128| SoftFloat::<F>::from_bits(F::Bits::from_u128(b));
+        let int = |n: i64| SoftFloat::<F>::from_i64(n, &mut Context::default());
+        let ratio = |n: i64, d: i64| int(n).divide(&int(d), &mut Context::default());
+        let m = F::MANTISSA_BITS;
+        let (zero, min_sub, largest_sub, min_normal) = (SoftFloat::<F>::zero(false), bits(1), bits((1 << m) - 1), bits(1 << m));
+        let (one_minus, one_plus) = (int(1).next_down(), int(1).next_up());
+        let (none, nx, uf) = (Flags::empty(), Flags::INEXACT, Flags::UNDERFLOW | Flags::INEXACT);
+        use Operation::{Add, Divide, Multiply, Subtract};
+
+        // (operation, a, b, mode, result, flags with tininess after rounding, flags with it before)
+        let cases = [
+            // exact subnormal results raise nothing, tiny or not
+            (Multiply, min_normal, ratio(1, 2), TiesToEven, bits(1 << (m - 1)), none, none),
+            (Add, largest_sub, min_sub, TiesToEven, min_normal, none, none),
+            (Subtract, min_normal, min_sub, TiesToEven, largest_sub, none, none),
+            (Divide, largest_sub, int(1), TowardZero, largest_sub, none, none),
+            // min normal - half a subnormal step: a tie, which rounds to the even min normal but is tiny after rounding
+            // either way, as it fits in full precision
+            (Multiply, one_minus, min_normal, TiesToEven, min_normal, uf, uf),
+            (Multiply, one_minus, min_normal, TiesToAway, min_normal, uf, uf),
+            (Multiply, one_minus, min_normal, TowardPositive, min_normal, uf, uf),
+            (Multiply, one_minus, min_normal, TowardZero, largest_sub, uf, uf),
+            (Multiply, one_minus, min_normal, TowardNegative, largest_sub, uf, uf),
+            // min normal - a sliver: full precision rounds it to min normal too, so it's only tiny before rounding
+            (Multiply, one_plus, largest_sub, TiesToEven, min_normal, nx, uf),
+            (Multiply, one_plus, largest_sub, TowardPositive, min_normal, nx, uf),
+            (Multiply, one_plus, largest_sub, TowardZero, largest_sub, uf, uf),
+            // below the smallest subnormal: 3/4 of it rounds up, a half is a tie (to even is 0), a quarter goes to 0
+            // unless rounding up
+            (Multiply, min_sub, ratio(3, 4), TiesToEven, min_sub, uf, uf),
+            (Multiply, min_sub, ratio(3, 4), TowardZero, zero, uf, uf),
+            (Multiply, min_sub, ratio(1, 2), TiesToEven, zero, uf, uf),
+            (Multiply, min_sub, ratio(1, 2), TiesToAway, min_sub, uf, uf),
+            (Divide, min_sub, int(2), TiesToEven, zero, uf, uf),
+            (Divide, min_sub, int(2), TowardPositive, min_sub, uf, uf),
+            (Multiply, min_sub, ratio(1, 4), TiesToEven, zero, uf, uf),
+            (Multiply, min_sub, ratio(1, 4), TiesToAway, zero, uf, uf),
+            (Multiply, min_sub, ratio(1, 4), TowardPositive, min_sub, uf, uf),
+            (Multiply, min_sub, ratio(1, 4), TowardNegative, zero, uf, uf),
+            (Multiply, min_sub, min_sub, TowardPositive, min_sub, uf, uf),
+            (Multiply, min_sub, min_sub, TiesToAway, zero, uf, uf),
+        ];
+        for (i, &(op, a, b, mode, expected, after, before)) in cases.iter().enumerate() {
+            for (tininess, flags) in [(Tininess::AfterRounding, after), (Tininess::BeforeRounding, before)] {
+                let mut ctx = Context { tininess, ..Context::new(mode) };
+                let result = op.apply(&a, &b, &mut ctx);
+                assert!(
+                    result.to_bits() == expected.to_bits() && ctx.flags == flags,
+                    "{} case {i}, {tininess:?}: {:?} {:?}",
+                    F::NAME,
+                    result.to_bits(),
+                    ctx.flags
+                );
+                // and the negative side, with the directed modes swapped
+                let mirrored = match mode {
+                    TowardPositive => TowardNegative,
+                    TowardNegative => TowardPositive,
+                    mode => mode,
+                };
+                let b = if op == Add || op == Subtract { neg(b) } else { b };
+                let mut ctx = Context { tininess, ..Context::new(mirrored) };
+                let result = op.apply(&neg(a), &b, &mut ctx);
+                assert!(result.to_bits() == neg(expected).to_bits() && ctx.flags == flags, "{} case {i} negated", F::NAME);
+            }
+        }
+
+        // flushing outputs follows tininess: min normal - a sliver survives if it's only tiny before rounding
+        let mut ctx = Context { flush_outputs: true, ..Context::new(TiesToEven) };
+        assert!(one_plus.multiply(&largest_sub, &mut ctx).to_bits() == min_normal.to_bits() && ctx.flags == nx);
+        let mut ctx = Context { flush_outputs: true, tininess: Tininess::BeforeRounding, ..Context::new(TiesToEven) };
+        assert!(one_plus.multiply(&largest_sub, &mut ctx).is_zero() && ctx.flags == Flags::OUTPUT_DENORMAL);
+
+        // sums of values this small are multiples of the smallest subnormal, so a tiny sum is always exact and adding
+        // never underflows (past twice the smallest normal it can be inexact, but it isn't tiny there)
+        for a in [min_sub, largest_sub, min_normal, min_normal.next_up(), int(2).multiply(&min_normal, &mut Context::default())] {
+            for b in [min_sub, bits(3), largest_sub.next_down(), min_normal] {
+                for (a, b) in [(a, b), (a, neg(b)), (neg(a), b)] {
+                    let mut ctx = Context { tininess: Tininess::BeforeRounding, ..Context::new(TiesToEven) };
+                    let sum = a.add(&b, &mut ctx);
+                    let magnitude = if sum.get_sign() { neg(sum) } else { sum };
+                    let tiny = magnitude.to_bits().to_u128() < 1 << m;
+                    assert!(!ctx.flags.contains(Flags::UNDERFLOW) && (!tiny || ctx.flags.is_empty()), "{} sum", F::NAME);
+                }
+            }
+        }
+    }
+    check::<Binary16>();
+    check::<Binary32>();
+    check::<Binary64>();
+    check::<Extended>();
+    check::<Minifloat>();
+
+    // Float has its own round_pack, so it has to agree with SoftFloat's on products near the boundary: the cases
+    // above (random products almost never land on them), then one operand near 1 and the other near the smallest
+    // normal, or two whose exponents sum to about -1075 (biased, 971)
+    use rand::Rng;
+    let mut rng = rand::rng();
+    let mut pairs: Vec<(u64, u64)> = vec![
+        (0x3FEF_FFFF_FFFF_FFFF, 0x0010_0000_0000_0000), // 1 - 2^-53 times min normal
+        (0x3FF0_0000_0000_0001, 0x000F_FFFF_FFFF_FFFF), // 1 + 2^-52 times the largest subnormal
+        (0x3FE8_0000_0000_0000, 1),                     // 3/4 of the smallest subnormal
+        (0x3FE0_0000_0000_0000, 1),
+        (0x3FD0_0000_0000_0000, 1),
+        (1, 1),
+    ];
+    let mantissa = |rng: &mut rand::rngs::ThreadRng| rng.random::<u64>() & ((1 << 52) - 1);
+    for i in 0..1_000_000 {
+        pairs.push(if i % 2 == 0 {
+            let exponent = rng.random_range(1021..1024);
+            (exponent << 52 | mantissa(&mut rng), rng.random_range(0..3u64) << 52 | mantissa(&mut rng))
+        } else {
+            let exponent = rng.random_range(470..530);
+            (exponent << 52 | mantissa(&mut rng), (971 - exponent + rng.random_range(0..4)) << 52 | mantissa(&mut rng))
+        });
+    }
+    for (i, &(a, b)) in pairs.iter().enumerate() {
+        for mode in [TiesToEven, TiesToAway, TowardZero, TowardPositive, TowardNegative] {
+            for tininess in [Tininess::AfterRounding, Tininess::BeforeRounding] {
+                let mut float_ctx = Context { tininess, ..Context::new(mode) };
+                let mut soft_ctx = float_ctx;
+                let float = float_ctx.multiply(&Float::from_bits(a), &Float::from_bits(b));
+                let soft = F64::from_bits(a).multiply(&F64::from_bits(b), &mut soft_ctx);
+                assert!(
+                    float.to_bits() == soft.to_bits() && float_ctx.flags == soft_ctx.flags,
+                    "{i}: {a:#x} * {b:#x} {mode:?} {tininess:?}: {:#x} {:?}, soft {:#x} {:?}",
+                    float.to_bits(),
+                    float_ctx.flags,
+                    soft.to_bits(),
+                    soft_ctx.flags
+                );
+            }
+        }
+    }
+    println!("Underflow test passed!");
+}
+
+fn traced_test() {
+    // the traced operations give the same results and flags as the plain ones, and their guard, round and sticky
+    // bits and decision agree with Float's explanations, which work them out independently
+    use floatfs::format::{Binary16, Binary32, Binary64, Extended, Minifloat};
+    use floatfs::{random, Context, Flags, Float, Format, Operation, RoundingDecision, RoundingMode, F32, F64};
+    use rand::RngCore;
+
+    const MODES: [RoundingMode; 5] = [
+        RoundingMode::TiesToEven,
+        RoundingMode::TiesToAway,
+        RoundingMode::TowardZero,
+        RoundingMode::TowardPositive,
+        RoundingMode::TowardNegative,
+    ];
+    const OPS: [Operation; 4] = [Operation::Add, Operation::Subtract, Operation::Multiply, Operation::Divide];
+
+    fn check<F: Format>(bits: &mut impl FnMut() -> u64) {
+        for i in 0..200_000 {
+            let (a, b) = (random::special::<F>(bits), random::special::<F>(bits));
+            let (op, mode) = (OPS[i % 4], MODES[i / 4 % 5]);
+            let (mut plain, mut traced) = (Context::new(mode), Context::new(mode));
+            let expected = op.apply(&a, &b, &mut plain);
+            let (result, trace) = op.apply_traced(&a, &b, &mut traced);
+            assert!(result.to_bits() == expected.to_bits() && plain.flags == traced.flags, "{} {op:?}", F::NAME);
+            // inexact exactly when the trace says something was dropped
+            let exact = trace.is_none_or(|t| t.decision == RoundingDecision::Exact);
+            assert!(exact != plain.flags.contains(Flags::INEXACT), "{} {op:?} {trace:?}", F::NAME);
+            if let Some(t) = trace {
+                assert!(t.overflow == plain.flags.contains(Flags::OVERFLOW) || t.decision == RoundingDecision::RoundUp);
+            }
+        }
+    }
+    let mut rng = rand::rng();
+    let mut bits = || rng.next_u64();
+    check::<Binary16>(&mut bits);
+    check::<Binary32>(&mut bits);
+    check::<Binary64>(&mut bits);
+    check::<Extended>(&mut bits);
+    check::<Minifloat>(&mut bits);
+
+    for i in 0..1_000_000 {
+        let (a, b) = (random::special::<Binary64>(&mut bits), random::special::<Binary64>(&mut bits));
+        let (x, y) = (Float::from_bits(a.to_bits()), Float::from_bits(b.to_bits()));
+        let mode = MODES[i % 5];
+        let (_, trace) = a.multiply_traced(&b, &mut Context::new(mode));
+        let explained = x.multiply_explained_with_rounding(&y, mode);
+        if let (Some(t), None) = (trace, explained.special) {
+            let expected = (explained.guard, explained.round, explained.sticky, explained.rounding);
+            assert_eq!((t.guard, t.round, t.sticky, t.decision), expected, "{:#x} * {:#x} {mode:?}", a.to_bits(), b.to_bits());
+        }
+        let (_, trace) = a.add_traced(&b, &mut Context::new(mode));
+        let explained = x.add_explained_with_rounding(&y, mode);
+        if let (Some(t), None) = (trace, explained.special) {
+            let expected = (explained.guard, explained.round, explained.sticky, explained.rounding);
+            assert_eq!((t.guard, t.round, t.sticky, t.decision), expected, "{:#x} + {:#x} {mode:?}", a.to_bits(), b.to_bits());
+        }
+    }
+
+    // 1 + 2^-24 in binary32 is a tie: the guard bit alone, rounded down to even, or up when rounding up
+    let (one, half_ulp) = (F32::from_bits(0x3F80_0000), F32::from_bits(0x3380_0000));
+    let (_, trace) = one.add_traced(&half_ulp, &mut Context::default());
+    let t = trace.unwrap();
+    assert!(t.guard && !t.round && !t.sticky && t.decision == RoundingDecision::RoundDown && !t.subnormal);
+    assert!(t.to_string().ends_with("guard 1, round 0, sticky 0, RoundDown"), "{t}");
+    let (_, trace) = one.add_traced(&half_ulp, &mut Context::new(RoundingMode::TowardPositive));
+    assert_eq!(trace.unwrap().decision, RoundingDecision::RoundUp);
+    // exact results still go through rounding, specials don't
+    let (_, trace) = one.multiply_traced(&one, &mut Context::default());
+    assert_eq!(trace.unwrap().decision, RoundingDecision::Exact);
+    assert!(one.divide_traced(&F32::zero(false), &mut Context::default()).1.is_none());
+    assert!(one.subtract_traced(&one, &mut Context::default()).1.is_none());
+    let (_, trace) = F64::largest_finite(false).add_traced(&F64::largest_finite(false), &mut Context::new(RoundingMode::TowardZero));
+    assert!(trace.unwrap().overflow && trace.unwrap().decision == RoundingDecision::RoundDown);
+    println!("Traced test passed!");
+}
+
+fn lza_test() {
+    // the anticipated shift is the difference's leading zeros or one fewer, never anything else, and with the
+    // correction normalize always lands on count_leading_zeros: every pair at small widths, then differences of
+    // binary64 significands lined up as the adder lines them up
+    use floatfs::lza::{anticipate, count_leading_zeros, diagram, normalize};
+    use rand::Rng;
+
+    for width in 1..=10 {
+        for a in 1..1u128 << width {
+            for b in 0..a {
+                let (predicted, actual) = (anticipate(a, b, width), count_leading_zeros(a, b, width));
+                assert!(predicted == actual || predicted + 1 == actual, "{a:b} - {b:b}: {predicted} {actual}");
+                let n = normalize(a, b, width);
+                assert!(n.shift == actual && n.significand == (a - b) << actual && n.significand >> (width - 1) == 1);
+                assert_eq!(n.corrected, predicted != actual);
+            }
+        }
+    }
+
+    let mut rng = rand::rng();
+    let mut corrected = 0;
+    for _ in 0..1_000_000 {
+        // 53-bit significands with a's leading 1 at bit 104, b shifted right by a small exponent difference and
+        // jammed; close exponents are where the cancellation is
+        let a = (rng.random::<u128>() >> 75 | 1 << 52) << 52;
+        let b = (rng.random::<u128>() >> 75 | 1 << 52) << 52;
+        let diff = rng.random_range(0..4);
+        let b = b >> diff | (b & ((1 << diff) - 1) != 0) as u128;
+        let (a, b) = if a > b { (a, b) } else { (b, a) };
+        if a == b {
+            continue;
+        }
+        let n = normalize(a, b, 105);
+        assert_eq!(n.shift, count_leading_zeros(a, b, 105), "{a:#x} - {b:#x}");
+        assert_eq!(n.significand, (a - b) << n.shift);
+        corrected += n.corrected as u32;
+    }
+    assert!(corrected > 0);
+
+    // 1000 - 0111 is anticipated exactly. 1000 - 0101 = 0011 isn't: the indicator can't see the carry in, so
+    // it stops a place early
+    let n = normalize(0b1000, 0b0111, 4);
+    assert!(n.anticipated == 3 && !n.corrected && n.significand == 0b1000);
+    let n = normalize(0b1000, 0b0101, 4);
+    assert!(n.anticipated == 1 && n.corrected && n.shift == 2 && n.significand == 0b1100);
+    assert_eq!(
+        diagram(0b1000, 0b0101, 4),
+        "a      1000\n!b     1010\n       gztz\nf      0101  anticipates 1\na - b  0011  2 leading zeros, so one more shift to correct"
+    );
+    println!("LZA test passed!");
+}
+
+fn fields_test() {
+    // the raw fields against the bits, negated, abs and is_finite against the host's, and full_significand against
+    // the value it stands for: a double's and a binary32's in host floats, and every minifloat's against to_f64 of
+    // the minifloat converted up
+    use floatfs::format::{Binary64, Minifloat};
+    use floatfs::{Context, SoftFloat, F32};
+    use rand::Rng;
+
+    let mut rng = rand::rng();
+    let edges = [0, 1, 0x000F_FFFF_FFFF_FFFF, 0x0010_0000_0000_0000, 0x3FF0_0000_0000_0000, 0x7FF0_0000_0000_0000, 0x7FF8_0000_0000_0001];
+    for bits in edges.into_iter().flat_map(|b| [b, b | 1 << 63]).chain((0..1_000_000).map(|_| rng.random::<u64>())) {
+        let x = Float::from_bits(bits);
+        assert_eq!(x.biased_exponent() as u64, bits >> 52 & 0x7FF);
+        assert_eq!(x.trailing_significand(), bits & ((1 << 52) - 1));
+        let (significand, exponent) = x.full_significand();
+        let value = f64::from_bits(bits);
+        assert_eq!(x.negated().to_bits(), (-value).to_bits());
+        assert_eq!(x.abs().to_bits(), value.abs().to_bits());
+        assert_eq!(x.is_finite(), value.is_finite());
+        if value.is_finite() {
+            assert_eq!(significand as f64 * 2f64.powi(-52) * 2f64.powi(exponent as i32), value.abs(), "{bits:x}");
+            assert!(significand < 1 << 53 && (significand >= 1 << 52 || exponent == -1022));
+        } else {
+            assert!(significand == x.trailing_significand() && exponent == 1024);
+        }
+        let y = F32::from_bits(bits as u32);
+        let (significand, exponent) = y.full_significand();
+        let value = f32::from_bits(bits as u32);
+        assert!(y.biased_exponent() == (bits as u32) >> 23 & 0xFF && y.trailing_significand() == bits as u128 & 0x7F_FFFF);
+        if value.is_finite() {
+            assert_eq!(significand as f64 * 2f64.powi(exponent - 23), value.abs() as f64, "{bits:x}");
+        } else {
+            assert!(significand == y.trailing_significand() && exponent == 128);
+        }
+    }
+    for bits in 0..=u8::MAX {
+        let x = SoftFloat::<Minifloat>::from_bits(bits);
+        let (significand, exponent) = x.full_significand();
+        let wide: SoftFloat<Binary64> = x.convert(&mut Context::default());
+        let value = f64::from_bits(wide.to_bits());
+        if value.is_finite() {
+            assert_eq!(significand as f64 * 2f64.powi(exponent - 3), value.abs());
+        }
+    }
+    println!("Fields test passed!");
+}
+
+fn from_parts_test() {
+    // try_from_parts takes exactly what from_parts would keep intact, and agrees with it there; the unchecked one
+    // agrees with both on parts that fit
+    use floatfs::PartsError;
+    use rand::Rng;
+
+    let mut rng = rand::rng();
+    for _ in 0..1_000_000 {
+        let sign = rng.random();
+        let exponent = if rng.random() { rng.random_range(-1100..1100) } else { rng.random::<i16>() };
+        let mantissa = if rng.random() { rng.random::<u64>() >> 11 } else { rng.random::<u64>() >> rng.random_range(0..64) };
+        match Float::try_from_parts(sign, exponent, mantissa) {
+            Ok(x) => {
+                assert!((-1023..=1024).contains(&exponent) && mantissa < 1 << 52);
+                assert_eq!(x.to_bits(), Float::from_parts(sign, exponent, mantissa).to_bits());
+                assert_eq!(x.to_bits(), Float::from_parts_unchecked(sign, exponent, mantissa).to_bits());
+                assert!(x.get_sign() == sign && x.get_exponent() == exponent && x.get_mantissa() == mantissa);
+            }
+            Err(PartsError::ExponentOutOfRange(e)) => assert!(e == exponent && !(-1023..=1024).contains(&exponent)),
+            Err(PartsError::MantissaOutOfRange(m)) => {
+                assert!(m == mantissa && mantissa >= 1 << 52 && (-1023..=1024).contains(&exponent))
+            }
+        }
+    }
+    assert_eq!(Float::try_from_parts(false, -1023, 1).map(|x| x.to_bits()), Ok(1));
+    assert_eq!(Float::try_from_parts(true, 1024, 0).map(|x| x.to_f64()), Ok(f64::NEG_INFINITY));
+    assert_eq!(Float::try_from_parts(false, i16::MIN, 0).map(|x| x.to_bits()), Err(PartsError::ExponentOutOfRange(i16::MIN)));
+    assert_eq!(Float::try_from_parts(false, 0, 1 << 52).map(|x| x.to_bits()), Err(PartsError::MantissaOutOfRange(1 << 52)));
+    assert_eq!(PartsError::ExponentOutOfRange(1025).to_string(), "exponent 1025 is outside -1023..=1024");
+    println!("From parts test passed!");
+}
+
+fn sort_test() {
+    // the key's order against f64::total_cmp, which is IEEE totalOrder, and back again; then radix_sort against
+    // sorting with total_cmp, on random bits, on few distinct values, and on values sorted or reversed already
+    use floatfs::sort::radix_sort;
+    use rand::Rng;
+
+    let mut rng = rand::rng();
+    let specials = [0.0, -0.0, f64::INFINITY, f64::NEG_INFINITY, f64::NAN, -f64::NAN, f64::MIN_POSITIVE, 5e-324, 1.0, -1.0];
+    let mut random = |i: usize| match i % 3 {
+        0 => rng.random::<u64>(),
+        1 => specials[rng.random_range(0..specials.len())].to_bits(),
+        _ => rng.random_range(-1e6..1e6f64).to_bits(),
+    };
+    for i in 0..1_000_000 {
+        let (a, b) = (random(i), random(i / 3));
+        let (x, y) = (Float::from_bits(a), Float::from_bits(b));
+        assert_eq!(x.to_total_order_key().cmp(&y.to_total_order_key()), f64::from_bits(a).total_cmp(&f64::from_bits(b)));
+        assert_eq!(Float::from_total_order_key(x.to_total_order_key()).to_bits(), a);
+    }
+    for n in [0, 1, 2, 31, 32, 33, 1000, 100_000, 1_000_000] {
+        for kind in 0..4 {
+            let mut bits: Vec<u64> = (0..n).map(|i| random(if kind == 1 { 1 } else { i })).collect();
+            if kind >= 2 {
+                bits.sort_by(|a, b| f64::from_bits(*a).total_cmp(&f64::from_bits(*b)));
+                if kind == 3 {
+                    bits.reverse();
+                }
+            }
+            let mut values: Vec<Float> = bits.iter().map(|&b| Float::from_bits(b)).collect();
+            radix_sort(&mut values);
+            bits.sort_by(|a, b| f64::from_bits(*a).total_cmp(&f64::from_bits(*b)));
+            assert!(values.iter().map(|x| x.to_bits()).eq(bits.iter().copied()), "{n} {kind}");
+        }
+    }
+    println!("Sort test passed!");
+}
+
+fn compare_test() {
+    // less_than, greater_than and equals against the host's <, > and ==, which are IEEE's quiet comparisons:
+    // negatives, both zeros, infinities and nans of either sign, and random bits
+    use rand::Rng;
+
+    let mut rng = rand::rng();
+    let specials = [0.0, -0.0, f64::INFINITY, f64::NEG_INFINITY, f64::NAN, -f64::NAN, 5e-324, -5e-324, 1.0, -1.0, -2.5];
+    for &a in &specials {
+        for &b in &specials {
+            let (x, y) = (Float::new(a), Float::new(b));
+            assert_eq!(x.less_than(&y), a < b, "{a} < {b}");
+            assert_eq!(x.greater_than(&y), a > b, "{a} > {b}");
+            assert_eq!(x.equals(&y), a == b, "{a} == {b}");
+        }
+    }
+    assert!(Float::new(-1.0).less_than(&Float::new(1.0)));
+    assert!(Float::new(-2.0).less_than(&Float::new(-1.0)));
+    assert!(!Float::new(-0.0).less_than(&Float::new(0.0)));
+    assert!(Float::new(-0.0).equals(&Float::new(0.0)));
+    assert!(!Float::new(f64::NAN).equals(&Float::new(f64::NAN)));
+    for _ in 0..1_000_000 {
+        let (a, b) = (f64::from_bits(rng.random()), f64::from_bits(rng.random()));
+        assert_eq!(Float::new(a).less_than(&Float::new(b)), a < b, "{a} < {b}");
+        assert_eq!(Float::new(a).greater_than(&Float::new(b)), a > b, "{a} > {b}");
+        assert_eq!(Float::new(a).equals(&Float::new(a)), !a.is_nan(), "{a} == {a}");
+    }
+    println!("Compare test passed!");
+}
+
+fn limbs_test() {
+    // one and two limbs against u128, then longer operands: karatsuba against schoolbook, the longer-times-shorter
+    // split, q b + r = a with r < b for division, and shifts that undo each other. random limbs, and limbs that are
+    // all ones or mostly zeros, which is where carries and knuth's add-back step happen.
+    use core::cmp::Ordering;
+    use floatfs::limbs::{self, KARATSUBA_THRESHOLD, MAX_LIMBS};
+    use rand::Rng;
+
+    let mut rng = rand::rng();
+    let limb = |rng: &mut rand::rngs::ThreadRng| match rng.random_range(0..4) {
+        0 => u64::MAX,
+        1 => rng.random_range(0..4),
+        2 => 1 << 63,
+        _ => rng.random(),
+    };
+    let split = |x: u128| [x as u64, (x >> 64) as u64];
+    let join = |x: &[u64]| x[0] as u128 | (x[1] as u128) << 64;
+
+    for _ in 0..1_000_000 {
+        let (x, y) = (join(&[limb(&mut rng), limb(&mut rng)]), join(&[limb(&mut rng), limb(&mut rng)]));
+        let (mut sum, mut difference) = (split(x), split(x));
+        assert_eq!((limbs::add_assign(&mut sum, &split(y)), join(&sum)), { let (s, c) = x.overflowing_add(y); (c, s) });
+        assert_eq!((limbs::sub_assign(&mut difference, &split(y)), join(&difference)), { let (d, b) = x.overflowing_sub(y); (b, d) });
+        assert_eq!(limbs::cmp(&split(x), &split(y)), x.cmp(&y));
+        assert_eq!(limbs::leading_zeros(&split(x)), x.leading_zeros());
+
+        let shift = rng.random_range(0..140);
+        let (mut left, mut right) = (split(x), split(x));
+        limbs::shl_assign(&mut left, shift);
+        let sticky = limbs::shr_assign(&mut right, shift);
+        assert_eq!(join(&left), x.checked_shl(shift).unwrap_or(0));
+        let dropped = match shift {
+            0 => 0,
+            1..128 => x << (128 - shift),
+            _ => x,
+        };
+        assert_eq!((join(&right), sticky), (x.checked_shr(shift).unwrap_or(0), dropped != 0));
+
+        let (a, b) = (limb(&mut rng), limb(&mut rng));
+        let mut product = [0; 2];
+        limbs::mul(&[a], &[b], &mut product);
+        assert_eq!(join(&product), a as u128 * b as u128);
+        if y != 0 {
+            let (mut quotient, mut remainder) = ([0; 2], [0; 2]);
+            let inexact = limbs::div_rem(&split(x), &split(y), &mut quotient, &mut remainder);
+            assert_eq!((join(&quotient), join(&remainder), inexact), (x / y, x % y, x % y != 0), "{x:#x} / {y:#x}");
+        }
+    }
+
+    for _ in 0..20_000 {
+        let (n, k) = (rng.random_range(1..=MAX_LIMBS), rng.random_range(1..=MAX_LIMBS));
+        let a: Vec<u64> = (0..n).map(|_| limb(&mut rng)).collect();
+        let b: Vec<u64> = (0..k).map(|_| limb(&mut rng)).collect();
+        let (mut product, mut expected) = (vec![0; n + k], vec![0; n + k]);
+        limbs::mul(&a, &b, &mut product);
+        limbs::mul_schoolbook(&a, &b, &mut expected);
+        assert_eq!(product, expected, "{n} x {k} limbs");
+        // squares take the karatsuba path from the threshold up
+        if n >= KARATSUBA_THRESHOLD {
+            let mut square = vec![0; 2 * n];
+            limbs::mul(&a, &a, &mut square);
+            let mut expected = vec![0; 2 * n];
+            limbs::mul_schoolbook(&a, &a, &mut expected);
+            assert_eq!(square, expected);
+        }
+
+        if b.iter().all(|&limb| limb == 0) {
+            continue;
+        }
+        let (mut quotient, mut remainder) = (vec![0; n], vec![0; k]);
+        let inexact = limbs::div_rem(&a, &b, &mut quotient, &mut remainder);
+        assert_eq!(limbs::cmp(&remainder, &b), Ordering::Less);
+        assert_eq!(inexact, remainder.iter().any(|&limb| limb != 0));
+        let mut check = vec![0; n + k];
+        limbs::mul(&quotient, &b, &mut check);
+        assert!(!limbs::add_assign(&mut check, &remainder));
+        assert_eq!(limbs::cmp(&check, &a), Ordering::Equal, "{n} / {k} limbs");
+    }
+    println!("Limbs test passed!");
+}
+
+fn exact_test() {
+    // a single sum or product rounds as the operation does, in every format and mode, flags included; a * b + c * d
+    // matches the superaccumulator's exactly rounded dot product; and longer expressions match their expansions
+    // summed exactly. then the things an expression rounding once gets right that rounding each step doesn't.
+    use floatfs::exact::Exact;
+    use floatfs::expansion::scale_expansion;
+    use floatfs::format::{Binary16, Binary32, Binary64, Extended, Minifloat};
+    use floatfs::sum::Superaccumulator;
+    use floatfs::{random, Context, Flags, Format, Operation, RoundingMode, SoftFloat, F64};
+    use rand::{Rng, RngCore};
+
+    const MODES: [RoundingMode; 5] = [
+        RoundingMode::TiesToEven,
+        RoundingMode::TiesToAway,
+        RoundingMode::TowardZero,
+        RoundingMode::TowardPositive,
+        RoundingMode::TowardNegative,
+    ];
+
+    fn check<F: Format>(bits: &mut impl FnMut() -> u64) {
+        for i in 0..200_000 {
+            let (a, b) = (random::special::<F>(bits), random::special::<F>(bits));
+            let mode = MODES[i % 5];
+            let (op, exact) = match i / 5 % 3 {
+                0 => (Operation::Add, Exact::from(a) + b),
+                1 => (Operation::Subtract, Exact::from(a) - b),
+                _ => (Operation::Multiply, Exact::from(a) * b),
+            };
+            let (mut expected_ctx, mut ctx) = (Context::new(mode), Context::new(mode));
+            let expected = op.apply(&a, &b, &mut expected_ctx);
+            let result: SoftFloat<F> = exact.round_to(&mut ctx);
+            let same = result.to_bits() == expected.to_bits() || result.is_nan() && expected.is_nan();
+            assert!(same && ctx.flags == expected_ctx.flags, "{} {op:?} {mode:?}", F::NAME);
+        }
+    }
+    let mut rng = rand::rng();
+    let mut bits = || rand::rng().next_u64();
+    check::<Binary16>(&mut bits);
+    check::<Binary32>(&mut bits);
+    check::<Binary64>(&mut bits);
+    check::<Extended>(&mut bits);
+    check::<Minifloat>(&mut bits);
+
+    for i in 0..1_000_000 {
+        let [a, b, c, d] = [(); 4].map(|_| Float::from_bits(random::special::<Binary64>(&mut bits).to_bits()));
+        let mode = MODES[i % 5];
+        let mut sum = Superaccumulator::new();
+        sum.add_product(&a, &b);
+        sum.add_product(&c, &d);
+        let result = (Exact::from(&a) * &b + Exact::from(&c) * &d).round_with_rounding(mode);
+        let expected = sum.value_with_rounding(mode);
+        // (the accumulator's zeros aren't all signed as additions would sign them, so those are checked below)
+        if !expected.is_zero() {
+            let same = result.to_bits() == expected.to_bits() || result.is_nan() && expected.is_nan();
+            assert!(same, "{a:?} * {b:?} + {c:?} * {d:?} {mode:?}");
+        }
+    }
+
+    // a b c + d e f - g h, with exponents kept where two_prod is exact, against the expansions of the products
+    // summed by the accumulator
+    for i in 0..200_000 {
+        let mut factor = || {
+            let sign = if rng.random() { -1.0 } else { 1.0 };
+            Float::new(sign * rng.random_range(1.0..2.0) * 2f64.powi(rng.random_range(-200..200)))
+        };
+        let [a, b, c, d, e, f, g, h] = [(); 8].map(|_| factor());
+        let mode = MODES[i % 5];
+        let exact = Exact::from(&a) * &b * &c + Exact::from(&d) * &e * &f - Exact::from(&g) * &h;
+        let mut sum = Superaccumulator::new();
+        for (x, y, z) in [(&a, &b, &c), (&d, &e, &f)] {
+            let (mut ab, mut abc) = ([(); 2].map(|_| Float::new(0.0)), [(); 4].map(|_| Float::new(0.0)));
+            let n = scale_expansion(&[Float::from_bits(x.to_bits())], y, &mut ab);
+            let n = scale_expansion(&ab[..n], z, &mut abc);
+            abc[..n].iter().for_each(|component| sum.add(component));
+        }
+        let mut minus_g = Float::from_bits(g.to_bits());
+        minus_g.negate();
+        sum.add_product(&minus_g, &h);
+        assert_eq!(exact.round_with_rounding(mode).to_bits(), sum.value_with_rounding(mode).to_bits(), "{mode:?}");
+    }
+
+    let two = |n: i32| Float::new(2f64.powi(n));
+    let (largest, tiny) = (Float::largest_finite(false), Float::from_bits(1));
+    // nothing overflows or underflows before the end
+    let mut ctx = Context::default();
+    let x: F64 = (Exact::from(&two(1000)) * &two(1000) * &two(-1000)).round_to(&mut ctx);
+    assert!(x.to_bits() == two(1000).to_bits() && ctx.flags.is_empty());
+    let x: F64 = (Exact::from(&two(-1000)) * &two(-1000) * &two(1000)).round_to(&mut ctx);
+    assert!(x.to_bits() == two(-1000).to_bits() && ctx.flags.is_empty());
+    let x: F64 = (Exact::from(&largest) * &two(1)).round_to(&mut ctx);
+    assert!(x.is_infinity() && ctx.take_flags() == Flags::OVERFLOW | Flags::INEXACT);
+    // the whole range in one sum, and back
+    assert_eq!((Exact::from(&largest) + &tiny - &largest).round().to_bits(), 1);
+    // 0.1 is a little over a tenth, so 1 - 0.1 * 10 is -2^-54, where rounding the product first gives 0
+    let tenth = Float::new(0.1);
+    assert_eq!((Exact::from(1) - Exact::from(&tenth) * 10).round().to_f64(), -(2f64.powi(-54)));
+    let mut ctx = Context::default();
+    let x: F64 = (Exact::from(&Float::new(0.5)) * 4).round_to(&mut ctx);
+    assert!(x.to_bits() == 2f64.to_bits() && ctx.flags.is_empty());
+    let _: F64 = (Exact::from(&tenth) * 3).round_to(&mut ctx);
+    assert_eq!(ctx.take_flags(), Flags::INEXACT);
+
+    // zeros: x x - x x cancels to +0, or -0 toward negative, as a single subtraction does; -0 * 5 + -0 is -0, and
+    // +0 + -0 is +0 except toward negative
+    let cancelled = Exact::from(&tenth) * &tenth - Exact::from(&tenth) * &tenth;
+    assert_eq!(cancelled.round().to_bits(), 0);
+    assert_eq!(cancelled.round_with_rounding(RoundingMode::TowardNegative).to_bits(), 1 << 63);
+    assert_eq!((cancelled * -1).round_with_rounding(RoundingMode::TowardNegative).to_bits(), 0);
+    let (zero, minus_zero) = (Float::new(0.0), Float::new(-0.0));
+    assert_eq!((Exact::from(&minus_zero) * 5 + &minus_zero).round().to_bits(), 1 << 63);
+    let mixed = Exact::from(&zero) + &minus_zero;
+    assert_eq!((mixed.round().to_bits(), mixed.round_with_rounding(RoundingMode::TowardNegative).to_bits()), (0, 1 << 63));
+
+    // inf - inf and inf * 0 raise invalid when the expression is rounded, wherever in it they were, as does a
+    // signaling nan; a quiet nan just comes through
+    let (infinity, quiet, signaling) = (Float::infinity(false), Float::nan(), Float::from_bits(0x7FF0_0000_0000_0001));
+    for (x, invalid) in [
+        (Exact::from(&infinity) - &infinity + 1, true),
+        (Exact::from(&infinity) * cancelled, true),
+        (Exact::from(&quiet) * &infinity + &infinity, false),
+        (Exact::from(2) * &signaling, true),
+    ] {
+        let mut ctx = Context::default();
+        let result: F64 = x.round_to(&mut ctx);
+        assert!(result.is_nan() && ctx.flags.contains(Flags::INVALID) == invalid);
+    }
+    let x: F64 = (Exact::from(&infinity) + Exact::from(&largest) * 2).round_to(&mut ctx);
+    assert!(x.is_infinity() && !x.get_sign() && ctx.flags.is_empty());
+
+    // 2^2046 + 2^-2049 spans exactly the 4096 bits there's room for, so taking 2^2046 off again leaves 2^-2049
+    // exactly, which scales back into range. (2^1023 + 2^-1074)^2 is 2^2046 + 2^-50 + 2^-2148, which doesn't fit:
+    // the last term is jammed, so it's gone once the rest is taken off, but the jammed bit still keeps it
+    // positive for rounding up
+    let top = Exact::from(&two(1023)) * &two(1023);
+    let spread = top + Exact::from(&two(-1000)) * &two(-1049) - top;
+    assert_eq!((spread * &two(1000) * &two(1000)).round().to_f64(), 2f64.powi(-49));
+    let square = (Exact::from(&two(1023)) + &tiny) * (Exact::from(&two(1023)) + &tiny);
+    let rest = square - top - &two(-50);
+    assert_eq!(rest.round_with_rounding(RoundingMode::TowardPositive).to_bits(), tiny.to_bits());
+    assert_eq!(rest.round_with_rounding(RoundingMode::TowardZero).to_bits(), 0);
+
+    // (1 + 2^-52)^100 - 1 - 100 2^-52 - 4950 2^-104 is 161700 2^-156 plus terms that take it past 4096 bits, so the
+    // lowest of them are jammed. still inexact and in the right place
+    let one_ulp = Float::new(1.0 + f64::EPSILON);
+    let mut power = Exact::from(1);
+    for _ in 0..100 {
+        power *= &one_ulp;
+    }
+    let rest = power - 1 - Exact::from(100) * &two(-52) - Exact::from(4950) * &two(-104);
+    let (down, up) = (rest.round_with_rounding(RoundingMode::TowardZero), rest.round_with_rounding(RoundingMode::TowardPositive));
+    assert!(down.next_up().to_bits() == up.to_bits() && [down.to_bits(), up.to_bits()].contains(&rest.round().to_bits()));
+    assert!((down.to_f64() / (161700.0 * 2f64.powi(-156)) - 1.0).abs() < 1e-9);
+    println!("Exact test passed!");
+}
+
+fn double_rounding_test() {
+    // binary32 through binary64 can't double round (53 >= 2 * 24 + 2), and neither can a directed mode through
+    // anything wider. binary64 through x87 extended in round to nearest can, so print the first case found.
+    use floatfs::format::Binary64;
+    use floatfs::{Context, Operation, RoundingMode, F32, F64};
+    use rand::Rng;
+    let ops = [Operation::Add, Operation::Subtract, Operation::Multiply, Operation::Divide];
+    let modes = [
+        RoundingMode::TiesToEven,
+        RoundingMode::TiesToAway,
+        RoundingMode::TowardZero,
+        RoundingMode::TowardPositive,
+        RoundingMode::TowardNegative,
+    ];
+    let mut rng = rand::rng();
+    let mut found = 0;
+    for _ in 0..1_000_000 {
+        let op = ops[rng.random_range(0..4)];
+        let ctx = Context::new(modes[rng.random_range(0..5)]);
+        let (a, b) = (F32::from_bits(rng.random()), F32::from_bits(rng.random()));
+        let check = a.double_rounding::<Binary64>(op, &b, &ctx);
+        assert!(!check.differs(), "{:?} {:?} {:?}\n{}", op, a, b, check);
+
+        // products of operands with long significands are the likeliest to land right next to a halfway point
+        let (x, y) = (F64::from_bits(rng.random::<u64>() >> 2 | 1 << 61), F64::from_bits(rng.random::<u64>() >> 2 | 1 << 61));
+        let check = x.double_rounding::<floatfs::format::Extended>(op, &y, &ctx);
+        if check.differs() {
+            assert!(matches!(ctx.rounding, RoundingMode::TiesToEven | RoundingMode::TiesToAway), "{}", check);
+            if found == 0 {
+                println!("{:?} {:?} {:?}\n{}", op, x, y, check);
+            }
+            found += 1;
+        }
+    }
+    println!("Double rounding test passed! ({} binary64 results changed through extended)", found);
+}
+
+fn math_test() {
+    // the exponentials, logarithms and cube root against the host libm, which is within an ulp and almost always correctly rounded,
+    // plus the directed modes bracketing the round to nearest result and the special values.
+    use floatfs::{Context, Flags, RoundingMode, F64};
+    use rand::Rng;
+    type Function = fn(&F64, &mut Context) -> F64;
+    type Host = fn(f64) -> f64;
+    let functions: [(&str, Function, Host); 9] = [
+        ("exp", F64::exp, f64::exp),
+        ("exp2", F64::exp2, f64::exp2),
+        ("exp10", F64::exp10, |x| 10f64.powf(x)),
+        ("expm1", F64::expm1, f64::exp_m1),
+        ("ln", F64::ln, f64::ln),
+        ("log2", F64::log2, f64::log2),
+        ("log10", F64::log10, f64::log10),
+        ("log1p", F64::log1p, f64::ln_1p),
+        ("cbrt", F64::cbrt, f64::cbrt),
+    ];
+    let mut rng = rand::rng();
+    let mut differ = 0;
+    for _ in 0..200_000 {
+        let (name, soft, host) = functions[rng.random_range(0..functions.len())];
+        let x = match name {
+            "exp" | "expm1" => rng.random_range(-760.0..720.0),
+            "exp2" => rng.random_range(-1100.0..1030.0),
+            "exp10" => rng.random_range(-330.0..310.0),
+            "log1p" if rng.random() => rng.random_range(-1.0..1.0) * 2f64.powi(-rng.random_range(0..60)),
+            _ => f64::from_bits(rng.random_range(1..0x7FF0000000000000)),
+        };
+        let a = F64::from_bits(x.to_bits());
+        let near = soft(&a, &mut Context::new(RoundingMode::TiesToEven)).to_bits();
+        let expected = host(x).to_bits();
+        // glibc's log10 is only within 2 ulps
+        let tolerance = if name == "log10" { 2 } else { 1 };
+        assert!(near.abs_diff(expected) <= tolerance, "{}({:e}): {:x}, host {:x}", name, x, near, expected);
+        differ += (near != expected) as u32;
+        let down = soft(&a, &mut Context::new(RoundingMode::TowardNegative)).to_bits();
+        let up = soft(&a, &mut Context::new(RoundingMode::TowardPositive)).to_bits();
+        let (down, up) = (f64::from_bits(down), f64::from_bits(up));
+        assert!(down <= f64::from_bits(near) && f64::from_bits(near) <= up && (down == up || down.next_up() == up), "{}({:e})", name, x);
+    }
+
+    let check = |f: Function, x: f64, expected: f64, flags: Flags| {
+        let mut ctx = Context::new(RoundingMode::TiesToEven);
+        let result = f64::from_bits(f(&F64::from_bits(x.to_bits()), &mut ctx).to_bits());
+        assert!(result.to_bits() == expected.to_bits() || (result.is_nan() && expected.is_nan()), "{:e}: {:e}", x, result);
+        assert_eq!(ctx.flags, flags, "{:e}", x);
+    };
+    check(F64::exp, 0.0, 1.0, Flags::empty());
+    check(F64::exp, f64::NEG_INFINITY, 0.0, Flags::empty());
+    check(F64::exp, 1000.0, f64::INFINITY, Flags::OVERFLOW | Flags::INEXACT);
+    check(F64::exp, -1000.0, 0.0, Flags::UNDERFLOW | Flags::INEXACT);
+    check(F64::exp2, -3.0, 0.125, Flags::empty());
+    check(F64::exp10, 22.0, 1e22, Flags::empty());
+    check(F64::exp10, 23.0, 1e23, Flags::INEXACT);
+    check(F64::expm1, -0.0, -0.0, Flags::empty());
+    check(F64::expm1, f64::NEG_INFINITY, -1.0, Flags::empty());
+    check(F64::expm1, 1e-300, 1e-300, Flags::INEXACT);
+    check(F64::ln, 1.0, 0.0, Flags::empty());
+    check(F64::ln, -0.0, f64::NEG_INFINITY, Flags::DIVIDE_BY_ZERO);
+    check(F64::ln, -1.0, f64::NAN, Flags::INVALID);
+    check(F64::log1p, -1.0, f64::NEG_INFINITY, Flags::DIVIDE_BY_ZERO);
+    check(F64::log1p, -2.0, f64::NAN, Flags::INVALID);
+    check(F64::log1p, 1e-300, 1e-300, Flags::INEXACT);
+    check(F64::log2, 0.125, -3.0, Flags::empty());
+    check(F64::log10, 1e22, 22.0, Flags::empty());
+    check(F64::log10, 2.0, 2f64.log10(), Flags::INEXACT);
+    check(F64::cbrt, -8.0, -2.0, Flags::empty());
+    check(F64::cbrt, f64::from_bits(8), 2f64.powi(-357), Flags::empty()); // 2^-1071
+    check(F64::cbrt, f64::from_bits(16), 2f64.cbrt() * 2f64.powi(-357), Flags::INEXACT);
+    check(F64::cbrt, -0.0, -0.0, Flags::empty());
+    check(F64::cbrt, f64::NEG_INFINITY, f64::NEG_INFINITY, Flags::empty());
+    println!("Math test passed! ({} of 200000 results differ from the host libm by an ulp)", differ);
+}
+
+fn pow_test() {
+    // powf against the host pow, the exact cases (which must not raise inexact) and IEEE 754's special case table,
+    // and powi and powi_exact.
+    use floatfs::{Context, Flags, RoundingMode, F64};
+    use rand::Rng;
+    let mut rng = rand::rng();
+    let mut differ = 0;
+    for _ in 0..200_000 {
+        let (x, y): (f64, f64) = match rng.random_range(0..3) {
+            0 => (2f64.powf(rng.random_range(-30.0..30.0)), rng.random_range(-40.0..40.0)),
+            1 => (rng.random_range(0.5..2.0), rng.random_range(-2000.0..2000.0)),
+            _ => (-rng.random_range(0.0..10.0), rng.random_range(-60..60) as f64),
+        };
+        let near = F64::from_bits(x.to_bits()).powf(&F64::from_bits(y.to_bits()), &mut Context::new(RoundingMode::TiesToEven));
+        let expected = x.powf(y).to_bits();
+        assert!(near.to_bits().abs_diff(expected) <= 1, "powf({:e}, {:e}): {:x}, host {:x}", x, y, near.to_bits(), expected);
+        differ += (near.to_bits() != expected) as u32;
+    }
+
+    // powi rounds every product like the host's, so it matches bit for bit. powi_exact is powf with an integer y
+    for _ in 0..200_000 {
+        let x = f64::from_bits(rng.random());
+        let n = std::hint::black_box(rng.random_range(-1100..1100)); // a constant n would let llvm expand powi inline
+        let a = F64::from_bits(x.to_bits());
+        let mut ctx = Context::new(RoundingMode::TiesToEven);
+        let result = a.powi(n, &mut ctx);
+        assert!(result.to_bits() == x.powi(n).to_bits() || (result.is_nan() && x.powi(n).is_nan()), "powi({:e}, {})", x, n);
+        let single = floatfs::F32::from_bits((x as f32).to_bits()).powi(n, &mut ctx);
+        assert!(single.to_bits() == (x as f32).powi(n).to_bits() || single.is_nan(), "powi({:e}, {})", x as f32, n);
+        let exact = a.powi_exact(n, &mut ctx);
+        let pow = a.powf(&F64::from_bits((n as f64).to_bits()), &mut ctx);
+        assert!(exact.to_bits() == pow.to_bits() || (exact.is_nan() && pow.is_nan()), "powi_exact({:e}, {})", x, n);
+    }
+
+    let check = |x: f64, y: f64, expected: f64, flags: Flags| {
+        let mut ctx = Context::new(RoundingMode::TowardZero); // exact results can't depend on the mode
+        let result = f64::from_bits(F64::from_bits(x.to_bits()).powf(&F64::from_bits(y.to_bits()), &mut ctx).to_bits());
+        assert!(result.to_bits() == expected.to_bits() || (result.is_nan() && expected.is_nan()), "powf({:e}, {:e}): {:e}", x, y, result);
+        assert_eq!(ctx.flags, flags, "powf({:e}, {:e})", x, y);
+    };
+    let snan = f64::from_bits(0x7FF0000000000001);
+    check(9.0, 0.5, 3.0, Flags::empty());
+    check(3.0, 2.0, 9.0, Flags::empty());
+    check(-3.0, 3.0, -27.0, Flags::empty());
+    check(16.0, -0.25, 0.5, Flags::empty());
+    check(1e10, 3.5, 1e35, Flags::INEXACT);
+    check(2.0, -1074.0, 5e-324, Flags::empty());
+    check(2.0, -1075.0, 0.0, Flags::UNDERFLOW | Flags::INEXACT);
+    check(2.0, 1024.0, f64::MAX, Flags::OVERFLOW | Flags::INEXACT);
+    check(f64::NAN, 0.0, 1.0, Flags::empty());
+    check(1.0, f64::NAN, 1.0, Flags::empty());
+    check(snan, 0.0, f64::NAN, Flags::INVALID);
+    check(-8.0, 1.0 / 3.0, f64::NAN, Flags::INVALID);
+    check(0.0, -1.0, f64::INFINITY, Flags::DIVIDE_BY_ZERO);
+    check(-0.0, -3.0, f64::NEG_INFINITY, Flags::DIVIDE_BY_ZERO);
+    check(-0.0, -2.0, f64::INFINITY, Flags::DIVIDE_BY_ZERO);
+    check(-0.0, f64::NEG_INFINITY, f64::INFINITY, Flags::empty());
+    check(-0.0, 3.0, -0.0, Flags::empty());
+    check(-0.0, 0.5, 0.0, Flags::empty());
+    check(-1.0, f64::INFINITY, 1.0, Flags::empty());
+    check(0.5, f64::NEG_INFINITY, f64::INFINITY, Flags::empty());
+    check(0.5, f64::INFINITY, 0.0, Flags::empty());
+    check(-2.0, f64::INFINITY, f64::INFINITY, Flags::empty());
+    check(f64::NEG_INFINITY, 3.0, f64::NEG_INFINITY, Flags::empty());
+    check(f64::NEG_INFINITY, -3.0, -0.0, Flags::empty());
+    check(f64::NEG_INFINITY, 2.5, f64::INFINITY, Flags::empty());
+    check(f64::INFINITY, -0.5, 0.0, Flags::empty());
+    let mut ctx = Context::new(RoundingMode::TiesToEven);
+    let x = F64::from_bits(3f64.to_bits());
+    assert_eq!(f64::from_bits(x.powi_exact(40, &mut ctx).to_bits()), 3f64.powi(40)); // 3^40 needs 64 bits, so it rounds
+    assert_eq!(ctx.flags, Flags::INEXACT);
+    let mut ctx = Context::new(RoundingMode::TiesToEven);
+    assert_eq!(f64::from_bits(x.powi_exact(33, &mut ctx).to_bits()), 5559060566555523.0); // 3^33 < 2^53
+    assert_eq!(ctx.flags, Flags::empty());
+    println!("Pow test passed! ({} of 200000 results differ from the host pow by an ulp)", differ);
+}
+
+fn trig_test() {
+    // sin, cos and tan against the host libm over the whole range, the directed modes bracketing round to nearest,
+    // sin_cos against sin and cos, and the worst case for binary64 argument reduction (6381956970095103 * 2^797
+    // is within 2^-61 of an odd multiple of pi/2) along with a couple of other huge arguments, checked against
+    // mpmath.
+    use floatfs::{Context, Flags, RoundingMode, F64};
+    use rand::Rng;
+    type Function = fn(&F64, &mut Context) -> F64;
+    type Host = fn(f64) -> f64;
+    let functions: [(&str, Function, Host); 3] = [("sin", F64::sin, f64::sin), ("cos", F64::cos, f64::cos), ("tan", F64::tan, f64::tan)];
+    let mut rng = rand::rng();
+    let mut differ = 0;
+    for i in 0..300_000 {
+        let (name, soft, host) = functions[i % 3];
+        let x = if rng.random() { f64::from_bits(rng.random::<u64>() & !(0x7FF << 52) | rng.random_range(0x3C0..0x7FF) << 52) } else { rng.random_range(-10.0..10.0) };
+        let a = F64::from_bits(x.to_bits());
+        let near = soft(&a, &mut Context::new(RoundingMode::TiesToEven)).to_bits();
+        let expected = host(x).to_bits();
+        // glibc's log10 is only within 2 ulps
+        let tolerance = if name == "log10" { 2 } else { 1 };
+        assert!(near.abs_diff(expected) <= tolerance, "{}({:e}): {:x}, host {:x}", name, x, near, expected);
+        differ += (near != expected) as u32;
+        let down = f64::from_bits(soft(&a, &mut Context::new(RoundingMode::TowardNegative)).to_bits());
+        let up = f64::from_bits(soft(&a, &mut Context::new(RoundingMode::TowardPositive)).to_bits());
+        assert!(down <= f64::from_bits(near) && f64::from_bits(near) <= up && down.next_up() == up, "{}({:e})", name, x);
+
+        let (mut ctx, mut separate) = (Context::new(RoundingMode::TiesToEven), Context::new(RoundingMode::TiesToEven));
+        let (sin, cos) = a.sin_cos(&mut ctx);
+        assert_eq!((sin.to_bits(), cos.to_bits()), (a.sin(&mut separate).to_bits(), a.cos(&mut separate).to_bits()), "sin_cos({:e})", x);
+        assert_eq!(ctx.flags, separate.flags);
+    }
+
+    let check = |f: Function, x: f64, expected: u64, flags: Flags| {
+        let mut ctx = Context::new(RoundingMode::TiesToEven);
+        let result = f(&F64::from_bits(x.to_bits()), &mut ctx).to_bits();
+        assert!(result == expected || (f64::from_bits(result).is_nan() && f64::from_bits(expected).is_nan()), "{:e}: {:x}", x, result);
+        assert_eq!(ctx.flags, flags, "{:e}", x);
+    };
+    let hardest = 6381956970095103.0 * 2f64.powi(797);
+    check(F64::sin, hardest, 0x3FF0000000000000, Flags::INEXACT);
+    check(F64::cos, hardest, 0xBC214AE72E6BA22F, Flags::INEXACT);
+    check(F64::tan, hardest, 0xC3BD9BA9A7975636, Flags::INEXACT);
+    check(F64::sin, 1e300, 0xBFEA2C16B010E385, Flags::INEXACT);
+    check(F64::cos, 1e300, 0xBFE2699022ADC4C1, Flags::INEXACT);
+    check(F64::tan, 1e22, 0xBFFA0F79C1B6B257, Flags::INEXACT);
+    check(F64::sin, -0.0, (-0f64).to_bits(), Flags::empty());
+    check(F64::tan, -0.0, (-0f64).to_bits(), Flags::empty());
+    check(F64::cos, -0.0, 1f64.to_bits(), Flags::empty());
+    check(F64::sin, 1e-300, 1e-300f64.to_bits(), Flags::INEXACT);
+    check(F64::cos, f64::INFINITY, f64::NAN.to_bits(), Flags::INVALID);
+    println!("Trig test passed! ({} of 300000 results differ from the host libm by an ulp)", differ);
+}
+
+fn inverse_trig_test() {
+    // asin, acos, atan and atan2 against the host libm, and atan2's table of signed zeros and infinities.
+    use floatfs::{Context, Flags, RoundingMode, F64};
+    use rand::Rng;
+    use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+    let mut rng = rand::rng();
+    let mut differ = 0;
+    let f = |x: f64| F64::from_bits(x.to_bits());
+    for i in 0..300_000 {
+        let mut ctx = Context::new(RoundingMode::TiesToEven);
+        let x: f64 = if rng.random() { rng.random_range(-1.0..1.0) } else { f64::from_bits(rng.random()) };
+        let y: f64 = f64::from_bits(rng.random());
+        let (soft, host) = match i % 4 {
+            0 => (f(x).asin(&mut ctx), x.asin()),
+            1 => (f(x).acos(&mut ctx), x.acos()),
+            2 => (f(x).atan(&mut ctx), x.atan()),
+            _ => (f(y).atan2(&f(x), &mut ctx), y.atan2(x)),
+        };
+        if host.is_nan() {
+            assert!(soft.is_nan(), "{} {:e} {:e}", i % 4, x, y);
+            continue;
+        }
+        assert!(soft.to_bits().abs_diff(host.to_bits()) <= 1, "{} {:e} {:e}: {:x}, host {:x}", i % 4, x, y, soft.to_bits(), host.to_bits());
+        differ += (soft.to_bits() != host.to_bits()) as u32;
+    }
+
+    let check = |y: f64, x: f64, expected: f64| {
+        let mut ctx = Context::new(RoundingMode::TiesToEven);
+        let result = f64::from_bits(f(y).atan2(&f(x), &mut ctx).to_bits());
+        assert_eq!(result.to_bits(), expected.to_bits(), "atan2({:e}, {:e})", y, x);
+        assert!(ctx.flags.is_empty() || ctx.flags == Flags::INEXACT, "atan2({:e}, {:e})", y, x);
+    };
+    let inf = f64::INFINITY;
+    for sign in [1.0, -1.0] {
+        check(sign * 0.0, -0.0, sign * PI);
+        check(sign * 0.0, 0.0, sign * 0.0);
+        check(sign * 0.0, -2.0, sign * PI);
+        check(sign * 0.0, 2.0, sign * 0.0);
+        check(sign * 2.0, 0.0, sign * FRAC_PI_2);
+        check(sign * 2.0, -0.0, sign * FRAC_PI_2);
+        check(sign * 2.0, -inf, sign * PI);
+        check(sign * 2.0, inf, sign * 0.0);
+        check(sign * inf, 2.0, sign * FRAC_PI_2);
+        check(sign * inf, -inf, sign * 3.0 * FRAC_PI_4);
+        check(sign * inf, inf, sign * FRAC_PI_4);
+        check(sign * 1.0, -1.0, sign * 3.0 * FRAC_PI_4);
+    }
+    let mut ctx = Context::new(RoundingMode::TiesToEven);
+    assert_eq!(f(1.0).acos(&mut ctx).to_bits(), 0);
+    assert_eq!(f(-1.0).acos(&mut ctx).to_bits(), PI.to_bits());
+    assert_eq!(f(-1.0).asin(&mut ctx).to_bits(), (-FRAC_PI_2).to_bits());
+    assert_eq!(f(-inf).atan(&mut ctx).to_bits(), (-FRAC_PI_2).to_bits());
+    assert_eq!(f(-0.0).asin(&mut ctx).to_bits(), (-0f64).to_bits());
+    assert!(!ctx.flags.contains(Flags::INVALID));
+    assert!(f(1.5).asin(&mut ctx).is_nan() && ctx.flags.contains(Flags::INVALID));
+    println!("Inverse trig test passed! ({} of 300000 results differ from the host libm by an ulp)", differ);
+}
+
+fn sinpi_test() {
+    // sinpi and cospi: the host has no sinpi, so sin(pi x) checks them loosely for small x (pi x is rounded there,
+    // which is the error sinpi exists to avoid), and the identities that hold exactly check the rest, along with
+    // the exact results at multiples of a half and IEEE 754's signed zeros.
+    use floatfs::{Context, Flags, RoundingMode, F64};
+    use rand::Rng;
+    use std::f64::consts::PI;
+    let mut rng = rand::rng();
+    let f = |x: f64| F64::from_bits(x.to_bits());
+    let sinpi = |x: f64| f64::from_bits(f(x).sinpi(&mut Context::new(RoundingMode::TiesToEven)).to_bits());
+    let cospi = |x: f64| f64::from_bits(f(x).cospi(&mut Context::new(RoundingMode::TiesToEven)).to_bits());
+    for _ in 0..200_000 {
+        let x: f64 = rng.random_range(-0.25..0.25);
+        assert!(sinpi(x).to_bits().abs_diff((PI * x).sin().to_bits()) <= 2, "sinpi({:e})", x);
+        assert!(cospi(x).to_bits().abs_diff((PI * x).cos().to_bits()) <= 1, "cospi({:e})", x);
+        // sin(pi (1 - y)) = sin(pi y), cos(pi y) = sin(pi (1/2 - y)), and a period of 2, where those are exact
+        let y = rng.random_range(0.5..1.0);
+        assert_eq!(sinpi(y).to_bits(), sinpi(1.0 - y).to_bits(), "sinpi({:e})", y);
+        let z = rng.random_range(0.25..0.5);
+        assert_eq!(cospi(z).to_bits(), sinpi(0.5 - z).to_bits(), "cospi({:e})", z);
+        let big = f64::from_bits(rng.random_range(0x4330000000000000..0x7FF0000000000000));
+        assert_eq!(sinpi(big).to_bits(), 0, "sinpi({:e})", big); // past 2^52 everything is an even integer
+        let w = rng.random_range(-1e6..1e6);
+        if w + 2.0 - 2.0 == w {
+            assert_eq!(sinpi(w + 2.0).to_bits(), sinpi(w).to_bits(), "sinpi({:e})", w);
+        }
+    }
+
+    let check = |f: fn(&F64, &mut Context) -> F64, x: f64, expected: f64, flags: Flags| {
+        let mut ctx = Context::new(RoundingMode::TiesToEven);
+        let result = f64::from_bits(f(&F64::from_bits(x.to_bits()), &mut ctx).to_bits());
+        assert!(result.to_bits() == expected.to_bits() || (result.is_nan() && expected.is_nan()), "{:e}: {:e}", x, result);
+        assert_eq!(ctx.flags, flags, "{:e}", x);
+    };
+    check(F64::sinpi, 0.5, 1.0, Flags::empty());
+    check(F64::sinpi, -1.5, 1.0, Flags::empty());
+    check(F64::sinpi, 3.0, 0.0, Flags::empty());
+    check(F64::sinpi, -3.0, -0.0, Flags::empty());
+    check(F64::sinpi, -0.0, -0.0, Flags::empty());
+    check(F64::sinpi, 0.25, std::f64::consts::FRAC_1_SQRT_2, Flags::INEXACT);
+    check(F64::cospi, -0.0, 1.0, Flags::empty());
+    check(F64::cospi, 1.0, -1.0, Flags::empty());
+    check(F64::cospi, -2.5, 0.0, Flags::empty());
+    check(F64::cospi, 1e300, 1.0, Flags::empty());
+    check(F64::cospi, f64::INFINITY, f64::NAN, Flags::INVALID);
+    // cos(pi x) for tiny x is just below 1, which rounding down has to see
+    let mut ctx = Context::new(RoundingMode::TowardZero);
+    assert_eq!(f(1e-200).cospi(&mut ctx).to_bits(), 1f64.next_down().to_bits());
+    println!("Sinpi test passed!");
+}
+
+fn hyperbolic_test() {
+    // the hyperbolic functions and their inverses against the host libm, arguments near the overflow threshold that
+    // exp itself can't take, and the special values.
+    use floatfs::{Context, Flags, RoundingMode, F64};
+    use rand::Rng;
+    // std's asinh, acosh and atanh are its own formulas, which overflow for huge x and lose bits near 1, so those
+    // go straight to the C library
+    extern "C" {
+        fn asinh(x: f64) -> f64;
+        fn acosh(x: f64) -> f64;
+        fn atanh(x: f64) -> f64;
+    }
+    let mut rng = rand::rng();
+    let mut differ = 0;
+    let f = |x: f64| F64::from_bits(x.to_bits());
+    for i in 0..300_000 {
+        let mut ctx = Context::new(RoundingMode::TiesToEven);
+        let x: f64 = match rng.random_range(0..3) {
+            0 => rng.random_range(-712.0..712.0),
+            1 => rng.random_range(-1.0..1.0) * 2f64.powi(-rng.random_range(0..60)),
+            _ => f64::from_bits(rng.random()),
+        };
+        let (soft, host) = match i % 6 {
+            0 => (f(x).sinh(&mut ctx), x.sinh()),
+            1 => (f(x).cosh(&mut ctx), x.cosh()),
+            2 => (f(x).tanh(&mut ctx), x.tanh()),
+            3 => (f(x).asinh(&mut ctx), unsafe { asinh(x) }),
+            4 => (f(1.0 + x.abs()).acosh(&mut ctx), unsafe { acosh(1.0 + x.abs()) }),
+            _ => (f(x).atanh(&mut ctx), unsafe { atanh(x) }),
+        };
+        if host.is_nan() {
+            assert!(soft.is_nan(), "{} {:e}", i % 6, x);
+            continue;
+        }
+        // glibc's hyperbolics are only within 2 ulps
+        assert!(soft.to_bits().abs_diff(host.to_bits()) <= 2, "{} {:e}: {:x}, host {:x}", i % 6, x, soft.to_bits(), host.to_bits());
+        differ += (soft.to_bits() != host.to_bits()) as u32;
+    }
+
+    let check = |f: fn(&F64, &mut Context) -> F64, x: f64, expected: f64, flags: Flags| {
+        let mut ctx = Context::new(RoundingMode::TiesToEven);
+        let result = f64::from_bits(f(&F64::from_bits(x.to_bits()), &mut ctx).to_bits());
+        assert!(result.to_bits() == expected.to_bits() || (result.is_nan() && expected.is_nan()), "{:e}: {:e}", x, result);
+        assert_eq!(ctx.flags, flags, "{:e}", x);
+    };
+    let inf = f64::INFINITY;
+    check(F64::cosh, 710.0, 1.1169973830808555e308, Flags::INEXACT);
+    check(F64::sinh, -710.0, -1.1169973830808555e308, Flags::INEXACT);
+    check(F64::cosh, 711.0, inf, Flags::OVERFLOW | Flags::INEXACT);
+    check(F64::cosh, -0.0, 1.0, Flags::empty());
+    check(F64::cosh, -inf, inf, Flags::empty());
+    check(F64::sinh, -0.0, -0.0, Flags::empty());
+    check(F64::sinh, -inf, -inf, Flags::empty());
+    check(F64::sinh, 1e-300, 1e-300, Flags::INEXACT);
+    check(F64::tanh, -inf, -1.0, Flags::empty());
+    check(F64::tanh, 1000.0, 1.0, Flags::INEXACT);
+    check(F64::asinh, -0.0, -0.0, Flags::empty());
+    check(F64::asinh, inf, inf, Flags::empty());
+    check(F64::asinh, -1e308, -709.889355822726, Flags::INEXACT);
+    check(F64::acosh, 1e308, 709.889355822726, Flags::INEXACT);
+    check(F64::acosh, 1.0, 0.0, Flags::empty());
+    check(F64::acosh, 0.5, f64::NAN, Flags::INVALID);
+    check(F64::acosh, -inf, f64::NAN, Flags::INVALID);
+    check(F64::atanh, -1.0, -inf, Flags::DIVIDE_BY_ZERO);
+    check(F64::atanh, 2.0, f64::NAN, Flags::INVALID);
+    check(F64::atanh, -0.0, -0.0, Flags::empty());
+    // tanh of a large argument stays below 1 when rounding toward zero
+    let mut ctx = Context::new(RoundingMode::TowardZero);
+    assert_eq!(f(40.0).tanh(&mut ctx).to_bits(), 1f64.next_down().to_bits());
+    println!("Hyperbolic test passed! ({} of 300000 results differ from the host libm)", differ);
+}
+
+fn hypot_test() {
+    // hypot against the host libm, including arguments whose squares overflow or underflow, then exact results, a
+    // halfway case (which has to round to even, not just land near it) and the special values.
+    use floatfs::{Context, Flags, RoundingMode, F64};
+    use rand::Rng;
+    let mut rng = rand::rng();
+    let mut differ = 0;
+    let f = |x: f64| F64::from_bits(x.to_bits());
+    for _ in 0..200_000 {
+        let x = f64::from_bits(rng.random_range(0..0x7FF0000000000000) | (rng.random::<u64>() & 1 << 63));
+        let y = if rng.random() { f64::from_bits(rng.random_range(0..0x7FF0000000000000)) } else { x * rng.random_range(0.0..2.0) };
+        let soft = f(x).hypot(&f(y), &mut Context::new(RoundingMode::TiesToEven)).to_bits();
+        let host = x.hypot(y).to_bits();
+        assert!(soft.abs_diff(host) <= 1, "hypot({:e}, {:e}): {:x}, host {:x}", x, y, soft, host);
+        differ += (soft != host) as u32;
+    }
+
+    let check = |x: f64, y: f64, expected: f64, flags: Flags| {
+        let mut ctx = Context::new(RoundingMode::TiesToEven);
+        let result = f64::from_bits(f(x).hypot(&f(y), &mut ctx).to_bits());
+        assert!(result.to_bits() == expected.to_bits() || (result.is_nan() && expected.is_nan()), "hypot({:e}, {:e}): {:e}", x, y, result);
+        assert_eq!(ctx.flags, flags, "hypot({:e}, {:e})", x, y);
+    };
+    let (inf, max) = (f64::INFINITY, f64::MAX);
+    check(3.0, -4.0, 5.0, Flags::empty());
+    check(3.0 * 2f64.powi(1000), 4.0 * 2f64.powi(1000), 5.0 * 2f64.powi(1000), Flags::empty());
+    check(f64::from_bits(3), f64::from_bits(4), f64::from_bits(5), Flags::empty()); // 3, 4 and 5 times 2^-1074
+    check(14859783102.102213, -9762704397.085625, 17779863638.0147, Flags::INEXACT);
+    check(max / 2.0, max / 2.0, max / 2.0 * std::f64::consts::SQRT_2, Flags::INEXACT);
+    check(max, max, inf, Flags::OVERFLOW | Flags::INEXACT);
+    check(-5.0, 0.0, 5.0, Flags::empty());
+    check(-0.0, -0.0, 0.0, Flags::empty());
+    check(inf, f64::NAN, inf, Flags::empty());
+    check(f64::NAN, -inf, inf, Flags::empty());
+    check(f64::NAN, 1.0, f64::NAN, Flags::empty());
+    let mut ctx = Context::new(RoundingMode::TiesToEven);
+    assert!(F64::from_bits(0x7FF0000000000001).hypot(&f(inf), &mut ctx).is_nan() && ctx.flags == Flags::INVALID);
+    // a result just above a representable value, far below an ulp, still rounds up when asked to
+    let mut ctx = Context::new(RoundingMode::TowardPositive);
+    assert_eq!(f(1.0).hypot(&f(1e-300), &mut ctx).to_bits(), 1f64.next_up().to_bits());
+    println!("Hypot test passed! ({} of 200000 results differ from the host libm by an ulp)", differ);
+}
+
+fn fdim_test() {
+    // fdim, fmax and fmin against the host libm, on special values and random doubles and floats, except for the
+    // sign of a zero from fmax or fmin of opposite zeros, which C leaves open (glibc gives the first operand)
+    // and is pinned here, and signaling nans, which glibc turns into a nan instead of ignoring. then flags.
+    use floatfs::{Context, Flags, RoundingMode, F32, F64};
+    use rand::Rng;
+    extern "C" {
+        fn fdim(x: f64, y: f64) -> f64;
+        fn fmax(x: f64, y: f64) -> f64;
+        fn fmin(x: f64, y: f64) -> f64;
+        fn fdimf(x: f32, y: f32) -> f32;
+        fn fmaxf(x: f32, y: f32) -> f32;
+        fn fminf(x: f32, y: f32) -> f32;
+    }
+    let mut rng = rand::rng();
+    let same = |a: u64, b: u64, nan: bool| a == b || nan;
+    let specials = [0.0, -0.0, 1.0, -1.0, f64::MIN_POSITIVE, 5e-324, f64::MAX, -f64::MAX, f64::INFINITY, f64::NEG_INFINITY, f64::NAN];
+    let mut values: Vec<f64> = specials.to_vec();
+    values.extend((0..300).map(|_| f64::from_bits(rng.random::<u64>())).filter(|v| !v.is_nan()));
+    for &x in &values {
+        for &y in &values {
+            let (a, b, mut ctx) = (F64::from_bits(x.to_bits()), F64::from_bits(y.to_bits()), Context::new(RoundingMode::TiesToEven));
+            let host = unsafe { [fdim(x, y), fmax(x, y), fmin(x, y)] };
+            let soft = [a.fdim(&b, &mut ctx), a.fmax(&b, &mut ctx), a.fmin(&b, &mut ctx)].map(|r| f64::from_bits(r.to_bits()));
+            for (i, (s, h)) in soft.iter().zip(host).enumerate() {
+                let zeros = x == 0.0 && y == 0.0 && i > 0;
+                assert!(same(s.to_bits(), h.to_bits(), s.is_nan() && h.is_nan()) || zeros && *s == 0.0, "{} {:e} {:e}: {:e}, host {:e}", i, x, y, s, h);
+            }
+            if x == 0.0 && y == 0.0 {
+                assert!(!soft[1].is_sign_negative() || x.is_sign_negative() && y.is_sign_negative(), "fmax {:e} {:e}", x, y);
+                assert!(soft[2].is_sign_negative() || x.is_sign_positive() && y.is_sign_positive(), "fmin {:e} {:e}", x, y);
+            }
+            // the positive difference is the difference, rounded in the context's mode
+            let mode = [RoundingMode::TowardZero, RoundingMode::TowardPositive, RoundingMode::TowardNegative][rng.random_range(0..3)];
+            let (mut ctx, mut expected) = (Context::new(mode), Context::new(mode));
+            let difference = if x > y { a.subtract(&b, &mut expected) } else { F64::zero(false) };
+            assert!(same(a.fdim(&b, &mut ctx).to_bits(), difference.to_bits(), x.is_nan() || y.is_nan()));
+            assert!(ctx.flags == expected.flags || x.is_nan() || y.is_nan());
+        }
+    }
+    for _ in 0..200_000 {
+        let (x, y) = (f32::from_bits(rng.random::<u32>()), f32::from_bits(rng.random::<u32>()));
+        if x.is_nan() && x.to_bits() & 1 << 22 == 0 || y.is_nan() && y.to_bits() & 1 << 22 == 0 {
+            continue; // signaling
+        }
+        let (a, b, mut ctx) = (F32::from_bits(x.to_bits()), F32::from_bits(y.to_bits()), Context::new(RoundingMode::TiesToEven));
+        let host = unsafe { [fdimf(x, y), fmaxf(x, y), fminf(x, y)] };
+        let soft = [a.fdim(&b, &mut ctx), a.fmax(&b, &mut ctx), a.fmin(&b, &mut ctx)].map(|r| f32::from_bits(r.to_bits()));
+        for (i, (s, h)) in soft.iter().zip(host).enumerate() {
+            assert!(s.to_bits() == h.to_bits() || s.is_nan() && h.is_nan(), "{} {:e} {:e}: {:e}, host {:e}", i, x, y, s, h);
+        }
+    }
+
+    let f = |x: f64| F64::from_bits(x.to_bits());
+    let signaling = F64::from_bits(0x7FF0000000000001);
+    let mut ctx = Context::new(RoundingMode::TiesToEven);
+    assert_eq!(signaling.fmax(&f(1.0), &mut ctx).to_bits(), 1f64.to_bits());
+    assert_eq!(f(-2.0).fmin(&signaling, &mut ctx).to_bits(), (-2f64).to_bits());
+    assert_eq!(ctx.flags, Flags::INVALID);
+    let mut ctx = Context::new(RoundingMode::TiesToEven);
+    assert!(f(f64::NAN).fmax(&f(f64::NAN), &mut ctx).is_nan() && f(f64::NAN).fmin(&f(1.0), &mut ctx).to_bits() == 1f64.to_bits());
+    assert!(f(f64::NAN).fdim(&f(1.0), &mut ctx).is_nan() && ctx.flags == Flags::empty());
+    assert!(signaling.fdim(&f(1.0), &mut ctx).is_nan() && ctx.flags == Flags::INVALID);
+    let mut ctx = Context::new(RoundingMode::TiesToEven);
+    assert_eq!(f(f64::MAX).fdim(&f(-f64::MAX), &mut ctx).to_bits(), f64::INFINITY.to_bits());
+    assert_eq!(ctx.flags, Flags::OVERFLOW | Flags::INEXACT);
+    let mut ctx = Context::new(RoundingMode::TiesToEven);
+    assert_eq!(f(f64::INFINITY).fdim(&f(f64::INFINITY), &mut ctx).to_bits(), 0);
+    assert_eq!(f(-3.0).fdim(&f(2.0), &mut ctx).to_bits(), 0);
+    assert_eq!(f(3.0).fdim(&f(-2.0), &mut ctx).to_bits(), 5f64.to_bits());
+    assert_eq!(ctx.flags, Flags::empty());
+    println!("Fdim test passed!");
+}
+
+fn lerp_test() {
+    // midpoint and lerp against the superaccumulator, which sums a/2 + b/2 and a + tb - ta exactly and rounds once,
+    // in every mode, on arguments spread over the whole range (so the terms of lerp's sum are often far apart
+    // and cancel), then C++20's guarantees: exact endpoints, lerp(a, a, t) = a, bounded and monotonic in t.
+    use floatfs::sum::Superaccumulator;
+    use floatfs::{Context, Flags, RoundingMode, F64};
+    use rand::Rng;
+    let mut rng = rand::rng();
+    let f = |x: f64| F64::from_bits(x.to_bits());
+    let host = |x: F64| f64::from_bits(x.to_bits());
+    let modes = [RoundingMode::TiesToEven, RoundingMode::TiesToAway, RoundingMode::TowardZero, RoundingMode::TowardPositive, RoundingMode::TowardNegative];
+    let same = |got: f64, want: f64| got.to_bits() == want.to_bits() || got == 0.0 && want == 0.0;
+    let random = |rng: &mut rand::rngs::ThreadRng| {
+        let sign = if rng.random_bool(0.5) { -1.0 } else { 1.0 };
+        match rng.random_range(0..4) {
+            0 => f64::from_bits(rng.random_range(0..0x7FF0000000000000)) * sign,
+            1 => rng.random_range(0.0..4.0) * sign,
+            2 => rng.random_range(1..64) as f64 * 2f64.powi(rng.random_range(-1070..1000)) * sign,
+            _ => [0.0, 1.0, 0.5, f64::MAX, f64::MIN_POSITIVE, 5e-324][rng.random_range(0..6)] * sign,
+        }
+    };
+    for _ in 0..300_000 {
+        let (a, b, t) = (random(&mut rng), random(&mut rng), random(&mut rng));
+        // and t mostly in [0, 1], and sometimes a tiny t against a huge b - a
+        let t = match rng.random_range(0..4) {
+            0 => t,
+            1 => 2f64.powi(-rng.random_range(0..200)),
+            _ => rng.random_range(0.0..1.0),
+        };
+        let b = if rng.random_bool(0.1) { (1.0 - a) / t } else { b };
+        if !b.is_finite() {
+            continue;
+        }
+        let mode = modes[rng.random_range(0..5)];
+        let mut exact = Superaccumulator::new();
+        exact.add_product(&Float::new(a), &Float::new(0.5));
+        exact.add_product(&Float::new(b), &Float::new(0.5));
+        let want = exact.value_with_rounding(mode).to_f64();
+        let got = host(f(a).midpoint(&f(b), &mut Context::new(mode)));
+        assert!(same(got, want), "midpoint({:e}, {:e}) {:?}: {:e}, want {:e}", a, b, mode, got, want);
+
+        let mut exact = Superaccumulator::new();
+        exact.add(&Float::new(a));
+        exact.add_product(&Float::new(t), &Float::new(b));
+        exact.add_product(&Float::new(-t), &Float::new(a));
+        let want = exact.value_with_rounding(mode).to_f64();
+        let got = host(f(a).lerp(&f(b), &f(t), &mut Context::new(mode)));
+        assert!(same(got, want), "lerp({:e}, {:e}, {:e}) {:?}: {:e}, want {:e}", a, b, t, mode, got, want);
+    }
+
+    let lerp = |a: f64, b: f64, t: f64| host(f(a).lerp(&f(b), &f(t), &mut Context::new(RoundingMode::TiesToEven)));
+    for _ in 0..2_000 {
+        let (a, b) = (random(&mut rng), random(&mut rng));
+        assert_eq!(lerp(a, b, 0.0).to_bits(), a.to_bits());
+        assert_eq!(lerp(a, b, 1.0).to_bits(), b.to_bits());
+        let t = random(&mut rng);
+        if (a * t).is_finite() {
+            assert_eq!(lerp(a, a, t).to_bits(), a.to_bits(), "lerp({:e}, {:e}, {:e})", a, a, t);
+        }
+        let mut ts: Vec<f64> = (0..50).map(|_| rng.random_range(-0.5..1.5)).collect();
+        ts.sort_by(f64::total_cmp);
+        let results: Vec<f64> = ts.iter().map(|&t| lerp(a, b, t)).collect();
+        for (pair, window) in ts.windows(2).zip(results.windows(2)) {
+            let rising = if b > a { window[0] <= window[1] } else { window[0] >= window[1] };
+            assert!(rising || pair[0] == pair[1], "lerp({:e}, {:e}) at {:?}: {:?}", a, b, pair, window);
+        }
+        for (&t, &r) in ts.iter().zip(&results) {
+            if (0.0..=1.0).contains(&t) {
+                assert!(a.min(b) <= r && r <= a.max(b), "lerp({:e}, {:e}, {:e}) = {:e}", a, b, t, r);
+            }
+        }
+    }
+    // the naive formula loses this one to cancellation: 1 + 2^-100 (-2^100 + 3 - 1) = 2^-99
+    assert_eq!(lerp(1.0, -(2f64.powi(100)) + 3.0 * 2f64.powi(48), 2f64.powi(-100)), 2f64.powi(-52) * 3.0 - 2f64.powi(-100));
+    assert_eq!(lerp(-0.0, -0.0, 0.5).to_bits(), (-0.0f64).to_bits());
+    assert_eq!(lerp(1.0, -1.0, 0.5).to_bits(), 0);
+    assert_eq!(lerp(1.0, 3.0, 0.5), 2.0);
+    assert!(lerp(1.0, f64::INFINITY, 0.0).is_nan()); // inf times 0, as the formula goes
+    let mut ctx = Context::new(RoundingMode::TiesToEven);
+    assert_eq!(host(f(f64::MAX).midpoint(&f(f64::MAX), &mut ctx)), f64::MAX);
+    assert_eq!(host(f(-f64::MAX).midpoint(&f(f64::MAX), &mut ctx)).to_bits(), 0);
+    assert_eq!(host(f(5e-324).midpoint(&f(0.0), &mut ctx)), 0.0); // half the smallest subnormal, a tie to even
+    assert_eq!(ctx.flags, Flags::UNDERFLOW | Flags::INEXACT);
+    assert_eq!(host(f(5e-324).midpoint(&f(1e-323), &mut Context::new(RoundingMode::TowardPositive))), 1e-323);
+    assert!(f(f64::INFINITY).midpoint(&f(f64::NEG_INFINITY), &mut ctx).is_nan());
+    println!("Lerp test passed!");
+}
+
+fn lround_test() {
+    // lround and lrint against glibc's, in every mode for lrint (set on the host around each call, with x86's
+    // fenv constants), comparing the invalid and inexact flags too, on values around halves, the edges of the
+    // i32 and i64 ranges and random bit patterns. the 32-bit forms are checked against the 64-bit ones.
+    use floatfs::{Context, Flags, RoundingMode, F64};
+    use rand::Rng;
+    extern "C" {
+        fn lround(x: f64) -> i64;
+        fn lrint(x: f64) -> i64;
+        fn fesetround(mode: i32) -> i32;
+        fn feclearexcept(excepts: i32) -> i32;
+        fn fetestexcept(excepts: i32) -> i32;
+    }
+    const INVALID: i32 = 0x01;
+    const INEXACT: i32 = 0x20;
+    let host_flags = |raised: i32| {
+        let mut flags = Flags::empty();
+        if raised & INVALID != 0 {
+            flags.raise(Flags::INVALID);
+        }
+        if raised & INEXACT != 0 {
+            flags.raise(Flags::INEXACT);
+        }
+        flags
+    };
+    let modes = [
+        (RoundingMode::TiesToEven, 0),
+        (RoundingMode::TowardNegative, 0x400),
+        (RoundingMode::TowardPositive, 0x800),
+        (RoundingMode::TowardZero, 0xC00),
+    ];
+    let mut rng = rand::rng();
+    let mut values = vec![0.0, -0.0, 0.5, -0.5, 1.5, 2.5, -2.5, 0.49999999999999994, f64::INFINITY, f64::NEG_INFINITY, f64::NAN, 5e-324];
+    for edge in [2f64.powi(31), 2f64.powi(63), 2f64.powi(52)] {
+        for sign in [1.0, -1.0] {
+            values.extend([edge, edge.next_up(), edge.next_down(), edge - 0.5, edge + 0.5, edge - 1.5].map(|v| v * sign));
+        }
+    }
+    values.extend((0..100_000).map(|_| rng.random_range(-1000..1000) as f64 * 0.5 + rng.random_range(-1.0..1.0) * 2f64.powi(-rng.random_range(1..60))));
+    values.extend((0..100_000).map(|_| f64::from_bits(rng.random::<u64>() & !(0x3FF << 53)))); // exponents up to 2^67
+    for x in values {
+        let s = F64::from_bits(x.to_bits());
+        let mut ctx = Context::new(RoundingMode::TiesToEven);
+        let soft = s.lround(&mut ctx);
+        let (host, raised) = unsafe {
+            feclearexcept(INVALID | INEXACT);
+            let result = lround(x);
+            (result, fetestexcept(INVALID | INEXACT))
+        };
+        assert_eq!(soft, host, "lround({:e})", x);
+        assert_eq!(ctx.flags, host_flags(raised & INVALID), "lround({:e}) flags", x);
+        let mut narrow = Context::new(RoundingMode::TiesToEven);
+        let expected = i32::try_from(host).ok().filter(|_| raised & INVALID == 0);
+        assert_eq!(s.lround_i32(&mut narrow), expected.unwrap_or(i32::MIN), "lround_i32({:e})", x);
+        assert_eq!(narrow.flags, if expected.is_some() { Flags::empty() } else { Flags::INVALID });
+
+        for (mode, host_mode) in modes {
+            let mut ctx = Context::new(mode);
+            let soft = s.lrint(&mut ctx);
+            let (host, raised) = unsafe {
+                fesetround(host_mode);
+                feclearexcept(INVALID | INEXACT);
+                let result = lrint(x);
+                let raised = fetestexcept(INVALID | INEXACT);
+                fesetround(0);
+                (result, raised)
+            };
+            assert_eq!(soft, host, "lrint({:e}) {:?}", x, mode);
+            assert_eq!(ctx.flags, host_flags(raised), "lrint({:e}) {:?} flags", x, mode);
+            let mut narrow = Context::new(mode);
+            let expected = i32::try_from(host).ok().filter(|_| raised & INVALID == 0);
+            assert_eq!(s.lrint_i32(&mut narrow), expected.unwrap_or(i32::MIN), "lrint_i32({:e}) {:?}", x, mode);
+            assert_eq!(narrow.flags.contains(Flags::INVALID), expected.is_none());
+        }
+    }
+    // halfway cases go away from zero whatever the mode, for lround only
+    let half = F64::from_bits(2.5f64.to_bits());
+    assert_eq!(half.lround(&mut Context::new(RoundingMode::TowardZero)), 3);
+    assert_eq!(half.lrint(&mut Context::new(RoundingMode::TiesToEven)), 2);
+    assert_eq!(half.lrint(&mut Context::new(RoundingMode::TiesToAway)), 3);
+    println!("Lround test passed!");
+}
+
+fn integer_predicates_test() {
+    // fits_in_* against the conversions: it fits exactly when the conversion succeeds without raising inexact in
+    // every mode. is_integer against the host's trunc on the formats that widen to f64 exactly. special values,
+    // and integers at the ends of each range and a half either side of them
+    use floatfs::format::{Binary16, Binary32, Binary64, Extended, Minifloat};
+    use floatfs::{random, Context, Format, RoundingMode, SoftFloat, F64};
+    use rand::RngCore;
+
+    const MODES: [RoundingMode; 5] = [
+        RoundingMode::TiesToEven,
+        RoundingMode::TiesToAway,
+        RoundingMode::TowardZero,
+        RoundingMode::TowardPositive,
+        RoundingMode::TowardNegative,
+    ];
+
+    fn check<F: Format>(x: &SoftFloat<F>, widens: bool) {
+        fn converts<T>(convert: impl Fn(RoundingMode, &mut Context) -> Option<T>) -> bool {
+            MODES.iter().all(|&mode| {
+                let mut ctx = Context::new(mode);
+                convert(mode, &mut ctx).is_some() && ctx.flags.is_empty()
+            })
+        }
+        assert_eq!(x.fits_in_i32(), converts(|mode, ctx| x.to_i32(mode, ctx)), "{} {x:?}", F::NAME);
+        assert_eq!(x.fits_in_i64(), converts(|mode, ctx| x.to_i64(mode, ctx)), "{} {x:?}", F::NAME);
+        assert_eq!(x.fits_in_u32(), converts(|mode, ctx| x.to_u32(mode, ctx)), "{} {x:?}", F::NAME);
+        assert_eq!(x.fits_in_u64(), converts(|mode, ctx| x.to_u64(mode, ctx)), "{} {x:?}", F::NAME);
+        if widens {
+            let wide = f64::from_bits(x.convert::<Binary64>(&mut Context::default()).to_bits());
+            assert_eq!(x.is_integer(), wide.is_finite() && wide.trunc() == wide, "{} {x:?}", F::NAME);
+        }
+    }
+
+    fn run<F: Format>(widens: bool) {
+        let mut bits = || rand::rng().next_u64();
+        for _ in 0..200_000 {
+            check(&random::special::<F>(&mut bits), widens);
+        }
+        for edge in [1u64 << 31, 1 << 32, 1 << 63, u64::MAX] {
+            for value in [edge as f64, -(edge as f64), edge as f64 - 1.0, edge as f64 + 1.0, -(edge as f64) - 1.0] {
+                for offset in [0.0, 0.5, -0.5] {
+                    check(&F64::from_bits((value + offset).to_bits()).convert::<F>(&mut Context::default()), widens);
+                }
+            }
+        }
+        for value in [i32::MIN as i64, i32::MAX as i64, i64::MIN, i64::MAX, u32::MAX as i64, 0, -1] {
+            for mode in MODES {
+                check(&SoftFloat::<F>::from_i64(value, &mut Context::new(mode)), widens);
+            }
+        }
+    }
+    run::<Binary16>(true);
+    run::<Binary32>(true);
+    run::<Binary64>(true);
+    run::<Minifloat>(true);
+    run::<Extended>(false);
+    println!("Integer predicates test passed!");
+}
+
+#[cfg(feature = "bigint")]
+fn ratio_test() {
+    // to_exact_ratio: the fraction times its denominator is the double scaled by a power of two, exactly, and it's
+    // in lowest terms. best_rational: against known answers (Python's limit_denominator) and against trying every
+    // denominator up to a small bound, with -x giving minus the result for x.
+    use floatfs::Float;
+    use num_bigint::BigInt;
+    use rand::Rng;
+
+    fn gcd(a: &BigInt, b: &BigInt) -> BigInt {
+        let (mut a, mut b) = (BigInt::from(a.magnitude().clone()), BigInt::from(b.magnitude().clone()));
+        while b != BigInt::ZERO {
+            let r = &a % &b;
+            (a, b) = (b, r);
+        }
+        a
+    }
+    let ratio = |x: f64, n: u64| {
+        let (p, q) = Float::new(x).best_rational(n).unwrap();
+        (p.to_string(), q.to_string())
+    };
+
+    let mut rng = rand::rng();
+    for i in 0..200_000 {
+        let x = f64::from_bits(rng.random::<u64>());
+        let x = if i % 2 == 0 { x } else { (rng.random::<f64>() - 0.5) * 2f64.powi(rng.random_range(-60..60)) };
+        let Some((n, d)) = Float::new(x).to_exact_ratio() else {
+            assert!(!x.is_finite(), "{:e}", x);
+            continue;
+        };
+        let k = d.bits() - 1;
+        assert!(d == BigInt::from(1) << k as usize, "{:e}", x);
+        assert!(gcd(&n, &d) == BigInt::from(1), "{:e}", x);
+        // x 2^k = n, scaled in steps small enough that none of them rounds or overflows
+        let (mut scaled, mut left) = (x, k as i32);
+        while left > 0 {
+            scaled *= 2f64.powi(left.min(500));
+            left -= left.min(500);
+        }
+        assert!(n.to_string().parse::<f64>().unwrap() == scaled, "{:e}", x);
+    }
+    assert!(Float::new(-0.0).to_exact_ratio() == Some((BigInt::ZERO, BigInt::from(1))));
+    assert!(Float::new(0.1).to_exact_ratio() == Some((BigInt::from(3602879701896397u64), BigInt::from(1) << 55)));
+    assert!(Float::nan().to_exact_ratio().is_none() && Float::infinity(true).best_rational(7).is_none());
+    assert!(Float::new(0.5).best_rational(0).is_none());
+
+    let pi = std::f64::consts::PI;
+    let s = |p: &str, q: &str| (p.to_string(), q.to_string());
+    assert!(ratio(pi, 7) == s("22", "7"));
+    assert!(ratio(pi, 113) == s("355", "113") && ratio(pi, 1000) == s("355", "113"));
+    assert!(ratio(pi, 1_000_000) == s("3126535", "995207"));
+    assert!(ratio(std::f64::consts::E, 1_000_000_000) == s("1032595833", "379870778"));
+    assert!(ratio(0.1, 10) == s("1", "10") && ratio(1.0 / 3.0, 100) == s("1", "3"));
+    assert!(ratio(2.5, 1) == s("2", "1") && ratio(-2.5, 1) == s("-2", "1"));
+    assert!(ratio(2f64.sqrt(), u64::MAX) == s("6369051672525773", "4503599627370496"));
+    assert!(ratio(5e-324, u64::MAX) == s("0", "1") && ratio(1e300, 3).1 == "1");
+
+    for _ in 0..20_000 {
+        let x = rng.random::<f64>() * 2f64.powi(rng.random_range(-8..8));
+        let limit = rng.random_range(1..60u64);
+        let (p, q) = Float::new(x).best_rational(limit).unwrap();
+        let (n, d) = Float::new(x).to_exact_ratio().unwrap();
+        assert!(q > BigInt::ZERO && q <= BigInt::from(limit) && gcd(&p, &q) == BigInt::from(1), "{} {}", x, limit);
+        // |p/q - x| against |c/m - x| for the two c around x m, as |p d - n q| m against |c d - n m| q
+        let distance = |p: &BigInt, q: &BigInt, other: u64| BigInt::from((p * &d - &n * q).magnitude().clone()) * other;
+        for m in 1..=limit {
+            let below = &n * m / &d;
+            for c in [below.clone(), below + 1] {
+                assert!(distance(&p, &q, m) <= distance(&c, &BigInt::from(m), 1) * &q, "{} {}", x, limit);
+            }
+        }
+        let (np, nq) = Float::new(-x).best_rational(limit).unwrap();
+        assert!(np == -p && nq == q, "{} {}", x, limit);
+    }
+    println!("Ratio test passed!");
+}
+
+fn fixed_test() {
+    // to_fixed against scaling by 2^n in f64 (exact for these magnitudes) and rounding with the host's round
+    // functions, then clamping or wrapping by hand, over random Q formats up to 64 bits, in every mode. from_fixed
+    // against from_i64/from_u64 followed by an exact scaling. then the usual Q15 cases.
+    use floatfs::fixed::{Overflow, QFormat};
+    use floatfs::{Context, Flags, RoundingMode, F32, F64};
+    use rand::Rng;
+
+    let modes = [
+        RoundingMode::TiesToEven,
+        RoundingMode::TiesToAway,
+        RoundingMode::TowardZero,
+        RoundingMode::TowardPositive,
+        RoundingMode::TowardNegative,
+    ];
+    let mut rng = rand::rng();
+    for _ in 0..1_000_000 {
+        let signed = rng.random::<bool>();
+        let bits = rng.random_range(1..=64u32);
+        let integer_bits = rng.random_range(0..=bits - signed as u32);
+        let fraction_bits = bits - signed as u32 - integer_bits;
+        let format = if signed {
+            QFormat::signed(integer_bits, fraction_bits)
+        } else {
+            QFormat::unsigned(integer_bits, fraction_bits)
+        };
+        assert!(format.bits() == bits);
+        let mode = modes[rng.random_range(0..5)];
+        let overflow = if rng.random::<bool>() { Overflow::Saturate } else { Overflow::Wrap };
+        let mut x = rng.random::<f64>() * 2f64.powi(integer_bits as i32 + rng.random_range(-70..3));
+        if rng.random::<bool>() {
+            x = -x;
+        }
+        if rng.random_range(0..10) == 0 {
+            // exactly representable, or a tie between two
+            x = (rng.random_range(-1000..1000) as f64 + [0.0, 0.5][rng.random_range(0..2)]) * 2f64.powi(-(fraction_bits as i32));
+        }
+        let scaled = x * 2f64.powi(fraction_bits as i32);
+        let rounded = match mode {
+            RoundingMode::TiesToEven => scaled.round_ties_even(),
+            RoundingMode::TiesToAway => scaled.round(),
+            RoundingMode::TowardZero => scaled.trunc(),
+            RoundingMode::TowardPositive => scaled.ceil(),
+            RoundingMode::TowardNegative => scaled.floor(),
+        } as i128;
+        let (expected, expected_flags) = if rounded < format.min_raw() || rounded > format.max_raw() {
+            let wrapped = rounded.rem_euclid(1 << bits);
+            let wrapped = if signed && wrapped >= 1 << (bits - 1) { wrapped - (1 << bits) } else { wrapped };
+            let limit = if rounded < 0 { format.min_raw() } else { format.max_raw() };
+            (if overflow == Overflow::Saturate { limit } else { wrapped }, Flags::INVALID)
+        } else {
+            (rounded, if rounded as f64 == scaled { Flags::empty() } else { Flags::INEXACT })
+        };
+        let mut ctx = Context::default();
+        let got = F64::from_bits(x.to_bits()).to_fixed(format, mode, overflow, &mut ctx);
+        assert!(got == expected && ctx.flags == expected_flags, "{:e} {:?} {:?} {:?}: {} {:?}, expected {} {:?}",
+            x, format, mode, overflow, got, ctx.flags, expected, expected_flags);
+
+        // and back: every raw value in range, from the float to its fixed neighbours
+        let raw = rng.random_range(format.min_raw()..=format.max_raw());
+        let mut ctx = Context { rounding: mode, ..Context::default() };
+        let back: F64 = F64::from_fixed(raw, format, &mut ctx);
+        let mut expected_ctx = Context { rounding: mode, ..Context::default() };
+        let integer = if raw < 0 { F64::from_i64(raw as i64, &mut expected_ctx) } else { F64::from_u64(raw as u64, &mut expected_ctx) };
+        let scale = F64::from_bits(2f64.powi(-(fraction_bits as i32)).to_bits());
+        let expected = integer.multiply(&scale, &mut expected_ctx);
+        assert!(back.to_bits() == expected.to_bits() && ctx.flags == expected_ctx.flags, "{} {:?} {:?}", raw, format, mode);
+        let back: F32 = F32::from_fixed(raw, format, &mut Context::default());
+        assert!(f32::from_bits(back.to_bits()) == raw as f32 * 2f32.powi(-(fraction_bits as i32)));
+        // the low bits are all that count
+        let wide = raw + (rng.random_range(-4..4i128) << bits);
+        let back: F64 = F64::from_fixed(raw, format, &mut Context::default());
+        assert!(F64::from_fixed(wide, format, &mut Context::default()).to_bits() == back.to_bits());
+    }
+
+    let q15 = QFormat::signed(0, 15);
+    let fixed = |x: f64, overflow: Overflow| {
+        let mut ctx = Context::default();
+        (F64::from_bits(x.to_bits()).to_fixed(q15, RoundingMode::TiesToEven, overflow, &mut ctx), ctx.flags)
+    };
+    assert!(q15.bits() == 16 && q15.min_raw() == -32768 && q15.max_raw() == 32767);
+    assert!(fixed(0.5, Overflow::Saturate) == (16384, Flags::empty()));
+    assert!(fixed(-1.0, Overflow::Saturate) == (-32768, Flags::empty()));
+    assert!(fixed(1.0, Overflow::Saturate) == (32767, Flags::INVALID));
+    assert!(fixed(1.0, Overflow::Wrap) == (-32768, Flags::INVALID));
+    assert!(fixed(0.3, Overflow::Saturate) == (9830, Flags::INEXACT));
+    assert!(fixed(f64::NAN, Overflow::Wrap) == (0, Flags::INVALID));
+    assert!(fixed(f64::NEG_INFINITY, Overflow::Wrap) == (-32768, Flags::INVALID));
+    assert!(fixed(1e300, Overflow::Wrap) == (0, Flags::INVALID) && fixed(-0.0, Overflow::Wrap) == (0, Flags::empty()));
+    assert!(fixed(-3e-5, Overflow::Saturate) == (-1, Flags::INEXACT) && fixed(5e-324, Overflow::Saturate).0 == 0);
+    let uq = QFormat::unsigned(8, 8);
+    let mut ctx = Context::default();
+    assert!(F64::from_bits((-0.001f64).to_bits()).to_fixed(uq, RoundingMode::TiesToEven, Overflow::Saturate, &mut ctx) == 0);
+    assert!(F64::from_bits((-0.01f64).to_bits()).to_fixed(uq, RoundingMode::TiesToEven, Overflow::Saturate, &mut ctx) == 0);
+    assert!(ctx.flags.contains(Flags::INVALID));
+    assert!(F64::from_fixed(0xFFFF, uq, &mut Context::default()).to_bits() == 255.99609375f64.to_bits());
+    assert!(F64::from_fixed(-1, QFormat::unsigned(64, 0), &mut Context::default()).to_bits() == 2f64.powi(64).to_bits());
+    println!("Fixed test passed!");
+}
+
+fn quantize_test() {
+    // nearest-even quantization against a search over every encoding's value (ties to the even encoding, and past
+    // the largest finite value, overflow once it's nearer the next power of two up, or a tie and the largest
+    // encoding is odd), for random f32s scaled by random powers of two. binary16 against convert too. then
+    // stochastic rounding: always a neighbour, up as often as it should be, and reproducible from the seed. then
+    // amax scaling and the stats, and arithmetic on every pair of encodings of the narrow formats against f64.
+    // the MX formats saturate where the others overflow, so their largest magnitude stands in for a nan.
+    use floatfs::format::Binary16;
+    use floatfs::quantize::{dequantize, quantize, Element, QuantizeStats, Rounding, Saturation, Scaling};
+    use floatfs::{Context, Operation, SoftFloat, F32};
+    use rand::Rng;
+
+    let elements = [
+        Element::BFloat16,
+        Element::Binary16,
+        Element::E4M3,
+        Element::E5M2,
+        Element::E2M3,
+        Element::E3M2,
+        Element::E2M1,
+    ];
+    // the finite nonnegative values in order, as (value, encoding)
+    let table = |element: Element| -> Vec<(f64, u32)> {
+        let codes: Vec<u16> = (0..1u32 << (element.bits() - 1)).map(|c| c as u16).collect();
+        let mut values = vec![0f32; codes.len()];
+        dequantize(&codes, &mut values, element, 0);
+        let mut table: Vec<(f64, u32)> =
+            values.iter().zip(0..).filter(|(v, _)| v.is_finite()).map(|(v, c)| (*v as f64, c)).collect();
+        table.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        table
+    };
+    let tables: Vec<Vec<(f64, u32)>> = elements.iter().map(|&e| table(e)).collect();
+    // the nonnegative encodings are in order, so a table is indexed by encoding too
+    assert!(tables.iter().all(|t| t.iter().enumerate().all(|(i, v)| v.1 == i as u32)));
+    assert!(tables[2].last().unwrap().0 == 448.0 && tables[3].last().unwrap().0 == 57344.0);
+    assert!(tables[0].len() == 0x7F80 && tables[1].len() == 0x7C00 && tables[2].len() == 0x7F && tables[3].len() == 0x7C);
+    assert!(tables[4..].iter().map(|t| (t.len(), t.last().unwrap().0)).eq([(0x20, 7.5), (0x20, 28.0), (0x8, 6.0)]));
+    // the encoding nearest t >= 0, or None for overflow
+    let nearest = |table: &[(f64, u32)], t: f64| -> Option<u32> {
+        let i = table.partition_point(|&(v, _)| v < t);
+        if i == table.len() {
+            let (max, code) = table[i - 1];
+            let next = max + (max - table[i - 2].0);
+            return if t < (max + next) / 2.0 || t == (max + next) / 2.0 && code % 2 == 0 { Some(code) } else { None };
+        }
+        let (above, below) = (table[i], table[i.saturating_sub(1)]);
+        if above.0 == t || t - below.0 > above.0 - t || t - below.0 == above.0 - t && above.1 % 2 == 0 {
+            Some(above.1)
+        } else {
+            Some(below.1)
+        }
+    };
+
+    let mut rng = rand::rng();
+    for _ in 0..200 {
+        let e = rng.random_range(0..elements.len());
+        let element = elements[e];
+        let k = rng.random_range(-20..20);
+        let input: Vec<f32> = (0..1000)
+            .map(|i| match i % 4 {
+                0 => f32::from_bits(rng.random::<u32>()),
+                1 => (rng.random::<f32>() - 0.5) * 2f32.powi(rng.random_range(-30..30)),
+                // exactly halfway between two encodings, or on one
+                _ => {
+                    let j = rng.random_range(1..tables[e].len());
+                    let (a, b) = (tables[e][j - 1].0, tables[e][j].0);
+                    (if i % 4 == 2 { (a + b) / 2.0 } else { b } * 2f64.powi(-k)) as f32
+                }
+            })
+            .collect();
+        let mut codes = vec![0u16; input.len()];
+        let saturation = if rng.random() { Saturation::Saturating } else { Saturation::NonSaturating };
+        let stats = quantize(&input, &mut codes, element, Rounding::NearestEven, Scaling::Exponent(k), saturation);
+        let mut expected_stats = QuantizeStats { scale_exponent: k, ..QuantizeStats::default() };
+        let sign_bit = 1 << (element.bits() - 1);
+        let (nan, mut infinity) = match element {
+            Element::BFloat16 => (0x7FC0, 0x7F80),
+            Element::Binary16 => (0x7E00, 0x7C00),
+            Element::E4M3 => (0x7F, 0x7F),
+            Element::E5M2 => (0x7E, 0x7C),
+            _ => (sign_bit - 1, sign_bit - 1),
+        };
+        // saturating, infinities and overflow go to the largest finite value instead
+        let mut overflow = if element.has_infinity() { infinity } else { nan };
+        if saturation == Saturation::Saturating {
+            (infinity, overflow) = (tables[e].len() as u32 - 1, tables[e].len() as u32 - 1);
+        }
+        for (x, &code) in input.iter().zip(&codes) {
+            let sign = if x.is_sign_negative() { sign_bit } else { 0 };
+            let expected = if x.is_nan() {
+                nan | sign
+            } else if x.is_infinite() {
+                infinity | sign
+            } else {
+                match nearest(&tables[e], x.abs() as f64 * 2f64.powi(k)) {
+                    Some(c) => {
+                        if c == 0 && *x != 0.0 {
+                            expected_stats.underflows += 1;
+                        }
+                        let error = (x.abs() as f64 - tables[e][c as usize].0 * 2f64.powi(-k)).abs();
+                        expected_stats.max_error = expected_stats.max_error.max(error);
+                        c | sign
+                    }
+                    None => {
+                        expected_stats.overflows += 1;
+                        overflow | sign
+                    }
+                }
+            };
+            assert!(code as u32 == expected, "{:e} {:?} {}: {:x}, expected {:x}", x, element, k, code, expected);
+            if element == Element::Binary16 && k == 0 {
+                let half: SoftFloat<Binary16> = F32::from_bits(x.to_bits()).convert(&mut Context::default());
+                let mut half = half.to_bits();
+                if saturation == Saturation::Saturating && half & 0x7FFF == 0x7C00 {
+                    half -= 1; // convert's infinity, saturated to the largest finite value of the same sign
+                }
+                assert!(half == code || x.is_nan(), "half {half:x} code {code:x} x {x:e} {saturation:?}");
+            }
+        }
+        assert!(stats == expected_stats, "{:?} {:?}", stats, expected_stats);
+        // and back, with the scaling undone
+        let mut back = vec![0f32; input.len()];
+        dequantize(&codes, &mut back, element, k);
+        for ((x, &code), y) in input.iter().zip(&codes).zip(&back) {
+            let magnitude = code as u32 & (sign_bit - 1);
+            match tables[e].get(magnitude as usize) {
+                Some(t) => assert!(*y == (t.0 * 2f64.powi(-k) * if code as u32 >= sign_bit { -1.0 } else { 1.0 }) as f32),
+                None => assert!(y.is_nan() || y.is_infinite() && element.has_infinity()),
+            }
+            assert!(y.is_sign_negative() == x.is_sign_negative(), "{:e} {:e}", x, y);
+        }
+    }
+
+    // stochastic: x a quarter of the way from 1 to the next encoding up rounds up a quarter of the time
+    for (e, element) in elements.into_iter().enumerate() {
+        let ulp = tables[e].iter().find(|t| t.0 > 1.0).unwrap().0 - 1.0;
+        for (fraction, x) in [(0.25, 1.0 + ulp / 4.0), (0.75, 1.0 + 3.0 * ulp / 4.0), (1.0 / 1024.0, 1.0 + ulp / 1024.0)] {
+            let input = vec![x as f32; 100_000];
+            let mut codes = vec![0u8; input.len()];
+            if element.bits() > 8 {
+                let mut wide = vec![0u16; input.len()];
+                quantize(&input, &mut wide, element, Rounding::Stochastic(7), Scaling::Unscaled, Saturation::NonSaturating);
+                let mut again = vec![0u16; input.len()];
+                quantize(&input, &mut again, element, Rounding::Stochastic(7), Scaling::Unscaled, Saturation::NonSaturating);
+                assert!(wide == again);
+                let low = *wide.iter().min().unwrap();
+                codes = wide.iter().map(|&c| (c != low) as u8).collect();
+            } else {
+                quantize(&input, &mut codes, element, Rounding::Stochastic(7), Scaling::Unscaled, Saturation::NonSaturating);
+                let low = *codes.iter().min().unwrap();
+                codes = codes.iter().map(|&c| (c != low) as u8).collect();
+            }
+            let ups = codes.iter().filter(|&&c| c == 1).count() as f64 / input.len() as f64;
+            let sigma = (fraction * (1.0 - fraction) / input.len() as f64).sqrt();
+            assert!((ups - fraction).abs() < 6.0 * sigma, "{:?} {} {}", element, fraction, ups);
+        }
+    }
+    for _ in 0..100 {
+        let e = rng.random_range(0..elements.len());
+        let input: Vec<f32> = (0..1000).map(|_| (rng.random::<f32>() - 0.5) * 2f32.powi(rng.random_range(-20..20))).collect();
+        let mut codes = vec![0u16; input.len()];
+        quantize(&input, &mut codes, elements[e], Rounding::Stochastic(rng.random()), Scaling::Unscaled, Saturation::NonSaturating);
+        for (x, &code) in input.iter().zip(&codes) {
+            let t = x.abs() as f64;
+            let i = tables[e].partition_point(|&(v, _)| v < t);
+            let magnitude = code as u32 & ((1 << (elements[e].bits() - 1)) - 1);
+            let neighbours = [tables[e].get(i).map(|t| t.1), i.checked_sub(1).map(|i| tables[e][i].1)];
+            assert!(neighbours.contains(&Some(magnitude)) || i == tables[e].len(), "{:e} {:?}", x, elements[e]);
+            if tables[e].get(i).is_some_and(|v| v.0 == t) {
+                assert!(magnitude == tables[e][i].1);
+            }
+        }
+    }
+
+    // amax: the largest magnitude lands in the top binade, at or below the largest finite value
+    for _ in 0..1000 {
+        let e = rng.random_range(0..elements.len());
+        let spread = rng.random_range(-40..40);
+        let mut input: Vec<f32> = (0..100).map(|_| (rng.random::<f32>() - 0.5) * 2f32.powi(spread)).collect();
+        input.push(f32::NAN);
+        input.push(f32::NEG_INFINITY);
+        let mut codes = vec![0u16; input.len()];
+        let stats = quantize(&input, &mut codes, elements[e], Rounding::NearestEven, Scaling::Amax, Saturation::NonSaturating);
+        let amax = input.iter().filter(|x| x.is_finite()).fold(0f64, |m, x| m.max(x.abs() as f64));
+        let max = tables[e].last().unwrap().0;
+        let scaled = amax * 2f64.powi(stats.scale_exponent);
+        assert!(stats.overflows == 0 && scaled <= max && scaled * 2.0 > max, "{:?} {:e} {:?}", elements[e], amax, stats);
+    }
+    let mut codes = [0u8; 4];
+    let stats = quantize(&[0.0, -0.0, 0.0, 0.0], &mut codes, Element::E4M3, Rounding::NearestEven, Scaling::Amax, Saturation::NonSaturating);
+    assert!(stats == QuantizeStats::default() && codes == [0, 0x80, 0, 0]);
+    let stats = quantize(&[448.0, 464.0, 465.0, -1e9], &mut codes, Element::E4M3, Rounding::NearestEven, Scaling::Unscaled, Saturation::NonSaturating);
+    assert!(codes == [0x7E, 0x7E, 0x7F, 0xFF] && stats.overflows == 2 && stats.max_error == 16.0);
+    // saturating, the same overflows go to the largest finite value, and so do infinities, without counting
+    let input = [448.0, 464.0, 465.0, -1e9, f32::INFINITY, -f32::NAN];
+    let mut codes = [0u8; 6];
+    let stats = quantize(&input, &mut codes, Element::E4M3, Rounding::NearestEven, Scaling::Unscaled, Saturation::Saturating);
+    assert!(codes == [0x7E, 0x7E, 0x7E, 0xFE, 0x7E, 0xFF] && stats.overflows == 2 && stats.max_error == 16.0);
+    let input = [57344.0, 61440.0, f32::NEG_INFINITY, f32::NAN];
+    let mut codes = [0u8; 4];
+    let stats = quantize(&input, &mut codes, Element::E5M2, Rounding::NearestEven, Scaling::Unscaled, Saturation::Saturating);
+    assert!(codes == [0x7B, 0x7B, 0xFB, 0x7E] && stats.overflows == 1);
+    let input = [6.0, 6.9, 7.0, -1e9, f32::INFINITY, f32::NAN, 0.25, -0.3];
+    let mut codes = [0u8; 8];
+    let stats = quantize(&input, &mut codes, Element::E2M1, Rounding::NearestEven, Scaling::Unscaled, Saturation::NonSaturating);
+    assert!(codes == [0x7, 0x7, 0x7, 0xF, 0x7, 0x7, 0x0, 0x9] && stats.overflows == 2 && stats.underflows == 1);
+
+    for (e, &element) in elements.iter().enumerate().skip(2) {
+        let sign_bit = 1u32 << (element.bits() - 1);
+        let codes: Vec<u16> = (0..sign_bit << 1).map(|c| c as u16).collect();
+        let mut values = vec![0f32; codes.len()];
+        dequantize(&codes, &mut values, element, 0);
+        let (nan, infinity) = match element {
+            Element::E4M3 => (0x7F, 0x7F),
+            Element::E5M2 => (0x7E, 0x7C),
+            _ => (sign_bit - 1, sign_bit - 1),
+        };
+        for op in [Operation::Add, Operation::Subtract, Operation::Multiply, Operation::Divide] {
+            for (a, x) in values.iter().enumerate() {
+                for (b, y) in values.iter().enumerate() {
+                    let (x, y) = (*x as f64, *y as f64);
+                    let exact = match op {
+                        Operation::Add => x + y,
+                        Operation::Subtract => x - y,
+                        Operation::Multiply => x * y,
+                        Operation::Divide => x / y,
+                    };
+                    let code = element.apply(op, a as u32, b as u32);
+                    let sign = if exact.is_sign_negative() { sign_bit } else { 0 };
+                    // the host's nans have either sign
+                    let (code, expected) = if exact.is_nan() {
+                        (code & (sign_bit - 1), nan)
+                    } else {
+                        (code, nearest(&tables[e], exact.abs()).filter(|_| exact.is_finite()).unwrap_or(infinity) | sign)
+                    };
+                    assert!(code == expected, "{:?} {x} {op:?} {y}: {code:x}, expected {expected:x}", element);
+                }
+            }
+        }
+    }
+    println!("Quantize test passed!");
+}
+
+fn presets_test() {
+    // every fp24 and pxr24 value converts to binary32 exactly and back; pxr24_from_binary32 against openexr's
+    // float_to_float24, written out, and against convert away from its edge cases; rgb9e5 against the
+    // EXT_texture_shared_exponent spec's algorithm in host floats
+    use floatfs::presets::{pxr24_from_binary32, pxr24_to_binary32, rgb9e5_from_binary32, rgb9e5_to_binary32, Fp24, Pxr24};
+    use floatfs::{Context, RoundingMode, SoftFloat, F32};
+    use rand::Rng;
+
+    for bits in 0..1u32 << 24 {
+        let x = SoftFloat::<Fp24>::from_bits(bits);
+        let mut ctx = Context::default();
+        let wide: F32 = x.convert(&mut ctx);
+        let back: SoftFloat<Fp24> = wide.convert(&mut ctx);
+        assert!(back.to_bits() == bits || x.is_signaling_nan() && back.is_nan(), "{bits:x}");
+        let x = SoftFloat::<Pxr24>::from_bits(bits);
+        let wide: F32 = x.convert(&mut Context::default());
+        assert!(wide.to_bits() == pxr24_to_binary32(&x).to_bits() || x.is_signaling_nan());
+        assert_eq!(pxr24_from_binary32(&pxr24_to_binary32(&x)).to_bits(), bits);
+    }
+    let largest: F32 = SoftFloat::<Fp24>::largest_finite(false).convert(&mut Context::default());
+    assert_eq!(f32::from_bits(largest.to_bits()), (2.0 - 2f32.powi(-16)) * 2f32.powi(63));
+
+    // openexr's float_to_float24
+    let reference = |f: u32| -> u32 {
+        let (s, e, m) = (f & 0x8000_0000, f & 0x7F80_0000, f & 0x007F_FFFF);
+        let i = if e == 0x7F80_0000 {
+            if m != 0 {
+                (e >> 8) | (m >> 8) | ((m >> 8) == 0) as u32
+            } else {
+                e >> 8
+            }
+        } else {
+            let i = ((e | m) + (m & 0x80)) >> 8;
+            if i >= 0x7F_8000 {
+                (e | m) >> 8
+            } else {
+                i
+            }
+        };
+        (s >> 8) | i
+    };
+    let mut rng = rand::rng();
+    let edges = [0, 0x80, 0x7F, 0x7F7F_FF7F, 0x7F7F_FF80, 0x7F7F_FFFF, 0x7F80_0000, 0x7F80_0001, 0x7FC0_0000, 0x7F80_0100];
+    for f in edges.into_iter().flat_map(|b| [b, b | 0x8000_0000]).chain((0..1_000_000).map(|_| rng.random::<u32>())) {
+        let x = F32::from_bits(f);
+        let p = pxr24_from_binary32(&x);
+        assert_eq!(p.to_bits(), reference(f), "{f:x}");
+        let mut ctx = Context::new(RoundingMode::TiesToAway);
+        let converted: SoftFloat<Pxr24> = x.convert(&mut ctx);
+        if !x.is_nan() && (!converted.is_infinity() || x.is_infinity()) {
+            assert_eq!(p.to_bits(), converted.to_bits(), "{f:x}");
+        }
+    }
+
+    // the spec, in floats
+    let spec = |rgb: [f32; 3]| -> u32 {
+        let max = 511.0 / 512.0 * 2f64.powi(16);
+        let c = rgb.map(|x| if x.is_nan() { 0.0 } else { (x as f64).clamp(0.0, max) });
+        let largest = c[0].max(c[1]).max(c[2]);
+        let log2 = if largest == 0.0 { -16 } else { (largest.log2().floor() as i32).max(-16) };
+        let mut exponent = log2 + 1 + 15;
+        if (largest / 2f64.powi(exponent - 24) + 0.5).floor() == 512.0 {
+            exponent += 1;
+        }
+        let [r, g, b] = c.map(|x| (x / 2f64.powi(exponent - 24) + 0.5).floor() as u32);
+        r | g << 9 | b << 18 | (exponent as u32) << 27
+    };
+    let specials = [0.0, -0.0, -1.0, f32::NAN, f32::INFINITY, 65408.0, 65472.0, 1e9, 2f32.powi(-16), 2f32.powi(-25), 1e-30];
+    for i in 0..1_000_000 {
+        let rgb: [f32; 3] = core::array::from_fn(|_| match i % 3 {
+            0 => specials[rng.random_range(0..specials.len())],
+            1 => rng.random::<f32>() * 2f32.powi(rng.random_range(-30..20)),
+            // halfway between two mantissas of a random exponent
+            _ => (rng.random_range(0..1024) as f32 + 0.5) * 2f32.powi(rng.random_range(-25..7)),
+        });
+        let packed = rgb9e5_from_binary32(rgb.map(|x| F32::from_bits(x.to_bits())));
+        assert_eq!(packed, spec(rgb), "{rgb:?}");
+        let exponent = (packed >> 27) as i32;
+        let unpacked = rgb9e5_to_binary32(packed);
+        for (n, x) in unpacked.iter().enumerate() {
+            let mantissa = (packed >> (9 * n) & 0x1FF) as f32;
+            assert_eq!(f32::from_bits(x.to_bits()), mantissa * 2f32.powi(exponent - 24));
+        }
+    }
+    assert_eq!(rgb9e5_to_binary32(u32::MAX).map(|x| f32::from_bits(x.to_bits())), [65408.0; 3]);
+    println!("Presets test passed!");
+}
+
+fn random_test() {
+    // unit: multiples of 2^-53 in [0, 1), evenly over 64 buckets. unit_dense and range on the minifloat, where
+    // every value's count can be checked against its gap (a chi-square test), and on binary64 each binade of
+    // unit_dense against its probability. then range's bounds on random doubles, and the extreme bit sources.
+    use floatfs::format::{Binary32, Binary64, Minifloat};
+    use floatfs::random::{range, unit, unit_dense};
+    use floatfs::{SoftFloat, F32, F64};
+    use rand::{Rng, RngCore};
+
+    let (mut rng, mut source) = (rand::rng(), rand::rng());
+    let mut bits = || source.next_u64();
+    // chi-square over counts against expected probabilities, as standard deviations from its mean
+    let chi = |counts: &[u64], expected: &[f64], n: u64| {
+        let cells: Vec<(f64, f64)> = counts.iter().zip(expected).filter(|(_, &p)| p > 0.0).map(|(&c, &p)| (c as f64, p * n as f64)).collect();
+        let statistic: f64 = cells.iter().map(|(c, e)| (c - e) * (c - e) / e).sum();
+        let df = (cells.len() - 1) as f64;
+        (statistic - df) / (2.0 * df).sqrt()
+    };
+
+    let n = 1_000_000;
+    let mut buckets = [0u64; 64];
+    for _ in 0..n {
+        let x = f64::from_bits(unit::<Binary64>(&mut bits).to_bits());
+        assert!((0.0..1.0).contains(&x) && (x * 2f64.powi(53)).fract() == 0.0);
+        buckets[(x * 64.0) as usize] += 1;
+    }
+    assert!(chi(&buckets, &[1.0 / 64.0; 64], n).abs() < 6.0);
+
+    // the minifloat's values in [0, 1) are 0 through 0x37, and each one's chance is its gap to the next
+    let value = |raw: u8| f64::from_bits(SoftFloat::<Minifloat>::from_bits(raw).convert::<Binary64>(&mut Default::default()).to_bits());
+    let gaps: Vec<f64> = (0..0x38u8).map(|r| value(r + 1) - value(r)).collect();
+    let mut counts = vec![0u64; 0x38];
+    for _ in 0..n {
+        let x: SoftFloat<Minifloat> = unit_dense(&mut bits);
+        counts[x.to_bits() as usize] += 1;
+    }
+    assert!(chi(&counts, &gaps, n).abs() < 6.0, "{}", chi(&counts, &gaps, n));
+    // and over ranges: [low, high) gets each value in it with its gap's share
+    for _ in 0..20 {
+        let (a, b) = (rng.random_range(0..0x77u8), rng.random_range(0..0x77u8));
+        let (a, b) = (a ^ (rng.random::<u8>() & 0x80), b ^ (rng.random::<u8>() & 0x80));
+        let (low, high) = if value(a) < value(b) { (a, b) } else { (b, a) };
+        if value(low) == value(high) {
+            continue;
+        }
+        let mut counts = vec![0u64; 256];
+        let mut expected = vec![0f64; 256];
+        for raw in 0..=255u8 {
+            let v = value(raw);
+            // the gap up to the next value, where -0 counts once (as +0)
+            let next = (0..=255u8).map(value).filter(|&w| w > v).fold(f64::INFINITY, f64::min);
+            if v >= value(low) && v < value(high) && !(raw == 0x80 && value(low) != 0.0 || raw == 0 && low == 0x80) {
+                expected[raw as usize] = (next.min(value(high)) - v) / (value(high) - value(low));
+            }
+        }
+        let (l, h) = (SoftFloat::<Minifloat>::from_bits(low), SoftFloat::<Minifloat>::from_bits(high));
+        for _ in 0..100_000 {
+            counts[range(&l, &h, &mut bits).unwrap().to_bits() as usize] += 1;
+        }
+        assert!(chi(&counts, &expected, 100_000).abs() < 6.0, "{:x} {:x}", low, high);
+        assert!(counts.iter().zip(&expected).all(|(&c, &p)| c == 0 || p > 0.0), "{:x} {:x}", low, high);
+    }
+
+    // binary64: each binade of unit_dense, down to 2^-20, gets half the one above
+    let mut binades = [0u64; 21];
+    for _ in 0..n {
+        let x = f64::from_bits(unit_dense::<Binary64>(&mut bits).to_bits());
+        assert!((0.0..1.0).contains(&x));
+        binades[(-x.log2().floor() as usize - 1).min(20)] += 1;
+    }
+    let mut probabilities: Vec<f64> = (1..=20).map(|k| 2f64.powi(-k)).collect();
+    probabilities.push(2f64.powi(-20));
+    assert!(chi(&binades, &probabilities, n).abs() < 6.0);
+
+    for _ in 0..1_000_000 {
+        let a = f64::from_bits(rng.random::<u64>() & !(0x7FF << 52) | (rng.random_range(900..1150u64) << 52));
+        let b = if rng.random::<bool>() { -a * rng.random::<f64>() } else { a + rng.random::<f64>() * a.abs() * 2f64.powi(-rng.random_range(0..60)) };
+        let (low, high) = if a < b { (a, b) } else { (b, a) };
+        let x = range(&F64::from_bits(low.to_bits()), &F64::from_bits(high.to_bits()), &mut bits);
+        if low == high {
+            assert!(x.is_none());
+            continue;
+        }
+        let x = f64::from_bits(x.unwrap().to_bits());
+        assert!(low <= x && x < high && (x != 0.0 || x.is_sign_positive() || low == 0.0), "{:e} {:e} {:e}", low, high, x);
+    }
+    let next = |x: f64| f64::from_bits(x.to_bits() + 1);
+    for x in [1.0, 0.0, f64::MAX / 2.0, 1e-310, 5e-324] {
+        let (low, high) = (F64::from_bits(x.to_bits()), F64::from_bits(next(x).to_bits()));
+        assert!(range(&low, &high, &mut bits).unwrap().to_bits() == low.to_bits());
+    }
+    let big = F64::from_bits(f64::MAX.to_bits());
+    let mut big_negative = big;
+    big_negative.negate();
+    let x = f64::from_bits(range(&big_negative, &big, &mut bits).unwrap().to_bits());
+    assert!(x.is_finite());
+    assert!(range(&big, &big, &mut bits).is_none() && range(&big, &big_negative, &mut bits).is_none());
+    assert!(range(&F64::nan(), &big, &mut bits).is_none() && range(&big_negative, &F64::infinity(false), &mut bits).is_none());
+    // a source of all zeros gives zeros, all ones the largest value below one (or below high)
+    let (zeros, ones) = (&mut || 0u64, &mut || u64::MAX);
+    assert!(unit::<Binary64>(zeros).to_bits() == 0 && unit_dense::<Binary64>(zeros).to_bits() == 0);
+    assert!(f64::from_bits(unit::<Binary64>(ones).to_bits()) == 1.0 - 2f64.powi(-53));
+    assert!(f64::from_bits(unit_dense::<Binary64>(ones).to_bits()) == 1.0 - 2f64.powi(-53));
+    assert!(f32::from_bits(unit_dense::<Binary32>(ones).to_bits()) == 1.0 - 2f32.powi(-24));
+    let (low, high) = (F32::from_bits(1f32.to_bits()), F32::from_bits(2f32.to_bits()));
+    assert!(range(&low, &high, ones).unwrap().to_bits() == (2f32 - 2f32.powi(-23)).to_bits());
+    assert!(range(&low, &high, zeros).unwrap().to_bits() == low.to_bits());
+    println!("Random test passed!");
+}
+
+#[cfg(feature = "rand")]
+fn distribution_test() {
+    // rng.random() for Float and SoftFloat: [0, 1) in steps of 2^-53 (2^-(m + 1) generally) as rand's f64, and the
+    // open ones without their ends, checked exhaustively on the minifloat. Uniform: inside its range, and an
+    // inclusive range gets its top value.
+    use floatfs::format::Minifloat;
+    use floatfs::{Float, SoftFloat, F32, F64};
+    use rand::distr::uniform::Error;
+    use rand::distr::{Distribution, Open01, OpenClosed01, Uniform};
+    use rand::Rng;
+
+    let mut rng = rand::rng();
+    let mut buckets = [0u32; 16];
+    for _ in 0..1_000_000 {
+        let x = rng.random::<Float>().to_f64();
+        assert!((0.0..1.0).contains(&x) && (x * 2f64.powi(53)).fract() == 0.0);
+        buckets[(x * 16.0) as usize] += 1;
+        let y = f32::from_bits(rng.random::<F32>().to_bits());
+        assert!((0.0..1.0).contains(&y) && (y * 2f32.powi(24)).fract() == 0.0);
+        let z = Open01.sample(&mut rng);
+        let z = f64::from_bits(F64::to_bits(&z));
+        assert!(z > 0.0 && z < 1.0 && (z * 2f64.powi(53)).fract() == 0.0);
+        let w: Float = OpenClosed01.sample(&mut rng);
+        assert!(w.to_f64() > 0.0 && w.to_f64() <= 1.0);
+    }
+    assert!(buckets.iter().all(|&b| (b as f64 - 62500.0).abs() < 6.0 * 242.0), "{:?}", buckets);
+
+    // minifloat: every allowed value turns up, and nothing else
+    let value = |x: SoftFloat<Minifloat>| {
+        f64::from_bits(x.convert::<floatfs::format::Binary64>(&mut Default::default()).to_bits())
+    };
+    let mut seen = [[false; 17]; 3];
+    for _ in 0..100_000 {
+        let x = value(rng.random());
+        let y = value(Open01.sample(&mut rng));
+        let z = value(OpenClosed01.sample(&mut rng));
+        assert!((x * 16.0).fract() == 0.0 && (y * 16.0 - 1.0) % 2.0 == 0.0 && (z * 16.0).fract() == 0.0);
+        seen[0][(x * 16.0) as usize] = true;
+        seen[1][(y * 16.0) as usize] = true;
+        seen[2][(z * 16.0) as usize] = true;
+    }
+    assert!(seen[0][..16].iter().all(|&s| s) && !seen[0][16]);
+    assert!(seen[1].iter().enumerate().all(|(i, &s)| s == (i % 2 == 1)));
+    assert!(seen[2][1..].iter().all(|&s| s) && !seen[2][0]);
+
+    for _ in 0..10_000 {
+        let (a, b) = (rng.random_range(-1e10..1e10), rng.random_range(-1e10..1e10));
+        let (low, high) = if a < b { (a, b) } else { (b, a) };
+        let uniform = Uniform::new(Float::new(low), Float::new(high)).unwrap();
+        for _ in 0..10 {
+            let x = uniform.sample(&mut rng).to_f64();
+            assert!(low <= x && x < high);
+        }
+    }
+    // [1, 1.25] on the minifloat: 1, 1.125 and 1.25, evenly, as 1.25's gap is the same as the others'
+    let (one, top) = (SoftFloat::<Minifloat>::from_bits(0x30), SoftFloat::<Minifloat>::from_bits(0x32));
+    let uniform = Uniform::new_inclusive(one, top).unwrap();
+    let mut counts = [0u32; 3];
+    for _ in 0..300_000 {
+        counts[(uniform.sample(&mut rng).to_bits() - 0x30) as usize] += 1;
+    }
+    assert!(counts.iter().all(|&c| (c as f64 - 100_000.0).abs() < 6.0 * 258.0), "{:?}", counts);
+    let largest = Float::from_bits(f64::MAX.to_bits());
+    assert!(Uniform::new_inclusive(Float::new(1.0), largest.copy()).unwrap().sample(&mut rng).to_f64() < f64::MAX);
+    assert!(Uniform::new_inclusive(Float::new(2.0), Float::new(2.0)).unwrap().sample(&mut rng).to_f64() == 2.0);
+    assert!(Uniform::new(Float::new(2.0), Float::new(2.0)).unwrap_err() == Error::EmptyRange);
+    assert!(Uniform::new(Float::new(3.0), Float::new(2.0)).unwrap_err() == Error::EmptyRange);
+    assert!(Uniform::new(Float::nan(), Float::new(2.0)).unwrap_err() == Error::NonFinite);
+    assert!(Uniform::new_inclusive(Float::new(0.0), Float::infinity(false)).unwrap_err() == Error::NonFinite);
+    assert!(Uniform::new_inclusive(Float::new(0.0), Float::nan()).unwrap_err() == Error::NonFinite);
+    println!("Distribution test passed!");
+}
+
+fn between_test() {
+    // Float::between against stepping with next_up from a and with next_down from b, on short random runs that
+    // cross zeros, subnormals, binade edges and the infinities; then skipping with nth from both ends, the zeros,
+    // empty ranges and the whole line.
+    use floatfs::Float;
+    use rand::Rng;
+
+    let mut rng = rand::rng();
+    let interesting = [0u64, 1 << 63, 1, 0x8000000000000001, 0x0010000000000000, 0x3FF0000000000000, 0x7FEFFFFFFFFFFFFF, 0xFFEFFFFFFFFFFFFF];
+    for i in 0..100_000 {
+        let a = if i % 2 == 0 { Float::from_bits(rng.random()) } else { Float::from_bits(interesting[rng.random_range(0..8)]) };
+        let mut a = if a.is_nan() { Float::new(1.0) } else { a };
+        for _ in 0..rng.random_range(0..60) {
+            a = a.next_down();
+        }
+        let mut b = a.copy();
+        for _ in 0..rng.random_range(0..120) {
+            b = b.next_up();
+        }
+        let values: Vec<Float> = Float::between(&a, &b).collect();
+        // next_up steps: the same values, with the zero as a, b or +0 rather than next_up's -0
+        let mut expected = vec![a.copy()];
+        while expected.last().unwrap().to_f64() != b.to_f64() {
+            expected.push(expected.last().unwrap().next_up());
+        }
+        assert!(values.len() == expected.len(), "{:e} {:e}", a.to_f64(), b.to_f64());
+        for (x, y) in values.iter().zip(&expected) {
+            assert!(x.to_f64() == y.to_f64() && (x.to_bits() == y.to_bits() || x.is_zero()));
+            if x.is_zero() {
+                let zero = if a.is_zero() { &a } else if b.is_zero() { &b } else { &Float::new(0.0) };
+                assert!(x.to_bits() == zero.to_bits());
+            }
+        }
+        let backwards: Vec<Float> = Float::between(&a, &b).rev().collect();
+        assert!(backwards.iter().rev().zip(&values).all(|(x, y)| x.to_bits() == y.to_bits()));
+        let mut down = vec![b.copy()];
+        while down.last().unwrap().to_f64() != a.to_f64() {
+            down.push(down.last().unwrap().next_down());
+        }
+        assert!(down.len() == values.len() && down.iter().zip(&backwards).all(|(x, y)| x.to_f64() == y.to_f64()));
+        // from both ends at once, and skipping
+        let mut it = Float::between(&a, &b);
+        let (mut front, mut back) = (0, values.len());
+        while front < back {
+            assert!(it.size_hint() == (back - front, Some(back - front)) && it.remaining() == (back - front) as u64);
+            if rng.random::<bool>() {
+                assert!(it.next().unwrap().to_bits() == values[front].to_bits());
+                front += 1;
+            } else {
+                back -= 1;
+                assert!(it.next_back().unwrap().to_bits() == values[back].to_bits());
+            }
+        }
+        assert!(it.next().is_none() && it.next_back().is_none() && it.remaining() == 0);
+        let step = rng.random_range(1..10);
+        assert!(Float::between(&a, &b).step_by(step).zip(values.iter().step_by(step)).all(|(x, y)| x.to_bits() == y.to_bits()));
+        assert!(Float::between(&a, &b).step_by(step).count() == values.iter().step_by(step).count());
+        let n = rng.random_range(0..130);
+        assert!(Float::between(&a, &b).nth_back(n).map(|x| x.to_bits()) == values.iter().rev().nth(n).map(|x| x.to_bits()));
+        if a.to_f64() != b.to_f64() {
+            assert!(Float::between(&b, &a).next().is_none());
+        }
+    }
+
+    let bits = |a: f64, b: f64| Float::between(&Float::new(a), &Float::new(b)).map(|x| x.to_bits()).collect::<Vec<u64>>();
+    assert!(bits(-5e-324, 5e-324) == [0x8000000000000001, 0, 1]);
+    assert!(bits(-5e-324, -0.0) == [0x8000000000000001, 1 << 63]);
+    assert!(bits(-0.0, 5e-324) == [1 << 63, 1] && bits(0.0, -0.0) == [0] && bits(-0.0, 0.0) == [1 << 63]);
+    assert!(bits(f64::MAX, f64::INFINITY) == [f64::MAX.to_bits(), f64::INFINITY.to_bits()]);
+    assert!(bits(1.0, 0.5).is_empty() && bits(f64::NAN, 1.0).is_empty() && bits(0.0, f64::NAN).is_empty());
+    // the whole line: every non-nan but -0, which is more than 2^63 of them
+    let mut all = Float::between(&Float::infinity(true), &Float::infinity(false));
+    assert!(all.remaining() == 2 * 0x7FF0000000000000 + 1);
+    assert!(all.nth(0x7FF0000000000000 + 5).unwrap().to_bits() == 5);
+    assert!(all.next_back().unwrap().is_infinity() && all.nth_back(0x7FF0000000000000 - 7).unwrap().to_bits() == 6);
+    assert!(all.remaining() == 0 && all.next().is_none());
+    println!("Between test passed!");
+}
+
+fn count_between_test() {
+    // count_between against counting the distinct values in [a, b] over every pair of minifloats, against
+    // Float::between's length on random doubles, and the sizes of whole formats.
+    use floatfs::format::{Binary64, Extended, Minifloat};
+    use floatfs::{Float, SoftFloat, F16, F32, F64, F80};
+    use rand::Rng;
+
+    let value = |raw: u8| f64::from_bits(SoftFloat::<Minifloat>::from_bits(raw).convert::<Binary64>(&mut Default::default()).to_bits());
+    let mut values: Vec<f64> = (0..=255u8).map(value).filter(|v| !v.is_nan()).collect();
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    values.dedup();
+    for a in 0..=255u8 {
+        for b in 0..=255u8 {
+            let (x, y) = (value(a), value(b));
+            let expected = values.iter().filter(|&&v| x <= v && v <= y).count() as u128;
+            let count = SoftFloat::<Minifloat>::count_between(&SoftFloat::from_bits(a), &SoftFloat::from_bits(b));
+            assert!(count == expected, "{:x} {:x}: {} {}", a, b, count, expected);
+        }
+    }
+    let mut rng = rand::rng();
+    for _ in 0..1_000_000 {
+        let a = Float::from_bits(rng.random());
+        let b = if rng.random::<bool>() { Float::from_bits(rng.random()) } else { Float::from_bits(a.to_bits() ^ rng.random_range(0..1000)) };
+        let count = F64::count_between(&F64::from_bits(a.to_bits()), &F64::from_bits(b.to_bits()));
+        assert!(count == Float::count_between(&a, &b) as u128 && count == Float::between(&a, &b).remaining() as u128);
+        if a.is_nan() || b.is_nan() || a.to_f64() > b.to_f64() {
+            assert!(count == 0);
+        } else if count < 1000 {
+            assert!(count == Float::between(&a, &b).count() as u128);
+        }
+    }
+    let f64s = |a: f64, b: f64| F64::count_between(&F64::from_bits(a.to_bits()), &F64::from_bits(b.to_bits()));
+    assert!(f64s(0.0, 1.0) == 0x3FF0000000000000 + 1 && f64s(-1.0, 1.0) == 2 * 0x3FF0000000000000 + 1);
+    assert!(f64s(-0.0, 5e-324) == 2 && f64s(0.0, -0.0) == 1 && f64s(1.0, 2.0) == (1 << 52) + 1 && f64s(2.0, 1.0) == 0);
+    assert!(f64s(f64::NEG_INFINITY, f64::INFINITY) == 2 * 0x7FF0000000000000 + 1 && f64s(f64::NAN, f64::NAN) == 0);
+    let f32s = F32::count_between(&F32::infinity(true), &F32::infinity(false));
+    assert!(f32s == (1 << 32) - (1 << 24) + 1);
+    assert!(F16::count_between(&F16::infinity(true), &F16::infinity(false)) == (1 << 16) - (1 << 11) + 1);
+    assert!(F80::count_between(&F80::infinity(true), &F80::infinity(false)) == 2 * (0x7FFF << 63) + 1);
+    let (one, two) = (F80::from_u64(1, &mut Default::default()), F80::from_u64(2, &mut Default::default()));
+    assert!(SoftFloat::<Extended>::count_between(&one, &two) == (1 << 63) + 1);
+    println!("Count between test passed!");
+}
+
+fn assert_float_eq_test() {
+    use std::panic;
+    // the message from a failing assert_float_eq!, or None if it passed
+    fn failure(check: impl FnOnce() + panic::UnwindSafe) -> Option<String> {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(|_| {}));
+        let result = panic::catch_unwind(check);
+        panic::set_hook(hook);
+        result.err().map(|e| e.downcast_ref::<String>().cloned().unwrap_or_default())
+    }
+    let one = Float::new(1.0);
+    let above = one.next_up();
+    let nan = Float::new(f64::NAN);
+    let other_nan = Float::from_bits(nan.to_bits() | 1);
+
+    // passing
+    assert_eq!(failure(|| assert_float_eq!(Float::new(1.0), Float::new(1.0))), None);
+    assert_eq!(failure(|| assert_float_eq!(Float::new(1.0), Float::new(1.0).next_up(), ulps = 1)), None);
+    assert_eq!(failure(|| assert_float_eq!(Float::new(0.0), Float::new(-0.0), ulps = 0)), None);
+    assert_eq!(failure(|| assert_float_eq!(Float::new(f64::NAN), Float::new(f64::NAN))), None);
+    let (n, m) = (nan.copy(), other_nan.copy());
+    assert_eq!(failure(move || assert_float_eq!(n, m, ulps = 0)), None);
+    let big = Float::new(f64::MAX);
+    assert_eq!(failure(move || assert_float_eq!(big, Float::new(f64::INFINITY), ulps = 1)), None);
+
+    // failing, with both values' bits and parts and the distance
+    let (a, b) = (one.copy(), above.copy());
+    let message = failure(move || assert_float_eq!(a, b)).expect("1 and next_up(1) aren't the same bits");
+    assert_eq!(
+        message,
+        "assertion `left == right` failed (exact bits)\n  \
+         left: 1e0 (0x3ff0000000000000: sign 0, exponent 0 (biased 1023), mantissa \
+         0000000000000000000000000000000000000000000000000000, normal)\n \
+         right: 1.0000000000000002e0 (0x3ff0000000000001: sign 0, exponent 0 (biased 1023), mantissa \
+         0000000000000000000000000000000000000000000000000001, normal)\n \
+         apart: 1 ulps"
+    );
+    let (a, b) = (Float::new(1.0), Float::new(1.0).next_up().next_up());
+    let message = failure(move || assert_float_eq!(a, b, ulps = 1, "step {}", 7)).expect("2 ulps apart");
+    assert!(message.starts_with("assertion `left == right` failed (within 1 ulps): step 7\n"), "{message}");
+    assert!(message.ends_with("\n apart: 2 ulps"), "{message}");
+    let message = failure(|| assert_float_eq!(Float::new(0.0), Float::new(-0.0), "zeros")).expect("different bits");
+    assert!(message.starts_with("assertion `left == right` failed (exact bits): zeros\n"), "{message}");
+    assert!(message.ends_with("\n apart: 0 ulps"), "{message}");
+    let message = failure(|| assert_float_eq!(Float::new(f64::NAN), Float::new(1.0), ulps = 10)).expect("nan vs 1");
+    assert!(message.ends_with("\n apart: a nan, no ulp distance"), "{message}");
+    let (n, m) = (nan.copy(), other_nan.copy());
+    assert!(failure(move || assert_float_eq!(n, m)).is_some(), "different nan payloads, exact bits");
+
+    // the message is only formatted on failure
+    let mut formatted = false;
+    let mut flag = || {
+        formatted = true;
+        "context"
+    };
+    assert_float_eq!(Float::new(2.0), Float::new(2.0), "{}", flag());
+    assert!(!formatted);
+
+    println!("Assert float eq test passed!");
+}
+
+fn parts_test() {
+    use floatfs::FloatParts;
+    use std::num::FpCategory;
+    let parts = |x: f64| Float::new(x).parts();
+    assert_eq!(
+        parts(-1.5),
+        FloatParts {
+            sign: true,
+            biased_exponent: 1023,
+            unbiased_exponent: 0,
+            mantissa: 1 << 51,
+            category: FpCategory::Normal
+        }
+    );
+    assert_eq!(
+        parts(f64::MIN_POSITIVE / 4.0),
+        FloatParts {
+            sign: false,
+            biased_exponent: 0,
+            unbiased_exponent: -1022,
+            mantissa: 1 << 50,
+            category: FpCategory::Subnormal
+        }
+    );
+    let zero = parts(-0.0);
+    assert!(zero.sign && zero.biased_exponent == 0 && zero.unbiased_exponent == -1022 && zero.mantissa == 0);
+    assert_eq!(zero.category, FpCategory::Zero);
+    let infinity = parts(f64::INFINITY);
+    assert!(infinity.biased_exponent == 2047 && infinity.unbiased_exponent == 1024 && infinity.mantissa == 0);
+    assert_eq!(infinity.category, FpCategory::Infinite);
+    assert_eq!(parts(f64::NAN).category, FpCategory::Nan);
+    assert_eq!(parts(f64::MAX).unbiased_exponent, 1023);
+    assert_eq!(parts(f64::MIN_POSITIVE).unbiased_exponent, -1022);
+
+    // the fields put back together are the bits again
+    use rand::Rng;
+    let mut rng = rand::rng();
+    for _ in 0..100_000 {
+        let x = Float::from_bits(rng.random());
+        let p = x.parts();
+        assert_eq!((p.sign as u64) << 63 | (p.biased_exponent as u64) << 52 | p.mantissa, x.to_bits());
+        assert_eq!(p.category, x.to_f64().classify());
+    }
+
+    assert_eq!(
+        parts(0.1).to_string(),
+        "sign 0, exponent -4 (biased 1019), mantissa 1001100110011001100110011001100110011001100110011010, normal"
+    );
+    assert_eq!(
+        parts(-5e-324).to_string(),
+        "sign 1, exponent -1022 (biased 0), mantissa 0000000000000000000000000000000000000000000000000001, subnormal"
+    );
+    println!("Parts test passed!");
+}
+
+fn debug_test() {
+    let debug = |bits: u64| format!("{:?}", Float::from_bits(bits));
+    assert_eq!(format!("{:?}", Float::new(1.1)), "Float(0x3FF199999999999A ≈ 1.1, normal)");
+    assert_eq!(format!("{:?}", Float::new(1.0)), "Float(0x3FF0000000000000 ≈ 1.0, normal)");
+    assert_eq!(format!("{:?}", Float::new(-0.0)), "Float(0x8000000000000000 ≈ -0.0, zero)");
+    assert_eq!(format!("{:?}", Float::new(1e300)), "Float(0x7E37E43C8800759C ≈ 1e300, normal)");
+    assert_eq!(debug(1), "Float(0x0000000000000001 ≈ 5e-324, subnormal)");
+    assert_eq!(debug(0xFFF0000000000000), "Float(0xFFF0000000000000 ≈ -inf, infinite)");
+    assert_eq!(debug(0x7FF8000000000000), "Float(0x7FF8000000000000 ≈ NaN, nan)");
+    assert_eq!(debug(0x7FF0000000000001), "Float(0x7FF0000000000001 ≈ NaN, signaling nan)");
+    assert_eq!(
+        format!("{:#?}", Float::new(-2.5)),
+        "Float(0xC004000000000000 ≈ -2.5, sign 1, exponent 1 (biased 1024), mantissa \
+         0100000000000000000000000000000000000000000000000000, normal)"
+    );
+    // and inside other types' derived Debug
+    assert_eq!(format!("{:?}", Some(Float::new(0.5))), "Some(Float(0x3FE0000000000000 ≈ 0.5, normal))");
+    println!("Debug test passed!");
+}
+
+fn decimal_test() {
+    use floatfs::format::{Format, Minifloat};
+    use floatfs::{Context, Flags, RoundingMode, SoftFloat, F16, F32, F64, F80};
+    use rand::Rng;
+    let mut rng = rand::rng();
+    let parse = |s: &str, mode: RoundingMode| {
+        let mut ctx = Context::new(mode);
+        let x = F64::parse(s, &mut ctx).unwrap_or_else(|_| panic!("{s} didn't parse"));
+        (x.to_bits(), ctx.flags)
+    };
+    let bits = |s: &str| parse(s, RoundingMode::TiesToEven).0;
+
+    // against the host's (correctly rounded) parsers, at every length from too few digits to plenty
+    for _ in 0..30_000 {
+        let x = f64::from_bits(rng.random::<u64>() & !(1 << 63));
+        if !x.is_finite() {
+            continue;
+        }
+        let s = format!("{:.*e}", rng.random_range(0..25), x);
+        assert_eq!(bits(&s), s.parse::<f64>().unwrap().to_bits(), "{s}");
+        let y = f32::from_bits(rng.random::<u32>() & 0x7F7FFFFF);
+        let s = format!("{:.*e}", rng.random_range(0..12), y);
+        assert_eq!(s.parse::<F32>().unwrap().to_bits(), s.parse::<f32>().unwrap().to_bits(), "{s}");
+    }
+    // the directed modes bracket the value: equal when it's exact, a step apart otherwise, with nearest one of them
+    for _ in 0..30_000 {
+        let x = f64::from_bits(rng.random::<u64>() & !(1 << 63));
+        if !x.is_finite() {
+            continue;
+        }
+        let s = format!("{:.*e}", rng.random_range(0..20), x);
+        let (down, flags) = parse(&s, RoundingMode::TowardNegative);
+        let (up, _) = parse(&s, RoundingMode::TowardPositive);
+        let (zero, _) = parse(&s, RoundingMode::TowardZero);
+        let nearest = bits(&s);
+        assert_eq!(down, zero, "{s}");
+        if flags.contains(Flags::INEXACT) {
+            assert_eq!(F64::from_bits(down).next_up().to_bits(), up, "{s}");
+            assert!(nearest == down || nearest == up, "{s}");
+        } else {
+            assert!(down == up && up == nearest, "{s}");
+        }
+    }
+
+    // hard cases: ties, a tie broken by a far digit, the edge of the normals, and too many digits
+    assert_eq!(bits("9007199254740993"), 9007199254740992f64.to_bits());
+    assert_eq!(bits("9007199254740993.000000000000000000000000001"), 9007199254740994f64.to_bits());
+    assert_eq!(bits("9007199254740995"), 9007199254740996f64.to_bits());
+    assert_eq!(bits("2.2250738585072011e-308"), 0x000FFFFFFFFFFFFF);
+    assert_eq!(bits("2.2250738585072012e-308"), 0x0010000000000000);
+    // half the smallest subnormal, exactly, is a tie to zero; a hair over isn't
+    let half = "2.4703282292062327208828439643411068618252990130716238221279284125033775363510437593264991818081799618989828234772285886546332835517796989819938739800539093906315035659515570226392290858392449105184435931802849936536152500319370457678249219365623669863658480757001585769269903706311928279558551332927834338409351978015531246597263579574622766465272827220056374006485499977096599470454020828166226237857393450736339007967761930577506740176324673600968951340535537458516661134223766678604162159680461914467291840300530057530849048765391711386591646239524912623653881879636239373280423891018672348497668235089863388587925628302755995657524455507255189313690836254779186948667994968324049705821028513185451396213837722826145437693412532098591327667236328125e-324";
+    assert_eq!(bits(half), 0);
+    assert_eq!(bits(&half.replace("125e-324", "1250000000000000000000000000000000001e-324")), 1);
+    let long = format!("1.{}1", "0".repeat(20000));
+    assert_eq!(parse(&long, RoundingMode::TowardPositive).0, 1f64.next_up().to_bits());
+    assert_eq!(bits(&format!("{}e-20000", "1".repeat(20001))), bits("1.1111111111111111111"));
+    assert_eq!(bits("0.000000000000000000000000000000000000000000000000001e51"), 1f64.to_bits());
+    assert_eq!(bits(".5"), 0.5f64.to_bits());
+    assert_eq!(bits("5."), 5f64.to_bits());
+    assert_eq!(bits("-0.0e7"), (-0f64).to_bits());
+    assert_eq!(bits("+1E+2"), 100f64.to_bits());
+
+    // out of range, with the flags
+    assert_eq!(parse("1e309", RoundingMode::TiesToEven), (0x7FF0000000000000, Flags::OVERFLOW | Flags::INEXACT));
+    assert_eq!(parse("-1e99999999999999999999", RoundingMode::TowardZero).0, (-f64::MAX).to_bits());
+    assert_eq!(parse("1e-400", RoundingMode::TiesToEven), (0, Flags::UNDERFLOW | Flags::INEXACT));
+    assert_eq!(parse("1e-99999999999999999999", RoundingMode::TowardPositive).0, 1);
+    assert_eq!(parse("1e-320", RoundingMode::TiesToEven).1, Flags::UNDERFLOW | Flags::INEXACT);
+    assert_eq!(parse("0.5", RoundingMode::TiesToEven).1, Flags(0));
+
+    // hex floats, inf and nan
+    assert_eq!(bits("0x1.8p3"), 12f64.to_bits());
+    assert_eq!(bits("-0X1P-1074"), (-5e-324f64).to_bits());
+    assert_eq!(bits("0x1p-1075"), 0);
+    assert_eq!(bits("0x1.8p-1074"), 2);
+    assert_eq!(bits("0x10"), 16f64.to_bits());
+    assert_eq!(bits("0x.000000000000000000000000000000000000001p160"), 16f64.to_bits());
+    assert_eq!(bits("0x1.fffffffffffff8p1023"), f64::INFINITY.to_bits());
+    assert_eq!("0x1p-149".parse::<F32>().unwrap().to_bits(), 1);
+    assert_eq!(bits("Infinity"), f64::INFINITY.to_bits());
+    assert_eq!(bits("-inf"), f64::NEG_INFINITY.to_bits());
+    assert!(F64::from_bits(bits("nan")).is_nan() && F64::from_bits(bits("-NaN")).get_sign());
+    for bad in ["", "-", "abc", "1e", "1e+", "1.2.3", "--1", "0x", "e5", "0x1p", "1 ", " 1", "1_0", "0x1.8q", "infinit"] {
+        assert!(bad.parse::<F64>().is_err(), "{bad:?}");
+    }
+    assert_eq!("1.5".parse::<Float>().unwrap().to_f64(), 1.5);
+
+    // the exact digits, and that they read back exactly, in every format
+    assert_eq!(F64::from_bits(0.1f64.to_bits()).to_exact_decimal(), "0.1000000000000000055511151231257827021181583404541015625");
+    assert_eq!(F64::from_bits(1e22f64.to_bits()).to_exact_decimal(), "10000000000000000000000");
+    assert_eq!(F64::from_bits(1e23f64.to_bits()).to_exact_decimal(), "99999999999999991611392");
+    assert_eq!(F64::from_bits(1.5f64.to_bits()).to_exact_decimal(), "1.5");
+    assert_eq!(F64::from_bits((-0f64).to_bits()).to_exact_decimal(), "-0");
+    assert_eq!(F16::largest_finite(true).to_exact_decimal(), "-65504");
+    assert_eq!(F16::from_bits(1).to_exact_decimal(), "0.000000059604644775390625");
+    let tiny = F64::from_bits(1).to_exact_decimal();
+    assert!(tiny.starts_with("0.000") && tiny.ends_with("625") && tiny.len() == 2 + 1074, "{tiny}");
+    assert_eq!(F80::infinity(true).to_exact_decimal(), "-inf");
+    fn round_trips<F: Format>(x: SoftFloat<F>) {
+        if x.is_nan() {
+            return;
+        }
+        let mut ctx = Context::new(RoundingMode::TowardZero);
+        let exact = x.to_exact_decimal();
+        assert_eq!(SoftFloat::<F>::parse(&exact, &mut ctx).map(|y| y.to_bits()), Ok(x.to_bits()), "{exact}");
+        assert_eq!(ctx.flags, Flags(0), "{exact}");
+        let shortest = x.to_shortest_decimal();
+        assert_eq!(shortest.parse::<SoftFloat<F>>().map(|y| y.to_bits()), Ok(x.to_bits()), "{shortest}");
+    }
+    for bits in 0..=u16::MAX {
+        round_trips(F16::from_bits(bits));
+    }
+    for bits in 0..=u8::MAX {
+        round_trips(SoftFloat::<Minifloat>::from_bits(bits));
+    }
+    for i in 0..2000 {
+        round_trips(F32::from_bits(rng.random()));
+        round_trips(F64::from_bits(rng.random()));
+        if i % 10 == 0 {
+            round_trips(F80::from_bits(rng.random::<u128>() >> 49)); // up to 16000 digits each way, so fewer
+        }
+    }
+
+    // shortest digits are the host's {:e}, which is also the shortest round trip (nearest on a choice)
+    for _ in 0..30_000 {
+        let x = f64::from_bits(rng.random());
+        assert_eq!(F64::from_bits(x.to_bits()).to_shortest_decimal(), format!("{x:e}"));
+        let y = f32::from_bits(rng.random());
+        assert_eq!(F32::from_bits(y.to_bits()).to_shortest_decimal(), format!("{y:e}"));
+    }
+    for x in [0.0, -0.0, 1.0, 0.3, 1e23, 5e-324, f64::MAX, f64::MIN_POSITIVE, 2f64.powi(-1022) * 3.0, 9007199254740993.0] {
+        assert_eq!(F64::from_bits(x.to_bits()).to_shortest_decimal(), format!("{x:e}"));
+    }
+    assert_eq!(F16::largest_finite(false).to_shortest_decimal(), "6.55e4");
+    assert_eq!("0.1".parse::<F80>().unwrap().to_shortest_decimal(), "1e-1");
+    assert_eq!(F80::largest_finite(false).to_shortest_decimal(), "1.189731495357231765e4932");
+    // the neighbors inspect shows, against Float's
+    for _ in 0..30_000 {
+        let x: u64 = rng.random();
+        let (f, s) = (Float::from_bits(x), F64::from_bits(x));
+        assert_eq!(s.next_up().to_bits(), f.next_up().to_bits(), "{x:#x}");
+        assert_eq!(s.next_down().to_bits(), f.next_down().to_bits(), "{x:#x}");
+    }
+    for x in [0, 1 << 63, 1, 0x8000000000000001, 0x7FEFFFFFFFFFFFFF, 0x7FF0000000000000, 0xFFF0000000000000, 0x7FF8000000000000] {
+        let (f, s) = (Float::from_bits(x), F64::from_bits(x));
+        assert_eq!(s.next_up().to_bits(), f.next_up().to_bits(), "{x:#x}");
+        assert_eq!(s.next_down().to_bits(), f.next_down().to_bits(), "{x:#x}");
+    }
+    println!("Decimal test passed!");
+}
+
+fn strtod_torture_test() {
+    // the classic hard inputs for decimal to binary conversion, each with the value it rounds to nearest and the
+    // two it rounds to toward -inf and +inf (equal when the input is exact). Paxson's stress inputs for 53 bits,
+    // which are within 1e-3 to 1e-25 of an ulp from halfway, then inputs from Gay's dtoa tests and from parser
+    // bug reports (php and java's 2.2250738585072012e-308 hang, exact ties and the digit either side of them,
+    // the ends of the subnormals and of the finite range), and the same sort of cases for binary32. after the
+    // table, testbase-style generated ties: the exact digits of the midpoint of two neighbouring doubles, and
+    // digits a hair above and below it. the table's nearest values are checked against the host's parser too, and
+    // every case is tried negated
+    use floatfs::format::{Binary32, Binary64, Storage};
+    use floatfs::{Context, Flags, Format, RoundingMode, SoftFloat, F64, F80};
+    use rand::Rng;
+
+    const BINARY64: &[(&str, u64, u64, u64)] = &[
+        ("9e-265", 0x091D05244FE5066A, 0x091D05244FE50669, 0x091D05244FE5066A),
+        ("85e-37", 0x38A698CCDC60015A, 0x38A698CCDC600159, 0x38A698CCDC60015A),
+        ("623e+100", 0x554640A62F3A83DF, 0x554640A62F3A83DE, 0x554640A62F3A83DF),
+        ("3571e+263", 0x77462644C61D41AA, 0x77462644C61D41A9, 0x77462644C61D41AA),
+        ("81661e+153", 0x60B7CA8E3D68578E, 0x60B7CA8E3D68578D, 0x60B7CA8E3D68578E),
+        ("920657e-23", 0x3C653A9985DBDE6C, 0x3C653A9985DBDE6B, 0x3C653A9985DBDE6C),
+        ("87575437e-309", 0x016E07320602056C, 0x016E07320602056B, 0x016E07320602056C),
+        ("245540327e+122", 0x5B01B6231E18C5CB, 0x5B01B6231E18C5CA, 0x5B01B6231E18C5CB),
+        ("83356057653e+193", 0x6A4544E6DAEE2A18, 0x6A4544E6DAEE2A17, 0x6A4544E6DAEE2A18),
+        ("2335141086879e+218", 0x6FC340A1C932C1EE, 0x6FC340A1C932C1ED, 0x6FC340A1C932C1EE),
+        ("36167929443327e-159", 0x21BCE77C2B3328FC, 0x21BCE77C2B3328FB, 0x21BCE77C2B3328FC),
+        ("609610927149051e-255", 0x0E104273B18918B1, 0x0E104273B18918B0, 0x0E104273B18918B1),
+        ("3743626360493413e-165", 0x20E8823A57ADBEF9, 0x20E8823A57ADBEF8, 0x20E8823A57ADBEF9),
+        ("94080055902682397e-242", 0x11364981E39E66CA, 0x11364981E39E66C9, 0x11364981E39E66CA),
+        ("899810892172646163e+283", 0x7E6ADF51FA055E03, 0x7E6ADF51FA055E02, 0x7E6ADF51FA055E03),
+        ("7120190517612959703e+120", 0x5CC3220DCD5899FD, 0x5CC3220DCD5899FC, 0x5CC3220DCD5899FD),
+        ("25188282901709339043e-252", 0x0FA4059AF3DB2A84, 0x0FA4059AF3DB2A83, 0x0FA4059AF3DB2A84),
+        ("308984926168550152811e-52", 0x39640DE48676653B, 0x39640DE48676653A, 0x39640DE48676653B),
+        ("6372891218502368041059e+64", 0x51C067047DBB38FE, 0x51C067047DBB38FD, 0x51C067047DBB38FE),
+        ("5e22", 0x44A52D02C7E14AF6, 0x44A52D02C7E14AF6, 0x44A52D02C7E14AF7),
+        ("7693e-42", 0x3804F13D0FFFE4A1, 0x3804F13D0FFFE4A1, 0x3804F13D0FFFE4A2),
+        ("9214843084008499", 0x43405E6CEC57761A, 0x43405E6CEC577619, 0x43405E6CEC57761A),
+        ("30078505129381147446200", 0x44997A3C7271B021, 0x44997A3C7271B020, 0x44997A3C7271B021),
+        ("1777820000000000000001", 0x4458180D5BAD2E3E, 0x4458180D5BAD2E3D, 0x4458180D5BAD2E3E),
+        ("0.3932922657273", 0x3FD92BB352C4623A, 0x3FD92BB352C46239, 0x3FD92BB352C4623A),
+        ("62.5364939768271845828", 0x404F44ABD5AA7CA4, 0x404F44ABD5AA7CA3, 0x404F44ABD5AA7CA4),
+        ("8.10109172351e-10", 0x3E0BD5CBAEF0FD0C, 0x3E0BD5CBAEF0FD0C, 0x3E0BD5CBAEF0FD0D),
+        ("3.518437208883201171875e13", 0x42C0000000000002, 0x42C0000000000001, 0x42C0000000000002),
+        ("1.00000000000000011102230246251565404236316680908203125", 0x3FF0000000000000, 0x3FF0000000000000, 0x3FF0000000000001),
+        ("1.00000000000000011102230246251565404236316680908203124", 0x3FF0000000000000, 0x3FF0000000000000, 0x3FF0000000000001),
+        ("1.00000000000000011102230246251565404236316680908203126", 0x3FF0000000000001, 0x3FF0000000000000, 0x3FF0000000000001),
+        ("0.500000000000000166533453693773481063544750213623046875", 0x3FE0000000000002, 0x3FE0000000000001, 0x3FE0000000000002),
+        ("2.2250738585072011e-308", 0x000FFFFFFFFFFFFF, 0x000FFFFFFFFFFFFF, 0x0010000000000000),
+        ("2.2250738585072012e-308", 0x0010000000000000, 0x000FFFFFFFFFFFFF, 0x0010000000000000),
+        ("2.4703282292062327e-324", 0x0000000000000000, 0x0000000000000000, 0x0000000000000001),
+        ("2.4703282292062328e-324", 0x0000000000000001, 0x0000000000000000, 0x0000000000000001),
+        ("4.9406564584124654e-324", 0x0000000000000001, 0x0000000000000000, 0x0000000000000001),
+        ("1.7976931348623157e308", 0x7FEFFFFFFFFFFFFF, 0x7FEFFFFFFFFFFFFE, 0x7FEFFFFFFFFFFFFF),
+        ("1.7976931348623158e308", 0x7FEFFFFFFFFFFFFF, 0x7FEFFFFFFFFFFFFF, 0x7FF0000000000000),
+        ("1.7976931348623159e308", 0x7FF0000000000000, 0x7FEFFFFFFFFFFFFF, 0x7FF0000000000000),
+        ("179769313486231580793728971405303415079934132710037826936173778980444968292764750946649017977587207096330286416692887910946555547851940402630657488671505820681908902000708383676273854845817711531764475730270069855571366959622842914819860834936475292719074168444365510704342711559699508093042880177904174497791", 0x7FEFFFFFFFFFFFFF, 0x7FEFFFFFFFFFFFFF, 0x7FF0000000000000),
+        ("179769313486231580793728971405303415079934132710037826936173778980444968292764750946649017977587207096330286416692887910946555547851940402630657488671505820681908902000708383676273854845817711531764475730270069855571366959622842914819860834936475292719074168444365510704342711559699508093042880177904174497792", 0x7FF0000000000000, 0x7FEFFFFFFFFFFFFF, 0x7FF0000000000000),
+    ];
+    const BINARY32: &[(&str, u32, u32, u32)] = &[
+        ("7.038531e-26", 0x15AE43FD, 0x15AE43FD, 0x15AE43FE),
+        ("1.00000005960464477539062499", 0x3F800000, 0x3F800000, 0x3F800001),
+        ("1.000000059604644775390625", 0x3F800000, 0x3F800000, 0x3F800001),
+        ("1.00000005960464477539062501", 0x3F800001, 0x3F800000, 0x3F800001),
+        ("1.00000017881393432617187499", 0x3F800001, 0x3F800001, 0x3F800002),
+        ("1.000000178813934326171875", 0x3F800002, 0x3F800001, 0x3F800002),
+        ("1.00000017881393432617187501", 0x3F800002, 0x3F800001, 0x3F800002),
+        ("3.4028235677973366e38", 0x7F7FFFFF, 0x7F7FFFFF, 0x7F800000),
+        ("340282356779733661637539395458142568448", 0x7F800000, 0x7F7FFFFF, 0x7F800000),
+        ("1.4e-45", 0x00000001, 0x00000000, 0x00000001),
+        ("7e-46", 0x00000000, 0x00000000, 0x00000001),
+        ("7.006492321624085354618e-46", 0x00000000, 0x00000000, 0x00000001),
+        ("7.0064923216240853546187e-46", 0x00000001, 0x00000000, 0x00000001),
+        ("1.175494140627517859246e-38", 0x007FFFFE, 0x007FFFFE, 0x007FFFFF),
+        ("1.1754942807573642917e-38", 0x007FFFFF, 0x007FFFFF, 0x00800000),
+        ("16777217", 0x4B800000, 0x4B800000, 0x4B800001),
+        ("16777219", 0x4B800002, 0x4B800001, 0x4B800002),
+        ("33554434.999999999", 0x4C000001, 0x4C000000, 0x4C000001),
+        ("8.589973e9", 0x50000026, 0x50000025, 0x50000026),
+        ("1.5e-45", 0x00000001, 0x00000001, 0x00000002),
+        ("2.1e-45", 0x00000001, 0x00000001, 0x00000002),
+        ("4.7223665e21", 0x63800000, 0x63800000, 0x63800001),
+        ("3.4028234e38", 0x7F7FFFFF, 0x7F7FFFFE, 0x7F7FFFFF),
+    ];
+
+    fn check<F: Format>(s: &str, nearest: u128, down: u128, up: u128) {
+        for (sign, negative) in [("", false), ("-", true)] {
+            let input = format!("{sign}{s}");
+            let flip = |bits: u128| bits | (negative as u128) << (F::EXPONENT_BITS + F::MANTISSA_BITS);
+            let (below, above) = if negative { (flip(up), flip(down)) } else { (flip(down), flip(up)) };
+            let expected = [
+                (RoundingMode::TiesToEven, flip(nearest)),
+                (RoundingMode::TowardNegative, below),
+                (RoundingMode::TowardPositive, above),
+                (RoundingMode::TowardZero, flip(down)),
+            ];
+            for (mode, bits) in expected {
+                let mut ctx = Context::new(mode);
+                let x = SoftFloat::<F>::parse(&input, &mut ctx).unwrap_or_else(|_| panic!("{input} didn't parse"));
+                assert_eq!(x.to_bits().to_u128(), bits, "{input} {mode:?} in {}", F::NAME);
+                assert_eq!(ctx.flags.contains(Flags::INEXACT), down != up, "{input} {mode:?} in {}", F::NAME);
+            }
+        }
+    }
+    for &(s, nearest, down, up) in BINARY64 {
+        assert_eq!(s.parse::<f64>().unwrap().to_bits(), nearest, "{s}");
+        check::<Binary64>(s, nearest as u128, down as u128, up as u128);
+    }
+    for &(s, nearest, down, up) in BINARY32 {
+        assert_eq!(s.parse::<f32>().unwrap().to_bits(), nearest, "{s}");
+        check::<Binary32>(s, nearest as u128, down as u128, up as u128);
+    }
+
+    // the midpoint of a double and the next one up takes 54 bits, so it's exact in x87 extended, and so are its
+    // digits
+    let mut rng = rand::rng();
+    let mut ctx = Context::default();
+    let half: F80 = F64::from_bits(0.5f64.to_bits()).convert(&mut ctx);
+    for _ in 0..20_000 {
+        let low = rng.random::<u64>() & !(1 << 63);
+        let high = low + 1;
+        if high >= 0x7FF0000000000000 {
+            continue;
+        }
+        let (a, b): (F80, F80) = (F64::from_bits(low).convert(&mut ctx), F64::from_bits(high).convert(&mut ctx));
+        let midpoint = a.add(&b, &mut ctx).multiply(&half, &mut ctx).to_exact_decimal();
+        let even = if low % 2 == 0 { low } else { high };
+        check::<Binary64>(&midpoint, even as u128, low as u128, high as u128);
+        // a digit past the end, or one off the last digit with 9s after it, moves the input off the tie
+        let point = if midpoint.contains('.') { "" } else { "." };
+        check::<Binary64>(&format!("{midpoint}{point}00000000000000000001"), high as u128, low as u128, high as u128);
+        let (digits, last) = midpoint.split_at(midpoint.len() - 1);
+        let last = last.parse::<u8>().unwrap();
+        if last > 0 {
+            let below = format!("{digits}{}{point}99999999999999999999", last - 1);
+            check::<Binary64>(&below, low as u128, low as u128, high as u128);
+        }
+    }
+    println!("Strtod torture test passed!");
+}
+
+fn round_trip_test() {
+    // parse(to_shortest_decimal(x)) and parse(to_exact_decimal(x)) give x back bit for bit, in every format, on
+    // random bits and on the edges random::special picks out. the exact digits read back in every mode with no
+    // flags raised, since nothing rounds; the shortest ones only promise it to nearest, and are no longer than
+    // the exact ones. (special draws a quarter of its values as uniformly random bits.) there's no binary128, as
+    // SoftFloat's arithmetic stops at 63 mantissa bits
+    use floatfs::format::{BFloat16, Binary16, Binary32, Binary64, Extended, Minifloat, E5M2};
+    use floatfs::presets::{Fp24, Pxr24};
+    use floatfs::{random, Context, Flags, Format, RoundingMode, SoftFloat};
+    use rand::RngCore;
+
+    const MODES: [RoundingMode; 5] = [
+        RoundingMode::TiesToEven,
+        RoundingMode::TiesToAway,
+        RoundingMode::TowardZero,
+        RoundingMode::TowardPositive,
+        RoundingMode::TowardNegative,
+    ];
+
+    fn run<F: Format>(count: usize) {
+        let mut bits = || rand::rng().next_u64();
+        for _ in 0..count {
+            let x = random::special::<F>(&mut bits);
+            let (shortest, exact) = (x.to_shortest_decimal(), x.to_exact_decimal());
+            let back = shortest.parse::<SoftFloat<F>>().unwrap_or_else(|_| panic!("{shortest} didn't parse"));
+            if x.is_nan() {
+                assert!(back.is_nan() && exact.parse::<SoftFloat<F>>().is_ok_and(|y| y.is_nan()), "{}", F::NAME);
+                continue;
+            }
+            assert_eq!(back.to_bits(), x.to_bits(), "{shortest} in {}", F::NAME);
+            let significant = |s: &str| s.split('e').next().unwrap_or("").chars().filter(char::is_ascii_digit).count();
+            let digits = exact.trim_start_matches(['-', '0', '.']).trim_end_matches('0');
+            assert!(significant(&shortest) <= digits.len().max(1), "{shortest} {exact} in {}", F::NAME);
+            for mode in MODES {
+                let mut ctx = Context::new(mode);
+                let y = SoftFloat::<F>::parse(&exact, &mut ctx).unwrap_or_else(|_| panic!("{exact} didn't parse"));
+                assert_eq!(y.to_bits(), x.to_bits(), "{exact} {mode:?} in {}", F::NAME);
+                assert_eq!(ctx.flags, Flags::empty(), "{exact} {mode:?} in {}", F::NAME);
+            }
+        }
+    }
+    run::<Minifloat>(10_000);
+    run::<Binary16>(100_000);
+    run::<BFloat16>(100_000);
+    run::<E5M2>(10_000);
+    run::<Pxr24>(100_000);
+    run::<Fp24>(100_000);
+    run::<Binary32>(100_000);
+    run::<Binary64>(50_000);
+    run::<Extended>(2_000); // up to 16000 digits each way
+    println!("Round trip test passed!");
+}
+
+fn engineering_test() {
+    // to_engineering_decimal against the host's {:.*e}, which rounds the exact value to nearest with ties to even
+    // too, with the point moved to make the exponent a multiple of 3; then the carries and zeros by hand
+    use floatfs::{F32, F64};
+    use rand::Rng;
+
+    fn engineering(scientific: &str) -> String {
+        let (mantissa, exponent) = scientific.split_once('e').unwrap();
+        let (sign, mantissa) = mantissa.strip_prefix('-').map_or(("", mantissa), |m| ("-", m));
+        let mut digits = mantissa.replace('.', "");
+        let exponent: i64 = exponent.parse().unwrap();
+        let three = exponent.div_euclid(3) * 3;
+        let before = (exponent - three + 1) as usize;
+        if before >= digits.len() {
+            digits.push_str(&"0".repeat(before - digits.len()));
+        } else {
+            digits.insert(before, '.');
+        }
+        format!("{sign}{digits}e{three}")
+    }
+
+    let mut rng = rand::rng();
+    for _ in 0..100_000 {
+        let n = rng.random_range(1..=20);
+        let x = f64::from_bits(rng.random());
+        if x.is_finite() {
+            let expected = engineering(&format!("{:.*e}", n - 1, x));
+            assert_eq!(F64::from_bits(x.to_bits()).to_engineering_decimal(n), expected, "{x:e} to {n}");
+        }
+        let y = f32::from_bits(rng.random());
+        if y.is_finite() {
+            let expected = engineering(&format!("{:.*e}", n - 1, y));
+            assert_eq!(F32::from_bits(y.to_bits()).to_engineering_decimal(n), expected, "{y:e} to {n}");
+        }
+    }
+    let eng = |x: f64, n: usize| F64::from_bits(x.to_bits()).to_engineering_decimal(n);
+    assert_eq!(eng(12340.0, 4), "12.34e3");
+    assert_eq!(eng(0.00012, 2), "120e-6");
+    assert_eq!(eng(999.96, 4), "1.000e3");
+    assert_eq!(eng(999999.0, 2), "1.0e6");
+    assert_eq!(eng(123456.0, 2), "120e3");
+    assert_eq!(eng(2.5, 1), "2e0");
+    assert_eq!(eng(3.5, 1), "4e0");
+    assert_eq!(eng(0.125, 2), "120e-3");
+    assert_eq!(eng(0.125, 3), "125e-3");
+    assert_eq!(eng(0.125, 0), "100e-3");
+    assert_eq!(eng(-0.0, 3), "-0.00e0");
+    assert_eq!(eng(f64::NEG_INFINITY, 3), "-inf");
+    assert_eq!(eng(f64::NAN, 3), "NaN");
+    println!("Engineering test passed!");
+}
+
+fn fixed_decimal_test() {
+    // to_fixed_decimal against the host's {:.*}, which rounds the exact value to nearest with ties to even, with
+    // the separators put in afterwards, so they can't have moved a digit
+    use floatfs::{F32, F64};
+    use rand::Rng;
+
+    fn grouped(plain: &str, separator: char) -> String {
+        let (sign, rest) = plain.strip_prefix('-').map_or(("", plain), |r| ("-", r));
+        let (integer, fraction) = rest.split_once('.').map_or((rest, None), |(i, f)| (i, Some(f)));
+        let mut text = String::from(sign);
+        for (i, c) in integer.chars().enumerate() {
+            if i > 0 && (integer.len() - i) % 3 == 0 {
+                text.push(separator);
+            }
+            text.push(c);
+        }
+        if let Some(fraction) = fraction {
+            text.push('.');
+            text.push_str(fraction);
+        }
+        text
+    }
+
+    let mut rng = rand::rng();
+    for i in 0..100_000 {
+        let places = rng.random_range(0..30);
+        // wide exponents on some, where the digits run into the hundreds, everyday values on the rest
+        let x = if i % 4 == 0 { f64::from_bits(rng.random()) } else { rng.random_range(-1e9..1e9) * 10f64.powi(-rng.random_range(0..12)) };
+        if x.is_finite() {
+            let s = F64::from_bits(x.to_bits());
+            assert_eq!(s.to_fixed_decimal(places, None), format!("{:.*}", places, x), "{x:e} to {places}");
+            assert_eq!(s.to_fixed_decimal(places, Some('_')), grouped(&format!("{:.*}", places, x), '_'), "{x:e}");
+        }
+        let y = x as f32;
+        if y.is_finite() {
+            assert_eq!(F32::from_bits(y.to_bits()).to_fixed_decimal(places, Some(',')), grouped(&format!("{:.*}", places, y), ','), "{y:e}");
+        }
+    }
+    let fixed = |x: f64, places: usize, separator: Option<char>| F64::from_bits(x.to_bits()).to_fixed_decimal(places, separator);
+    assert_eq!(fixed(1234567.125, 2, Some(',')), "1,234,567.12");
+    assert_eq!(fixed(1234567.375, 2, Some(',')), "1,234,567.38");
+    assert_eq!(fixed(999.5, 0, Some(' ')), "1 000");
+    assert_eq!(fixed(123456.0, 0, Some('\'')), "123'456");
+    assert_eq!(fixed(-999999.996, 2, Some(',')), "-1,000,000.00");
+    assert_eq!(fixed(0.5, 0, Some(',')), "0");
+    assert_eq!(fixed(-0.001, 2, Some(',')), "-0.00");
+    assert_eq!(fixed(0.0, 3, None), "0.000");
+    assert_eq!(fixed(f64::INFINITY, 2, Some(',')), "inf");
+    println!("Fixed decimal test passed!");
+}
+
+fn decimal64_test() {
+    // to_decimal64 against encodings worked out with Python's decimal module (prec=16, Emin=-383, Emax=384,
+    // clamp=1), which rounds the exact value of Decimal(float) once, as the BID encoding laid over the result;
+    // from_decimal64 at the edges of binary64's range and on the encodings that aren't plain numbers
+    use floatfs::{Context, Flags, RoundingMode, F64, F80};
+
+    let d64 = |x: f64, mode: RoundingMode| {
+        let mut ctx = Context::new(mode);
+        (F64::from_bits(x.to_bits()).to_decimal64(&mut ctx), ctx.flags)
+    };
+    let nearest = |x: f64| d64(x, RoundingMode::TiesToEven).0;
+    assert_eq!(nearest(1.0), 0x31C0000000000001);
+    assert_eq!(nearest(100.0), 0x31C0000000000064); // an integer keeps its zeros at 10^0
+    assert_eq!(nearest(1.5), 0x31A000000000000F);
+    assert_eq!(nearest(-0.0), 0xB1C0000000000000);
+    assert_eq!(nearest(0.1), 0x2FC38D7EA4C68000); // 0.1000000000000000
+    assert_eq!(nearest(1.1), 0x2FE3E871B540C000);
+    assert_eq!(nearest(2f64.powi(60)), 0x3224189374BC6A7F); // 1152921504606847e3, past 2^53: the 11 form
+    assert_eq!(nearest(f64::MAX), 0x566662FE0CB7F7EC);
+    assert_eq!(nearest(f64::from_bits(1)), 0x07718D80392931B1);
+    assert_eq!(nearest(f64::INFINITY), 0x7800000000000000);
+    assert_eq!(nearest(-f64::NAN), 0xFC00000000000000);
+    assert_eq!(d64(1.0 / 3.0, RoundingMode::TiesToEven), (0x2FCBD7A625405555, Flags::INEXACT));
+    assert_eq!(d64(1.0 / 3.0, RoundingMode::TowardPositive), (0x2FCBD7A625405556, Flags::INEXACT));
+    assert_eq!(d64(-1.0 / 3.0, RoundingMode::TowardNegative), (0xAFCBD7A625405556, Flags::INEXACT));
+    assert_eq!(d64(0.5, RoundingMode::TowardZero), (0x31A0000000000005, Flags::empty()));
+    assert_eq!(d64(f64::from_bits(0x7FF0000000000001), RoundingMode::TiesToEven), (0x7C00000000000000, Flags::INVALID));
+    // 2^-24 is 5.9604644775390625e-8, 17 digits, so halfway between two 16-digit decimals
+    assert_eq!(d64(2f64.powi(-24), RoundingMode::TiesToEven), (0x2EF52D02C7E14AF6, Flags::INEXACT));
+    assert_eq!(d64(2f64.powi(-24), RoundingMode::TiesToAway), (0x2EF52D02C7E14AF7, Flags::INEXACT));
+
+    // x87 extended reaches past decimal64's range at both ends, where binary64 doesn't
+    let wide = |bits: u128, mode: RoundingMode| {
+        let mut ctx = Context::new(mode);
+        (F80::from_bits(bits).to_decimal64(&mut ctx), ctx.flags)
+    };
+    let largest = 0x7FFE << 63 | ((1 << 63) - 1);
+    assert_eq!(wide(largest, RoundingMode::TiesToEven), (0x7800000000000000, Flags::OVERFLOW | Flags::INEXACT));
+    assert_eq!(wide(largest, RoundingMode::TowardZero), (0x77FB86F26FC0FFFF, Flags::OVERFLOW | Flags::INEXACT));
+    let tiny = 0x3AEB << 63; // 2^-1300, 4.581478e-392 as a subnormal decimal64
+    assert_eq!(wide(tiny, RoundingMode::TiesToEven), (0x000000000045E866, Flags::UNDERFLOW | Flags::INEXACT));
+
+    let from = |bits: u64, mode: RoundingMode| {
+        let mut ctx = Context::new(mode);
+        (F64::from_decimal64(bits, &mut ctx).to_bits(), ctx.flags)
+    };
+    let largest = 0x77FB86F26FC0FFFF; // 9999999999999999e369
+    assert_eq!(from(largest, RoundingMode::TiesToEven), (0x7FF0000000000000, Flags::OVERFLOW | Flags::INEXACT));
+    assert_eq!(from(largest, RoundingMode::TowardZero), (0x7FEFFFFFFFFFFFFF, Flags::OVERFLOW | Flags::INEXACT));
+    assert_eq!(from(1, RoundingMode::TiesToEven), (0, Flags::UNDERFLOW | Flags::INEXACT)); // 1e-398
+    assert_eq!(from(1, RoundingMode::TowardPositive), (1, Flags::UNDERFLOW | Flags::INEXACT));
+    assert_eq!(from(0x31A0000000000001, RoundingMode::TiesToEven), (0x3FB999999999999A, Flags::INEXACT)); // 0.1
+    assert_eq!(from(0x31A0000000000001, RoundingMode::TowardZero), (0x3FB9999999999999, Flags::INEXACT));
+    assert_eq!(from(0x31C0000000000064, RoundingMode::TiesToEven), (100f64.to_bits(), Flags::empty()));
+    // 9999999999999999 in the 11 form, odd and past 2^53 for binary64 but exact in x87 extended
+    assert_eq!(from(0x6C7386F26FC0FFFF, RoundingMode::TiesToEven), (1e16f64.to_bits(), Flags::INEXACT));
+    assert_eq!(F80::from_decimal64(0x6C7386F26FC0FFFF, &mut Context::default()).to_bits(), 0x201A0E1BC9BF03FFFC00);
+    // a coefficient past 10^16 - 1 isn't canonical, and is a zero
+    assert_eq!(from(0x6FFFFFFFFFFFFFFF, RoundingMode::TiesToEven), (0, Flags::empty()));
+    assert_eq!(from(0xF800000000000000, RoundingMode::TiesToEven), ((-f64::INFINITY).to_bits(), Flags::empty()));
+    let mut ctx = Context::default();
+    let snan = F64::from_decimal64(0x7E00000000000000, &mut ctx);
+    assert!(snan.is_nan() && !snan.is_signaling_nan() && ctx.flags == Flags::INVALID);
+    let mut ctx = Context::default();
+    let nan = F64::from_decimal64(0xFC00000000000000, &mut ctx);
+    assert!(nan.is_nan() && nan.get_sign() && ctx.flags.is_empty());
+    println!("Decimal64 test passed!");
+}
+
+fn decimal128_test() {
+    // to_decimal128 against Python's decimal module again (prec=34, Emin=-6143, Emax=6144, clamp=1), in every
+    // mode on a tie and off one, and from_decimal128 at the edges of its range, where x87 extended overflows and
+    // underflows
+    use floatfs::{Context, Flags, RoundingMode, F64, F80};
+
+    const MODES: [RoundingMode; 5] = [
+        RoundingMode::TiesToEven,
+        RoundingMode::TiesToAway,
+        RoundingMode::TowardZero,
+        RoundingMode::TowardPositive,
+        RoundingMode::TowardNegative,
+    ];
+    let d128 = |x: f64, mode: RoundingMode| {
+        let mut ctx = Context::new(mode);
+        (F64::from_bits(x.to_bits()).to_decimal128(&mut ctx), ctx.flags)
+    };
+    let nearest = |x: f64| d128(x, RoundingMode::TiesToEven).0;
+    assert_eq!(nearest(1.0), 0x30400000000000000000000000000001);
+    assert_eq!(nearest(100.0), 0x30400000000000000000000000000064);
+    assert_eq!(nearest(1.5), 0x303E000000000000000000000000000F);
+    assert_eq!(nearest(2f64.powi(60)), 0x30400000000000001000000000000000); // exact: 19 digits fit
+    assert_eq!(nearest(0.1), 0x2FFC314DC6448D933986922312364CE3);
+    assert_eq!(nearest(f64::MAX), 0x326658A213CC7A4FFAE03C4825156FB4);
+    assert_eq!(nearest(f64::from_bits(1)), 0x2D76F397DA03AF06AA833FD25715F6E6);
+    assert_eq!(nearest(-f64::INFINITY), 0xF8000000000000000000000000000000);
+    assert_eq!(nearest(f64::NAN), 0x7C000000000000000000000000000000);
+    // a third, and 2^-50, whose 35 digits 8.8817841970012523233890533447265625e-16 are a tie at 34
+    let third = [0x2FFCA45894E48295654877CDC34AFFB6, 0x2FFCA45894E48295654877CDC34AFFB5];
+    let tie = [0x2FDFB5E7E08CA3A8F6987819BAECBE22, 0x2FDFB5E7E08CA3A8F6987819BAECBE23];
+    let sign = 1 << 127;
+    for (mode, up, tie_up) in [
+        (RoundingMode::TiesToEven, true, false),
+        (RoundingMode::TiesToAway, true, true),
+        (RoundingMode::TowardZero, false, false),
+        (RoundingMode::TowardPositive, true, true),
+        (RoundingMode::TowardNegative, false, false),
+    ] {
+        let (third_down, tie_down) = match mode {
+            RoundingMode::TowardPositive => (false, false),
+            RoundingMode::TowardNegative => (true, true),
+            _ => (up, tie_up),
+        };
+        assert_eq!(d128(1.0 / 3.0, mode), (third[!up as usize], Flags::INEXACT), "{mode:?}");
+        assert_eq!(d128(-1.0 / 3.0, mode), (sign | third[!third_down as usize], Flags::INEXACT), "{mode:?}");
+        assert_eq!(d128(2f64.powi(-50), mode), (tie[tie_up as usize], Flags::INEXACT), "{mode:?}");
+        assert_eq!(d128(-(2f64.powi(-50)), mode), (sign | tie[tie_down as usize], Flags::INEXACT), "{mode:?}");
+    }
+    // x87 extended's whole range fits, the smallest subnormal with room to spare
+    let wide = |bits: u128| {
+        let mut ctx = Context::default();
+        (F80::from_bits(bits).to_decimal128(&mut ctx), ctx.flags)
+    };
+    assert_eq!(wide(0x7FFE << 63 | ((1 << 63) - 1)), (0x56863AA885CB1A6CECF38634CCF08E3A, Flags::INEXACT));
+    assert_eq!(wide(1 << 63), (0x0976A5C3B52962CBBAE990A91EAAE919, Flags::INEXACT));
+    assert_eq!(wide(1), (0x0950B3B8E2EDA91A232DD950102978DC, Flags::INEXACT));
+
+    let from = |bits: u128, mode: RoundingMode| {
+        let mut ctx = Context::new(mode);
+        (F80::from_decimal128(bits, &mut ctx).to_bits(), ctx.flags)
+    };
+    let largest = 0x5FFFED09BEAD87C0378D8E63FFFFFFFF; // 10^6145 - 10^6111
+    let f80_max = 0x7FFE << 63 | ((1 << 63) - 1);
+    for mode in MODES {
+        let (expected, flags) = from(largest, mode);
+        let to_infinity = matches!(mode, RoundingMode::TiesToEven | RoundingMode::TiesToAway | RoundingMode::TowardPositive);
+        assert_eq!(expected, if to_infinity { 0x7FFF << 63 } else { f80_max }, "{mode:?}");
+        assert_eq!(flags, Flags::OVERFLOW | Flags::INEXACT, "{mode:?}");
+        // 10^-6176, far below x87 extended's smallest subnormal
+        let (expected, flags) = from(1, mode);
+        assert_eq!(expected, (mode == RoundingMode::TowardPositive) as u128, "{mode:?}");
+        assert_eq!(flags, Flags::UNDERFLOW | Flags::INEXACT, "{mode:?}");
+    }
+    assert_eq!(from(0x30400000000000000000000000000064, RoundingMode::TiesToEven), (0x2002C800000000000000, Flags::empty()));
+    // 10^34 isn't canonical, and neither is anything in the 11 form
+    assert_eq!(from(0x3041ED09BEAD87C0378D8E6400000000, RoundingMode::TiesToEven), (0, Flags::empty()));
+    assert_eq!(from(0xE0000000000000000000000000000001, RoundingMode::TiesToEven), (1 << 78, Flags::empty()));
+    let mut ctx = Context::default();
+    let snan = F64::from_decimal128(0x7E000000000000000000000000000000, &mut ctx);
+    assert!(snan.is_nan() && !snan.is_signaling_nan() && ctx.flags == Flags::INVALID);
+    println!("Decimal128 test passed!");
+}
+
+fn decimal_round_trip_test() {
+    // binary -> decimal -> binary gives the same bits to nearest whenever the decimal has more digits than the
+    // binary needs (17 for binary64, 21 for x87, 9 for binary32), and decimal64 -> x87 -> decimal64 gives the
+    // same value, as 2^63 is past 10^16. random decimals read into binary in every mode exactly as their digits
+    // parse, flags included
+    use floatfs::format::{Binary32, Binary64, Extended};
+    use floatfs::{Context, Format, RoundingMode, SoftFloat, Tininess, F32, F64, F80};
+    use rand::Rng;
+
+    const MODES: [RoundingMode; 5] = [
+        RoundingMode::TiesToEven,
+        RoundingMode::TiesToAway,
+        RoundingMode::TowardZero,
+        RoundingMode::TowardPositive,
+        RoundingMode::TowardNegative,
+    ];
+    // the BID encodings written out plainly, and a decimal64's value as coefficient and exponent without zeros
+    // on the end, to compare values with different quanta
+    let bid64 = |sign: bool, c: u64, q: i64| {
+        let biased = (q + 398) as u64;
+        let body = if c < 1 << 53 { biased << 53 | c } else { 0b11 << 61 | biased << 51 | c & ((1 << 51) - 1) };
+        (sign as u64) << 63 | body
+    };
+    let bid128 = |sign: bool, c: u128, q: i64| (sign as u128) << 127 | ((q + 6176) as u128) << 113 | c;
+    let value64 = |bits: u64| {
+        let (biased, mut c) = if bits >> 61 & 3 == 3 {
+            (bits >> 51 & 0x3FF, 0b100 << 51 | bits & ((1 << 51) - 1))
+        } else {
+            (bits >> 53 & 0x3FF, bits & ((1 << 53) - 1))
+        };
+        let mut q = biased as i64 - 398;
+        while c != 0 && c % 10 == 0 {
+            (c, q) = (c / 10, q + 1);
+        }
+        (bits >> 63, c, if c == 0 { 0 } else { q })
+    };
+
+    fn binary_round_trip<F: Format>(bits: F::Bits, decimal128: bool) {
+        let x = SoftFloat::<F>::from_bits(bits);
+        if x.is_nan() {
+            return;
+        }
+        let mut ctx = Context::default();
+        let back = if decimal128 {
+            SoftFloat::<F>::from_decimal128(x.to_decimal128(&mut ctx), &mut ctx)
+        } else {
+            SoftFloat::<F>::from_decimal64(x.to_decimal64(&mut ctx), &mut ctx)
+        };
+        assert_eq!(back.to_bits(), x.to_bits(), "{} through decimal{}", x.to_exact_decimal(), if decimal128 { 128 } else { 64 });
+    }
+
+    let mut rng = rand::rng();
+    for _ in 0..100_000 {
+        binary_round_trip::<Binary32>(rng.random(), false);
+        binary_round_trip::<Binary32>(rng.random(), true);
+        binary_round_trip::<Binary64>(rng.random(), true);
+    }
+    for _ in 0..2_000 {
+        binary_round_trip::<Extended>(rng.random::<u128>() & ((1 << 79) - 1), true);
+    }
+
+    for _ in 0..100_000 {
+        let (sign, q) = (rng.random(), rng.random_range(-398..=369));
+        let c = rng.random_range(0..10u64.pow(16)) / 10u64.pow(rng.random_range(0..16)); // short ones too
+        let d = bid64(sign, c, q);
+        let x = F80::from_decimal64(d, &mut Context::default());
+        let again = x.to_decimal64(&mut Context::default());
+        assert_eq!(value64(again), value64(d), "{c}e{q}");
+
+        // in binary64's range, and in binary32's for a quarter of them
+        let q = if rng.random_range(0..4) == 0 { rng.random_range(-60..30) } else { rng.random_range(-345..=300) };
+        let d = bid64(sign, c, q);
+        let c128 = rng.random_range(0..10u128.pow(34)) / 10u128.pow(rng.random_range(0..34));
+        let d128 = bid128(sign, c128, q - 18);
+        for mode in MODES {
+            let tininess = if rng.random() { Tininess::AfterRounding } else { Tininess::BeforeRounding };
+            let context = || Context { tininess, ..Context::new(mode) };
+            let text = format!("{}{c}e{q}", if sign { "-" } else { "" });
+            let text128 = format!("{}{c128}e{}", if sign { "-" } else { "" }, q - 18);
+            let (mut a, mut b) = (context(), context());
+            let (x, y) = (F64::from_decimal64(d, &mut a), F64::parse(&text, &mut b).unwrap());
+            assert_eq!((x.to_bits(), a.flags), (y.to_bits(), b.flags), "{text} {mode:?}");
+            let (mut a, mut b) = (context(), context());
+            let (x, y) = (F32::from_decimal64(d, &mut a), F32::parse(&text, &mut b).unwrap());
+            assert_eq!((x.to_bits(), a.flags), (y.to_bits(), b.flags), "{text} {mode:?}");
+            let (mut a, mut b) = (context(), context());
+            let (x, y) = (F64::from_decimal128(d128, &mut a), F64::parse(&text128, &mut b).unwrap());
+            assert_eq!((x.to_bits(), a.flags), (y.to_bits(), b.flags), "{text128} {mode:?}");
+        }
+    }
+    println!("Decimal round trip test passed!");
+}
+
+fn decimal_quantize_test() {
+    // quantize and same_quantum on BID encodings, checked against Python's decimal module: rounding to cents in
+    // every mode, zeros on the end, results with too many digits, and the specials
+    use floatfs::{
+        quantize_decimal128, quantize_decimal64, same_quantum_decimal128, same_quantum_decimal64, Context, Flags,
+        RoundingMode,
+    };
+
+    let bid64 = |sign: bool, c: u64, q: i64| (sign as u64) << 63 | ((q + 398) as u64) << 53 | c;
+    let bid128 = |sign: bool, c: u128, q: i64| (sign as u128) << 127 | ((q + 6176) as u128) << 113 | c;
+    let (infinity, nan, snan) = (0x7800_0000_0000_0000, 0x7C00_0000_0000_0000, 0x7E00_0000_0000_0000);
+    let q64 = |x: u64, y: u64, mode: RoundingMode| {
+        let mut ctx = Context::new(mode);
+        (quantize_decimal64(x, y, &mut ctx), ctx.flags)
+    };
+    let cent = bid64(false, 1, -2);
+    for (mode, tie_even, tie_odd, below) in [
+        (RoundingMode::TiesToEven, 124, 124, 123),
+        (RoundingMode::TiesToAway, 124, 125, 123),
+        (RoundingMode::TowardZero, 123, 124, 123),
+        (RoundingMode::TowardPositive, 124, 125, 124),
+        (RoundingMode::TowardNegative, 123, 124, 123),
+    ] {
+        // 1.235 and 1.245 are ties, and 1.2345 is below one
+        assert_eq!(q64(bid64(false, 1235, -3), cent, mode), (bid64(false, tie_even, -2), Flags::INEXACT), "{mode:?}");
+        assert_eq!(q64(bid64(false, 1245, -3), cent, mode), (bid64(false, tie_odd, -2), Flags::INEXACT), "{mode:?}");
+        assert_eq!(q64(bid64(false, 12345, -4), cent, mode), (bid64(false, below, -2), Flags::INEXACT), "{mode:?}");
+        assert_eq!(q64(bid64(false, 1230, -3), cent, mode), (bid64(false, 123, -2), Flags::empty()), "{mode:?}");
+    }
+    let down = |x: u64| q64(x, cent, RoundingMode::TowardNegative);
+    assert_eq!(down(bid64(true, 12345, -4)), (bid64(true, 124, -2), Flags::INEXACT));
+    assert_eq!(down(bid64(false, 1, 0)), (bid64(false, 100, -2), Flags::empty())); // 1 is 1.00
+    assert_eq!(down(bid64(true, 0, 5)), (bid64(true, 0, -2), Flags::empty()));
+    // 9.99 to 10 at 10^0, and to 0 and 1 at 10^369 however far that drops it
+    let nearest = |x: u64, y: u64| q64(x, y, RoundingMode::TiesToEven);
+    assert_eq!(nearest(bid64(false, 999, -2), bid64(false, 7, 0)), (bid64(false, 10, 0), Flags::INEXACT));
+    assert_eq!(nearest(bid64(false, 999, -2), bid64(false, 0, 369)), (bid64(false, 0, 369), Flags::INEXACT));
+    let up = q64(bid64(false, 999, -2), bid64(false, 0, 369), RoundingMode::TowardPositive);
+    assert_eq!(up, (bid64(false, 1, 369), Flags::INEXACT));
+    // 16 nines are a whole decimal64 coefficient (in the 11 form), so they have no room for a digit after the point
+    let nines = 0b11 << 61 | 398 << 51 | 9_999_999_999_999_999 & ((1 << 51) - 1);
+    assert_eq!(nearest(nines, bid64(false, 1, -1)), (nan, Flags::INVALID));
+    assert_eq!(nearest(nines, bid64(false, 1, 0)), (nines, Flags::empty()));
+    // the specials
+    assert_eq!(nearest(infinity, bid64(false, 1, 0)), (nan, Flags::INVALID));
+    assert_eq!(nearest(cent, 1 << 63 | infinity), (nan, Flags::INVALID));
+    assert_eq!(nearest(1 << 63 | infinity, infinity), (1 << 63 | infinity, Flags::empty()));
+    assert_eq!(nearest(1 << 63 | nan, cent), (1 << 63 | nan, Flags::empty()));
+    assert_eq!(nearest(cent, snan), (nan, Flags::INVALID));
+
+    // decimal128 the same way: 1/3 to six places, and the smallest subnormal to 10^0
+    let q128 = |x: u128, y: u128, mode: RoundingMode| {
+        let mut ctx = Context::new(mode);
+        (quantize_decimal128(x, y, &mut ctx), ctx.flags)
+    };
+    let third = bid128(false, 3_333_333_333_333_333_333_333_333_333_333_333, -34);
+    let micro = bid128(false, 1, -6);
+    assert_eq!(q128(third, micro, RoundingMode::TiesToEven), (bid128(false, 333_333, -6), Flags::INEXACT));
+    assert_eq!(q128(third, micro, RoundingMode::TowardPositive), (bid128(false, 333_334, -6), Flags::INEXACT));
+    let tiny = bid128(true, 1, -6176);
+    assert_eq!(q128(tiny, bid128(false, 1, 0), RoundingMode::TiesToEven), (bid128(true, 0, 0), Flags::INEXACT));
+    assert_eq!(q128(tiny, bid128(false, 1, 0), RoundingMode::TowardNegative), (bid128(true, 1, 0), Flags::INEXACT));
+    assert_eq!(q128(bid128(false, 5, 0), bid128(false, 1, -33), RoundingMode::TiesToEven).1, Flags::empty());
+    assert_eq!(q128(bid128(false, 10, 0), bid128(false, 1, -33), RoundingMode::TiesToEven).1, Flags::INVALID);
+
+    // same_quantum compares exponents only: 1.0 and 1.00 differ, 1.0 and -2.5 don't, and specials only match
+    // their own kind
+    assert!(!same_quantum_decimal64(bid64(false, 10, -1), bid64(false, 100, -2)));
+    assert!(same_quantum_decimal64(bid64(false, 10, -1), bid64(true, 25, -1)));
+    assert!(same_quantum_decimal64(infinity, 1 << 63 | infinity));
+    assert!(same_quantum_decimal64(nan, snan));
+    assert!(!same_quantum_decimal64(nan, infinity));
+    assert!(!same_quantum_decimal64(bid64(false, 0, 0), nan));
+    assert!(same_quantum_decimal128(bid128(false, 0, -6176), bid128(true, 7, -6176)));
+    assert!(!same_quantum_decimal128(bid128(false, 1, 0), bid128(false, 1, 1)));
+    println!("Decimal quantize test passed!");
+}
+
+fn f128_string_test() {
+    // binary128 strings are worked out at binary128's precision: hard cases with their nearest, downward and
+    // upward roundings; every binary64 and x87 value's exact digits parse to its binary128 embedding; and the
+    // shortest digits of random encodings, never more than 36, read back. parse_f16 and format_f16 are F16's
+    // own, which decimal_test covers
+    use floatfs::{format_f128, format_f16, parse_f128, parse_f16, Context, Flags, RoundingMode, F16, F64, F80};
+    use rand::Rng;
+
+    const CASES: &[(&str, u128, u128, u128)] = &[
+        ("1.1", 0x3FFF199999999999999999999999999A, 0x3FFF1999999999999999999999999999, 0x3FFF199999999999999999999999999A),
+        ("0.1", 0x3FFB999999999999999999999999999A, 0x3FFB9999999999999999999999999999, 0x3FFB999999999999999999999999999A),
+        ("3.14159265358979323846264338327950288", 0x4000921FB54442D18469898CC51701B8, 0x4000921FB54442D18469898CC51701B8, 0x4000921FB54442D18469898CC51701B9),
+        ("1.18973149535723176508575932662800702e4932", 0x7FFEFFFFFFFFFFFFFFFFFFFFFFFFFFFF, 0x7FFEFFFFFFFFFFFFFFFFFFFFFFFFFFFF, 0x7FFF0000000000000000000000000000),
+        ("1.18973149535723176508575932662800703e4932", 0x7FFEFFFFFFFFFFFFFFFFFFFFFFFFFFFF, 0x7FFEFFFFFFFFFFFFFFFFFFFFFFFFFFFF, 0x7FFF0000000000000000000000000000),
+        ("6.47517511943802511092443895822764655e-4966", 0x00000000000000000000000000000001, 0x00000000000000000000000000000000, 0x00000000000000000000000000000001),
+        ("3.2375875597190125554622194791138232e-4966", 0x00000000000000000000000000000000, 0x00000000000000000000000000000000, 0x00000000000000000000000000000001),
+        ("3.36210314311209350626267781732175260e-4932", 0x00010000000000000000000000000000, 0x0000FFFFFFFFFFFFFFFFFFFFFFFFFFFF, 0x00010000000000000000000000000000),
+        ("1e4933", 0x7FFF0000000000000000000000000000, 0x7FFEFFFFFFFFFFFFFFFFFFFFFFFFFFFF, 0x7FFF0000000000000000000000000000),
+        ("9007199254740993", 0x40340000000000000800000000000000, 0x40340000000000000800000000000000, 0x40340000000000000800000000000000),
+        ("10384593717069655257060992658440193", 0x40700000000000000000000000000000, 0x40700000000000000000000000000000, 0x40700000000000000000000000000001),
+        ("10384593717069655257060992658440192.5", 0x40700000000000000000000000000000, 0x40700000000000000000000000000000, 0x40700000000000000000000000000001),
+    ];
+    for &(s, nearest, down, up) in CASES {
+        for (mode, expected) in [(RoundingMode::TiesToEven, nearest), (RoundingMode::TowardNegative, down), (RoundingMode::TowardPositive, up)] {
+            let mut ctx = Context::new(mode);
+            assert_eq!(parse_f128(s, &mut ctx), Ok(expected), "{s} {mode:?}");
+            assert_eq!(ctx.flags.contains(Flags::INEXACT), down != up, "{s} {mode:?}");
+        }
+    }
+    assert_eq!(format_f128(0x3FFF199999999999999999999999999A), "1.1e0");
+    assert_eq!(format_f128(0x4000921FB54442D18469898CC51701B8), "3.1415926535897932384626433832795028e0");
+    assert_eq!(format_f128(1), "6e-4966");
+    assert_eq!(format_f128(0x7FFF0000000000000000000000000000), "inf");
+
+    // binary64 and x87 values sit in binary128 with the exponent rebiased and zeros below the mantissa
+    let embed = |sign: bool, biased: u128, mantissa: u128| (sign as u128) << 127 | biased << 112 | mantissa;
+    let mut rng = rand::rng();
+    for _ in 0..20_000 {
+        let x = F64::from_bits(rng.random::<u64>() & !(0x7FF << 52) | rng.random_range(1..0x7FF) << 52);
+        let bits = x.to_bits() as u128;
+        let expected = embed(x.get_sign(), (bits >> 52 & 0x7FF) + 16383 - 1023, (bits & ((1 << 52) - 1)) << 60);
+        assert_eq!(parse_f128(&x.to_exact_decimal(), &mut Context::default()), Ok(expected), "{}", x.to_exact_decimal());
+    }
+    // x87 extended has binary128's exponent, and 63 of its 112 mantissa bits. (exact x87 digits run to thousands,
+    // so fewer of them)
+    for _ in 0..500 {
+        let y = F80::from_bits(rng.random::<u128>() & ((1 << 79) - 1));
+        if y.is_nan() || y.is_infinity() {
+            continue;
+        }
+        let bits = y.to_bits();
+        let expected = embed(y.get_sign(), bits >> 63 & 0x7FFF, (bits & ((1 << 63) - 1)) << 49);
+        assert_eq!(parse_f128(&y.to_exact_decimal(), &mut Context::default()), Ok(expected), "{y:?}");
+    }
+    for i in 0..20_000 {
+        let bits = rng.random::<u128>();
+        let s = format_f128(bits);
+        if s == "NaN" {
+            continue;
+        }
+        let digits = s.split('e').next().unwrap().chars().filter(char::is_ascii_digit).count();
+        assert!(digits <= 36, "{s}");
+        assert_eq!(parse_f128(&s, &mut Context::default()), Ok(bits), "{s}");
+        if i % 100 == 0 {
+            let f = F16::from_bits(bits as u16);
+            assert_eq!(format_f16(bits as u16), f.to_shortest_decimal());
+            if !f.is_nan() {
+                assert_eq!(parse_f16(&format_f16(bits as u16), &mut Context::default()), Ok(bits as u16));
+            }
+        }
+    }
+    assert_eq!(format_f16(0x2E66), "1e-1");
+    println!("F128 string test passed!");
+}
+
+fn conformance_test() {
+    // every requirement floatfs provides passes its checks
+    let entries = floatfs::conformance::report();
+    let failed: Vec<_> = entries.iter().filter(|e| e.status == floatfs::conformance::Status::Failed).collect();
+    assert!(failed.is_empty(), "{failed:#?}");
+    println!("Conformance test passed!");
 }
 
-impl Float {
-    fn from_bits(bits: u64) -> Self {
-        Float { bits }
+fn testbench_test() {
+    // random::special reaches every class and the edge encodings in each format, and the vector lines come out
+    // as documented
+    use floatfs::format::{Binary16, Binary32, Extended, Minifloat};
+    use floatfs::random::special;
+    use floatfs::testbench::{self, Vector, DEFAULT_TEMPLATE};
+    use floatfs::{Context, Flags, Format, Operation, RoundingMode, SoftFloat, F32};
+    use rand::RngCore;
+
+    fn reaches<F: Format>(bits: &mut impl FnMut() -> u64) {
+        let (mut classes, mut signaling, mut smallest, mut largest) = (Vec::new(), false, false, false);
+        for _ in 0..100_000 {
+            let x = special::<F>(bits);
+            assert!(x.to_bits() == SoftFloat::<F>::from_bits(x.to_bits()).to_bits());
+            if !classes.contains(&x.classify()) {
+                classes.push(x.classify());
+            }
+            signaling |= x.is_signaling_nan();
+            smallest |= x.next_down().is_zero() && !x.is_zero() && !x.get_sign();
+            largest |= x.next_up().is_infinity() && !x.is_infinity() && !x.get_sign();
+        }
+        assert!(classes.len() == 5 && signaling && smallest && largest, "{}", F::NAME);
+    }
+    let mut rng = rand::rng();
+    let mut bits = || rng.next_u64();
+    reaches::<Binary16>(&mut bits);
+    reaches::<Binary32>(&mut bits);
+    reaches::<Extended>(&mut bits);
+    reaches::<Minifloat>(&mut bits);
+
+    // 1 + 2^-24 is a tie in binary32, so it rounds to even, inexactly
+    let (one, half_ulp) = (F32::from_bits(0x3F80_0000), F32::from_bits(0x3380_0000));
+    let v = Vector::new(Operation::Add, one, half_ulp, &Context::default());
+    assert_eq!(testbench::line(DEFAULT_TEMPLATE, &v), "3F800000 33800000 3F800000 01");
+    let v = Vector::new(Operation::Add, one, half_ulp, &Context::new(RoundingMode::TowardPositive));
+    assert_eq!(testbench::line("{op} {rm} 0x{a}, 0x{result}; {x} {flags", &v), "0 3 0x3F800000, 0x3F800001; {x} {flags");
+    let v = Vector::new(Operation::Divide, one, F32::zero(true), &Context::default());
+    assert_eq!(testbench::line("{result}_{flags}", &v), "FF800000_08");
+    assert_eq!(v.flags, Flags::DIVIDE_BY_ZERO);
+    assert_eq!(testbench::header::<Binary16>("{a} {result}"), "// binary16: {a} {result}");
+
+    // the vectors are the operation's results, with only their own flags
+    let ctx = Context { flags: Flags::INVALID, ..Context::new(RoundingMode::TowardZero) };
+    for v in testbench::vectors::<Binary32>(Operation::Multiply, &ctx, 1000, &mut bits) {
+        let mut fresh = Context::new(RoundingMode::TowardZero);
+        assert_eq!(v.a.multiply(&v.b, &mut fresh).to_bits(), v.result.to_bits());
+        assert_eq!((v.flags, v.rounding), (fresh.flags, RoundingMode::TowardZero));
     }
+    println!("Testbench test passed!");
+}
 
-    fn new(value: f64) -> Self {
-        Float {
-            bits: value.to_bits(),
+fn gamma_test() {
+    // tgamma and lgamma against the host libm for positive x (glibc's tgamma is only within a few ulps, and worse
+    // for negative x, so the negative side is checked against values from mpmath instead), then the exact
+    // factorials, the poles and the sign lgamma_r reports.
+    use floatfs::{Context, Flags, RoundingMode, F64};
+    use rand::Rng;
+    extern "C" {
+        fn tgamma(x: f64) -> f64;
+        fn lgamma(x: f64) -> f64;
+    }
+    let mut rng = rand::rng();
+    let f = |x: f64| F64::from_bits(x.to_bits());
+    for i in 0..200_000 {
+        let x: f64 = if rng.random() { rng.random_range(0.0..180.0) } else { f64::from_bits(rng.random::<u64>() >> 1) };
+        let (soft, host, tolerance): (fn(&F64, &mut Context) -> F64, f64, u64) = if i % 2 == 0 {
+            (F64::tgamma, unsafe { tgamma(x) }, 6)
+        } else {
+            (F64::lgamma, unsafe { lgamma(x) }, 3)
+        };
+        let near = soft(&f(x), &mut Context::new(RoundingMode::TiesToEven));
+        if host.is_nan() {
+            assert!(near.is_nan(), "{} {:e}", i % 2, x);
+            continue;
         }
+        assert!(near.to_bits().abs_diff(host.to_bits()) <= tolerance, "{} {:e}: {:x}, host {:x}", i % 2, x, near.to_bits(), host.to_bits());
+        let down = f64::from_bits(soft(&f(x), &mut Context::new(RoundingMode::TowardNegative)).to_bits());
+        let up = f64::from_bits(soft(&f(x), &mut Context::new(RoundingMode::TowardPositive)).to_bits());
+        assert!(down == up || down.next_up() == up, "{:e}", x);
     }
 
-    fn to_f64(&self) -> f64 {
-        f64::from_bits(self.bits)
+    let check = |f: fn(&F64, &mut Context) -> F64, x: f64, expected: f64, flags: Flags| {
+        let mut ctx = Context::new(RoundingMode::TiesToEven);
+        let result = f64::from_bits(f(&F64::from_bits(x.to_bits()), &mut ctx).to_bits());
+        assert!(result.to_bits() == expected.to_bits() || (result.is_nan() && expected.is_nan()), "{:e}: {:e}", x, result);
+        assert_eq!(ctx.flags, flags, "{:e}", x);
+    };
+    let inf = f64::INFINITY;
+    check(F64::tgamma, 23.0, 1124000727777607680000.0, Flags::empty()); // 22!
+    check(F64::tgamma, 24.0, 25852016738884976640000.0, Flags::INEXACT);
+    check(F64::tgamma, 0.5, 1.772453850905516, Flags::INEXACT);
+    check(F64::tgamma, -2.5, -0.9453087204829419, Flags::INEXACT);
+    check(F64::tgamma, -77.22798259819739, f64::from_bits(0x287E184FF793BA09), Flags::INEXACT);
+    check(F64::tgamma, 172.0, inf, Flags::OVERFLOW | Flags::INEXACT);
+    check(F64::tgamma, -0.0, -inf, Flags::DIVIDE_BY_ZERO);
+    check(F64::tgamma, -3.0, f64::NAN, Flags::INVALID);
+    check(F64::tgamma, -inf, f64::NAN, Flags::INVALID);
+    check(F64::tgamma, inf, inf, Flags::empty());
+    check(F64::lgamma, 1.0, 0.0, Flags::empty());
+    check(F64::lgamma, 2.0, 0.0, Flags::empty());
+    check(F64::lgamma, -2.5, -0.056243716497674054, Flags::INEXACT);
+    check(F64::lgamma, -2.0, inf, Flags::DIVIDE_BY_ZERO);
+    check(F64::lgamma, -inf, inf, Flags::empty());
+    let mut ctx = Context::new(RoundingMode::TiesToEven);
+    let (value, sign) = f(-0.5).lgamma_r(&mut ctx);
+    assert_eq!((value.to_bits(), sign), (1.2655121234846454f64.to_bits(), -1));
+    assert_eq!(f(-1.5).lgamma_r(&mut ctx).1, 1);
+    assert_eq!(f(-0.0).lgamma_r(&mut ctx).1, -1);
+    println!("Gamma test passed!");
+}
+
+fn reduction_test() {
+    // reduce_mod_pi_over_2: sin x and cos x rebuilt from the quadrant and hi with the host libm, across every
+    // binary64 exponent, lo under half an ulp of hi, and fl(pi/2), whose remainder is pi/2's second and third
+    // double-double pieces.
+    use floatfs::{Context, Flags, RoundingMode, F32, F64};
+    use rand::Rng;
+    let mut rng = rand::rng();
+    let reduce = |x: f64| {
+        let (q, hi, lo) = F64::from_bits(x.to_bits()).reduce_mod_pi_over_2(&mut Context::new(RoundingMode::TowardZero));
+        (q, f64::from_bits(hi.to_bits()), f64::from_bits(lo.to_bits()))
+    };
+    for _ in 0..200_000 {
+        let x = f64::from_bits(rng.random::<u64>() & !(0x7FF << 52) | (rng.random_range(0..0x7FF) << 52));
+        let (q, hi, lo) = reduce(x);
+        assert!(hi.abs() < 0.79 && hi + lo == hi, "{:e}: {:e} {:e}", x, hi, lo);
+        let (sin, cos) = match q {
+            0 => (hi.sin(), hi.cos()),
+            1 => (hi.cos(), -hi.sin()),
+            2 => (-hi.sin(), -hi.cos()),
+            _ => (-hi.cos(), hi.sin()),
+        };
+        assert!(sin.to_bits().abs_diff(x.sin().to_bits()) <= 2, "sin({:e}): {}, {:e}", x, q, hi);
+        assert!(cos.to_bits().abs_diff(x.cos().to_bits()) <= 2, "cos({:e}): {}, {:e}", x, q, hi);
+        // -x is a quadrant the other way round with the remainder negated
+        let (nq, nhi, nlo) = reduce(-x);
+        assert!((nq, nhi.to_bits()) == ((4 - q) & 3, (-hi).to_bits()) && nlo == -lo, "{:e}", x);
     }
 
-    fn get_sign(&self) -> bool {
-        (self.bits >> 63) & 1 == 1 // false for positive, true for negative
+    let (q, hi, lo) = reduce(std::f64::consts::FRAC_PI_2);
+    assert_eq!((q, hi), (1, -6.123233995736766e-17));
+    assert!(lo.to_bits().abs_diff(1.4973849048591698e-33f64.to_bits()) <= 1, "{:e}", lo);
+    // the binary64 closest to a multiple of pi/2, about 2^-61 from it
+    let (q, hi, lo) = reduce(6381956970095103.0 * 2f64.powi(797));
+    assert_eq!((q, hi), (1, 4.687165924254628e-19));
+    assert!(lo.to_bits().abs_diff((-4.3720557429382733e-36f64).to_bits()) <= 1, "{:e}", lo);
+    assert_eq!(reduce(-0.0).1.to_bits(), (-0.0f64).to_bits());
+    assert_eq!(reduce(0.5), (0, 0.5, 0.0));
+    // 2^1023 is (4k + 2) pi/2 - 0.598165904072084362...
+    let (q, hi, _) = reduce(2f64.powi(1023));
+    assert_eq!((q, hi), (2, -0.5981659040720844));
+    let mut ctx = Context::new(RoundingMode::TiesToEven);
+    assert!(F32::from_bits(0x7F800000).reduce_mod_pi_over_2(&mut ctx).1.is_nan());
+    assert_eq!(ctx.flags, Flags::INVALID);
+    let mut ctx = Context::new(RoundingMode::TiesToEven);
+    F32::from_bits(1e30f32.to_bits()).reduce_mod_pi_over_2(&mut ctx);
+    assert!(ctx.flags.is_empty());
+    println!("Reduction test passed!");
+}
+
+fn augmented_test() {
+    // augmented_add and augmented_mul against host arithmetic: the ties-to-even result and its exact error (from
+    // Knuth's two-sum and fma), moved one step toward zero when the exact value was a tie that went away from
+    // zero. the inputs are built so a good share of them are ties. then the special cases.
+    use rand::Rng;
+    let mut rng = rand::rng();
+    let toward_zero = |s: f64| f64::from_bits(s.to_bits() - 1); // for a finite nonzero s
+    let reference = |s: f64, error: f64| {
+        if error != 0.0 && s.is_finite() && s != 0.0 && (toward_zero(s) - s) / 2.0 == error {
+            (toward_zero(s), -error)
+        } else {
+            (s, if error == 0.0 { 0f64.copysign(s) } else { error })
+        }
+    };
+    let check = |(a, b): (Float, Float), (want_a, want_b): (f64, f64), what: &str| {
+        assert_eq!((a.to_bits(), b.to_bits()), (want_a.to_bits(), want_b.to_bits()), "{}: {:e} {:e} want {:e} {:e}", what, a.to_f64(), b.to_f64(), want_a, want_b);
+    };
+    let (mut add_ties, mut mul_ties) = (0, 0);
+    for _ in 0..200_000 {
+        let x: f64 = rng.random_range(-1.0..1.0) * 2f64.powi(rng.random_range(-50..50));
+        let ulp = f64::from_bits(x.abs().to_bits() + 1) - x.abs();
+        let y = rng.random_range(-8..8) as f64 * ulp * 2f64.powi(rng.random_range(-2..60));
+        let s = x + y;
+        let (bv, av) = (s - x, s - (s - x));
+        let want = reference(s, (x - av) + (y - bv));
+        add_ties += (want.0 != s) as u32;
+        check(Float::new(x).augmented_add(&Float::new(y)), want, &format!("{:e} + {:e}", x, y));
+        check(Float::new(y).augmented_add(&Float::new(x)), want, &format!("{:e} + {:e}", y, x));
+
+        // products of 20 to 34-bit integers often have exactly 54 bits
+        let x = rng.random_range(1u64 << 19..1 << 34) as f64 * 2f64.powi(rng.random_range(-200..200));
+        let y = rng.random_range(-(1i64 << 34)..1 << 34) as f64 * 2f64.powi(rng.random_range(-200..200));
+        let p = x * y;
+        let want = reference(p, x.mul_add(y, -p));
+        mul_ties += (want.0 != p) as u32;
+        check(Float::new(x).augmented_mul(&Float::new(y)), want, &format!("{:e} * {:e}", x, y));
     }
+    assert!(add_ties > 1000 && mul_ties > 100, "ties: {} {}", add_ties, mul_ties);
+
+    let f = |a: f64, b: f64| (Float::new(a), Float::new(b));
+    let add = |a: f64, b: f64| { let (x, y) = f(a, b); x.augmented_add(&y) };
+    let mul = |a: f64, b: f64| { let (x, y) = f(a, b); x.augmented_mul(&y) };
+    let eps = f64::EPSILON;
+    check(add(1.0, eps / 2.0), (1.0, eps / 2.0), "1 + half ulp");
+    check(add(1.0 + eps, eps / 2.0), (1.0 + eps, eps / 2.0), "odd + half ulp"); // ties to even would go up
+    check(add(-1.0 - eps, -eps / 2.0), (-1.0 - eps, -eps / 2.0), "negative tie");
+    check(add(f64::MAX, 2f64.powi(970)), (f64::MAX, 2f64.powi(970)), "max + half ulp");
+    check(add(-f64::MAX, -2f64.powi(970)), (-f64::MAX, -2f64.powi(970)), "-max - half ulp");
+    check(add(f64::MAX, 2f64.powi(971)), (f64::INFINITY, f64::INFINITY), "overflow");
+    check(add(1.0, 2.0), (3.0, 0.0), "exact");
+    check(add(-1.0, -2.0), (-3.0, -0.0), "exact negative");
+    check(add(1.0, -1.0), (0.0, 0.0), "cancel");
+    check(add(-0.0, -0.0), (-0.0, -0.0), "negative zeros");
+    check(add(f64::INFINITY, 1.0), (f64::INFINITY, f64::INFINITY), "infinity");
+    assert!(add(f64::INFINITY, f64::NEG_INFINITY).1.is_nan());
+    check(mul(-0.0, 5.0), (-0.0, -0.0), "zero product");
+    check(mul(f64::MAX, 2.0), (f64::INFINITY, f64::INFINITY), "product overflow");
+    check(mul(-f64::MAX, f64::INFINITY), (f64::NEG_INFINITY, f64::NEG_INFINITY), "infinite product");
+    assert!(mul(0.0, f64::INFINITY).0.is_nan() && mul(f64::NAN, 1.0).1.is_nan());
+    // 2^-1023 + 3 * 2^-1075 is a tie between subnormals, and the error under the smallest one rounds to zero
+    let tiny = f64::from_bits(1);
+    check(mul(1.0 + 3.0 * eps, 2f64.powi(-1023)), (2f64.powi(-1023) + tiny, 0.0), "subnormal tie");
+    check(mul(-1.0 - 3.0 * eps, 2f64.powi(-1023)), (-2f64.powi(-1023) - tiny, -0.0), "negative subnormal tie");
+    check(mul(1.0 + eps, 2f64.powi(-1022) * (1.0 + eps)), (2f64.powi(-1022) * (1.0 + 2.0 * eps), 0.0), "error underflows");
+    check(mul(tiny, 0.5), (0.0, 0.0), "product underflows");
+    println!("Augmented test passed!");
+}
 
-    fn get_exponent(&self) -> i16 {
-        let exp_bits = ((self.bits >> 52) & ((1 << 11) - 1)) as i16;
-        exp_bits - 1023 // Subtracting the bias
+fn error_free_test() {
+    // two_sum, two_prod and sub_exact: two_sum against knuth's two-sum on the host, two_prod against fma(a, b, -p), which is
+    // exact wherever the error is representable, then the operands dekker's splitting would overflow on and the
+    // non-finite results.
+    use rand::Rng;
+    let mut rng = rand::rng();
+    let random = |rng: &mut rand::rngs::ThreadRng| f64::from_bits(rng.random::<u64>() & !(0x7FF << 52) | rng.random_range(0x300..0x500) << 52);
+    for _ in 0..1_000_000 {
+        let (a, b) = (random(&mut rng), random(&mut rng));
+        let (s, err) = Float::new(a).two_sum(&Float::new(b));
+        let host = a + b;
+        let (a_part, b_part) = (host - b, host - (host - b));
+        assert_eq!((s.to_f64(), err.to_f64()), (host, (a - a_part) + (b - b_part)), "{:e} + {:e}", a, b);
+        let (p, err) = Float::new(a).two_prod(&Float::new(b));
+        assert_eq!((p.to_f64(), err.to_bits()), (a * b, a.mul_add(b, -(a * b)).to_bits()), "{:e} * {:e}", a, b);
     }
+    let (a, b) = (1.0 + f64::EPSILON, 1e300);
+    let (p, err) = Float::new(a).two_prod(&Float::new(b));
+    assert_eq!((p.to_f64(), err.to_f64()), (a * b, a.mul_add(b, -(a * b))));
+    let (s, err) = Float::new(1.0).two_sum(&Float::new(1e-30));
+    assert_eq!((s.to_f64(), err.to_f64()), (1.0, 1e-30));
+    let (p, err) = Float::new(1e300).two_prod(&Float::new(1e300));
+    assert_eq!((p.to_f64(), err.to_bits()), (f64::INFINITY, 0));
+    let (s, err) = Float::new(f64::MAX).two_sum(&Float::new(f64::MAX));
+    assert_eq!((s.to_f64(), err.to_bits()), (f64::INFINITY, 0));
+    assert!(Float::new(f64::NAN).two_sum(&Float::new(1.0)).0.is_nan());
 
-    fn get_mantissa(&self) -> u64 {
-        self.bits & ((1 << 52) - 1) // last 52 bits
+    // sub_exact: Some exactly when the operands are within a factor of 2 of each other, and then the difference
+    // is exact (its two_sum error is zero); random bits reach the subnormals and the top binade
+    for i in 0..1_000_000 {
+        let a: f64 = if i % 2 == 0 { rng.random_range(-4.0..4.0) } else { f64::from_bits(rng.random()) };
+        let b = match i % 4 {
+            0 | 1 => a * rng.random_range(0.3..2.5),
+            2 => f64::from_bits(a.to_bits() ^ rng.random_range(0..1 << 20)),
+            _ => f64::from_bits(rng.random()),
+        };
+        let sterbenz = a.is_finite() && b.is_finite() && (a == 0.0 && b == 0.0 || a.signum() == b.signum() && a.abs() <= 2.0 * b.abs() && b.abs() <= 2.0 * a.abs());
+        let difference = Float::new(a).sub_exact(&Float::new(b));
+        assert_eq!(difference.is_some(), sterbenz, "{:e} {:e}", a, b);
+        if let Some(d) = difference {
+            assert_eq!(d.to_bits(), (a - b).to_bits());
+            assert!(Float::new(a).two_sum(&Float::new(-b)).1.is_zero());
+        }
     }
+    assert_eq!(Float::new(f64::MAX).sub_exact(&Float::new(f64::MAX / 1.5)).map(|d| d.to_f64()), Some(f64::MAX - f64::MAX / 1.5));
+    assert!(Float::new(1.0).sub_exact(&Float::new(0.0)).is_none() && Float::new(-1.0).sub_exact(&Float::new(1.0)).is_none());
+    assert_eq!(Float::new(-0.0).sub_exact(&Float::new(0.0)).map(|d| d.to_bits()), Some((-0.0f64).to_bits()));
+    println!("Error-free test passed!");
+}
 
-    fn negate(&mut self) {
-        self.bits ^= 1 << 63; // flip the sign bit by XORing because 1^0=1 and 1^1=0
+fn exactness_test() {
+    // add_exact, multiply_exact and divide_exact: the result is the rounded operation's, and the flag is whether
+    // a + b - s, a b - p or q b - a is exactly zero, worked out in Exact. an infinity or nan operand makes any
+    // result exact, and finite operands make an infinity only by overflowing (division by zero aside)
+    use floatfs::exact::Exact;
+    use floatfs::RoundingMode;
+    use rand::Rng;
+    let mut rng = rand::rng();
+    let specials = [0.0, -0.0, 1.0, 3.0, 0.5, f64::INFINITY, f64::NAN, f64::MAX, 5e-324, f64::MIN_POSITIVE];
+    let mut random = |i: usize| match i % 3 {
+        0 => f64::from_bits(rng.random()),
+        1 => specials[rng.random_range(0..specials.len())],
+        _ => rng.random_range(-64i64..64) as f64 * 2f64.powi(rng.random_range(-8..8)),
+    };
+    for i in 0..1_000_000 {
+        let (a, b) = (random(i), random(i / 3));
+        let (fa, fb) = (Float::new(a), Float::new(b));
+        let given = !a.is_finite() || !b.is_finite();
+        // (a residual below the subnormals rounds to 0 to nearest, but not both up and down)
+        let zero = |r: Exact| r.round_with_rounding(RoundingMode::TowardPositive).is_zero() && r.round_with_rounding(RoundingMode::TowardNegative).is_zero();
+        let exact = |result: &Float, residual: &dyn Fn() -> Exact| given || !result.is_nan() && !result.is_infinity() && zero(residual());
+        let (s, flag) = fa.add_exact(&fb);
+        assert_eq!(s.to_bits(), fa.add(&fb).to_bits());
+        assert_eq!(flag, exact(&s, &|| Exact::from(&fa) + &fb - &s), "{:e} + {:e}", a, b);
+        let (p, flag) = fa.multiply_exact(&fb);
+        assert_eq!(p.to_bits(), fa.multiply(&fb).to_bits());
+        assert_eq!(flag, exact(&p, &|| Exact::from(&fa) * &fb - &p), "{:e} * {:e}", a, b);
+        let (q, flag) = fa.divide_exact(&fb);
+        assert_eq!(q.to_bits(), fa.divide(&fb).to_bits());
+        if b != 0.0 {
+            assert_eq!(flag, exact(&q, &|| Exact::from(&q) * &fb - &fa), "{:e} / {:e}", a, b);
+        }
     }
+    assert!(Float::new(1.0).divide_exact(&Float::new(0.0)).1);
+    assert!(!Float::new(f64::MAX).add_exact(&Float::new(f64::MAX)).1);
+    assert!(!Float::new(1.0).divide_exact(&Float::new(3.0)).1);
+    println!("Exactness test passed!");
+}
+
+fn expansion_test() {
+    // expansions are exact, so adding a pile of random floats and then subtracting them again in another order has
+    // to come back to exactly [0], and the same for a scaled pile against the products done one at a time. every
+    // intermediate expansion has to be nonoverlapping and in increasing order, and its estimate the correctly
+    // rounded sum where that's easy to know.
+    use floatfs::expansion::{estimate, expansion_sum, grow_expansion, scale_expansion};
+    use rand::seq::SliceRandom;
+    use rand::Rng;
+    let mut rng = rand::rng();
+    let negated = |f: &Float| Float::from_bits(f.to_bits() ^ 1 << 63);
+    // the lowest set bit of each component is above the highest bit of the one before
+    let check = |e: &[Float]| {
+        for pair in e.windows(2) {
+            let (low, high) = (pair[0].to_f64().abs(), pair[1].to_f64().abs());
+            let high_lsb = high.to_bits().trailing_zeros().min(52); // of the significand, for normal high
+            let lsb = 2f64.powi(pair[1].get_exponent() as i32 - 52 + high_lsb as i32);
+            assert!(!pair[0].is_zero() && low < lsb, "overlap: {:e} {:e}", low, high);
+        }
+    };
+    let add = |e: &[Float], f: &[Float]| {
+        let mut h: Vec<Float> = (0..e.len() + f.len()).map(|_| Float::new(0.0)).collect();
+        let length = expansion_sum(e, f, &mut h);
+        h.truncate(length);
+        check(&h);
+        h
+    };
+    for _ in 0..2_000 {
+        let values: Vec<Float> = (0..rng.random_range(1..40))
+            .map(|_| Float::new(rng.random_range(-1.0..1.0) * 2f64.powi(rng.random_range(-200..200))))
+            .collect();
+        let mut e = vec![Float::new(0.0)];
+        for v in &values {
+            let mut h: Vec<Float> = (0..e.len() + 1).map(|_| Float::new(0.0)).collect();
+            let length = grow_expansion(&e, v, &mut h);
+            h.truncate(length);
+            check(&h);
+            e = h;
+        }
+        // e is exact, so taking the values back out, in another order and grouping, leaves nothing
+        let mut back: Vec<Float> = values.iter().map(negated).collect();
+        back.shuffle(&mut rng);
+        let mut total = e.iter().map(|f| f.copy()).collect::<Vec<_>>();
+        for chunk in back.chunks(3) {
+            let mut part = vec![Float::new(0.0)];
+            for v in chunk {
+                part = add(&part, &[v.copy()]);
+            }
+            total = add(&total, &part);
+        }
+        assert_eq!(total.iter().map(|f| f.to_bits()).collect::<Vec<_>>(), [0]);
 
-    // thank you william kahan todo: consider negative numbers
-    fn less_than(&self, other: &Float) -> bool {
-        self.bits < other.bits
+        let b = Float::new(rng.random_range(-1e10..1e10));
+        let mut scaled: Vec<Float> = (0..2 * e.len()).map(|_| Float::new(0.0)).collect();
+        let length = scale_expansion(&e, &b, &mut scaled);
+        scaled.truncate(length);
+        check(&scaled);
+        for v in &values {
+            let (p, err) = v.two_prod(&b);
+            let mut product = [negated(&err), negated(&p)];
+            product.sort_by_key(|f| f.to_bits() & !(1 << 63));
+            scaled = add(&scaled, &product);
+        }
+        assert_eq!(scaled.iter().map(|f| f.to_bits()).collect::<Vec<_>>(), [0]);
     }
-    fn greater_than(&self, other: &Float) -> bool {
-        self.bits > other.bits
+    // 1e16 + 1 - 1e16 is 1, which plain addition loses
+    let mut e = vec![Float::new(0.0)];
+    for v in [1e16, 1.0, -1e16] {
+        e = add(&e, &[Float::new(v)]);
     }
-    fn equals(&self, other: &Float) -> bool {
-        self.bits == other.bits
+    assert_eq!((e.len(), estimate(&e).to_f64()), (1, 1.0));
+    let mut h = [Float::new(0.0), Float::new(0.0)];
+    assert_eq!(grow_expansion(&[], &Float::new(0.0), &mut h), 1);
+    assert_eq!(scale_expansion(&[], &Float::new(3.0), &mut h), 1);
+    println!("Expansion test passed!");
+}
+
+fn summation_test() {
+    // sum_kahan and sum_neumaier against the same loops on host f64 (every step is a correctly rounded add, so they
+    // have to match bit for bit), the streaming sum against sum_neumaier, and sums that cancel badly against the
+    // exact sum from an expansion, where neumaier has to stay within an ulp and the plain sum doesn't.
+    use floatfs::expansion::{estimate, grow_expansion};
+    use floatfs::sum::{sum_kahan, sum_neumaier, CompensatedSum};
+    use rand::Rng;
+    let mut rng = rand::rng();
+    let floats = |values: &[f64]| values.iter().map(|&v| Float::new(v)).collect::<Vec<Float>>();
+    let host_kahan = |values: &[f64]| {
+        let (mut sum, mut c) = (0.0f64, 0.0f64);
+        for &x in values {
+            let y = x - c;
+            let t = sum + y;
+            c = (t - sum) - y;
+            sum = t;
+        }
+        sum
+    };
+    let host_neumaier = |values: &[f64]| {
+        let (mut sum, mut c) = (0.0f64, 0.0f64);
+        for &x in values {
+            let t = sum + x;
+            c += if sum.abs() >= x.abs() { (sum - t) + x } else { (x - t) + sum };
+            sum = t;
+        }
+        sum + c
+    };
+    let exact = |values: &[Float]| {
+        let mut e = vec![Float::new(0.0)];
+        for v in values {
+            let mut h: Vec<Float> = (0..e.len() + 1).map(|_| Float::new(0.0)).collect();
+            let length = grow_expansion(&e, v, &mut h);
+            h.truncate(length);
+            e = h;
+        }
+        estimate(&e)
+    };
+
+    assert_eq!(sum_kahan(&[]).to_bits(), 0);
+    assert_eq!(sum_neumaier(&[]).to_bits(), 0);
+    assert_eq!(sum_kahan(&floats(&[1.0, 1e100, 1.0, -1e100])).to_f64(), 0.0);
+    assert_eq!(sum_neumaier(&floats(&[1.0, 1e100, 1.0, -1e100])).to_f64(), 2.0);
+    assert_eq!(sum_kahan(&floats(&[1.0, f64::INFINITY, 1.0])).to_f64(), f64::INFINITY);
+    assert_eq!(sum_neumaier(&floats(&[1.0, f64::INFINITY, 1.0])).to_f64(), f64::INFINITY);
+    assert_eq!(sum_neumaier(&floats(&[f64::MAX, f64::MAX, -1.0])).to_f64(), f64::INFINITY);
+    assert!(sum_kahan(&floats(&[f64::INFINITY, -f64::INFINITY])).is_nan());
+    assert!(sum_neumaier(&floats(&[1.0, f64::NAN])).is_nan());
+
+    let (mut plain_worse, mut cases) = (0, 0);
+    for _ in 0..5_000 {
+        let values: Vec<f64> = (0..rng.random_range(1..200))
+            .map(|_| rng.random_range(-1.0..1.0) * 2f64.powi(rng.random_range(-60..60)))
+            .collect();
+        let fs = floats(&values);
+        assert_eq!(sum_kahan(&fs).to_bits(), host_kahan(&values).to_bits(), "{:?}", values);
+        assert_eq!(sum_neumaier(&fs).to_bits(), host_neumaier(&values).to_bits(), "{:?}", values);
+        let mut running = CompensatedSum::new();
+        for (n, x) in fs.iter().enumerate() {
+            running.add(x);
+            assert_eq!(running.value().to_bits(), sum_neumaier(&fs[..=n]).to_bits());
+        }
+
+        // terms up to 2^20 and their negations, shuffled, plus a few small ones: the big terms cancel completely.
+        // that's a condition number around 1e9, enough to ruin the plain sum but far from neumaier's limit
+        let big: Vec<f64> = values.iter().map(|_| rng.random_range(-1.0..1.0) * 2f64.powi(rng.random_range(0..20))).collect();
+        let small: Vec<f64> = (0..3).map(|_| rng.random_range(-1.0..1.0)).collect();
+        let mut cancelling: Vec<f64> = big.iter().flat_map(|&v| [v, -v]).chain(small).collect();
+        rand::seq::SliceRandom::shuffle(&mut cancelling[..], &mut rng);
+        let fs = floats(&cancelling);
+        let want = exact(&fs);
+        assert!(sum_neumaier(&fs).ulp_distance(&want) <= 1, "{:?}", cancelling);
+        let plain: f64 = cancelling.iter().sum();
+        plain_worse += (Float::new(plain).ulp_distance(&want) > 1) as u32;
+        cases += 1;
     }
+    assert!(plain_worse * 2 > cases, "the plain sum was off only {} of {} times", plain_worse, cases);
+    println!("Summation test passed!");
+}
 
-    fn from_parts(sign: bool, exponent: i16, mantissa: u64) -> Self {
-        Float {
-            bits: (
-                (sign as u64) << 63) |
-                ((((exponent + 1023) as u64) & ((1 << 11)-1)) << 52) | // select lower 11 bits of exponent and shift
-                (mantissa & ((1 << 52) - 1) // select lower 52 bits of mantissa
-            ),
+fn pairwise_test() {
+    // sum_pairwise against the recursive halving on host f64 (for n a power of two times the block, the binary
+    // counter builds the same tree, so they match bit for bit), a block as long as the input against the plain
+    // sum, the streaming sum and the Sum impls against the slice function, and the error on a long series.
+    use floatfs::sum::{sum_neumaier, sum_pairwise, PairwiseSum, Summation, DEFAULT_BLOCK};
+    use rand::Rng;
+    let mut rng = rand::rng();
+    let floats = |values: &[f64]| values.iter().map(|&v| Float::new(v)).collect::<Vec<Float>>();
+    fn halving(values: &[f64], block: usize) -> f64 {
+        if values.len() <= block {
+            return values.iter().fold(-0.0, |s, x| s + x);
         }
+        let (left, right) = values.split_at(values.len() / 2);
+        halving(left, block) + halving(right, block)
+    }
+
+    assert_eq!(sum_pairwise(&[], 4).to_bits(), 0);
+    assert_eq!(Float::new(-0.0).to_bits(), sum_pairwise(&floats(&[-0.0, -0.0, -0.0]), 2).to_bits());
+    assert!(!sum_pairwise(&[Float::from_bits(0x7FF0000000000001)], 4).is_signaling_nan());
+    assert_eq!(sum_pairwise(&floats(&[1.0, 2.0, 3.0]), 0).to_f64(), 6.0);
+    for _ in 0..3_000 {
+        let block = 1 << rng.random_range(0..5);
+        let values: Vec<f64> = (0..block << rng.random_range(0..7))
+            .map(|_| rng.random_range(-1.0..1.0) * 2f64.powi(rng.random_range(-40..40)))
+            .collect();
+        let fs = floats(&values);
+        assert_eq!(sum_pairwise(&fs, block).to_bits(), halving(&values, block).to_bits(), "{} {:?}", block, values);
+        let plain = values.iter().fold(0.0, |s, x| s + x);
+        assert_eq!(sum_pairwise(&fs, values.len()).to_f64(), plain, "{:?}", values);
+        assert_eq!(Summation::Plain.sum(&fs).to_f64(), plain, "{:?}", values);
+
+        // any length, through every entry point
+        let fs = &fs[..rng.random_range(0..=fs.len())];
+        let want = sum_pairwise(fs, DEFAULT_BLOCK).to_bits();
+        assert_eq!(fs.iter().sum::<Float>().to_bits(), want);
+        assert_eq!(fs.iter().map(|f| f.copy()).sum::<Float>().to_bits(), want);
+        assert_eq!(Summation::default().sum(fs).to_bits(), want);
+        let mut running = PairwiseSum::default();
+        fs.iter().for_each(|f| running.add(f));
+        assert_eq!(running.value().to_bits(), want);
+        assert_eq!(Summation::Pairwise(3).sum(fs).to_bits(), sum_pairwise(fs, 3).to_bits());
     }
 
-    fn is_zero(&self) -> bool {
-        self.get_exponent() == -1023 && self.get_mantissa() == 0
+    // a million tenths: the plain sum drifts by thousands of ulps, pairwise stays within a few, block 1 closer still
+    let tenths: Vec<Float> = (0..1_000_000).map(|_| Float::new(0.1)).collect();
+    let exact = sum_neumaier(&tenths);
+    let plain = Summation::Plain.sum(&tenths).ulp_distance(&exact);
+    let (pairwise, single) = (sum_pairwise(&tenths, DEFAULT_BLOCK).ulp_distance(&exact), sum_pairwise(&tenths, 1).ulp_distance(&exact));
+    assert!(plain > 1000 && pairwise < 64 && single <= 4, "plain {} pairwise {} block 1 {}", plain, pairwise, single);
+    println!("Pairwise test passed!");
+}
+
+fn superaccumulator_test() {
+    // sums and dot products against i128 for values that are multiples of 2^-60 below 2^60 (i128 as f64 rounds
+    // correctly, so that's the exactly rounded answer), the same terms shuffled and split across accumulators
+    // that are merged, cancellation across the whole exponent range, rounding modes, zeros and specials.
+    use floatfs::sum::{sum_exact, Summation, Superaccumulator};
+    use floatfs::RoundingMode;
+    use rand::seq::SliceRandom;
+    use rand::Rng;
+    let mut rng = rand::rng();
+    let floats = |values: &[f64]| values.iter().map(|&v| Float::new(v)).collect::<Vec<Float>>();
+    let unit = 2f64.powi(-60);
+    for _ in 0..5_000 {
+        // up to 2^28 of them in the worst case still fits in i128, so the count isn't a worry
+        let integers: Vec<i128> = (0..rng.random_range(1..100))
+            .map(|_| (rng.random_range(-(1i64 << 53)..1 << 53) as i128) << rng.random_range(0..67) >> 53)
+            .collect();
+        let values: Vec<f64> = integers.iter().map(|&i| i as f64 * unit).collect();
+        assert!(integers.iter().zip(&values).all(|(&i, &v)| v / unit == i as f64)); // all exact
+        let want = integers.iter().sum::<i128>() as f64 * unit;
+        let mut fs = floats(&values);
+        assert_eq!(sum_exact(&fs).to_f64(), want, "{:?}", values);
+        fs.shuffle(&mut rng);
+        let split = rng.random_range(0..=fs.len());
+        let (mut left, mut right) = (Superaccumulator::new(), Superaccumulator::new());
+        fs[..split].iter().for_each(|f| left.add(f));
+        fs[split..].iter().for_each(|f| right.add(f));
+        right.merge(&left);
+        assert_eq!(right.value().to_f64(), want, "{:?}", values);
+        assert_eq!(Summation::Exact.sum(&fs).to_f64(), want);
+
+        // products of terms up to 2^23 on a 2^-30 grid
+        let pairs: Vec<(i128, i128)> = (0..rng.random_range(1..50))
+            .map(|_| (rng.random_range(-(1i64 << 53)..1 << 53) as i128 >> rng.random_range(0..53), rng.random_range(-(1i64 << 53)..1 << 53) as i128 >> rng.random_range(0..53)))
+            .collect();
+        let half = 2f64.powi(-30);
+        let mut dot = Superaccumulator::new();
+        pairs.iter().for_each(|(a, b)| dot.add_product(&Float::new(*a as f64 * half), &Float::new(*b as f64 * half)));
+        let exact: i128 = pairs.iter().map(|(a, b)| a * b).sum();
+        assert!(pairs.iter().all(|(a, b)| (*a as f64) as i128 == *a && (*b as f64) as i128 == *b));
+        assert_eq!(dot.value().to_f64(), exact as f64 * unit, "{:?}", pairs);
     }
 
-    fn is_nan(&self) -> bool {
-        self.get_exponent() == 1024 && self.get_mantissa() != 0
+    // the whole range at once: every term cancels except the smallest
+    let tiny = f64::from_bits(1);
+    assert_eq!(sum_exact(&floats(&[f64::MAX, tiny, 1.0, -f64::MAX, -1.0])).to_f64(), tiny);
+    assert_eq!(sum_exact(&floats(&[f64::MAX, f64::MAX, -f64::MAX])).to_f64(), f64::MAX);
+    assert_eq!(sum_exact(&floats(&[f64::MAX, f64::MAX])).to_f64(), f64::INFINITY);
+    let mut dot = Superaccumulator::new();
+    dot.add_product(&Float::new(tiny), &Float::new(tiny)); // 2^-2148
+    dot.add_product(&Float::new(f64::MAX), &Float::new(f64::MAX));
+    dot.add_product(&Float::new(-f64::MAX), &Float::new(f64::MAX));
+    dot.add_product(&Float::new(2f64.powi(-500)), &Float::new(2f64.powi(-500)));
+    assert_eq!(dot.value_with_rounding(RoundingMode::TiesToEven).to_f64(), 2f64.powi(-1000));
+    assert_eq!(dot.value_with_rounding(RoundingMode::TowardPositive).to_f64(), f64::from_bits(2f64.powi(-1000).to_bits() + 1));
+    assert_eq!(dot.value_with_rounding(RoundingMode::TowardZero).to_f64(), 2f64.powi(-1000));
+    let mut dot = Superaccumulator::new();
+    dot.add_product(&Float::new(-tiny), &Float::new(tiny));
+    assert_eq!(dot.value().to_bits(), 1 << 63); // rounds to -0
+    assert_eq!(dot.value_with_rounding(RoundingMode::TowardNegative).to_f64(), -tiny);
+
+    // zeros
+    assert_eq!(sum_exact(&[]).to_bits(), 0);
+    assert_eq!(sum_exact(&floats(&[-0.0, -0.0])).to_bits(), 1 << 63);
+    assert_eq!(sum_exact(&floats(&[-0.0, 0.0])).to_bits(), 0);
+    assert_eq!(sum_exact(&floats(&[1.0, -1.0])).to_bits(), 0);
+    let mut cancel = Superaccumulator::new();
+    cancel.add(&Float::new(1.0));
+    cancel.add(&Float::new(-1.0));
+    assert_eq!(cancel.value_with_rounding(RoundingMode::TowardNegative).to_bits(), 1 << 63);
+    let (mut a, mut b) = (Superaccumulator::new(), Superaccumulator::new());
+    a.add(&Float::new(-0.0));
+    b.merge(&a);
+    assert_eq!(b.value().to_bits(), 1 << 63);
+
+    // specials
+    assert_eq!(sum_exact(&floats(&[1.0, f64::INFINITY, -1e308])).to_f64(), f64::INFINITY);
+    assert_eq!(sum_exact(&floats(&[f64::NEG_INFINITY, 1e308])).to_f64(), f64::NEG_INFINITY);
+    assert!(sum_exact(&floats(&[f64::INFINITY, f64::NEG_INFINITY])).is_nan());
+    assert!(sum_exact(&floats(&[1.0, f64::NAN])).is_nan());
+    let mut dot = Superaccumulator::new();
+    dot.add_product(&Float::new(f64::INFINITY), &Float::new(-2.0));
+    assert_eq!(dot.value().to_f64(), f64::NEG_INFINITY);
+    dot.add_product(&Float::new(f64::INFINITY), &Float::new(0.0));
+    assert!(dot.value().is_nan());
+    println!("Superaccumulator test passed!");
+}
+
+fn quire_test() {
+    // a single sum or product through the quire rounds as the operation does, flags included, in every format and
+    // mode; binary64 dot products match the superaccumulator; a binary32 one matches the superaccumulator's
+    // double rounded to odd and then to binary32 (53 >= 24 + 2, so that's correct), in any order and split across
+    // quires that are merged
+    use floatfs::format::{Binary16, Binary32, Binary64, Minifloat};
+    use floatfs::sum::{Quire, Superaccumulator};
+    use floatfs::{random, Context, Flags, Format, Operation, RoundingMode, SoftFloat, F32, F64};
+    use rand::seq::SliceRandom;
+    use rand::{Rng, RngCore};
+
+    const MODES: [RoundingMode; 5] = [
+        RoundingMode::TiesToEven,
+        RoundingMode::TiesToAway,
+        RoundingMode::TowardZero,
+        RoundingMode::TowardPositive,
+        RoundingMode::TowardNegative,
+    ];
+
+    fn check<F: Format>(bits: &mut impl FnMut() -> u64) {
+        for i in 0..200_000 {
+            let (a, b) = (random::special::<F>(bits), random::special::<F>(bits));
+            let mode = MODES[i % 5];
+            let mut quire = Quire::<F>::new();
+            let op = if i / 5 % 2 == 0 {
+                quire.add(&a);
+                quire.add(&b);
+                Operation::Add
+            } else {
+                quire.add_product(&a, &b);
+                Operation::Multiply
+            };
+            let (mut expected_ctx, mut ctx) = (Context::new(mode), Context::new(mode));
+            let expected = op.apply(&a, &b, &mut expected_ctx);
+            let result = quire.round(&mut ctx);
+            let same = result.to_bits() == expected.to_bits() || result.is_nan() && expected.is_nan();
+            assert!(same && ctx.flags == expected_ctx.flags, "{} {op:?} {mode:?}", F::NAME);
+        }
     }
+    let mut rng = rand::rng();
+    let mut bits = || rand::rng().next_u64();
+    check::<Binary16>(&mut bits);
+    check::<Binary32>(&mut bits);
+    check::<Binary64>(&mut bits);
+    check::<Minifloat>(&mut bits);
 
-    fn is_infinity(&self) -> bool {
-        self.get_exponent() == 1024 && self.get_mantissa() == 0
+    for i in 0..20_000 {
+        let mode = MODES[i % 5];
+        let n = rng.random_range(1..50);
+        let pairs: Vec<(F64, F64)> =
+            (0..n).map(|_| (random::special::<Binary64>(&mut bits), random::special::<Binary64>(&mut bits))).collect();
+        let (mut quire, mut sum) = (Quire::<Binary64>::new(), Superaccumulator::new());
+        for (a, b) in &pairs {
+            quire.add_product(a, b);
+            sum.add_product(&Float::from_bits(a.to_bits()), &Float::from_bits(b.to_bits()));
+        }
+        let (result, expected) = (quire.round(&mut Context::new(mode)), sum.value_with_rounding(mode));
+        // (the superaccumulator signs +0 + +0 -0 toward negative, where the quire keeps +0 as addition does)
+        if !expected.is_zero() {
+            assert!(result.to_bits() == expected.to_bits() || result.is_nan() && expected.is_nan(), "{mode:?}");
+        }
     }
 
-    fn nan() -> Float {
-        Float::from_bits(0x7FF8000000000000)
+    for i in 0..20_000 {
+        let mode = MODES[i % 5];
+        let mut pairs: Vec<(F32, F32)> = (0..rng.random_range(1..50))
+            .map(|_| (random::special::<Binary32>(&mut bits), random::special::<Binary32>(&mut bits)))
+            .filter(|(a, b)| !a.is_nan() && !b.is_nan() && !a.is_infinity() && !b.is_infinity())
+            .collect();
+        let mut sum = Superaccumulator::new();
+        let widen = |x: &F32| Float::from_bits(x.convert::<Binary64>(&mut Context::default()).to_bits());
+        pairs.iter().for_each(|(a, b)| sum.add_product(&widen(a), &widen(b)));
+        let down = sum.value_with_rounding(RoundingMode::TowardZero);
+        let (up, below) = (sum.value_with_rounding(RoundingMode::TowardPositive), sum.value_with_rounding(RoundingMode::TowardNegative));
+        let odd = if down.to_f64() == up.to_f64() && down.to_f64() == below.to_f64() {
+            down.to_bits()
+        } else {
+            down.to_bits() | 1
+        };
+        let expected: F32 = F64::from_bits(odd).convert(&mut Context::new(mode));
+        // (the accumulator rounding toward zero signs a cancelled sum +0 whatever the mode, so zeros are left out)
+        pairs.shuffle(&mut rng);
+        let split = rng.random_range(0..=pairs.len());
+        let (mut left, mut right) = (Quire::<Binary32>::new(), Quire::<Binary32>::new());
+        pairs[..split].iter().for_each(|(a, b)| left.add_product(a, b));
+        pairs[split..].iter().for_each(|(a, b)| right.add_product(a, b));
+        right.merge(&left);
+        let result = right.round(&mut Context::new(mode));
+        if !expected.is_zero() {
+            assert_eq!(result.to_bits(), expected.to_bits(), "{mode:?}");
+        }
     }
 
-    fn infinity(sign: bool) -> Float {
-        Float::from_bits((sign as u64) << 63 | (0x7FF << 52)) // infinity
+    fn negated<F: Format>(mut x: SoftFloat<F>) -> SoftFloat<F> {
+        x.negate();
+        x
     }
+    // exact to the end: 2^127 * 2^127 - 2^127 * 2^127 + 2^-149 * 2^-149 is 2^-298, far below binary32, so it
+    // rounds to the smallest subnormal or zero, and says so
+    let (big, tiny) = (F32::from_bits(0x7F00_0000), F32::from_bits(1));
+    let mut quire = Quire::<Binary32>::new();
+    quire.add_product(&big, &big);
+    quire.add_product(&negated(big), &big);
+    quire.add_product(&tiny, &tiny);
+    let mut ctx = Context::new(RoundingMode::TowardPositive);
+    assert_eq!(quire.round(&mut ctx).to_bits(), 1);
+    assert!(ctx.flags.contains(Flags::UNDERFLOW | Flags::INEXACT));
+    let mut ctx = Context::default();
+    assert_eq!(quire.round(&mut ctx).to_bits(), 0);
+    // overflow only once it's rounded
+    let mut quire = Quire::<Binary16>::new();
+    let largest = SoftFloat::<Binary16>::largest_finite(false);
+    quire.add_product(&largest, &largest);
+    let mut ctx = Context::default();
+    assert!(quire.round(&mut ctx).is_infinity() && ctx.flags.contains(Flags::OVERFLOW));
+    quire.add_product(&negated(largest), &largest);
+    quire.add(&largest);
+    let mut ctx = Context::default();
+    assert!(quire.round(&mut ctx).to_bits() == largest.to_bits() && ctx.flags.is_empty());
+
+    // zeros and specials, whatever the order
+    let mut ctx = Context::default();
+    assert!(Quire::<Binary32>::new().round(&mut ctx).to_bits() == 0 && ctx.flags.is_empty());
+    let mut quire = Quire::<Binary32>::new();
+    quire.add_product(&F32::zero(true), &F32::zero(false));
+    quire.add(&F32::zero(true));
+    assert_eq!(quire.round(&mut ctx).to_bits(), 0x8000_0000);
+    let (infinity, one) = (F32::infinity(false), F32::from_bits(0x3F80_0000));
+    let mut quire = Quire::<Binary32>::new();
+    quire.add_product(&infinity, &one);
+    quire.add(&F32::nan());
+    quire.add(&negated(infinity));
+    assert!(quire.round(&mut ctx).is_nan() && ctx.take_flags() == Flags::INVALID);
+    let mut quire = Quire::<Binary32>::new();
+    quire.add(&F32::nan());
+    quire.add_product(&infinity, &infinity);
+    assert!(quire.round(&mut ctx).is_nan() && ctx.take_flags().is_empty());
+    println!("Quire test passed!");
+}
 
-    fn copy(&self) -> Float {
-        Float { bits: self.bits }
+fn stats_test() {
+    // the plain statistics against the same welford loop on f64, bit for bit; the compensated ones against the
+    // exact mean S / n and variance (n Q - S^2) / n^2 (S the sum and Q the sum of squares), checked through Exact:
+    // a correctly rounded m has n (m + m-) <= 2 S <= n (m + m+), m- and m+ its neighbours. data far from zero
+    // next to its spread is where the plain variance goes wrong
+    use floatfs::exact::Exact;
+    use floatfs::stats::RunningStats;
+    use rand::Rng;
+
+    fn between(target: Exact, scale: Exact, x: &Float) -> bool {
+        let (below, above) = (x.next_down(), x.next_up());
+        let low = (scale * x + scale * &below - target).round();
+        let high = (scale * x + scale * &above - target).round();
+        low.to_f64() <= 0.0 && high.to_f64() >= 0.0
     }
 
-    // fn largest_finite() -> float {
-    //     float::from_bits(0x7FEFFFFFFFFFFFFF) // largest finite number
-    // }
-    // fn smallest_normal() -> float {
-    //     float::from_bits(0x0010000000000000) // smallest normal number
-    // }
-    // fn smallest_subnormal() -> float {
-    //     float::from_bits(0x0000000000000001) // smallest subnormal number
-    // }
+    let mut rng = rand::rng();
+    let mut plain_wrong = 0;
+    for i in 0..20_000 {
+        let n = rng.random_range(1..200);
+        let offset = [0.0, 1.0, 1e6, -3e9, 1e-300][i % 5];
+        let spread = [1.0, 1e-3, 1e-8][i / 5 % 3];
+        let values: Vec<Float> = (0..n).map(|_| Float::new(offset + rng.random_range(-spread..spread))).collect();
+        let (mut plain, mut compensated) = (RunningStats::new(), RunningStats::compensated());
+        let (mut mean, mut squares) = (0.0f64, 0.0f64);
+        for (k, x) in values.iter().enumerate() {
+            plain.add(x);
+            compensated.add(x);
+            let delta = x.to_f64() - mean;
+            mean += delta / (k + 1) as f64;
+            squares += delta * (x.to_f64() - mean);
+        }
+        assert_eq!(plain.count(), n as u64);
+        assert_eq!(plain.mean().to_bits(), mean.to_bits());
+        assert_eq!(plain.variance().to_bits(), (squares / n as f64).to_bits());
 
-    fn nan_logic(&self, other: &Float) -> Option<Float> {
-        // this nan logic is not super important but matches apple's cpu behavior
-        // the rule is that signaling nans take precedence over quiet nans,
-        // that if both are the same type the first operand takes precedence,
-        // and that if one is a nan and the other is not, the nan is returned.
-        let self_is_nan = self.is_nan();
-        let other_is_nan = other.is_nan();
-        if self_is_nan || other_is_nan {
-            let chosen_nan = if other_is_nan
-                && (other.get_mantissa() >> 51) == 0
-                && !(self_is_nan && (self.get_mantissa() >> 51) == 0)
-            {
-                // other is signaling nan and self is not signaling nan
-                other.bits
-            } else if self_is_nan {
-                self.bits
-            } else {
-                other.bits
-            };
-            return Some(Float::from_bits(chosen_nan | 1 << 51)); // quiet nan
+        let (mut sum, mut sum_squares) = (Exact::from(0), Exact::from(0));
+        for x in &values {
+            sum += x;
+            sum_squares += Exact::from(x) * x;
         }
-        None
+        let n = n as i64;
+        let mean = compensated.mean();
+        assert!(between(sum * 2, Exact::from(n), &mean), "mean {i}");
+        let numerator = (sum_squares * n - sum * sum) * 2;
+        let variance = compensated.variance();
+        assert!(between(numerator, Exact::from(n * n), &variance), "variance {i}");
+        if n > 1 {
+            assert!(between(numerator, Exact::from(n * (n - 1)), &compensated.sample_variance()), "sample {i}");
+        }
+        plain_wrong += !between(numerator, Exact::from(n * n), &plain.variance()) as u32;
     }
+    assert!(plain_wrong > 0);
 
-    // returns mantissa with implicit leading 1 and adjusts exponent for subnormals
-    fn get_full_mantissa(&self, exponent: &mut i16) -> u64 {
-        let is_normal = (((self.bits >> 52) & ((1 << 11) - 1)) != 0) as u64; // exponent bits non-zero
-        *exponent += 1 - is_normal as i16; // adjust exponent for subnormal (interpreted as -1022)
-        self.get_mantissa() | (is_normal << 52) // implicit leading 1
+    let mut stats = RunningStats::compensated();
+    assert!(stats.mean().is_nan() && stats.variance().is_nan());
+    stats.add(&Float::new(-0.0));
+    assert_eq!(stats.mean().to_bits(), 0);
+    assert!(stats.variance().is_zero() && stats.sample_variance().is_nan());
+    stats.add(&Float::new(f64::INFINITY));
+    assert_eq!(stats.mean().to_f64(), f64::INFINITY);
+    assert!(stats.variance().is_nan());
+    stats.add(&Float::new(f64::NEG_INFINITY));
+    assert!(stats.mean().is_nan());
+    // 1e308 and -1e308 overflow the plain recurrence's delta, not the compensated one's
+    let (mut plain, mut compensated) = (RunningStats::new(), RunningStats::compensated());
+    for x in [1e308, -1e308] {
+        plain.add(&Float::new(x));
+        compensated.add(&Float::new(x));
     }
+    assert!(plain.mean().is_infinity() && compensated.mean().is_zero());
+    assert_eq!(compensated.variance().to_f64(), f64::INFINITY);
+    println!("Stats test passed!");
+}
 
-    fn multiply(&self, other: &Float) -> Float {
-        if let Some(nan) = self.nan_logic(other) {
-            return nan;
+fn reproducible_test() {
+    // sum_reproducible has to give the same bits for the same terms in any order and split any way between sums
+    // that get merged, including past the 2048 deposits that force a renormalization. it should also be within
+    // its error bound of the exact sum, and exact when all the terms fit in its three bins (subnormals, or terms
+    // close in size), including at the top and bottom of the range.
+    use floatfs::sum::{sum_exact, sum_reproducible, ReproducibleSum, Summation};
+    use rand::seq::SliceRandom;
+    use rand::Rng;
+    let mut rng = rand::rng();
+    let floats = |values: &[f64]| values.iter().map(|&v| Float::new(v)).collect::<Vec<Float>>();
+    let random = |rng: &mut rand::rngs::ThreadRng, low: i32, high: i32| {
+        rng.random_range(-1.0..1.0) * 2f64.powi(rng.random_range(low..high))
+    };
+    for round in 0..300 {
+        let (low, high) = [(-60, 60), (-1074, -1022), (900, 1000), (-300, 300)][round % 4];
+        let n = if round % 10 == 0 { rng.random_range(2_000..10_000) } else { rng.random_range(1..300) };
+        let mut values: Vec<f64> = (0..n).map(|_| random(&mut rng, low, high)).collect();
+        if round % 3 == 0 {
+            // big terms that cancel, to make the plain sum order dependent
+            let big: Vec<f64> = (0..n / 4).map(|_| random(&mut rng, high, high + 20)).collect();
+            values.extend(big.iter().flat_map(|&b| [b, -b]));
+        }
+        let mut fs = floats(&values);
+        let want = sum_reproducible(&fs).to_bits();
+        for _ in 0..5 {
+            fs.shuffle(&mut rng);
+            assert_eq!(sum_reproducible(&fs).to_bits(), want);
+            // split into chunks, sum them separately and merge in a random order
+            let mut parts: Vec<ReproducibleSum> = vec![];
+            let mut rest = &fs[..];
+            while !rest.is_empty() {
+                let (chunk, tail) = rest.split_at(rng.random_range(1..=rest.len()));
+                let mut part = ReproducibleSum::new();
+                chunk.iter().for_each(|f| part.add(f));
+                parts.push(part);
+                rest = tail;
+            }
+            parts.shuffle(&mut rng);
+            while parts.len() > 1 {
+                let other = parts.swap_remove(rng.random_range(0..parts.len()));
+                let index = rng.random_range(0..parts.len());
+                parts[index].merge(&other);
+            }
+            assert_eq!(parts[0].value().to_bits(), want);
+        }
+        // n * 2^-80 times the largest term, plus half an ulp of the sum
+        let exact = sum_exact(&fs).to_f64();
+        let largest = values.iter().fold(0f64, |m, v| m.max(v.abs()));
+        let error = (f64::from_bits(want) - exact).abs();
+        assert!(error <= fs.len() as f64 * largest * 2f64.powi(-80) + exact.abs() * 2f64.powi(-53), "{} {:e} {:e}", round, f64::from_bits(want), exact);
+        if round % 4 == 1 {
+            assert_eq!(f64::from_bits(want), exact); // subnormals all fit in the window
         }
+        assert_eq!(Summation::Reproducible.sum(&fs).to_bits(), want);
+    }
+
+    // terms within a factor of 2^39 of each other land in at most two bins, so the sum is exact
+    for _ in 0..2_000 {
+        let e = rng.random_range(-1000..980);
+        let values: Vec<f64> = (0..rng.random_range(1..50)).map(|_| random(&mut rng, e, e + 39)).collect();
+        assert_eq!(sum_reproducible(&floats(&values)).to_f64(), sum_exact(&floats(&values)).to_f64(), "{:?}", values);
+    }
+    assert_eq!(sum_reproducible(&floats(&[f64::MAX, -f64::MAX, 1.0])).to_f64(), 0.0); // 1 is below the window
+    assert_eq!(sum_reproducible(&floats(&[f64::MAX, f64::MAX])).to_f64(), f64::INFINITY);
+    assert_eq!(sum_reproducible(&floats(&[f64::MAX, f64::MAX, -f64::MAX])).to_f64(), f64::MAX);
+    let tiny = f64::from_bits(1);
+    assert_eq!(sum_reproducible(&floats(&[tiny, tiny, -tiny * 3.0])).to_f64(), -tiny);
+    assert_eq!(sum_reproducible(&floats(&[1e16, 1.0, -1e16, 1.0])).to_f64(), 2.0);
 
-        let sign = self.get_sign() ^ other.get_sign(); // same sign means pos, else neg
+    assert_eq!(sum_reproducible(&[]).to_bits(), 0);
+    assert_eq!(sum_reproducible(&floats(&[-0.0, -0.0])).to_bits(), 1 << 63);
+    assert_eq!(sum_reproducible(&floats(&[1.0, -1.0])).to_bits(), 0);
+    assert_eq!(sum_reproducible(&floats(&[1.0, f64::INFINITY])).to_f64(), f64::INFINITY);
+    assert!(sum_reproducible(&floats(&[f64::INFINITY, f64::NEG_INFINITY])).is_nan());
+    assert!(sum_reproducible(&floats(&[f64::NAN, 1.0])).is_nan());
+    println!("Reproducible test passed!");
+}
 
-        if self.is_infinity() || other.is_infinity() {
-            if self.is_zero() || other.is_zero() {
-                return Float::nan(); // infinity * 0 = nan
+fn ball_test() {
+    // every result has to contain the exact result of the operation on any members of the operands. members are
+    // doubles picked from each operand ball (its ends, midpoint and points between), and the exact result is in a
+    // ball whose ends are doubles exactly when both its rounding down and its rounding up are, which is checkable:
+    // the arithmetic has directed rounding, and the elementary functions are correctly rounded in every mode
+    // (barring a value within 2^-120 of a boundary, which random arguments won't find). radii also have to be
+    // tight: within a hair of the first-order bound plus an ulp of the midpoint.
+    use floatfs::ball::Ball;
+    use floatfs::{Context, RoundingMode, F64};
+    use rand::Rng;
+    let mut rng = rand::rng();
+    let ball = |mid: f64, rad: f64| Ball::new(Float::new(mid), Float::new(rad));
+    let ulp = |x: f64| Float::new(x.abs()).next_up().to_f64() - x.abs();
+    let holds = |b: &Ball, down: Float, up: Float| b.contains(&down) && b.contains(&up);
+    let random_ball = |rng: &mut rand::rngs::ThreadRng, positive: bool| {
+        let sign = if positive || rng.random_bool(0.5) { 1.0 } else { -1.0 };
+        let mid = sign * rng.random_range(0.5..1.0) * 2f64.powi(rng.random_range(-40..40));
+        let rad = if rng.random_bool(0.2) { 0.0 } else { mid.abs() * 2f64.powi(-rng.random_range(2..60)) };
+        ball(mid, rad)
+    };
+    let members = |rng: &mut rand::rngs::ThreadRng, b: &Ball| {
+        let (lo, hi) = (b.lower().to_f64(), b.upper().to_f64());
+        let mut points = vec![lo, hi, b.mid().to_f64()];
+        points.extend((0..3).map(|_| (lo + rng.random_range(0.0..1.0) * (hi - lo)).clamp(lo, hi)));
+        // lower() and upper() are rounded outward, so keep only the points provably within the radius
+        let (m, r) = (b.mid().to_f64(), b.rad().to_f64());
+        let gap = |p: f64, mode| Float::new(p).add_with_rounding(&Float::new(-m), mode).to_f64();
+        points.retain(|&p| gap(p, RoundingMode::TowardPositive) <= r && gap(p, RoundingMode::TowardNegative) >= -r);
+        points
+    };
+    let modes = [RoundingMode::TowardNegative, RoundingMode::TowardPositive];
+    for _ in 0..20_000 {
+        let (x, y) = (random_ball(&mut rng, false), random_ball(&mut rng, false));
+        let (sum, product, quotient) = (x.add(&y), x.multiply(&y), x.divide(&y));
+        let difference = x.subtract(&y);
+        for a in members(&mut rng, &x) {
+            for b in members(&mut rng, &y) {
+                let (fa, fb, minus_b) = (Float::new(a), Float::new(b), Float::new(-b));
+                let [down, up] = modes.map(|m| fa.add_with_rounding(&fb, m));
+                assert!(holds(&sum, down, up), "{:?} + {:?} at {} {}: {:?}", x, y, a, b, sum);
+                let [down, up] = modes.map(|m| fa.add_with_rounding(&minus_b, m));
+                assert!(holds(&difference, down, up), "{:?} - {:?} at {} {}: {:?}", x, y, a, b, difference);
+                let [down, up] = modes.map(|m| fa.multiply_with_rounding(&fb, m));
+                assert!(holds(&product, down, up), "{:?} * {:?} at {} {}: {:?}", x, y, a, b, product);
+                let [down, up] = modes.map(|m| fa.divide_with_rounding(&fb, m));
+                assert!(holds(&quotient, down, up), "{:?} / {:?} at {} {}: {:?}", x, y, a, b, quotient);
             }
-            return Float::infinity(sign);
         }
+        let (m1, r1, m2, r2) = (x.mid().to_f64(), x.rad().to_f64(), y.mid().to_f64(), y.rad().to_f64());
+        let slack = 1.0 + 1e-12;
+        assert!(sum.rad().to_f64() <= (r1 + r2) * slack + ulp(m1 + m2) / 2.0);
+        assert!(product.rad().to_f64() <= (m1.abs() * r2 + m2.abs() * r1 + r1 * r2) * slack + ulp(m1 * m2) / 2.0);
+        let spread = (m2.abs() * r1 + m1.abs() * r2) / (m2.abs() * (m2.abs() - r2));
+        assert!(quotient.rad().to_f64() <= spread * slack + ulp(m1 / m2) / 2.0, "{:?} / {:?}", x, y);
 
-        let mut exponent = self.get_exponent() + other.get_exponent();
+        let positive = rng.random_bool(0.8);
+        let x = random_ball(&mut rng, positive);
+        type Function = fn(&F64, &mut Context) -> F64;
+        let functions: [(&str, Ball, Function); 6] = [
+            ("exp", x.exp(), F64::exp),
+            ("ln", x.ln(), F64::ln),
+            ("sqrt", x.sqrt(), |v: &F64, ctx: &mut Context| v.powf(&F64::from_bits(0.5f64.to_bits()), ctx)),
+            ("sin", x.sin(), F64::sin),
+            ("cos", x.cos(), F64::cos),
+            ("atan", x.atan(), F64::atan),
+        ];
+        for (name, result, f) in functions {
+            if result.mid().is_nan() {
+                // only ln and sqrt give up, and only when the ball reaches zero
+                assert!((name == "ln" || name == "sqrt") && x.lower().to_f64() <= 0.0, "{} {:?}", name, x);
+                continue;
+            }
+            for a in members(&mut rng, &x) {
+                let [down, up] = modes.map(|m| Float::from_bits(f(&F64::from_bits(a.to_bits()), &mut Context::new(m)).to_bits()));
+                assert!(holds(&result, down, up), "{}({:?}) at {}: {:?}", name, x, a, result);
+            }
+        }
+    }
 
-        let mut mantissa_full = u128::from(self.get_full_mantissa(&mut exponent)) * u128::from(other.get_full_mantissa(&mut exponent)); // 53 + 53 = 106 bits
+    let exact = |v: f64| Ball::exact(Float::new(v));
+    let parts = |b: Ball| (b.mid().to_f64(), b.rad().to_f64());
+    assert_eq!(parts(exact(1.0).add(&exact(2.0))), (3.0, 0.0));
+    assert_eq!(parts(exact(0.1).add(&exact(0.2))), (0.30000000000000004, 2.7755575615628914e-17));
+    assert_eq!(parts(exact(3.0).multiply(&exact(-0.5))), (-1.5, 0.0));
+    assert_eq!(parts(exact(0.0).multiply(&ball(1.0, f64::INFINITY))), (0.0, 0.0));
+    assert_eq!(parts(exact(0.0).exp()), (1.0, f64::EPSILON));
+    assert_eq!(parts(exact(4.0).sqrt()).0, 2.0);
+    assert_eq!(parts(ball(0.0, 1e300).sin()), (0.0, 2.0000000000000004)); // 2 and an ulp of 0, rounded up
+    assert!(ball(1.0, 1.0).divide(&ball(1.0, 1.0)).mid().is_nan());
+    assert!(ball(1.0, 0.5).divide(&ball(-1.0, 2.0)).contains(&Float::new(1e300)));
+    assert!(ball(1.0, 2.0).ln().mid().is_nan());
+    assert!(ball(-1.0, 0.5).sqrt().mid().is_nan());
+    assert!(exact(f64::NAN).contains(&Float::new(-7.0)));
+    assert!(!exact(1.0).contains(&Float::nan()));
+    let overflowed = exact(1e308).multiply(&exact(10.0));
+    assert!(!overflowed.is_finite() && overflowed.contains(&Float::new(f64::MAX)));
+    assert_eq!(parts(exact(f64::MAX).add(&exact(f64::MAX))).1, f64::INFINITY);
+    // a thousand steps of x -> x^2 / 2 + 1/2 near its fixed point at 1 lose the midpoint's accuracy but never
+    // the true value
+    let mut x = exact(0.999);
+    let mut truth = 0.999f64;
+    for _ in 0..1000 {
+        x = x.multiply(&x).multiply(&exact(0.5)).add(&exact(0.5));
+        truth = truth * truth * 0.5 + 0.5;
+    }
+    assert!(x.rad().to_f64() < 1e-12 && (x.mid().to_f64() - truth).abs() < 1e-12);
+    println!("Ball test passed!");
+}
 
-        // println!("Mantissa full: {:0106b}", mantissa_full);
+fn complex_test() {
+    // the Annex G properties over every combination of special parts: infinite times nonzero or infinite is
+    // infinite, infinite over finite is infinite, finite over infinite is zero and nonzero over zero is infinite,
+    // none of which the textbook formulas give. then against the G.5.1 code itself on host doubles, on those and
+    // on random values where its scalbn (here a multiply by a power of two) is exact, and division by values big
+    // enough that c^2 + d^2 overflows, which the scaling has to undo exactly.
+    use floatfs::complex::Complex;
+    use rand::Rng;
+    let mut rng = rand::rng();
+    let complex = |re: f64, im: f64| Complex::new(Float::new(re), Float::new(im));
+    let parts = |z: Complex<Float>| (z.re.to_f64(), z.im.to_f64());
+    let logb = |x: f64| {
+        let bits = x.abs().to_bits();
+        if bits >> 52 == 0 { -1011 - bits.leading_zeros() as i32 } else { (bits >> 52) as i32 - 1023 }
+    };
+    let boxed = |x: f64| if x.is_infinite() { 1f64.copysign(x) } else { 0f64.copysign(x) };
+    let zeroed = |x: f64| if x.is_nan() { 0f64.copysign(x) } else { x };
+    let multiply = |mut a: f64, mut b: f64, mut c: f64, mut d: f64| {
+        let (ac, bd, ad, bc) = (a * c, b * d, a * d, b * c);
+        let (x, y) = (ac - bd, ad + bc);
+        if !(x.is_nan() && y.is_nan()) {
+            return (x, y);
+        }
+        let mut recalc = false;
+        if a.is_infinite() || b.is_infinite() {
+            (a, b, c, d) = (boxed(a), boxed(b), zeroed(c), zeroed(d));
+            recalc = true;
+        }
+        if c.is_infinite() || d.is_infinite() {
+            (a, b, c, d) = (zeroed(a), zeroed(b), boxed(c), boxed(d));
+            recalc = true;
+        }
+        if !recalc && (ac.is_infinite() || bd.is_infinite() || ad.is_infinite() || bc.is_infinite()) {
+            (a, b, c, d) = (zeroed(a), zeroed(b), zeroed(c), zeroed(d));
+            recalc = true;
+        }
+        if !recalc {
+            return (x, y);
+        }
+        (f64::INFINITY * (a * c - b * d), f64::INFINITY * (a * d + b * c))
+    };
+    let divide = |mut a: f64, mut b: f64, mut c: f64, mut d: f64| {
+        let big = c.abs().max(d.abs());
+        let mut scale = 0;
+        if big.is_finite() && big != 0.0 {
+            scale = logb(big);
+            (c, d) = (c * 2f64.powi(-scale), d * 2f64.powi(-scale));
+        }
+        let denominator = c * c + d * d;
+        let x = (a * c + b * d) / denominator * 2f64.powi(-scale);
+        let y = (b * c - a * d) / denominator * 2f64.powi(-scale);
+        if !(x.is_nan() && y.is_nan()) {
+            return (x, y);
+        }
+        if denominator == 0.0 && (!a.is_nan() || !b.is_nan()) {
+            return (f64::INFINITY.copysign(c) * a, f64::INFINITY.copysign(c) * b);
+        }
+        if (a.is_infinite() || b.is_infinite()) && c.is_finite() && d.is_finite() {
+            (a, b) = (boxed(a), boxed(b));
+            return (f64::INFINITY * (a * c + b * d), f64::INFINITY * (b * c - a * d));
+        }
+        if big.is_infinite() && a.is_finite() && b.is_finite() {
+            (c, d) = (boxed(c), boxed(d));
+            return (0.0 * (a * c + b * d), 0.0 * (b * c - a * d));
+        }
+        (x, y)
+    };
+    let same = |got: (f64, f64), want: (f64, f64)| {
+        let eq = |g: f64, w: f64| g.to_bits() == w.to_bits() || g.is_nan() && w.is_nan();
+        eq(got.0, want.0) && eq(got.1, want.1)
+    };
+    let infinite = |(x, y): (f64, f64)| x.is_infinite() || y.is_infinite();
+    let finite = |x: f64, y: f64| x.is_finite() && y.is_finite();
+    let zero = |x: f64, y: f64| x == 0.0 && y == 0.0;
 
-        // if-else block normalizes mantissa_full so that the 105th bit is set.
-        // why bit 105? because we're going to shift down by 52 and so the implicit 1 will be correctly at bit 53.
-        if mantissa_full >> 105 != 0 {
-            // is 106th bit set? this means we overflowed.
-            // println!("Normalizing mantissa, shifting right");
-            exponent += 1;
-            mantissa_full >>= 1; // todo: technically this could affect rounding??
-        } else {
-            // this case only happens when subnormals are involved, since min normal mantissa is 2^52 and 2^52 * 2^52 = 2^104, which has the 105th bit set.
-            // todo: handle upper case by using leading zeros too?
-            let shift_amt = mantissa_full.leading_zeros() - (128 - 105); // this will never be negative since we handled that case above. we want 23 leading zeros.
-            mantissa_full <<= shift_amt;
-            exponent -= shift_amt as i16;
+    let specials = [0.0, -0.0, 1.0, -1.0, 2.5, -3.0, f64::INFINITY, f64::NEG_INFINITY, f64::NAN];
+    for a in specials {
+        for b in specials {
+            for c in specials {
+                for d in specials {
+                    let (z, w) = (complex(a, b), complex(c, d));
+                    let product = parts(z.multiply(&w));
+                    let quotient = parts(z.divide(&w));
+                    assert!(same(product, multiply(a, b, c, d)), "({} {}) * ({} {}) = {:?}", a, b, c, d, product);
+                    assert!(same(quotient, divide(a, b, c, d)), "({} {}) / ({} {}) = {:?}", a, b, c, d, quotient);
+                    let (z_infinite, w_infinite) = (z.is_infinity(), w.is_infinity());
+                    if z_infinite && (w_infinite || finite(c, d) && !zero(c, d))
+                        || w_infinite && finite(a, b) && !zero(a, b)
+                    {
+                        assert!(infinite(product), "({} {}) * ({} {}) = {:?}", a, b, c, d, product);
+                    }
+                    if z_infinite && finite(c, d) || finite(a, b) && !zero(a, b) && zero(c, d) {
+                        assert!(infinite(quotient), "({} {}) / ({} {}) = {:?}", a, b, c, d, quotient);
+                    }
+                    if finite(a, b) && w_infinite {
+                        assert!(zero(quotient.0, quotient.1), "({} {}) / ({} {}) = {:?}", a, b, c, d, quotient);
+                    }
+                }
+            }
         }
+    }
+    // the textbook formula loses these
+    assert!(infinite(parts(complex(f64::INFINITY, f64::NAN).multiply(&complex(1.0, 1.0)))));
+    assert!(infinite(parts(complex(1.0, 1.0).divide(&complex(0.0, 0.0)))));
+    assert!(zero(parts(complex(1.0, 1.0).divide(&complex(f64::INFINITY, f64::INFINITY))).0, 0.0));
+    assert_eq!(parts(complex(1e300, 1e300).divide(&complex(1e300, 1e300))), (1.0, 0.0));
+    assert_eq!(parts(complex(1e-300, -1e-300).divide(&complex(1e-300, 1e-300))), (0.0, -1.0));
+
+    for _ in 0..200_000 {
+        let mut random = || rng.random_range(-1.0..1.0) * 2f64.powi(rng.random_range(-100..100));
+        let (a, b, c, d) = (random(), random(), random(), random());
+        let (z, w) = (complex(a, b), complex(c, d));
+        assert!(same(parts(z.multiply(&w)), (a * c - b * d, a * d + b * c)), "({} {}) * ({} {})", a, b, c, d);
+        let quotient = parts(z.divide(&w));
+        assert!(same(quotient, divide(a, b, c, d)), "({} {}) / ({} {}) = {:?}", a, b, c, d, quotient);
+        // scaling both sides by a power of two doesn't change the quotient, even where c^2 overflows or underflows
+        let k = rng.random_range(400..900) * if rng.random_bool(0.5) { 1 } else { -1 };
+        let s = 2f64.powi(k);
+        let scaled = parts(complex(a * s, b * s).divide(&complex(c * s, d * s)));
+        assert!(same(scaled, quotient), "({} {}) / ({} {}) * 2^{} = {:?}", a, b, c, d, k, scaled);
+    }
+    println!("Complex test passed!");
+}
 
-        let shift_and_round = |mantissa_full: u128, shift: u32| -> u64 {
-            let mantissa = (mantissa_full >> shift) as u64;
-            let remainder = mantissa_full & ((1u128 << shift) - 1);
-            let half_way = 1u128 << (shift - 1);
+fn blas_test() {
+    // axpy, scal and asum against the same loops on host f64, bit for bit. nrm2 on integer vectors against the exact
+    // sum of squares: the result r = m * 2^e is correctly rounded when the halfway points (2m - 1) 2^(e-1) and
+    // (2m + 1) 2^(e-1) bracket the true norm, which in integers is (2m - 1)^2 <= 4 S 2^-2e <= (2m + 1)^2. then
+    // the same vectors scaled far up and down, where squaring in doubles would overflow or underflow, and specials.
+    // fma_slices and fma_accumulate against host mul_add, on random bits and on products cancelling against c, and
+    // Context::fused_multiply_add in each rounding mode against the exact value rounded.
+    use floatfs::blas::{asum, axpy, fma_accumulate, fma_slices, nrm2, scal};
+    use floatfs::exact::Exact;
+    use floatfs::{Context, RoundingMode, F64};
+    use rand::Rng;
+    let modes = [
+        RoundingMode::TiesToEven,
+        RoundingMode::TiesToAway,
+        RoundingMode::TowardZero,
+        RoundingMode::TowardPositive,
+        RoundingMode::TowardNegative,
+    ];
+    let mut rng = rand::rng();
+    let floats = |values: &[f64]| values.iter().map(|&v| Float::new(v)).collect::<Vec<Float>>();
+    for _ in 0..5_000 {
+        let n = rng.random_range(0..40);
+        let x: Vec<f64> = (0..n).map(|_| rng.random_range(-1.0..1.0) * 2f64.powi(rng.random_range(-30..30))).collect();
+        let y: Vec<f64> = (0..n + rng.random_range(0..3)).map(|_| rng.random_range(-1.0..1.0)).collect();
+        let alpha = rng.random_range(-4.0..4.0);
+        let mut fy = floats(&y);
+        axpy(&Float::new(alpha), &floats(&x), &mut fy);
+        let want: Vec<f64> = y.iter().enumerate().map(|(i, &yi)| x.get(i).map_or(yi, |&xi| alpha * xi + yi)).collect();
+        assert!(fy.iter().zip(&want).all(|(f, w)| f.to_bits() == w.to_bits()), "axpy {:?} {:?}", x, y);
+        let mut fx = floats(&x);
+        scal(&Float::new(alpha), &mut fx);
+        assert!(fx.iter().zip(&x).all(|(f, xi)| f.to_f64().to_bits() == (alpha * xi).to_bits()));
+        assert_eq!(asum(&floats(&x)).to_bits(), x.iter().fold(0.0, |s, xi| s + xi.abs()).to_bits());
 
-            if remainder > half_way || (remainder == half_way && mantissa & 1 == 1) {
-                // if past halfway or exactly halfway and mantissa is odd (add instead of subtract since other case rounds down.)
-                mantissa + 1
-            } else {
-                // round down (truncate)
-                mantissa
+        let ints: Vec<i64> = (0..n).map(|_| rng.random_range(-(1i64 << 40)..1 << 40) >> rng.random_range(0..40)).collect();
+        let values: Vec<f64> = ints.iter().map(|&i| i as f64).collect();
+        let r = nrm2(&floats(&values)).to_f64();
+        let squares: u128 = ints.iter().map(|&i| (i as i128 * i as i128) as u128).sum();
+        if squares == 0 {
+            assert_eq!(r.to_bits(), 0);
+            continue;
+        }
+        let (m, e) = ((r.to_bits() & ((1 << 52) - 1) | 1 << 52) as u128, (r.to_bits() >> 52) as i32 - 1075);
+        assert!(e <= 0); // the norm is under 2^47, and the shifted sum is about (2m)^2, around 2^108
+        let scaled = squares << (2 - 2 * e);
+        assert!((2 * m - 1).pow(2) <= scaled && scaled <= (2 * m + 1).pow(2), "nrm2 {:?} = {:e}", ints, r);
+        for k in [-1000, 1000] {
+            let scale = 2f64.powi(k);
+            let far = nrm2(&floats(&values.iter().map(|v| v * scale).collect::<Vec<f64>>())).to_f64();
+            assert_eq!(far, r * scale, "nrm2 {:?} * 2^{}", ints, k);
+        }
+    }
+    let nrm = |values: &[f64]| nrm2(&floats(values)).to_f64();
+    assert_eq!(nrm(&[-3.0, 4.0]), 5.0);
+    assert_eq!(nrm(&[1e300, 1e300]), 1.4142135623730952e300); // from mpmath; 1e-300 * sqrt(2) rounds twice
+    assert_eq!(nrm(&[1e-300, 1e-300]), 1.414213562373095e-300);
+    assert_eq!(nrm(&[f64::MAX, f64::MAX]), f64::INFINITY);
+    assert_eq!(nrm(&[f64::from_bits(1); 4]), f64::from_bits(2));
+    assert_eq!(nrm(&[]).to_bits(), 0);
+    assert_eq!(nrm(&[-0.0]).to_bits(), 0);
+    assert_eq!(nrm(&[f64::NAN, f64::NEG_INFINITY]), f64::INFINITY);
+    assert!(nrm(&[1.0, f64::NAN]).is_nan());
+    assert!(nrm2(&[Float::from_bits(0x7FF0000000000001), Float::new(f64::INFINITY)]).is_nan());
+    let mut y = floats(&[1.0, 2.0]);
+    axpy(&Float::new(0.0), &floats(&[f64::NAN, f64::INFINITY]), &mut y);
+    assert_eq!((y[0].to_f64(), y[1].to_f64()), (1.0, 2.0));
+
+    let specials = [0.0, -0.0, 1.0, f64::MAX, f64::MIN_POSITIVE, f64::from_bits(1), f64::INFINITY, f64::NAN];
+    for i in 0..200_000 {
+        let [a, b, c]: [f64; 3] = match i % 3 {
+            0 => core::array::from_fn(|_| f64::from_bits(rng.random())),
+            1 => {
+                // c close to -a * b, so the low half of the product is all that's left
+                let a = rng.random_range(-1.0..1.0) * 2f64.powi(rng.random_range(-600..600));
+                let b = rng.random::<f64>();
+                [a, b, -(a * b) * (1.0 + rng.random_range(-1e-15..1e-15))]
             }
+            _ => core::array::from_fn(|_| {
+                let special = specials[rng.random_range(0..specials.len())];
+                if rng.random() { -special } else { special }
+            }),
         };
+        let mut dst = [Float::new(0.0)];
+        fma_slices(&mut dst, &floats(&[a]), &floats(&[b]), &floats(&[c]));
+        let want = a.mul_add(b, c);
+        assert!(dst[0].to_bits() == want.to_bits() || want.is_nan() && dst[0].is_nan(), "fma({:e}, {:e}, {:e})", a, b, c);
+        let mut acc = floats(&[c]);
+        fma_accumulate(&mut acc, &floats(&[a]), &floats(&[b]));
+        assert!(acc[0].to_bits() == dst[0].to_bits() || want.is_nan() && acc[0].is_nan());
+        // and Context::fused_multiply_add in every mode, with its flags, against rounding the exact value
+        let mode = modes[i % modes.len()];
+        let (fa, fb, fc) = (Float::new(a), Float::new(b), Float::new(c));
+        let mut ctx = Context::new(mode);
+        let result = ctx.fused_multiply_add(&fa, &fb, &fc);
+        if [a, b, c].iter().all(|x| x.is_finite()) && a * b + c != 0.0 {
+            let mut expected_ctx = Context::new(mode);
+            let expected: F64 = (Exact::from(&fa) * &fb + &fc).round_to(&mut expected_ctx);
+            assert_eq!(result.to_bits(), expected.to_bits(), "fma({:e}, {:e}, {:e}) in {:?}", a, b, c, mode);
+            assert_eq!(ctx.flags, expected_ctx.flags, "fma({:e}, {:e}, {:e}) in {:?}", a, b, c, mode);
+        } else if mode == RoundingMode::TiesToEven {
+            assert!(result.to_bits() == want.to_bits() || want.is_nan() && result.is_nan());
+        }
+    }
+    // one rounding: (1 + 2^-52)(1 - 2^-52) - 1 is -2^-104, which rounding the product first loses
+    let mut dst = floats(&[-1.0, -1.0]);
+    fma_accumulate(&mut dst, &floats(&[1.0 + f64::EPSILON, 2.0]), &floats(&[1.0 - f64::EPSILON, 0.5]));
+    assert_eq!((dst[0].to_f64(), dst[1].to_bits()), (-(2f64.powi(-104)), 0));
+    let mut short = floats(&[0.0, 9.0]);
+    fma_slices(&mut short, &floats(&[1.0, 2.0]), &floats(&[3.0]), &floats(&[4.0, 5.0]));
+    assert_eq!((short[0].to_f64(), short[1].to_f64()), (7.0, 9.0));
+    println!("BLAS test passed!");
+}
 
-        if exponent >= 1024 { // overflow to infinity
-            return Float::infinity(sign);
+fn strided_test() {
+    // a column of a row-major matrix and one channel of an interleaved buffer, through the iterator forms of the
+    // BLAS and the sums, against copying them out to slices first. the elements that aren't in the view stay put
+    use floatfs::blas::{asum, axpy, fma_accumulate, fma_slices, nrm2, scal};
+    use floatfs::sum::{sum_exact, sum_kahan, sum_neumaier, sum_pairwise, sum_reproducible, Summation};
+    use rand::Rng;
+    let mut rng = rand::rng();
+    let bits = |x: &[Float]| x.iter().map(Float::to_bits).collect::<Vec<u64>>();
+    let same = |x: &[Float], y: &[Float]| bits(x) == bits(y);
+    for _ in 0..2_000 {
+        let (rows, columns) = (rng.random_range(0..20), rng.random_range(1..6));
+        let j = rng.random_range(0..columns);
+        let mut matrix: Vec<Float> = (0..rows * columns)
+            .map(|_| Float::new(rng.random_range(-1.0..1.0) * 2f64.powi(rng.random_range(-40..40))))
+            .collect();
+        let column = || -> Vec<usize> { (0..rows).map(|i| i * columns + j).collect() };
+        let copy = |m: &[Float]| column().iter().map(|&k| m[k].copy()).collect::<Vec<Float>>();
+        let x = copy(&matrix);
+
+        assert_eq!(nrm2(matrix.iter().skip(j).step_by(columns)).to_bits(), nrm2(&x).to_bits());
+        assert_eq!(asum(matrix.iter().skip(j).step_by(columns)).to_bits(), asum(&x).to_bits());
+        for summation in [Summation::Plain, Summation::Pairwise(4), Summation::Kahan, Summation::Exact] {
+            assert_eq!(summation.sum(matrix.iter().skip(j).step_by(columns)).to_bits(), summation.sum(&x).to_bits());
         }
+        let strided = || matrix.iter().skip(j).step_by(columns);
+        assert_eq!(sum_kahan(strided()).to_bits(), sum_kahan(&x).to_bits());
+        assert_eq!(sum_neumaier(strided()).to_bits(), sum_neumaier(&x).to_bits());
+        assert_eq!(sum_pairwise(strided(), 3).to_bits(), sum_pairwise(&x, 3).to_bits());
+        assert_eq!(sum_exact(strided()).to_bits(), sum_exact(&x).to_bits());
+        assert_eq!(sum_reproducible(strided()).to_bits(), sum_reproducible(&x).to_bits());
+
+        // y in the column, x and the factors from a buffer of (x, a, b) records
+        let records: Vec<Float> = (0..3 * rows).map(|_| Float::new(rng.random_range(-4.0..4.0))).collect();
+        let field = |k: usize| records.iter().skip(k).step_by(3);
+        let fields: Vec<Vec<Float>> = (0..3).map(|k| field(k).map(Float::copy).collect()).collect();
+        let (rx, ra, rb) = (&fields[0], &fields[1], &fields[2]);
+        let alpha = Float::new(rng.random_range(-2.0..2.0));
+        let before = matrix.iter().map(Float::copy).collect::<Vec<Float>>();
+        let mut y = x.iter().map(Float::copy).collect::<Vec<Float>>();
 
-        let mut shift = 52; // we want to shift right by 52 to get 53 bits (including implicit leading 1). another way to think of this is that when we multiplied the mantissas we did an implicit mult by 2^52.
+        axpy(&alpha, field(0), matrix.iter_mut().skip(j).step_by(columns));
+        axpy(&alpha, rx, &mut y);
+        assert!(same(&copy(&matrix), &y));
+        scal(&alpha, matrix.iter_mut().skip(j).step_by(columns));
+        scal(&alpha, &mut y);
+        assert!(same(&copy(&matrix), &y));
+        fma_accumulate(matrix.iter_mut().skip(j).step_by(columns), field(1), field(2));
+        fma_accumulate(&mut y, ra, rb);
+        assert!(same(&copy(&matrix), &y));
+        fma_slices(matrix.iter_mut().skip(j).step_by(columns), field(1), field(2), field(0));
+        fma_slices(&mut y, ra, rb, rx);
+        assert!(same(&copy(&matrix), &y));
 
-        if exponent <= -1023 {
-            // can we create a subnormal number?
-            if exponent < -1075 {
-                // min subnormal is 2^-52 * 2^-1022 = 2^-1074. we still allow exponent -1075 because we might round up to that value
-                // underflow to zero
-                return Float::from_bits((sign as u64) << 63); // zero
+        let outside = |m: &[Float]| {
+            bits(m).into_iter().enumerate().filter(|(k, _)| k % columns != j).collect::<Vec<_>>()
+        };
+        assert_eq!(outside(&matrix), outside(&before));
+    }
+    println!("Strided test passed!");
+}
+
+#[cfg(feature = "ndarray")]
+fn arrays_test() {
+    // element-wise operations against the same Context calls one at a time, flags included, on a transposed view
+    // as well as the array itself; sums of a whole array and along each axis against the sum module on the same
+    // elements copied out in order
+    use floatfs::arrays::{apply, sum, sum_axis};
+    use floatfs::sum::Summation;
+    use floatfs::{Context, Operation, RoundingMode};
+    use ndarray::{Array2, Array3, Axis};
+    use rand::Rng;
+    let mut rng = rand::rng();
+    let bits = |a: &[Float]| a.iter().map(Float::to_bits).collect::<Vec<u64>>();
+    for _ in 0..500 {
+        let (rows, columns) = (rng.random_range(0..8), rng.random_range(0..8));
+        let a = Array2::from_shape_fn((rows, columns), |_| Float::from_bits(rng.random()));
+        let b = Array2::from_shape_fn((rows, columns), |_| Float::from_bits(rng.random()));
+        for op in [Operation::Add, Operation::Subtract, Operation::Multiply, Operation::Divide] {
+            let mode = RoundingMode::TowardNegative;
+            let (mut ctx, mut expected_ctx) = (Context::new(mode), Context::new(mode));
+            let c = apply(op, &a.t(), &b.t(), &mut ctx).unwrap();
+            assert_eq!(c.shape(), [columns, rows]);
+            for ((x, y), z) in a.t().iter().zip(b.t().iter()).zip(c.iter()) {
+                let want = match op {
+                    Operation::Add => expected_ctx.add(x, y),
+                    Operation::Subtract => expected_ctx.subtract(x, y),
+                    Operation::Multiply => expected_ctx.multiply(x, y),
+                    Operation::Divide => expected_ctx.divide(x, y),
+                };
+                assert!(z.to_bits() == want.to_bits() || z.is_nan() && want.is_nan());
             }
-            shift += (-1023 + 1 - exponent) as u32; // correct by induction: if exponent is -1023, we want to shift by 1 extra since -1022 is the exponent this subnormal will be interpreted as having. if exponent is -1024 we want to shift by 2 extra, etc.
-            exponent = -1023; // mark as subnormal
+            assert_eq!(ctx.flags, expected_ctx.flags);
+        }
+        if rows != columns {
+            assert!(apply(Operation::Add, &a, &b.t(), &mut Context::default()).is_none());
         }
-        // from parts selects the lower 52 bits of the mantissa for us.
-        Float::from_parts(sign, exponent, shift_and_round(mantissa_full, shift) as u64)
-    }
 
-    fn add(&self, other: &Float) -> Float {
-        if let Some(nan) = self.nan_logic(other) {
-            return nan;
+        let x = Array3::from_shape_fn((rows, columns, 3), |_| Float::new(rng.random_range(-1.0..1.0)));
+        let copied: Vec<Float> = x.iter().map(Float::copy).collect();
+        for summation in [Summation::Plain, Summation::Pairwise(2), Summation::Neumaier, Summation::Exact] {
+            assert_eq!(sum(&x, summation).to_bits(), summation.sum(&copied).to_bits());
+            for axis in 0..3 {
+                let sums = sum_axis(&x, Axis(axis), summation);
+                let expected: Vec<Float> = x.lanes(Axis(axis)).into_iter().map(|lane| summation.sum(lane)).collect();
+                assert_eq!(bits(&sums.iter().map(Float::copy).collect::<Vec<Float>>()), bits(&expected));
+            }
         }
+    }
+    println!("Arrays test passed!");
+}
 
-        if self.is_zero() {
-            return other.copy();
+#[cfg(feature = "bytemuck")]
+fn bytemuck_test() {
+    // slices of every format cast to bytes as their bits in native order, and back, without copying
+    use floatfs::format::{Extended, Minifloat, Storage};
+    use floatfs::{Format, SoftFloat, F16, F32, F64};
+    fn check<F: Format>(values: &[f64])
+    where
+        F::Bits: bytemuck::Pod,
+    {
+        let floats: Vec<SoftFloat<F>> = values.iter().map(|v| v.to_string().parse().unwrap()).collect();
+        let bytes: &[u8] = bytemuck::cast_slice(&floats);
+        let width = core::mem::size_of::<F::Bits>();
+        assert_eq!(bytes.len(), floats.len() * width);
+        for (chunk, x) in bytes.chunks(width).zip(&floats) {
+            assert_eq!(chunk, &x.to_bits().to_u128().to_ne_bytes()[..width], "{}", F::NAME);
         }
-        if other.is_zero() {
-            return self.copy();
+        let back: &[SoftFloat<F>] = bytemuck::cast_slice(bytes);
+        assert_eq!(back, &floats[..]);
+        assert_eq!(bytemuck::cast_slice::<F::Bits, SoftFloat<F>>(bytemuck::cast_slice(&floats)), &floats[..]);
+    }
+    let values = [0.0, -0.0, 1.0, -2.5, 65504.0, 1e-5, f64::INFINITY, f64::NEG_INFINITY];
+    check::<Minifloat>(&values[..4]);
+    check::<floatfs::format::Binary16>(&values);
+    check::<floatfs::format::Binary32>(&values);
+    check::<floatfs::format::Binary64>(&values);
+    check::<Extended>(&values);
+    let zeros: [F32; 4] = bytemuck::Zeroable::zeroed();
+    assert!(zeros.iter().all(|z| z.is_zero() && !z.get_sign()));
+    assert_eq!(bytemuck::cast::<F64, u64>(F64::from_bits(0x3FF0_0000_0000_0000)), 0x3FF0_0000_0000_0000);
+    assert_eq!(bytemuck::cast::<u16, F16>(0x3C00), "1".parse::<F16>().unwrap());
+    println!("Bytemuck test passed!");
+}
+
+#[cfg(feature = "rkyv")]
+fn rkyv_test() {
+    // vectors of Float and of every SoftFloat format through an archive: read in place, bit for bit, checked
+    // access turning down a truncated buffer, and deserialized back
+    use floatfs::format::{Binary16, Binary32, Binary64, Extended, Minifloat};
+    use floatfs::{random, Format, SoftFloat};
+    use rand::RngCore;
+    use rkyv::rancor::Error;
+    let mut rng = rand::rng();
+    let mut bits = || rng.next_u64();
+
+    let floats: Vec<Float> = (0..1000).map(|_| Float::from_bits(bits())).collect();
+    let bytes = rkyv::to_bytes::<Error>(&floats).unwrap();
+    let archived = rkyv::access::<rkyv::Archived<Vec<Float>>, Error>(&bytes).unwrap();
+    assert!(archived.iter().zip(&floats).all(|(a, f)| a.get().to_bits() == f.to_bits()));
+    let back = rkyv::deserialize::<Vec<Float>, Error>(archived).unwrap();
+    assert!(back.iter().zip(&floats).all(|(b, f)| b.to_bits() == f.to_bits()) && back.len() == floats.len());
+    assert!(rkyv::access::<rkyv::Archived<Vec<Float>>, Error>(&bytes[..bytes.len() - 1]).is_err());
+
+    // each format read in place and deserialized back. a macro, since the bounds rkyv's generic entry points want
+    // are longer than the check
+    macro_rules! check {
+        ($format:ty) => {
+            let values: Vec<SoftFloat<$format>> = (0..1000).map(|_| random::special::<$format>(&mut bits)).collect();
+            let bytes = rkyv::to_bytes::<Error>(&values).unwrap();
+            let archived = rkyv::access::<rkyv::Archived<Vec<SoftFloat<$format>>>, Error>(&bytes).unwrap();
+            assert!(archived.iter().zip(&values).all(|(a, v)| a.get() == *v), "{}", <$format>::NAME);
+            let back = rkyv::deserialize::<Vec<SoftFloat<$format>>, Error>(archived).unwrap();
+            assert_eq!(back, values, "{}", <$format>::NAME);
+        };
+    }
+    check!(Minifloat);
+    check!(Binary16);
+    check!(Binary32);
+    check!(Binary64);
+    check!(Extended);
+    println!("Rkyv test passed!");
+}
+
+fn predicates_test() {
+    // orient2d, orient3d and incircle where the plain formulas get the sign wrong. orient2d on the example from
+    // Kettner et al, "Classroom examples of robustness problems": p near (0.5, 0.5) against (12, 12) and (24, 24),
+    // where the differences themselves round, and orient3d on the same points with one lifted to z = 1. incircle
+    // on lattice points of x^2 + y^2 = 5^12 (exactly cocircular, or with one nudged a unit off), moved far from
+    // the origin. the coordinates are integers times a power of two, so the exact signs come from i128. then
+    // random points against the plain formula, where it's clearly right.
+    use floatfs::predicates::{incircle, orient2d, orient3d};
+    use rand::Rng;
+    let mut rng = rand::rng();
+    let sign = |f: &Float| if f.is_zero() { 0 } else if f.get_sign() { -1 } else { 1 };
+    let cross = |a: &[i128; 2], b: &[i128; 2], c: &[i128; 2]| (a[0] - c[0]) * (b[1] - c[1]) - (a[1] - c[1]) * (b[0] - c[0]);
+    let naive_cross = |a: &[f64; 2], b: &[f64; 2], c: &[f64; 2]| (a[0] - c[0]) * (b[1] - c[1]) - (a[1] - c[1]) * (b[0] - c[0]);
+    let naive_sign = |x: f64| x.partial_cmp(&0.0).unwrap() as i32;
+
+    let mut naive_wrong = 0;
+    let unit = 2f64.powi(-53);
+    for i in 0..256 {
+        for j in 0..256 {
+            let scale = 2f64.powi(rng.random_range(-200..200));
+            let p = [0.5 + i as f64 * unit, 0.5 + j as f64 * unit];
+            let (q, r) = ([12.0, 12.0], [24.0, 24.0]);
+            let fp = |v: &[f64; 2]| [Float::new(v[0] * scale), Float::new(v[1] * scale)];
+            let ip = |v: &[f64; 2]| [(v[0] / unit) as i128, (v[1] / unit) as i128];
+            let exact = cross(&ip(&p), &ip(&q), &ip(&r)).signum() as i32;
+            assert_eq!(sign(&orient2d(&fp(&p), &fp(&q), &fp(&r))), exact, "{} {}", i, j);
+            assert_eq!(sign(&orient2d(&fp(&q), &fp(&r), &fp(&p))), exact, "{} {}", i, j);
+            assert_eq!(sign(&orient2d(&fp(&r), &fp(&q), &fp(&p))), -exact, "{} {}", i, j);
+            naive_wrong += (naive_sign(naive_cross(&p, &q, &r)) != exact) as u32;
+
+            // with d straight above p, orient3d(p, q, r, d) is -orient2d(p, q, r)
+            let [px, py] = fp(&p);
+            let lift = |v: &[f64; 2]| [Float::new(v[0] * scale), Float::new(v[1] * scale), Float::new(0.0)];
+            let d = [px.copy(), py.copy(), Float::new(scale)];
+            assert_eq!(sign(&orient3d(&lift(&p), &lift(&q), &lift(&r), &d)), -exact, "{} {}", i, j);
         }
-        if self.is_infinity() {
-            if other.is_infinity() {
-                if self.get_sign() != other.get_sign() {
-                    return Float::nan(); // infinity + -infinity = nan
-                }
-            }
-            return self.copy();
+    }
+    assert!(naive_wrong > 1000, "the plain orient2d was wrong only {} times", naive_wrong);
+
+    // the lattice points of x^2 + y^2 = 5^12 come from (2 + i)^k (2 - i)^(12 - k) and its conjugates and rotations
+    let gaussian = |a: (i64, i64), b: (i64, i64)| (a.0 * b.0 - a.1 * b.1, a.0 * b.1 + a.1 * b.0);
+    let mut circle = vec![];
+    for k in 0..=12 {
+        let z = (0..12).fold((1, 0), |z, n| gaussian(z, if n < k { (2, 1) } else { (2, -1) }));
+        circle.extend([(z.0, z.1), (-z.1, z.0), (-z.0, -z.1), (z.1, -z.0)]);
+    }
+    let (mut naive_wrong, mut cocircular) = (0, 0);
+    for _ in 0..20_000 {
+        let scale = 2f64.powi(rng.random_range(-100..100));
+        let center = [rng.random_range(-1i64 << 50..1 << 50), rng.random_range(-1i64 << 50..1 << 50)];
+        let mut points: Vec<[i64; 2]> = (0..4)
+            .map(|_| circle[rng.random_range(0..circle.len())])
+            .map(|(x, y)| [center[0] + x, center[1] + y])
+            .collect();
+        if rng.random() {
+            points[3][rng.random_range(0..2)] += if rng.random() { 1 } else { -1 };
         }
-        if other.is_infinity() {
-            return other.copy();
+        let [a, b, c] = [0, 1, 2].map(|n| [(points[n][0] - points[3][0]) as i128, (points[n][1] - points[3][1]) as i128]);
+        let lift = |p: &[i128; 2]| p[0] * p[0] + p[1] * p[1];
+        let o = [0, 0];
+        let exact = (lift(&a) * cross(&b, &c, &o) + lift(&b) * cross(&c, &a, &o) + lift(&c) * cross(&a, &b, &o)).signum() as i32;
+        let f = |p: &[i64; 2]| [Float::new(p[0] as f64 * scale), Float::new(p[1] as f64 * scale)];
+        let [fa, fb, fc, fd] = [0, 1, 2, 3].map(|n| f(&points[n]));
+        assert_eq!(sign(&incircle(&fa, &fb, &fc, &fd)), exact, "{:?}", points);
+        assert_eq!(sign(&incircle(&fb, &fa, &fc, &fd)), -exact, "{:?}", points);
+
+        let g = |n: usize| [(points[n][0] - points[3][0]) as f64, (points[n][1] - points[3][1]) as f64];
+        let glift = |p: &[f64; 2]| p[0] * p[0] + p[1] * p[1];
+        let (ga, gb, gc) = (g(0), g(1), g(2));
+        let naive = glift(&ga) * naive_cross(&gb, &gc, &[0.0, 0.0]) + glift(&gb) * naive_cross(&gc, &ga, &[0.0, 0.0])
+            + glift(&gc) * naive_cross(&ga, &gb, &[0.0, 0.0]);
+        naive_wrong += (naive_sign(naive) != exact) as u32;
+        cocircular += (exact == 0) as u32;
+    }
+    assert!(naive_wrong > cocircular / 10, "the plain incircle was wrong only {} times", naive_wrong);
+
+    for _ in 0..20_000 {
+        let p: Vec<[f64; 2]> = (0..3).map(|_| [rng.random_range(-1.0..1.0), rng.random_range(-1.0..1.0)]).collect();
+        let plain = (p[0][0] - p[2][0]) * (p[1][1] - p[2][1]) - (p[0][1] - p[2][1]) * (p[1][0] - p[2][0]);
+        let f = |q: &[f64; 2]| [Float::new(q[0]), Float::new(q[1])];
+        let robust = orient2d(&f(&p[0]), &f(&p[1]), &f(&p[2])).to_f64();
+        if plain.abs() > 1e-10 {
+            assert!((robust - plain).abs() <= 1e-15, "{:?}: {:e} {:e}", p, robust, plain);
         }
-        
-        // both are finite and non-zero
+    }
+    println!("Predicates test passed!");
+}
 
-        let (mut a, mut b) = if self.get_exponent() > other.get_exponent() {
-            (self.copy(), other.copy())
-        } else {
-            (other.copy(), self.copy())
-        }; // a has the larger exponent
-        let mut exp_a = a.get_exponent();
-        let mut exp_b = b.get_exponent();
+fn polynomial_test() {
+    // horner and horner_compensated: exact small cases, agreement on well-conditioned polynomials, and (x - 2)^7
+    // expanded, which cancels badly near 2: compensated Horner has to stay close to (x - 2)^7 computed directly,
+    // where plain Horner loses most of its digits.
+    use floatfs::polynomial::{horner, horner_compensated};
+    use rand::Rng;
+    let mut rng = rand::rng();
+    let floats = |values: &[f64]| values.iter().map(|&v| Float::new(v)).collect::<Vec<Float>>();
+    let (c, two) = (floats(&[1.0, 2.0, 3.0]), Float::new(2.0));
+    assert_eq!(horner(&c, &two).to_f64(), 17.0);
+    assert_eq!(horner_compensated(&c, &two).to_f64(), 17.0);
+    assert_eq!(horner(&[], &two).to_bits(), 0);
+    assert_eq!(horner_compensated(&[], &two).to_bits(), 0);
+    assert!(horner_compensated(&c, &Float::new(f64::NAN)).is_nan());
+    assert_eq!(horner_compensated(&c, &Float::new(1e300)).to_f64(), f64::INFINITY);
 
-        let sign = a.get_sign(); // sign of the result is the sign of the larger exponent
-        let mut mantissa_a = a.get_full_mantissa(&mut exp_a);
-        let mut mantissa_b = b.get_full_mantissa(&mut exp_b);
+    for _ in 0..10_000 {
+        let c: Vec<f64> = (0..rng.random_range(1..12)).map(|_| rng.random_range(0.0..10.0)).collect();
+        let x = Float::new(rng.random_range(0.0..2.0));
+        let (plain, compensated) = (horner(&floats(&c), &x), horner_compensated(&floats(&c), &x));
+        assert!(plain.ulp_distance(&compensated) <= 2 * c.len() as u64, "{:?} at {:e}", c, x.to_f64());
+    }
 
-        let exp_diff = (exp_a - exp_b) as u32;
+    // (x - 2)^7 = x^7 - 14 x^6 + 84 x^5 - 280 x^4 + 560 x^3 - 672 x^2 + 448 x - 128
+    let c = floats(&[-128.0, 448.0, -672.0, 560.0, -280.0, 84.0, -14.0, 1.0]);
+    let (mut worst_plain, mut worst_compensated) = (0f64, 0f64);
+    for _ in 0..10_000 {
+        let d: f64 = rng.random_range(0.005..0.05) * if rng.random() { 1.0 } else { -1.0 };
+        let x = 2.0 + d;
+        let exact = (x - 2.0).powi(7); // x - 2 is exact, so this is within a few ulps
+        let error = |p: Float| ((p.to_f64() - exact) / exact).abs();
+        worst_plain = worst_plain.max(error(horner(&c, &Float::new(x))));
+        worst_compensated = worst_compensated.max(error(horner_compensated(&c, &Float::new(x))));
+    }
+    assert!(worst_compensated < 1e-9, "{:e}", worst_compensated);
+    println!("(x - 2)^7 near 2: relative error {:.1e} plain, {:.1e} compensated", worst_plain, worst_compensated);
+    println!("Polynomial test passed!");
+}
 
-        // todo: think about signs and rounding.
+fn accuracy_test() {
+    // Accuracy::Faithful against the correctly rounded results: in every rounding mode the faithful result has to
+    // be the correctly rounded one rounded down or up, which are the two values either side of the exact result.
+    // exact results stay exact, and the speedup over the default gets printed.
+    use floatfs::format::{Binary32, Binary64, Extended};
+    use floatfs::{Accuracy, Context, Flags, Format, RoundingMode, SoftFloat, F64};
+    use rand::Rng;
+    use std::time::Instant;
+    type Unary<F> = fn(&SoftFloat<F>, &mut Context) -> SoftFloat<F>;
+    let modes = [
+        RoundingMode::TiesToEven,
+        RoundingMode::TiesToAway,
+        RoundingMode::TowardZero,
+        RoundingMode::TowardPositive,
+        RoundingMode::TowardNegative,
+    ];
+    let faithful = |mode| Context { accuracy: Accuracy::Faithful, ..Context::new(mode) };
+    fn functions<F: Format>() -> [(&'static str, Unary<F>, f64, f64); 21] {
+        [
+            ("exp", SoftFloat::exp, -700.0, 700.0),
+            ("exp2", SoftFloat::exp2, -1000.0, 1000.0),
+            ("exp10", SoftFloat::exp10, -300.0, 300.0),
+            ("expm1", SoftFloat::expm1, -2.0, 2.0),
+            ("ln", SoftFloat::ln, 0.0, 1e300),
+            ("log1p", SoftFloat::log1p, -0.9, 2.0),
+            ("log2", SoftFloat::log2, 0.0, 4.0),
+            ("log10", SoftFloat::log10, 0.0, 1e30),
+            ("sin", SoftFloat::sin, -1e6, 1e6),
+            ("cos", SoftFloat::cos, -10.0, 10.0),
+            ("tan", SoftFloat::tan, -1e20, 1e20),
+            ("sinpi", SoftFloat::sinpi, -4.0, 4.0),
+            ("cospi", SoftFloat::cospi, -4.0, 4.0),
+            ("asin", SoftFloat::asin, -1.0, 1.0),
+            ("acos", SoftFloat::acos, -1.0, 1.0),
+            ("atan", SoftFloat::atan, -1e10, 1e10),
+            ("sinh", SoftFloat::sinh, -20.0, 20.0),
+            ("cosh", SoftFloat::cosh, -20.0, 20.0),
+            ("tanh", SoftFloat::tanh, -5.0, 5.0),
+            ("asinh", SoftFloat::asinh, -1e6, 1e6),
+            ("atanh", SoftFloat::atanh, -1.0, 1.0),
+        ]
+    }
+    fn check<F: Format>(name: &str, f: impl Fn(&mut Context) -> SoftFloat<F>, mode: RoundingMode, faithful: &mut Context) {
+        let result = f(faithful);
+        let down = f(&mut Context::new(RoundingMode::TowardNegative));
+        let up = f(&mut Context::new(RoundingMode::TowardPositive));
+        let same = |a: &SoftFloat<F>, b: &SoftFloat<F>| a.to_bits() == b.to_bits() || (a.is_nan() && b.is_nan());
+        assert!(same(&result, &down) || same(&result, &up), "{} {:?}", name, mode);
+    }
+    fn run<F: Format>(rng: &mut impl Rng, modes: &[RoundingMode], faithful: impl Fn(RoundingMode) -> Context) {
+        let f64_to = |x: f64| F64::from_bits(x.to_bits()).convert::<F>(&mut Context::default());
+        for _ in 0..2_000 {
+            let mode = modes[rng.random_range(0..modes.len())];
+            for (name, f, low, high) in functions::<F>() {
+                // uniform across the range half the time, otherwise a random magnitude from the bottom of it
+                let x = if rng.random() { rng.random_range(low..high) } else { high.min(-low.max(-high)) * 2f64.powi(-rng.random_range(0..60)) };
+                let x = if low < 0.0 && rng.random() { -x } else { x };
+                let x = f64_to(x);
+                check(name, |ctx: &mut Context| f(&x, ctx), mode, &mut faithful(mode));
+            }
+            let (x, y) = (f64_to(rng.random_range(0.0..100.0)), f64_to(rng.random_range(-150.0..150.0)));
+            check("powf", |ctx: &mut Context| x.powf(&y, ctx), mode, &mut faithful(mode));
+            let n = rng.random_range(-300..300);
+            check("powi_exact", |ctx: &mut Context| x.powi_exact(n, ctx), mode, &mut faithful(mode));
+            let y = f64_to(rng.random_range(-100.0..100.0));
+            check("atan2", |ctx: &mut Context| x.atan2(&y, ctx), mode, &mut faithful(mode));
+        }
+    }
+    let mut rng = rand::rng();
+    run::<Binary32>(&mut rng, &modes, faithful);
+    run::<Binary64>(&mut rng, &modes, faithful);
+    run::<Extended>(&mut rng, &modes, faithful);
 
-        let shifted_out = mantissa_b & ((1 << exp_diff) - 1); // for rounding
+    // the exact cases don't depend on the series
+    let f = |x: f64| F64::from_bits(x.to_bits());
+    for (result, expected) in [
+        (f(10.0).exp2(&mut faithful(RoundingMode::TiesToEven)), 1024f64),
+        (f(5.0).exp10(&mut faithful(RoundingMode::TiesToEven)), 100000.0),
+        (f(0.125).log2(&mut faithful(RoundingMode::TiesToEven)), -3.0),
+        (f(1000.0).log10(&mut faithful(RoundingMode::TiesToEven)), 3.0),
+        (f(1.0).ln(&mut faithful(RoundingMode::TiesToEven)), 0.0),
+        (f(9.0).powf(&f(1.5), &mut faithful(RoundingMode::TiesToEven)), 27.0),
+        (f(-2.5).sinpi(&mut faithful(RoundingMode::TiesToEven)), -1.0),
+    ] {
+        assert_eq!(result.to_bits(), expected.to_bits(), "{:e}", expected);
+    }
+    let mut ctx = faithful(RoundingMode::TiesToEven);
+    f(3.0).exp2(&mut ctx);
+    assert_eq!(ctx.flags, Flags::empty());
 
-        mantissa_b = if exp_diff >= 64 { // we could choose a smaller number such as 54 here since each mantissa is at most 53 bits.
-            0
-        } else {
-            mantissa_b >> exp_diff
+    let inputs: Vec<F64> = (0..20_000).map(|_| f(rng.random_range(-20.0..20.0))).collect();
+    for (name, function) in [("exp", F64::exp as fn(&F64, &mut Context) -> F64), ("sin", F64::sin), ("atan", F64::atan)] {
+        let time = |mut ctx: Context| {
+            let start = Instant::now();
+            for x in &inputs {
+                std::hint::black_box(function(x, &mut ctx));
+            }
+            start.elapsed()
         };
+        let (full, fast) = (time(Context::new(RoundingMode::TiesToEven)), time(faithful(RoundingMode::TiesToEven)));
+        println!("binary64 {}: {:.2}x faster when faithful", name, full.as_secs_f64() / fast.as_secs_f64());
+    }
+    println!("Accuracy test passed!");
+}
 
-        let mantissa = mantissa_a + mantissa_b; // 53 + 53 = 54 bits
-
-        // Float::from_parts(sign, exponent, mantissa_a + mantissa_b)
-        return Float::nan(); // todo
-    }
-
-            // if exp_diff != 0 {
-        //     if exp_diff > 53 { // each mantissa is at most 53 bits.
-        //         // mantissa_b will be shifted out completely
-        //         mantissa_b = 0; // todo: think about rounding
-        //     } else {
-        //         // shift right with jamming
-                // if shifted_out != 0 {
-        //     mantissa_b |= 1; // jam bit
-        // }
-            // }
-        // }
-
-    // fn divide(&self, other: &Float) -> Float {
-    //     if let Some(nan) = self.nan_logic(other) {
-    //         return nan;
-    //     }
-    //     // division by zero and zero divided by zero both yield NaN
-    //     if other.is_zero() {
-    //         return Float::nan();
-    //     }
-        
-    //     let sign = self.get_sign() ^ other.get_sign(); // same sign means pos, else neg
-        
-    //     if self.is_zero() {
-    //         return Float::from_bits((sign as u64) << 63); // zero
-    //     }
-    //     if self.is_infinity() {
-    //         if other.is_infinity() {
-    //             return Float::nan(); // infinity / infinity = nan
-    //         }
-    //         return Float::infinity(sign); // infinity / finite = infinity
-    //     }
-    //     if other.is_infinity() {
-    //         return Float::from_bits((sign as u64) << 63); // finite / infinity = 0
-    //     }
-
-    //     let mut exponent = self.get_exponent() - other.get_exponent();
-    //     let mut mantissa_full = {
-    //         // mutable because closure borrows exponent mutably
-    //         let mut get_full_mantissa = |f: &Float| -> u64 {
-    //             // branchless version. should profile to see if this is actually faster.
-    //             let is_normal = (((f.bits >> 52) & ((1 << 11) - 1)) != 0) as u64; // exponent bits non-zero
-    //             exponent += 1 - is_normal as i16; // adjust exponent for subnormal (interpreted as -1022)
-    //             f.get_mantissa() | (is_normal << 52) // implicit leading 1
-    //         };
-    //         (u128::from(get_full_mantissa(self)) << 52) / u128::from(get_full_mantissa(other))
-    //         // shift by 52 to keep precision.
-    //     };
-    //     println!("Mantissa full: {:0106b}", mantissa_full);
-    //     // if-else block normalizes mantissa_full so that the 105th bit is set.
-
-    //     // todo: think about rounding.
-        
-    //     return Float::from_parts(sign, exponent, mantissa_full as u64); // todo
-    // }
-
-    fn print_bits(&self) {
-        println!("{:064b}", self.bits);
-    }
-
-    fn print_parts(&self) {
-        println!(
-            "Sign: {}, Exponent: {}, Mantissa: {:052b}",
-            self.get_sign(),
-            self.get_exponent(),
-            self.get_mantissa()
-        );
+fn smt_export(op: floatfs::smtlib::Operation, mode: floatfs::RoundingMode) {
+    // writes the exhaustive minifloat query for one operation; check it with `z3 <file>` (unsat = all match)
+    use floatfs::format::Minifloat;
+    let path = format!("minifloat_{:?}_{:?}.smt2", op, mode).to_lowercase();
+    std::fs::write(&path, floatfs::smtlib::exhaustive_check::<Minifloat>(op, mode)).unwrap();
+    println!("wrote {}", path);
+}
+
+#[cfg(target_arch = "x86_64")]
+fn x86_hardware_test() {
+    // runs random operands through the sse unit under random MXCSR settings and compares results and status
+    // bits against floatfs::x86. only builds on x86_64.
+    use floatfs::x86::{self, Mxcsr};
+    use floatfs::{Context, F32, F64};
+    use rand::Rng;
+
+    macro_rules! hardware {
+        ($insn:literal, $suffix:literal, $float:ty, $mxcsr:expr, $a:expr, $b:expr) => {{
+            let mut result = <$float>::from_bits($a);
+            let (mxcsr, mut status) = ($mxcsr, 0u32);
+            unsafe {
+                std::arch::asm!(
+                    "ldmxcsr [{mxcsr}]",
+                    concat!($insn, $suffix, " {r}, {b}"),
+                    "stmxcsr [{status}]",
+                    mxcsr = in(reg) &mxcsr,
+                    status = in(reg) &mut status,
+                    r = inout(xmm_reg) result,
+                    b = in(xmm_reg) <$float>::from_bits($b),
+                );
+            }
+            (result.to_bits(), status & 0x3F)
+        }};
+    }
+
+    // operands skewed toward subnormals, zeros, and infinities and nans
+    macro_rules! operand {
+        ($rng:expr, $bits:ty, $exponent_mask:expr, $tiny_mask:expr) => {{
+            let bits: $bits = $rng.random();
+            match $rng.random_range(0..5) {
+                0 => bits & $tiny_mask,
+                1 => bits & !(<$bits>::MAX >> 1),
+                2 => bits | $exponent_mask,
+                _ => bits,
+            }
+        }};
+    }
+
+    macro_rules! check {
+        ($float:ty, $soft:ty, $bits:ty, $suffix:literal, $exponent_mask:expr, $tiny_mask:expr, $rng:expr, $mxcsr:expr) => {{
+            let mxcsr = Mxcsr($mxcsr);
+            let (a, b) = (operand!($rng, $bits, $exponent_mask, $tiny_mask), operand!($rng, $bits, $exponent_mask, $tiny_mask));
+            let (x, y) = (<$soft>::from_bits(a), <$soft>::from_bits(b));
+            let ops: [(&str, fn(&$soft, &$soft, &mut Context) -> $soft, ($bits, u32)); 6] = [
+                ("add", |x, y, ctx| x.add(y, ctx), hardware!("add", $suffix, $float, $mxcsr, a, b)),
+                ("sub", |x, y, ctx| x.subtract(y, ctx), hardware!("sub", $suffix, $float, $mxcsr, a, b)),
+                ("mul", |x, y, ctx| x.multiply(y, ctx), hardware!("mul", $suffix, $float, $mxcsr, a, b)),
+                ("div", |x, y, ctx| x.divide(y, ctx), hardware!("div", $suffix, $float, $mxcsr, a, b)),
+                ("min", |x, y, ctx| x86::min(x, y, ctx), hardware!("min", $suffix, $float, $mxcsr, a, b)),
+                ("max", |x, y, ctx| x86::max(x, y, ctx), hardware!("max", $suffix, $float, $mxcsr, a, b)),
+            ];
+            for (name, op, expected) in ops {
+                let mut ctx = mxcsr.context();
+                let result = op(&x, &y, &mut ctx);
+                let actual = (result.to_bits(), mxcsr.status(ctx.flags, &[x, y]));
+                assert_eq!(actual, expected, "{}{} {:x} {:x} mxcsr {:04x}", name, $suffix, a, b, $mxcsr);
+            }
+        }};
+    }
+
+    let mut rng = rand::rng();
+    for _ in 0..1_000_000 {
+        let mxcsr = Mxcsr::RESET | (rng.random::<u32>() & (Mxcsr::DAZ | Mxcsr::FTZ | 3 << Mxcsr::RC_SHIFT));
+        check!(f32, F32, u32, "ss", 0x7F80_0000, 0x80FF_FFFF, rng, mxcsr);
+        check!(f64, F64, u64, "sd", 0x7FF0_0000_0000_0000, 0x801F_FFFF_FFFF_FFFF, rng, mxcsr);
     }
+    println!("x86 hardware test passed!");
 }
 
-fn mult_check_print(a: Float, b: Float, print: bool) {
-    let result = a.multiply(&b);
-    let expected = a.to_f64() * b.to_f64();
-    let actual = result.to_f64();
+#[cfg(target_arch = "x86_64")]
+fn x87_hardware_test() {
+    // runs random operands through the x87 under random precision and rounding control, both as binary64
+    // load/op/store (where the double rounding shows up) and on 80-bit values, and compares against
+    // floatfs::x87. only builds on x86_64. DE is left out: it isn't a flag floatfs has.
+    use floatfs::x87::ControlWord;
+    use floatfs::{Context, Flags, F64, F80};
+    use rand::Rng;
 
-    if print {
-        a.print_parts();
-        b.print_parts();
-        result.print_parts();
+    // fninit leaves every exception masked and the stack empty, so each run starts from scratch
+    macro_rules! hardware {
+        ($insn:literal, $size:literal, $cw:expr, $a:expr, $b:expr, $result:expr) => {{
+            let mut status = 0u16;
+            unsafe {
+                std::arch::asm!(
+                    "fninit",
+                    "fldcw [{cw}]",
+                    concat!("fld ", $size, " ptr [{b}]"),
+                    concat!("fld ", $size, " ptr [{a}]"),
+                    concat!($insn, " st, st(1)"),
+                    concat!("fstp ", $size, " ptr [{result}]"),
+                    "fstp st(0)",
+                    "fnstsw [{status}]",
+                    "fninit",
+                    cw = in(reg) &$cw,
+                    a = in(reg) &$a,
+                    b = in(reg) &$b,
+                    result = in(reg) &mut $result,
+                    status = in(reg) &mut status,
+                    out("st(0)") _, out("st(1)") _, out("st(2)") _, out("st(3)") _,
+                    out("st(4)") _, out("st(5)") _, out("st(6)") _, out("st(7)") _,
+                );
+            }
+            status & 0x3D
+        }};
     }
 
-    if expected.to_bits() != actual.to_bits() {
-        println!("Mismatch!");
-        println!("x: {}, y: {}", a.to_f64(), b.to_f64());
-        println!("expected: {:e}, actual: {:e}", expected, actual);
-        a.print_parts();
-        b.print_parts();
-        result.print_parts();
-        Float::new(expected).print_parts();
-        panic!("Test failed");
-    } else if print {
-        println!("Match!");
-        println!("x: {}, y: {}", a.to_f64(), b.to_f64());
-        println!("expected: {:e}, actual: {:e}", expected, actual);
+    fn status(flags: Flags) -> u16 {
+        let bits = [(Flags::INVALID, 1), (Flags::DIVIDE_BY_ZERO, 4), (Flags::OVERFLOW, 8), (Flags::UNDERFLOW, 16), (Flags::INEXACT, 32)];
+        bits.iter().filter(|(flag, _)| flags.contains(*flag)).fold(0, |status, (_, bit)| status | bit)
+    }
+
+    // binary64 operands skewed toward subnormals, zeros, nans and exponents whose products leave the range
+    fn binary64(rng: &mut impl Rng) -> u64 {
+        let bits: u64 = rng.random();
+        match rng.random_range(0..5) {
+            0 => bits & 0x801F_FFFF_FFFF_FFFF,
+            1 => bits & (1 << 63),
+            2 => bits | 0x7FF0_0000_0000_0000,
+            _ => bits,
+        }
+    }
+
+    type Op = fn(&ControlWord, &F80, &F80, &mut Context) -> F80;
+    let ops: [(&str, Op); 4] = [
+        ("fadd", ControlWord::add),
+        ("fsub", ControlWord::subtract),
+        ("fmul", ControlWord::multiply),
+        ("fdiv", ControlWord::divide),
+    ];
+    let mut rng = rand::rng();
+    for _ in 0..1_000_000 {
+        let cw = ControlWord(ControlWord::RESET & !(0xF << ControlWord::PC_SHIFT) | rng.random_range(0..16) << ControlWord::PC_SHIFT);
+        let (a, b) = (binary64(&mut rng), binary64(&mut rng));
+        let (a80, b80) = (F80::from_bits(rng.random::<u128>() >> 49), F80::from_bits(rng.random::<u128>() >> 49));
+        let (a80_bytes, b80_bytes) = (a80.to_x87_bytes(), b80.to_x87_bytes());
+        for (i, (name, op)) in ops.iter().enumerate() {
+            let mut result = 0u64;
+            let mut result80 = [0u8; 10];
+            let (hardware, hardware80) = match i {
+                0 => (hardware!("fadd", "qword", cw.0, a, b, result), hardware!("fadd", "tbyte", cw.0, a80_bytes, b80_bytes, result80)),
+                1 => (hardware!("fsub", "qword", cw.0, a, b, result), hardware!("fsub", "tbyte", cw.0, a80_bytes, b80_bytes, result80)),
+                2 => (hardware!("fmul", "qword", cw.0, a, b, result), hardware!("fmul", "tbyte", cw.0, a80_bytes, b80_bytes, result80)),
+                _ => (hardware!("fdiv", "qword", cw.0, a, b, result), hardware!("fdiv", "tbyte", cw.0, a80_bytes, b80_bytes, result80)),
+            };
+
+            let mut ctx = cw.context();
+            let (x, y) = (ControlWord::load(&F64::from_bits(a), &mut ctx), ControlWord::load(&F64::from_bits(b), &mut ctx));
+            let stored = ControlWord::store(&op(&cw, &x, &y, &mut ctx), &mut ctx);
+            assert_eq!((stored.to_bits(), status(ctx.flags)), (result, hardware), "{} {:x} {:x} cw {:04x}", name, a, b, cw.0);
+
+            let mut ctx = cw.context();
+            let wide = op(&cw, &a80, &b80, &mut ctx);
+            assert_eq!(
+                (wide.to_bits(), status(ctx.flags)),
+                (F80::from_x87_bytes(result80).to_bits(), hardware80),
+                "{} {:x} {:x} cw {:04x}",
+                name,
+                a80.to_bits(),
+                b80.to_bits(),
+                cw.0
+            );
+        }
     }
+    println!("x87 hardware test passed!");
 }
 
-fn mult_stress_test() {
+#[cfg(target_arch = "aarch64")]
+fn arm_hardware_test() {
+    // runs random single-precision operands through the real fpu under random FPCR settings and compares
+    // results and FPSR against floatfs::arm. only builds on aarch64.
+    use floatfs::arm::{fpsr, Fpcr};
+    use floatfs::format::{Binary16, Binary32};
+    use floatfs::F32;
     use rand::Rng;
+
+    macro_rules! hardware {
+        ($insn:literal, $fpcr:expr, $a:expr, $b:expr) => {{
+            let (result, status): (f32, u64);
+            unsafe {
+                std::arch::asm!(
+                    "mrs {saved}, fpcr",
+                    "msr fpcr, {fpcr}",
+                    "msr fpsr, xzr",
+                    concat!($insn, " {r:s}, {a:s}, {b:s}"),
+                    "mrs {status}, fpsr",
+                    "msr fpcr, {saved}",
+                    saved = out(reg) _,
+                    fpcr = in(reg) $fpcr as u64,
+                    status = out(reg) status,
+                    r = out(vreg) result,
+                    a = in(vreg) f32::from_bits($a),
+                    b = in(vreg) f32::from_bits($b),
+                );
+            }
+            (result.to_bits(), status as u32 & 0x9F)
+        }};
+    }
+
+    fn to_half_hardware(fpcr: u32, a: u32) -> (u16, u32) {
+        let (half, status): (u32, u64);
+        unsafe {
+            std::arch::asm!(
+                "mrs {saved}, fpcr",
+                "msr fpcr, {fpcr}",
+                "msr fpsr, xzr",
+                "fcvt {h:h}, {a:s}",
+                "umov {half:w}, {h:v}.h[0]",
+                "mrs {status}, fpsr",
+                "msr fpcr, {saved}",
+                saved = out(reg) _,
+                fpcr = in(reg) fpcr as u64,
+                status = out(reg) status,
+                h = out(vreg) _,
+                half = out(reg) half,
+                a = in(vreg) f32::from_bits(a),
+            );
+        }
+        (half as u16, status as u32 & 0x9F)
+    }
+
+    // operands skewed toward the interesting places: subnormals, nans, and the edges of the half range
+    fn operand(rng: &mut impl Rng) -> u32 {
+        let bits: u32 = rng.random();
+        match rng.random_range(0..4) {
+            0 => bits & 0x80FF_FFFF,                 // tiny, often subnormal
+            1 => bits | 0x7F80_0000,                 // infinities and nans
+            2 => (bits & 0x807F_FFFF) | 0x4780_0000, // around 2^16
+            _ => bits,
+        }
+    }
+
     let mut rng = rand::rng();
     for _ in 0..10_000_000 {
-        let fx = Float::from_bits(rng.random());
-        let fy = Float::from_bits(rng.random());
-        mult_check_print(fx, fy, false);
+        let fpcr = rng.random::<u32>() & (Fpcr::AHP | Fpcr::DN | Fpcr::FZ | 3 << Fpcr::RMODE_SHIFT);
+        let fpcr_model = Fpcr(fpcr);
+        let (a, b) = (operand(&mut rng), operand(&mut rng));
+        let (x, y) = (F32::from_bits(a), F32::from_bits(b));
+
+        let mut ctx = fpcr_model.context::<Binary32>();
+        let product = x.multiply(&y, &mut ctx).to_bits();
+        assert_eq!(hardware!("fmul", fpcr, a, b), (product, fpsr(ctx.flags)), "fmul {:08x} {:08x} fpcr {:08x}", a, b, fpcr);
+
+        let mut ctx = fpcr_model.context::<Binary32>();
+        let sum = x.add(&y, &mut ctx).to_bits();
+        assert_eq!(hardware!("fadd", fpcr, a, b), (sum, fpsr(ctx.flags)), "fadd {:08x} {:08x} fpcr {:08x}", a, b, fpcr);
+
+        let mut ctx = fpcr_model.convert_context::<Binary32, Binary16>();
+        let half = fpcr_model.to_half(&x, &mut ctx);
+        assert_eq!(to_half_hardware(fpcr, a), (half, fpsr(ctx.flags)), "fcvt {:08x} fpcr {:08x}", a, fpcr);
     }
-    println!("Stress test passed!");
+    println!("ARM hardware test passed!");
 }
 
 fn main() {
     let a = Float::new(1.1);
     // let a = Float::new(-1.02735137937997933477e+00);
     println!("{:?}", a.to_f64());
-    a.print_parts();
+    println!("{}", a.parts());
     a.print_bits();
     let b = Float::new(1.1);
     // let b = Float::new(-1.02735137937997933477e+00);
     println!("{:?}", b.to_f64());
-    b.print_parts();
+    println!("{}", b.parts());
     b.print_bits();
 
     let c = a.multiply(&b);
     println!("{:?}", c.to_f64());
-    c.print_parts();
+    println!("{}", c.parts());
     c.print_bits();
 
-    // b.print_parts();
-    // c.print_parts();
+    println!("{}", a.multiply_explained(&b));
+    c.print_layout();
+
+    // println!("{}", b.parts());
+    // println!("{}", c.parts());
     // println!("{:?}", c.to_f64());
 
     // let expected = a.to_f64() * b.to_f64();
     // println!("Expected: {:?}", expected);
-    // Float::new(expected).print_parts();
+    // println!("{}", Float::new(expected).parts());
 
     // mult_stress_test();
+
     mult_benchmark();
+    // add_benchmark();
     // mult_tie_test();
+    // mult_explained_test();
+    // add_explained_test();
+    // wide_gap_add_test();
+    // overflow_test();
+    // zero_sign_test();
+    // underflow_test();
+    // traced_test();
+    // lza_test();
+    // fields_test();
+    // from_parts_test();
+    // sort_test();
+    // compare_test();
+    // limbs_test();
+    // exact_test();
+    // add_stress_test();
+    // div_stress_test();
+    // panic_free_test();
+    // double_rounding_test();
+    // math_test();
+    // pow_test();
+    // trig_test();
+    // inverse_trig_test();
+    // sinpi_test();
+    // reduction_test();
+    // hyperbolic_test();
+    // hypot_test();
+    // fdim_test();
+    // lerp_test();
+    // lround_test();
+    // integer_predicates_test();
+    // #[cfg(feature = "bigint")]
+    // ratio_test();
+    // fixed_test();
+    // quantize_test();
+    // presets_test();
+    // random_test();
+    // #[cfg(feature = "rand")]
+    // distribution_test();
+    // between_test();
+    // count_between_test();
+    // assert_float_eq_test();
+    // parts_test();
+    // debug_test();
+    // decimal_test();
+    // strtod_torture_test();
+    // round_trip_test();
+    // engineering_test();
+    // fixed_decimal_test();
+    // decimal64_test();
+    // decimal128_test();
+    // decimal_round_trip_test();
+    // decimal_quantize_test();
+    // f128_string_test();
+    // conformance_test();
+    // testbench_test();
+    // gamma_test();
+    // accuracy_test();
+    // polynomial_test();
+    // error_free_test();
+    // exactness_test();
+    // augmented_test();
+    // expansion_test();
+    // predicates_test();
+    // blas_test();
+    // strided_test();
+    // #[cfg(feature = "ndarray")]
+    // arrays_test();
+    // #[cfg(feature = "bytemuck")]
+    // bytemuck_test();
+    // #[cfg(feature = "rkyv")]
+    // rkyv_test();
+    // summation_test();
+    // pairwise_test();
+    // superaccumulator_test();
+    // quire_test();
+    // reproducible_test();
+    // stats_test();
+    // complex_test();
+    // ball_test();
+    // smt_export(floatfs::smtlib::Operation::Multiply, floatfs::RoundingMode::TiesToEven);
+    // #[cfg(target_arch = "aarch64")]
+    // arm_hardware_test();
+    // #[cfg(target_arch = "x86_64")]
+    // x86_hardware_test();
+    // #[cfg(target_arch = "x86_64")]
+    // x87_hardware_test();
+    #[cfg(feature = "no-panic")]
+    panic_free_test(); // makes every checked function reachable, so the link fails if one of them can panic
 
     // let c = a.divide(&b);
 }
@@ -416,6 +6042,41 @@ fn mult_benchmark() {
     println!("Software is {} times slower", duration1.as_secs_f64() / duration2.as_secs_f64());
 }
 
+fn add_benchmark() {
+    // the sums in a decaying series are mostly far apart, where add stops at the larger operand; the close ones
+    // go all the way through alignment and rounding. both against lining the operands up exactly in a wide
+    // integer (Exact) and rounding that, which is what add would be without jamming.
+    use floatfs::exact::Exact;
+    use floatfs::RoundingMode;
+    use std::hint::black_box;
+    use std::time::Instant;
+
+    let n = 10_000_000;
+    // rounding up, the far apart sum moves off the larger operand to the next value
+    let pairs = [
+        ("far apart", Float::new(1.0), Float::new(1e-30), RoundingMode::TiesToEven),
+        ("far apart, rounding up", Float::new(1.0), Float::new(1e-30), RoundingMode::TowardPositive),
+        ("close", Float::new(1.1), Float::new(1.3), RoundingMode::TiesToEven),
+    ];
+    for (name, a, b, mode) in pairs {
+        let start = Instant::now();
+        for _ in 0..n {
+            black_box(black_box(&a).add_with_rounding(black_box(&b), mode));
+        }
+        let duration1 = start.elapsed();
+        println!("Time elapsed in addition ({}): {:?}", name, duration1);
+
+        let start = Instant::now();
+        for _ in 0..n {
+            black_box((Exact::from(black_box(&a)) + black_box(&b)).round_with_rounding(mode));
+        }
+        let duration2 = start.elapsed();
+        println!("Time elapsed in wide integer addition ({}): {:?}", name, duration2);
+
+        println!("Wide integer addition is {} times slower", duration2.as_secs_f64() / duration1.as_secs_f64());
+    }
+}
+
 fn mult_tie_test() {
     // mantissa1 * mantissa2 = (some_value << 52) + (1 << 51)
     // mantissa1 = 2^26, mantissa2 = 2^26 + 2^25, product = 2^52 + 2^51
@@ -428,13 +6089,13 @@ fn mult_tie_test() {
 
     mult_check_print(a, b, true);
 
-    // a.print_parts();
-    // b.print_parts();
+    // println!("{}", a.parts());
+    // println!("{}", b.parts());
 
     // let result = a.multiply(&b);
     // println!("Result = {:.17e}", result.to_f64());
-    // result.print_parts();
+    // println!("{}", result.parts());
     // let expected = a.to_f64() * b.to_f64();
     // println!("Expected = {:.17e}", expected);
-    // Float::new(expected).print_parts();
+    // println!("{}", Float::new(expected).parts());
 }
@@ -0,0 +1,791 @@
+// elementary functions, in soft-float only so they give the same bits everywhere. each one reduces its argument,
+// sums a series in Wide (128-bit) arithmetic and rounds once at the end, so results are faithful in every mode and
+// correctly rounded unless the exact value is within about 2^-120 of a rounding boundary. with Accuracy::Faithful
+// in the context the series stop a dozen bits past the format's precision instead, which is still faithful and
+// takes a bit over half the time. special values follow C's Annex F.
+
+use core::cmp::Ordering;
+
+use crate::format::Format;
+use crate::wide::Wide;
+use crate::{Accuracy, Context, Flags, RoundingMode, SoftFloat};
+
+// each constant is its value's leading 128 bits, rounded to nearest. there are no fitted polynomials behind
+// these functions: every series is a taylor series whose coefficients are worked out as it's summed, so nothing
+// here needs a minimax fit to reproduce
+const LN2: Wide = Wide { sign: false, exponent: -128, significand: 0xB17217F7D1CF79ABC9E3B39803F2F6AF };
+const LOG2_E: Wide = Wide { sign: false, exponent: -127, significand: 0xB8AA3B295C17F0BBBE87FED0691D3E89 };
+const LOG10_E: Wide = Wide { sign: false, exponent: -129, significand: 0xDE5BD8A937287195355BAAAFAD33DC32 };
+const LN10: Wide = Wide { sign: false, exponent: -126, significand: 0x935D8DDDAAA8AC16EA56D62B82D30A29 };
+const LOG10_2: Wide = Wide { sign: false, exponent: -129, significand: 0x9A209A84FBCFF7988F8959AC0B7C9178 };
+const SQRT2: u128 = 0xB504F333F9DE6484597D89B3754ABE9F; // as a significand
+
+// how far the series run for correctly rounded results: past the last bit of the 128-bit intermediate
+pub(crate) const FULL_BITS: i32 = 130;
+
+// where the series stop: FULL_BITS, or a dozen bits past the format's precision for a faithful result. the
+// series are where the time goes, and the final rounding only needs the error well under half an ulp
+pub(crate) fn series_bits<F: Format>(ctx: &Context) -> i32 {
+    match ctx.accuracy {
+        Accuracy::CorrectlyRounded => FULL_BITS,
+        Accuracy::Faithful => F::MANTISSA_BITS as i32 + 12,
+    }
+}
+
+// every format overflows (underflows) well before e^(2^15) (e^(-2^15)), even in base 10, and clamping keeps k small
+const EXP_CLAMP: i32 = 15;
+
+// e^r - 1 for |r| <= ln2/2, from r + r^2/2! + ... without the leading 1, so tiny r loses nothing to
+// cancellation. 27 taylor terms get the series below 2^-128
+fn exp_series(r: Wide, bits: i32) -> Wide {
+    let (mut sum, mut term) = (r, r);
+    for n in 2..=27 {
+        term = term.mul(r).div(Wide::from_int(n));
+        sum = sum.add(term); // even a negligible last term goes in: it's what makes the sum inexact
+        if term.is_zero() || term.magnitude() < sum.magnitude() - bits {
+            break;
+        }
+    }
+    sum
+}
+
+// the odd (n = 1) or even (n = 2) half of the exp series, x^n/n! + x^(n+2)/(n+2)! + ..., for |x| < 1: sinh(x)
+// and cosh(x) - 1. every term has the same sign, so a tiny x comes out on the right side of x or 1
+fn half_exp_series(x: Wide, n: i64, bits: i32) -> Wide {
+    let x2 = x.mul(x);
+    let mut term = if n == 1 { x } else { x2.scale(-1) };
+    let mut sum = term;
+    for k in (n..=40).step_by(2) {
+        term = term.mul(x2).div(Wide::from_int((k + 1) * (k + 2)));
+        sum = sum.add(term);
+        if term.is_zero() || term.magnitude() < sum.magnitude() - bits {
+            break;
+        }
+    }
+    sum
+}
+
+// splits x into k and r = x - k*ln2, with r in [-ln2/2, ln2/2]
+fn reduce_ln2(x: Wide) -> (i64, Wide) {
+    let k = x.mul(LOG2_E).round_to_int();
+    (k, x.sub(Wide::from_int(k).mul(LN2)))
+}
+
+// e^x as 2^k * e^r, with x limited to +-2^15 first
+pub(crate) fn exp_wide(x: Wide, bits: i32) -> Wide {
+    let x = if !x.is_zero() && x.magnitude() >= EXP_CLAMP { Wide { sign: x.sign, ..Wide::from_int(1 << EXP_CLAMP) } } else { x };
+    let (k, r) = reduce_ln2(x);
+    exp_series(r, bits).add(Wide::ONE).scale(k as i32)
+}
+
+// e^x - 1 without the cancellation: the series alone when the reduction leaves k = 0
+fn expm1_wide(x: Wide, bits: i32) -> Wide {
+    let (k, r) = reduce_ln2(x);
+    if k == 0 {
+        return exp_series(r, bits);
+    }
+    // |x| > ln2/2 here, so subtracting the 1 cancels at most a couple of bits
+    exp_series(r, bits).add(Wide::ONE).scale(k as i32).sub(Wide::ONE)
+}
+
+// 2 atanh(s) = 2 (s + s^3/3 + s^5/5 + ...), which is ln((1 + s)/(1 - s)). callers keep |s| < 0.172,
+// so 25 terms are plenty
+fn atanh_series(s: Wide, bits: i32) -> Wide {
+    let s2 = s.mul(s);
+    let (mut sum, mut power) = (s, s);
+    for n in 1..=25 {
+        power = power.mul(s2);
+        sum = sum.add(power.div(Wide::from_int(2 * n + 1)));
+        if power.is_zero() || power.magnitude() < sum.magnitude() - bits {
+            break;
+        }
+    }
+    sum.scale(1)
+}
+
+// splits a positive x into e and m, with x = 2^e * m and m in [sqrt(1/2), sqrt(2)]
+fn reduce_sqrt2(x: Wide) -> (i64, Wide) {
+    let high = x.significand > SQRT2;
+    (x.magnitude() as i64 + high as i64, Wide { exponent: -127 - high as i32, ..x })
+}
+
+// e and ln(m) for x = 2^e * m, using s = (m - 1)/(m + 1). m = 1 gives an exact zero, which keeps log2 of a
+// power of two exact
+pub(crate) fn ln_parts(x: Wide, bits: i32) -> (i64, Wide) {
+    let (e, m) = reduce_sqrt2(x);
+    (e, atanh_series(m.sub(Wide::ONE).div(m.add(Wide::ONE)), bits))
+}
+
+// ln x for positive x
+pub(crate) fn ln_wide(x: Wide, bits: i32) -> Wide {
+    let (e, ln_m) = ln_parts(x, bits);
+    Wide::from_int(e).mul(LN2).add(ln_m)
+}
+
+// ln(1 + w) for w > -1. with 1 + w in [sqrt(1/2), sqrt(2)], s = w/(2 + w) comes straight from w instead of through
+// the rounded 1 + w
+pub(crate) fn log1p_wide(w: Wide, bits: i32) -> Wide {
+    let (e, m) = reduce_sqrt2(w.add(Wide::ONE));
+    if e == 0 {
+        return atanh_series(w.div(w.add(Wide::from_int(2))), bits);
+    }
+    Wide::from_int(e).mul(LN2).add(atanh_series(m.sub(Wide::ONE).div(m.add(Wide::ONE)), bits))
+}
+
+// x as a Wide, limited to +-2^15 for the exponentials
+fn clamped<F: Format>(x: &SoftFloat<F>) -> Wide {
+    let w = Wide::from_soft(x);
+    if w.magnitude() >= EXP_CLAMP {
+        return Wide { sign: w.sign, ..Wide::from_int(1 << EXP_CLAMP) };
+    }
+    w
+}
+
+// x^y (x positive, both finite and nonzero) when the result is a power of two times an integer under 2^128, None
+// otherwise. those are the only powers that can land on a representable value or a halfway point, so everything
+// else is safe to round from an approximation. with x = m * 2^e (m odd) and y = n * 2^-q (n odd), x^y needs exact
+// square roots q times before it is an integer power
+fn exact_pow(x: Wide, y: Wide) -> Option<Wide> {
+    let (x_zeros, y_zeros) = (x.significand.trailing_zeros(), y.significand.trailing_zeros());
+    let (mut m, mut e) = (x.significand >> x_zeros, x.exponent as i64 + x_zeros as i64);
+    let (n, mut q) = (y.significand >> y_zeros, -(y.exponent as i64 + y_zeros as i64));
+    while q > 0 {
+        let root = m.isqrt();
+        if root * root != m || e % 2 != 0 {
+            return None;
+        }
+        (m, e, q) = (root, e / 2, q - 1);
+    }
+    // y is the integer n * 2^-q now
+    if m == 1 {
+        // a power of two, and only the range can stop it being exact. past 2^20 every format has overflowed
+        let k = if -q >= 64 || n >> 64 != 0 { i128::MAX } else { (e as i128 * n as i128).saturating_mul(1 << -q) };
+        let k = k.clamp(-(1 << 20), 1 << 20) as i32;
+        return Some(Wide::ONE.scale(if y.sign { -k } else { k }));
+    }
+    // m is at least 3 from here on, so a negative power is never exact and 128 is past any power that fits
+    if y.sign || -q >= 8 || n << -q > 128 {
+        return None;
+    }
+    let p = (n << -q) as u32;
+    m.checked_pow(p).map(|power| Wide::new(false, (e * p as i64) as i32, power))
+}
+
+// x^y for positive x, both finite and nonzero: exact when exact_pow can manage it, otherwise exp(y ln x)
+fn pow_wide(x: Wide, y: Wide, bits: i32) -> Wide {
+    if let Some(exact) = exact_pow(x, y) {
+        return exact;
+    }
+    // |y ln x| is under 2^14 for any result in range, so ln x needs that many more bits for e^(y ln x) to keep bits
+    exp_wide(y.mul(ln_wide(x, bits + 14)), bits)
+}
+
+impl<F: Format> SoftFloat<F> {
+    // nan in, nan out (quieted, with invalid for a signaling one)
+    fn nan_result(&self, ctx: &mut Context) -> Option<Self> {
+        self.propagate_nan(self, ctx)
+    }
+
+    // Some(true) for odd integers, Some(false) for even ones (zeros and infinities count as even), None otherwise
+    pub(crate) fn integer_parity(&self) -> Option<bool> {
+        if self.is_nan() {
+            return None;
+        }
+        if self.is_zero() || self.is_infinity() {
+            return Some(false);
+        }
+        let u = self.unpack();
+        match u.exponent {
+            1.. => Some(false),
+            0 => Some(u.significand & 1 == 1),
+            -127..=-1 if u.significand.trailing_zeros() >= (-u.exponent) as u32 => Some((u.significand >> -u.exponent) & 1 == 1),
+            _ => None,
+        }
+    }
+
+    // the cases exp, exp2 and exp10 share: nans, exp(-inf) = +0, exp(+inf) = +inf, exp(±0) = 1
+    fn exp_special(&self, ctx: &mut Context) -> Option<Self> {
+        if let Some(nan) = self.nan_result(ctx) {
+            return Some(nan);
+        }
+        if self.is_infinity() {
+            return Some(if self.get_sign() { Self::zero(false) } else { *self });
+        }
+        if self.is_zero() {
+            return Some(Wide::ONE.round(ctx));
+        }
+        None
+    }
+
+    // the cases every logarithm shares: None for positive finite x, which is left to the caller
+    fn log_special(&self, ctx: &mut Context) -> Option<Self> {
+        if let Some(nan) = self.nan_result(ctx) {
+            return Some(nan);
+        }
+        if self.is_zero() {
+            ctx.flags.raise(Flags::DIVIDE_BY_ZERO);
+            return Some(Self::infinity(true));
+        }
+        if self.get_sign() {
+            ctx.flags.raise(Flags::INVALID);
+            return Some(Self::default_nan(ctx.nan_mode));
+        }
+        if self.is_infinity() {
+            return Some(*self);
+        }
+        None
+    }
+
+    /// e^x. exp(0) = 1 exactly, exp(-inf) = +0, and results out of range overflow or underflow as usual.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn exp(&self, ctx: &mut Context) -> Self {
+        let x = self.flushed(ctx);
+        if let Some(special) = x.exp_special(ctx) {
+            return special;
+        }
+        exp_wide(clamped(&x), series_bits::<F>(ctx)).round(ctx)
+    }
+
+    /// 2^x, exact for integer x in range.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn exp2(&self, ctx: &mut Context) -> Self {
+        let x = self.flushed(ctx);
+        if let Some(special) = x.exp_special(ctx) {
+            return special;
+        }
+        // the integer part goes straight into the exponent, and the subtraction is exact
+        let w = clamped(&x);
+        let k = w.round_to_int();
+        exp_series(w.sub(Wide::from_int(k)).mul(LN2), series_bits::<F>(ctx)).add(Wide::ONE).scale(k as i32).round(ctx)
+    }
+
+    /// 10^x, exact when x is an integer and the power of ten fits in the format.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn exp10(&self, ctx: &mut Context) -> Self {
+        let x = self.flushed(ctx);
+        if let Some(special) = x.exp_special(ctx) {
+            return special;
+        }
+        let w = clamped(&x);
+        let n = w.round_to_int();
+        if (0..=38).contains(&n) && Wide::from_int(n) == w {
+            return Wide::new(false, 0, 10u128.pow(n as u32)).round(ctx);
+        }
+        exp_wide(w.mul(LN10), series_bits::<F>(ctx)).round(ctx)
+    }
+
+    /// e^x - 1, accurate for x near zero where exp(x) - 1 would cancel. expm1(±0) = ±0, expm1(-inf) = -1.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn expm1(&self, ctx: &mut Context) -> Self {
+        let x = self.flushed(ctx);
+        if let Some(nan) = x.nan_result(ctx) {
+            return nan;
+        }
+        if x.is_infinity() {
+            return if x.get_sign() { Wide::ONE.neg().round(ctx) } else { x };
+        }
+        if x.is_zero() {
+            return x;
+        }
+        expm1_wide(clamped(&x), series_bits::<F>(ctx)).round(ctx)
+    }
+
+    /// The natural logarithm. ln(±0) = -inf (divide by zero), ln(x < 0) is invalid, ln(1) = +0.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn ln(&self, ctx: &mut Context) -> Self {
+        let x = self.flushed(ctx);
+        if let Some(special) = x.log_special(ctx) {
+            return special;
+        }
+        ln_wide(Wide::from_soft(&x), series_bits::<F>(ctx)).round(ctx)
+    }
+
+    /// ln(1 + x), accurate for x near zero where 1 + x would round. log1p(±0) = ±0, log1p(-1) = -inf (divide by
+    /// zero), and x < -1 is invalid.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn log1p(&self, ctx: &mut Context) -> Self {
+        let x = self.flushed(ctx);
+        if let Some(nan) = x.nan_result(ctx) {
+            return nan;
+        }
+        if x.is_zero() || (x.is_infinity() && !x.get_sign()) {
+            return x;
+        }
+        let w = if x.is_infinity() { Wide::from_int(-2) } else { Wide::from_soft(&x) };
+        let u = w.add(Wide::ONE);
+        if u.is_zero() {
+            ctx.flags.raise(Flags::DIVIDE_BY_ZERO);
+            return Self::infinity(true);
+        }
+        if u.sign {
+            ctx.flags.raise(Flags::INVALID);
+            return Self::default_nan(ctx.nan_mode);
+        }
+        log1p_wide(w, series_bits::<F>(ctx)).round(ctx)
+    }
+
+    /// Base 2 logarithm, exact for powers of two.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn log2(&self, ctx: &mut Context) -> Self {
+        let x = self.flushed(ctx);
+        if let Some(special) = x.log_special(ctx) {
+            return special;
+        }
+        let (e, ln_m) = ln_parts(Wide::from_soft(&x), series_bits::<F>(ctx));
+        Wide::from_int(e).add(ln_m.mul(LOG2_E)).round(ctx)
+    }
+
+    /// Base 10 logarithm, exact for the powers of ten the format holds exactly.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn log10(&self, ctx: &mut Context) -> Self {
+        let x = self.flushed(ctx);
+        if let Some(special) = x.log_special(ctx) {
+            return special;
+        }
+        let w = Wide::from_soft(&x);
+        let (e, ln_m) = ln_parts(w, series_bits::<F>(ctx));
+        let result = Wide::from_int(e).mul(LOG10_2).add(ln_m.mul(LOG10_E));
+        // 10^n up to 10^38 fits a u128, and no negative power of ten is a binary float
+        let n = result.round_to_int();
+        if (0..=38).contains(&n) && Wide::new(false, 0, 10u128.pow(n as u32)) == w {
+            return Wide::from_int(n).round(ctx);
+        }
+        result.round(ctx)
+    }
+
+    /// x^y, correctly rounded unless x^y is within about 2^-110 of a rounding boundary, and exact whenever the
+    /// result is representable. The special cases are IEEE 754's pow: powf(x, ±0) and powf(+1, y) are 1 even for
+    /// a quiet nan, powf(±0, y < 0) is an infinity (divide by zero, except for y = -inf), powf(-1, ±inf) = 1, and a
+    /// negative x to a non-integer power is invalid. Odd integer powers keep the sign of x.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn powf(&self, other: &Self, ctx: &mut Context) -> Self {
+        let (x, y) = (self.flushed(ctx), other.flushed(ctx));
+        let x_is_one = !x.is_nan() && !x.is_infinity() && !x.is_zero() && Wide::from_soft(&x) == Wide::ONE;
+        if (y.is_zero() || x_is_one) && !x.is_signaling_nan() && !y.is_signaling_nan() {
+            return Wide::ONE.round(ctx);
+        }
+        if let Some(nan) = x.propagate_nan(&y, ctx) {
+            return nan;
+        }
+        let parity = y.integer_parity();
+        let negative = x.get_sign() && parity == Some(true);
+        if x.is_zero() {
+            if !y.get_sign() {
+                return Self::zero(negative);
+            }
+            if !y.is_infinity() {
+                ctx.flags.raise(Flags::DIVIDE_BY_ZERO);
+            }
+            return Self::infinity(negative);
+        }
+        if y.is_infinity() {
+            let ax = if x.is_infinity() { Wide::from_int(2) } else { Wide { sign: false, ..Wide::from_soft(&x) } };
+            if ax == Wide::ONE {
+                return Wide::ONE.round(ctx);
+            }
+            // |x| > 1 grows toward +inf and shrinks toward -inf, |x| < 1 the other way around
+            let grows = ax.magnitude() >= 0;
+            return if grows != y.get_sign() { Self::infinity(false) } else { Self::zero(false) };
+        }
+        if x.is_infinity() {
+            return if y.get_sign() { Self::zero(negative) } else { Self::infinity(negative) };
+        }
+        if x.get_sign() && parity.is_none() {
+            ctx.flags.raise(Flags::INVALID);
+            return Self::default_nan(ctx.nan_mode);
+        }
+
+        let ax = Wide { sign: false, ..Wide::from_soft(&x) };
+        Wide { sign: negative, ..pow_wide(ax, Wide::from_soft(&y), series_bits::<F>(ctx)) }.round(ctx)
+    }
+
+    /// x^n the way f64::powi does it (compiler-rt's __powidf2): square and multiply in this format, each product
+    /// rounded, then one reciprocal for negative n. powi(x, 0) is 1 for any x, nans included. With round to
+    /// nearest and no overflow or underflow along the way, the relative error is at most about (|n| - 1) * 2^-p
+    /// from the products (squaring doubles the error already there) plus 2^-p for the reciprocal, with p the
+    /// MANTISSA_BITS + 1 bits of precision. Intermediate products can overflow or underflow when x^n itself
+    /// wouldn't, as on the real thing.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn powi(&self, n: i32, ctx: &mut Context) -> Self {
+        let one: Self = Wide::ONE.round(ctx);
+        let (mut base, mut result, mut k) = (*self, one, n);
+        loop {
+            if k & 1 != 0 {
+                result = result.multiply(&base, ctx);
+            }
+            k /= 2;
+            if k == 0 {
+                break;
+            }
+            base = base.multiply(&base, ctx);
+        }
+        if n < 0 {
+            one.divide(&result, ctx)
+        } else {
+            result
+        }
+    }
+
+    /// x^n rounded once, like powf with an integer y: exact whenever the result is representable, since the
+    /// product is carried in 128 bits while it fits.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn powi_exact(&self, n: i32, ctx: &mut Context) -> Self {
+        let x = self.flushed(ctx);
+        if n == 0 && !x.is_signaling_nan() {
+            return Wide::ONE.round(ctx);
+        }
+        if let Some(nan) = x.nan_result(ctx) {
+            return nan;
+        }
+        let negative = x.get_sign() && n & 1 != 0;
+        if x.is_zero() {
+            if n > 0 {
+                return Self::zero(negative);
+            }
+            ctx.flags.raise(Flags::DIVIDE_BY_ZERO);
+            return Self::infinity(negative);
+        }
+        if x.is_infinity() {
+            return if n < 0 { Self::zero(negative) } else { Self::infinity(negative) };
+        }
+        let ax = Wide { sign: false, ..Wide::from_soft(&x) };
+        Wide { sign: negative, ..pow_wide(ax, Wide::from_int(n as i64), series_bits::<F>(ctx)) }.round(ctx)
+    }
+
+    /// The cube root, with the sign of x: cbrt(-8) = -2, and ±0 and ±inf are returned as they are. Exact (no
+    /// inexact flag) whenever x is the cube of a representable value, subnormals included.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn cbrt(&self, ctx: &mut Context) -> Self {
+        let x = self.flushed(ctx);
+        if let Some(nan) = x.nan_result(ctx) {
+            return nan;
+        }
+        if x.is_zero() || x.is_infinity() {
+            return x;
+        }
+        Wide::from_soft(&x).cbrt().round(ctx)
+    }
+
+    /// sqrt(x^2 + y^2), correctly rounded, without overflow or underflow in between: only a result out of range
+    /// overflows, and hypot(3, 4) is exactly 5. An infinity wins over a quiet nan (hypot(inf, nan) = +inf), as
+    /// IEEE 754 and C specify, and hypot(x, ±0) = |x|.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn hypot(&self, other: &Self, ctx: &mut Context) -> Self {
+        let (x, y) = (self.flushed(ctx), other.flushed(ctx));
+        if (x.is_infinity() || y.is_infinity()) && !x.is_signaling_nan() && !y.is_signaling_nan() {
+            return Self::infinity(false);
+        }
+        if let Some(nan) = x.propagate_nan(&y, ctx) {
+            return nan;
+        }
+        let (ax, ay) = (Wide { sign: false, ..Wide::from_soft(&x) }, Wide { sign: false, ..Wide::from_soft(&y) });
+        let (big, small) = if (ax.exponent, ax.significand) >= (ay.exponent, ay.significand) { (ax, ay) } else { (ay, ax) };
+        if small.is_zero() {
+            return big.round(ctx);
+        }
+        // the root is only good to about 2^-125, so it's rounded to q, the nearest number with one more bit than
+        // the format (every representable value and halfway point has one), and then y^2 against (q - x)(q + x)
+        // exactly says which side of q the true result is on, or that it is q itself
+        let root = big.mul(big).add(small.mul(small)).sqrt();
+        let drop = 126 - F::MANTISSA_BITS;
+        let q = Wide::new(false, root.exponent + drop as i32, ((root.significand >> (drop - 1)) + 1) >> 1);
+        let (below, above) = (q.sub(big), q.add(big));
+        // (q can't be below x with the root this close, but if it were, the result would be above q too)
+        let result = match small.mul_exact(small).cmp(&below.mul_exact(above)) {
+            Ordering::Equal if !below.sign => q,
+            Ordering::Less if !below.sign => Wide::new(false, q.exponent, q.significand - 1),
+            _ => Wide { significand: q.significand | 1, ..q },
+        };
+        result.round(ctx)
+    }
+
+    /// C's fdim, the positive difference: x - y, rounded as usual, when x > y and +0 otherwise, so
+    /// fdim(inf, inf) = +0. A nan operand gives a nan.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn fdim(&self, other: &Self, ctx: &mut Context) -> Self {
+        let (x, y) = (self.flushed(ctx), other.flushed(ctx));
+        if let Some(nan) = x.propagate_nan(&y, ctx) {
+            return nan;
+        }
+        if x.compare(&y) == Some(Ordering::Greater) {
+            x.subtract(&y, ctx)
+        } else {
+            Self::zero(false)
+        }
+    }
+
+    // fmin and fmax: one nan operand is ignored, two give a nan, and -0 counts as below +0
+    fn fmin_fmax(&self, other: &Self, ctx: &mut Context, want_max: bool) -> Self {
+        let (x, y) = (self.flushed(ctx), other.flushed(ctx));
+        if x.is_nan() && y.is_nan() {
+            return x.propagate_nan(&y, ctx).unwrap_or(x);
+        }
+        if x.is_signaling_nan() || y.is_signaling_nan() {
+            ctx.flags.raise(Flags::INVALID);
+        }
+        if x.is_nan() {
+            return y;
+        }
+        if y.is_nan() {
+            return x;
+        }
+        let x_smaller = match x.compare(&y) {
+            Some(Ordering::Equal) => x.get_sign(), // only differs for zeros of opposite sign
+            ordering => ordering == Some(Ordering::Less),
+        };
+        if x_smaller != want_max {
+            x
+        } else {
+            y
+        }
+    }
+
+    /// C's fmax: the larger operand, where a single nan operand is ignored, so fmax(nan, 1) = 1, and only two
+    /// nans give a nan. That is IEEE 754-2019 maximumNumber, not maximum, which would give the nan. fmax(-0, +0)
+    /// is +0, which C allows but doesn't require. A signaling nan raises invalid but is still ignored (glibc
+    /// returns a nan for one instead, as IEEE 754-2008's maxNum did).
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn fmax(&self, other: &Self, ctx: &mut Context) -> Self {
+        self.fmin_fmax(other, ctx, true)
+    }
+
+    /// C's fmin, the smaller operand, treating nans and zeros as fmax does: fmin(nan, 1) = 1 and
+    /// fmin(-0, +0) = -0.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn fmin(&self, other: &Self, ctx: &mut Context) -> Self {
+        self.fmin_fmax(other, ctx, false)
+    }
+
+    /// (a + b) / 2, correctly rounded and without overflow in between: the sum is formed in the 128-bit Wide,
+    /// with the bits of a far smaller operand jammed into a sticky bit, which is all the single rounding needs.
+    /// Only that rounding raises anything, and midpoint(MAX, MAX) = MAX. Infinities, nans and an exact zero sum
+    /// come out as a + b does.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn midpoint(&self, other: &Self, ctx: &mut Context) -> Self {
+        let (x, y) = (self.flushed(ctx), other.flushed(ctx));
+        if x.is_nan() || x.is_infinity() || y.is_nan() || y.is_infinity() {
+            return x.add(&y, ctx);
+        }
+        let sum = Wide::from_soft(&x).add(Wide::from_soft(&y));
+        if sum.is_zero() {
+            return x.add(&y, ctx);
+        }
+        sum.scale(-1).round(ctx)
+    }
+
+    /// a + t (b - a), correctly rounded: a + tb - ta is summed exactly and rounded once. That gives C++20's
+    /// guarantees for finite arguments: lerp(a, b, 0) = a and lerp(a, b, 1) = b (zeros' signs included),
+    /// lerp(a, a, t) = a, the result stays between a and b for t in [0, 1], and it's monotonic in t. Any other
+    /// exact zero is +0 (-0 rounding down) unless a and b are both -0. An infinite or nan argument goes through
+    /// a + t * (b - a) as written.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn lerp(&self, b: &Self, t: &Self, ctx: &mut Context) -> Self {
+        let (a, b, t) = (self.flushed(ctx), b.flushed(ctx), t.flushed(ctx));
+        if [a, b, t].iter().any(|x| x.is_nan() || x.is_infinity()) {
+            let difference = b.subtract(&a, ctx);
+            return a.add(&t.multiply(&difference, ctx), ctx);
+        }
+        if t.is_zero() {
+            return a;
+        }
+        // every format's significand is at most 64 bits, so both products are exact
+        let (wa, wb, wt) = (Wide::from_soft(&a), Wide::from_soft(&b), Wide::from_soft(&t));
+        if wt == Wide::ONE {
+            return b;
+        }
+        let sum = Wide::sum3([wa, wt.mul(wb), wt.mul(wa).neg()]);
+        if sum.is_zero() {
+            let negative = if a.is_zero() && b.is_zero() && a.get_sign() == b.get_sign() {
+                a.get_sign()
+            } else {
+                ctx.rounding == RoundingMode::TowardNegative
+            };
+            return Self::zero(negative);
+        }
+        sum.round(ctx)
+    }
+
+    // lround and lrint: x rounded in `mode`, or min with invalid raised when that's outside [min, max] or x is
+    // infinite or a nan. min is x86's "integer indefinite", which its conversions give and glibc passes on
+    fn round_to_long(&self, mode: RoundingMode, ctx: &mut Context, min: i128, max: i128, inexact: bool) -> i128 {
+        let mut scratch = Context { flags: Flags::empty(), ..*ctx };
+        let value = self.round_to_integer(mode, &mut scratch, min, max).unwrap_or(min);
+        ctx.flags.raise(if inexact { scratch.flags } else { Flags(scratch.flags.0 & !Flags::INEXACT.0) });
+        value
+    }
+
+    /// C's lround (and llround) with a 64-bit long: the nearest integer, halfway cases away from zero whatever
+    /// the context's mode. Out of range values, infinities and nans raise invalid and give i64::MIN, as on x86.
+    /// Inexact isn't raised, which C allows and glibc does too.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn lround(&self, ctx: &mut Context) -> i64 {
+        self.round_to_long(RoundingMode::TiesToAway, ctx, i64::MIN as i128, i64::MAX as i128, false) as i64
+    }
+
+    /// lround with a 32-bit long, as on Windows and 32-bit targets.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn lround_i32(&self, ctx: &mut Context) -> i32 {
+        self.round_to_long(RoundingMode::TiesToAway, ctx, i32::MIN as i128, i32::MAX as i128, false) as i32
+    }
+
+    /// C's lrint (and llrint) with a 64-bit long: x rounded to an integer in the context's mode, raising inexact
+    /// if that changed it. Out of range values, infinities and nans raise invalid and give i64::MIN.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn lrint(&self, ctx: &mut Context) -> i64 {
+        self.round_to_long(ctx.rounding, ctx, i64::MIN as i128, i64::MAX as i128, true) as i64
+    }
+
+    /// lrint with a 32-bit long.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn lrint_i32(&self, ctx: &mut Context) -> i32 {
+        self.round_to_long(ctx.rounding, ctx, i32::MIN as i128, i32::MAX as i128, true) as i32
+    }
+
+    /// The hyperbolic sine. sinh(±0) = ±0 and sinh(±inf) = ±inf. e^|x| is carried with the 128-bit intermediate's
+    /// exponent, so only a result that is itself out of range overflows.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn sinh(&self, ctx: &mut Context) -> Self {
+        let x = self.flushed(ctx);
+        if let Some(nan) = x.nan_result(ctx) {
+            return nan;
+        }
+        if x.is_zero() || x.is_infinity() {
+            return x;
+        }
+        let (ax, bits) = (Wide { sign: false, ..clamped(&x) }, series_bits::<F>(ctx));
+        let result = if ax.magnitude() < 0 {
+            half_exp_series(ax, 1, bits)
+        } else {
+            // e^|x| >= e, so the subtraction cancels less than a bit
+            let e = exp_wide(ax, bits);
+            e.sub(Wide::ONE.div(e)).scale(-1)
+        };
+        Wide { sign: x.get_sign(), ..result }.round(ctx)
+    }
+
+    /// The hyperbolic cosine. cosh(±0) = 1 and cosh(±inf) = +inf, and like sinh it only overflows when the result
+    /// does (cosh(710) is finite in binary64 although exp(710) isn't).
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn cosh(&self, ctx: &mut Context) -> Self {
+        let x = self.flushed(ctx);
+        if let Some(nan) = x.nan_result(ctx) {
+            return nan;
+        }
+        if x.is_infinity() {
+            return Self::infinity(false);
+        }
+        if x.is_zero() {
+            return Wide::ONE.round(ctx);
+        }
+        let (ax, bits) = (Wide { sign: false, ..clamped(&x) }, series_bits::<F>(ctx));
+        if ax.magnitude() < 0 {
+            return half_exp_series(ax, 2, bits).add(Wide::ONE).round(ctx);
+        }
+        let e = exp_wide(ax, bits);
+        e.add(Wide::ONE.div(e)).scale(-1).round(ctx)
+    }
+
+    /// The hyperbolic tangent. tanh(±0) = ±0 and tanh(±inf) = ±1; large finite x gives ±1 inexactly, just under
+    /// it when rounding toward zero.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn tanh(&self, ctx: &mut Context) -> Self {
+        let x = self.flushed(ctx);
+        if let Some(nan) = x.nan_result(ctx) {
+            return nan;
+        }
+        if x.is_zero() {
+            return x;
+        }
+        if x.is_infinity() {
+            return Wide { sign: x.get_sign(), ..Wide::ONE }.round(ctx);
+        }
+        let (ax, bits) = (Wide { sign: false, ..clamped(&x) }, series_bits::<F>(ctx));
+        let result = if ax.magnitude() < -40 {
+            // x - x^3/3, directly: going through expm1 could land the tiny correction on the wrong side of x
+            ax.sub(ax.mul(ax).mul(ax).div(Wide::from_int(3)))
+        } else if ax.magnitude() < 0 {
+            let m = expm1_wide(ax.scale(1), bits);
+            m.div(m.add(Wide::from_int(2)))
+        } else {
+            // 1 - 2/(e^2|x| + 1), which keeps the distance from 1 even once it is far below an ulp
+            Wide::ONE.sub(Wide::from_int(2).div(exp_wide(ax.scale(1), bits).add(Wide::ONE)))
+        };
+        Wide { sign: x.get_sign(), ..result }.round(ctx)
+    }
+
+    /// The inverse hyperbolic sine, ln(x + sqrt(x^2 + 1)) with the sign of x. asinh(±0) = ±0 and
+    /// asinh(±inf) = ±inf.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn asinh(&self, ctx: &mut Context) -> Self {
+        let x = self.flushed(ctx);
+        if let Some(nan) = x.nan_result(ctx) {
+            return nan;
+        }
+        if x.is_zero() || x.is_infinity() {
+            return x;
+        }
+        let ax = Wide { sign: false, ..Wide::from_soft(&x) };
+        let result = if ax.magnitude() < -40 {
+            ax.sub(ax.mul(ax).mul(ax).div(Wide::from_int(6)))
+        } else {
+            // ln(1 + t) with t = |x| + x^2/(1 + sqrt(x^2 + 1)), which is |x| + sqrt(x^2 + 1) - 1 without the cancellation
+            let x2 = ax.mul(ax);
+            log1p_wide(ax.add(x2.div(Wide::ONE.add(x2.add(Wide::ONE).sqrt()))), series_bits::<F>(ctx))
+        };
+        Wide { sign: x.get_sign(), ..result }.round(ctx)
+    }
+
+    /// The inverse hyperbolic cosine, ln(x + sqrt(x^2 - 1)). acosh(1) = +0, acosh(+inf) = +inf, and x < 1 is
+    /// invalid.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn acosh(&self, ctx: &mut Context) -> Self {
+        let x = self.flushed(ctx);
+        if let Some(nan) = x.nan_result(ctx) {
+            return nan;
+        }
+        if x.is_infinity() && !x.get_sign() {
+            return x;
+        }
+        let d = if x.is_infinity() { Wide::ONE.neg() } else { Wide::from_soft(&x).sub(Wide::ONE) };
+        if d.sign && !d.is_zero() {
+            ctx.flags.raise(Flags::INVALID);
+            return Self::default_nan(ctx.nan_mode);
+        }
+        if d.is_zero() {
+            return Self::zero(false);
+        }
+        // ln(1 + t) with t = d + sqrt(d (d + 2)) for d = x - 1, which is exact, so x near 1 keeps its accuracy
+        log1p_wide(d.add(d.mul(d.add(Wide::from_int(2))).sqrt()), series_bits::<F>(ctx)).round(ctx)
+    }
+
+    /// The inverse hyperbolic tangent, ln((1 + x)/(1 - x))/2. atanh(±0) = ±0, atanh(±1) = ±inf (divide by zero),
+    /// and |x| > 1 is invalid.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn atanh(&self, ctx: &mut Context) -> Self {
+        let x = self.flushed(ctx);
+        if let Some(nan) = x.nan_result(ctx) {
+            return nan;
+        }
+        if x.is_zero() {
+            return x;
+        }
+        let ax = if x.is_infinity() { Wide::from_int(2) } else { Wide { sign: false, ..Wide::from_soft(&x) } };
+        if ax == Wide::ONE {
+            ctx.flags.raise(Flags::DIVIDE_BY_ZERO);
+            return Self::infinity(x.get_sign());
+        }
+        if ax.magnitude() >= 0 {
+            ctx.flags.raise(Flags::INVALID);
+            return Self::default_nan(ctx.nan_mode);
+        }
+        let bits = series_bits::<F>(ctx);
+        let result = if ax.magnitude() < -3 {
+            // the series itself, all terms positive, for |x| < 1/8
+            atanh_series(ax, bits).scale(-1)
+        } else {
+            // ln(1 + t)/2 with t = 2|x|/(1 - |x|), and 1 - |x| is exact
+            log1p_wide(ax.scale(1).div(Wide::ONE.sub(ax)), bits).scale(-1)
+        };
+        Wide { sign: x.get_sign(), ..result }.round(ctx)
+    }
+}
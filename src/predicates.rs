@@ -0,0 +1,182 @@
+// shewchuk's robust geometric predicates on Float coordinates: the sign of each answer is exact, however close
+// the points are to degenerate, and since every operation is this crate's own the answers are the same on every
+// machine. each starts with the plain floating-point determinant and a bound on its error, which settles almost
+// every input, and falls back to exact expansion arithmetic when the bound can't. orient2d has shewchuk's
+// intermediate stages in between; orient3d and incircle go straight from the first stage to the exact one,
+// which only costs time in the nearly degenerate cases. like the originals, they assume nothing overflows or
+// underflows along the way.
+
+use crate::expansion::{estimate, expansion_sum, magnitude, scale_expansion};
+use crate::Float;
+
+// shewchuk's error bounds, from epsilon = 2^-53 as his exactinit() computes them
+const RESULT_ERROR_BOUND: u64 = 0x3CB8000000000002; // (3 + 8 eps) eps
+const CCW_ERROR_BOUND_A: u64 = 0x3CB8000000000004; // (3 + 16 eps) eps
+const CCW_ERROR_BOUND_B: u64 = 0x3CB0000000000003; // (2 + 12 eps) eps
+const CCW_ERROR_BOUND_C: u64 = 0x3982000000000004; // (9 + 64 eps) eps^2
+const O3D_ERROR_BOUND_A: u64 = 0x3CCC000000000007; // (7 + 56 eps) eps
+const ICC_ERROR_BOUND_A: u64 = 0x3CD4000000000006; // (10 + 96 eps) eps
+
+// bound * permanent, rounded to nearest like the rest
+fn bound(bits: u64, permanent: &Float) -> Float {
+    Float::from_bits(bits).multiply(permanent)
+}
+
+// an expansion in a fixed-size array, for the exact stages
+struct Exact<const N: usize> {
+    components: [Float; N],
+    length: usize,
+}
+
+impl<const N: usize> Exact<N> {
+    fn empty() -> [Float; N] {
+        core::array::from_fn(|_| Float::new(0.0))
+    }
+
+    fn sum(e: &[Float], f: &[Float]) -> Exact<N> {
+        let mut components = Self::empty();
+        let length = expansion_sum(e, f, &mut components);
+        Exact { components, length }
+    }
+
+    fn scale(e: &[Float], b: &Float) -> Exact<N> {
+        let mut components = Self::empty();
+        let length = scale_expansion(e, b, &mut components);
+        Exact { components, length }
+    }
+
+    fn get(&self) -> &[Float] {
+        &self.components[..self.length]
+    }
+}
+
+// px qy - py qx, exactly, for the first two coordinates of p and q
+fn cross(p: &[Float], q: &[Float]) -> Exact<4> {
+    let (left, left_error) = p[0].two_prod(&q[1]);
+    let (right, right_error) = p[1].two_prod(&q[0]);
+    Exact::sum(&[left_error, left], &[right_error.negated(), right.negated()])
+}
+
+// the 3x3 determinants with rows (x, y, 1) for b, c, d and the other three triples, which the exact orient3d
+// and incircle are expanded in: (bcd, cda, dab, abc), where bcd = [bc] + [cd] + [db] with [pq] = px qy - py qx
+fn minors(a: &[Float], b: &[Float], c: &[Float], d: &[Float]) -> [Exact<12>; 4] {
+    let triple = |p: Exact<4>, q: Exact<4>, r: Exact<4>| Exact::<12>::sum(Exact::<8>::sum(p.get(), q.get()).get(), r.get());
+    [
+        triple(cross(b, c), cross(c, d), cross(d, b)),
+        triple(cross(c, d), cross(d, a), cross(a, c)),
+        triple(cross(d, a), cross(a, b), cross(b, d)),
+        triple(cross(a, b), cross(b, c), cross(c, a)),
+    ]
+}
+
+fn orient2d_exact(a: &[Float; 2], b: &[Float; 2], c: &[Float; 2]) -> Float {
+    let (ab, bc, ca) = (cross(a, b), cross(b, c), cross(c, a));
+    estimate(Exact::<12>::sum(Exact::<8>::sum(ab.get(), bc.get()).get(), ca.get()).get())
+}
+
+/// Twice the signed area of the triangle abc: positive if a, b, c run counterclockwise, negative if clockwise,
+/// and zero exactly when they're collinear. The value is (ax - cx)(by - cy) - (ay - cy)(bx - cx) to within
+/// a small relative error, and its sign is always right.
+pub fn orient2d(a: &[Float; 2], b: &[Float; 2], c: &[Float; 2]) -> Float {
+    let (acx, acx_tail) = a[0].two_sum(&c[0].negated());
+    let (bcx, bcx_tail) = b[0].two_sum(&c[0].negated());
+    let (acy, acy_tail) = a[1].two_sum(&c[1].negated());
+    let (bcy, bcy_tail) = b[1].two_sum(&c[1].negated());
+    let (left, right) = (acx.multiply(&bcy), acy.multiply(&bcx));
+    let det = left.subtract(&right);
+    // opposite signs (or a zero) can't cancel, so the rounded difference has the right sign already
+    if left.is_zero() || right.is_zero() || left.get_sign() != right.get_sign() {
+        return det;
+    }
+    let sum = left.add(&right).abs();
+    if magnitude(&det) >= magnitude(&bound(CCW_ERROR_BOUND_A, &sum)) {
+        return det;
+    }
+    // the products of the rounded differences, exactly
+    let (left, left_error) = acx.two_prod(&bcy);
+    let (right, right_error) = acy.two_prod(&bcx);
+    let products = Exact::<4>::sum(&[left_error, left], &[right_error.negated(), right.negated()]);
+    let mut det = estimate(products.get());
+    if magnitude(&det) >= magnitude(&bound(CCW_ERROR_BOUND_B, &sum)) {
+        return det;
+    }
+    if [&acx_tail, &bcx_tail, &acy_tail, &bcy_tail].iter().all(|tail| tail.is_zero()) {
+        return det; // the differences were exact, so the products were the whole determinant
+    }
+    // first-order corrections for the tails
+    let error = bound(CCW_ERROR_BOUND_C, &sum).add(&bound(RESULT_ERROR_BOUND, &det.abs()));
+    let correction = acx.multiply(&bcy_tail).add(&bcy.multiply(&acx_tail))
+        .subtract(&acy.multiply(&bcx_tail).add(&bcx.multiply(&acy_tail)));
+    det = det.add(&correction);
+    if magnitude(&det) >= magnitude(&error) {
+        return det;
+    }
+    orient2d_exact(a, b, c)
+}
+
+/// Six times the signed volume of the tetrahedron abcd: positive if d is below the plane through a, b and c
+/// (where below means a, b, c look counterclockwise from above), negative if above, and zero exactly when the
+/// four are coplanar. The value is the determinant of the rows a - d, b - d, c - d, to within a small relative
+/// error, and its sign is always right.
+pub fn orient3d(a: &[Float; 3], b: &[Float; 3], c: &[Float; 3], d: &[Float; 3]) -> Float {
+    let diff = |p: &[Float; 3]| [p[0].subtract(&d[0]), p[1].subtract(&d[1]), p[2].subtract(&d[2])];
+    let (ad, bd, cd) = (diff(a), diff(b), diff(c));
+    let (bdx_cdy, cdx_bdy) = (bd[0].multiply(&cd[1]), cd[0].multiply(&bd[1]));
+    let (cdx_ady, adx_cdy) = (cd[0].multiply(&ad[1]), ad[0].multiply(&cd[1]));
+    let (adx_bdy, bdx_ady) = (ad[0].multiply(&bd[1]), bd[0].multiply(&ad[1]));
+    let det = ad[2]
+        .multiply(&bdx_cdy.subtract(&cdx_bdy))
+        .add(&bd[2].multiply(&cdx_ady.subtract(&adx_cdy)))
+        .add(&cd[2].multiply(&adx_bdy.subtract(&bdx_ady)));
+    let permanent = bdx_cdy.abs().add(&cdx_bdy.abs()).multiply(&ad[2].abs())
+        .add(&cdx_ady.abs().add(&adx_cdy.abs()).multiply(&bd[2].abs()))
+        .add(&adx_bdy.abs().add(&bdx_ady.abs()).multiply(&cd[2].abs()));
+    if magnitude(&det) > magnitude(&bound(O3D_ERROR_BOUND_A, &permanent)) {
+        return det;
+    }
+    // the 4x4 determinant with rows (x, y, z, 1), expanded along z: az bcd - bz cda + cz dab - dz abc
+    let [bcd, cda, dab, abc] = minors(a, b, c, d);
+    let (a_part, b_part) = (Exact::<24>::scale(bcd.get(), &a[2]), Exact::<24>::scale(cda.get(), &b[2].negated()));
+    let (c_part, d_part) = (Exact::<24>::scale(dab.get(), &c[2]), Exact::<24>::scale(abc.get(), &d[2].negated()));
+    let (ab_part, cd_part) = (Exact::<48>::sum(a_part.get(), b_part.get()), Exact::<48>::sum(c_part.get(), d_part.get()));
+    estimate(Exact::<96>::sum(ab_part.get(), cd_part.get()).get())
+}
+
+// (px^2 + py^2) times the expansion e, exactly
+fn lifted(e: &[Float], p: &[Float; 2], negative: bool) -> Exact<96> {
+    let (x, y) = if negative { (p[0].negated(), p[1].negated()) } else { (p[0].copy(), p[1].copy()) };
+    let (ex, ey) = (Exact::<24>::scale(e, &p[0]), Exact::<24>::scale(e, &p[1]));
+    let (exx, eyy) = (Exact::<48>::scale(ex.get(), &x), Exact::<48>::scale(ey.get(), &y));
+    Exact::sum(exx.get(), eyy.get())
+}
+
+/// Positive if d is inside the circle through a, b and c, negative if outside, zero exactly when the four are
+/// cocircular, for a, b, c in counterclockwise order (the signs flip for clockwise). The value is the
+/// determinant of the rows (x - dx, y - dy, (x - dx)^2 + (y - dy)^2) for a, b and c, to within a small relative
+/// error, and its sign is always right.
+pub fn incircle(a: &[Float; 2], b: &[Float; 2], c: &[Float; 2], d: &[Float; 2]) -> Float {
+    let diff = |p: &[Float; 2]| [p[0].subtract(&d[0]), p[1].subtract(&d[1])];
+    let (ad, bd, cd) = (diff(a), diff(b), diff(c));
+    let lift = |p: &[Float; 2]| p[0].multiply(&p[0]).add(&p[1].multiply(&p[1]));
+    let (a_lift, b_lift, c_lift) = (lift(&ad), lift(&bd), lift(&cd));
+    let (bdx_cdy, cdx_bdy) = (bd[0].multiply(&cd[1]), cd[0].multiply(&bd[1]));
+    let (cdx_ady, adx_cdy) = (cd[0].multiply(&ad[1]), ad[0].multiply(&cd[1]));
+    let (adx_bdy, bdx_ady) = (ad[0].multiply(&bd[1]), bd[0].multiply(&ad[1]));
+    let det = a_lift
+        .multiply(&bdx_cdy.subtract(&cdx_bdy))
+        .add(&b_lift.multiply(&cdx_ady.subtract(&adx_cdy)))
+        .add(&c_lift.multiply(&adx_bdy.subtract(&bdx_ady)));
+    let permanent = bdx_cdy.abs().add(&cdx_bdy.abs()).multiply(&a_lift)
+        .add(&cdx_ady.abs().add(&adx_cdy.abs()).multiply(&b_lift))
+        .add(&adx_bdy.abs().add(&bdx_ady.abs()).multiply(&c_lift));
+    if magnitude(&det) > magnitude(&bound(ICC_ERROR_BOUND_A, &permanent)) {
+        return det;
+    }
+    // the 4x4 determinant with rows (x, y, x^2 + y^2, 1), which is the same thing, expanded along the lifted
+    // column: la bcd - lb cda + lc dab - ld abc
+    let [bcd, cda, dab, abc] = minors(a, b, c, d);
+    let (a_part, b_part) = (lifted(bcd.get(), a, false), lifted(cda.get(), b, true));
+    let (c_part, d_part) = (lifted(dab.get(), c, false), lifted(abc.get(), d, true));
+    let (ab_part, cd_part) = (Exact::<192>::sum(a_part.get(), b_part.get()), Exact::<192>::sum(c_part.get(), d_part.get()));
+    estimate(Exact::<384>::sum(ab_part.get(), cd_part.get()).get())
+}
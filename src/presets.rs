@@ -0,0 +1,128 @@
+// layouts that never made it into IEEE 754 but are still around in files and hardware. PXR24 is Pixar's 24-bit
+// float, binary32 with the low 8 mantissa bits dropped, kept alive by OpenEXR's lossy pxr24 compression; fp24 is
+// the pixel shader float of the first DirectX 9 GPUs (ATI's R300 and its successors), 7 exponent bits and 16
+// mantissa bits. both are IEEE-like, so they're Formats, and convert, the arithmetic and the rest work on them as
+// on any other. RGB9E5 isn't: three 9-bit mantissas share one 5-bit exponent and there's no sign, no implicit
+// bit, no infinity and no nan. it's the HDR texture format (GL_RGB9_E5, DXGI_FORMAT_R9G9B9E5_SHAREDEXP), packed
+// and unpacked here as the EXT_texture_shared_exponent spec does it, in integers.
+//
+//     let half_res: SoftFloat<Pxr24> = pxr24_from_binary32(&x); // bit for bit what OpenEXR writes
+//     let shader: SoftFloat<Fp24> = x.convert(&mut ctx);
+//     let texel = rgb9e5_from_binary32([r, g, b]);
+
+use crate::format::Format;
+use crate::soft::shift_round;
+use crate::wide::Wide;
+use crate::{Context, RoundingMode, SoftFloat, F32};
+
+/// Pixar's 24-bit float: binary32's sign and 8-bit exponent, and the top 15 of its mantissa bits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Pxr24;
+
+impl Format for Pxr24 {
+    type Bits = u32;
+    const EXPONENT_BITS: u32 = 8;
+    const MANTISSA_BITS: u32 = 15;
+    const NAME: &'static str = "pxr24";
+}
+
+/// The 24-bit float of early DirectX 9 pixel shaders: 7 exponent bits and 16 mantissa bits, so a largest finite
+/// value of about 1.8e19.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Fp24;
+
+impl Format for Fp24 {
+    type Bits = u32;
+    const EXPONENT_BITS: u32 = 7;
+    const MANTISSA_BITS: u32 = 16;
+    const NAME: &'static str = "fp24";
+}
+
+/// A binary32 rounded to PXR24 the way OpenEXR's pxr24 compressor does it, which isn't quite `convert`: ties
+/// round away from zero, a finite value that would round up to infinity is truncated to the largest finite value
+/// instead, and a nan whose payload is all in the dropped bits keeps a payload bit, so it stays a nan.
+#[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+pub fn pxr24_from_binary32(x: &F32) -> SoftFloat<Pxr24> {
+    let bits = x.to_bits();
+    let (sign, magnitude) = (bits & 0x8000_0000, bits & 0x7FFF_FFFF);
+    let rounded = if x.is_nan() {
+        (magnitude >> 8).max(0x7F_8001)
+    } else if x.is_infinity() {
+        magnitude >> 8
+    } else {
+        let up = (magnitude + 0x80) >> 8;
+        if up >= 0x7F_8000 {
+            magnitude >> 8
+        } else {
+            up
+        }
+    };
+    SoftFloat::from_bits(sign >> 8 | rounded)
+}
+
+/// PXR24 to binary32, which is exact: the dropped mantissa bits come back as zeros.
+#[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+pub fn pxr24_to_binary32(x: &SoftFloat<Pxr24>) -> F32 {
+    F32::from_bits(x.to_bits() << 8)
+}
+
+// RGB9E5: 9 mantissa bits, an exponent bias of 15, and a mantissa's last bit worth 2^(exponent - 15 - 9)
+const RGB9E5_MANTISSA_BITS: i32 = 9;
+const RGB9E5_BIAS: i32 = 15;
+const RGB9E5_MAX: u32 = 0x477F_8000; // 511/512 * 2^16, the largest value it holds
+
+// a channel in [0, RGB9E5_MAX]: negatives, -0 and nans go to 0, and anything larger to the largest value.
+// nonnegative binary32s order as their bits do
+fn rgb9e5_clamp(x: &F32) -> F32 {
+    if x.is_nan() || x.get_sign() {
+        return F32::zero(false);
+    }
+    F32::from_bits(x.to_bits().min(RGB9E5_MAX))
+}
+
+// x / 2^scale rounded half up, for a clamped channel
+fn rgb9e5_mantissa(x: &F32, scale: i32) -> u32 {
+    if x.is_zero() {
+        return 0;
+    }
+    let u = x.unpack();
+    let shift = scale - u.exponent;
+    if shift <= 0 {
+        return (u.significand << -shift) as u32;
+    }
+    shift_round(u.significand, shift as u32, false, RoundingMode::TiesToAway).0 as u32
+}
+
+/// Packs three binary32s into RGB9E5, the red mantissa in the low bits and the shared exponent in the top 5, as
+/// EXT_texture_shared_exponent specifies: each channel is clamped to [0, 65408] (nans become 0), the exponent is
+/// the one that holds the largest of them, and each mantissa is rounded half up.
+#[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+pub fn rgb9e5_from_binary32(rgb: [F32; 3]) -> u32 {
+    let rgb = rgb.map(|x| rgb9e5_clamp(&x));
+    let largest = rgb.iter().max_by_key(|x| x.to_bits()).copied().unwrap_or_default();
+    // the spec's max(-B - 1, floor(log2(largest))) + 1 + B
+    let log2 = if largest.is_zero() {
+        -RGB9E5_BIAS - 1
+    } else {
+        let u = largest.unpack();
+        (u.exponent + 127 - u.significand.leading_zeros() as i32).max(-RGB9E5_BIAS - 1)
+    };
+    let mut exponent = log2 + 1 + RGB9E5_BIAS;
+    // rounding the largest mantissa up can carry it to 2^9, a binade higher
+    if rgb9e5_mantissa(&largest, exponent - RGB9E5_BIAS - RGB9E5_MANTISSA_BITS) == 1 << RGB9E5_MANTISSA_BITS {
+        exponent += 1;
+    }
+    let scale = exponent - RGB9E5_BIAS - RGB9E5_MANTISSA_BITS;
+    let [r, g, b] = rgb.map(|x| rgb9e5_mantissa(&x, scale));
+    r | g << 9 | b << 18 | (exponent as u32) << 27
+}
+
+/// Unpacks RGB9E5 into three binary32s, exactly.
+#[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+pub fn rgb9e5_to_binary32(packed: u32) -> [F32; 3] {
+    let scale = (packed >> 27) as i32 - RGB9E5_BIAS - RGB9E5_MANTISSA_BITS;
+    [0, 9, 18].map(|shift| {
+        let mantissa = (packed >> shift & 0x1FF) as u128;
+        Wide::new(false, scale, mantissa).round(&mut Context::default())
+    })
+}
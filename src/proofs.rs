@@ -0,0 +1,114 @@
+// kani proof harnesses: the random checks in main.rs sample the input space, these cover all of it.
+// run with `cargo kani` (or `cargo kani --harness <name>` for one). multiply and add take a few minutes each.
+
+use crate::format::Binary32;
+use crate::{Context, Flags, Float, RoundingMode, F64};
+
+fn any_mode() -> RoundingMode {
+    match kani::any::<u8>() % 5 {
+        0 => RoundingMode::TiesToEven,
+        1 => RoundingMode::TiesToAway,
+        2 => RoundingMode::TowardZero,
+        3 => RoundingMode::TowardPositive,
+        _ => RoundingMode::TowardNegative,
+    }
+}
+
+// what every arithmetic result has to satisfy, whatever the operands
+fn check_result(result: &Float, flags: Flags, operand_infinite: bool) {
+    assert!(!result.is_signaling_nan()); // nans come out quiet
+    if flags.contains(Flags::OVERFLOW) || flags.contains(Flags::UNDERFLOW) {
+        assert!(flags.contains(Flags::INEXACT));
+    }
+    if result.is_infinity() && !operand_infinite {
+        assert!(flags.contains(Flags::OVERFLOW) || flags.contains(Flags::DIVIDE_BY_ZERO));
+    }
+}
+
+#[kani::proof]
+fn multiply_result_is_consistent() {
+    let a = Float::from_bits(kani::any());
+    let b = Float::from_bits(kani::any());
+    let mut ctx = Context::new(any_mode());
+    let result = ctx.multiply(&a, &b);
+    check_result(&result, ctx.flags, a.is_infinity() || b.is_infinity());
+}
+
+#[kani::proof]
+fn add_result_is_consistent() {
+    let a = Float::from_bits(kani::any());
+    let b = Float::from_bits(kani::any());
+    let mut ctx = Context::new(any_mode());
+    let result = ctx.add(&a, &b);
+    check_result(&result, ctx.flags, a.is_infinity() || b.is_infinity());
+}
+
+#[kani::proof]
+fn add_is_commutative() {
+    let a = Float::from_bits(kani::any());
+    let b = Float::from_bits(kani::any());
+    kani::assume(!a.is_nan() && !b.is_nan()); // which nan payload wins depends on the order
+    let mode = any_mode();
+    assert_eq!(a.add_with_rounding(&b, mode).to_bits(), b.add_with_rounding(&a, mode).to_bits());
+}
+
+// a carry out of the rounded significand must land in the exponent, never in the sign
+#[kani::proof]
+fn rounding_carry_stays_in_the_exponent() {
+    let sign: bool = kani::any();
+    let exponent: i16 = kani::any();
+    kani::assume((-1022..=1023).contains(&exponent));
+    let significand: u64 = kani::any();
+    kani::assume(significand >> 52 == 1 || significand == 1 << 53); // what shift_and_round returns for a normal
+
+    let result = Float::pack_rounded(sign, exponent, significand);
+    assert_eq!(result.get_sign(), sign);
+    if significand == 1 << 53 {
+        // 1.11..1 rounded up to 10.0: the next binade, or infinity from the top one
+        assert_eq!(result.get_exponent(), exponent + 1);
+        assert_eq!(result.get_mantissa(), 0);
+    } else {
+        assert_eq!(result.get_exponent(), exponent);
+        assert_eq!(result.get_mantissa(), significand & ((1 << 52) - 1));
+    }
+}
+
+// the largest subnormal rounding up has to become the smallest normal
+#[kani::proof]
+fn subnormal_carry_reaches_min_normal() {
+    let sign: bool = kani::any();
+    let significand: u64 = kani::any();
+    kani::assume(significand <= 1 << 52);
+
+    let result = Float::pack_rounded(sign, -1023, significand);
+    assert_eq!(result.get_sign(), sign);
+    assert_eq!(result.to_bits() & !(1 << 63), significand);
+}
+
+#[kani::proof]
+fn from_parts_round_trip() {
+    let f = Float::from_bits(kani::any());
+    let g = Float::from_parts(f.get_sign(), f.get_exponent(), f.get_mantissa());
+    assert_eq!(f.to_bits(), g.to_bits());
+}
+
+#[kani::proof]
+fn next_up_is_one_ulp_above() {
+    let f = Float::from_bits(kani::any());
+    kani::assume(!f.is_nan() && f.to_bits() != 0x7FF0000000000000);
+    let up = f.next_up();
+    assert_eq!(f.ulp_distance(&up), 1);
+    assert!(up.to_f64() > f.to_f64());
+}
+
+// every binary32 value survives a trip through binary64 and back, exactly
+#[kani::proof]
+fn f32_widen_narrow_round_trip() {
+    let bits: u32 = kani::any();
+    kani::assume((bits >> 23) & 0xFF != 0xFF || bits & ((1 << 23) - 1) == 0); // nans get quieted, so leave them out
+    let mut ctx = Context::new(any_mode());
+    let wide = F64::from(ctx.f32_to_f64(bits));
+    let narrow = wide.convert::<Binary32>(&mut ctx);
+    assert_eq!(narrow.to_bits(), bits);
+    assert!(ctx.flags.is_empty());
+}
@@ -0,0 +1,186 @@
+// pyo3 bindings, so notebooks can use the soft implementation as a reference against numpy.
+// build with `maturin develop -m bindings/Cargo.toml --features python`, then `import floatfs`.
+
+use pyo3::prelude::*;
+
+use crate::{Context, Flags, Float, RoundingMode};
+
+#[pyclass(name = "Float", frozen)]
+pub struct PyFloat {
+    inner: Float,
+}
+
+impl PyFloat {
+    fn wrap(inner: Float) -> PyFloat {
+        PyFloat { inner }
+    }
+}
+
+#[pymethods]
+impl PyFloat {
+    #[new]
+    fn new(value: f64) -> Self {
+        PyFloat::wrap(Float::new(value))
+    }
+
+    #[staticmethod]
+    fn from_bits(bits: u64) -> Self {
+        PyFloat::wrap(Float::from_bits(bits))
+    }
+
+    #[staticmethod]
+    fn from_parts(sign: bool, exponent: i16, mantissa: u64) -> Self {
+        PyFloat::wrap(Float::from_parts(sign, exponent, mantissa))
+    }
+
+    #[getter]
+    fn bits(&self) -> u64 {
+        self.inner.to_bits()
+    }
+
+    #[getter]
+    fn sign(&self) -> bool {
+        self.inner.get_sign()
+    }
+
+    #[getter]
+    fn exponent(&self) -> i16 {
+        self.inner.get_exponent()
+    }
+
+    #[getter]
+    fn mantissa(&self) -> u64 {
+        self.inner.get_mantissa()
+    }
+
+    #[getter]
+    fn classification(&self) -> String {
+        format!("{:?}", self.inner.classify()).to_lowercase()
+    }
+
+    fn next_up(&self) -> Self {
+        PyFloat::wrap(self.inner.next_up())
+    }
+
+    fn next_down(&self) -> Self {
+        PyFloat::wrap(self.inner.next_down())
+    }
+
+    fn ulp_distance(&self, other: &PyFloat) -> u64 {
+        self.inner.ulp_distance(&other.inner)
+    }
+
+    #[pyo3(signature = (other, rounding = RoundingMode::TiesToEven))]
+    fn multiply(&self, other: &PyFloat, rounding: RoundingMode) -> Self {
+        PyFloat::wrap(self.inner.multiply_with_rounding(&other.inner, rounding))
+    }
+
+    #[pyo3(signature = (other, rounding = RoundingMode::TiesToEven))]
+    fn add(&self, other: &PyFloat, rounding: RoundingMode) -> Self {
+        PyFloat::wrap(self.inner.add_with_rounding(&other.inner, rounding))
+    }
+
+    // the stage-by-stage breakdown as text
+    #[pyo3(signature = (other, rounding = RoundingMode::TiesToEven))]
+    fn explain_multiply(&self, other: &PyFloat, rounding: RoundingMode) -> String {
+        self.inner.multiply_explained_with_rounding(&other.inner, rounding).to_string()
+    }
+
+    fn layout(&self) -> String {
+        self.inner.layout(false)
+    }
+
+    fn __mul__(&self, other: &PyFloat) -> Self {
+        PyFloat::wrap(self.inner.multiply(&other.inner))
+    }
+
+    fn __add__(&self, other: &PyFloat) -> Self {
+        PyFloat::wrap(self.inner.add(&other.inner))
+    }
+
+    fn __float__(&self) -> f64 {
+        self.inner.to_f64()
+    }
+
+    // compares bits, so nans with the same payload are equal and -0.0 != 0.0
+    fn __eq__(&self, other: &PyFloat) -> bool {
+        self.inner.to_bits() == other.inner.to_bits()
+    }
+
+    fn __hash__(&self) -> u64 {
+        self.inner.to_bits()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Float({:?}, bits=0x{:016x})", self.inner.to_f64(), self.inner.to_bits())
+    }
+}
+
+/// Holds the rounding mode so a notebook can set it once instead of passing it to every call,
+/// and collects the exception flags the operations raise.
+#[pyclass(name = "Context")]
+pub struct PyContext {
+    inner: Context,
+}
+
+#[pymethods]
+impl PyContext {
+    #[new]
+    #[pyo3(signature = (rounding = RoundingMode::TiesToEven))]
+    fn new(rounding: RoundingMode) -> Self {
+        PyContext { inner: Context::new(rounding) }
+    }
+
+    #[getter]
+    fn rounding(&self) -> RoundingMode {
+        self.inner.rounding
+    }
+
+    #[setter]
+    fn set_rounding(&mut self, rounding: RoundingMode) {
+        self.inner.rounding = rounding;
+    }
+
+    // names of the raised flags, e.g. ["inexact", "underflow"]
+    #[getter]
+    fn flags(&self) -> Vec<&'static str> {
+        let names = [
+            (Flags::INEXACT, "inexact"),
+            (Flags::UNDERFLOW, "underflow"),
+            (Flags::OVERFLOW, "overflow"),
+            (Flags::DIVIDE_BY_ZERO, "divide_by_zero"),
+            (Flags::INVALID, "invalid"),
+            (Flags::INPUT_DENORMAL, "input_denormal"),
+            (Flags::OUTPUT_DENORMAL, "output_denormal"),
+        ];
+        names.iter().filter(|(flag, _)| self.inner.flags.contains(*flag)).map(|(_, name)| *name).collect()
+    }
+
+    fn clear_flags(&mut self) {
+        self.inner.take_flags();
+    }
+
+    fn multiply(&mut self, a: &PyFloat, b: &PyFloat) -> PyFloat {
+        PyFloat::wrap(self.inner.multiply(&a.inner, &b.inner))
+    }
+
+    fn add(&mut self, a: &PyFloat, b: &PyFloat) -> PyFloat {
+        PyFloat::wrap(self.inner.add(&a.inner, &b.inner))
+    }
+
+    fn subtract(&mut self, a: &PyFloat, b: &PyFloat) -> PyFloat {
+        PyFloat::wrap(self.inner.subtract(&a.inner, &b.inner))
+    }
+
+    fn explain_multiply(&self, a: &PyFloat, b: &PyFloat) -> String {
+        a.inner.multiply_explained_with_rounding(&b.inner, self.inner.rounding).to_string()
+    }
+}
+
+#[pymodule]
+fn floatfs(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyFloat>()?;
+    m.add_class::<PyContext>()?;
+    m.add_class::<RoundingMode>()?;
+    Ok(())
+}
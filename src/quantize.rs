@@ -0,0 +1,351 @@
+// quantizing f32 tensors to the narrow formats machine learning stores and multiplies in: bfloat16, IEEE half, the
+// two OCP FP8 formats and the FP6 and FP4 elements of the OCP microscaling (MX) formats, for emulating
+// low-precision training and inference without the hardware. everything is integer work on the unpacked f32 (no
+// host float ops), so results are the same on every machine.
+//
+//   let mut codes = [0u8; 4];
+//   let stats = quantize(&[0.1, -3.0, 500.0, 1e-9], &mut codes, Element::E4M3, Rounding::NearestEven, Scaling::Amax,
+//                        Saturation::NonSaturating);
+//   let mut back = [0f32; 4];
+//   dequantize(&codes, &mut back, Element::E4M3, stats.scale_exponent);
+//
+// E4M3 isn't an IEEE layout, so it can't be a Format: it has no infinities and gives its top binade to finite
+// values, all but S.1111.111, the only nan, so its largest value is 448 and not 240. the MX elements go further and
+// have neither: every encoding is finite, so overflow, infinities and nans all saturate to the largest magnitude, as
+// the MX spec's conversion does. the other three are IEEE-like. overflow goes to infinity, or to the nan for E4M3,
+// as the OCP spec's non-saturating conversion does, or with Saturation::Saturating to the largest finite value,
+// the way E4M3 is mostly used in practice.
+//
+// there's no arithmetic in the narrow formats themselves: Element::apply widens to binary64, where the operation
+// is exact or rounds with more than twice the bits (so rounding again can't go wrong), and rounds once back.
+//
+// stochastic rounding rounds up with probability (dropped bits) / (one ulp), so the result is right on average,
+// which is what keeps tiny gradient updates from all rounding away. the random bits come from splitmix64 on a
+// seed, so a run is reproducible.
+
+use crate::format::Storage;
+use crate::soft::shift_round;
+use crate::wide::Wide;
+use crate::{Context, Operation, RoundingMode, F32, F64};
+
+/// A narrow format to quantize to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Element {
+    BFloat16, // 8 exponent bits and 7 mantissa bits: f32's range at a third of its precision
+    Binary16, // IEEE half, 5 and 10
+    E4M3, // OCP FP8 (E4M3FN), 4 and 3: no infinities, one nan per sign, largest finite 448
+    E5M2, // OCP FP8, 5 and 2, IEEE-like: largest finite 57344
+    E2M3, // OCP MX FP6, 2 and 3: no infinities or nans, largest 7.5
+    E3M2, // OCP MX FP6, 3 and 2: no infinities or nans, largest 28
+    E2M1, // OCP MX FP4, 2 and 1: no infinities or nans, largest 6
+}
+
+/// How a quantizer rounds values that fall between two representable ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Rounding {
+    #[default]
+    NearestEven,
+    Stochastic(u64), // up or down at random, in proportion to the distance; the u64 seeds the generator
+}
+
+/// Per-tensor scaling: inputs are multiplied by 2^k before rounding, which is exact, and dequantize divides it
+/// back out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Scaling {
+    #[default]
+    Unscaled, // k = 0
+    Amax, // the largest k that keeps the largest finite |x| within the format, so nothing overflows
+    Exponent(i32), // a given k, such as one carried over from an earlier step (delayed scaling)
+}
+
+/// What a quantize call does with finite values past the largest finite one. Either way they count as overflows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Saturation {
+    #[default]
+    NonSaturating, // to infinity, or the nan for E4M3, as the OCP FP8 spec's default conversion does
+    Saturating, // to the largest finite value with the sign; infinities too, as nvidia's satfinite conversion does
+}
+
+/// What a quantize call did.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct QuantizeStats {
+    pub scale_exponent: i32, // the k the inputs were scaled by 2^k with
+    pub overflows: usize, // finite inputs that became infinities (nans for E4M3, the largest magnitude for MX)
+    pub underflows: usize, // nonzero inputs that became zeros
+    pub max_error: f64, // the largest |x - dequantized|, in the inputs' units, over the results that didn't overflow
+}
+
+impl Element {
+    pub fn exponent_bits(self) -> u32 {
+        match self {
+            Element::BFloat16 => 8,
+            Element::Binary16 | Element::E5M2 => 5,
+            Element::E4M3 => 4,
+            Element::E3M2 => 3,
+            Element::E2M3 | Element::E2M1 => 2,
+        }
+    }
+
+    pub fn mantissa_bits(self) -> u32 {
+        match self {
+            Element::BFloat16 => 7,
+            Element::Binary16 => 10,
+            Element::E4M3 | Element::E2M3 => 3,
+            Element::E5M2 | Element::E3M2 => 2,
+            Element::E2M1 => 1,
+        }
+    }
+
+    /// The encoding's width, sign included.
+    pub fn bits(self) -> u32 {
+        1 + self.exponent_bits() + self.mantissa_bits()
+    }
+
+    pub fn has_infinity(self) -> bool {
+        matches!(self, Element::BFloat16 | Element::Binary16 | Element::E5M2)
+    }
+
+    pub fn has_nan(self) -> bool {
+        self.has_infinity() || self == Element::E4M3
+    }
+
+    fn bias(self) -> i32 {
+        (1 << (self.exponent_bits() - 1)) - 1
+    }
+
+    // encodings without the sign bit
+    fn max_code(self) -> u32 {
+        let (e, m) = (self.exponent_bits(), self.mantissa_bits());
+        if self.has_infinity() {
+            (((1 << e) - 1) << m) - 1
+        } else if self.has_nan() {
+            (1 << (e + m)) - 2
+        } else {
+            (1 << (e + m)) - 1
+        }
+    }
+
+    fn infinity_code(self) -> u32 {
+        ((1 << self.exponent_bits()) - 1) << self.mantissa_bits()
+    }
+
+    fn nan_code(self) -> u32 {
+        if self.has_infinity() {
+            self.infinity_code() | (1 << (self.mantissa_bits() - 1))
+        } else {
+            (1 << (self.bits() - 1)) - 1
+        }
+    }
+
+    fn sign_bit(self, sign: bool) -> u32 {
+        (sign as u32) << (self.bits() - 1)
+    }
+
+    // what an infinity or nan encodes as: its own encoding if there is one, E4M3's nan for its infinities, and the
+    // largest magnitude in the MX formats, which have neither. overflow goes the same way
+    fn special_code(self, sign: bool, nan: bool) -> u32 {
+        let code = if self.has_nan() && (nan || !self.has_infinity()) {
+            self.nan_code()
+        } else if self.has_infinity() {
+            self.infinity_code()
+        } else {
+            self.max_code()
+        };
+        self.sign_bit(sign) | code
+    }
+
+    // the value of an encoding as (sign, exponent, significand), significand * 2^exponent, or None for
+    // infinities and nans. zeros have a zero significand
+    fn unpack(self, code: u32) -> Option<(bool, i32, u32)> {
+        let m = self.mantissa_bits();
+        let magnitude = code & ((1 << (self.bits() - 1)) - 1);
+        let sign = code >> (self.bits() - 1) & 1 == 1;
+        if magnitude > self.max_code() {
+            return None;
+        }
+        let (biased, fraction) = ((magnitude >> m) as i32, magnitude & ((1 << m) - 1));
+        if biased == 0 {
+            Some((sign, 1 - self.bias() - m as i32, fraction))
+        } else {
+            Some((sign, biased - self.bias() - m as i32, fraction | (1 << m)))
+        }
+    }
+
+    // rounds sign * significand * 2^exponent (significand nonzero) to an encoding, as round_pack does for a Format,
+    // randomly if given random bits. also says whether it overflowed
+    fn encode(self, sign: bool, exponent: i32, significand: u128, random: Option<u64>) -> (u32, bool) {
+        let m = self.mantissa_bits() as i32;
+        let min_exponent = 1 - self.bias();
+        let e = exponent + 127 - significand.leading_zeros() as i32; // the value is 1.xxx * 2^e
+        let subnormal = e < min_exponent;
+        let shift = if subnormal { min_exponent } else { e } - m - exponent; // bits to drop to keep m + 1
+        let rounded = if shift <= 0 {
+            significand << shift.unsigned_abs()
+        } else if let Some(random) = random {
+            stochastic(significand, shift as u32, random)
+        } else {
+            shift_round(significand, shift as u32, sign, RoundingMode::TiesToEven).0
+        };
+        // adding lets a carry out of the mantissa bump the exponent, as in round_pack
+        let biased = if subnormal { 0 } else { (e + self.bias() - 1) as u128 };
+        let code = (biased << m) + rounded;
+        if code > self.max_code() as u128 {
+            return (self.special_code(sign, false), true);
+        }
+        (self.sign_bit(sign) | code as u32, false)
+    }
+
+    // an encoding's value in binary64, exactly
+    fn widen(self, code: u32) -> F64 {
+        let code = code & ((1 << self.bits()) - 1);
+        let sign = code >> (self.bits() - 1) & 1 == 1;
+        match self.unpack(code) {
+            Some((sign, exponent, significand)) => {
+                Wide::new(sign, exponent, significand as u128).round(&mut Context::default())
+            }
+            None if self.has_infinity() && code & !self.sign_bit(true) == self.infinity_code() => F64::infinity(sign),
+            None => F64::nan(),
+        }
+    }
+
+    /// The encoding of a op b, rounded to nearest even as if the format had arithmetic of its own. The operation
+    /// is done in binary64, which holds every sum and product exactly and has more than 2p + 2 bits for a
+    /// quotient, so rounding that again is still correct. Results the format can't hold become what quantize
+    /// makes of them.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn apply(self, op: Operation, a: u32, b: u32) -> u32 {
+        let x = op.apply(&self.widen(a), &self.widen(b), &mut Context::default());
+        if x.is_nan() || x.is_infinity() {
+            return self.special_code(x.get_sign(), x.is_nan());
+        }
+        if x.is_zero() {
+            return self.sign_bit(x.get_sign());
+        }
+        let u = x.unpack();
+        self.encode(u.sign, u.exponent, u.significand, None).0
+    }
+}
+
+// significand / 2^shift rounded down, plus one with probability (dropped bits) / 2^shift. only the top 64 of the
+// dropped bits count, so a chance below 2^-64 is taken as zero
+fn stochastic(significand: u128, shift: u32, random: u64) -> u128 {
+    let (kept, dropped) = if shift >= 128 {
+        (0, significand)
+    } else {
+        (significand >> shift, significand & ((1 << shift) - 1))
+    };
+    // dropped + r carries past 2^shift for a uniform r in [0, 2^shift) exactly that often
+    let up = if shift <= 64 {
+        (dropped + (random >> (64 - shift)) as u128) >> shift != 0
+    } else {
+        (dropped.checked_shr(shift - 64).unwrap_or(0) + random as u128) >> 64 != 0
+    };
+    kept + up as u128
+}
+
+// splitmix64
+fn next_random(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E3779B97F4A7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+// the largest k with amax * 2^k <= the largest finite value
+fn amax_exponent(input: &[f32], element: Element) -> i32 {
+    let amax = input.iter().map(|x| x.to_bits() & 0x7FFFFFFF).filter(|&bits| bits < 0x7F800000).max().unwrap_or(0);
+    let Some((_, exponent, significand)) = element.unpack(element.max_code()) else { return 0 };
+    if amax == 0 {
+        return 0;
+    }
+    let (amax, max) = (Wide::from_soft(&F32::from_bits(amax)), Wide::new(false, exponent, significand as u128));
+    max.exponent - amax.exponent - (amax.significand > max.significand) as i32
+}
+
+/// Quantizes input to output, element by element over the shorter of the two, as encodings in the low bits of T
+/// (u8 for the FP8, FP6 and FP4 formats, u16 for the others; a narrower T keeps only the low bits). Nans stay nans
+/// with their sign, and infinities become infinities, or nans in E4M3, without counting as overflows; saturating,
+/// or in the MX formats, infinities become the largest magnitude with their sign, and so do nans in the MX
+/// formats, which have no nan.
+#[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+pub fn quantize<T: Storage>(
+    input: &[f32],
+    output: &mut [T],
+    element: Element,
+    rounding: Rounding,
+    scaling: Scaling,
+    saturation: Saturation,
+) -> QuantizeStats {
+    let k = match scaling {
+        Scaling::Unscaled => 0,
+        Scaling::Amax => amax_exponent(input, element),
+        Scaling::Exponent(k) => k,
+    };
+    let mut stats = QuantizeStats { scale_exponent: k, ..QuantizeStats::default() };
+    // past 2^±1000 every f32 overflows or underflows anyway, and this keeps the exponents clear of i32's limits
+    let k = k.clamp(-1000, 1000);
+    let mut state = match rounding {
+        Rounding::NearestEven => 0,
+        Rounding::Stochastic(seed) => seed,
+    };
+    let mut max_error = F64::zero(false);
+    let mut ctx = Context::default();
+    for (x, out) in input.iter().zip(output.iter_mut()) {
+        let x = F32::from_bits(x.to_bits());
+        let random = match rounding {
+            Rounding::NearestEven => None,
+            Rounding::Stochastic(_) => Some(next_random(&mut state)),
+        };
+        let code = if x.is_infinity() && saturation == Saturation::Saturating {
+            element.sign_bit(x.get_sign()) | element.max_code()
+        } else if x.is_nan() || x.is_infinity() {
+            element.special_code(x.get_sign(), x.is_nan())
+        } else if x.is_zero() {
+            element.sign_bit(x.get_sign())
+        } else {
+            let u = x.unpack();
+            let (mut code, overflowed) = element.encode(u.sign, u.exponent + k, u.significand, random);
+            if overflowed {
+                stats.overflows += 1;
+                if saturation == Saturation::Saturating {
+                    code = element.sign_bit(u.sign) | element.max_code();
+                }
+            } else if let Some((sign, exponent, significand)) = element.unpack(code) {
+                stats.underflows += (significand == 0) as usize;
+                // |x - q / 2^k|, exactly and then rounded once
+                let q = Wide::new(sign, exponent - k, significand as u128);
+                let difference = Wide::new(u.sign, u.exponent, u.significand).sub(q);
+                let error: F64 = Wide { sign: false, ..difference }.round(&mut ctx);
+                if error.compare(&max_error) == Some(core::cmp::Ordering::Greater) {
+                    max_error = error;
+                }
+            }
+            code
+        };
+        *out = T::from_u128(code as u128);
+    }
+    stats.max_error = f64::from_bits(max_error.to_bits());
+    stats
+}
+
+/// The values of encodings, divided by 2^scale_exponent (the stats' from quantize) and rounded to nearest if that
+/// takes them out of f32's range, element by element over the shorter of input and output.
+#[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+pub fn dequantize<T: Storage>(input: &[T], output: &mut [f32], element: Element, scale_exponent: i32) {
+    for (code, out) in input.iter().zip(output.iter_mut()) {
+        let code = code.to_u128() as u32 & ((1 << element.bits()) - 1);
+        let sign = code >> (element.bits() - 1) & 1 == 1;
+        let k = scale_exponent.clamp(-1000, 1000); // as in quantize
+        let value = match element.unpack(code) {
+            Some((sign, exponent, significand)) => {
+                Wide::new(sign, exponent - k, significand as u128).round(&mut Context::default())
+            }
+            None if element.has_infinity() && code & !element.sign_bit(true) == element.infinity_code() => {
+                F32::infinity(sign)
+            }
+            None => F32::from_bits(F32::nan().to_bits() | (sign as u32) << 31),
+        };
+        *out = f32::from_bits(value.to_bits());
+    }
+}
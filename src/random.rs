@@ -0,0 +1,108 @@
+// uniformly distributed floats, built from random bits without any float arithmetic. the bits come from a closure
+// handing out uniformly random u64s, so this works with any generator and without std:
+//
+//   let mut rng = rand::rng();
+//   let x: F64 = random::unit(&mut || rng.random());
+//
+// unit is the usual construction, the top m + 1 bits over 2^(m + 1): evenly spaced values, which skips most of the
+// floats below 1/2 (and every one below 2^-(m + 1) but zero). unit_dense gives every float in [0, 1), each with the
+// chance of a uniform real landing in the gap between it and the next one up: the exponent from the position of the
+// first one bit in an endless run of random bits (so each binade gets half the chance of the one above), then the
+// mantissa uniformly. a binary64 below 2^-64 takes more than one draw, but that's a 2^-64 chance.
+//
+// special is for testing rather than sampling: it picks the encodings where implementations go wrong far more
+// often than uniform bits would.
+
+use core::cmp::Ordering;
+
+use crate::format::Extended;
+use crate::wide::Wide;
+use crate::{Context, Format, RoundingMode, SoftFloat};
+
+/// A uniform multiple of 2^-(m + 1) in [0, 1), m being the format's mantissa bits, from one draw.
+#[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+pub fn unit<F: Format>(bits: &mut impl FnMut() -> u64) -> SoftFloat<F> {
+    let precision = F::MANTISSA_BITS + 1;
+    let k = bits() >> (64 - precision);
+    if k == 0 {
+        return SoftFloat::zero(false);
+    }
+    SoftFloat::round_pack(false, -(precision as i32), k as u128, &mut Context::default())
+}
+
+/// Any float in [0, 1), each with probability equal to its distance to the next float up: the uniform real, rounded
+/// down. Zero comes out with the chance of the smallest subnormal.
+#[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+pub fn unit_dense<F: Format>(bits: &mut impl FnMut() -> u64) -> SoftFloat<F> {
+    let m = F::MANTISSA_BITS;
+    let bias = (1 << (F::EXPONENT_BITS - 1)) - 1;
+    // the value is in [2^e, 2^(e + 1)) with probability 2^e, e = -1 - (leading zeros), until it's below the normals
+    let mut e = -1;
+    loop {
+        let r = bits();
+        e -= r.leading_zeros() as i32;
+        if r != 0 || e < 1 - bias {
+            break;
+        }
+    }
+    let mantissa = if m == 0 { 0 } else { (bits() >> (64 - m)) as u128 };
+    // below the normals every subnormal is equally likely, as their gaps are all the same
+    let biased = if e < 1 - bias { 0 } else { (e + bias) as u128 };
+    SoftFloat::from_raw(biased << m | mantissa)
+}
+
+/// A float in [low, high): a uniform real there, rounded down, so each float gets the share of the range up to the
+/// next one (to within 2^-64 of the range). The real is low + (high - low) u for a dense u in [0, 1) with a 64-bit
+/// significand, worked out exactly and rounded once. A zero is +0 unless low is -0. None unless low < high, both
+/// finite.
+#[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+pub fn range<F: Format>(
+    low: &SoftFloat<F>,
+    high: &SoftFloat<F>,
+    bits: &mut impl FnMut() -> u64,
+) -> Option<SoftFloat<F>> {
+    if low.is_infinity() || high.is_infinity() || low.compare(high) != Some(Ordering::Less) {
+        return None;
+    }
+    let u = Wide::from_soft(&unit_dense::<Extended>(bits));
+    let (low, high) = (Wide::from_soft(low), Wide::from_soft(high));
+    let value = Wide::sum3([low, high.mul(u), low.mul(u).neg()]);
+    if value.is_zero() {
+        return Some(SoftFloat::zero(low.is_zero() && low.sign)); // not the -0 an exact zero rounded down would be
+    }
+    Some(value.round(&mut Context::new(RoundingMode::TowardNegative)))
+}
+
+/// Any encoding, heavily biased toward the ones arithmetic gets wrong: zeros, infinities, quiet and signaling nans,
+/// subnormals and the ends of their range, the smallest normal and the largest finite value, the binades next to
+/// those, and values near 1. A quarter of the draws are uniformly random bits. Either sign, equally.
+#[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+pub fn special<F: Format>(bits: &mut impl FnMut() -> u64) -> SoftFloat<F> {
+    let (m, e) = (F::MANTISSA_BITS, F::EXPONENT_BITS);
+    let r = bits();
+    let mut random = || (bits() as u128) << 64 | bits() as u128;
+    if r % 16 >= 12 {
+        return SoftFloat::from_raw(random() & ((1 << (1 + e + m)) - 1));
+    }
+    let sign = (r >> 63) as u128;
+    let pick = (r >> 8) as u128;
+    let (top, all) = ((1u128 << e) - 1, (1u128 << m) - 1); // the exponent of infinities and nans, a full mantissa
+    let quiet = 1 << (m - 1);
+    let mantissa = random() & all;
+    let few = mantissa >> (pick % (m as u128 + 1)); // random, but often only its last few bits
+    let (exponent, mantissa) = match r % 16 {
+        0 => (0, 0),                                 // zero
+        1 => (top, 0),                               // infinity
+        2 => (top, quiet | mantissa),                // quiet nan
+        3 => (top, (mantissa & (quiet - 1)).max(1)), // signaling nan
+        4 => (0, mantissa.max(1)),                   // subnormal
+        5 => (0, 1.max(few)),                        // near the smallest subnormal
+        6 => (0, all - few),                         // near the largest subnormal
+        7 => (1, few),                               // near the smallest normal
+        8 => (top - 1, all - few),                   // near the largest finite value
+        9 => (top >> 1, few),                        // 1 plus a few ulps
+        10 => ((1 + pick % 4).min(top - 1), mantissa), // the lowest normal binades
+        _ => ((top - 1).saturating_sub(pick % 4).max(1), mantissa), // the highest
+    };
+    SoftFloat::from_raw(sign << (e + m) | exponent << m | mantissa)
+}
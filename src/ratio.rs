@@ -0,0 +1,72 @@
+// exact fractions from Floats, with num-bigint integers (the bigint feature). every finite double is a dyadic
+// rational, n / 2^k, so the exact fraction is just the significand and exponent with the trailing zeros cancelled.
+// best_rational goes the other way, for the 0.1 that came out of a computation and should have been 1/10: the
+// closest fraction with a denominator under a bound, from the continued fraction of the exact value.
+
+use num_bigint::{BigInt, Sign};
+
+use crate::Float;
+
+impl Float {
+    /// The exact value as (numerator, denominator) in lowest terms. The denominator is a positive power of two:
+    /// 0.1 is 3602879701896397 / 36028797018963968. Both zeros give 0 / 1; infinities and nans give None.
+    pub fn to_exact_ratio(&self) -> Option<(BigInt, BigInt)> {
+        if self.is_nan() || self.is_infinity() {
+            return None;
+        }
+        if self.is_zero() {
+            return Some((BigInt::ZERO, BigInt::from(1)));
+        }
+        let bits = self.to_bits();
+        let (biased, fraction) = ((bits >> 52 & 0x7FF) as i32, bits & ((1 << 52) - 1));
+        let (significand, exponent) = if biased == 0 { (fraction, -1074) } else { (fraction | 1 << 52, biased - 1075) };
+        let zeros = significand.trailing_zeros();
+        let (significand, exponent) = (significand >> zeros, exponent + zeros as i32);
+        let numerator = if self.get_sign() { -BigInt::from(significand) } else { BigInt::from(significand) };
+        if exponent >= 0 {
+            Some((numerator << exponent as usize, BigInt::from(1)))
+        } else {
+            Some((numerator, BigInt::from(1) << exponent.unsigned_abs() as usize))
+        }
+    }
+
+    /// The fraction p / q closest to the exact value with 0 < q <= max_denominator, in lowest terms. It's the
+    /// last convergent of the continued fraction whose denominator is in bounds, or the largest semiconvergent
+    /// past it when that's closer, the convergent winning a tie, as in Python's Fraction.limit_denominator. The
+    /// result for -x is minus the one for x. Infinities, nans and a max_denominator of 0 give None.
+    pub fn best_rational(&self, max_denominator: u64) -> Option<(BigInt, BigInt)> {
+        let (numerator, denominator) = self.to_exact_ratio()?;
+        if max_denominator == 0 {
+            return None;
+        }
+        let max = BigInt::from(max_denominator);
+        if denominator <= max {
+            return Some((numerator, denominator));
+        }
+        let negative = numerator.sign() == Sign::Minus;
+        let x = BigInt::from(numerator.magnitude().clone());
+        // convergents p1 / q1 (and the one before, p0 / q0) of x, while the next denominator is in bounds. the last
+        // convergent is x itself, whose denominator isn't, so the remainder d never reaches zero here
+        let (mut p0, mut q0, mut p1, mut q1) = (BigInt::ZERO, BigInt::from(1), BigInt::from(1), BigInt::ZERO);
+        let (mut n, mut d) = (x.clone(), denominator.clone());
+        loop {
+            let a = &n / &d;
+            let q2 = &q0 + &a * &q1;
+            if q2 > max {
+                break;
+            }
+            let p2 = &p0 + &a * &p1;
+            (p0, q0, p1, q1) = (p1, q1, p2, q2);
+            let r = &n - &a * &d;
+            (n, d) = (d, r);
+        }
+        let k = (&max - &q0) / &q1;
+        let (p2, q2) = (&p0 + &k * &p1, &q0 + &k * &q1);
+        // |p / q - x| scaled by the other candidate's denominator (and x's), so the two compare as integers
+        let distance = |p: &BigInt, q: &BigInt, other: &BigInt| {
+            (p * &denominator - &x * q).magnitude() * other.magnitude()
+        };
+        let (p, q) = if distance(&p1, &q1, &q2) <= distance(&p2, &q2, &q1) { (p1, q1) } else { (p2, q2) };
+        Some((if negative { -p } else { p }, q))
+    }
+}
@@ -0,0 +1,118 @@
+// the pieces of the risc-v F and D extensions that differ from plain IEEE 754, so an emulator can run its
+// float instructions straight through the generic SoftFloat ops: canonical nans, saturating fcvt, and the
+// fmin/fmax/fclass rules. Flags already uses the fflags layout (NV DZ OF UF NX from bit 4 down).
+
+use crate::{Accuracy, Context, Flags, Format, NanMode, RoundingMode, SoftFloat, Tininess};
+
+impl Context {
+    /// Round to nearest even, tininess after rounding, and canonical nans, as a risc-v hart starts out.
+    pub fn riscv() -> Context {
+        Context {
+            rounding: RoundingMode::TiesToEven,
+            tininess: Tininess::AfterRounding,
+            nan_mode: NanMode::Canonical,
+            flush_inputs: false,
+            flush_outputs: false,
+            accuracy: Accuracy::CorrectlyRounded,
+            flags: Flags::empty(),
+        }
+    }
+}
+
+/// Decodes the 3-bit rm field (of an instruction, or frm). None for the reserved encodings and for
+/// dyn (7), which means "use frm" and has to be resolved by the caller.
+pub fn rounding_from_rm(rm: u8) -> Option<RoundingMode> {
+    match rm {
+        0 => Some(RoundingMode::TiesToEven),
+        1 => Some(RoundingMode::TowardZero),
+        2 => Some(RoundingMode::TowardNegative),
+        3 => Some(RoundingMode::TowardPositive),
+        4 => Some(RoundingMode::TiesToAway),
+        _ => None,
+    }
+}
+
+/// The bits to or into fflags.
+pub fn fflags(flags: Flags) -> u8 {
+    flags.0
+}
+
+/// fcvt.w: to an i32 in mode. Out of range saturates, and nan counts as +infinity; both raise invalid.
+pub fn fcvt_w<F: Format>(x: &SoftFloat<F>, mode: RoundingMode, ctx: &mut Context) -> i32 {
+    x.to_i32(mode, ctx).unwrap_or(if x.get_sign() && !x.is_nan() { i32::MIN } else { i32::MAX })
+}
+
+/// fcvt.wu: to a u32, saturating as fcvt_w does (a negative value to 0).
+pub fn fcvt_wu<F: Format>(x: &SoftFloat<F>, mode: RoundingMode, ctx: &mut Context) -> u32 {
+    x.to_u32(mode, ctx).unwrap_or(if x.get_sign() && !x.is_nan() { 0 } else { u32::MAX })
+}
+
+/// fcvt.l: to an i64, saturating as fcvt_w does.
+pub fn fcvt_l<F: Format>(x: &SoftFloat<F>, mode: RoundingMode, ctx: &mut Context) -> i64 {
+    x.to_i64(mode, ctx).unwrap_or(if x.get_sign() && !x.is_nan() { i64::MIN } else { i64::MAX })
+}
+
+/// fcvt.lu: to a u64, saturating as fcvt_w does (a negative value to 0).
+pub fn fcvt_lu<F: Format>(x: &SoftFloat<F>, mode: RoundingMode, ctx: &mut Context) -> u64 {
+    x.to_u64(mode, ctx).unwrap_or(if x.get_sign() && !x.is_nan() { 0 } else { u64::MAX })
+}
+
+// fmin and fmax (IEEE 754-2019 minimumNumber/maximumNumber): a nan operand is ignored unless both are nan,
+// -0 is below +0, and a signaling nan raises invalid even when the other operand is returned
+fn min_max<F: Format>(a: &SoftFloat<F>, b: &SoftFloat<F>, ctx: &mut Context, want_max: bool) -> SoftFloat<F> {
+    if a.is_signaling_nan() || b.is_signaling_nan() {
+        ctx.flags.raise(Flags::INVALID);
+    }
+    match (a.is_nan(), b.is_nan()) {
+        (true, true) => return SoftFloat::nan(),
+        (true, false) => return *b,
+        (false, true) => return *a,
+        _ => {}
+    }
+    let a_smaller = match a.compare(b) {
+        Some(core::cmp::Ordering::Equal) => a.get_sign(), // only differs for zeros of opposite sign
+        ordering => ordering == Some(core::cmp::Ordering::Less),
+    };
+    if a_smaller != want_max {
+        *a
+    } else {
+        *b
+    }
+}
+
+pub fn fmin<F: Format>(a: &SoftFloat<F>, b: &SoftFloat<F>, ctx: &mut Context) -> SoftFloat<F> {
+    min_max(a, b, ctx, false)
+}
+
+pub fn fmax<F: Format>(a: &SoftFloat<F>, b: &SoftFloat<F>, ctx: &mut Context) -> SoftFloat<F> {
+    min_max(a, b, ctx, true)
+}
+
+/// The 10-bit mask fclass writes: exactly one of -inf, -normal, -subnormal, -0, +0, +subnormal, +normal,
+/// +inf, signaling nan, quiet nan (bit 0 to bit 9).
+pub fn fclass<F: Format>(x: &SoftFloat<F>) -> u16 {
+    let sign = x.get_sign();
+    let bit = if x.is_nan() {
+        if x.is_signaling_nan() {
+            8
+        } else {
+            9
+        }
+    } else {
+        let magnitude_rank = if x.is_infinity() {
+            0
+        } else if x.biased_exponent() != 0 {
+            1
+        } else if !x.is_zero() {
+            2
+        } else {
+            3
+        };
+        if sign {
+            magnitude_rank
+        } else {
+            7 - magnitude_rank
+        }
+    };
+    1 << bit
+}
@@ -0,0 +1,36 @@
+/// The five rounding-direction attributes from IEEE 754.
+#[cfg_attr(feature = "wasm", wasm_bindgen::prelude::wasm_bindgen)]
+#[cfg_attr(feature = "python", pyo3::pyclass(eq, eq_int, from_py_object))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RoundingMode {
+    #[default]
+    TiesToEven, // round to nearest, ties to even. what hardware does unless told otherwise
+    TiesToAway, // round to nearest, ties away from zero
+    TowardZero, // truncate
+    TowardPositive, // round up
+    TowardNegative, // round down
+}
+
+impl RoundingMode {
+    // decides whether a truncated magnitude should be incremented.
+    // lsb is the last kept bit, half is the first bit shifted out, sticky is whether anything below half was nonzero.
+    pub(crate) fn round_up(self, sign: bool, lsb: bool, half: bool, sticky: bool) -> bool {
+        match self {
+            RoundingMode::TiesToEven => half && (sticky || lsb),
+            RoundingMode::TiesToAway => half,
+            RoundingMode::TowardZero => false,
+            RoundingMode::TowardPositive => !sign && (half || sticky),
+            RoundingMode::TowardNegative => sign && (half || sticky),
+        }
+    }
+
+    // whether a result too large to represent becomes infinity (true) or the largest finite value (false)
+    pub(crate) fn overflows_to_infinity(self, sign: bool) -> bool {
+        match self {
+            RoundingMode::TiesToEven | RoundingMode::TiesToAway => true,
+            RoundingMode::TowardZero => false,
+            RoundingMode::TowardPositive => !sign,
+            RoundingMode::TowardNegative => sign,
+        }
+    }
+}
@@ -0,0 +1,99 @@
+// smt-lib2 output using the FloatingPoint theory, so a solver (z3, cvc5, bitwuzla) can check our arithmetic
+// against its own model of IEEE 754. `formula` states an operation on symbolic operands; `exhaustive_check`
+// adds a table of what we compute for every pair of operands and asks for one where the two disagree.
+// `z3 check.smt2` printing unsat means there is none.
+
+use std::fmt::Write;
+
+use crate::{Context, Format, RoundingMode, SoftFloat};
+
+pub use crate::Operation;
+
+impl Operation {
+    fn smt_name(self) -> &'static str {
+        match self {
+            Operation::Add => "fp.add",
+            Operation::Subtract => "fp.sub",
+            Operation::Multiply => "fp.mul",
+            Operation::Divide => "fp.div",
+        }
+    }
+}
+
+impl RoundingMode {
+    // the smt-lib name of the rounding mode
+    fn smt_name(self) -> &'static str {
+        match self {
+            RoundingMode::TiesToEven => "RNE",
+            RoundingMode::TiesToAway => "RNA",
+            RoundingMode::TowardZero => "RTZ",
+            RoundingMode::TowardPositive => "RTP",
+            RoundingMode::TowardNegative => "RTN",
+        }
+    }
+}
+
+fn width<F: Format>() -> u32 {
+    1 + F::EXPONENT_BITS + F::MANTISSA_BITS
+}
+
+// the theory counts the implicit bit in the significand width
+fn sort<F: Format>() -> String {
+    format!("(_ FloatingPoint {} {})", F::EXPONENT_BITS, F::MANTISSA_BITS + 1)
+}
+
+fn bitvector<F: Format>(value: &SoftFloat<F>) -> String {
+    format!("#b{:0width$b}", value.raw(), width = width::<F>() as usize)
+}
+
+/// Declares symbolic operands a and b in format F and defines `result` as `a op b`, rounded in `mode`.
+pub fn formula<F: Format>(op: Operation, mode: RoundingMode) -> String {
+    let mut out = String::new();
+    writeln!(out, "; {} {:?} {:?}", F::NAME, op, mode).unwrap();
+    writeln!(out, "(set-logic QF_FP)").unwrap();
+    writeln!(out, "(declare-const a {})", sort::<F>()).unwrap();
+    writeln!(out, "(declare-const b {})", sort::<F>()).unwrap();
+    writeln!(out, "(define-fun result () {} ({} {} a b))", sort::<F>(), op.smt_name(), mode.smt_name()).unwrap();
+    out
+}
+
+/// A query that is unsat exactly when our `op` agrees with the solver's on every pair of operands.
+/// Nans count as equal to each other whatever their payload, as the theory has only one.
+///
+/// The table has 2^(2 * width) rows, so this is only practical for formats of about 8 bits, like `Minifloat`.
+pub fn exhaustive_check<F: Format>(op: Operation, mode: RoundingMode) -> String {
+    let w = width::<F>();
+    let mut out = String::new();
+    writeln!(out, "; {} {:?} {:?}: unsat means every result matches", F::NAME, op, mode).unwrap();
+    writeln!(out, "(set-logic QF_FPBV)").unwrap();
+    for name in ["a_bits", "b_bits", "ours_bits"] {
+        writeln!(out, "(declare-const {} (_ BitVec {}))", name, w).unwrap();
+    }
+    let to_fp = format!("(_ to_fp {} {})", F::EXPONENT_BITS, F::MANTISSA_BITS + 1);
+    writeln!(out, "(define-fun a () {} ({} a_bits))", sort::<F>(), to_fp).unwrap();
+    writeln!(out, "(define-fun b () {} ({} b_bits))", sort::<F>(), to_fp).unwrap();
+    writeln!(out, "(define-fun ieee () {} ({} {} a b))", sort::<F>(), op.smt_name(), mode.smt_name()).unwrap();
+    writeln!(out, "(define-fun ours () {} ({} ours_bits))", sort::<F>(), to_fp).unwrap();
+
+    writeln!(out, "; what we compute, one row per pair of operands").unwrap();
+    for a in 0..1u128 << w {
+        for b in 0..1u128 << w {
+            let (x, y) = (SoftFloat::<F>::from_raw(a), SoftFloat::<F>::from_raw(b));
+            let result = op.apply(&x, &y, &mut Context::new(mode));
+            writeln!(
+                out,
+                "(assert (=> (and (= a_bits {}) (= b_bits {})) (= ours_bits {})))",
+                bitvector(&x),
+                bitvector(&y),
+                bitvector(&result)
+            )
+            .unwrap();
+        }
+    }
+
+    // smt-lib's = on floats is identity: all nans are equal and +0 is not -0
+    writeln!(out, "(assert (not (= ours ieee)))").unwrap();
+    writeln!(out, "(check-sat)").unwrap();
+    writeln!(out, "(get-model)").unwrap();
+    out
+}
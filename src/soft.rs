@@ -0,0 +1,859 @@
+use core::cmp::Ordering;
+use core::fmt;
+use core::marker::PhantomData;
+use core::num::FpCategory;
+
+use crate::format::{Binary16, Binary32, Binary64, Extended, Format, Storage};
+use crate::explain::{RoundingDecision, RoundingTrace};
+use crate::{Context, Flags, Float, NanMode, RoundingMode, Tininess};
+
+/// A value in any `Format`, with arithmetic written once for all of them.
+///
+/// `Float` stays the hand-written binary64 implementation; this is the machinery for every other width.
+///
+/// In memory it is exactly its `F::Bits`, so with the bytemuck feature a slice of them casts to and from bytes.
+/// The formats narrower than their storage (`Extended` in a u128, say) take the whole of it, upper bits zero.
+/// With the rkyv feature it archives as its bits, little-endian whatever the machine, and `get` on the archived
+/// value reads it in place; `Float` does the same.
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "rkyv", derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize))]
+#[repr(transparent)]
+pub struct SoftFloat<F: Format> {
+    bits: F::Bits,
+    format: PhantomData<F>,
+}
+
+pub type F16 = SoftFloat<Binary16>;
+pub type F32 = SoftFloat<Binary32>;
+pub type F64 = SoftFloat<Binary64>;
+pub type F80 = SoftFloat<Extended>;
+
+/// The four basic operations, for code that picks one at run time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+impl Operation {
+    pub fn apply<F: Format>(self, a: &SoftFloat<F>, b: &SoftFloat<F>, ctx: &mut Context) -> SoftFloat<F> {
+        match self {
+            Operation::Add => a.add(b, ctx),
+            Operation::Subtract => a.subtract(b, ctx),
+            Operation::Multiply => a.multiply(b, ctx),
+            Operation::Divide => a.divide(b, ctx),
+        }
+    }
+
+    /// Like `apply`, also returning what the rounding stage did (see `RoundingTrace`).
+    pub fn apply_traced<F: Format>(
+        self,
+        a: &SoftFloat<F>,
+        b: &SoftFloat<F>,
+        ctx: &mut Context,
+    ) -> (SoftFloat<F>, Option<RoundingTrace>) {
+        match self {
+            Operation::Add => a.add_traced(b, ctx),
+            Operation::Subtract => a.subtract_traced(b, ctx),
+            Operation::Multiply => a.multiply_traced(b, ctx),
+            Operation::Divide => a.divide_traced(b, ctx),
+        }
+    }
+}
+
+// a finite nonzero value, equal to significand * 2^exponent
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Unpacked {
+    pub(crate) sign: bool,
+    pub(crate) exponent: i32,
+    pub(crate) significand: u128,
+}
+
+// what an operation has just before rounding: a result it finished without rounding (a special case, or an
+// exact zero), or a value for round_pack
+enum Unrounded<F: Format> {
+    Done(SoftFloat<F>),
+    Round(Unpacked),
+}
+
+impl<F: Format> Unrounded<F> {
+    fn finish(self, ctx: &mut Context) -> SoftFloat<F> {
+        match self {
+            Unrounded::Done(x) => x,
+            Unrounded::Round(u) => SoftFloat::round_pack(u.sign, u.exponent, u.significand, ctx),
+        }
+    }
+
+    fn finish_traced(self, ctx: &mut Context) -> (SoftFloat<F>, Option<RoundingTrace>) {
+        match self {
+            Unrounded::Done(x) => (x, None),
+            Unrounded::Round(u) => {
+                let x = SoftFloat::round_pack(u.sign, u.exponent, u.significand, ctx);
+                (x, Some(SoftFloat::trace(u, &x, ctx.rounding)))
+            }
+        }
+    }
+}
+
+// shifts significand right by shift (any amount), rounding in the given mode. also returns whether anything nonzero was dropped.
+pub(crate) fn shift_round(significand: u128, shift: u32, sign: bool, mode: RoundingMode) -> (u128, bool) {
+    if shift == 0 {
+        return (significand, false);
+    }
+    let (kept, half, sticky) = if shift > 128 {
+        (0, false, significand != 0)
+    } else if shift == 128 {
+        (0, significand >> 127 == 1, significand << 1 != 0)
+    } else {
+        (
+            significand >> shift,
+            (significand >> (shift - 1)) & 1 == 1,
+            significand & ((1 << (shift - 1)) - 1) != 0,
+        )
+    };
+    (kept + mode.round_up(sign, kept & 1 == 1, half, sticky) as u128, half || sticky)
+}
+
+impl<F: Format> SoftFloat<F> {
+    const BIAS: i32 = (1 << (F::EXPONENT_BITS - 1)) - 1;
+    const MAX_BIASED: u128 = (1 << F::EXPONENT_BITS) - 1;
+    const MANTISSA_MASK: u128 = (1 << F::MANTISSA_BITS) - 1;
+    const SIGN_SHIFT: u32 = F::EXPONENT_BITS + F::MANTISSA_BITS;
+
+    pub fn from_bits(bits: F::Bits) -> Self {
+        SoftFloat { bits, format: PhantomData }
+    }
+
+    pub fn to_bits(&self) -> F::Bits {
+        self.bits
+    }
+
+    pub(crate) fn from_raw(raw: u128) -> Self {
+        SoftFloat::from_bits(F::Bits::from_u128(raw))
+    }
+
+    pub(crate) fn raw(&self) -> u128 {
+        self.bits.to_u128()
+    }
+
+    pub fn get_sign(&self) -> bool {
+        (self.raw() >> Self::SIGN_SHIFT) & 1 == 1
+    }
+
+    pub fn biased_exponent(&self) -> u32 {
+        ((self.raw() >> F::MANTISSA_BITS) & Self::MAX_BIASED) as u32
+    }
+
+    // like Float::get_exponent: the field minus the bias, so subnormals report 1 less than they're interpreted as
+    pub fn get_exponent(&self) -> i32 {
+        self.biased_exponent() as i32 - Self::BIAS
+    }
+
+    pub fn get_mantissa(&self) -> u128 {
+        self.raw() & Self::MANTISSA_MASK
+    }
+
+    /// The trailing significand field, without the implicit bit (the same bits as `get_mantissa`).
+    pub fn trailing_significand(&self) -> u128 {
+        self.get_mantissa()
+    }
+
+    /// The significand with its implicit bit, and the exponent that goes with it, as in Float::full_significand:
+    /// a finite value is significand * 2^(exponent - MANTISSA_BITS), subnormals and zeros have the smallest
+    /// normal exponent, and infinities and nans give the trailing significand and the largest exponent plus one.
+    pub fn full_significand(&self) -> (u128, i32) {
+        let biased = self.biased_exponent() as u128;
+        if biased == 0 {
+            (self.get_mantissa(), 1 - Self::BIAS)
+        } else if biased == Self::MAX_BIASED {
+            (self.get_mantissa(), self.get_exponent())
+        } else {
+            (self.get_mantissa() | 1 << F::MANTISSA_BITS, self.get_exponent())
+        }
+    }
+
+    pub fn is_nan(&self) -> bool {
+        self.biased_exponent() as u128 == Self::MAX_BIASED && self.get_mantissa() != 0
+    }
+
+    pub fn is_infinity(&self) -> bool {
+        self.biased_exponent() as u128 == Self::MAX_BIASED && self.get_mantissa() == 0
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.biased_exponent() == 0 && self.get_mantissa() == 0
+    }
+
+    pub fn is_signaling_nan(&self) -> bool {
+        self.is_nan() && (self.get_mantissa() >> (F::MANTISSA_BITS - 1)) == 0
+    }
+
+    pub fn classify(&self) -> FpCategory {
+        if self.is_nan() {
+            FpCategory::Nan
+        } else if self.is_infinity() {
+            FpCategory::Infinite
+        } else if self.is_zero() {
+            FpCategory::Zero
+        } else if self.biased_exponent() == 0 {
+            FpCategory::Subnormal
+        } else {
+            FpCategory::Normal
+        }
+    }
+
+    pub fn zero(sign: bool) -> Self {
+        SoftFloat::from_raw((sign as u128) << Self::SIGN_SHIFT)
+    }
+
+    pub fn infinity(sign: bool) -> Self {
+        SoftFloat::from_raw(((sign as u128) << Self::SIGN_SHIFT) | (Self::MAX_BIASED << F::MANTISSA_BITS))
+    }
+
+    // the default quiet nan: positive, only the quiet bit set
+    pub fn nan() -> Self {
+        SoftFloat::from_raw((Self::MAX_BIASED << F::MANTISSA_BITS) | (1 << (F::MANTISSA_BITS - 1)))
+    }
+
+    // what an invalid operation returns: x86 sets the sign of its default nan
+    pub(crate) fn default_nan(mode: NanMode) -> Self {
+        let sign = matches!(mode, NanMode::FirstOperand | NanMode::LargerSignificand) as u128;
+        SoftFloat::from_raw(Self::nan().raw() | (sign << Self::SIGN_SHIFT))
+    }
+
+    pub fn largest_finite(sign: bool) -> Self {
+        SoftFloat::from_raw(Self::infinity(sign).raw() - 1)
+    }
+
+    pub fn negate(&mut self) {
+        *self = SoftFloat::from_raw(self.raw() ^ (1 << Self::SIGN_SHIFT));
+    }
+
+    /// The smallest value above self, as Float::next_up: nans and +inf stay put, and both zeros go to the smallest
+    /// subnormal.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn next_up(&self) -> Self {
+        if self.is_nan() || self.is_infinity() && !self.get_sign() {
+            *self
+        } else if self.is_zero() {
+            SoftFloat::from_raw(1)
+        } else if self.get_sign() {
+            SoftFloat::from_raw(self.raw() - 1)
+        } else {
+            SoftFloat::from_raw(self.raw() + 1)
+        }
+    }
+
+    /// The largest value below self: -next_up(-self).
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn next_down(&self) -> Self {
+        let mut x = *self;
+        x.negate();
+        let mut x = x.next_up();
+        x.negate();
+        x
+    }
+
+    fn is_subnormal(&self) -> bool {
+        self.biased_exponent() == 0 && self.get_mantissa() != 0
+    }
+
+    // with flush_inputs set, a subnormal operand counts as a zero of the same sign. the public operations apply
+    // this and hand the operands on to an _in version, like Float's, rather than calling themselves again.
+    pub(crate) fn flushed(&self, ctx: &mut Context) -> Self {
+        if ctx.flush_inputs && self.is_subnormal() {
+            ctx.flags.raise(Flags::INPUT_DENORMAL);
+            return Self::zero(self.get_sign());
+        }
+        *self
+    }
+
+    // an exact result that skipped round_pack, like x + 0, still has to be flushed if it is subnormal
+    fn flush_output(&self, ctx: &mut Context) -> Self {
+        if ctx.flush_outputs && self.is_subnormal() {
+            ctx.flags.raise(Flags::OUTPUT_DENORMAL);
+            return Self::zero(self.get_sign());
+        }
+        *self
+    }
+
+    fn magnitude(&self) -> u128 {
+        self.raw() & !(1 << Self::SIGN_SHIFT)
+    }
+
+    // only meaningful for finite nonzero values
+    pub(crate) fn unpack(&self) -> Unpacked {
+        let biased = self.biased_exponent() as i32;
+        let (exponent, significand) = if biased == 0 {
+            (1 - Self::BIAS, self.get_mantissa()) // subnormal: interpreted with the min exponent, no implicit 1
+        } else {
+            (biased - Self::BIAS, self.get_mantissa() | (1 << F::MANTISSA_BITS))
+        };
+        Unpacked {
+            sign: self.get_sign(),
+            exponent: exponent - F::MANTISSA_BITS as i32,
+            significand,
+        }
+    }
+
+    // by default the same rule as Float::nan_logic: signaling beats quiet, then the first operand wins. the other
+    // modes are the architectures' rules (see NanMode). raises invalid for signaling nans.
+    pub(crate) fn propagate_nan(&self, other: &Self, ctx: &mut Context) -> Option<Self> {
+        let (a, b) = (self.is_nan(), other.is_nan());
+        if !a && !b {
+            return None;
+        }
+        if self.is_signaling_nan() || other.is_signaling_nan() {
+            ctx.flags.raise(Flags::INVALID);
+        }
+        let chosen = match ctx.nan_mode {
+            NanMode::Canonical => return Some(Self::nan()),
+            _ if !b => self,
+            _ if !a => other,
+            NanMode::Propagate if other.is_signaling_nan() && !self.is_signaling_nan() => other,
+            NanMode::Propagate | NanMode::FirstOperand => self,
+            NanMode::LargerSignificand if self.is_signaling_nan() != other.is_signaling_nan() => {
+                if self.is_signaling_nan() {
+                    other
+                } else {
+                    self
+                }
+            }
+            NanMode::LargerSignificand => {
+                // between equal payloads the positive one
+                if (other.magnitude(), !other.get_sign()) > (self.magnitude(), !self.get_sign()) {
+                    other
+                } else {
+                    self
+                }
+            }
+        };
+        Some(SoftFloat::from_raw(chosen.raw() | (1 << (F::MANTISSA_BITS - 1))))
+    }
+
+    /// Rounds sign * significand * 2^exponent (significand nonzero, any width) into this format,
+    /// raising inexact/underflow/overflow. Every generic operation ends here.
+    ///
+    /// Callers that had to drop bits must have jammed them into a sticky bit at least two places
+    /// below where the result will be rounded.
+    pub(crate) fn round_pack(sign: bool, exponent: i32, significand: u128, ctx: &mut Context) -> Self {
+        let mode = ctx.rounding;
+        let m = F::MANTISSA_BITS as i32;
+        let top = 127 - significand.leading_zeros() as i32; // position of the leading 1
+        let e = exponent + top; // the value is 1.xxx * 2^e
+        let min_exponent = 1 - Self::BIAS;
+
+        if e > Self::BIAS {
+            ctx.flags.raise(Flags::OVERFLOW | Flags::INEXACT);
+            if mode.overflows_to_infinity(sign) {
+                return Self::infinity(sign);
+            }
+            return Self::largest_finite(sign);
+        }
+
+        let mut shift = top - m; // how many bits to drop to keep m + 1. negative means pad with zeros
+        let subnormal = e < min_exponent;
+        let mut tiny = false;
+        if subnormal {
+            // tiny after rounding unless rounding to full precision would carry up to the min exponent
+            tiny = ctx.tininess == Tininess::BeforeRounding || e < min_exponent - 1 || {
+                let (full, _) = shift_round(significand, shift.max(0) as u32, sign, mode);
+                full >> (m + 1) == 0
+            };
+            if tiny && ctx.flush_outputs {
+                ctx.flags.raise(Flags::OUTPUT_DENORMAL);
+                return Self::zero(sign);
+            }
+            shift += min_exponent - e;
+        }
+
+        let (rounded, inexact) = if shift <= 0 {
+            (significand << (-shift) as u32, false)
+        } else {
+            shift_round(significand, shift as u32, sign, mode)
+        };
+
+        if inexact {
+            ctx.flags.raise(Flags::INEXACT);
+            if tiny {
+                ctx.flags.raise(Flags::UNDERFLOW);
+            }
+        }
+
+        // adding (instead of or-ing) lets a carry out of the mantissa bump the exponent, as in Float::pack_rounded
+        let biased = if subnormal { 0 } else { (e + Self::BIAS - 1) as u128 };
+        let result = SoftFloat::from_raw(((sign as u128) << Self::SIGN_SHIFT) | ((biased << F::MANTISSA_BITS) + rounded));
+        if result.is_infinity() {
+            ctx.flags.raise(Flags::OVERFLOW);
+        }
+        result
+    }
+
+    // what round_pack did with u to get x: the same shift it works out, the bits below it, and which way it went.
+    // kept out of round_pack itself so the untraced operations don't pay for it
+    fn trace(u: Unpacked, x: &Self, mode: RoundingMode) -> RoundingTrace {
+        let m = F::MANTISSA_BITS as i32;
+        let top = 127 - u.significand.leading_zeros() as i32;
+        let e = u.exponent + top;
+        let min_exponent = 1 - Self::BIAS;
+        let (subnormal, overflow) = (e < min_exponent, e > Self::BIAS);
+        let shift = (top - m + if subnormal { min_exponent - e } else { 0 }).max(0) as u32;
+
+        let bit = |i: u32| i < 128 && (u.significand >> i) & 1 == 1;
+        let guard = shift >= 1 && bit(shift - 1);
+        let round = shift >= 2 && bit(shift - 2);
+        let sticky = shift >= 3 && (shift - 2 >= 128 || u.significand & ((1 << (shift - 2)) - 1) != 0);
+        let decision = if overflow {
+            if x.is_infinity() { RoundingDecision::RoundUp } else { RoundingDecision::RoundDown }
+        } else if x.is_zero() {
+            RoundingDecision::RoundDown // everything dropped, or flushed
+        } else if !(guard || round || sticky) {
+            RoundingDecision::Exact
+        } else if shift_round(u.significand, shift, u.sign, mode).0 != u.significand.checked_shr(shift).unwrap_or(0) {
+            RoundingDecision::RoundUp
+        } else {
+            RoundingDecision::RoundDown
+        };
+        RoundingTrace {
+            sign: u.sign,
+            exponent: u.exponent,
+            significand: u.significand,
+            dropped: shift,
+            guard,
+            round,
+            sticky,
+            decision,
+            subnormal,
+            overflow,
+        }
+    }
+
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn add(&self, other: &Self, ctx: &mut Context) -> Self {
+        let (a, b) = (self.flushed(ctx), other.flushed(ctx));
+        a.add_unrounded(&b, ctx).finish(ctx)
+    }
+
+    /// Like `add`, also returning what the rounding stage did, or None if the sum didn't need rounding at all
+    /// (a nan, an infinity, a zero operand or an exact zero).
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn add_traced(&self, other: &Self, ctx: &mut Context) -> (Self, Option<RoundingTrace>) {
+        let (a, b) = (self.flushed(ctx), other.flushed(ctx));
+        a.add_unrounded(&b, ctx).finish_traced(ctx)
+    }
+
+    fn add_unrounded(&self, other: &Self, ctx: &mut Context) -> Unrounded<F> {
+        use Unrounded::Done;
+        if let Some(nan) = self.propagate_nan(other, ctx) {
+            return Done(nan);
+        }
+        if self.is_infinity() {
+            if other.is_infinity() && self.get_sign() != other.get_sign() {
+                ctx.flags.raise(Flags::INVALID);
+                return Done(Self::default_nan(ctx.nan_mode));
+            }
+            return Done(*self);
+        }
+        if other.is_infinity() {
+            return Done(*other);
+        }
+
+        // an exact zero sum is +0, except in round-down where it is -0
+        let zero_sign = if self.get_sign() == other.get_sign() {
+            self.get_sign()
+        } else {
+            ctx.rounding == RoundingMode::TowardNegative
+        };
+        if self.is_zero() {
+            return Done(if other.is_zero() { Self::zero(zero_sign) } else { other.flush_output(ctx) });
+        }
+        if other.is_zero() {
+            return Done(self.flush_output(ctx));
+        }
+
+        let (big, small) = if self.magnitude() >= other.magnitude() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        let a = big.unpack();
+        let b = small.unpack();
+
+        if (a.exponent - b.exponent) as u32 > F::MANTISSA_BITS + 3 {
+            // b is below an eighth of a's last place, so it only reaches the sticky bit: the sum is a with one more
+            // bit on the end, or one less. four extra places keep the guard and round bits what they'd be for the
+            // exact sum, even when taking b away from a power of two drops the leading bit.
+            let significand = if a.sign == b.sign { (a.significand << 4) + 1 } else { (a.significand << 4) - 1 };
+            return Unrounded::Round(Unpacked { sign: a.sign, exponent: a.exponent - 4, significand });
+        }
+
+        // move a's leading bit up to bit 125, then line b up with it, jamming whatever falls off into the last bit
+        let headroom = 125 - F::MANTISSA_BITS;
+        let mantissa_a = a.significand << headroom;
+        let diff = (a.exponent - b.exponent) as u32; // never negative since |a| >= |b|
+        let mantissa_b = if diff >= 128 {
+            1
+        } else {
+            let b_shifted = b.significand << headroom;
+            (b_shifted >> diff) | ((b_shifted & ((1 << diff) - 1)) != 0) as u128
+        };
+
+        let sum = if a.sign == b.sign {
+            mantissa_a + mantissa_b
+        } else {
+            mantissa_a - mantissa_b
+        };
+        if sum == 0 {
+            return Done(Self::zero(zero_sign));
+        }
+        Unrounded::Round(Unpacked { sign: a.sign, exponent: a.exponent - headroom as i32, significand: sum })
+    }
+
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn subtract(&self, other: &Self, ctx: &mut Context) -> Self {
+        self.add(&other.negated_unless_nan(), ctx)
+    }
+
+    /// Like `subtract`, also returning what the rounding stage did (see `add_traced`).
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn subtract_traced(&self, other: &Self, ctx: &mut Context) -> (Self, Option<RoundingTrace>) {
+        self.add_traced(&other.negated_unless_nan(), ctx)
+    }
+
+    // a nan operand comes back out with its sign unchanged, as on hardware
+    fn negated_unless_nan(&self) -> Self {
+        let mut negated = *self;
+        if !self.is_nan() {
+            negated.negate();
+        }
+        negated
+    }
+
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn multiply(&self, other: &Self, ctx: &mut Context) -> Self {
+        let (a, b) = (self.flushed(ctx), other.flushed(ctx));
+        a.multiply_unrounded(&b, ctx).finish(ctx)
+    }
+
+    /// Like `multiply`, also returning what the rounding stage did, or None for a product that didn't need
+    /// rounding (a nan, an infinity or a zero).
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn multiply_traced(&self, other: &Self, ctx: &mut Context) -> (Self, Option<RoundingTrace>) {
+        let (a, b) = (self.flushed(ctx), other.flushed(ctx));
+        a.multiply_unrounded(&b, ctx).finish_traced(ctx)
+    }
+
+    fn multiply_unrounded(&self, other: &Self, ctx: &mut Context) -> Unrounded<F> {
+        use Unrounded::Done;
+        if let Some(nan) = self.propagate_nan(other, ctx) {
+            return Done(nan);
+        }
+        let sign = self.get_sign() ^ other.get_sign();
+        if self.is_infinity() || other.is_infinity() {
+            if self.is_zero() || other.is_zero() {
+                ctx.flags.raise(Flags::INVALID);
+                return Done(Self::default_nan(ctx.nan_mode));
+            }
+            return Done(Self::infinity(sign));
+        }
+        if self.is_zero() || other.is_zero() {
+            return Done(Self::zero(sign));
+        }
+
+        let a = self.unpack();
+        let b = other.unpack();
+        // the product is exact: two significands of at most 62 bits each
+        Unrounded::Round(Unpacked { sign, exponent: a.exponent + b.exponent, significand: a.significand * b.significand })
+    }
+
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn divide(&self, other: &Self, ctx: &mut Context) -> Self {
+        let (a, b) = (self.flushed(ctx), other.flushed(ctx));
+        a.divide_unrounded(&b, ctx).finish(ctx)
+    }
+
+    /// Like `divide`, also returning what the rounding stage did, or None for a quotient that didn't need
+    /// rounding (a nan, an infinity, a zero or a division by zero).
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn divide_traced(&self, other: &Self, ctx: &mut Context) -> (Self, Option<RoundingTrace>) {
+        let (a, b) = (self.flushed(ctx), other.flushed(ctx));
+        a.divide_unrounded(&b, ctx).finish_traced(ctx)
+    }
+
+    fn divide_unrounded(&self, other: &Self, ctx: &mut Context) -> Unrounded<F> {
+        use Unrounded::Done;
+        if let Some(nan) = self.propagate_nan(other, ctx) {
+            return Done(nan);
+        }
+        let sign = self.get_sign() ^ other.get_sign();
+        if self.is_infinity() {
+            if other.is_infinity() {
+                ctx.flags.raise(Flags::INVALID);
+                return Done(Self::default_nan(ctx.nan_mode));
+            }
+            return Done(Self::infinity(sign));
+        }
+        if other.is_infinity() {
+            return Done(Self::zero(sign));
+        }
+        if other.is_zero() {
+            if self.is_zero() {
+                ctx.flags.raise(Flags::INVALID);
+                return Done(Self::default_nan(ctx.nan_mode));
+            }
+            ctx.flags.raise(Flags::DIVIDE_BY_ZERO);
+            return Done(Self::infinity(sign));
+        }
+        if self.is_zero() {
+            return Done(Self::zero(sign));
+        }
+
+        // normalize both significands to have their leading 1 at bit MANTISSA_BITS, so their ratio is in (1/2, 2)
+        let normalized = |u: Unpacked| -> (u128, i32) {
+            let shift = u.significand.leading_zeros() - (127 - F::MANTISSA_BITS);
+            (u.significand << shift, u.exponent - shift as i32)
+        };
+        let (mantissa_a, exp_a) = normalized(self.unpack());
+        let (mantissa_b, exp_b) = normalized(other.unpack());
+
+        // long division, one quotient bit at a time: MANTISSA_BITS + 3 bits is enough for the kept bits and the half bit
+        let mut remainder = mantissa_a;
+        let mut quotient = 0u128;
+        for _ in 0..F::MANTISSA_BITS + 3 {
+            quotient <<= 1;
+            if remainder >= mantissa_b {
+                remainder -= mantissa_b;
+                quotient |= 1;
+            }
+            remainder <<= 1;
+        }
+        let significand = (quotient << 1) | (remainder != 0) as u128; // sticky bit for what's left over
+        Unrounded::Round(Unpacked { sign, exponent: exp_a - exp_b - (F::MANTISSA_BITS as i32 + 3), significand })
+    }
+
+    /// Converts to another format, rounding if it is narrower. Nans keep as much of their payload as fits and are quieted.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn convert<G: Format>(&self, ctx: &mut Context) -> SoftFloat<G> {
+        self.flushed(ctx).convert_in(ctx)
+    }
+
+    fn convert_in<G: Format>(&self, ctx: &mut Context) -> SoftFloat<G> {
+        let sign = self.get_sign();
+        if self.is_nan() {
+            if self.is_signaling_nan() {
+                ctx.flags.raise(Flags::INVALID);
+            }
+            if ctx.nan_mode == NanMode::Canonical {
+                return SoftFloat::nan();
+            }
+            // line the payloads up at the top of the mantissa
+            let payload = if F::MANTISSA_BITS >= G::MANTISSA_BITS {
+                self.get_mantissa() >> (F::MANTISSA_BITS - G::MANTISSA_BITS)
+            } else {
+                self.get_mantissa() << (G::MANTISSA_BITS - F::MANTISSA_BITS)
+            };
+            let quiet = SoftFloat::<G>::nan().raw() | payload;
+            return SoftFloat::from_raw(quiet | ((sign as u128) << SoftFloat::<G>::SIGN_SHIFT));
+        }
+        if self.is_infinity() {
+            return SoftFloat::infinity(sign);
+        }
+        if self.is_zero() {
+            return SoftFloat::zero(sign);
+        }
+        let u = self.unpack();
+        SoftFloat::round_pack(sign, u.exponent, u.significand, ctx)
+    }
+
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn from_u64(value: u64, ctx: &mut Context) -> Self {
+        if value == 0 {
+            return Self::zero(false);
+        }
+        Self::round_pack(false, 0, value as u128, ctx)
+    }
+
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn from_i64(value: i64, ctx: &mut Context) -> Self {
+        if value == 0 {
+            return Self::zero(false);
+        }
+        Self::round_pack(value < 0, 0, value.unsigned_abs() as u128, ctx)
+    }
+
+    // rounds to an integer in the given mode and checks it against [min, max]. out of range and nan raise invalid and give None.
+    pub(crate) fn round_to_integer(&self, mode: RoundingMode, ctx: &mut Context, min: i128, max: i128) -> Option<i128> {
+        if ctx.flush_inputs && self.is_subnormal() {
+            return self.flushed(ctx).round_to_integer(mode, ctx, min, max);
+        }
+        if self.is_nan() || self.is_infinity() {
+            ctx.flags.raise(Flags::INVALID);
+            return None;
+        }
+        if self.is_zero() {
+            return Some(0);
+        }
+        let u = self.unpack();
+        let (magnitude, inexact) = if u.exponent >= 0 {
+            if u.exponent as u32 > u.significand.leading_zeros().saturating_sub(2) {
+                ctx.flags.raise(Flags::INVALID); // doesn't even fit in an i128
+                return None;
+            }
+            (u.significand << u.exponent, false)
+        } else {
+            shift_round(u.significand, (-u.exponent) as u32, u.sign, mode)
+        };
+        let value = if u.sign { -(magnitude as i128) } else { magnitude as i128 };
+        if value < min || value > max {
+            ctx.flags.raise(Flags::INVALID);
+            return None;
+        }
+        if inexact {
+            ctx.flags.raise(Flags::INEXACT);
+        }
+        Some(value)
+    }
+
+    /// Rounds to an i32 in the given mode. None (with invalid raised) for nan, infinities and out of range values.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn to_i32(&self, mode: RoundingMode, ctx: &mut Context) -> Option<i32> {
+        self.round_to_integer(mode, ctx, i32::MIN as i128, i32::MAX as i128).map(|v| v as i32)
+    }
+
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn to_i64(&self, mode: RoundingMode, ctx: &mut Context) -> Option<i64> {
+        self.round_to_integer(mode, ctx, i64::MIN as i128, i64::MAX as i128).map(|v| v as i64)
+    }
+
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn to_u32(&self, mode: RoundingMode, ctx: &mut Context) -> Option<u32> {
+        self.round_to_integer(mode, ctx, 0, u32::MAX as i128).map(|v| v as u32)
+    }
+
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn to_u64(&self, mode: RoundingMode, ctx: &mut Context) -> Option<u64> {
+        self.round_to_integer(mode, ctx, 0, u64::MAX as i128).map(|v| v as u64)
+    }
+
+    /// Whether this is a finite integer (zeros included), read off the fields without rounding anything.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn is_integer(&self) -> bool {
+        if self.is_nan() || self.is_infinity() {
+            return false;
+        }
+        if self.is_zero() {
+            return true;
+        }
+        let u = self.unpack();
+        u.exponent >= 0 || u.significand.trailing_zeros() >= u.exponent.unsigned_abs()
+    }
+
+    // an integer that fits in `bits` bits, two's complement if signed. -0 fits an unsigned type as 0 does
+    fn is_integer_within(&self, bits: u32, signed: bool) -> bool {
+        if !self.is_integer() {
+            return false;
+        }
+        if self.is_zero() {
+            return true;
+        }
+        let u = self.unpack();
+        let length = 128 - u.significand.leading_zeros() as i32 + u.exponent;
+        if !signed {
+            return !u.sign && length <= bits as i32;
+        }
+        // -2^(bits - 1) is the one value that takes all the bits
+        length < bits as i32 || u.sign && length == bits as i32 && u.significand.is_power_of_two()
+    }
+
+    /// Whether `to_i32` would convert this exactly in every mode: an integer from -2^31 to 2^31 - 1.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn fits_in_i32(&self) -> bool {
+        self.is_integer_within(32, true)
+    }
+
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn fits_in_i64(&self) -> bool {
+        self.is_integer_within(64, true)
+    }
+
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn fits_in_u32(&self) -> bool {
+        self.is_integer_within(32, false)
+    }
+
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn fits_in_u64(&self) -> bool {
+        self.is_integer_within(64, false)
+    }
+
+    /// IEEE comparison: None if either is nan, and -0 == +0. Raises nothing; callers decide what's signaling.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn compare(&self, other: &Self) -> Option<Ordering> {
+        if self.is_nan() || other.is_nan() {
+            return None;
+        }
+        let key = |f: &Self| -> i128 {
+            if f.get_sign() {
+                -(f.magnitude() as i128)
+            } else {
+                f.magnitude() as i128
+            }
+        };
+        Some(key(self).cmp(&key(other)))
+    }
+
+    /// How many values lie in [a, b], ends included, counting the two zeros as one value: 2 for [-0, min subnormal],
+    /// 2^32 - 2^24 + 1 for binary32 from -infinity to +infinity. 0 if a > b or either is a nan.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn count_between(a: &Self, b: &Self) -> u128 {
+        if a.is_nan() || b.is_nan() {
+            return 0;
+        }
+        // compare's keys, on which neighbouring values are neighbouring integers
+        let key = |f: &Self| if f.get_sign() { -(f.magnitude() as i128) } else { f.magnitude() as i128 };
+        let (a, b) = (key(a), key(b));
+        if a > b {
+            0
+        } else {
+            b.abs_diff(a) + 1
+        }
+    }
+}
+
+impl<F: Format> fmt::Debug for SoftFloat<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}(0x{:x})", F::NAME, self.raw())
+    }
+}
+
+// sound since SoftFloat is repr(transparent) over F::Bits: any bits make a SoftFloat, as from_bits already allows
+#[cfg(feature = "bytemuck")]
+unsafe impl<F: Format> bytemuck::Zeroable for SoftFloat<F> where F::Bits: bytemuck::Zeroable {}
+
+#[cfg(feature = "bytemuck")]
+unsafe impl<F: Format> bytemuck::Pod for SoftFloat<F> where F::Bits: bytemuck::Pod {}
+
+#[cfg(feature = "rkyv")]
+impl<F: Format> ArchivedSoftFloat<F>
+where
+    F::Bits: rkyv::Archive<Archived: Copy + Into<F::Bits>>,
+{
+    /// The value, read in place from the archive.
+    pub fn get(&self) -> SoftFloat<F> {
+        SoftFloat::from_bits(self.bits.into())
+    }
+}
+
+impl From<Float> for F64 {
+    fn from(f: Float) -> F64 {
+        F64::from_bits(f.to_bits())
+    }
+}
+
+impl From<F64> for Float {
+    fn from(f: F64) -> Float {
+        Float::from_bits(f.to_bits())
+    }
+}
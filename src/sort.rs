@@ -0,0 +1,66 @@
+// sorting Floats in IEEE 754 totalOrder (-nan, -inf, ..., -0, +0, ..., +inf, +nan) through to_total_order_key.
+// it's an in-place MSD radix sort on the key a byte at a time (american flag sort): each pass counts the bytes,
+// swaps every value into its bucket, and sorts the buckets on the next byte down, so it needs no allocation and
+// never compares floats. small buckets are left to an insertion sort, which is faster at that size.
+//
+//     radix_sort(&mut values);
+
+use crate::Float;
+
+const INSERTION: usize = 32; // buckets this small are insertion sorted
+
+/// Sorts values in totalOrder, in place. Equal keys are the same bits, so there's no question of stability.
+pub fn radix_sort(values: &mut [Float]) {
+    sort_byte(values, 7);
+}
+
+// sorts values that agree on every key byte above `byte`
+fn sort_byte(values: &mut [Float], byte: u32) {
+    if values.len() <= INSERTION {
+        insertion_sort(values);
+        return;
+    }
+    let digit = |x: &Float| (x.to_total_order_key() >> (8 * byte)) as u8 as usize;
+    let mut counts = [0usize; 256];
+    for x in values.iter() {
+        counts[digit(x)] += 1;
+    }
+    let mut ends = [0usize; 256];
+    let mut total = 0;
+    for (end, count) in ends.iter_mut().zip(counts) {
+        total += count;
+        *end = total;
+    }
+    // next[b] is the first place in bucket b that doesn't hold a value of its own yet
+    let mut next: [usize; 256] = core::array::from_fn(|b| ends[b] - counts[b]);
+    for bucket in 0..256 {
+        while next[bucket] < ends[bucket] {
+            let home = digit(&values[next[bucket]]);
+            if home == bucket {
+                next[bucket] += 1;
+            } else {
+                values.swap(next[bucket], next[home]);
+                next[home] += 1;
+            }
+        }
+    }
+    if byte == 0 {
+        return;
+    }
+    let mut start = 0;
+    for end in ends {
+        sort_byte(&mut values[start..end], byte - 1);
+        start = end;
+    }
+}
+
+fn insertion_sort(values: &mut [Float]) {
+    for i in 1..values.len() {
+        let key = values[i].to_total_order_key();
+        let mut j = i;
+        while j > 0 && values[j - 1].to_total_order_key() > key {
+            values.swap(j - 1, j);
+            j -= 1;
+        }
+    }
+}
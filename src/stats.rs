@@ -0,0 +1,157 @@
+// the mean and variance of Floats that arrive one at a time, by Welford's recurrence: each term moves the mean by
+// (x - mean) / n and adds (x - old mean) * (x - new mean) to the sum of squared deviations, so nothing ever
+// squares the raw values and the cancellation of the textbook sum(x^2) - n * mean^2 never happens. the plain
+// recurrence rounds every step to binary64, as the same loop over f64 would; the compensated one carries the
+// mean and the sum of squares at 128 bits (Wide) and rounds only when asked for a result. either way every step is
+// soft arithmetic, so the statistics are the same bits on every platform.
+//
+//     let mut stats = RunningStats::compensated();
+//     for x in &samples {
+//         stats.add(x);
+//     }
+//     let (mean, variance) = (stats.mean(), stats.sample_variance());
+
+use crate::wide::Wide;
+use crate::{Context, Float, F64};
+
+fn to_wide(x: &Float) -> Wide {
+    Wide::from_soft(&F64::from_bits(x.to_bits()))
+}
+
+// rounds to nearest; a zero comes out +0
+fn to_float(x: Wide) -> Float {
+    if x.is_zero() {
+        return Float::new(0.0);
+    }
+    let rounded: F64 = x.round(&mut Context::default());
+    Float::from_bits(rounded.to_bits())
+}
+
+#[derive(Debug)]
+enum State {
+    Plain { mean: Float, squares: Float },
+    Compensated { mean: Wide, squares: Wide },
+}
+
+/// A running mean and variance by Welford's recurrence. `new` rounds every step to binary64, which is the usual
+/// algorithm and loses about n ulps at worst; `compensated` keeps 128 bits between steps, so its error is around
+/// n * 2^-127 of the largest magnitude (over the standard deviation, for the variance), and the results are
+/// correctly rounded to nearest unless the exact value is about that close to a halfway point.
+///
+/// Infinities and nans are kept on the side in the compensated statistics, as the plain recurrence would end up
+/// treating them: a nan, or infinities of both signs, make the mean a nan, any other infinity makes it that
+/// infinity, and either makes the variance a nan. With no terms the mean and variance are nans, and so is the
+/// sample variance of a single term. A zero mean is +0.
+#[derive(Debug)]
+pub struct RunningStats {
+    count: u64,
+    state: State,
+    nan: bool,
+    positive_infinity: bool,
+    negative_infinity: bool,
+}
+
+impl Default for RunningStats {
+    fn default() -> RunningStats {
+        RunningStats::new()
+    }
+}
+
+impl RunningStats {
+    pub fn new() -> RunningStats {
+        RunningStats::with_state(State::Plain { mean: Float::new(0.0), squares: Float::new(0.0) })
+    }
+
+    pub fn compensated() -> RunningStats {
+        RunningStats::with_state(State::Compensated { mean: Wide::ZERO, squares: Wide::ZERO })
+    }
+
+    fn with_state(state: State) -> RunningStats {
+        RunningStats { count: 0, state, nan: false, positive_infinity: false, negative_infinity: false }
+    }
+
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn add(&mut self, x: &Float) {
+        self.count += 1;
+        match &mut self.state {
+            State::Plain { mean, squares } => {
+                let n = Float::new(self.count as f64);
+                let mut minus_mean = mean.copy();
+                minus_mean.negate();
+                let delta = x.add(&minus_mean);
+                *mean = mean.add(&delta.divide(&n));
+                minus_mean = mean.copy();
+                minus_mean.negate();
+                *squares = squares.add(&delta.multiply(&x.add(&minus_mean)));
+            }
+            State::Compensated { mean, squares } => {
+                if x.is_nan() {
+                    self.nan = true;
+                    return;
+                }
+                if x.is_infinity() {
+                    if x.get_sign() {
+                        self.negative_infinity = true;
+                    } else {
+                        self.positive_infinity = true;
+                    }
+                    return;
+                }
+                // counts stay below 2^63 in any run that finishes
+                let n = Wide::from_int(self.count as i64);
+                let x = to_wide(x);
+                let delta = x.sub(*mean);
+                *mean = mean.add(delta.div(n));
+                *squares = squares.add(delta.mul(x.sub(*mean)));
+            }
+        }
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn mean(&self) -> Float {
+        match &self.state {
+            State::Plain { mean, .. } if self.count > 0 => mean.copy(),
+            State::Compensated { mean, .. } if self.count > 0 => {
+                if self.nan || (self.positive_infinity && self.negative_infinity) {
+                    Float::nan()
+                } else if self.positive_infinity || self.negative_infinity {
+                    Float::infinity(self.negative_infinity)
+                } else {
+                    to_float(*mean)
+                }
+            }
+            _ => Float::nan(),
+        }
+    }
+
+    /// The population variance, the sum of squared deviations over n.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn variance(&self) -> Float {
+        self.squares_over(self.count)
+    }
+
+    /// The sample variance, with Bessel's correction: the sum of squared deviations over n - 1.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn sample_variance(&self) -> Float {
+        self.squares_over(self.count.saturating_sub(1))
+    }
+
+    fn squares_over(&self, divisor: u64) -> Float {
+        if divisor == 0 {
+            return Float::nan();
+        }
+        match &self.state {
+            State::Plain { squares, .. } => squares.divide(&Float::new(divisor as f64)),
+            State::Compensated { squares, .. } => {
+                if self.nan || self.positive_infinity || self.negative_infinity {
+                    return Float::nan();
+                }
+                to_float(squares.div(Wide::from_int(divisor as i64)))
+            }
+        }
+    }
+}
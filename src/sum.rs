@@ -0,0 +1,822 @@
+// summing many Floats. plain left-to-right addition loses up to n ulps of the largest partial sum; the
+// compensated sums here carry the rounding error of every addition along and add it back, so the error stays
+// around an ulp of the result however long the series (times the condition number, for sums that cancel).
+// pairwise summation sits in between: no extra work per term, and the error grows with log n instead of n.
+// and the superaccumulator doesn't round at all until the end, so its sum is exact, and the same in any order;
+// the quire is the same accumulator for SoftFloats of any format up to binary64, rounding through a Context.
+// the binned sum is the same in any order too, and much smaller, but only accurate, not exact.
+// every operation rounds to nearest. an empty slice sums to +0. the sums take any iterator of &Float, so a column
+// of a matrix or one field of interleaved records sums in place:
+//
+//     let total = Summation::Neumaier.sum(&values);
+//     let column = sum_exact(matrix.iter().skip(j).step_by(columns));
+//     let total: Float = values.iter().sum(); // pairwise, in blocks of DEFAULT_BLOCK
+
+use core::marker::PhantomData;
+
+use crate::format::Format;
+use crate::wide::Wide;
+use crate::{Context, Flags, Float, RoundingMode, SoftFloat, F64};
+
+/// Kahan's compensated sum: each term is corrected by the error of the previous addition before it goes in.
+/// The error is about 2 ulps plus n * 2^-106 times the sum of the magnitudes, so it can still lose out when a
+/// term is larger than the running sum (1 + 1e100 - 1e100 gives 0); `sum_neumaier` doesn't.
+#[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+pub fn sum_kahan<'a>(values: impl IntoIterator<Item = &'a Float>) -> Float {
+    let (mut sum, mut compensation) = (Float::new(0.0), Float::new(0.0));
+    for x in values {
+        let y = x.add(&compensation.negated());
+        let t = sum.add(&y);
+        // once the sum is infinite or nan the compensation would turn it into nan: leave it alone
+        compensation = if t.is_nan() || t.is_infinity() {
+            Float::new(0.0)
+        } else {
+            t.add(&sum.negated()).add(&y.negated())
+        };
+        sum = t;
+    }
+    sum
+}
+
+/// Neumaier's improvement of Kahan's sum: the error of each addition is kept exactly, whichever operand is
+/// larger, and the errors are summed on the side and added once at the end. 1 + 1e100 - 1e100 gives 1.
+#[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+pub fn sum_neumaier<'a>(values: impl IntoIterator<Item = &'a Float>) -> Float {
+    let mut sum = CompensatedSum::new();
+    for x in values {
+        sum.add(x);
+    }
+    sum.value()
+}
+
+/// A running Neumaier sum, for terms that arrive one at a time. `value` gives the same result `sum_neumaier`
+/// would for the terms added so far, and the sum can keep going after it.
+#[derive(Debug)]
+pub struct CompensatedSum {
+    sum: Float,
+    compensation: Float,
+}
+
+impl Default for CompensatedSum {
+    fn default() -> CompensatedSum {
+        CompensatedSum::new()
+    }
+}
+
+impl CompensatedSum {
+    pub fn new() -> CompensatedSum {
+        CompensatedSum { sum: Float::new(0.0), compensation: Float::new(0.0) }
+    }
+
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn add(&mut self, x: &Float) {
+        // two_sum's error is exact (and +0 once the sum isn't finite), which is Neumaier's branch on the
+        // larger magnitude without the branch
+        let (sum, error) = self.sum.two_sum(x);
+        self.sum = sum;
+        self.compensation = self.compensation.add(&error);
+    }
+
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn value(&self) -> Float {
+        self.sum.add(&self.compensation)
+    }
+}
+
+/// The block size `Summation::default()` and the `Sum` impls use, the same as numpy's.
+pub const DEFAULT_BLOCK: usize = 128;
+
+/// Pairwise summation: blocks of `block` terms are summed left to right, and the block sums are added in a
+/// balanced binary tree. The error bound is (block + log2(n / block)) ulps of the sum of the magnitudes, so a
+/// block of 1 is the most accurate and a block of n is the plain sum. A block of 0 counts as 1.
+#[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+pub fn sum_pairwise<'a>(values: impl IntoIterator<Item = &'a Float>, block: usize) -> Float {
+    let mut sum = PairwiseSum::new(block);
+    for x in values {
+        sum.add(x);
+    }
+    sum.value()
+}
+
+/// A running pairwise sum, for terms that arrive one at a time. The tree is built as the blocks fill up, like a
+/// binary counter, so it takes no allocation: finished subtrees wait in one slot per level until one of the
+/// same size comes along.
+#[derive(Debug)]
+pub struct PairwiseSum {
+    block: usize,
+    current: Float,
+    filled: usize, // terms in current
+    levels: [Float; 64], // levels[k] is the sum of 2^k blocks, when occupied has bit k set
+    occupied: u64,
+}
+
+impl Default for PairwiseSum {
+    fn default() -> PairwiseSum {
+        PairwiseSum::new(DEFAULT_BLOCK)
+    }
+}
+
+impl PairwiseSum {
+    pub fn new(block: usize) -> PairwiseSum {
+        PairwiseSum {
+            block: block.max(1),
+            current: Float::new(0.0),
+            filled: 0,
+            levels: core::array::from_fn(|_| Float::new(0.0)),
+            occupied: 0,
+        }
+    }
+
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn add(&mut self, x: &Float) {
+        // a block starts from -0, which leaves every x alone (and -0 + -0 stays -0), but quiets a signaling nan
+        self.current = if self.filled == 0 { x.add(&Float::from_bits(1 << 63)) } else { self.current.add(x) };
+        self.filled += 1;
+        if self.filled < self.block {
+            return;
+        }
+        let mut carry = core::mem::replace(&mut self.current, Float::new(0.0));
+        self.filled = 0;
+        for (level, slot) in self.levels.iter_mut().enumerate() {
+            if self.occupied & 1 << level == 0 {
+                *slot = carry;
+                self.occupied |= 1 << level;
+                return;
+            }
+            carry = slot.add(&carry); // the older subtree holds the earlier terms, so it goes on the left
+            self.occupied &= !(1 << level);
+        }
+    }
+
+    /// The sum so far: the subtrees waiting on each level, oldest (highest) first, then the unfinished block.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn value(&self) -> Float {
+        let mut total: Option<Float> = None;
+        for (level, slot) in self.levels.iter().enumerate().rev() {
+            if self.occupied & 1 << level != 0 {
+                total = Some(total.map_or(slot.copy(), |t| t.add(slot)));
+            }
+        }
+        match (total, self.filled) {
+            (None, 0) => Float::new(0.0),
+            (None, _) => self.current.copy(),
+            (Some(t), 0) => t,
+            (Some(t), _) => t.add(&self.current),
+        }
+    }
+}
+
+/// Which summation `Summation::sum` runs. The default, pairwise in blocks of `DEFAULT_BLOCK`, is what the `Sum`
+/// impls on Float use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Summation {
+    /// Left to right, one rounding per term.
+    Plain,
+    /// `sum_pairwise` with this block size.
+    Pairwise(usize),
+    /// `sum_kahan`.
+    Kahan,
+    /// `sum_neumaier`.
+    Neumaier,
+    /// `sum_exact`.
+    Exact,
+    /// `sum_reproducible`.
+    Reproducible,
+}
+
+impl Default for Summation {
+    fn default() -> Summation {
+        Summation::Pairwise(DEFAULT_BLOCK)
+    }
+}
+
+impl Summation {
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn sum<'a>(&self, values: impl IntoIterator<Item = &'a Float>) -> Float {
+        match *self {
+            Summation::Plain => values.into_iter().fold(Float::new(0.0), |sum, x| sum.add(x)),
+            Summation::Pairwise(block) => sum_pairwise(values, block),
+            Summation::Kahan => sum_kahan(values),
+            Summation::Neumaier => sum_neumaier(values),
+            Summation::Exact => sum_exact(values),
+            Summation::Reproducible => sum_reproducible(values),
+        }
+    }
+}
+
+impl<'a> core::iter::Sum<&'a Float> for Float {
+    fn sum<I: Iterator<Item = &'a Float>>(iter: I) -> Float {
+        let mut sum = PairwiseSum::default();
+        iter.for_each(|x| sum.add(x));
+        sum.value()
+    }
+}
+
+impl core::iter::Sum<Float> for Float {
+    fn sum<I: Iterator<Item = Float>>(iter: I) -> Float {
+        let mut sum = PairwiseSum::default();
+        iter.for_each(|x| sum.add(&x));
+        sum.value()
+    }
+}
+
+// the accumulator's lowest bit is 2^-2148, the smallest product of two subnormals, and the value of a product of
+// two of the largest doubles fits below 2^2048. that's 4196 bits, and 92 more on top keep 2^91 terms of any size
+// from overflowing it. 67 limbs of 64 is Kulisch's 4288.
+const LIMBS: usize = 67;
+const LOWEST: i32 = -2148;
+
+// a finite double as significand * 2^exponent
+fn integer_parts(x: &Float) -> (u64, i32) {
+    let (biased, fraction) = ((x.to_bits() >> 52 & 0x7FF) as i32, x.to_bits() & ((1 << 52) - 1));
+    if biased == 0 {
+        (fraction, -1074)
+    } else {
+        (fraction | 1 << 52, biased - 1075)
+    }
+}
+
+// adds (or subtracts) significand * 2^position to a two's complement accumulator, position counting from its
+// lowest bit
+fn deposit(limbs: &mut [u64; LIMBS], negative: bool, significand: u128, position: u32) {
+    let shift = position % 64;
+    // the significand shifted into place, as three limbs
+    let words = [
+        (significand << shift) as u64,
+        ((significand << shift) >> 64) as u64,
+        if shift == 0 { 0 } else { (significand >> (128 - shift)) as u64 },
+    ];
+    let mut carry = false;
+    for (n, limb) in limbs.iter_mut().skip((position / 64) as usize).enumerate() {
+        let word = words.get(n).copied().unwrap_or(0);
+        if n >= words.len() && !carry {
+            break;
+        }
+        // subtracting is adding the complement, with the borrow as an inverted carry
+        let (word, carry_in) = if negative { (!word, !carry) } else { (word, carry) };
+        let (sum, first) = limb.overflowing_add(word);
+        let (sum, second) = sum.overflowing_add(carry_in as u64);
+        *limb = sum;
+        carry = (first || second) != negative;
+    }
+}
+
+// adds another accumulator's limbs in
+fn merge_limbs(limbs: &mut [u64; LIMBS], other: &[u64; LIMBS]) {
+    let mut carry = false;
+    for (limb, other_limb) in limbs.iter_mut().zip(other.iter()) {
+        let (sum, first) = limb.overflowing_add(*other_limb);
+        let (sum, second) = sum.overflowing_add(carry as u64);
+        *limb = sum;
+        carry = first || second;
+    }
+}
+
+// the accumulator as a sign, the position of the last of the top 128 bits from the leading one down, and those
+// bits, with anything below jammed into the last. None if it's zero
+fn leading_bits(limbs: &[u64; LIMBS]) -> Option<(bool, i32, u128)> {
+    let negative = limbs[LIMBS - 1] >> 63 == 1;
+    let mut magnitude = *limbs;
+    if negative {
+        // two's complement: invert and add one
+        let mut carry = true;
+        for limb in magnitude.iter_mut() {
+            let (sum, overflow) = (!*limb).overflowing_add(carry as u64);
+            *limb = sum;
+            carry = overflow;
+        }
+    }
+    let (top, &leading) = magnitude.iter().enumerate().rev().find(|(_, limb)| **limb != 0)?;
+    let highest = top as i32 * 64 + 63 - leading.leading_zeros() as i32;
+    let low = highest - 127;
+    let mut significand = 0u128;
+    let mut sticky = false;
+    for (n, &limb) in magnitude.iter().enumerate() {
+        let offset = n as i32 * 64 - low; // where this limb's bit 0 goes in the significand
+        if offset >= 128 {
+            break;
+        }
+        if offset <= -64 {
+            sticky |= limb != 0;
+        } else if offset < 0 {
+            sticky |= limb << (64 + offset) != 0;
+            significand |= (limb >> -offset) as u128;
+        } else {
+            significand |= (limb as u128) << offset;
+        }
+    }
+    Some((negative, low, significand | sticky as u128))
+}
+
+/// A Kulisch long accumulator: a 4288-bit two's complement fixed-point number that every double and every
+/// product of two doubles adds into exactly, so the sum is only rounded once, by `value`. Since nothing rounds
+/// along the way, the result doesn't depend on the order of the terms, or on how they were split between
+/// accumulators that are `merge`d at the end. Infinities and nans are kept on the side, as IEEE addition
+/// would treat them: a nan or infinities of both signs give a nan, otherwise an infinity wins.
+#[derive(Debug, Clone)]
+pub struct Superaccumulator {
+    limbs: [u64; LIMBS], // least significant first
+    nan: bool,
+    positive_infinity: bool,
+    negative_infinity: bool,
+    any_term: bool,
+    only_negative_zeros: bool, // so that summing -0s gives -0, as adding them does
+}
+
+impl Default for Superaccumulator {
+    fn default() -> Superaccumulator {
+        Superaccumulator::new()
+    }
+}
+
+impl Superaccumulator {
+    pub fn new() -> Superaccumulator {
+        Superaccumulator {
+            limbs: [0; LIMBS],
+            nan: false,
+            positive_infinity: false,
+            negative_infinity: false,
+            any_term: false,
+            only_negative_zeros: true,
+        }
+    }
+
+    fn add_special(&mut self, x: &Float) {
+        if x.is_nan() {
+            self.nan = true;
+        } else if x.get_sign() {
+            self.negative_infinity = true;
+        } else {
+            self.positive_infinity = true;
+        }
+    }
+
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn add(&mut self, x: &Float) {
+        self.any_term = true;
+        self.only_negative_zeros &= x.is_zero() && x.get_sign();
+        if x.is_nan() || x.is_infinity() {
+            self.add_special(x);
+            return;
+        }
+        let (significand, exponent) = integer_parts(x);
+        if significand != 0 {
+            deposit(&mut self.limbs, x.get_sign(), significand as u128, (exponent - LOWEST) as u32);
+        }
+    }
+
+    /// Adds a * b, exactly.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn add_product(&mut self, a: &Float, b: &Float) {
+        self.any_term = true;
+        let sign = a.get_sign() != b.get_sign();
+        self.only_negative_zeros &= (a.is_zero() || b.is_zero()) && sign;
+        if a.is_nan() || b.is_nan() || (a.is_infinity() && b.is_zero()) || (a.is_zero() && b.is_infinity()) {
+            self.nan = true;
+            return;
+        }
+        if a.is_infinity() || b.is_infinity() {
+            self.add_special(&Float::infinity(sign));
+            return;
+        }
+        let ((a_significand, a_exponent), (b_significand, b_exponent)) = (integer_parts(a), integer_parts(b));
+        let product = a_significand as u128 * b_significand as u128;
+        if product != 0 {
+            deposit(&mut self.limbs, sign, product, (a_exponent + b_exponent - LOWEST) as u32);
+        }
+    }
+
+    /// Adds everything another accumulator holds, as if its terms had been added to this one.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn merge(&mut self, other: &Superaccumulator) {
+        merge_limbs(&mut self.limbs, &other.limbs);
+        self.nan |= other.nan;
+        self.positive_infinity |= other.positive_infinity;
+        self.negative_infinity |= other.negative_infinity;
+        self.only_negative_zeros &= other.only_negative_zeros || !other.any_term;
+        self.any_term |= other.any_term;
+    }
+
+    /// The sum, rounded once to nearest.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn value(&self) -> Float {
+        self.value_with_rounding(RoundingMode::TiesToEven)
+    }
+
+    /// The sum, rounded once in the given mode. An exact zero is -0 if every term was -0, or if terms that
+    /// weren't zero cancelled while rounding toward negative, and +0 otherwise.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn value_with_rounding(&self, mode: RoundingMode) -> Float {
+        if self.nan || (self.positive_infinity && self.negative_infinity) {
+            return Float::nan();
+        }
+        if self.positive_infinity || self.negative_infinity {
+            return Float::infinity(self.negative_infinity);
+        }
+        let Some((negative, low, significand)) = leading_bits(&self.limbs) else {
+            let cancelled = !self.only_negative_zeros && mode == RoundingMode::TowardNegative;
+            let sign = self.any_term && (self.only_negative_zeros || cancelled);
+            return Float::from_bits((sign as u64) << 63);
+        };
+        let wide = Wide::new(negative, low + LOWEST, significand);
+        let rounded: F64 = wide.round(&mut Context::new(mode));
+        Float::from_bits(rounded.to_bits())
+    }
+}
+
+/// The exactly rounded sum of the values, whatever their order, through a `Superaccumulator`.
+#[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+pub fn sum_exact<'a>(values: impl IntoIterator<Item = &'a Float>) -> Float {
+    let mut sum = Superaccumulator::new();
+    for x in values {
+        sum.add(x);
+    }
+    sum.value()
+}
+
+/// The superaccumulator for any format up to binary64's range, after the posit standard's quire: terms and
+/// products of two terms are added exactly, and `round` rounds the total once, in the context's mode, raising
+/// inexact, overflow and underflow as a single operation would. It's the whole of what a reproducible dot
+/// product needs, so the result is the same whatever the order of the terms or how they were `merge`d. A nan or
+/// infinities of both signs give a nan; invalid is raised for a signaling nan, an infinity times zero, or
+/// infinities of both signs, in whatever order they went in. Formats with a wider exponent than binary64 don't
+/// fit, and fail to compile.
+#[derive(Debug, Clone)]
+pub struct Quire<F: Format> {
+    limbs: [u64; LIMBS], // least significant first, the lowest bit LOWEST for F
+    nan: bool,
+    invalid: bool,
+    positive_infinity: bool,
+    negative_infinity: bool,
+    any_term: bool,
+    // so that zeros of one sign sum to that zero, and anything else that comes to zero is +0, or -0 toward
+    // negative, as adding them does
+    only_negative_zeros: bool,
+    only_positive_zeros: bool,
+    format: PhantomData<F>,
+}
+
+impl<F: Format> Default for Quire<F> {
+    fn default() -> Quire<F> {
+        Quire::new()
+    }
+}
+
+impl<F: Format> Quire<F> {
+    // the smallest product of two subnormals, as in LOWEST: 2^(2 (emin - mantissa bits))
+    const LOWEST: i32 = 2 * (2 - (1 << (F::EXPONENT_BITS - 1)) - F::MANTISSA_BITS as i32);
+
+    pub fn new() -> Quire<F> {
+        const { assert!(F::EXPONENT_BITS <= 11 && F::MANTISSA_BITS <= 52, "a quire holds formats up to binary64") };
+        Quire {
+            limbs: [0; LIMBS],
+            nan: false,
+            invalid: false,
+            positive_infinity: false,
+            negative_infinity: false,
+            any_term: false,
+            only_negative_zeros: true,
+            only_positive_zeros: true,
+            format: PhantomData,
+        }
+    }
+
+    fn add_infinity(&mut self, sign: bool) {
+        if sign {
+            self.negative_infinity = true;
+        } else {
+            self.positive_infinity = true;
+        }
+    }
+
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn add(&mut self, x: &SoftFloat<F>) {
+        self.any_term = true;
+        self.only_negative_zeros &= x.is_zero() && x.get_sign();
+        self.only_positive_zeros &= x.is_zero() && !x.get_sign();
+        if x.is_nan() {
+            self.nan = true;
+            self.invalid |= x.is_signaling_nan();
+        } else if x.is_infinity() {
+            self.add_infinity(x.get_sign());
+        } else if !x.is_zero() {
+            let u = x.unpack();
+            deposit(&mut self.limbs, u.sign, u.significand, (u.exponent - Self::LOWEST) as u32);
+        }
+    }
+
+    /// Adds a * b, exactly.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn add_product(&mut self, a: &SoftFloat<F>, b: &SoftFloat<F>) {
+        self.any_term = true;
+        let sign = a.get_sign() != b.get_sign();
+        self.only_negative_zeros &= (a.is_zero() || b.is_zero()) && sign;
+        self.only_positive_zeros &= (a.is_zero() || b.is_zero()) && !sign;
+        if a.is_nan() || b.is_nan() {
+            self.nan = true;
+            self.invalid |= a.is_signaling_nan() || b.is_signaling_nan();
+        } else if (a.is_infinity() && b.is_zero()) || (a.is_zero() && b.is_infinity()) {
+            self.nan = true;
+            self.invalid = true;
+        } else if a.is_infinity() || b.is_infinity() {
+            self.add_infinity(sign);
+        } else if !a.is_zero() && !b.is_zero() {
+            let (a, b) = (a.unpack(), b.unpack());
+            let position = (a.exponent + b.exponent - Self::LOWEST) as u32;
+            deposit(&mut self.limbs, sign, a.significand * b.significand, position);
+        }
+    }
+
+    /// Adds everything another quire holds, as if its terms had been added to this one.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn merge(&mut self, other: &Quire<F>) {
+        merge_limbs(&mut self.limbs, &other.limbs);
+        self.nan |= other.nan;
+        self.invalid |= other.invalid;
+        self.positive_infinity |= other.positive_infinity;
+        self.negative_infinity |= other.negative_infinity;
+        self.only_negative_zeros &= other.only_negative_zeros || !other.any_term;
+        self.only_positive_zeros &= other.only_positive_zeros || !other.any_term;
+        self.any_term |= other.any_term;
+    }
+
+    /// The total, rounded once. An exact zero is -0 if every term was -0 and +0 if every term was +0 (or there
+    /// were none); otherwise it's -0 rounding toward negative and +0 in the other modes.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn round(&self, ctx: &mut Context) -> SoftFloat<F> {
+        let both_infinities = self.positive_infinity && self.negative_infinity;
+        if self.invalid || both_infinities {
+            ctx.flags.raise(Flags::INVALID);
+        }
+        if self.nan || both_infinities {
+            return SoftFloat::nan();
+        }
+        if self.positive_infinity || self.negative_infinity {
+            return SoftFloat::infinity(self.negative_infinity);
+        }
+        let Some((negative, low, significand)) = leading_bits(&self.limbs) else {
+            let down = ctx.rounding == RoundingMode::TowardNegative;
+            return SoftFloat::zero(self.any_term && (self.only_negative_zeros || down && !self.only_positive_zeros));
+        };
+        Wide::new(negative, low + Self::LOWEST, significand).round(ctx)
+    }
+}
+
+// binned summation, after ReproBLAS (Demmel, Ahrens and Nguyen). the exponent range is cut into bins of BIN_WIDTH
+// bits on a fixed grid, and a sum keeps FOLDS of them: the bin of the largest term so far and the ones just
+// below. every term is split into its slices in those bins by adding it to a primary of 1.5 * 2^(bin + 52),
+// which rounds it to the bin's last bit, and the slices add into the primaries exactly. bits below the lowest
+// bin are dropped (rounded into it), but which bits those are depends only on the largest term, not on the
+// order, and or-ing a 1 into the last bit of what's deposited means no slice is ever a tie, so rounding to
+// even can't look at the primary's last bit either.
+const BIN_WIDTH: i32 = 40;
+const FOLDS: usize = 3;
+const ENDURANCE: u32 = 1 << 11; // deposits a primary takes before it could leave its binade
+const TOP_BIN: i32 = 25; // holds everything up to 2^1024
+const BOTTOM_BIN: i32 = -27; // its last bit is 2^-1080, below every subnormal
+
+// 2^e for e from -1074 to 1023
+fn power_of_two(e: i32) -> Float {
+    if e >= -1022 {
+        Float::from_bits(((e + 1023) as u64) << 52)
+    } else {
+        Float::from_bits(1 << (e + 1074))
+    }
+}
+
+// the primary of the top bin would be past the largest double and the bottom one's would be subnormal, so those
+// two work in units of 2^128 and 2^-128 instead: values going in are scaled by 2^-scale
+fn scale(bin: i32) -> i32 {
+    if bin >= TOP_BIN {
+        128
+    } else if bin <= BOTTOM_BIN {
+        -128
+    } else {
+        0
+    }
+}
+
+// 1.5 * 2^(bin * BIN_WIDTH + 52), in the bin's scaled units. its last bit is the bin's last bit
+fn empty_primary(bin: i32) -> Float {
+    power_of_two(bin * BIN_WIDTH + 52 - scale(bin)).multiply(&Float::new(1.5))
+}
+
+// the lowest bin whose half-ulp is above |x|, so x has no slice in any bin above it
+fn bin_of(x: &Float) -> i32 {
+    let (significand, exponent) = integer_parts(x);
+    let top = exponent + 63 - significand.leading_zeros() as i32; // |x| is in [2^top, 2^(top + 1))
+    (top + 2 + BIN_WIDTH - 1).div_euclid(BIN_WIDTH) - 1
+}
+
+// one bin of the sum: carry * 2^((bin + 1) * BIN_WIDTH) + (primary - empty_primary(bin)) * 2^scale(bin)
+#[derive(Debug)]
+struct Bin {
+    primary: Float,
+    carry: i64,
+}
+
+impl Bin {
+    fn new(bin: i32) -> Bin {
+        Bin { primary: empty_primary(bin), carry: 0 }
+    }
+
+    fn copy(&self) -> Bin {
+        Bin { primary: self.primary.copy(), carry: self.carry }
+    }
+
+    // moves everything from the bin's 41st bit up into the carry, leaving the primary 1.5 * 2^e plus [0, 2^40)
+    // of its last bit. that's the same state for the same value, however the deposits went in
+    fn renormalize(&mut self, bin: i32) {
+        let empty = empty_primary(bin);
+        let excess = self.primary.add(&empty.negated()); // exact: the two are within a factor of 2
+        let unit = bin * BIN_WIDTH - scale(bin);
+        let (significand, exponent) = integer_parts(&excess);
+        // a multiple of the unit, though its significand can have trailing zeros that put the exponent lower
+        let shift = exponent - unit;
+        let units = match shift {
+            _ if significand == 0 => 0,
+            0.. => (significand << shift) as i64,
+            _ => (significand >> -shift) as i64,
+        };
+        let units = if excess.get_sign() { -units } else { units };
+        self.carry = self.carry.wrapping_add(units >> BIN_WIDTH);
+        let rest = Float::new((units & ((1 << BIN_WIDTH) - 1)) as f64);
+        self.primary = empty.add(&rest.multiply(&power_of_two(unit)));
+    }
+}
+
+/// A reproducible running sum: the result depends only on which terms went in, not on their order or on how
+/// they were split between sums that were `merge`d, so a parallel reduction gives the same bits on every run
+/// and any number of threads. Unlike `Superaccumulator` it isn't exact, but it's a handful of doubles: three
+/// 40-bit bins below the largest term seen, with an error of at most about n * 2^-80 times the largest
+/// magnitude, plus the final rounding. Infinities and nans are kept on the side, as in `Superaccumulator`.
+#[derive(Debug)]
+pub struct ReproducibleSum {
+    top: Option<i32>, // the bin of folds[0], once there's a term that isn't zero
+    folds: [Bin; FOLDS],
+    deposits: u32, // since the last renormalization
+    nan: bool,
+    positive_infinity: bool,
+    negative_infinity: bool,
+    any_term: bool,
+    only_negative_zeros: bool,
+}
+
+impl Default for ReproducibleSum {
+    fn default() -> ReproducibleSum {
+        ReproducibleSum::new()
+    }
+}
+
+impl Clone for ReproducibleSum {
+    fn clone(&self) -> ReproducibleSum {
+        ReproducibleSum { folds: self.folds.each_ref().map(Bin::copy), ..*self }
+    }
+}
+
+impl ReproducibleSum {
+    pub fn new() -> ReproducibleSum {
+        ReproducibleSum {
+            top: None,
+            folds: core::array::from_fn(|_| Bin::new(0)),
+            deposits: 0,
+            nan: false,
+            positive_infinity: false,
+            negative_infinity: false,
+            any_term: false,
+            only_negative_zeros: true,
+        }
+    }
+
+    // the bin of fold k, given the top
+    fn bin(top: i32, k: usize) -> i32 {
+        top - k as i32
+    }
+
+    // moves the folds down so the top one is `top`, dropping bins that fall off the bottom
+    fn raise(&mut self, top: i32) {
+        let old = self.top.unwrap_or(i32::MIN / 2);
+        if old >= top {
+            return;
+        }
+        let shift = (top - old).clamp(0, FOLDS as i32) as usize;
+        self.folds.rotate_right(shift);
+        for (k, fold) in self.folds.iter_mut().enumerate().take(shift) {
+            *fold = Bin::new(ReproducibleSum::bin(top, k));
+        }
+        self.top = Some(top);
+    }
+
+    fn renormalize(&mut self) {
+        if let Some(top) = self.top {
+            for (k, fold) in self.folds.iter_mut().enumerate() {
+                fold.renormalize(ReproducibleSum::bin(top, k));
+            }
+        }
+        self.deposits = 0;
+    }
+
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn add(&mut self, x: &Float) {
+        self.any_term = true;
+        self.only_negative_zeros &= x.is_zero() && x.get_sign();
+        if x.is_nan() {
+            self.nan = true;
+            return;
+        }
+        if x.is_infinity() {
+            if x.get_sign() {
+                self.negative_infinity = true;
+            } else {
+                self.positive_infinity = true;
+            }
+            return;
+        }
+        if x.is_zero() {
+            return;
+        }
+        let own = bin_of(x);
+        self.raise(own);
+        let Some(top) = self.top else { return };
+        if self.deposits >= ENDURANCE {
+            self.renormalize();
+        }
+        self.deposits += 1;
+        let mut rest = x.copy();
+        for (k, fold) in self.folds.iter_mut().enumerate() {
+            let bin = ReproducibleSum::bin(top, k);
+            if bin > own {
+                continue; // nothing of x up here
+            }
+            let scaled = rest.multiply(&power_of_two(-scale(bin)));
+            let before = fold.primary.copy();
+            fold.primary = fold.primary.add(&Float::from_bits(scaled.to_bits() | 1));
+            // what's left, still scaled: the top bin's slice of the largest doubles can round up to 2^1024
+            let slice = fold.primary.add(&before.negated());
+            rest = scaled.add(&slice.negated()).multiply(&power_of_two(scale(bin)));
+        }
+    }
+
+    /// Adds everything another sum holds, as if its terms had been added to this one.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn merge(&mut self, other: &ReproducibleSum) {
+        self.nan |= other.nan;
+        self.positive_infinity |= other.positive_infinity;
+        self.negative_infinity |= other.negative_infinity;
+        self.only_negative_zeros &= other.only_negative_zeros || !other.any_term;
+        self.any_term |= other.any_term;
+        let Some(other_top) = other.top else { return };
+        let mut other = other.clone();
+        self.raise(other_top);
+        let Some(top) = self.top else { return };
+        other.raise(top);
+        self.renormalize();
+        other.renormalize();
+        for (k, (fold, other_fold)) in self.folds.iter_mut().zip(other.folds.iter()).enumerate() {
+            let excess = other_fold.primary.add(&empty_primary(ReproducibleSum::bin(top, k)).negated());
+            fold.primary = fold.primary.add(&excess); // exact: both excesses are under 2^40 units
+            fold.carry = fold.carry.wrapping_add(other_fold.carry);
+        }
+    }
+
+    /// The sum, rounded to nearest. The folds are added up exactly, so the only rounding is this one.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn value(&self) -> Float {
+        if self.nan || (self.positive_infinity && self.negative_infinity) {
+            return Float::nan();
+        }
+        if self.positive_infinity || self.negative_infinity {
+            return Float::infinity(self.negative_infinity);
+        }
+        let mut exact = Superaccumulator::new();
+        if self.any_term {
+            exact.add(&Float::from_bits((self.only_negative_zeros as u64) << 63));
+        }
+        let mut canonical = self.clone();
+        canonical.renormalize();
+        let Some(top) = canonical.top else { return exact.value() };
+        for (k, fold) in canonical.folds.iter().enumerate() {
+            let bin = ReproducibleSum::bin(top, k);
+            let excess = fold.primary.add(&empty_primary(bin).negated());
+            exact.add_product(&excess, &power_of_two(scale(bin)));
+            // carry * 2^((bin + 1) * BIN_WIDTH), in two 32-bit halves, each a product of factors in range
+            let exponent = (bin + 1) * BIN_WIDTH;
+            for (half, shift) in [(fold.carry >> 32, 32), (fold.carry & 0xFFFF_FFFF, 0)] {
+                let total = exponent + shift;
+                let factor = Float::new(half as f64).multiply(&power_of_two(total / 2));
+                exact.add_product(&factor, &power_of_two(total - total / 2));
+            }
+        }
+        exact.value()
+    }
+}
+
+/// The sum through a `ReproducibleSum`: the same bits for any order of the same values.
+#[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+pub fn sum_reproducible<'a>(values: impl IntoIterator<Item = &'a Float>) -> Float {
+    let mut sum = ReproducibleSum::new();
+    for x in values {
+        sum.add(x);
+    }
+    sum.value()
+}
@@ -0,0 +1,120 @@
+// test vectors for hardware: an operation on operands from random::special, with the result and the flags,
+// written a line per vector in hex so a Verilog or VHDL testbench can $readmemh or textio them and compare its
+// FPU against ours.
+//
+//   let mut rng = rand::rng();
+//   for v in testbench::vectors::<Binary32>(Operation::Add, &Context::riscv(), 1000, &mut || rng.random()) {
+//       println!("{}", testbench::line(testbench::DEFAULT_TEMPLATE, &v));
+//   }
+//
+// a line is a template with fields filled in: {a}, {b} and {result} are the encodings, zero padded to the format's
+// width; {flags} is two hex digits in the risc-v fflags layout (NV DZ OF UF NX from bit 4 down, which is the IEEE
+// order too); {rm} is the rounding mode as a risc-v rm field (0 RNE, 1 RTZ, 2 RDN, 3 RUP, 4 RMM) and {op} the
+// operation (0 add, 1 sub, 2 mul, 3 div). anything else is copied as is, so separators and 0x prefixes are up to
+// the template.
+
+use crate::format::Storage;
+use crate::{random, Context, Flags, Format, Operation, RoundingMode, SoftFloat};
+
+/// Both operands, the result and the flags, space separated.
+pub const DEFAULT_TEMPLATE: &str = "{a} {b} {result} {flags}";
+
+/// One operation and what it gave.
+#[derive(Debug, Clone, Copy)]
+pub struct Vector<F: Format> {
+    pub op: Operation,
+    pub rounding: RoundingMode,
+    pub a: SoftFloat<F>,
+    pub b: SoftFloat<F>,
+    pub result: SoftFloat<F>,
+    pub flags: Flags,
+}
+
+impl<F: Format> Vector<F> {
+    /// Runs op on a and b in ctx (its flags are ignored; the vector has only the ones this operation raised).
+    pub fn new(op: Operation, a: SoftFloat<F>, b: SoftFloat<F>, ctx: &Context) -> Vector<F> {
+        let mut ctx = Context { flags: Flags::empty(), ..*ctx };
+        let result = op.apply(&a, &b, &mut ctx);
+        Vector { op, rounding: ctx.rounding, a, b, result, flags: ctx.flags }
+    }
+}
+
+/// count vectors of op in ctx, with both operands from random::special.
+pub fn vectors<F: Format>(
+    op: Operation,
+    ctx: &Context,
+    count: usize,
+    bits: &mut impl FnMut() -> u64,
+) -> Vec<Vector<F>> {
+    (0..count)
+        .map(|_| {
+            let a = random::special(bits);
+            Vector::new(op, a, random::special(bits), ctx)
+        })
+        .collect()
+}
+
+/// The vector written out with template (see DEFAULT_TEMPLATE and the top of this file).
+pub fn line<F: Format>(template: &str, v: &Vector<F>) -> String {
+    let width = (1 + F::EXPONENT_BITS + F::MANTISSA_BITS).div_ceil(4) as usize;
+    let hex = |x: &SoftFloat<F>| format!("{:0width$X}", x.to_bits().to_u128());
+    let mut out = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let field = rest[start..].find('}').map(|end| &rest[start + 1..start + end]);
+        let value = match field {
+            Some("a") => hex(&v.a),
+            Some("b") => hex(&v.b),
+            Some("result") => hex(&v.result),
+            Some("flags") => format!("{:02X}", v.flags.0 & 0x1F), // the five IEEE flags only
+            Some("rm") => rm(v.rounding).to_string(),
+            Some("op") => op_code(v.op).to_string(),
+            _ => {
+                out.push('{');
+                rest = &rest[start + 1..];
+                continue;
+            }
+        };
+        out.push_str(&value);
+        rest = &rest[start + field.map_or(0, str::len) + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// A comment line saying what the template's fields hold, for the top of a vector file. // works in Verilog's
+/// $readmemh, and is easy to skip in VHDL.
+pub fn header<F: Format>(template: &str) -> String {
+    let mut out = format!("// {}: {template}", F::NAME);
+    if template.contains("{flags}") {
+        out.push_str(", flags NV DZ OF UF NX from bit 4");
+    }
+    if template.contains("{rm}") {
+        out.push_str(", rm 0 RNE 1 RTZ 2 RDN 3 RUP 4 RMM");
+    }
+    if template.contains("{op}") {
+        out.push_str(", op 0 add 1 sub 2 mul 3 div");
+    }
+    out
+}
+
+// the risc-v rm encoding, as riscv::rounding_from_rm reads it
+fn rm(mode: RoundingMode) -> u8 {
+    match mode {
+        RoundingMode::TiesToEven => 0,
+        RoundingMode::TowardZero => 1,
+        RoundingMode::TowardNegative => 2,
+        RoundingMode::TowardPositive => 3,
+        RoundingMode::TiesToAway => 4,
+    }
+}
+
+fn op_code(op: Operation) -> u8 {
+    match op {
+        Operation::Add => 0,
+        Operation::Subtract => 1,
+        Operation::Multiply => 2,
+        Operation::Divide => 3,
+    }
+}
@@ -0,0 +1,512 @@
+// sin, cos and tan, and their inverses. the argument is reduced to r in [-pi/4, pi/4] plus a quadrant, then the series for sin r and
+// cos r are summed in Wide. the reduction has to be right for every representable x, since a 64-bit significand
+// can sit within 2^-70 or so of a multiple of pi/2: up to 2^27 it is Cody-Waite (x - k pi/2 with pi/2 in three
+// pieces short enough that each k * piece is exact), past that Payne-Hanek, which multiplies the significand by
+// only the bits of 2/pi that matter at x's exponent.
+
+use crate::format::Format;
+use crate::math::series_bits;
+use crate::wide::Wide;
+use crate::{Context, Flags, RoundingMode, SoftFloat};
+
+// 128 bits each, rounded to nearest, as in math.rs
+const PI_OVER_2: Wide = Wide { sign: false, exponent: -127, significand: 0xC90FDAA22168C234C4C6628B80DC1CD1 };
+const TWO_OVER_PI: Wide = Wide { sign: false, exponent: -128, significand: 0xA2F9836E4E441529FC2757D1F534DDC1 };
+// pi/2 as PIO2_1 + PIO2_2 + PIO2_3, the first two with 100 significant bits so k * piece is exact for k < 2^27
+const PIO2_1: Wide = Wide { sign: false, exponent: -127, significand: 0xC90FDAA22168C234C4C6628B80000000 };
+const PIO2_2: Wide = Wide { sign: false, exponent: -231, significand: 0xDC1CD129024E088A67CC740200000000 };
+const PIO2_3: Wide = Wide { sign: false, exponent: -331, significand: 0xBBEA63B139B22514A08798E3404DDEF9 };
+const CODY_WAITE_LIMIT: i32 = 27;
+
+// 2/pi in binary, the first bit worth 1/2. enough of it for the largest 15-bit exponent plus the window below
+const TWO_OVER_PI_BITS: [u64; 264] = [
+    0xA2F9836E4E441529, 0xFC2757D1F534DDC0, 0xDB6295993C439041, 0xFE5163ABDEBBC561,
+    0xB7246E3A424DD2E0, 0x06492EEA09D1921C, 0xFE1DEB1CB129A73E, 0xE88235F52EBB4484,
+    0xE99C7026B45F7E41, 0x3991D639835339F4, 0x9C845F8BBDF9283B, 0x1FF897FFDE05980F,
+    0xEF2F118B5A0A6D1F, 0x6D367ECF27CB09B7, 0x4F463F669E5FEA2D, 0x7527BAC7EBE5F17B,
+    0x3D0739F78A5292EA, 0x6BFB5FB11F8D5D08, 0x56033046FC7B6BAB, 0xF0CFBC209AF4361D,
+    0xA9E391615EE61B08, 0x6599855F14A06840, 0x8DFFD8804D732731, 0x06061556CA73A8C9,
+    0x60E27BC08C6B47C4, 0x19C367CDDCE8092A, 0x8359C4768B961CA6, 0xDDAF44D15719053E,
+    0xA5FF07053F7E33E8, 0x32C2DE4F98327DBB, 0xC33D26EF6B1E5EF8, 0x9F3A1F35CAF27F1D,
+    0x87F121907C7C246A, 0xFA6ED5772D30433B, 0x15C614B59D19C3C2, 0xC4AD414D2C5D000C,
+    0x467D862D71E39AC6, 0x9B0062337CD2B497, 0xA7B4D55537F63ED7, 0x1810A3FC764D2A9D,
+    0x64ABD770F87C6357, 0xB07AE715175649C0, 0xD9D63B3884A7CB23, 0x24778AD623545AB9,
+    0x1F001B0AF1DFCE19, 0xFF319F6A1E666157, 0x9947FBACD87F7EB7, 0x652289E83260BFE6,
+    0xCDC4EF09366CD43F, 0x5DD7DE16DE3B5892, 0x9BDE2822D2E88628, 0x4D58E232CAC616E3,
+    0x08CB7DE050C017A7, 0x1DF35BE01834132E, 0x6212830148835B8E, 0xF57FB0ADF2E91E43,
+    0x4A48D36710D8DDAA, 0x425FAECE616AA428, 0x0AB499D3F2A6067F, 0x775C83C2A3883C61,
+    0x78738A5A8CAFBDD7, 0x6F63A62DCBBFF4EF, 0x818D67C12645CA55, 0x36D9CAD2A8288D61,
+    0xC277C9121426049B, 0x4612C459C444C5C8, 0x91B24DF31700AD43, 0xD4E5492910D5FDFC,
+    0xBE00CC941EEECE70, 0xF53E1380F1ECC3E7, 0xB328F8C79405933E, 0x71C1B3092EF3450B,
+    0x9C12887B20AB9FB5, 0x2EC292472F327B6D, 0x550C90A7721FE76B, 0x96CB314A1679E279,
+    0x4189DFF49794E884, 0xE6E29731996BED88, 0x365F5F0EFDBBB49A, 0x486CA46742727132,
+    0x5D8DB8159F09E5BC, 0x25318D3974F71C05, 0x30010C0D68084B58, 0xEE2C90AA4702E774,
+    0x24D6BDA67DF77248, 0x6EEF169FA6948EF6, 0x91B45153D1F20ACF, 0x3398207E4BF56863,
+    0xB25F3EDD035D407F, 0x8985295255C06437, 0x10D86D324832754C, 0x5BD4714E6E5445C1,
+    0x090B69F52AD56614, 0x9D072750045DDB3B, 0xB4C576EA17F9877D, 0x6B49BA271D296996,
+    0xACCCC65414AD6AE2, 0x9089D98850722CBE, 0xA4049407777030F3, 0x27FC00A871EA49C2,
+    0x663DE06483DD9797, 0x3FA3FD94438C860D, 0xDE41319D39928C70, 0xDDE7B7173BDF082B,
+    0x3715A0805C93805A, 0x921110D8E80FAF80, 0x6C4BFFDB0F903876, 0x185915A562BBCB61,
+    0xB989C7BD401004F2, 0xD2277549F6B6EBBB, 0x22DBAA140A2F2689, 0x768364333B091A94,
+    0x0EAA3A51C2A31DAE, 0xEDAF12265C4DC26D, 0x9C7A2D9756C0833F, 0x03F6F0098C402B99,
+    0x316D07B43915200C, 0x5BC3D8C492F54BAD, 0xC6A5CA4ECD37A736, 0xA9E69492AB6842DD,
+    0xDE6319EF8C76528B, 0x6837DBFCABA1AE31, 0x15DFA1AE00DAFB0C, 0x664D64B705ED3065,
+    0x29BF56573AFF47B9, 0xF96AF3BE75DF9328, 0x3080ABF68C6615CB, 0x040622FA1DE4D9A4,
+    0xB33D8F1B5709CD36, 0xE9424EA4BE13B523, 0x331AAAF0A8654FA5, 0xC1D20F3F0BCD785B,
+    0x76F923048B7B7217, 0x8953A6C6E26E6F00, 0xEBEF584A9BB7DAC4, 0xBA66AACFCF761D02,
+    0xD12DF1B1C1998C77, 0xADC3DA4886A05DF7, 0xF480C62FF0AC9AEC, 0xDDBC5C3F6DDED01F,
+    0xC790B6DB2A3A25A3, 0x9AAF009353AD0457, 0xB6B42D297E804BA7, 0x07DA0EAA76A1597B,
+    0x2A12162DB7DCFDE5, 0xFAFEDB89FDBE896C, 0x76E4FCA90670803E, 0x156E85FF87FD073E,
+    0x2833676186182AEA, 0xBD4DAFE7B36E6D8F, 0x3967955BBF3148D7, 0x8416DF30432DC735,
+    0x6125CE70C9B8CB30, 0xFD6CBFA200A4E46C, 0x05A0DD5A476F21D2, 0x1262845CB9496170,
+    0xE0566B0152993755, 0x50B7D51EC4F1335F, 0x6E13E4305DA92E85, 0xC3B21D3632A1A4B7,
+    0x08D4B1EA21F716E4, 0x698F77FF2780030C, 0x2D408DA0CD4F99A5, 0x20D3A2B30A5D2F42,
+    0xF9B4CBDA11D0BE7D, 0xC1DB9BBD17AB81A2, 0xCA5C6A0817552E55, 0x0027F0147F8607E1,
+    0x640B148D4196DEBE, 0x872AFDDAB6256B34, 0x897BFEF3059EBFB9, 0x4F6A68A82A4A5AC4,
+    0x4FBCF82D985AD795, 0xC7F48D4D0DA63A20, 0x5F57A4B13F149538, 0x800120CC86DD71B6,
+    0xDEC9F560BF11654D, 0x6B0701ACB08CD0C0, 0xB24855510EFB1EC3, 0x72953B06A33540C0,
+    0x7BDC06CC45E0FA29, 0x4EC8CAD641F3E8DE, 0x647CD8649B31BED9, 0xC397A4D45877C5E3,
+    0x6913DAF03C3ABA46, 0x18465F7555F5BDD2, 0xC6926E5D2EACED44, 0x0E423E1C87C461E9,
+    0xFD29F3D6E7CA7C22, 0x35916FC5E0088DD7, 0xFFE26A6EC6FDB0C1, 0x0893745D7CB2AD6B,
+    0x9D6ECD7B723E6A11, 0xC6A9CFF7DF7329BA, 0xC9B55100B70DB2E2, 0x24BA74607DE58AD8,
+    0x742C150D0C188194, 0x667E162901767A9F, 0xBEFDFDEF4556367E, 0xD913D9ECB9BA8BFC,
+    0x97C427A831C36EF1, 0x36C59456A8D8B5A8, 0xB40ECCCF2D891234, 0x576F89562CE3CE99,
+    0xB920D6AA5E6B9C2A, 0x3ECC5F114A0BFDFB, 0xF4E16D3B8E2C86E2, 0x84D4E9A9B4FCD1EE,
+    0xEFC9352E61392F44, 0x2138C8D91B0AFC81, 0x6A4AFBD81C2F84B4, 0x538C994ECC2254DC,
+    0x552AD6C6C096190B, 0xB8701A649569605A, 0x26EE523F0F117F11, 0xB5F4F5CBFC2DBC34,
+    0xEEBC34CC5DE8605E, 0xDD9B8E67EF3392B8, 0x17C99B5861BC57E1, 0xC68351103ED84871,
+    0xDDDD1C2DA118AF46, 0x2C21D7F359987AD9, 0xC0549EFA864FFC06, 0x56AE79E536228922,
+    0xAD38DC9367AAE855, 0x3826829BE7CAA40D, 0x51B133990ED7A948, 0x0569F0B265A7887F,
+    0x974C8836D1F9B392, 0x214A827B21CF98DC, 0x9F405547DC3A74E1, 0x42EB67DF9DFE5FD4,
+    0x5EA4677B7AACBAA2, 0xF65523882B55BA41, 0x086E59862A218347, 0x39E6E389D49EE540,
+    0xFB49E956FFCA0F1C, 0x8A59C52BFA94C5C1, 0xD3CFC50FAE5ADB86, 0xC5476243853B8621,
+    0x94792C8761107B4C, 0x2A1A2C8012BF4390, 0x2688893C78E4C4A8, 0x7BDBE5C23AC4EAF4,
+    0x268A67F7BF920D2B, 0xA365B1933D0B7CBD, 0xDC51A463DD27DDE1, 0x6919949A9529A828,
+    0xCE68B4ED09209F44, 0xCA984E638270237C, 0x7E32B90F8EF5A7E7, 0x561408F1212A9DB5,
+    0x4D7E6F5119A5ABF9, 0xB5D6DF8261DD9602, 0x36169F3AC4A1A283, 0x6DED727A8D39A9B8,
+    0x825C326B5B2746ED, 0x34007700D255F4FC, 0x4D59018071E0E13F, 0x89B295F364A8F1AE,
+    0xA74B38FC4CEAB2BB, 0x47270BABC3A734BA, 0x6052DD34F8563AEB, 0x7E8A31BB365895B7
+];
+const WINDOW: usize = 5; // words of 2/pi multiplied in: 320 bits, which leaves over 180 good ones after cancellation
+const PRODUCT: usize = WINDOW + 1;
+
+// shifts a big-endian multiword integer left by n < 64 * PRODUCT bits
+fn shift_left(a: &mut [u64; PRODUCT], n: u32) {
+    let (words, bits) = ((n / 64) as usize, n % 64);
+    for i in 0..PRODUCT {
+        let high = a.get(i + words).copied().unwrap_or(0);
+        let low = a.get(i + words + 1).copied().unwrap_or(0);
+        a[i] = if bits == 0 { high } else { (high << bits) | (low >> (64 - bits)) };
+    }
+}
+
+// Payne-Hanek for x = m * 2^e, m with its top bit set and e >= -36 (so x >= 2^27)
+fn reduce_large(m: u64, e: i32) -> (u32, Wide) {
+    // the bits of 2/pi before s only add multiples of 4 to x * 2/pi, which don't change the quadrant
+    let s = (e - 1).max(1) as usize;
+    let (word, shift) = ((s - 1) / 64, ((s - 1) % 64) as u32);
+    let mut window = [0u64; WINDOW];
+    for (i, w) in window.iter_mut().enumerate() {
+        // the table covers every exponent a format can have, so the zeros never come into it
+        let bits = |j: usize| TWO_OVER_PI_BITS.get(j).copied().unwrap_or(0);
+        let (high, low) = (bits(word + i), bits(word + i + 1));
+        *w = if shift == 0 { high } else { (high << shift) | (low >> (64 - shift)) };
+    }
+    let mut p = [0u64; PRODUCT];
+    let mut carry = 0u128;
+    for i in (0..WINDOW).rev() {
+        let t = m as u128 * window[i] as u128 + carry;
+        p[i + 1] = t as u64;
+        carry = t >> 64;
+    }
+    p[0] = carry as u64;
+    // x * 2/pi = p * 2^(e - s - 64 * WINDOW + 1). line the two integer bits up with the top of p: everything
+    // above them is a multiple of 4, and the 382 bits below are the fraction
+    let point = s as i32 + 64 * WINDOW as i32 - 1 - e;
+    shift_left(&mut p, (64 * PRODUCT as i32 - 2 - point) as u32);
+    let mut quadrant = (p[0] >> 62) as u32;
+    p[0] &= (1 << 62) - 1;
+    // a fraction past one half is the next quadrant minus something
+    let negative = p[0] >> 61 == 1;
+    if negative {
+        let mut carry = true; // two's complement: invert and add one
+        for w in p.iter_mut().rev() {
+            (*w, carry) = (!*w).overflowing_add(carry as u64);
+        }
+        p[0] &= (1 << 62) - 1;
+        quadrant += 1;
+    }
+    let zeros = p.iter().take_while(|&&w| w == 0).count() as u32 * 64 + p.iter().find(|&&w| w != 0).map_or(0, |w| w.leading_zeros());
+    if zeros >= 64 * PRODUCT as u32 - 128 {
+        return (quadrant & 3, Wide::ZERO); // can't happen for a format's x, but don't shift past the end
+    }
+    shift_left(&mut p, zeros);
+    let significand = ((p[0] as u128) << 64) | p[1] as u128 | p[2..].iter().any(|&w| w != 0) as u128;
+    // the top bit of p was worth 2^1 before the shift
+    let fraction = Wide { sign: negative, exponent: 1 - zeros as i32 - 127, significand };
+    (quadrant & 3, fraction.mul(PI_OVER_2))
+}
+
+// x mod pi/2 for finite x >= 0: the quadrant (which multiple of pi/2 was taken off, mod 4) and r in about
+// [-pi/4, pi/4]
+pub(crate) fn reduce(x: Wide) -> (u32, Wide) {
+    if x.is_zero() || x.magnitude() < -1 {
+        return (0, x); // under pi/4 already
+    }
+    if x.magnitude() < CODY_WAITE_LIMIT {
+        let k = x.mul(TWO_OVER_PI).round_to_int();
+        let wk = Wide::from_int(k);
+        let r = x.sub(wk.mul(PIO2_1)).sub(wk.mul(PIO2_2)).sub(wk.mul(PIO2_3));
+        return ((k & 3) as u32, r);
+    }
+    let top = (x.significand >> 64) as u64; // formats have at most 64 significant bits
+    reduce_large(top, x.exponent + 64)
+}
+
+// sin r and cos r for |r| <= pi/4 from their taylor series. 18 terms each get below 2^-130
+fn sin_cos_series(r: Wide, bits: i32) -> (Wide, Wide) {
+    let r2 = r.mul(r);
+    let (mut sin, mut sin_term) = (r, r);
+    let (mut cos, mut cos_term) = (Wide::ONE, Wide::ONE);
+    for n in 1..=18 {
+        // sin_term = (-1)^n r^(2n+1)/(2n+1)!, cos_term = (-1)^n r^(2n)/(2n)!
+        cos_term = cos_term.mul(r2).div(Wide::from_int((2 * n - 1) * (2 * n))).neg();
+        sin_term = sin_term.mul(r2).div(Wide::from_int(2 * n * (2 * n + 1))).neg();
+        (sin, cos) = (sin.add(sin_term), cos.add(cos_term));
+        if sin_term.is_zero() || sin_term.magnitude() < sin.magnitude() - bits {
+            break;
+        }
+    }
+    (sin, cos)
+}
+
+// sin x and cos x in Wide, for finite x
+pub(crate) fn sin_cos_wide(x: Wide, bits: i32) -> (Wide, Wide) {
+    let (quadrant, r) = reduce(Wide { sign: false, ..x });
+    let (sin, cos) = sin_cos_series(r, bits);
+    let (sin, cos) = match quadrant {
+        0 => (sin, cos),
+        1 => (cos, sin.neg()),
+        2 => (sin.neg(), cos.neg()),
+        _ => (cos.neg(), sin),
+    };
+    (if x.sign { sin.neg() } else { sin }, cos)
+}
+
+// 2|x| split exactly into n + s, with n an integer and |s| <= 1/2, so pi |x| = (n + s) pi/2 with nothing to
+// reduce by. only n mod 4 is kept, which is all sin and cos need
+fn reduce_pi(x: Wide) -> (u32, Wide) {
+    let y = Wide { sign: false, ..x }.scale(1);
+    if y.is_zero() || y.exponent >= 0 {
+        let n = match y.exponent {
+            0 => y.significand,
+            1 => y.significand << 1,
+            _ => 0,
+        };
+        return (n as u32 & 3, Wide::ZERO);
+    }
+    let (n, fraction) = match -y.exponent {
+        shift @ 1..=127 => (y.significand >> shift, y.significand & ((1 << shift) - 1)),
+        _ => (0, y.significand),
+    };
+    let s = Wide::new(false, y.exponent, fraction);
+    // past a half, s - 1 instead, and n + 1
+    if !s.is_zero() && s.magnitude() >= -1 && s != Wide::ONE.scale(-1) {
+        return ((n as u32).wrapping_add(1) & 3, s.sub(Wide::ONE));
+    }
+    (n as u32 & 3, s)
+}
+
+// sin(pi x) and cos(pi x), exact (zeros and ones) at multiples of a half
+pub(crate) fn sin_cos_pi_wide(x: Wide, bits: i32) -> (Wide, Wide) {
+    let (quadrant, s) = reduce_pi(x);
+    let (sin, cos) = sin_cos_series(PI_OVER_2.mul(s), bits);
+    let (sin, cos) = match quadrant {
+        0 => (sin, cos),
+        1 => (cos, sin.neg()),
+        2 => (sin.neg(), cos.neg()),
+        _ => (cos.neg(), sin),
+    };
+    (if x.sign { sin.neg() } else { sin }, cos)
+}
+
+// sin(pi x), for the reflection formula in gamma.rs
+pub(crate) fn sin_pi_wide(x: Wide, bits: i32) -> Wide {
+    sin_cos_pi_wide(x, bits).0
+}
+
+// atan x for |x| <= 1/8 from x - x^3/3 + x^5/5 - ..., which gets below 2^-130 in 22 terms
+fn atan_series(x: Wide, bits: i32) -> Wide {
+    let x2 = x.mul(x);
+    let (mut sum, mut power) = (x, x);
+    for n in 1..=22 {
+        power = power.mul(x2).neg();
+        sum = sum.add(power.div(Wide::from_int(2 * n + 1)));
+        if power.is_zero() || power.magnitude() < sum.magnitude() - bits {
+            break;
+        }
+    }
+    sum
+}
+
+// atan x for finite x. past 1 it's pi/2 - atan(1/x), then atan x = 2 atan(x/(1 + sqrt(1 + x^2))) halves the
+// argument until the series converges quickly. small x goes straight to the series, so the result stays on the
+// right side of x
+pub(crate) fn atan_wide(x: Wide, bits: i32) -> Wide {
+    let mut a = Wide { sign: false, ..x };
+    let flipped = !a.is_zero() && a.magnitude() >= 0 && a != Wide::ONE;
+    if flipped {
+        a = Wide::ONE.div(a);
+    }
+    let mut halvings = 0;
+    while !a.is_zero() && a.magnitude() >= -3 {
+        a = a.div(Wide::ONE.add(Wide::ONE.add(a.mul(a)).sqrt()));
+        halvings += 1;
+    }
+    let mut result = atan_series(a, bits).scale(halvings);
+    if flipped {
+        result = PI_OVER_2.sub(result);
+    }
+    Wide { sign: x.sign, ..result }
+}
+
+// asin x for |x| < 1: atan(x/sqrt(1 - x^2)), with 1 - x^2 as (1 - x)(1 + x) so it's exact. tiny x uses
+// x + x^3/6 directly, as atan_wide does
+fn asin_wide(x: Wide, bits: i32) -> Wide {
+    if x.is_zero() || x.magnitude() < -40 {
+        return x.add(x.mul(x).mul(x).div(Wide::from_int(6)));
+    }
+    atan_wide(x.div(Wide::ONE.sub(x).mul(Wide::ONE.add(x)).sqrt()), bits)
+}
+
+impl<F: Format> SoftFloat<F> {
+    // nans propagate, infinities are invalid. None for finite x
+    fn trig_special(&self, ctx: &mut Context) -> Option<Self> {
+        if let Some(nan) = self.propagate_nan(self, ctx) {
+            return Some(nan);
+        }
+        if self.is_infinity() {
+            ctx.flags.raise(Flags::INVALID);
+            return Some(Self::default_nan(ctx.nan_mode));
+        }
+        None
+    }
+
+    /// sin x for any finite x, huge ones included. sin(±0) = ±0, sin(±inf) is invalid.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn sin(&self, ctx: &mut Context) -> Self {
+        let x = self.flushed(ctx);
+        if let Some(special) = x.trig_special(ctx) {
+            return special;
+        }
+        if x.is_zero() {
+            return x;
+        }
+        sin_cos_wide(Wide::from_soft(&x), series_bits::<F>(ctx)).0.round(ctx)
+    }
+
+    /// cos x for any finite x. cos(±0) = 1, cos(±inf) is invalid.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn cos(&self, ctx: &mut Context) -> Self {
+        let x = self.flushed(ctx);
+        if let Some(special) = x.trig_special(ctx) {
+            return special;
+        }
+        sin_cos_wide(Wide::from_soft(&x), series_bits::<F>(ctx)).1.round(ctx)
+    }
+
+    /// (sin x, cos x) from one argument reduction, which is most of the cost for large x. Both results are
+    /// the same as sin and cos give, and the flags are those of both.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn sin_cos(&self, ctx: &mut Context) -> (Self, Self) {
+        let x = self.flushed(ctx);
+        if let Some(special) = x.trig_special(ctx) {
+            return (special, special);
+        }
+        let (sin, cos) = sin_cos_wide(Wide::from_soft(&x), series_bits::<F>(ctx));
+        let sin = if x.is_zero() { x } else { sin.round(ctx) };
+        (sin, cos.round(ctx))
+    }
+
+    /// x mod pi/2, for building other periodic functions on: (q, hi, lo) with x = (4k + q) pi/2 + hi + lo for
+    /// some integer k, q in 0..4 and |hi + lo| at most about pi/4. hi is the remainder rounded to nearest and lo
+    /// what's left of it, also rounded to nearest, so the pair carries about twice the format's precision (less
+    /// where lo underflows). The remainder is good to about 2^-120 relative even for the x closest to a multiple
+    /// of pi/2: Cody-Waite up to 2^27 and Payne-Hanek past it, the same reduction sin and cos use. The split
+    /// raises no flags of its own; nans and infinities give (0, nan, nan) as sin would.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn reduce_mod_pi_over_2(&self, ctx: &mut Context) -> (u32, Self, Self) {
+        let x = self.flushed(ctx);
+        if let Some(special) = x.trig_special(ctx) {
+            return (0, special, special);
+        }
+        let (quadrant, r) = reduce(Wide { sign: false, ..Wide::from_soft(&x) });
+        // -x = -(q pi/2 + r) = (4 - q) pi/2 - r, give or take a multiple of 2 pi
+        let (quadrant, r) = if x.get_sign() { (quadrant.wrapping_neg() & 3, r.neg()) } else { (quadrant, r) };
+        let mut split = Context::new(RoundingMode::TiesToEven);
+        let hi: Self = r.round(&mut split);
+        let lo = r.sub(Wide::from_soft(&hi)).round(&mut split);
+        (quadrant, hi, lo)
+    }
+
+    /// sin(pi x), without rounding pi x first: exact at multiples of a half, and accurate for huge x, which are
+    /// all even integers anyway. sinpi(±0) = ±0, sinpi(n) = +0 for positive integers n and -0 for negative
+    /// ones, and sinpi(±inf) is invalid, as in IEEE 754's sinPi.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn sinpi(&self, ctx: &mut Context) -> Self {
+        let x = self.flushed(ctx);
+        if let Some(special) = x.trig_special(ctx) {
+            return special;
+        }
+        let sin = sin_cos_pi_wide(Wide::from_soft(&x), series_bits::<F>(ctx)).0;
+        if sin.is_zero() {
+            return Self::zero(x.get_sign());
+        }
+        sin.round(ctx)
+    }
+
+    /// cos(pi x), exact at multiples of a half like sinpi. cospi(±0) = 1, cospi(n + 1/2) = +0 for every integer
+    /// n, and cospi(±inf) is invalid.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn cospi(&self, ctx: &mut Context) -> Self {
+        let x = self.flushed(ctx);
+        if let Some(special) = x.trig_special(ctx) {
+            return special;
+        }
+        let cos = sin_cos_pi_wide(Wide::from_soft(&x), series_bits::<F>(ctx)).1;
+        if cos.is_zero() {
+            return Self::zero(false);
+        }
+        cos.round(ctx)
+    }
+
+    /// tan x for any finite x. tan(±0) = ±0, tan(±inf) is invalid. No float is close enough to an odd multiple
+    /// of pi/2 for the result to overflow.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn tan(&self, ctx: &mut Context) -> Self {
+        let x = self.flushed(ctx);
+        if let Some(special) = x.trig_special(ctx) {
+            return special;
+        }
+        if x.is_zero() {
+            return x;
+        }
+        let w = Wide::from_soft(&x);
+        if w.magnitude() < -40 {
+            // tan x = x + x^3/3 + ..., within 2^-80 of x: sin/cos would lose which side of x it's on, since
+            // both are truncated. the next term is below 2^-160 of x
+            return w.add(w.mul(w).mul(w).div(Wide::from_int(3))).round(ctx);
+        }
+        let (sin, cos) = sin_cos_wide(w, series_bits::<F>(ctx));
+        sin.div(cos).round(ctx)
+    }
+
+    // |x| > 1 is invalid for asin and acos. None for x in [-1, 1]
+    fn inverse_special(&self, ctx: &mut Context) -> Option<Self> {
+        if let Some(nan) = self.propagate_nan(self, ctx) {
+            return Some(nan);
+        }
+        let beyond_one = !self.is_zero() && !self.is_infinity() && {
+            let w = Wide { sign: false, ..Wide::from_soft(self) };
+            w.magnitude() >= 0 && w != Wide::ONE
+        };
+        if self.is_infinity() || beyond_one {
+            ctx.flags.raise(Flags::INVALID);
+            return Some(Self::default_nan(ctx.nan_mode));
+        }
+        None
+    }
+
+    /// asin x, in [-pi/2, pi/2]. asin(±0) = ±0, and |x| > 1 is invalid.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn asin(&self, ctx: &mut Context) -> Self {
+        let x = self.flushed(ctx);
+        if let Some(special) = x.inverse_special(ctx) {
+            return special;
+        }
+        if x.is_zero() {
+            return x;
+        }
+        let w = Wide::from_soft(&x);
+        if w.magnitude() >= 0 {
+            return Wide { sign: w.sign, ..PI_OVER_2 }.round(ctx); // ±1
+        }
+        asin_wide(w, series_bits::<F>(ctx)).round(ctx)
+    }
+
+    /// acos x, in [0, pi]. acos(1) = +0, and |x| > 1 is invalid.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn acos(&self, ctx: &mut Context) -> Self {
+        let x = self.flushed(ctx);
+        if let Some(special) = x.inverse_special(ctx) {
+            return special;
+        }
+        let w = Wide::from_soft(&x);
+        if w == Wide::ONE {
+            return Self::zero(false);
+        }
+        if w == Wide::ONE.neg() {
+            return PI_OVER_2.scale(1).round(ctx);
+        }
+        // 2 atan(sqrt((1 - x)/(1 + x))) doesn't cancel near x = 1 the way pi/2 - asin x would
+        atan_wide(Wide::ONE.sub(w).div(Wide::ONE.add(w)).sqrt(), series_bits::<F>(ctx)).scale(1).round(ctx)
+    }
+
+    /// atan x, in [-pi/2, pi/2]. atan(±0) = ±0 and atan(±inf) = ±pi/2.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn atan(&self, ctx: &mut Context) -> Self {
+        let x = self.flushed(ctx);
+        if let Some(nan) = x.propagate_nan(&x, ctx) {
+            return nan;
+        }
+        if x.is_zero() {
+            return x;
+        }
+        if x.is_infinity() {
+            return Wide { sign: x.get_sign(), ..PI_OVER_2 }.round(ctx);
+        }
+        atan_wide(Wide::from_soft(&x), series_bits::<F>(ctx)).round(ctx)
+    }
+
+    /// The angle of the point (x, y), in [-pi, pi], with self as y: y.atan2(&x). Signed zeros and infinities
+    /// follow C's Annex F: atan2(±0, -0) = ±pi, atan2(±0, +0) = ±0, atan2(±inf, -inf) = ±3pi/4,
+    /// atan2(±inf, +inf) = ±pi/4, and the result always has the sign of y.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn atan2(&self, other: &Self, ctx: &mut Context) -> Self {
+        let (y, x) = (self.flushed(ctx), other.flushed(ctx));
+        if let Some(nan) = y.propagate_nan(&x, ctx) {
+            return nan;
+        }
+        let (sign, left) = (y.get_sign(), x.get_sign());
+        let angle = if y.is_zero() || (x.is_infinity() && !y.is_infinity()) {
+            // on the x axis, or as good as
+            if !left {
+                return Self::zero(sign);
+            }
+            PI_OVER_2.scale(1)
+        } else if x.is_zero() || (y.is_infinity() && !x.is_infinity()) {
+            PI_OVER_2
+        } else if y.is_infinity() {
+            let quarter = PI_OVER_2.scale(-1);
+            if left {
+                PI_OVER_2.add(quarter)
+            } else {
+                quarter
+            }
+        } else {
+            let (ay, ax) = (Wide { sign: false, ..Wide::from_soft(&y) }, Wide { sign: false, ..Wide::from_soft(&x) });
+            // atan of a ratio no bigger than 1, so the huge and tiny ones don't overflow the division
+            let first = if ay.magnitude() < ax.magnitude() || (ay.magnitude() == ax.magnitude() && ay.significand <= ax.significand) {
+                atan_wide(ay.div(ax), series_bits::<F>(ctx))
+            } else {
+                PI_OVER_2.sub(atan_wide(ax.div(ay), series_bits::<F>(ctx)))
+            };
+            if left {
+                PI_OVER_2.scale(1).sub(first)
+            } else {
+                first
+            }
+        };
+        Wide { sign, ..angle }.round(ctx)
+    }
+}
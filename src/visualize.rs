@@ -0,0 +1,129 @@
+use crate::Float;
+
+const RESET: &str = "\x1b[0m";
+const SIGN_COLOR: &str = "\x1b[1;34m"; // bold blue
+const EXPONENT_COLOR: &str = "\x1b[1;32m"; // bold green
+const MANTISSA_COLOR: &str = "\x1b[1;31m"; // bold red
+const DIFF_COLOR: &str = "\x1b[1;30;43m"; // bold black on yellow
+
+// wraps text in an ansi color, or leaves it alone when color is off
+fn paint(text: &str, color: &str, enabled: bool) -> String {
+    if enabled {
+        format!("{}{}{}", color, text, RESET)
+    } else {
+        text.to_string()
+    }
+}
+
+impl Float {
+    /// Renders the bit layout with each field colored, a legend, and the field values underneath.
+    ///
+    /// Pass `color: false` to get the same layout without ansi escapes (e.g. when not writing to a terminal).
+    pub fn layout(&self, color: bool) -> String {
+        let bits = format!("{:064b}", self.to_bits());
+        let biased = (self.to_bits() >> 52) & ((1 << 11) - 1);
+        let is_subnormal = biased == 0 && !self.is_zero();
+        let implicit = if biased == 0 { 0 } else { 1 };
+
+        let mut out = String::new();
+        out += &format!(
+            "{} {} {}\n",
+            paint(&bits[0..1], SIGN_COLOR, color),
+            paint(&bits[1..12], EXPONENT_COLOR, color),
+            paint(&bits[12..64], MANTISSA_COLOR, color)
+        );
+        out += &format!(
+            "{} {} {}\n",
+            paint("s", SIGN_COLOR, color),
+            paint(&"e".repeat(11), EXPONENT_COLOR, color),
+            paint(&"m".repeat(52), MANTISSA_COLOR, color)
+        );
+        out += &format!(
+            "{} sign  {} exponent  {} mantissa\n\n",
+            paint("■", SIGN_COLOR, color),
+            paint("■", EXPONENT_COLOR, color),
+            paint("■", MANTISSA_COLOR, color)
+        );
+        out += &format!(
+            "{}     = {} ({})\n",
+            paint("sign", SIGN_COLOR, color),
+            self.get_sign() as u8,
+            if self.get_sign() { "negative" } else { "positive" }
+        );
+        out += &format!(
+            "{} = {} - 1023 = {}{}\n",
+            paint("exponent", EXPONENT_COLOR, color),
+            biased,
+            self.get_exponent(),
+            if is_subnormal { " (subnormal, interpreted as -1022)" } else { "" }
+        );
+        out += &format!(
+            "{} = 0x{:013x} (significand {}.{})\n",
+            paint("mantissa", MANTISSA_COLOR, color),
+            self.get_mantissa(),
+            implicit,
+            &bits[12..64]
+        );
+        out += &format!("value    = {:e}\n", self.to_f64());
+        out
+    }
+
+    pub fn print_layout(&self) {
+        print!("{}", self.layout(true));
+    }
+
+    /// Renders self and other one above the other with the bits that differ highlighted,
+    /// followed by the fields that differ and the ulp distance between them.
+    pub fn bit_diff(&self, other: &Float, color: bool) -> String {
+        let ours = format!("{:064b}", self.to_bits());
+        let theirs = format!("{:064b}", other.to_bits());
+        let changed = self.to_bits() ^ other.to_bits();
+
+        // highlights the differing bits of one row and splits it into fields
+        let row = |bits: &str| -> String {
+            let mut out = String::new();
+            for (i, c) in bits.chars().enumerate() {
+                if i == 1 || i == 12 {
+                    out.push(' ');
+                }
+                let differs = (changed >> (63 - i)) & 1 == 1;
+                out += &paint(&c.to_string(), DIFF_COLOR, color && differs);
+            }
+            out
+        };
+        let mut markers = String::new();
+        for i in 0..64 {
+            if i == 1 || i == 12 {
+                markers.push(' ');
+            }
+            markers.push(if (changed >> (63 - i)) & 1 == 1 { '^' } else { ' ' });
+        }
+
+        let mut fields = Vec::new();
+        if changed >> 63 != 0 {
+            fields.push("sign");
+        }
+        if (changed >> 52) & ((1 << 11) - 1) != 0 {
+            fields.push("exponent");
+        }
+        if changed & ((1 << 52) - 1) != 0 {
+            fields.push("mantissa");
+        }
+
+        let mut out = String::new();
+        out += &format!("{}  {:e}\n", row(&ours), self.to_f64());
+        out += &format!("{}  {:e}\n", row(&theirs), other.to_f64());
+        out += &format!("{}\n", markers.trim_end());
+        if fields.is_empty() {
+            out += "identical bits\n";
+        } else {
+            out += &format!("differs in: {}\n", fields.join(", "));
+        }
+        out += &format!("ulp distance: {}\n", self.ulp_distance(other));
+        out
+    }
+
+    pub fn print_bit_diff(&self, other: &Float) {
+        print!("{}", self.bit_diff(other, true));
+    }
+}
@@ -0,0 +1,160 @@
+// wasm-bindgen bindings for the browser playground. build with `wasm-pack build bindings --features wasm`.
+// u64s become BigInts on the js side, and u128s (which js can't take) are passed as binary strings.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{Float, RoundingDecision, RoundingMode, SpecialCase};
+
+#[wasm_bindgen(js_name = Float)]
+pub struct JsFloat {
+    inner: Float,
+}
+
+#[wasm_bindgen(js_class = Float)]
+impl JsFloat {
+    #[wasm_bindgen(constructor)]
+    pub fn new(value: f64) -> JsFloat {
+        JsFloat { inner: Float::new(value) }
+    }
+
+    #[wasm_bindgen(js_name = fromBits)]
+    pub fn from_bits(bits: u64) -> JsFloat {
+        JsFloat { inner: Float::from_bits(bits) }
+    }
+
+    // accepts 16 hex digits, with or without a 0x prefix
+    #[wasm_bindgen(js_name = fromHex)]
+    pub fn from_hex(hex: &str) -> Option<JsFloat> {
+        let digits = hex.trim().trim_start_matches("0x");
+        u64::from_str_radix(digits, 16).ok().map(JsFloat::from_bits)
+    }
+
+    #[wasm_bindgen(js_name = fromParts)]
+    pub fn from_parts(sign: bool, exponent: i16, mantissa: u64) -> JsFloat {
+        JsFloat { inner: Float::from_parts(sign, exponent, mantissa) }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn value(&self) -> f64 {
+        self.inner.to_f64()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn bits(&self) -> u64 {
+        self.inner.to_bits()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn hex(&self) -> String {
+        format!("0x{:016x}", self.inner.to_bits())
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn sign(&self) -> bool {
+        self.inner.get_sign()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn exponent(&self) -> i16 {
+        self.inner.get_exponent()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn mantissa(&self) -> u64 {
+        self.inner.get_mantissa()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn classification(&self) -> String {
+        format!("{:?}", self.inner.classify()).to_lowercase()
+    }
+
+    // plain-text version of the colored layout
+    pub fn layout(&self) -> String {
+        self.inner.layout(false)
+    }
+
+    #[wasm_bindgen(js_name = nextUp)]
+    pub fn next_up(&self) -> JsFloat {
+        JsFloat { inner: self.inner.next_up() }
+    }
+
+    #[wasm_bindgen(js_name = nextDown)]
+    pub fn next_down(&self) -> JsFloat {
+        JsFloat { inner: self.inner.next_down() }
+    }
+
+    pub fn multiply(&self, other: &JsFloat, mode: RoundingMode) -> JsFloat {
+        JsFloat { inner: self.inner.multiply_with_rounding(&other.inner, mode) }
+    }
+
+    pub fn add(&self, other: &JsFloat, mode: RoundingMode) -> JsFloat {
+        JsFloat { inner: self.inner.add_with_rounding(&other.inner, mode) }
+    }
+
+    #[wasm_bindgen(js_name = explainMultiply)]
+    pub fn explain_multiply(&self, other: &JsFloat, mode: RoundingMode) -> Explanation {
+        let e = self.inner.multiply_explained_with_rounding(&other.inner, mode);
+        Explanation {
+            sign: e.sign,
+            exponent_sum: e.exponent_sum,
+            product: format!("{:0106b}", e.product),
+            normalization_shift: e.normalization_shift,
+            normalized_exponent: e.normalized_exponent,
+            shift: e.shift,
+            guard: e.guard,
+            round: e.round,
+            sticky: e.sticky,
+            rounding: match e.rounding {
+                RoundingDecision::Exact => "exact",
+                RoundingDecision::RoundDown => "down",
+                RoundingDecision::RoundUp => "up",
+            }
+            .to_string(),
+            special: e.special.map(|s| {
+                match s {
+                    SpecialCase::Nan => "nan",
+                    SpecialCase::InfinityTimesZero => "infinity times zero",
+                    SpecialCase::InfinityMinusInfinity => "infinity minus infinity",
+                    SpecialCase::Infinity => "infinity",
+                    SpecialCase::Zero => "zero",
+                    SpecialCase::Cancellation => "cancellation",
+                    SpecialCase::Overflow => "overflow",
+                    SpecialCase::Underflow => "underflow",
+                }
+                .to_string()
+            }),
+            text: e.to_string(),
+            result: JsFloat { inner: e.result },
+        }
+    }
+}
+
+/// The stages of `MultiplyExplanation` in a shape js can read directly.
+#[wasm_bindgen(getter_with_clone)]
+pub struct Explanation {
+    pub sign: bool,
+    #[wasm_bindgen(js_name = exponentSum)]
+    pub exponent_sum: i16,
+    pub product: String,
+    #[wasm_bindgen(js_name = normalizationShift)]
+    pub normalization_shift: i32,
+    #[wasm_bindgen(js_name = normalizedExponent)]
+    pub normalized_exponent: i16,
+    pub shift: u32,
+    pub guard: bool,
+    pub round: bool,
+    pub sticky: bool,
+    pub rounding: String,
+    pub special: Option<String>,
+    pub text: String, // the same breakdown the Display impl prints
+    result: JsFloat,
+}
+
+#[wasm_bindgen]
+impl Explanation {
+    #[wasm_bindgen(getter)]
+    pub fn result(&self) -> JsFloat {
+        JsFloat { inner: self.result.inner.copy() }
+    }
+}
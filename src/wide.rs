@@ -0,0 +1,349 @@
+// the unrounded intermediate the math functions compute in: (-1)^sign * significand * 2^exponent, with the
+// significand's leading 1 at bit 127 (or a zero significand). that's twice the precision of the widest format,
+// so rounding a Wide result once with round_pack gives the correctly rounded answer except when the true value
+// is within about 2^-120 of a halfway point. operations truncate, jamming whatever they drop into the last bit
+// like the adders do, so a result that is off by a little is still off on the right side of a representable value.
+
+use crate::format::Format;
+use crate::{Context, SoftFloat};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Wide {
+    pub(crate) sign: bool,
+    pub(crate) exponent: i32,
+    pub(crate) significand: u128,
+}
+
+// shifts right, collapsing everything shifted out into the last bit
+fn shift_jam(significand: u128, shift: u32) -> u128 {
+    if shift == 0 {
+        significand
+    } else if shift >= 128 {
+        (significand != 0) as u128
+    } else {
+        (significand >> shift) | (significand & ((1 << shift) - 1) != 0) as u128
+    }
+}
+
+// the integer cube root, rounded down, a bit at a time
+fn icbrt(mut n: u128) -> u128 {
+    let mut root = 0u128;
+    for shift in (0..=126).rev().step_by(3) {
+        root <<= 1;
+        // (root + 1)^3 - root^3, for the candidate root + 1 at this bit position
+        let step = 3 * root * (root + 1) + 1;
+        if n >> shift >= step {
+            n -= step << shift;
+            root += 1;
+        }
+    }
+    root
+}
+
+// a two's complement sum in a 512-bit window whose bit 0 is worth 2^base, exact for terms that land inside it
+struct Window {
+    limbs: [u128; 4],
+    base: i32,
+}
+
+impl Window {
+    fn new(base: i32) -> Window {
+        Window { limbs: [0; 4], base }
+    }
+
+    // x's bits have to be at or above 2^base, and the sum has to stay below 2^(base + 511)
+    fn add(&mut self, x: Wide) {
+        if x.is_zero() {
+            return;
+        }
+        let offset = (x.exponent - self.base).clamp(0, 383) as u32;
+        let (index, shift) = ((offset / 128) as usize, offset % 128);
+        let mut term = [0u128; 4];
+        for (i, limb) in term.iter_mut().enumerate() {
+            if i == index {
+                *limb = x.significand << shift;
+            } else if i == index + 1 {
+                *limb = x.significand.checked_shr(128 - shift).unwrap_or(0);
+            }
+        }
+        if x.sign {
+            negate(&mut term);
+        }
+        let mut carry = false;
+        for (limb, t) in self.limbs.iter_mut().zip(term) {
+            let (sum, first) = limb.overflowing_add(t);
+            let (sum, second) = sum.overflowing_add(carry as u128);
+            (*limb, carry) = (sum, first || second);
+        }
+    }
+
+    // the sum as a Wide, jammed, and whether that lost anything
+    fn to_wide(&self) -> (Wide, bool) {
+        let sign = self.limbs[3] >> 127 == 1;
+        let mut magnitude = self.limbs;
+        if sign {
+            negate(&mut magnitude);
+        }
+        let Some(top) = magnitude.iter().rposition(|&limb| limb != 0) else {
+            return (Wide::ZERO, false);
+        };
+        let high = magnitude.get(top).copied().unwrap_or(0);
+        let below = top.checked_sub(1).and_then(|i| magnitude.get(i)).copied().unwrap_or(0);
+        let zeros = high.leading_zeros();
+        let significand = (high << zeros) | below.checked_shr(128 - zeros).unwrap_or(0);
+        let dropped = below << zeros != 0 || magnitude.iter().take(top.saturating_sub(1)).any(|&limb| limb != 0);
+        let exponent = self.base + 128 * top as i32 - zeros as i32;
+        (Wide { sign, exponent, significand: significand | dropped as u128 }, dropped)
+    }
+}
+
+fn negate(limbs: &mut [u128; 4]) {
+    let mut carry = true;
+    for limb in limbs.iter_mut() {
+        (*limb, carry) = (!*limb).overflowing_add(carry as u128);
+    }
+}
+
+impl Wide {
+    pub(crate) const ZERO: Wide = Wide { sign: false, exponent: 0, significand: 0 };
+    pub(crate) const ONE: Wide = Wide { sign: false, exponent: -127, significand: 1 << 127 };
+
+    pub(crate) fn new(sign: bool, exponent: i32, significand: u128) -> Wide {
+        if significand == 0 {
+            return Wide { sign, ..Wide::ZERO };
+        }
+        let shift = significand.leading_zeros();
+        Wide { sign, exponent: exponent - shift as i32, significand: significand << shift }
+    }
+
+    pub(crate) fn from_int(value: i64) -> Wide {
+        Wide::new(value < 0, 0, value.unsigned_abs() as u128)
+    }
+
+    // zeros included, infinities and nans not
+    pub(crate) fn from_soft<F: Format>(x: &SoftFloat<F>) -> Wide {
+        if x.is_zero() {
+            return Wide { sign: x.get_sign(), ..Wide::ZERO };
+        }
+        let u = x.unpack();
+        Wide::new(u.sign, u.exponent, u.significand)
+    }
+
+    /// Rounds into F once, raising inexact, underflow and overflow as round_pack does.
+    pub(crate) fn round<F: Format>(&self, ctx: &mut Context) -> SoftFloat<F> {
+        if self.is_zero() {
+            return SoftFloat::zero(self.sign);
+        }
+        SoftFloat::round_pack(self.sign, self.exponent, self.significand, ctx)
+    }
+
+    pub(crate) fn is_zero(&self) -> bool {
+        self.significand == 0
+    }
+
+    pub(crate) fn neg(self) -> Wide {
+        Wide { sign: !self.sign, ..self }
+    }
+
+    // multiplies by 2^k, exactly
+    pub(crate) fn scale(self, k: i32) -> Wide {
+        if self.is_zero() {
+            return self;
+        }
+        Wide { exponent: self.exponent + k, ..self }
+    }
+
+    // the exponent of the leading bit, so the value is in [2^e, 2^(e + 1)). meaningless for zero
+    pub(crate) fn magnitude(&self) -> i32 {
+        self.exponent + 127
+    }
+
+    pub(crate) fn add(self, other: Wide) -> Wide {
+        if self.is_zero() {
+            // the sign of a zero sum doesn't matter to the functions using this, beyond -0 + -0 = -0
+            return if other.is_zero() { Wide { sign: self.sign && other.sign, ..Wide::ZERO } } else { other };
+        }
+        if other.is_zero() {
+            return self;
+        }
+        let (big, small) = if (self.exponent, self.significand) >= (other.exponent, other.significand) {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        // small shifted into a 256-bit (high, low) pair under big, so nothing is dropped until the result is
+        // normalized, and then all of it goes into the sticky bit
+        let shift = (big.exponent - small.exponent) as u32;
+        let (b_high, b_low) = match shift {
+            0 => (small.significand, 0),
+            1..=127 => (small.significand >> shift, small.significand << (128 - shift)),
+            _ => (0, shift_jam(small.significand, shift - 128)),
+        };
+        if big.sign == small.sign {
+            let (high, carry) = big.significand.overflowing_add(b_high);
+            if carry {
+                let significand = (1 << 127) | (high >> 1) | (high & 1 != 0 || b_low != 0) as u128;
+                return Wide { sign: big.sign, exponent: big.exponent + 1, significand };
+            }
+            return Wide { sign: big.sign, exponent: big.exponent, significand: high | (b_low != 0) as u128 };
+        }
+        let (low, borrow) = 0u128.overflowing_sub(b_low);
+        let high = big.significand - b_high - borrow as u128;
+        let (high, low, exponent) = if high == 0 { (low, 0, big.exponent - 128) } else { (high, low, big.exponent) };
+        if high == 0 {
+            return Wide::ZERO;
+        }
+        let zeros = high.leading_zeros();
+        if zeros == 0 {
+            return Wide { sign: big.sign, exponent, significand: high | (low != 0) as u128 };
+        }
+        let significand = (high << zeros) | (low >> (128 - zeros)) | (low << zeros != 0) as u128;
+        Wide::new(big.sign, exponent - zeros as i32, significand)
+    }
+
+    pub(crate) fn sub(self, other: Wide) -> Wide {
+        self.add(other.neg())
+    }
+
+    // the magnitude of the product, exactly: (exponent, high, low) for (high * 2^128 + low) * 2^exponent, with the
+    // leading bit of high at 127, so two of them compare as tuples. a zero product is (i32::MIN, 0, 0)
+    pub(crate) fn mul_exact(self, other: Wide) -> (i32, u128, u128) {
+        if self.is_zero() || other.is_zero() {
+            return (i32::MIN, 0, 0);
+        }
+        // the 256-bit product from four 64x64 ones
+        let (a_hi, a_lo) = (self.significand >> 64, self.significand as u64 as u128);
+        let (b_hi, b_lo) = (other.significand >> 64, other.significand as u64 as u128);
+        let (middle, middle_carry) = (a_hi * b_lo).overflowing_add(a_lo * b_hi);
+        let (low, low_carry) = (a_lo * b_lo).overflowing_add(middle << 64);
+        let high = a_hi * b_hi + (middle >> 64) + ((middle_carry as u128) << 64) + low_carry as u128;
+        // both significands are at least 2^127, so the product is at least 2^254: at most one bit to shift in
+        let shifted = high >> 127 == 0;
+        let (high, low) = if shifted { ((high << 1) | (low >> 127), low << 1) } else { (high, low) };
+        (self.exponent + other.exponent - shifted as i32, high, low)
+    }
+
+    pub(crate) fn mul(self, other: Wide) -> Wide {
+        let sign = self.sign ^ other.sign;
+        if self.is_zero() || other.is_zero() {
+            return Wide { sign, ..Wide::ZERO };
+        }
+        // the high half, and a sticky bit for the low one
+        let (exponent, high, low) = self.mul_exact(other);
+        Wide { sign, exponent: exponent + 128, significand: high | (low != 0) as u128 }
+    }
+
+    // other must be nonzero
+    pub(crate) fn div(self, other: Wide) -> Wide {
+        let sign = self.sign ^ other.sign;
+        if self.is_zero() {
+            return Wide { sign, ..Wide::ZERO };
+        }
+        // restoring division, one quotient bit at a time. the first bit is worth 1, since the ratio of the
+        // significands is in (1/2, 2), and the remainder can grow past 128 bits after a shift, which the carry
+        // keeps track of
+        let (divisor, mut remainder, mut quotient) = (other.significand, self.significand, 0u128);
+        let mut carry = false;
+        for _ in 0..128 {
+            quotient <<= 1;
+            if carry || remainder >= divisor {
+                remainder = remainder.wrapping_sub(divisor);
+                quotient |= 1;
+            }
+            carry = remainder >> 127 == 1;
+            remainder <<= 1;
+        }
+        Wide::new(sign, self.exponent - other.exponent - 127, quotient | (remainder != 0 || carry) as u128)
+    }
+
+    // self must not be negative. the integer square root of the significand is a first guess good to 64 bits,
+    // and two newton steps take it past 128
+    pub(crate) fn sqrt(self) -> Wide {
+        if self.is_zero() {
+            return self;
+        }
+        let (significand, exponent) = if self.exponent % 2 != 0 {
+            (self.significand >> 1, self.exponent + 1)
+        } else {
+            (self.significand, self.exponent)
+        };
+        let mut root = Wide::new(false, exponent / 2, significand.isqrt());
+        for _ in 0..2 {
+            root = root.add(self.div(root)).scale(-1);
+        }
+        root
+    }
+
+    // exact when self is a cube of something under 2^128 (the only way the root can be representable or a halfway
+    // point), which is checked on the odd part of the significand first. otherwise the integer cube root is a
+    // first guess good to 42 bits, and two newton steps take it past 128
+    pub(crate) fn cbrt(self) -> Wide {
+        if self.is_zero() {
+            return self;
+        }
+        let zeros = self.significand.trailing_zeros();
+        let (odd, e) = (self.significand >> zeros, self.exponent + zeros as i32);
+        let root = icbrt(odd);
+        if e % 3 == 0 && root * root * root == odd {
+            return Wide::new(self.sign, e / 3, root);
+        }
+        let shift = 3 - self.exponent.rem_euclid(3) as u32;
+        let x = Wide { sign: false, ..self };
+        let mut root = Wide::new(false, (self.exponent + shift as i32) / 3, icbrt(self.significand >> shift));
+        for _ in 0..2 {
+            root = root.scale(1).add(x.div(root.mul(root))).div(Wide::from_int(3));
+        }
+        Wide { sign: self.sign, ..root }
+    }
+
+    // a + b + c exactly, jammed into one Wide as a single add would be. chaining adds isn't enough: a + b gets
+    // jammed, and if c then cancels most of it, the jammed bit ends up in the rounding. so the terms go into a
+    // window, largest first, and are summed exactly there, except that a term entirely below the last bit of
+    // everything above it only decides which way the rest is off, which its sign (and being nonzero) says
+    pub(crate) fn sum3(terms: [Wide; 3]) -> Wide {
+        let mut terms = terms;
+        terms.sort_unstable_by_key(|w| core::cmp::Reverse(if w.is_zero() { i32::MIN } else { w.exponent }));
+        let [a, b, c] = terms;
+        if b.is_zero() {
+            return a;
+        }
+        if b.magnitude() < a.exponent - 2 {
+            // b + c is under half of a's last bit
+            return a.add(b.add(c));
+        }
+        // a's last bit at bit 300 of the window, b's no more than 129 below it and c's no more than 129 below b's
+        let mut window = Window::new(a.exponent - 300);
+        window.add(a);
+        window.add(b);
+        if c.is_zero() || c.magnitude() >= b.exponent - 2 {
+            window.add(c);
+            return window.to_wide().0;
+        }
+        // c is under half of the last bit of a + b, so it only matters if a + b fit without jamming
+        let (sum, dropped) = window.to_wide();
+        if sum.is_zero() {
+            c
+        } else if dropped {
+            sum
+        } else {
+            sum.add(c)
+        }
+    }
+
+    // the nearest integer, ties away from zero, saturating outside +-2^62
+    pub(crate) fn round_to_int(&self) -> i64 {
+        if self.is_zero() || self.magnitude() < -1 {
+            return 0;
+        }
+        let magnitude = if self.magnitude() >= 62 {
+            1 << 62
+        } else {
+            ((self.significand >> (-self.exponent - 1) as u32) + 1) as i64 >> 1
+        };
+        if self.sign {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+}
@@ -0,0 +1,109 @@
+// x86 SSE MXCSR emulation: the Context an sse unit uses for a given MXCSR value, the status bits an
+// instruction leaves behind, and min/max. only masked exceptions are modeled: with one unmasked the
+// instruction traps instead, and `unmasked` says whether it would.
+
+use crate::{Accuracy, Context, Flags, Format, NanMode, RoundingMode, SoftFloat, Tininess};
+
+/// An MXCSR value: status flags in bits 0-5, DAZ, the six exception masks, the rounding control and FTZ.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mxcsr(pub u32);
+
+impl Default for Mxcsr {
+    fn default() -> Mxcsr {
+        Mxcsr(Mxcsr::RESET)
+    }
+}
+
+impl Mxcsr {
+    pub const RESET: u32 = 0x1F80; // all exceptions masked, round to nearest, no flushing
+    pub const IE: u32 = 1 << 0; // invalid
+    pub const DE: u32 = 1 << 1; // denormal operand
+    pub const ZE: u32 = 1 << 2; // divide by zero
+    pub const OE: u32 = 1 << 3; // overflow
+    pub const UE: u32 = 1 << 4; // underflow
+    pub const PE: u32 = 1 << 5; // precision (inexact)
+    pub const DAZ: u32 = 1 << 6; // denormal operands are read as zero
+    pub const MASK_SHIFT: u32 = 7; // IM DM ZM OM UM PM, in the same order as the flags
+    pub const RC_SHIFT: u32 = 13; // 2-bit rounding control
+    pub const FTZ: u32 = 1 << 15; // tiny results are flushed to zero
+
+    fn has(&self, bit: u32) -> bool {
+        self.0 & bit != 0
+    }
+
+    pub fn rounding(&self) -> RoundingMode {
+        match (self.0 >> Mxcsr::RC_SHIFT) & 3 {
+            0 => RoundingMode::TiesToEven,
+            1 => RoundingMode::TowardNegative,
+            2 => RoundingMode::TowardPositive,
+            _ => RoundingMode::TowardZero,
+        }
+    }
+
+    /// The context for sse arithmetic in any format.
+    pub fn context(&self) -> Context {
+        Context {
+            rounding: self.rounding(),
+            tininess: Tininess::AfterRounding,
+            nan_mode: NanMode::FirstOperand,
+            flush_inputs: self.has(Mxcsr::DAZ),
+            flush_outputs: self.has(Mxcsr::FTZ),
+            accuracy: Accuracy::CorrectlyRounded,
+            flags: Flags::empty(),
+        }
+    }
+
+    /// The status bits an instruction sets, given the flags it raised in `context()` and its operands.
+    /// DE isn't a flag the context knows about: it's set for a denormal operand, unless DAZ is on,
+    /// an operand is nan, or the instruction divided by zero.
+    pub fn status<F: Format>(&self, flags: Flags, operands: &[SoftFloat<F>]) -> u32 {
+        let bits = [
+            (Flags::INVALID, Mxcsr::IE),
+            (Flags::DIVIDE_BY_ZERO, Mxcsr::ZE),
+            (Flags::OVERFLOW, Mxcsr::OE),
+            (Flags::UNDERFLOW, Mxcsr::UE),
+            (Flags::INEXACT, Mxcsr::PE),
+            (Flags::OUTPUT_DENORMAL, Mxcsr::UE | Mxcsr::PE), // a flushed result counts as inexact underflow
+        ];
+        let mut status = bits.iter().filter(|(flag, _)| flags.contains(*flag)).fold(0, |status, (_, bit)| status | bit);
+        let denormal = operands.iter().any(|x| x.biased_exponent() == 0 && !x.is_zero());
+        if denormal
+            && !self.has(Mxcsr::DAZ)
+            && !operands.iter().any(|x| x.is_nan())
+            && !flags.contains(Flags::DIVIDE_BY_ZERO)
+        {
+            status |= Mxcsr::DE;
+        }
+        status
+    }
+
+    /// The status bits in `status` whose exceptions are unmasked, so the instruction would have trapped (#XM).
+    pub fn unmasked(&self, status: u32) -> u32 {
+        status & !(self.0 >> Mxcsr::MASK_SHIFT) & 0x3F
+    }
+}
+
+// minss/minsd and maxss/maxsd (and the packed forms, per lane) return the second operand unless the first
+// compares strictly below (above) it, so a nan in either place or a pair of zeros gives the second. any nan
+// raises invalid, quiet or not.
+fn min_max<F: Format>(a: &SoftFloat<F>, b: &SoftFloat<F>, ctx: &mut Context, want_max: bool) -> SoftFloat<F> {
+    let (a, b) = (a.flushed(ctx), b.flushed(ctx)); // with DAZ the flushed zero is what gets returned
+    if a.is_nan() || b.is_nan() {
+        ctx.flags.raise(Flags::INVALID);
+        return b;
+    }
+    let wanted = if want_max { core::cmp::Ordering::Greater } else { core::cmp::Ordering::Less };
+    if a.compare(&b) == Some(wanted) {
+        a
+    } else {
+        b
+    }
+}
+
+pub fn min<F: Format>(a: &SoftFloat<F>, b: &SoftFloat<F>, ctx: &mut Context) -> SoftFloat<F> {
+    min_max(a, b, ctx, false)
+}
+
+pub fn max<F: Format>(a: &SoftFloat<F>, b: &SoftFloat<F>, ctx: &mut Context) -> SoftFloat<F> {
+    min_max(a, b, ctx, true)
+}
@@ -0,0 +1,158 @@
+// x87 emulation. values on the register stack are 80-bit extended (F80), and the precision control field of
+// the control word makes arithmetic round to 24, 53 or 64 bits, always keeping the 15-bit exponent. so code
+// that loads binary64 operands, computes, and stores the result rounds twice: once in the register and once
+// on the store. that's where the double rounding (and the missing overflows and underflows) of old 32-bit
+// binaries comes from.
+//
+//     let cw = ControlWord::default(); // 64-bit precision, as linux starts out. windows uses 53
+//     let mut ctx = cw.context();
+//     let (x, y) = (ControlWord::load(&a, &mut ctx), ControlWord::load(&b, &mut ctx));
+//     let sum = cw.add(&x, &y, &mut ctx);
+//     let stored = ControlWord::store(&sum, &mut ctx); // an F64
+
+use crate::format::{Extended, Format};
+use crate::{Context, Flags, NanMode, RoundingMode, SoftFloat, Tininess, F64, F80};
+
+// arithmetic rounded to 53 or 24 bits still has the full exponent range
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct DoublePrecision;
+
+impl Format for DoublePrecision {
+    type Bits = u128;
+    const EXPONENT_BITS: u32 = 15;
+    const MANTISSA_BITS: u32 = 52;
+    const NAME: &'static str = "x87 extended, 53-bit precision";
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct SinglePrecision;
+
+impl Format for SinglePrecision {
+    type Bits = u128;
+    const EXPONENT_BITS: u32 = 15;
+    const MANTISSA_BITS: u32 = 23;
+    const NAME: &'static str = "x87 extended, 24-bit precision";
+}
+
+/// An x87 control word. Only the precision and rounding control are modeled; the exception masks are ignored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ControlWord(pub u16);
+
+impl Default for ControlWord {
+    fn default() -> ControlWord {
+        ControlWord(ControlWord::RESET)
+    }
+}
+
+impl ControlWord {
+    pub const RESET: u16 = 0x037F; // what fninit sets: exceptions masked, 64-bit precision, round to nearest
+    pub const PC_SHIFT: u16 = 8; // 2-bit precision control: 0 is 24 bits, 2 is 53, 3 is 64
+    pub const RC_SHIFT: u16 = 10; // 2-bit rounding control, in the same encoding as MXCSR
+
+    pub fn rounding(&self) -> RoundingMode {
+        match (self.0 >> ControlWord::RC_SHIFT) & 3 {
+            0 => RoundingMode::TiesToEven,
+            1 => RoundingMode::TowardNegative,
+            2 => RoundingMode::TowardPositive,
+            _ => RoundingMode::TowardZero,
+        }
+    }
+
+    /// Bits of significand arithmetic results are rounded to. The reserved encoding (1) acts like 64 bits on
+    /// the hardware we checked.
+    pub fn precision(&self) -> u32 {
+        match (self.0 >> ControlWord::PC_SHIFT) & 3 {
+            0 => 24,
+            2 => 53,
+            _ => 64,
+        }
+    }
+
+    /// The context for x87 arithmetic, loads and stores.
+    pub fn context(&self) -> Context {
+        Context {
+            rounding: self.rounding(),
+            tininess: Tininess::AfterRounding,
+            nan_mode: NanMode::LargerSignificand,
+            ..Context::default()
+        }
+    }
+
+    /// fld: widening is exact, though a signaling nan is quieted and raises invalid.
+    pub fn load(x: &F64, ctx: &mut Context) -> F80 {
+        x.convert(ctx)
+    }
+
+    /// fst to a binary64: the second rounding, now in the binary64 exponent range as well.
+    pub fn store(x: &F80, ctx: &mut Context) -> F64 {
+        x.convert(ctx)
+    }
+
+    // runs op at full extended precision and rounds the exact result once to the precision control. for 53 and 24
+    // bits it rounds to odd first (truncate, then set the last bit if anything was dropped): with 11 or more bits
+    // to spare, that leaves the second rounding with the same answer as rounding the exact result.
+    fn rounded(&self, ctx: &mut Context, op: impl Fn(&mut Context) -> F80) -> F80 {
+        let precision = self.precision();
+        if precision == 64 {
+            return op(ctx);
+        }
+        let mut odd = Context {
+            rounding: RoundingMode::TowardZero,
+            flags: Flags::empty(),
+            ..*ctx
+        };
+        let truncated = op(&mut odd);
+        let exact = !odd.flags.contains(Flags::INEXACT);
+        if truncated.is_nan() || truncated.is_infinity() || (truncated.is_zero() && exact) {
+            return op(ctx); // nothing to round, but the mode picks the sign of an exact zero sum
+        }
+        let jammed = if odd.flags.contains(Flags::OVERFLOW) || truncated.is_zero() {
+            op(ctx) // outside the extended range, so outside the narrower one too: the real mode picks the result
+        } else {
+            F80::from_raw(truncated.raw() | !exact as u128)
+        };
+        if precision == 53 {
+            jammed.convert::<DoublePrecision>(ctx).convert(ctx)
+        } else {
+            jammed.convert::<SinglePrecision>(ctx).convert(ctx)
+        }
+    }
+
+    pub fn add(&self, a: &F80, b: &F80, ctx: &mut Context) -> F80 {
+        self.rounded(ctx, |ctx| a.add(b, ctx))
+    }
+
+    pub fn subtract(&self, a: &F80, b: &F80, ctx: &mut Context) -> F80 {
+        self.rounded(ctx, |ctx| a.subtract(b, ctx))
+    }
+
+    pub fn multiply(&self, a: &F80, b: &F80, ctx: &mut Context) -> F80 {
+        self.rounded(ctx, |ctx| a.multiply(b, ctx))
+    }
+
+    pub fn divide(&self, a: &F80, b: &F80, ctx: &mut Context) -> F80 {
+        self.rounded(ctx, |ctx| a.divide(b, ctx))
+    }
+}
+
+// the in-memory (fld/fstp m80) layout has the integer bit explicit. unnormals (exponent set, integer bit clear)
+// and pseudo-denormals (the reverse) aren't modeled: they read as if the bit matched the exponent.
+impl SoftFloat<Extended> {
+    /// The 10 bytes fstp m80 writes, little endian.
+    pub fn to_x87_bytes(&self) -> [u8; 10] {
+        let raw = self.raw();
+        let exponent = (raw >> 63) & 0x7FFF;
+        let integer = (exponent != 0) as u128; // 1 for normals, infinities and nans
+        let bits = ((raw >> 78) << 79) | (exponent << 64) | (integer << 63) | (raw & ((1 << 63) - 1));
+        let mut bytes = [0; 10];
+        bytes.copy_from_slice(&bits.to_le_bytes()[..10]);
+        bytes
+    }
+
+    pub fn from_x87_bytes(bytes: [u8; 10]) -> F80 {
+        let mut wide = [0; 16];
+        wide[..10].copy_from_slice(&bytes);
+        let bits = u128::from_le_bytes(wide);
+        F80::from_raw(((bits >> 64) << 63) | (bits & ((1 << 63) - 1)))
+    }
+}